@@ -38,4 +38,22 @@ fn main() {
     save_spirv("textured-face.frag", ShaderType::Fragment);
     save_spirv("line.vert", ShaderType::Vertex);
     save_spirv("line.frag", ShaderType::Fragment);
+    save_spirv("wide-line.vert", ShaderType::Vertex);
+    save_spirv("sdf-line.vert", ShaderType::Vertex);
+    save_spirv("sdf-line.frag", ShaderType::Fragment);
+    save_spirv("hidden-line.vert", ShaderType::Vertex);
+    save_spirv("hidden-line.frag", ShaderType::Fragment);
+    save_spirv("id.frag", ShaderType::Fragment);
+    save_spirv("normal-depth.frag", ShaderType::Fragment);
+    save_spirv("outline.vert", ShaderType::Vertex);
+    save_spirv("outline.frag", ShaderType::Fragment);
+    save_spirv("skinned-polygon.vert", ShaderType::Vertex);
+    save_spirv("skinned-textured-polygon.vert", ShaderType::Vertex);
+    save_spirv("toon.frag", ShaderType::Fragment);
+    std::env::set_current_dir("../../../truck-platform/src/shaders").unwrap();
+    save_spirv("ground-shadow.vert", ShaderType::Vertex);
+    save_spirv("ground-shadow.frag", ShaderType::Fragment);
+    save_spirv("ambient-occlusion.vert", ShaderType::Vertex);
+    save_spirv("ambient-occlusion.frag", ShaderType::Fragment);
+    save_spirv("ambient-occlusion-ms.frag", ShaderType::Fragment);
 }
@@ -0,0 +1,114 @@
+use crate::cgmath64::*;
+use crate::tolerance::*;
+
+/// Returns whether the last row of the matrix is `(0, 0, 0, 1)`.
+#[inline(always)]
+fn is_affine(mat: &Matrix4) -> bool {
+    mat[0][3] == 0.0 && mat[1][3] == 0.0 && mat[2][3] == 0.0 && mat[3][3] == 1.0
+}
+
+/// Transforms the points by the matrix in place.
+///
+/// The columns of the matrix are extracted before the loop and the division by the weight
+/// is skipped for affine matrices, so that the loop is easily vectorized by the compiler.
+/// The result is the same as `mat.transform_point(pt)` for each point.
+/// # Examples
+/// ```
+/// use truck_base::{assert_near, cgmath64::*, batch, tolerance::*};
+/// let mat = Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0))
+///     * Matrix4::from_angle_z(Rad(1.0))
+///     * Matrix4::from_scale(2.0);
+/// let points: Vec<Point3> = (0..10)
+///     .map(|i| Point3::new(i as f64, (i * i) as f64, 1.0))
+///     .collect();
+/// let mut transformed = points.clone();
+/// batch::transform_points(&mat, &mut transformed);
+/// for (p, q) in points.iter().zip(&transformed) {
+///     assert_near!(mat.transform_point(*p), *q);
+/// }
+///
+/// // projective transformation
+/// let mat = perspective(Rad(1.0), 1.0, 0.1, 10.0);
+/// let mut transformed = points.clone();
+/// batch::transform_points(&mat, &mut transformed);
+/// for (p, q) in points.iter().zip(&transformed) {
+///     assert_near!(mat.transform_point(*p), *q);
+/// }
+/// ```
+pub fn transform_points(mat: &Matrix4, points: &mut [Point3]) {
+    let (c0, c1, c2, c3) = (mat[0], mat[1], mat[2], mat[3]);
+    if is_affine(mat) {
+        points.iter_mut().for_each(|pt| {
+            let (x, y, z) = (pt[0], pt[1], pt[2]);
+            pt[0] = c0[0] * x + c1[0] * y + c2[0] * z + c3[0];
+            pt[1] = c0[1] * x + c1[1] * y + c2[1] * z + c3[1];
+            pt[2] = c0[2] * x + c1[2] * y + c2[2] * z + c3[2];
+        })
+    } else {
+        points.iter_mut().for_each(|pt| {
+            let (x, y, z) = (pt[0], pt[1], pt[2]);
+            let w = c0[3] * x + c1[3] * y + c2[3] * z + c3[3];
+            pt[0] = (c0[0] * x + c1[0] * y + c2[0] * z + c3[0]) / w;
+            pt[1] = (c0[1] * x + c1[1] * y + c2[1] * z + c3[1]) / w;
+            pt[2] = (c0[2] * x + c1[2] * y + c2[2] * z + c3[2]) / w;
+        })
+    }
+}
+
+/// Transforms the vectors by the linear part of the matrix in place.
+///
+/// The result is the same as `mat.transform_vector(vec)` for each vector.
+/// # Examples
+/// ```
+/// use truck_base::{assert_near, cgmath64::*, batch, tolerance::*};
+/// let mat = Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0))
+///     * Matrix4::from_angle_x(Rad(1.0));
+/// let vectors: Vec<Vector3> = (0..10)
+///     .map(|i| Vector3::new(i as f64, 1.0, -(i as f64)))
+///     .collect();
+/// let mut transformed = vectors.clone();
+/// batch::transform_vectors(&mat, &mut transformed);
+/// for (v, w) in vectors.iter().zip(&transformed) {
+///     assert_near!(mat.transform_vector(*v), *w);
+/// }
+/// ```
+pub fn transform_vectors(mat: &Matrix4, vectors: &mut [Vector3]) {
+    let (c0, c1, c2) = (mat[0], mat[1], mat[2]);
+    vectors.iter_mut().for_each(|vec| {
+        let (x, y, z) = (vec[0], vec[1], vec[2]);
+        vec[0] = c0[0] * x + c1[0] * y + c2[0] * z;
+        vec[1] = c0[1] * x + c1[1] * y + c2[1] * z;
+        vec[2] = c0[2] * x + c1[2] * y + c2[2] * z;
+    })
+}
+
+/// Transforms the normal vectors by the inverse transpose of the matrix and normalizes them.
+///
+/// The normals are not changed if the matrix is not invertible,
+/// and the normals which become too small are left as they are.
+/// # Examples
+/// ```
+/// use truck_base::{assert_near, cgmath64::*, batch, tolerance::*};
+/// let mat = Matrix4::from_nonuniform_scale(1.0, 2.0, 1.0);
+/// let mut normals = vec![Vector3::new(1.0, 1.0, 0.0).normalize()];
+/// batch::transform_normals(&mat, &mut normals);
+/// assert_near!(normals[0], Vector3::new(2.0, 1.0, 0.0).normalize());
+/// ```
+pub fn transform_normals(mat: &Matrix4, normals: &mut [Vector3]) {
+    let normal_matrix = match mat.invert() {
+        Some(inv) => inv.transpose(),
+        None => return,
+    };
+    let (c0, c1, c2) = (normal_matrix[0], normal_matrix[1], normal_matrix[2]);
+    normals.iter_mut().for_each(|n| {
+        let (x, y, z) = (n[0], n[1], n[2]);
+        let vec = Vector3::new(
+            c0[0] * x + c1[0] * y + c2[0] * z,
+            c0[1] * x + c1[1] * y + c2[1] * z,
+            c0[2] * x + c1[2] * y + c2[2] * z,
+        );
+        if !vec.so_small() {
+            *n = vec.normalize();
+        }
+    })
+}
@@ -8,4 +8,4 @@ macro_rules! f64_type {
         };
         ($a: ident, $($b: ident), *) => { f64_type!($a); f64_type!($($b),*); }
     }
-f64_type!(Vector1, Vector2, Vector3, Vector4, Matrix2, Matrix3, Matrix4, Point1, Point2, Point3);
+f64_type!(Vector1, Vector2, Vector3, Vector4, Matrix2, Matrix3, Matrix4, Point1, Point2, Point3, Quaternion);
@@ -11,6 +11,8 @@
     unused_qualifications
 )]
 
+/// Batch transformations of points and vectors for hot loops
+pub mod batch;
 /// Defines bounding box
 pub mod bounding_box;
 /// Redefines vectors, matrices or points with scalar = f64.
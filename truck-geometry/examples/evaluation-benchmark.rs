@@ -0,0 +1,122 @@
+//! Measures the hot loops of the evaluations and the transformations.
+//! - de Boor's algorithm vs the sum of all basis functions for B-spline curves
+//! - the batch evaluation `grid_derivatives` vs the double loop of `subs`, `uder` and `vder`
+//! - `batch::transform_points` vs `transform_point` for each point
+//!
+//! Run with `cargo run --release --example evaluation-benchmark`.
+
+use std::time::Instant;
+use truck_base::batch;
+use truck_geometry::*;
+
+const CURVE_SAMPLES: usize = 1_000_000;
+const GRID_SIZE: usize = 300;
+const POINTS: usize = 1_000_000;
+
+fn measure<T, F: FnMut() -> T>(name: &str, mut f: F) -> (T, f64) {
+    let instant = Instant::now();
+    let res = f();
+    let time = instant.elapsed().as_secs_f64();
+    println!("{:<40} {:>10.3} ms", name, time * 1000.0);
+    (res, time)
+}
+
+fn curve_benchmark() {
+    let knot_vec = KnotVec::uniform_knot(3, 30);
+    let ctrl_pts: Vec<Vector3> = (0..33)
+        .map(|i| Vector3::new(i as f64, (i as f64).sin(), (i as f64).cos()))
+        .collect();
+    let curve = BSplineCurve::new(knot_vec.clone(), ctrl_pts.clone());
+    let params = (0..CURVE_SAMPLES).map(|i| i as f64 / CURVE_SAMPLES as f64);
+    let (sum0, time0) = measure("curve: sum of all basis functions", || {
+        params.clone().fold(Vector3::zero(), |sum, t| {
+            let basis = knot_vec.bspline_basis_functions(3, t);
+            ctrl_pts
+                .iter()
+                .zip(basis)
+                .fold(sum, |sum, (pt, b)| sum + *pt * b)
+        })
+    });
+    let (sum1, time1) = measure("curve: de Boor", || {
+        params
+            .clone()
+            .fold(Vector3::zero(), |sum, t| sum + curve.subs(t))
+    });
+    assert!((sum0 - sum1).magnitude() < 1.0e-6 * sum0.magnitude());
+    println!("speed up: {:.2}", time0 / time1);
+}
+
+fn surface_benchmark() {
+    let surface = BSplineSurface::new(
+        (KnotVec::uniform_knot(3, 10), KnotVec::uniform_knot(3, 10)),
+        (0..13)
+            .map(|i| {
+                (0..13)
+                    .map(|j| Vector3::new(i as f64, j as f64, ((i * j) as f64).sin()))
+                    .collect()
+            })
+            .collect(),
+    );
+    let params: Vec<f64> = (0..GRID_SIZE)
+        .map(|i| i as f64 / (GRID_SIZE - 1) as f64)
+        .collect();
+    let (grid0, time0) = measure("surface: double loop", || {
+        params
+            .iter()
+            .map(|u| {
+                params
+                    .iter()
+                    .map(|v| {
+                        [
+                            surface.subs(*u, *v),
+                            surface.uder(*u, *v),
+                            surface.vder(*u, *v),
+                        ]
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>()
+    });
+    let (grid1, time1) = measure("surface: grid_derivatives", || {
+        surface.grid_derivatives(&params, &params)
+    });
+    grid0
+        .iter()
+        .flatten()
+        .zip(grid1.iter().flatten())
+        .for_each(|(a, b)| {
+            a.iter().zip(b).for_each(|(a, b)| assert_near!(a, b));
+        });
+    println!("speed up: {:.2}", time0 / time1);
+}
+
+fn transform_benchmark() {
+    let mat = Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0))
+        * Matrix4::from_angle_y(Rad(0.5))
+        * Matrix4::from_scale(2.0);
+    let points: Vec<Point3> = (0..POINTS)
+        .map(|i| Point3::new(i as f64, (i as f64).sqrt(), 1.0))
+        .collect();
+    let (points0, time0) = measure("transform: transform_point", || {
+        points
+            .iter()
+            .map(|pt| mat.transform_point(*pt))
+            .collect::<Vec<_>>()
+    });
+    let (points1, time1) = measure("transform: batch::transform_points", || {
+        let mut res = points.clone();
+        batch::transform_points(&mat, &mut res);
+        res
+    });
+    points0
+        .iter()
+        .zip(&points1)
+        .for_each(|(p, q)| assert!(p.distance(*q) < 1.0e-9 * p.to_vec().magnitude().max(1.0)));
+    println!("speed up: {:.2}", time0 / time1);
+}
+
+fn main() {
+    curve_benchmark();
+    surface_benchmark();
+    transform_benchmark();
+}
@@ -0,0 +1,462 @@
+use crate::errors::Error;
+use crate::*;
+
+/// The continuity at the joint of two curves.
+///
+/// The variants are ordered by the strength: `C0 < G1 < C1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Continuity {
+    /// The end points coincide.
+    C0,
+    /// The end points and the directions of the derivations coincide.
+    G1,
+    /// The end points and the derivations coincide.
+    C1,
+}
+
+/// The curves whose parameters can be transformed and which can be concatenated.
+pub trait Composable: ParametricCurve + Invertible {
+    /// Transforms the parameter by the affine map `t -> a * t + b`.
+    /// # Panics
+    /// Panic occurs if `a` is not positive.
+    fn affine_reparameterize(&mut self, a: f64, b: f64);
+    /// Makes the knot vector clamped without changing the curve.
+    fn clamp_knot_vec(&mut self);
+    /// Concats `other` to the back of `self`.
+    /// The back of the parameter range of `self` must coincide with the front of the one of `other`.
+    fn try_concat_curve(&mut self, other: &mut Self) -> Result<()>;
+}
+
+fn affine_knot_vec(knot_vec: &KnotVec, a: f64, b: f64) -> KnotVec {
+    assert!(
+        a > 0.0,
+        "the scale of the parameter must be positive: {}",
+        a
+    );
+    knot_vec.iter().map(|t| a * t + b).collect()
+}
+
+impl<V> Composable for BSplineCurve<V>
+where
+    V: TangentSpace<f64> + Tolerance,
+    V::Space: EuclideanSpace<Scalar = f64, Diff = V>,
+{
+    #[inline(always)]
+    fn affine_reparameterize(&mut self, a: f64, b: f64) {
+        let knot_vec = affine_knot_vec(self.knot_vec(), a, b);
+        *self = BSplineCurve::new(knot_vec, self.control_points().clone());
+    }
+    #[inline(always)]
+    fn clamp_knot_vec(&mut self) { self.clamp(); }
+    #[inline(always)]
+    fn try_concat_curve(&mut self, other: &mut Self) -> Result<()> {
+        self.try_concat(other).map(|_| ())
+    }
+}
+
+impl<V: Homogeneous<f64> + Tolerance> Composable for NURBSCurve<V> {
+    #[inline(always)]
+    fn affine_reparameterize(&mut self, a: f64, b: f64) {
+        let knot_vec = affine_knot_vec(self.knot_vec(), a, b);
+        *self = NURBSCurve::new(BSplineCurve::new(knot_vec, self.control_points().clone()));
+    }
+    #[inline(always)]
+    fn clamp_knot_vec(&mut self) { self.clamp(); }
+    #[inline(always)]
+    fn try_concat_curve(&mut self, other: &mut Self) -> Result<()> {
+        self.try_concat(other).map(|_| ())
+    }
+}
+
+/// Returns the continuity at the joint of the back of `curve0` and the front of `curve1`.
+/// Returns `None` if the back of `curve0` and the front of `curve1` are not near.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// use composition::Continuity;
+/// let curve0 = BSplineCurve::new(
+///     KnotVec::bezier_knot(1),
+///     vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)],
+/// );
+/// let curve1 = BSplineCurve::new(
+///     KnotVec::bezier_knot(1),
+///     vec![Vector2::new(1.0, 0.0), Vector2::new(2.0, 0.0)],
+/// );
+/// let curve2 = BSplineCurve::new(
+///     KnotVec::bezier_knot(1),
+///     vec![Vector2::new(1.0, 0.0), Vector2::new(3.0, 0.0)],
+/// );
+/// let curve3 = BSplineCurve::new(
+///     KnotVec::bezier_knot(1),
+///     vec![Vector2::new(1.0, 0.0), Vector2::new(1.0, 1.0)],
+/// );
+/// assert_eq!(composition::joint_continuity(&curve0, &curve1), Some(Continuity::C1));
+/// assert_eq!(composition::joint_continuity(&curve0, &curve2), Some(Continuity::G1));
+/// assert_eq!(composition::joint_continuity(&curve0, &curve3), Some(Continuity::C0));
+/// assert_eq!(composition::joint_continuity(&curve1, &curve0), None);
+/// ```
+pub fn joint_continuity<C>(curve0: &C, curve1: &C) -> Option<Continuity>
+where
+    C: ParametricCurve,
+    C::Point: Tolerance,
+    C::Vector: InnerSpace<Scalar = f64> + Tolerance, {
+    let ((_, t0), (t1, _)) = (curve0.parameter_range(), curve1.parameter_range());
+    if !curve0.subs(t0).near(&curve1.subs(t1)) {
+        return None;
+    }
+    let (der0, der1) = (curve0.der(t0), curve1.der(t1));
+    if der0.near(&der1) {
+        Some(Continuity::C1)
+    } else if !der0.magnitude().so_small() && !der1.magnitude().so_small() && der0.normalize().near(&der1.normalize()) {
+        Some(Continuity::G1)
+    } else {
+        Some(Continuity::C0)
+    }
+}
+
+/// Concats two curves with the continuity `continuity` at the joint.
+///
+/// The parameter of `curve1` is translated so that its front coincides with the back of `curve0`.
+/// If `continuity` is `C1` and the curves are only `G1` at the joint, the parameter of
+/// `curve1` is also scaled so that the derivations coincide. Neither of the curves is
+/// changed as a set of points.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// use composition::Continuity;
+/// let curve0 = BSplineCurve::new(
+///     KnotVec::bezier_knot(2),
+///     vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0), Vector2::new(2.0, 1.0)],
+/// );
+/// let curve1 = BSplineCurve::new(
+///     KnotVec::bezier_knot(2),
+///     vec![Vector2::new(2.0, 1.0), Vector2::new(4.0, 3.0), Vector2::new(4.0, 4.0)],
+/// );
+/// let curve = composition::concat(&curve0, &curve1, Continuity::C1).unwrap();
+/// assert_near!(curve.subs(0.0), Vector2::new(0.0, 0.0));
+/// assert_near!(curve.subs(1.0), Vector2::new(2.0, 1.0));
+/// assert_near!(curve.back(), Point2::new(4.0, 4.0));
+///
+/// // the derivation is continuous at the joint
+/// let (_, t) = curve0.parameter_range();
+/// assert_near!(curve.der(t - 1.0e-9), curve.der(t + 1.0e-9));
+/// ```
+/// # Failures
+/// * If the back of `curve0` is not near the front of `curve1`, returns
+/// [`Error::DisconnectedCurves`](../errors/enum.Error.html#variant.DisconnectedCurves).
+/// * If `continuity` is not `C0` and the directions of the derivations are different at the joint,
+/// returns [`Error::NotTangentContinuous`](../errors/enum.Error.html#variant.NotTangentContinuous).
+/// ```
+/// use truck_geometry::*;
+/// use composition::Continuity;
+/// use errors::Error;
+/// let curve0 = BSplineCurve::new(
+///     KnotVec::bezier_knot(1),
+///     vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)],
+/// );
+/// let curve1 = BSplineCurve::new(
+///     KnotVec::bezier_knot(1),
+///     vec![Vector2::new(1.0, 0.0), Vector2::new(1.0, 1.0)],
+/// );
+/// assert!(composition::concat(&curve0, &curve1, Continuity::C0).is_ok());
+/// assert_eq!(
+///     composition::concat(&curve0, &curve1, Continuity::G1),
+///     Err(Error::NotTangentContinuous),
+/// );
+/// assert_eq!(
+///     composition::concat(&curve1, &curve0, Continuity::C0),
+///     Err(Error::DisconnectedCurves),
+/// );
+/// ```
+pub fn concat<C>(curve0: &C, curve1: &C, continuity: Continuity) -> Result<C>
+where
+    C: Composable,
+    C::Point: Tolerance,
+    C::Vector: InnerSpace<Scalar = f64> + Tolerance, {
+    let (mut curve0, mut curve1) = (curve0.clone(), curve1.clone());
+    curve0.clamp_knot_vec();
+    curve1.clamp_knot_vec();
+    let ((_, t0), (t1, _)) = (curve0.parameter_range(), curve1.parameter_range());
+    curve1.affine_reparameterize(1.0, t0 - t1);
+    let joint = joint_continuity(&curve0, &curve1).ok_or(Error::DisconnectedCurves)?;
+    if continuity > Continuity::C0 && joint == Continuity::C0 {
+        return Err(Error::NotTangentContinuous);
+    }
+    if continuity == Continuity::C1 && joint == Continuity::G1 {
+        let a = curve1.der(t0).magnitude() / curve0.der(t0).magnitude();
+        curve1.affine_reparameterize(a, t0 * (1.0 - a));
+    }
+    curve0.try_concat_curve(&mut curve1)?;
+    Ok(curve0)
+}
+
+/// Returns the curve with the inverted direction.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// let curve = BSplineCurve::new(
+///     KnotVec::uniform_knot(2, 3),
+///     vec![
+///         Vector2::new(0.0, 0.0),
+///         Vector2::new(1.0, 2.0),
+///         Vector2::new(2.0, 0.0),
+///         Vector2::new(3.0, 2.0),
+///         Vector2::new(4.0, 0.0),
+///     ],
+/// );
+/// let reversed = composition::reverse(&curve);
+/// const N: usize = 10;
+/// for i in 0..=N {
+///     let t = i as f64 / N as f64;
+///     assert_near!(ParametricCurve::subs(&curve, t), ParametricCurve::subs(&reversed, 1.0 - t));
+/// }
+/// ```
+#[inline(always)]
+pub fn reverse<C: Invertible>(curve: &C) -> C { curve.inverse() }
+
+/// Returns the curve whose parameter range is `(t0, t1)` by the linear transformation of the parameter.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// let curve = NURBSCurve::new(BSplineCurve::new(
+///     KnotVec::bezier_knot(2),
+///     vec![
+///         Vector3::new(1.0, 0.0, 1.0),
+///         Vector3::new(1.0, 1.0, 1.0),
+///         Vector3::new(0.0, 2.0, 2.0),
+///     ],
+/// ));
+/// let reparam = composition::reparameterize(&curve, (2.0, 6.0));
+/// assert_eq!(reparam.parameter_range(), (2.0, 6.0));
+/// assert_near!(reparam.subs(3.0), curve.subs(0.25));
+/// assert_near!(reparam.der(3.0) * 4.0, curve.der(0.25));
+/// ```
+/// # Panics
+/// Panic occurs if `t0 >= t1`.
+pub fn reparameterize<C: Composable>(curve: &C, (t0, t1): (f64, f64)) -> C {
+    let (s0, s1) = curve.parameter_range();
+    let a = (t1 - t0) / (s1 - s0);
+    let mut curve = curve.clone();
+    curve.affine_reparameterize(a, t0 - a * s0);
+    curve
+}
+
+/// Returns the NURBS curve reparameterized by the rational linear transformation.
+///
+/// The weight of the `i`-th control point of each Bézier segment is multiplied by `c^i`.
+/// Neither the curve as a set of points nor the knots are changed, however the speed of
+/// the parameter in each segment is changed. The tangent directions are kept at the inner
+/// knots, but the derivations are not continuous in general.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// // the quarter of the unit circle
+/// let curve = NURBSCurve::new(BSplineCurve::new(
+///     KnotVec::bezier_knot(2),
+///     vec![
+///         Vector3::new(1.0, 0.0, 1.0),
+///         Vector3::new(1.0, 1.0, 1.0),
+///         Vector3::new(0.0, 2.0, 2.0),
+///     ],
+/// ));
+/// let reparam = composition::rational_reparameterize(&curve, 2.0);
+/// assert!(!curve.near_as_curve(&reparam));
+/// assert_near!(reparam.front(), curve.front());
+/// assert_near!(reparam.back(), curve.back());
+/// const N: usize = 10;
+/// for i in 0..=N {
+///     let t = i as f64 / N as f64;
+///     assert_near!(reparam.subs(t).to_vec().magnitude(), 1.0);
+/// }
+/// ```
+/// # Panics
+/// Panic occurs if `c` is not positive.
+pub fn rational_reparameterize<V: Homogeneous<f64> + Tolerance>(
+    curve: &NURBSCurve<V>,
+    c: f64,
+) -> NURBSCurve<V> {
+    assert!(c > 0.0, "the ratio of the weights must be positive: {}", c);
+    let mut iter = curve.bezier_decomposition().into_iter().map(|mut bezier| {
+        let mut w = 1.0;
+        bezier.transform_control_points(|pt| {
+            *pt = *pt * w;
+            w *= c;
+        });
+        bezier
+    });
+    let mut res = iter.next().unwrap();
+    iter.for_each(|mut bezier| {
+        res.concat(&mut bezier);
+    });
+    res
+}
+
+/// Creates the curve by concatenating the Bézier segments in turn.
+///
+/// The parameter of each segment is translated so that the front coincides with the back
+/// of the previous one. This is the inverse of `bezier_decomposition`.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// let curve = BSplineCurve::new(
+///     KnotVec::uniform_knot(3, 4),
+///     vec![
+///         Vector2::new(0.0, 0.0),
+///         Vector2::new(1.0, 2.0),
+///         Vector2::new(2.0, -1.0),
+///         Vector2::new(3.0, 2.0),
+///         Vector2::new(4.0, 0.0),
+///         Vector2::new(5.0, 1.0),
+///         Vector2::new(6.0, 0.0),
+///     ],
+/// );
+/// let beziers = curve.bezier_decomposition();
+/// assert_eq!(beziers.len(), 4);
+/// let composed = composition::from_bezier_segments(&beziers).unwrap();
+/// assert!(curve.near2_as_curve(&composed));
+///
+/// // the parameters of the segments are not necessarily continued
+/// let beziers: Vec<_> = beziers.iter().map(|bezier| {
+///     let mut bezier = bezier.clone();
+///     bezier.knot_normalize();
+///     bezier
+/// }).collect();
+/// let composed = composition::from_bezier_segments(&beziers).unwrap();
+/// assert_eq!(composed.parameter_range(), (0.0, 4.0));
+/// ```
+/// # Failures
+/// * If the slice is empty, returns
+/// [`Error::EmptyCurveCollector`](../errors/enum.Error.html#variant.EmptyCurveCollector).
+/// * If two consecutive segments are not connected, returns
+/// [`Error::DisconnectedCurves`](../errors/enum.Error.html#variant.DisconnectedCurves).
+pub fn from_bezier_segments<C>(segments: &[C]) -> Result<C>
+where
+    C: Composable,
+    C::Point: Tolerance,
+    C::Vector: InnerSpace<Scalar = f64> + Tolerance, {
+    let (first, rest) = segments.split_first().ok_or(Error::EmptyCurveCollector)?;
+    rest.iter().try_fold(first.clone(), |curve, segment| {
+        concat(&curve, segment, Continuity::C0)
+    })
+}
+
+/// Approximates `curve` by the C1 cubic B-spline curve within the tolerance `tol`.
+///
+/// The parameter range is divided into `division` intervals at first, and each interval is
+/// bisected until the cubic curve interpolating the points and the derivations of `curve` at
+/// its ends is within `tol` from `curve`. The parameter of the B-spline curve is the same as
+/// the one of `curve`, and the inner knots are double. The zero derivations are interpolated
+/// as they are, so `curve` should be parameterized regularly for the modeling.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// use std::f64::consts::PI;
+/// // the logarithmic spiral, which cannot be represented by NURBS
+/// let spiral = Spiral::logarithmic(Point2::origin(), 1.0, 0.1, (0.0, 4.0 * PI));
+/// let bspline = composition::cubic_approximation(&spiral, 8, 1.0e-4);
+/// assert_eq!(bspline.degree(), 3);
+/// for i in 0..=100 {
+///     let t = 4.0 * PI * i as f64 / 100.0;
+///     let pt = Point2::from_vec(bspline.subs(t));
+///     assert!(pt.distance(spiral.subs(t)) < 1.0e-4);
+/// }
+/// ```
+pub fn cubic_approximation<C>(curve: &C, division: usize, tol: f64) -> BSplineCurve<C::Vector>
+where
+    C: ParametricCurve,
+    C::Point: EuclideanSpace<Scalar = f64, Diff = C::Vector> + MetricSpace<Metric = f64>,
+    C::Vector: VectorSpace<Scalar = f64>, {
+    let hermite = |t0: f64, t1: f64| {
+        let (p0, p1) = (curve.subs(t0), curve.subs(t1));
+        let h = (t1 - t0) / 3.0;
+        [p0, p0 + curve.der(t0) * h, p1 - curve.der(t1) * h, p1]
+    };
+    let div = bisection_division(curve.parameter_range(), division, |t0, t1| {
+        let pts = hermite(t0, t1);
+        (1..8).all(|i| {
+            let (s, t) = (i as f64 / 8.0, 1.0 - i as f64 / 8.0);
+            let vec = pts[0].to_vec() * (t * t * t)
+                + pts[1].to_vec() * (3.0 * t * t * s)
+                + pts[2].to_vec() * (3.0 * t * s * s)
+                + pts[3].to_vec() * (s * s * s);
+            C::Point::from_vec(vec).distance(curve.subs(t0 + (t1 - t0) * s)) < tol
+        })
+    });
+    let n = div.len() - 1;
+    let mut knots = vec![div[0]; 4];
+    div[1..n].iter().for_each(|t| knots.extend([*t, *t].iter()));
+    knots.extend([div[n]; 4].iter());
+    let mut control_points = vec![curve.subs(div[0]).to_vec()];
+    div.windows(2).for_each(|a| {
+        let pts = hermite(a[0], a[1]);
+        control_points.push(pts[1].to_vec());
+        control_points.push(pts[2].to_vec());
+    });
+    control_points.push(curve.subs(div[n]).to_vec());
+    BSplineCurve::new(KnotVec::from(knots), control_points)
+}
+
+/// the maximal depth of the bisections of the parameter ranges
+const MAX_BISECTIONS: usize = 24;
+
+/// Bisects the parameter range divided into `division` intervals until `is_fine` holds on
+/// all the intervals.
+pub(crate) fn bisection_division<F: Fn(f64, f64) -> bool>(
+    (t0, t1): (f64, f64),
+    division: usize,
+    is_fine: F,
+) -> Vec<f64> {
+    let n = division.max(1);
+    let mut div: Vec<f64> = (0..=n)
+        .map(|i| t0 + (t1 - t0) * i as f64 / n as f64)
+        .collect();
+    for _ in 0..MAX_BISECTIONS {
+        let mut new_div = vec![div[0]];
+        for a in div.windows(2) {
+            if !is_fine(a[0], a[1]) {
+                new_div.push((a[0] + a[1]) / 2.0);
+            }
+            new_div.push(a[1]);
+        }
+        if new_div.len() == div.len() {
+            break;
+        }
+        div = new_div;
+    }
+    div
+}
+
+#[test]
+fn concat_g1_to_c1() {
+    let curve0 = BSplineCurve::new(
+        KnotVec::bezier_knot(2),
+        vec![
+            Vector2::new(0.0, 0.0),
+            Vector2::new(1.0, 0.0),
+            Vector2::new(2.0, 1.0),
+        ],
+    );
+    // the derivation at the front is three times the one at the back of `curve0`
+    let mut curve1 = BSplineCurve::new(
+        KnotVec::bezier_knot(2),
+        vec![
+            Vector2::new(2.0, 1.0),
+            Vector2::new(5.0, 4.0),
+            Vector2::new(5.0, 6.0),
+        ],
+    );
+    curve1.knot_translate(5.0);
+    assert_eq!(joint_continuity(&curve0, &curve1), Some(Continuity::G1));
+    let g1 = concat(&curve0, &curve1, Continuity::G1).unwrap();
+    assert_eq!(ParametricCurve::parameter_range(&g1), (0.0, 2.0));
+    let c1 = concat(&curve0, &curve1, Continuity::C1).unwrap();
+    let (t0, t1) = ParametricCurve::parameter_range(&c1);
+    assert_near!(Vector2::new(t0, t1), Vector2::new(0.0, 4.0));
+    assert_near!(c1.der(1.0 - 1.0e-9), c1.der(1.0 + 1.0e-9));
+    assert!(!g1.der(1.0 - 1.0e-9).near(&g1.der(1.0 + 1.0e-9)));
+    const N: usize = 10;
+    for i in 0..=N {
+        let t = i as f64 / N as f64;
+        assert_near!(c1.subs(1.0 + 3.0 * t), g1.subs(1.0 + t));
+    }
+}
@@ -0,0 +1,337 @@
+use super::*;
+use crate::composition::Composable;
+use crate::errors::Error;
+use std::cmp::Ordering;
+
+impl<C> CompositeCurve<C>
+where
+    C: ParametricCurve,
+    C::Point: Tolerance,
+    C::Vector: InnerSpace<Scalar = f64> + Tolerance,
+{
+    /// Creates the composite curve from the segments.
+    /// # Failures
+    /// * If `segments` is empty, returns
+    /// [`Error::EmptyCurveCollector`](../errors/enum.Error.html#variant.EmptyCurveCollector).
+    /// * If the back of a segment is not near the front of the next segment, returns
+    /// [`Error::DisconnectedCurves`](../errors/enum.Error.html#variant.DisconnectedCurves).
+    /// ```
+    /// use truck_geometry::*;
+    /// use errors::Error;
+    /// let line0 = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)],
+    /// );
+    /// let line1 = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector2::new(1.0, 1.0), Vector2::new(2.0, 1.0)],
+    /// );
+    /// assert_eq!(
+    ///     CompositeCurve::<BSplineCurve<Vector2>>::try_new(Vec::new()).unwrap_err(),
+    ///     Error::EmptyCurveCollector,
+    /// );
+    /// assert_eq!(
+    ///     CompositeCurve::try_new(vec![line0, line1]).unwrap_err(),
+    ///     Error::DisconnectedCurves,
+    /// );
+    /// ```
+    pub fn try_new(segments: Vec<C>) -> Result<Self> {
+        let mut iter = segments.into_iter();
+        let first = iter.next().ok_or(Error::EmptyCurveCollector)?;
+        let (t0, t1) = first.parameter_range();
+        let mut curve = CompositeCurve {
+            segments: vec![first],
+            breakpoints: vec![t0, t1],
+            continuities: Vec::new(),
+        };
+        for segment in iter {
+            curve.try_push(segment)?;
+        }
+        Ok(curve)
+    }
+
+    /// Creates the composite curve from the segments.
+    /// # Panics
+    /// Panic occurs if `segments` is empty or the segments are not connected.
+    #[inline(always)]
+    pub fn new(segments: Vec<C>) -> Self {
+        Self::try_new(segments).unwrap_or_else(|error| panic!("{}", error))
+    }
+
+    /// Adds a segment to the back of the curve.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// use composition::Continuity;
+    /// let mut curve = CompositeCurve::new(vec![BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)],
+    /// )]);
+    /// // the parameter range of the segment is not necessarily continued
+    /// let mut line = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector2::new(1.0, 0.0), Vector2::new(1.0, 2.0)],
+    /// );
+    /// line.knot_translate(5.0);
+    /// curve.try_push(line).unwrap();
+    /// assert_eq!(curve.num_segments(), 2);
+    /// assert_eq!(curve.breakpoints(), &[0.0, 1.0, 2.0]);
+    /// assert_eq!(curve.continuities(), &[Continuity::C0]);
+    /// assert_near!(curve.subs(1.5), Point2::new(1.0, 1.0));
+    /// ```
+    /// # Failures
+    /// If the back of the curve is not near the front of `segment`, returns
+    /// [`Error::DisconnectedCurves`](../errors/enum.Error.html#variant.DisconnectedCurves).
+    pub fn try_push(&mut self, segment: C) -> Result<&mut Self> {
+        let continuity = composition::joint_continuity(self.segments.last().unwrap(), &segment)
+            .ok_or(Error::DisconnectedCurves)?;
+        let (t0, t1) = segment.parameter_range();
+        let back = *self.breakpoints.last().unwrap();
+        self.breakpoints.push(back + t1 - t0);
+        self.continuities.push(continuity);
+        self.segments.push(segment);
+        Ok(self)
+    }
+
+    /// Adds a segment to the back of the curve.
+    /// # Panics
+    /// Panic occurs if the back of the curve is not near the front of `segment`.
+    #[inline(always)]
+    pub fn push(&mut self, segment: C) -> &mut Self {
+        self.try_push(segment)
+            .unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<C> CompositeCurve<C> {
+    /// Returns the segments.
+    #[inline(always)]
+    pub fn segments(&self) -> &[C] { &self.segments }
+    /// Returns the segments.
+    #[inline(always)]
+    pub fn into_segments(self) -> Vec<C> { self.segments }
+    /// Returns the number of the segments.
+    #[inline(always)]
+    pub fn num_segments(&self) -> usize { self.segments.len() }
+    /// Returns the parameters of the front of the curve, the joints, and the back of the curve.
+    #[inline(always)]
+    pub fn breakpoints(&self) -> &[f64] { &self.breakpoints }
+    /// Returns the continuities at the joints.
+    #[inline(always)]
+    pub fn continuities(&self) -> &[Continuity] { &self.continuities }
+    /// Returns the weakest continuity at the joints.
+    /// Returns `C1` if the curve consists of only one segment.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// use composition::Continuity;
+    /// let curve = BSplineCurve::new(
+    ///     KnotVec::uniform_knot(2, 3),
+    ///     vec![
+    ///         Vector2::new(0.0, 0.0),
+    ///         Vector2::new(1.0, 2.0),
+    ///         Vector2::new(2.0, 0.0),
+    ///         Vector2::new(3.0, 2.0),
+    ///         Vector2::new(4.0, 0.0),
+    ///     ],
+    /// );
+    /// let composite = CompositeCurve::new(curve.bezier_decomposition());
+    /// assert_eq!(composite.num_segments(), 3);
+    /// assert_eq!(composite.continuity(), Continuity::C1);
+    /// ```
+    #[inline(always)]
+    pub fn continuity(&self) -> Continuity {
+        self.continuities
+            .iter()
+            .min()
+            .cloned()
+            .unwrap_or(Continuity::C1)
+    }
+    /// Returns the index of the segment including the parameter `t`.
+    /// The joints are included in the latter segments.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let curve = BSplineCurve::new(
+    ///     KnotVec::uniform_knot(1, 4),
+    ///     vec![
+    ///         Vector2::new(0.0, 0.0),
+    ///         Vector2::new(1.0, 1.0),
+    ///         Vector2::new(2.0, 0.0),
+    ///         Vector2::new(3.0, 1.0),
+    ///         Vector2::new(4.0, 0.0),
+    ///     ],
+    /// );
+    /// let composite = CompositeCurve::new(curve.bezier_decomposition());
+    /// assert_eq!(composite.segment_index(-1.0), 0);
+    /// assert_eq!(composite.segment_index(0.1), 0);
+    /// assert_eq!(composite.segment_index(0.25), 1);
+    /// assert_eq!(composite.segment_index(0.6), 2);
+    /// assert_eq!(composite.segment_index(1.0), 3);
+    /// assert_eq!(composite.segment_index(2.0), 3);
+    /// ```
+    pub fn segment_index(&self, t: f64) -> usize {
+        let n = self.segments.len();
+        let idx = match self.breakpoints[1..n]
+            .binary_search_by(|x| x.partial_cmp(&t).unwrap_or(Ordering::Less))
+        {
+            Ok(idx) => idx + 1,
+            Err(idx) => idx,
+        };
+        usize::min(idx, n - 1)
+    }
+}
+
+impl<C: ParametricCurve> CompositeCurve<C> {
+    /// Returns the index of the segment and the parameter of the segment
+    /// corresponding to the parameter `t` of the composite curve.
+    #[inline(always)]
+    pub fn local_parameter(&self, t: f64) -> (usize, f64) {
+        let idx = self.segment_index(t);
+        let (t0, _) = self.segments[idx].parameter_range();
+        (idx, t0 + t - self.breakpoints[idx])
+    }
+}
+
+impl<C: Composable> CompositeCurve<C>
+where
+    C::Point: Tolerance,
+    C::Vector: InnerSpace<Scalar = f64> + Tolerance,
+{
+    /// Concats all segments into a single curve, keeping the continuities at the joints.
+    ///
+    /// The parameter of the result may be different from the one of the composite curve,
+    /// since the parameters of the segments are scaled at the `G1` joints.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let line = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)],
+    /// );
+    /// let arc = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(2),
+    ///     vec![Vector2::new(1.0, 0.0), Vector2::new(2.0, 0.0), Vector2::new(2.0, 1.0)],
+    /// );
+    /// let curve = CompositeCurve::new(vec![line, arc]);
+    /// let single = curve.try_concat_segments().unwrap();
+    /// assert_near!(single.front(), curve.front());
+    /// assert_near!(single.back(), curve.back());
+    /// ```
+    pub fn try_concat_segments(&self) -> Result<C> {
+        self.segments[1..]
+            .iter()
+            .zip(&self.continuities)
+            .try_fold(self.segments[0].clone(), |curve, (segment, continuity)| {
+                composition::concat(&curve, segment, *continuity)
+            })
+    }
+}
+
+impl<C: ParametricCurve> ParametricCurve for CompositeCurve<C> {
+    type Point = C::Point;
+    type Vector = C::Vector;
+    #[inline(always)]
+    fn subs(&self, t: f64) -> C::Point {
+        let (idx, t) = self.local_parameter(t);
+        self.segments[idx].subs(t)
+    }
+    #[inline(always)]
+    fn der(&self, t: f64) -> C::Vector {
+        let (idx, t) = self.local_parameter(t);
+        self.segments[idx].der(t)
+    }
+    #[inline(always)]
+    fn der2(&self, t: f64) -> C::Vector {
+        let (idx, t) = self.local_parameter(t);
+        self.segments[idx].der2(t)
+    }
+    #[inline(always)]
+    fn parameter_range(&self) -> (f64, f64) {
+        (self.breakpoints[0], *self.breakpoints.last().unwrap())
+    }
+}
+
+impl<C: Invertible + Clone> Invertible for CompositeCurve<C> {
+    fn invert(&mut self) {
+        let (t0, t1) = (self.breakpoints[0], *self.breakpoints.last().unwrap());
+        self.segments.iter_mut().for_each(Invertible::invert);
+        self.segments.reverse();
+        self.breakpoints.reverse();
+        self.breakpoints.iter_mut().for_each(|t| *t = t0 + t1 - *t);
+        self.continuities.reverse();
+    }
+    fn inverse(&self) -> Self {
+        let mut curve = self.clone();
+        curve.invert();
+        curve
+    }
+}
+
+impl<C: Transformed<T> + Clone, T: Copy> Transformed<T> for CompositeCurve<C> {
+    #[inline(always)]
+    fn transform_by(&mut self, trans: T) {
+        self.segments
+            .iter_mut()
+            .for_each(|segment| segment.transform_by(trans));
+    }
+    #[inline(always)]
+    fn transformed(&self, trans: T) -> Self {
+        let mut curve = self.clone();
+        curve.transform_by(trans);
+        curve
+    }
+}
+
+impl<C: ParametricCurve + ParameterDivision1D> ParameterDivision1D for CompositeCurve<C> {
+    fn parameter_division(&self, tol: f64) -> Vec<f64> {
+        let mut res = vec![self.breakpoints[0]];
+        self.segments
+            .iter()
+            .zip(&self.breakpoints)
+            .for_each(|(segment, t)| {
+                let (t0, _) = segment.parameter_range();
+                let division = segment.parameter_division(tol);
+                res.extend(division.into_iter().skip(1).map(|s| t + s - t0));
+            });
+        res
+    }
+}
+
+#[test]
+fn composite_curve_invert() {
+    let curve = BSplineCurve::new(
+        KnotVec::uniform_knot(2, 3),
+        vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 2.0, 0.0),
+            Vector3::new(2.0, 0.0, 1.0),
+            Vector3::new(3.0, 2.0, 0.0),
+            Vector3::new(4.0, 0.0, 1.0),
+        ],
+    );
+    let mut segments = curve.bezier_decomposition();
+    segments[1].knot_translate(3.0);
+    segments[2].knot_translate(-2.0);
+    let composite = CompositeCurve::new(segments);
+    let inverse = composite.inverse();
+    assert_eq!(
+        ParametricCurve::parameter_range(&composite),
+        inverse.parameter_range()
+    );
+    const N: usize = 20;
+    for i in 0..=N {
+        let t = i as f64 / N as f64;
+        assert_near!(
+            ParametricCurve::subs(&curve, t),
+            composite.subs(t),
+            "parameter: {}",
+            t
+        );
+        assert_near!(composite.subs(t), inverse.subs(1.0 - t), "parameter: {}", t);
+    }
+    let division = composite.parameter_division(0.01);
+    assert_eq!(division[0], 0.0);
+    assert_eq!(*division.last().unwrap(), 1.0);
+    assert!(division.windows(2).all(|a| a[0] < a[1]));
+}
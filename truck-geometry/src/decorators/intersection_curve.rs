@@ -0,0 +1,296 @@
+use super::*;
+
+const SEARCH_TRIALS: usize = 100;
+const MAX_SUBDIVISION: usize = 16;
+
+impl<C, S> IntersectionCurve<C, S> {
+    /// Creates the intersection curve of `surface0` and `surface1` approximated by `leader`.
+    #[inline(always)]
+    pub fn new(surface0: S, surface1: S, leader: C) -> Self {
+        IntersectionCurve {
+            surface0,
+            surface1,
+            leader,
+        }
+    }
+    /// Returns the first surface
+    #[inline(always)]
+    pub fn surface0(&self) -> &S { &self.surface0 }
+    /// Returns the second surface
+    #[inline(always)]
+    pub fn surface1(&self) -> &S { &self.surface1 }
+    /// Returns the approximation of the intersection curve
+    #[inline(always)]
+    pub fn leader(&self) -> &C { &self.leader }
+    /// Returns the mutable reference of the approximation of the intersection curve
+    #[inline(always)]
+    pub fn leader_mut(&mut self) -> &mut C { &mut self.leader }
+    /// Returns the approximation of the intersection curve
+    #[inline(always)]
+    pub fn into_leader(self) -> C { self.leader }
+}
+
+impl<C, S> IntersectionCurve<C, S>
+where
+    C: ParametricCurve<Point = Point3, Vector = Vector3>,
+    S: ParametricSurface<Point = Point3, Vector = Vector3> + BoundedSurface,
+{
+    /// Returns the point on the intersection, the normals of the two surfaces at the point.
+    fn search(&self, t: f64) -> Option<(Point3, Vector3, Vector3)> {
+        let origin = self.leader.subs(t);
+        let der = self.leader.der(t);
+        let mut point = origin;
+        let mut hint0 = presearch(&self.surface0, point);
+        let mut hint1 = presearch(&self.surface1, point);
+        for _ in 0..SEARCH_TRIALS {
+            hint0 = surface_search_nearest_parameter(&self.surface0, point, hint0, SEARCH_TRIALS)?;
+            hint1 = surface_search_nearest_parameter(&self.surface1, point, hint1, SEARCH_TRIALS)?;
+            let (q0, n0) = (
+                self.surface0.subs(hint0.0, hint0.1),
+                self.surface0.normal(hint0.0, hint0.1),
+            );
+            let (q1, n1) = (
+                self.surface1.subs(hint1.0, hint1.1),
+                self.surface1.normal(hint1.0, hint1.1),
+            );
+            // the intersection of the two tangent planes and the normal plane of the leader
+            let mat = Matrix3::from_cols(n0, n1, der).transpose();
+            let vec = Vector3::new(
+                n0.dot(q0.to_vec()),
+                n1.dot(q1.to_vec()),
+                der.dot(origin.to_vec()),
+            );
+            let next = Point3::from_vec(mat.invert()? * vec);
+            if next.near(&point) {
+                return Some((next, n0, n1));
+            }
+            point = next;
+        }
+        None
+    }
+
+    /// Returns the point on the intersection in the normal plane of the leader at `t`.
+    /// Returns `None` if the Newton's method does not converge.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// // the planes z = 0 and x = 0.5
+    /// let surface0 = Plane::new(
+    ///     Point3::new(0.0, 0.0, 0.0),
+    ///     Point3::new(1.0, 0.0, 0.0),
+    ///     Point3::new(0.0, 1.0, 0.0),
+    /// )
+    /// .into_bspline();
+    /// let surface1 = Plane::new(
+    ///     Point3::new(0.5, 0.0, -1.0),
+    ///     Point3::new(0.5, 1.0, -1.0),
+    ///     Point3::new(0.5, 0.0, 1.0),
+    /// )
+    /// .into_bspline();
+    /// let leader = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector3::new(0.5, 0.0, 0.1), Vector3::new(0.5, 1.0, 0.1)],
+    /// );
+    /// let curve = IntersectionCurve::new(surface0, surface1, leader);
+    /// assert_near!(curve.search_intersection(0.3).unwrap(), Point3::new(0.5, 0.3, 0.0));
+    /// ```
+    #[inline(always)]
+    pub fn search_intersection(&self, t: f64) -> Option<Point3> {
+        self.search(t).map(|(pt, _, _)| pt)
+    }
+
+    /// Re-evaluates the intersection curve with the tolerance `tol`, and returns the polyline
+    /// whose vertices are on the intersection.
+    ///
+    /// The parameters of the vertices are the ones of the leader, and each segment is divided
+    /// until the distance between its midpoint and the intersection is less than `tol`.
+    /// Returns `None` if the search of the intersection fails.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// // the plane z = 0
+    /// let surface0 = Plane::new(
+    ///     Point3::new(-2.0, -2.0, 0.0),
+    ///     Point3::new(2.0, -2.0, 0.0),
+    ///     Point3::new(-2.0, 2.0, 0.0),
+    /// )
+    /// .into_bspline();
+    /// // the parabolic cylinder z = x^2 - 0.25
+    /// let surface1 = BSplineSurface::new(
+    ///     (KnotVec::bezier_knot(2), KnotVec::bezier_knot(1)),
+    ///     vec![
+    ///         vec![Vector3::new(0.0, 0.0, -0.25), Vector3::new(0.0, 1.0, -0.25)],
+    ///         vec![Vector3::new(0.5, 0.0, -0.25), Vector3::new(0.5, 1.0, -0.25)],
+    ///         vec![Vector3::new(1.0, 0.0, 0.75), Vector3::new(1.0, 1.0, 0.75)],
+    ///     ],
+    /// );
+    /// let leader = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector3::new(0.4, 0.0, 0.0), Vector3::new(0.6, 1.0, 0.0)],
+    /// );
+    /// let curve = IntersectionCurve::new(surface0, surface1, leader);
+    /// let polyline = curve.polyline(1.0e-3).unwrap();
+    /// assert_eq!(polyline.parameter_range(), (0.0, 1.0));
+    /// for pt in polyline.points() {
+    ///     assert_near!(pt.x, 0.5);
+    ///     assert_near!(pt.z, 0.0);
+    /// }
+    /// ```
+    pub fn polyline(&self, tol: f64) -> Option<PolylineCurve<Point3>>
+    where C: ParameterDivision1D {
+        let division = self.leader.parameter_division(tol);
+        let mut params = vec![division[0]];
+        let mut points = vec![self.search_intersection(division[0])?];
+        for t in division.into_iter().skip(1) {
+            let pt = self.search_intersection(t)?;
+            let mut stack = vec![(t, pt, 0)];
+            while let Some((t1, pt1, depth)) = stack.pop() {
+                let (t0, pt0) = (*params.last().unwrap(), *points.last().unwrap());
+                let mid = (t0 + t1) / 2.0;
+                let pt_mid = self.search_intersection(mid)?;
+                if depth < MAX_SUBDIVISION && pt_mid.distance(pt0.midpoint(pt1)) > tol {
+                    stack.push((t1, pt1, depth + 1));
+                    stack.push((mid, pt_mid, depth + 1));
+                } else {
+                    params.push(t1);
+                    points.push(pt1);
+                }
+            }
+        }
+        PolylineCurve::try_with_parameters(points, params).ok()
+    }
+}
+
+impl<S> IntersectionCurve<PolylineCurve<Point3>, S>
+where S: ParametricSurface<Point = Point3, Vector = Vector3> + BoundedSurface
+{
+    /// Returns the curves in the parameter spaces of `surface0` and `surface1` whose vertices
+    /// correspond to the ones of the leader polyline, with the same parameters.
+    ///
+    /// The parameters of each vertex are searched from the ones of the previous vertex, so the
+    /// vertices of the leader are assumed to be on the both surfaces, e.g. the leaders returned
+    /// by [`intersection_curve`]. Returns `None` if the search fails.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// // the planes z = 0 and x = 0.5
+    /// let surface0 = Plane::new(
+    ///     Point3::new(0.0, 0.0, 0.0),
+    ///     Point3::new(1.0, 0.0, 0.0),
+    ///     Point3::new(0.0, 1.0, 0.0),
+    /// )
+    /// .into_bspline();
+    /// let surface1 = Plane::new(
+    ///     Point3::new(0.5, 0.0, -1.0),
+    ///     Point3::new(0.5, 1.0, -1.0),
+    ///     Point3::new(0.5, 0.0, 1.0),
+    /// )
+    /// .into_bspline();
+    /// let leader = PolylineCurve::new(vec![
+    ///     Point3::new(0.5, 0.0, 0.0),
+    ///     Point3::new(0.5, 0.5, 0.0),
+    ///     Point3::new(0.5, 1.0, 0.0),
+    /// ]);
+    /// let curve = IntersectionCurve::new(surface0, surface1, leader);
+    /// let (curve0, curve1) = curve.parameter_curves().unwrap();
+    /// assert_eq!(curve0.parameters(), curve.leader().parameters());
+    /// assert_near!(curve0.subs(1.0), Point2::new(0.5, 0.5));
+    /// assert_near!(curve1.subs(1.0), Point2::new(0.5, 0.5));
+    /// assert_near!(curve1.back(), Point2::new(1.0, 0.5));
+    /// ```
+    ///
+    /// [`intersection_curve`]: ./fn.intersection_curve.html
+    pub fn parameter_curves(&self) -> Option<(PolylineCurve<Point2>, PolylineCurve<Point2>)> {
+        let points = self.leader.points();
+        let mut hint0 = presearch(&self.surface0, points[0]);
+        let mut hint1 = presearch(&self.surface1, points[0]);
+        let mut params0 = Vec::with_capacity(points.len());
+        let mut params1 = Vec::with_capacity(points.len());
+        for pt in points {
+            hint0 = surface_search_nearest_parameter(&self.surface0, *pt, hint0, SEARCH_TRIALS)?;
+            hint1 = surface_search_nearest_parameter(&self.surface1, *pt, hint1, SEARCH_TRIALS)?;
+            params0.push(Point2::new(hint0.0, hint0.1));
+            params1.push(Point2::new(hint1.0, hint1.1));
+        }
+        let params = self.leader.parameters().clone();
+        Some((
+            PolylineCurve::with_parameters(params0, params.clone()),
+            PolylineCurve::with_parameters(params1, params),
+        ))
+    }
+}
+
+impl<C, S> ParametricCurve for IntersectionCurve<C, S>
+where
+    C: ParametricCurve<Point = Point3, Vector = Vector3>,
+    S: ParametricSurface<Point = Point3, Vector = Vector3> + BoundedSurface,
+{
+    type Point = Point3;
+    type Vector = Vector3;
+    /// Returns the point on the intersection, or the point of the leader if the search fails.
+    #[inline(always)]
+    fn subs(&self, t: f64) -> Point3 {
+        self.search_intersection(t)
+            .unwrap_or_else(|| self.leader.subs(t))
+    }
+    /// Returns the derivation of the leader projected to the tangent line of the intersection.
+    #[inline(always)]
+    fn der(&self, t: f64) -> Vector3 {
+        let der = self.leader.der(t);
+        match self.search(t) {
+            Some((_, n0, n1)) => {
+                let dir = n0.cross(n1);
+                match dir.so_small() {
+                    true => der,
+                    false => dir * (der.dot(dir) / dir.magnitude2()),
+                }
+            }
+            None => der,
+        }
+    }
+    /// Returns the 2nd-order derivation of the leader.
+    #[inline(always)]
+    fn der2(&self, t: f64) -> Vector3 { self.leader.der2(t) }
+    #[inline(always)]
+    fn parameter_range(&self) -> (f64, f64) { self.leader.parameter_range() }
+}
+
+impl<C: Invertible, S: Clone> Invertible for IntersectionCurve<C, S> {
+    #[inline(always)]
+    fn invert(&mut self) { self.leader.invert() }
+    #[inline(always)]
+    fn inverse(&self) -> Self {
+        IntersectionCurve {
+            surface0: self.surface0.clone(),
+            surface1: self.surface1.clone(),
+            leader: self.leader.inverse(),
+        }
+    }
+}
+
+impl<C, S, T> Transformed<T> for IntersectionCurve<C, S>
+where
+    C: Transformed<T>,
+    S: Transformed<T>,
+    T: Copy,
+{
+    #[inline(always)]
+    fn transform_by(&mut self, trans: T) {
+        self.surface0.transform_by(trans);
+        self.surface1.transform_by(trans);
+        self.leader.transform_by(trans);
+    }
+    #[inline(always)]
+    fn transformed(&self, trans: T) -> Self {
+        IntersectionCurve {
+            surface0: self.surface0.transformed(trans),
+            surface1: self.surface1.transformed(trans),
+            leader: self.leader.transformed(trans),
+        }
+    }
+}
+
+impl<C: ParameterDivision1D, S> ParameterDivision1D for IntersectionCurve<C, S> {
+    #[inline(always)]
+    fn parameter_division(&self, tol: f64) -> Vec<f64> { self.leader.parameter_division(tol) }
+}
@@ -1,4 +1,5 @@
 use crate::*;
+use crate::composition::Continuity;
 use std::ops::{Deref, DerefMut, Mul};
 
 /// surface constructed by revoluting a curve
@@ -102,5 +103,134 @@ pub struct Processor<E, T> {
     orientation: bool,
 }
 
+/// curve consisting of the segments connected in turn
+///
+/// The parameter of each segment is translated so that the front coincides with the back of
+/// the previous one, and the continuity at each joint is recorded.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// use composition::Continuity;
+/// let line = BSplineCurve::new(
+///     KnotVec::bezier_knot(1),
+///     vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)],
+/// );
+/// let arc = BSplineCurve::new(
+///     KnotVec::bezier_knot(2),
+///     vec![Vector2::new(1.0, 0.0), Vector2::new(2.0, 0.0), Vector2::new(2.0, 1.0)],
+/// );
+/// let curve = CompositeCurve::new(vec![line, arc]);
+/// assert_eq!(curve.parameter_range(), (0.0, 2.0));
+/// assert_eq!(curve.continuities(), &[Continuity::G1]);
+/// assert_near!(curve.subs(0.5), Point2::new(0.5, 0.0));
+/// assert_near!(curve.subs(2.0), Point2::new(2.0, 1.0));
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CompositeCurve<C> {
+    segments: Vec<C>,
+    breakpoints: Vec<f64>,
+    continuities: Vec<Continuity>,
+}
+
+/// intersection curve of two surfaces
+///
+/// The leader is an approximation of the intersection curve, for example a polyline or a B-spline
+/// curve created by booleans. Each point of the curve is re-evaluated by projecting the point of
+/// the leader to the both surfaces, so that the intersection can be recalculated with another
+/// tolerance after the leader becomes stale.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// // the plane z = 0
+/// let surface0 = Plane::new(
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+/// )
+/// .into_bspline();
+/// // the parabolic cylinder z = x^2 - 0.25
+/// let surface1 = BSplineSurface::new(
+///     (KnotVec::bezier_knot(2), KnotVec::bezier_knot(1)),
+///     vec![
+///         vec![Vector3::new(0.0, 0.0, -0.25), Vector3::new(0.0, 1.0, -0.25)],
+///         vec![Vector3::new(0.5, 0.0, -0.25), Vector3::new(0.5, 1.0, -0.25)],
+///         vec![Vector3::new(1.0, 0.0, 0.75), Vector3::new(1.0, 1.0, 0.75)],
+///     ],
+/// );
+/// // the rough approximation of the intersection line x = 0.5, z = 0
+/// let leader = BSplineCurve::new(
+///     KnotVec::bezier_knot(1),
+///     vec![Vector3::new(0.4, 0.0, 0.0), Vector3::new(0.6, 1.0, 0.0)],
+/// );
+/// let curve = IntersectionCurve::new(surface0, surface1, leader);
+/// const N: usize = 10;
+/// for i in 0..=N {
+///     let t = i as f64 / N as f64;
+///     let pt = curve.subs(t);
+///     assert_near!(pt.x, 0.5);
+///     assert_near!(pt.z, 0.0);
+///     assert_near!(curve.der(t).normalize(), Vector3::unit_y());
+/// }
+/// assert_near!(curve.subs(0.5), Point3::new(0.5, 0.5, 0.0));
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IntersectionCurve<C, S> {
+    surface0: S,
+    surface1: S,
+    leader: C,
+}
+
+/// surface trimmed by the closed curves in its parameter space
+///
+/// The domain of the surface is the region enclosed by the boundaries with the non-zero
+/// winding number: the outer boundaries are counter-clockwise, and the holes are clockwise.
+/// If there are no boundaries, the domain is the whole base surface.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// let plane = Plane::new(
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+/// );
+/// // the square with the square hole
+/// let outer = PolylineCurve::new(vec![
+///     Point2::new(0.0, 0.0),
+///     Point2::new(4.0, 0.0),
+///     Point2::new(4.0, 4.0),
+///     Point2::new(0.0, 4.0),
+///     Point2::new(0.0, 0.0),
+/// ]);
+/// let hole = PolylineCurve::new(vec![
+///     Point2::new(1.0, 1.0),
+///     Point2::new(1.0, 3.0),
+///     Point2::new(3.0, 3.0),
+///     Point2::new(3.0, 1.0),
+///     Point2::new(1.0, 1.0),
+/// ]);
+/// let surface = TrimmedSurface::new(plane, vec![outer, hole]);
+///
+/// // The geometry is the one of the base surface.
+/// assert_near!(surface.subs(0.5, 2.5), Point3::new(0.5, 2.5, 0.0));
+/// assert_near!(surface.normal(0.5, 2.5), Vector3::unit_z());
+///
+/// // point-in-trim queries
+/// assert_eq!(surface.winding_number(Point2::new(0.5, 2.0), 0.01), 1);
+/// assert_eq!(surface.winding_number(Point2::new(2.0, 2.0), 0.01), 0);
+/// assert!(surface.contains_parameter(Point2::new(3.5, 3.5), 0.01));
+/// assert!(!surface.contains_parameter(Point2::new(2.0, 2.0), 0.01));
+/// assert!(!surface.contains_parameter(Point2::new(5.0, 2.0), 0.01));
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TrimmedSurface<S, C> {
+    base: S,
+    boundaries: Vec<C>,
+}
+
 mod revolved_curve;
 mod processor;
+mod composite_curve;
+mod intersection_curve;
+mod surface_intersection;
+mod trimmed_surface;
+pub use surface_intersection::intersection_curve;
@@ -13,6 +13,12 @@ impl<E, T: One> Processor<E, T> {
     /// Returns the reference of entity
     #[inline(always)]
     pub fn entity(&self) -> &E { &self.entity }
+    /// Returns the transform applied to the entity.
+    #[inline(always)]
+    pub fn transform(&self) -> &T { &self.transform }
+    /// Returns whether the entity is not inverted.
+    #[inline(always)]
+    pub fn orientation(&self) -> bool { self.orientation }
     #[inline(always)]
     fn sign(&self) -> f64 {
         match self.orientation {
@@ -148,7 +154,13 @@ where
     S: BoundedSurface<Point = Point3, Vector = Vector3>,
     T: Transform<S::Point> + Clone,
 {
-    fn parameter_range(&self) -> ((f64, f64), (f64, f64)) { self.entity.parameter_range() }
+    fn parameter_range(&self) -> ((f64, f64), (f64, f64)) {
+        let (urange, vrange) = self.entity.parameter_range();
+        match self.orientation {
+            true => (urange, vrange),
+            false => (vrange, urange),
+        }
+    }
 }
 
 impl<E, T> Deref for Processor<E, T> {
@@ -223,7 +235,11 @@ impl<S: ParameterDivision2D> ParameterDivision2D for Processor<S, Matrix3> {
         let n = a[0][0] * a[0][0] + a[0][1] * a[0][1] + a[0][2] * a[0][2]
             + a[1][0] * a[1][0] + a[1][1] * a[1][1] + a[1][2] * a[1][2]
             + a[2][0] * a[2][0] + a[2][1] * a[2][1] + a[2][2] * a[2][2];
-        self.entity.parameter_division(tol / n.sqrt())
+        let (udiv, vdiv) = self.entity.parameter_division(tol / n.sqrt());
+        match self.orientation {
+            true => (udiv, vdiv),
+            false => (vdiv, udiv),
+        }
     }
 }
 
@@ -234,7 +250,11 @@ impl<S: ParameterDivision2D> ParameterDivision2D for Processor<S, Matrix4> {
             + a[1][0] * a[1][0] + a[1][1] * a[1][1] + a[1][2] * a[1][2] + a[1][3] * a[1][3]
             + a[2][0] * a[2][0] + a[2][1] * a[2][1] + a[2][2] * a[2][2] + a[2][3] * a[2][3]
             + a[3][0] * a[3][0] + a[3][1] * a[3][1] + a[3][2] * a[3][2] + a[3][3] * a[3][3];
-        self.entity.parameter_division(tol / n.sqrt())
+        let (udiv, vdiv) = self.entity.parameter_division(tol / n.sqrt());
+        match self.orientation {
+            true => (udiv, vdiv),
+            false => (vdiv, udiv),
+        }
     }
 }
 
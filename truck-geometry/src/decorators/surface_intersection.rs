@@ -0,0 +1,434 @@
+use super::*;
+
+/// the chord error of the marched polylines relative to the size of the surfaces
+const MARCHING_PRECISION: f64 = 1.0e-3;
+/// the maximum length of the steps relative to the size of the surfaces
+const MAX_STEP_RATIO: f64 = 0.05;
+/// the minimum number of the divisions of each parameter for searching the seeds
+const SEED_DIVISION: usize = 8;
+/// the maximum number of the trials of the marching from one seed
+const MAX_STEPS: usize = 10_000;
+/// the maximum number of the iterations of the Newton's method
+const NEWTON_TRIALS: usize = 32;
+
+type Surface = BSplineSurface<Vector3>;
+
+/// The parameters `(u0, v0, u1, v1)` of the two surfaces are stored in one `Vector4`.
+struct Marcher<'a> {
+    surface0: &'a Surface,
+    surface1: &'a Surface,
+    ranges: [(f64, f64); 4],
+    tol: f64,
+    max_step: f64,
+}
+
+impl<'a> Marcher<'a> {
+    fn new(surface0: &'a Surface, surface1: &'a Surface) -> Self {
+        let ((u0, v0), (u1, v1)) = (surface0.parameter_range(), surface1.parameter_range());
+        let bdb = surface0.roughly_bounding_box() + surface1.roughly_bounding_box();
+        let size = bdb.diameter();
+        Marcher {
+            surface0,
+            surface1,
+            ranges: [u0, v0, u1, v1],
+            tol: size * MARCHING_PRECISION,
+            max_step: size * MAX_STEP_RATIO,
+        }
+    }
+
+    #[inline(always)]
+    fn point(&self, x: Vector4) -> Point3 { ParametricSurface::subs(self.surface0, x[0], x[1]) }
+
+    #[inline(always)]
+    fn residual(&self, x: Vector4) -> Vector3 {
+        self.point(x) - ParametricSurface::subs(self.surface1, x[2], x[3])
+    }
+
+    // the derivations of the residual by the parameters
+    #[inline(always)]
+    fn jacobian(&self, x: Vector4) -> [Vector3; 4] {
+        [
+            self.surface0.uder(x[0], x[1]),
+            self.surface0.vder(x[0], x[1]),
+            -self.surface1.uder(x[2], x[3]),
+            -self.surface1.vder(x[2], x[3]),
+        ]
+    }
+
+    fn clamp(&self, mut x: Vector4) -> Vector4 {
+        for (i, (min, max)) in self.ranges.iter().enumerate() {
+            x[i] = f64::min(f64::max(x[i], *min), *max);
+        }
+        x
+    }
+
+    /// Returns the unit tangent of the intersection and the derivation of the parameters along
+    /// the tangent. Returns `None` if the surfaces are tangent to each other.
+    fn tangent(&self, x: Vector4) -> Option<(Vector3, Vector4)> {
+        let [u0, v0, u1, v1] = self.jacobian(x);
+        let (n0, n1) = (u0.cross(v0), u1.cross(v1));
+        let dir = n0.cross(n1);
+        if dir.magnitude() <= n0.magnitude() * n1.magnitude() * TOLERANCE {
+            return None;
+        }
+        let dir = dir.normalize();
+        let inverse = |ud: Vector3, vd: Vector3| {
+            let (a, b, c) = (ud.dot(ud), ud.dot(vd), vd.dot(vd));
+            let (p, q) = (ud.dot(dir), vd.dot(dir));
+            let det = a * c - b * b;
+            Vector2::new(c * p - b * q, a * q - b * p) / det
+        };
+        // The signs of the derivations of the second surface are turned over in the jacobian.
+        let (d0, d1) = (inverse(u0, v0), inverse(-u1, -v1));
+        Some((dir, Vector4::new(d0.x, d0.y, d1.x, d1.y)))
+    }
+
+    /// Projects `x` to the intersection by the Newton's method of the minimum norm steps.
+    fn seed(&self, mut x: Vector4) -> Option<Vector4> {
+        for _ in 0..NEWTON_TRIALS {
+            let res = self.residual(x);
+            if res.so_small() {
+                return Some(x);
+            }
+            let jac = self.jacobian(x);
+            let mat = jac.iter().fold(Matrix3::zero(), |sum, j| {
+                sum + Matrix3::from_cols(*j * j.x, *j * j.y, *j * j.z)
+            });
+            let y = mat.invert()? * res;
+            let delta = Vector4::new(jac[0].dot(y), jac[1].dot(y), jac[2].dot(y), jac[3].dot(y));
+            x = self.clamp(x - delta);
+        }
+        None
+    }
+
+    /// Projects `x` to the intersection by the Newton's method, where the additional constraint
+    /// returns its value and its gradient.
+    fn correct<F>(&self, mut x: Vector4, constraint: F) -> Option<Vector4>
+    where F: Fn(Vector4) -> (f64, Vector4) {
+        for _ in 0..NEWTON_TRIALS {
+            let res = self.residual(x);
+            let (value, grad) = constraint(x);
+            let jac = self.jacobian(x);
+            let row = |i: usize| Vector4::new(jac[0][i], jac[1][i], jac[2][i], jac[3][i]);
+            let mat = Matrix4::from_cols(row(0), row(1), row(2), grad).transpose();
+            let delta = mat.invert()? * res.extend(value);
+            x = self.clamp(x - delta);
+            if delta.so_small() && res.so_small() {
+                return Some(x);
+            }
+        }
+        None
+    }
+
+    /// Returns the point on the intersection whose projection to the tangent at `x` is moved by
+    /// `step`.
+    fn correct_on_plane(
+        &self,
+        x: Vector4,
+        (dir, der): (Vector3, Vector4),
+        step: f64,
+    ) -> Option<Vector4> {
+        let target = self.point(x) + dir * step;
+        self.correct(x + der * step, |y| {
+            let (ud, vd) = (self.surface0.uder(y[0], y[1]), self.surface0.vder(y[0], y[1]));
+            let value = (self.point(y) - target).dot(dir);
+            (value, Vector4::new(ud.dot(dir), vd.dot(dir), 0.0, 0.0))
+        })
+    }
+
+    /// Returns the ratio of `step` to the boundary of the parameter ranges, the index of the
+    /// parameter and the value at the boundary, if `x + step` is out of the ranges.
+    fn boundary(&self, x: Vector4, step: Vector4) -> Option<(f64, usize, f64)> {
+        let mut res: Option<(f64, usize, f64)> = None;
+        for (i, (min, max)) in self.ranges.iter().enumerate() {
+            let bound = match x[i] + step[i] {
+                t if t > *max => *max,
+                t if t < *min => *min,
+                _ => continue,
+            };
+            let ratio = f64::max((bound - x[i]) / step[i], 0.0);
+            if res.map(|(r, _, _)| ratio < r).unwrap_or(true) {
+                res = Some((ratio, i, bound));
+            }
+        }
+        res
+    }
+
+    /// Marches from `start` along the intersection in the direction `sign`, and returns the
+    /// parameters of the vertices and whether the intersection is closed or not.
+    fn march(&self, start: Vector4, sign: f64) -> (Vec<Vector4>, bool) {
+        let origin = self.point(start);
+        let mut points = vec![start];
+        let mut step = self.max_step;
+        let mut left = false;
+        for _ in 0..MAX_STEPS {
+            let x = *points.last().unwrap();
+            let (dir, der) = match self.tangent(x) {
+                Some((dir, der)) => (dir * sign, der * sign),
+                None => break,
+            };
+            let boundary = self.boundary(x, der * step);
+            let next = match boundary {
+                Some((ratio, _, _)) if ratio.so_small() => break,
+                Some((ratio, i, bound)) => self.correct(x + der * step * ratio, |y| {
+                    (y[i] - bound, unit(i))
+                }),
+                None => self.correct_on_plane(x, (dir, der), step),
+            };
+            let length = step * boundary.map(|(ratio, _, _)| ratio).unwrap_or(1.0);
+            // The next point has to be on the same branch, and the chord has to be close to
+            // the curve.
+            let accepted = next.and_then(|next| {
+                let (p, q) = (self.point(x), self.point(next));
+                let (next_dir, _) = self.tangent(next)?;
+                let mid = self.correct_on_plane(x, (dir, der), length / 2.0)?;
+                let valid = p.distance(q) <= 2.0 * length
+                    && (next_dir * sign).dot(dir) > 0.0
+                    && self.point(mid).distance(p.midpoint(q)) <= self.tol;
+                Some(next).filter(|_| valid)
+            });
+            let next = match accepted {
+                Some(next) => next,
+                None => {
+                    step /= 2.0;
+                    match step < self.tol * TOLERANCE {
+                        true => break,
+                        false => continue,
+                    }
+                }
+            };
+            let (p, q) = (self.point(x), self.point(next));
+            if left && points.len() > 2 && segment_distance(origin, p, q) <= 2.0 * self.tol {
+                points.push(start);
+                return (points, true);
+            }
+            left = left || q.distance(origin) > 4.0 * self.tol;
+            points.push(next);
+            if boundary.is_some() {
+                break;
+            }
+            step = f64::min(step * 2.0, self.max_step);
+        }
+        (points, false)
+    }
+
+    /// Returns the parameters of the vertices of the intersection through `seed`.
+    fn trace(&self, seed: Vector4) -> Vec<Vector4> {
+        let (forward, closed) = self.march(seed, 1.0);
+        match closed {
+            true => forward,
+            false => {
+                let (mut backward, _) = self.march(seed, -1.0);
+                backward.reverse();
+                backward.extend(forward.into_iter().skip(1));
+                backward
+            }
+        }
+    }
+
+    /// Returns the pairs of the parameters of the cells of the two surfaces whose bounding boxes
+    /// overlap.
+    fn seeds(&self) -> Vec<Vector4> {
+        let cells0 = cells(self.surface0, self.tol);
+        let cells1 = cells(self.surface1, self.tol);
+        cells0
+            .iter()
+            .flat_map(|(bdb0, x0)| {
+                cells1
+                    .iter()
+                    .filter(move |(bdb1, _)| overlap(bdb0, bdb1))
+                    .map(move |(_, x1)| Vector4::new(x0.x, x0.y, x1.x, x1.y))
+            })
+            .collect()
+    }
+}
+
+#[inline(always)]
+fn unit(i: usize) -> Vector4 {
+    let mut vec = Vector4::zero();
+    vec[i] = 1.0;
+    vec
+}
+
+/// the distance between the point and the segment
+fn segment_distance(point: Point3, p: Point3, q: Point3) -> f64 {
+    let dir = q - p;
+    let t = match dir.so_small() {
+        true => 0.0,
+        false => ((point - p).dot(dir) / dir.magnitude2()).clamp(0.0, 1.0),
+    };
+    point.distance(p + dir * t)
+}
+
+#[inline(always)]
+fn overlap(bdb0: &BoundingBox<Point3>, bdb1: &BoundingBox<Point3>) -> bool {
+    let (min0, max0, min1, max1) = (bdb0.min(), bdb0.max(), bdb1.min(), bdb1.max());
+    (0..3).all(|i| min0[i] <= max1[i] && min1[i] <= max0[i])
+}
+
+/// Returns the division of the parameters bisected until the number of the spans is at least
+/// `SEED_DIVISION`.
+fn seed_division(mut params: Vec<f64>) -> Vec<f64> {
+    while params.len() <= SEED_DIVISION {
+        params = params
+            .windows(2)
+            .flat_map(|a| vec![a[0], (a[0] + a[1]) / 2.0])
+            .chain(params.last().cloned())
+            .collect();
+    }
+    params
+}
+
+/// Returns the bounding boxes of the cells of the surface expanded by `tol`, and the parameters
+/// of their centers.
+fn cells(surface: &Surface, tol: f64) -> Vec<(BoundingBox<Point3>, Vector2)> {
+    let (udiv, vdiv) = surface.parameter_division(tol);
+    let (udiv, vdiv) = (seed_division(udiv), seed_division(vdiv));
+    let margin = Vector3::new(tol, tol, tol);
+    udiv.windows(2)
+        .flat_map(|us| vdiv.windows(2).map(move |vs| (us[0], us[1], vs[0], vs[1])))
+        .map(|(u0, u1, v0, v1)| {
+            let mut bdb: BoundingBox<Point3> = (0..9)
+                .map(|i| {
+                    let (p, q) = ((i % 3) as f64 / 2.0, (i / 3) as f64 / 2.0);
+                    let (u, v) = (u0 + (u1 - u0) * p, v0 + (v1 - v0) * q);
+                    ParametricSurface::subs(surface, u, v)
+                })
+                .collect();
+            let (min, max) = (*bdb.min() - margin, *bdb.max() + margin);
+            bdb.push(&min);
+            bdb.push(&max);
+            (bdb, Vector2::new((u0 + u1) / 2.0, (v0 + v1) / 2.0))
+        })
+        .collect()
+}
+
+/// Returns the intersection curves of two B-spline surfaces.
+///
+/// The curves are traced by marching along the intersection from the seeds found in the
+/// overlapping cells of the surfaces, where each step is refined by the Newton's method. Each
+/// leader is the polyline whose vertices are on the both surfaces, whose chord error is about
+/// `1.0e-3` of the size of the surfaces and whose parameter is the chord length. The curves in the
+/// parameter spaces of the surfaces are obtained by [`IntersectionCurve::parameter_curves`].
+/// The closed intersections are returned as the polylines whose fronts coincide with their backs.
+///
+/// The points where the surfaces are tangent to each other are not traced, and the curves end
+/// at such points.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// // the plane z = 0
+/// let plane = Plane::new(
+///     Point3::new(-1.0, -1.0, 0.0),
+///     Point3::new(1.0, -1.0, 0.0),
+///     Point3::new(-1.0, 1.0, 0.0),
+/// )
+/// .into_bspline();
+/// // the paraboloid z = x^2 + y^2 - 0.5 on [-1, 1]^2
+/// let paraboloid = BSplineSurface::new(
+///     (KnotVec::bezier_knot(2), KnotVec::bezier_knot(2)),
+///     vec![
+///         vec![
+///             Vector3::new(-1.0, -1.0, 1.5),
+///             Vector3::new(-1.0, 0.0, -0.5),
+///             Vector3::new(-1.0, 1.0, 1.5),
+///         ],
+///         vec![
+///             Vector3::new(0.0, -1.0, -0.5),
+///             Vector3::new(0.0, 0.0, -2.5),
+///             Vector3::new(0.0, 1.0, -0.5),
+///         ],
+///         vec![
+///             Vector3::new(1.0, -1.0, 1.5),
+///             Vector3::new(1.0, 0.0, -0.5),
+///             Vector3::new(1.0, 1.0, 1.5),
+///         ],
+///     ],
+/// );
+///
+/// // the circle x^2 + y^2 = 0.5
+/// let curves = intersection_curve(&plane, &paraboloid);
+/// assert_eq!(curves.len(), 1);
+/// let leader = curves[0].leader();
+/// assert_near!(leader.front(), leader.back());
+/// for pt in leader.points() {
+///     assert!(f64::abs(pt.x * pt.x + pt.y * pt.y - 0.5) < 1.0e-4);
+///     assert!(pt.z.so_small());
+/// }
+///
+/// // the curves in the parameter spaces
+/// let (curve0, curve1) = curves[0].parameter_curves().unwrap();
+/// for ((pt, p0), p1) in leader.points().iter().zip(curve0.points()).zip(curve1.points()) {
+///     let uv = Point2::new((pt.x + 1.0) / 2.0, (pt.y + 1.0) / 2.0);
+///     assert_near!(*p0, uv);
+///     assert_near!(*p1, uv);
+/// }
+///
+/// // the plane x = 0.25 cuts the paraboloid along the parabola z = y^2 - 0.4375
+/// let plane = Plane::new(
+///     Point3::new(0.25, -1.0, -1.0),
+///     Point3::new(0.25, 1.0, -1.0),
+///     Point3::new(0.25, -1.0, 2.0),
+/// )
+/// .into_bspline();
+/// let curves = intersection_curve(&plane, &paraboloid);
+/// assert_eq!(curves.len(), 1);
+/// let leader = curves[0].leader();
+/// assert_near!(f64::abs(leader.front().y), 1.0);
+/// assert_near!(leader.front().y + leader.back().y, 0.0);
+/// for pt in leader.points() {
+///     assert_near!(pt.x, 0.25);
+///     assert_near!(pt.z, pt.y * pt.y - 0.4375);
+/// }
+/// ```
+///
+/// [`IntersectionCurve::parameter_curves`]: ./struct.IntersectionCurve.html#method.parameter_curves
+pub fn intersection_curve(
+    surface0: &BSplineSurface<Vector3>,
+    surface1: &BSplineSurface<Vector3>,
+) -> Vec<IntersectionCurve<PolylineCurve<Point3>, BSplineSurface<Vector3>>> {
+    let marcher = Marcher::new(surface0, surface1);
+    let mut polylines: Vec<Vec<Point3>> = Vec::new();
+    for seed in marcher.seeds() {
+        let seed = match marcher.seed(seed) {
+            Some(seed) => seed,
+            None => continue,
+        };
+        if marcher.tangent(seed).is_none() {
+            continue;
+        }
+        let pt = marcher.point(seed);
+        let traced = polylines.iter().any(|points| {
+            points
+                .windows(2)
+                .any(|a| segment_distance(pt, a[0], a[1]) <= 2.0 * marcher.tol)
+        });
+        if traced {
+            continue;
+        }
+        let mut points: Vec<Point3> = Vec::new();
+        for x in marcher.trace(seed) {
+            let pt = marcher.point(x);
+            if points.last().map(|last| !last.near(&pt)).unwrap_or(true) {
+                points.push(pt);
+            }
+        }
+        if points.len() > 1 {
+            polylines.push(points);
+        }
+    }
+    polylines
+        .into_iter()
+        .map(|points| {
+            let mut length = 0.0;
+            let params = std::iter::once(0.0)
+                .chain(points.windows(2).map(|a| {
+                    length += a[0].distance(a[1]);
+                    length
+                }))
+                .collect();
+            let leader = PolylineCurve::with_parameters(points, params);
+            IntersectionCurve::new(surface0.clone(), surface1.clone(), leader)
+        })
+        .collect()
+}
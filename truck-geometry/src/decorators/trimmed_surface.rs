@@ -0,0 +1,136 @@
+use super::*;
+use crate::errors::Error;
+
+impl<S, C> TrimmedSurface<S, C>
+where C: ParametricCurve<Point = Point2>
+{
+    /// Creates the surface trimmed by the closed curves in the parameter space of `base`.
+    /// # Failures
+    /// If the front of a boundary is not near its back, returns
+    /// [`Error::OpenBoundary`](../errors/enum.Error.html#variant.OpenBoundary).
+    pub fn try_new(base: S, boundaries: Vec<C>) -> Result<Self> {
+        match boundaries
+            .iter()
+            .position(|curve| !curve.front().near(&curve.back()))
+        {
+            Some(idx) => Err(Error::OpenBoundary(idx)),
+            None => Ok(TrimmedSurface { base, boundaries }),
+        }
+    }
+
+    /// Creates the surface trimmed by the closed curves in the parameter space of `base`.
+    /// # Panics
+    /// Panic occurs if a boundary is not closed.
+    #[inline(always)]
+    pub fn new(base: S, boundaries: Vec<C>) -> Self {
+        Self::try_new(base, boundaries).unwrap_or_else(|error| panic!("{}", error))
+    }
+}
+
+impl<S, C> TrimmedSurface<S, C> {
+    /// Returns the untrimmed surface.
+    #[inline(always)]
+    pub fn base(&self) -> &S { &self.base }
+    /// Returns the mutable reference of the untrimmed surface.
+    #[inline(always)]
+    pub fn base_mut(&mut self) -> &mut S { &mut self.base }
+    /// Returns the boundaries in the parameter space.
+    #[inline(always)]
+    pub fn boundaries(&self) -> &[C] { &self.boundaries }
+    /// Returns the untrimmed surface and the boundaries.
+    #[inline(always)]
+    pub fn into_parts(self) -> (S, Vec<C>) { (self.base, self.boundaries) }
+}
+
+impl<S, C> TrimmedSurface<S, C>
+where C: ParametricCurve<Point = Point2> + ParameterDivision1D
+{
+    /// Returns the polylines approximating the boundaries within the tolerance `tol`.
+    /// The last point of each polyline is the first one.
+    pub fn boundary_polylines(&self, tol: f64) -> Vec<Vec<Point2>> {
+        self.boundaries
+            .iter()
+            .map(|curve| {
+                let mut polyline: Vec<Point2> = curve
+                    .parameter_division(tol)
+                    .into_iter()
+                    .map(|t| curve.subs(t))
+                    .collect();
+                polyline.pop();
+                polyline.push(polyline[0]);
+                polyline
+            })
+            .collect()
+    }
+
+    /// Returns the sum of the winding numbers of the boundaries around `uv`, where
+    /// the boundaries are approximated by the polylines within the tolerance `tol`.
+    pub fn winding_number(&self, uv: Point2, tol: f64) -> i32 {
+        self.boundary_polylines(tol)
+            .iter()
+            .flat_map(|polyline| polyline.windows(2))
+            .map(|edge| {
+                let (a, b) = (edge[0] - uv, edge[1] - uv);
+                match (a[1] <= 0.0, b[1] <= 0.0) {
+                    // upward crossing with `uv` on the left
+                    (true, false) if a.perp_dot(b) > 0.0 => 1,
+                    // downward crossing with `uv` on the right
+                    (false, true) if a.perp_dot(b) < 0.0 => -1,
+                    _ => 0,
+                }
+            })
+            .sum()
+    }
+
+    /// Returns whether the parameter `uv` is in the domain of the trimmed surface.
+    /// If there are no boundaries, returns `true`.
+    #[inline(always)]
+    pub fn contains_parameter(&self, uv: Point2, tol: f64) -> bool {
+        self.boundaries.is_empty() || self.winding_number(uv, tol) != 0
+    }
+}
+
+impl<S: ParametricSurface, C: Clone> ParametricSurface for TrimmedSurface<S, C> {
+    type Point = S::Point;
+    type Vector = S::Vector;
+    #[inline(always)]
+    fn subs(&self, u: f64, v: f64) -> S::Point { self.base.subs(u, v) }
+    #[inline(always)]
+    fn uder(&self, u: f64, v: f64) -> S::Vector { self.base.uder(u, v) }
+    #[inline(always)]
+    fn vder(&self, u: f64, v: f64) -> S::Vector { self.base.vder(u, v) }
+    #[inline(always)]
+    fn uuder(&self, u: f64, v: f64) -> S::Vector { self.base.uuder(u, v) }
+    #[inline(always)]
+    fn uvder(&self, u: f64, v: f64) -> S::Vector { self.base.uvder(u, v) }
+    #[inline(always)]
+    fn vvder(&self, u: f64, v: f64) -> S::Vector { self.base.vvder(u, v) }
+    #[inline(always)]
+    fn normal(&self, u: f64, v: f64) -> S::Vector { self.base.normal(u, v) }
+}
+
+impl<S: BoundedSurface, C: Clone> BoundedSurface for TrimmedSurface<S, C> {
+    /// Returns the parameter range of the base surface.
+    #[inline(always)]
+    fn parameter_range(&self) -> ((f64, f64), (f64, f64)) { self.base.parameter_range() }
+}
+
+impl<S: ParameterDivision2D, C> ParameterDivision2D for TrimmedSurface<S, C> {
+    #[inline(always)]
+    fn parameter_division(&self, tol: f64) -> (Vec<f64>, Vec<f64>) {
+        self.base.parameter_division(tol)
+    }
+}
+
+impl<S: Transformed<T>, C: Clone, T> Transformed<T> for TrimmedSurface<S, C> {
+    /// Transforms the base surface. The boundaries in the parameter space are kept.
+    #[inline(always)]
+    fn transform_by(&mut self, trans: T) { self.base.transform_by(trans) }
+    #[inline(always)]
+    fn transformed(&self, trans: T) -> Self {
+        TrimmedSurface {
+            base: self.base.transformed(trans),
+            boundaries: self.boundaries.clone(),
+        }
+    }
+}
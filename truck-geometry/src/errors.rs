@@ -143,6 +143,103 @@ pub enum Error {
     /// assert_eq!(error, Err(Error::EmptyCurveCollector));
     /// ```
     EmptyCurveCollector,
+    /// The knot vector must consist of finite values.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// use errors::Error;
+    /// assert_eq!(
+    ///     KnotVec::try_new(vec![0.0, 0.0, f64::INFINITY, f64::INFINITY], 1),
+    ///     Err(Error::NonFiniteKnot(2)),
+    /// );
+    /// ```
+    NonFiniteKnot(usize),
+    /// The multiplicity of a knot must not be more than `degree + 1`.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// use errors::Error;
+    /// assert_eq!(
+    ///     KnotVec::try_new(vec![0.0, 0.0, 0.0, 1.0, 1.0], 1),
+    ///     Err(Error::TooLargeMultiplicity(0.0, 3)),
+    /// );
+    /// ```
+    TooLargeMultiplicity(f64, usize),
+    /// The back of the former curve and the front of the latter curve are not near.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// use composition::Continuity;
+    /// use errors::Error;
+    /// let curve0 = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)],
+    /// );
+    /// let curve1 = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector2::new(2.0, 0.0), Vector2::new(3.0, 0.0)],
+    /// );
+    /// assert_eq!(
+    ///     composition::concat(&curve0, &curve1, Continuity::C0),
+    ///     Err(Error::DisconnectedCurves),
+    /// );
+    /// ```
+    DisconnectedCurves,
+    /// The directions of the derivations of two curves are different at the joint.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// use composition::Continuity;
+    /// use errors::Error;
+    /// let curve0 = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)],
+    /// );
+    /// let curve1 = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector2::new(1.0, 0.0), Vector2::new(1.0, 1.0)],
+    /// );
+    /// assert_eq!(
+    ///     composition::concat(&curve0, &curve1, Continuity::C1),
+    ///     Err(Error::NotTangentContinuous),
+    /// );
+    /// ```
+    NotTangentContinuous,
+    /// The number of the parameters is different from the one of the points.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// use errors::Error;
+    /// assert_eq!(
+    ///     PolylineCurve::try_with_parameters(
+    ///         vec![Point2::new(0.0, 0.0), Point2::new(1.0, 0.0)],
+    ///         vec![0.0, 1.0, 2.0],
+    ///     ),
+    ///     Err(Error::DifferentLengthParameters(2, 3)),
+    /// );
+    /// ```
+    DifferentLengthParameters(usize, usize),
+    /// The boundary of the trimmed surface is not closed. The index of the boundary is attached.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// use errors::Error;
+    /// let plane = Plane::new(
+    ///     Point3::new(0.0, 0.0, 0.0),
+    ///     Point3::new(1.0, 0.0, 0.0),
+    ///     Point3::new(0.0, 1.0, 0.0),
+    /// );
+    /// let boundary = PolylineCurve::new(vec![
+    ///     Point2::new(0.0, 0.0),
+    ///     Point2::new(1.0, 0.0),
+    ///     Point2::new(1.0, 1.0),
+    /// ]);
+    /// assert_eq!(
+    ///     TrimmedSurface::try_new(plane, vec![boundary]).unwrap_err(),
+    ///     Error::OpenBoundary(0),
+    /// );
+    /// ```
+    OpenBoundary(usize),
 }
 
 impl std::fmt::Display for Error {
@@ -164,6 +261,18 @@ impl std::fmt::Display for Error {
                 ),
             Error::IrregularControlPoints => f.pad("The number of control points is irregular"),
             Error::EmptyCurveCollector => f.pad("The curve collector is empty."),
+            Error::NonFiniteKnot(idx) => f.pad(&format!("The {}th knot in this knot vector is not finite.", idx)),
+            Error::TooLargeMultiplicity(knot, mult) => f.write_fmt(
+                format_args!("The multiplicity of a knot is too large compared to the degree.\nthe knot: {}\nthe multiplicity: {}",
+                    knot, mult)
+                ),
+            Error::DisconnectedCurves => f.pad("The back of the former curve and the front of the latter curve are not near."),
+            Error::NotTangentContinuous => f.pad("The directions of the derivations of the curves are different at the joint."),
+            Error::DifferentLengthParameters(pt_len, param_len) => f.write_fmt(
+                format_args!("The number of the parameters is different from the one of the points.\nthe number of points: {}\nthe number of parameters: {}",
+                    pt_len, param_len)
+                ),
+            Error::OpenBoundary(idx) => f.pad(&format!("The {}th boundary of the trimmed surface is not closed.", idx)),
         }
     }
 }
@@ -186,5 +295,11 @@ fn print_messages() {
     writeln!(stderr, "{}\n", Error::TooShortKnotVector(1, 2)).unwrap();
     writeln!(stderr, "{}\n", Error::IrregularControlPoints).unwrap();
     writeln!(stderr, "{}\n", Error::EmptyCurveCollector).unwrap();
+    writeln!(stderr, "{}\n", Error::NonFiniteKnot(3)).unwrap();
+    writeln!(stderr, "{}\n", Error::TooLargeMultiplicity(0.5, 4)).unwrap();
+    writeln!(stderr, "{}\n", Error::DisconnectedCurves).unwrap();
+    writeln!(stderr, "{}\n", Error::NotTangentContinuous).unwrap();
+    writeln!(stderr, "{}\n", Error::DifferentLengthParameters(2, 3)).unwrap();
+    writeln!(stderr, "{}\n", Error::OpenBoundary(1)).unwrap();
     writeln!(stderr, "*******************************************************").unwrap();
 }
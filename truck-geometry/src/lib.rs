@@ -40,6 +40,13 @@ pub use errors::*;
 pub mod specifieds;
 pub use specifieds::*;
 
+/// Composition of curves: concatenation, reparameterization, and Bézier segments
+pub mod composition;
+
+/// Evaluation of surfaces on structured grids
+pub mod sample_grid;
+pub use sample_grid::*;
+
 /// Declares some decorators
 pub mod decorators;
 pub use decorators::*;
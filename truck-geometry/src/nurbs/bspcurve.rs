@@ -177,6 +177,9 @@ impl<V: VectorSpace<Scalar = f64>> BSplineCurve<V> {
     /// ```
     #[inline(always)]
     pub fn subs(&self, t: f64) -> V {
+        if let Some(res) = self.de_boor(t) {
+            return res;
+        }
         let basis = self
             .knot_vec
             .try_bspline_basis_functions(self.degree(), t)
@@ -186,6 +189,47 @@ impl<V: VectorSpace<Scalar = f64>> BSplineCurve<V> {
             .zip(basis)
             .fold(V::zero(), |sum, (vec, basis)| sum + *vec * basis)
     }
+
+    /// Substitutes by de Boor's algorithm, which uses only the `degree + 1` control points
+    /// of the span including `t`. Returns `None` if `t` is out of the parameter range or
+    /// the span does not have enough control points.
+    fn de_boor(&self, t: f64) -> Option<V> {
+        let degree = self.degree();
+        let knot_vec = &self.knot_vec;
+        let n = knot_vec.len() - 1;
+        if knot_vec[0].near(&knot_vec[n]) || t > knot_vec[n] {
+            return None;
+        }
+        let span = match knot_vec.floor(t)? {
+            idx if idx == n => n - knot_vec.multiplicity(n),
+            idx => idx,
+        };
+        if span < degree || span >= self.control_points.len() {
+            return None;
+        }
+        // The working points are on the stack up to degree 8, which covers the most curves.
+        let ctrl_pts = &self.control_points[span - degree..=span];
+        let mut array = [V::zero(); 9];
+        let mut vec;
+        let points: &mut [V] = match ctrl_pts.len() <= array.len() {
+            true => {
+                array[..ctrl_pts.len()].copy_from_slice(ctrl_pts);
+                &mut array[..ctrl_pts.len()]
+            }
+            false => {
+                vec = ctrl_pts.to_vec();
+                &mut vec
+            }
+        };
+        for r in 1..=degree {
+            for j in (r..=degree).rev() {
+                let i = j + span - degree;
+                let alpha = (t - knot_vec[i]) * inv_or_zero(knot_vec[i + degree + 1 - r] - knot_vec[i]);
+                points[j] = points[j - 1] * (1.0 - alpha) + points[j] * alpha;
+            }
+        }
+        Some(points[degree])
+    }
     /// Substitutes to the derived B-spline curve.
     /// # Examples
     /// ```
@@ -1470,3 +1514,40 @@ fn test_parameter_division() {
         assert!(value_middle.distance(param_middle) < tol);
     }
 }
+
+#[test]
+fn test_de_boor() {
+    let knot_vec = KnotVec::from(vec![
+        0.0, 0.0, 0.0, 0.0, 1.0, 1.5, 1.5, 3.0, 4.0, 5.0, 5.0, 5.0, 5.0,
+    ]);
+    let ctrl_pts: Vec<Vector3> = (0..9)
+        .map(|i| Vector3::new(i as f64, (i * i) as f64, (i % 3) as f64))
+        .collect();
+    let bspcurve = BSplineCurve::new(knot_vec.clone(), ctrl_pts.clone());
+    const N: usize = 100;
+    for i in 0..=N {
+        let t = 5.0 * i as f64 / N as f64;
+        let basis = knot_vec.bspline_basis_functions(3, t);
+        let ans = ctrl_pts
+            .iter()
+            .zip(basis)
+            .fold(Vector3::zero(), |sum, (pt, b)| sum + *pt * b);
+        assert_near!(bspcurve.subs(t), ans);
+    }
+
+    // the degree over the points on the stack
+    let knot_vec = KnotVec::bezier_knot(10);
+    let ctrl_pts: Vec<Vector3> = (0..11)
+        .map(|i| Vector3::new(i as f64, (i * i) as f64, (i % 3) as f64))
+        .collect();
+    let bspcurve = BSplineCurve::new(knot_vec.clone(), ctrl_pts.clone());
+    for i in 0..=N {
+        let t = i as f64 / N as f64;
+        let basis = knot_vec.bspline_basis_functions(10, t);
+        let ans = ctrl_pts
+            .iter()
+            .zip(basis)
+            .fold(Vector3::zero(), |sum, (pt, b)| sum + *pt * b);
+        assert_near!(bspcurve.subs(t), ans);
+    }
+}
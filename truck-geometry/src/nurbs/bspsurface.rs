@@ -4,6 +4,13 @@ use std::convert::TryInto;
 use std::iter::FusedIterator;
 use std::ops::*;
 
+/// the size of the cells searched for the intersections with curves relative to the whole size
+const INTERSECTION_CELL_RATIO: f64 = 1.0e-3;
+/// the maximum number of the iterations of the Newton's method for the intersections with curves
+const INTERSECTION_TRIALS: usize = 100;
+/// the maximum depth of the subdivision for the intersections with curves
+const INTERSECTION_MAX_DEPTH: usize = 64;
+
 impl<V> BSplineSurface<V> {
     /// constructor.
     /// # Arguments
@@ -641,6 +648,74 @@ impl<V: VectorSpace<Scalar = f64>> BSplineSurface<V> {
     #[inline(always)]
     pub fn get_closure(&self) -> impl Fn(f64, f64) -> V + '_ { move |u, v| self.subs(u, v) }
 
+    /// Substitutes the surface and its derivations on the grid `us` × `vs` at once.
+    ///
+    /// Returns the matrix whose `(i, j)`-element is `[subs, uder, vder]` at `(us[i], vs[j])`.
+    /// The basis functions are evaluated only once for each parameter, and the control points
+    /// are reduced to the sections at each `u` before the evaluations along `v`, so that
+    /// the evaluations are much faster than calling `subs`, `uder` and `vder` in a double loop.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let knot_vecs = (KnotVec::bezier_knot(1), KnotVec::bezier_knot(2));
+    /// let ctrl_pts = vec![
+    ///     vec![Vector2::new(0.0, 0.0), Vector2::new(0.5, -1.0), Vector2::new(1.0, 0.0)],
+    ///     vec![Vector2::new(0.0, 1.0), Vector2::new(0.5, 2.0), Vector2::new(1.0, 1.0)],
+    /// ];
+    /// let bspsurface = BSplineSurface::new(knot_vecs, ctrl_pts);
+    ///
+    /// let params: Vec<f64> = (0..=10).map(|i| i as f64 / 10.0).collect();
+    /// let grid = bspsurface.grid_derivatives(&params, &params);
+    /// for (i, u) in params.iter().enumerate() {
+    ///     for (j, v) in params.iter().enumerate() {
+    ///         assert_near2!(grid[i][j][0], bspsurface.subs(*u, *v));
+    ///         assert_near2!(grid[i][j][1], bspsurface.uder(*u, *v));
+    ///         assert_near2!(grid[i][j][2], bspsurface.vder(*u, *v));
+    ///     }
+    /// }
+    /// ```
+    pub fn grid_derivatives(&self, us: &[f64], vs: &[f64]) -> Vec<Vec<[V; 3]>> {
+        let (degree0, degree1) = self.degrees();
+        let BSplineSurface {
+            knot_vecs: (ref uknot_vec, ref vknot_vec),
+            ref control_points,
+        } = self;
+        let vbases: Vec<(Vec<f64>, Vec<f64>)> = vs
+            .iter()
+            .map(|v| basis_with_derivation(vknot_vec, degree1, *v))
+            .collect();
+        let len = control_points[0].len();
+        us.iter()
+            .map(|u| {
+                let (basis0, der0) = basis_with_derivation(uknot_vec, degree0, *u);
+                // the control points of the section at `u` and of its derivation
+                let mut section = vec![V::zero(); len];
+                let mut section_der = vec![V::zero(); len];
+                for ((vec, b0), d0) in control_points.iter().zip(&basis0).zip(&der0) {
+                    if *b0 == 0.0 && *d0 == 0.0 {
+                        continue;
+                    }
+                    for (j, pt) in vec.iter().enumerate() {
+                        section[j] = section[j] + *pt * *b0;
+                        section_der[j] = section_der[j] + *pt * *d0;
+                    }
+                }
+                vbases
+                    .iter()
+                    .map(|(basis1, der1)| {
+                        let mut res = [V::zero(); 3];
+                        for j in 0..len {
+                            res[0] = res[0] + section[j] * basis1[j];
+                            res[1] = res[1] + section_der[j] * basis1[j];
+                            res[2] = res[2] + section[j] * der1[j];
+                        }
+                        res
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
     #[inline(always)]
     fn udelta_control_points(&self, i: usize, j: usize) -> V {
         if i == 0 {
@@ -1651,6 +1726,123 @@ impl<V: VectorSpace<Scalar = f64> + Tolerance> BSplineSurface<V> {
         BSplineSurface::new_unchecked((uknot_vec, vknot_vec), control_points)
     }
 
+    /// Creates the surface skinning the sections `curves`, i.e. the surface interpolating
+    /// the curves in the second parameter `v` at `params`.
+    ///
+    /// The degrees and the knot vectors of the curves are synchronized, and the knot vector of
+    /// `v` is the averaged one of `params` with the degree `min(3, curves.len() - 1)`.
+    /// # Failures
+    /// - If `curves` is empty, returns [`Error::EmptyControlPoints`].
+    /// - If the lengths of `curves` and `params` are different, returns [`Error::DifferentLengthParameters`].
+    /// - If there is only one curve, returns [`Error::TooLargeDegree`].
+    /// - If `params` is not strictly increasing, returns [`Error::NotSortedVector`].
+    ///
+    /// [`Error::EmptyControlPoints`]: errors/enum.Error.html#variant.EmptyControlPoints
+    /// [`Error::DifferentLengthParameters`]: errors/enum.Error.html#variant.DifferentLengthParameters
+    /// [`Error::TooLargeDegree`]: errors/enum.Error.html#variant.TooLargeDegree
+    /// [`Error::NotSortedVector`]: errors/enum.Error.html#variant.NotSortedVector
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let curves: Vec<BSplineCurve<Vector2>> = vec![
+    ///     BSplineCurve::new(
+    ///         KnotVec::bezier_knot(1),
+    ///         vec![Vector2::new(0.0, 0.0), Vector2::new(1.0, 0.0)],
+    ///     ),
+    ///     BSplineCurve::new(
+    ///         KnotVec::bezier_knot(2),
+    ///         vec![Vector2::new(0.0, 1.0), Vector2::new(0.5, 2.0), Vector2::new(1.0, 1.0)],
+    ///     ),
+    ///     BSplineCurve::new(
+    ///         KnotVec::bezier_knot(1),
+    ///         vec![Vector2::new(0.0, 3.0), Vector2::new(1.0, 3.0)],
+    ///     ),
+    /// ];
+    /// let params = [0.0, 0.4, 1.0];
+    /// let surface = BSplineSurface::skin(curves.clone(), &params).unwrap();
+    /// assert_eq!(surface.degrees(), (2, 2));
+    ///
+    /// // the surface contains the sections
+    /// const N: usize = 10;
+    /// for (curve, v) in curves.iter().zip(&params) {
+    ///     for i in 0..=N {
+    ///         let u = i as f64 / N as f64;
+    ///         assert_near!(surface.subs(u, *v), curve.subs(u));
+    ///     }
+    /// }
+    /// ```
+    pub fn skin(mut curves: Vec<BSplineCurve<V>>, params: &[f64]) -> Result<BSplineSurface<V>> {
+        if curves.is_empty() {
+            return Err(Error::EmptyControlPoints);
+        } else if curves.len() != params.len() {
+            return Err(Error::DifferentLengthParameters(curves.len(), params.len()));
+        }
+        let vdegree = usize::max(usize::min(3, curves.len() - 1), 1);
+        let vknot_vec = KnotVec::averaged(params, vdegree)?;
+        if params.windows(2).any(|t| t[1] - t[0] < TOLERANCE) {
+            return Err(Error::NotSortedVector);
+        }
+
+        let udegree = curves.iter().map(|curve| curve.degree()).max().unwrap();
+        for curve in &mut curves {
+            for _ in curve.degree()..udegree {
+                curve.elevate_degree();
+            }
+            curve.optimize();
+        }
+        // the knots of the first curve is the union of the ones of all curves after first loop.
+        let (first, others) = curves.split_first_mut().unwrap();
+        for _ in 0..2 {
+            for curve in others.iter_mut() {
+                first.syncro_knots(curve);
+            }
+        }
+
+        // interpolates the control points in `v` by the Gaussian elimination with partial pivoting
+        let mut matrix: Vec<Vec<f64>> = params
+            .iter()
+            .map(|t| vknot_vec.bspline_basis_functions(vdegree, *t))
+            .collect();
+        let mut rows: Vec<Vec<V>> = curves
+            .iter()
+            .map(|curve| curve.control_points().clone())
+            .collect();
+        let n = rows.len();
+        for i in 0..n {
+            let pivot = (i..n)
+                .max_by(|a, b| matrix[*a][i].abs().partial_cmp(&matrix[*b][i].abs()).unwrap())
+                .unwrap();
+            matrix.swap(i, pivot);
+            rows.swap(i, pivot);
+            for j in i + 1..n {
+                let coef = matrix[j][i] / matrix[i][i];
+                for k in i..n {
+                    matrix[j][k] -= coef * matrix[i][k];
+                }
+                for k in 0..rows[j].len() {
+                    let sub = rows[i][k] * coef;
+                    rows[j][k] = rows[j][k] - sub;
+                }
+            }
+        }
+        for i in (0..n).rev() {
+            for j in i + 1..n {
+                for k in 0..rows[i].len() {
+                    let sub = rows[j][k] * matrix[i][j];
+                    rows[i][k] = rows[i][k] - sub;
+                }
+            }
+            let inv = 1.0 / matrix[i][i];
+            rows[i].iter_mut().for_each(|pt| *pt = *pt * inv);
+        }
+
+        let uknot_vec = curves[0].knot_vec().clone();
+        let control_points = (0..rows[0].len())
+            .map(|i| rows.iter().map(|row| row[i]).collect())
+            .collect();
+        Ok(BSplineSurface::new_unchecked((uknot_vec, vknot_vec), control_points))
+    }
+
     /// Creats a surface by its boundary.
     /// # Examples
     /// ```
@@ -2162,6 +2354,217 @@ impl BSplineSurface<Vector3> {
     }
 }
 
+impl BSplineSurface<Vector3> {
+    /// Returns the intersections of the surface and `curve`, i.e. the tuples of the parameter of
+    /// the curve, the parameter of the surface and the point, sorted by the parameters of the curve.
+    ///
+    /// Equivalent to `self.intersect_curve_with_tolerance(curve, TOLERANCE)`.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// // the paraboloid z = x^2 + y^2 on [-1, 1]^2
+    /// let surface = BSplineSurface::new(
+    ///     (KnotVec::bezier_knot(2), KnotVec::bezier_knot(2)),
+    ///     vec![
+    ///         vec![Vector3::new(-1.0, -1.0, 2.0), Vector3::new(-1.0, 0.0, 0.0), Vector3::new(-1.0, 1.0, 2.0)],
+    ///         vec![Vector3::new(0.0, -1.0, 0.0), Vector3::new(0.0, 0.0, -2.0), Vector3::new(0.0, 1.0, 0.0)],
+    ///         vec![Vector3::new(1.0, -1.0, 2.0), Vector3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 2.0)],
+    ///     ],
+    /// );
+    ///
+    /// // the line z = 0.25 along the x-axis crosses the paraboloid at x = -0.5 and x = 0.5.
+    /// let line = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector3::new(-1.0, 0.0, 0.25), Vector3::new(1.0, 0.0, 0.25)],
+    /// );
+    /// let intersections = surface.intersect_curve(&line);
+    /// assert_eq!(intersections.len(), 2);
+    /// let (t, uv, pt) = intersections[0];
+    /// assert_near!(t, 0.25);
+    /// assert_near!(uv, Point2::new(0.25, 0.5));
+    /// assert_near!(pt, Point3::new(-0.5, 0.0, 0.25));
+    /// assert_near!(intersections[1].2, Point3::new(0.5, 0.0, 0.25));
+    ///
+    /// // the line z = 0 touches the paraboloid at the origin.
+    /// let line = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector3::new(-1.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0)],
+    /// );
+    /// let intersections = surface.intersect_curve(&line);
+    /// assert_eq!(intersections.len(), 1);
+    /// assert!(intersections[0].2.to_vec().magnitude() < 1.0e-3);
+    ///
+    /// // the line z = -0.1 misses the paraboloid.
+    /// let line = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(1),
+    ///     vec![Vector3::new(-1.0, 0.0, -0.1), Vector3::new(1.0, 0.0, -0.1)],
+    /// );
+    /// assert!(surface.intersect_curve(&line).is_empty());
+    /// ```
+    #[inline(always)]
+    pub fn intersect_curve(&self, curve: &BSplineCurve<Vector3>) -> Vec<(f64, Point2, Point3)> {
+        self.intersect_curve_with_tolerance(curve, TOLERANCE)
+    }
+
+    /// Returns the intersections of the surface and `curve` whose distances between the curve and
+    /// the surface are less than `tol`, i.e. the tuples of the parameter of the curve, the
+    /// parameter of the surface and the point, sorted by the parameters of the curve.
+    ///
+    /// The candidates are searched by subdividing the curve and the surface until the bounding
+    /// boxes of the control points are small, and are refined by the damped Newton's method, which
+    /// converges even if the curve is tangent to the surface. The candidates converging to the same
+    /// intersection are merged if the curve between them is within `tol` from the surface, so each
+    /// tangential contact is returned as one point.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// // the plane z = 0 on [0, 1]^2
+    /// let surface = BSplineSurface::new(
+    ///     (KnotVec::bezier_knot(1), KnotVec::bezier_knot(1)),
+    ///     vec![
+    ///         vec![Vector3::new(0.0, 0.0, 0.0), Vector3::new(0.0, 1.0, 0.0)],
+    ///         vec![Vector3::new(1.0, 0.0, 0.0), Vector3::new(1.0, 1.0, 0.0)],
+    ///     ],
+    /// );
+    /// // the parabola whose top z = 1.0e-4 is slightly above the plane
+    /// let curve = BSplineCurve::new(
+    ///     KnotVec::bezier_knot(2),
+    ///     vec![
+    ///         Vector3::new(0.0, 0.5, -1.0),
+    ///         Vector3::new(0.5, 0.5, 1.0002),
+    ///         Vector3::new(1.0, 0.5, -1.0),
+    ///     ],
+    /// );
+    /// assert!(surface.intersect_curve(&curve).len() == 2);
+    /// // With the rough tolerance, the parabola touches the plane.
+    /// let intersections = surface.intersect_curve_with_tolerance(&curve, 1.0e-3);
+    /// assert_eq!(intersections.len(), 1);
+    /// assert!(intersections[0].2.distance(Point3::new(0.5, 0.5, 0.0)) < 0.01);
+    /// ```
+    pub fn intersect_curve_with_tolerance(
+        &self,
+        curve: &BSplineCurve<Vector3>,
+        tol: f64,
+    ) -> Vec<(f64, Point2, Point3)> {
+        let bdb = self.roughly_bounding_box() + curve.roughly_bounding_box();
+        let cell_size = f64::max(tol, bdb.diameter() * INTERSECTION_CELL_RATIO);
+        let mut bspcurve = curve.clone();
+        bspcurve.clamp();
+        let mut candidates = Vec::new();
+        sub_intersect_curve(bspcurve, self.clone(), tol, cell_size, 0, &mut candidates);
+        let mut solutions: Vec<(f64, (f64, f64), f64)> = candidates
+            .into_iter()
+            .filter_map(|hint| self.curve_intersection_newton(curve, hint, tol))
+            .collect();
+        solutions.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap_or(std::cmp::Ordering::Equal));
+        let mut res: Vec<(f64, (f64, f64), f64)> = Vec::new();
+        for solution in solutions {
+            match res.last_mut() {
+                Some(last) if self.is_touching(curve, *last, solution, tol) => {
+                    if solution.2 < last.2 {
+                        *last = solution;
+                    }
+                }
+                _ => res.push(solution),
+            }
+        }
+        res.into_iter()
+            .map(|(t, (u, v), _)| (t, Point2::new(u, v), Point3::from_vec(curve.subs(t))))
+            .collect()
+    }
+
+    /// Refines the hint `(t, u, v)` of the intersection by the damped Newton's method, and returns
+    /// the parameters and the distance between the curve and the surface.
+    fn curve_intersection_newton(
+        &self,
+        curve: &BSplineCurve<Vector3>,
+        (mut t, mut u, mut v): (f64, f64, f64),
+        tol: f64,
+    ) -> Option<(f64, (f64, f64), f64)> {
+        let trange = ParametricCurve::parameter_range(curve);
+        let ((u0, u1), (v0, v1)) = self.parameter_range();
+        let clamp = |x: f64, (min, max): (f64, f64)| f64::min(f64::max(x, min), max);
+        for _ in 0..INTERSECTION_TRIALS {
+            let diff = curve.subs(t) - self.subs(u, v);
+            let jac = Matrix3::from_cols(curve.der(t), -self.uder(u, v), -self.vder(u, v));
+            let jtj = jac.transpose() * jac;
+            // The damping keeps the step finite where the curve is tangent to the surface.
+            let damping = (jtj.x.x + jtj.y.y + jtj.z.z) * TOLERANCE2;
+            let delta = (jtj + Matrix3::identity() * damping).invert()? * (jac.transpose() * diff);
+            t = clamp(t - delta.x, trange);
+            u = clamp(u - delta.y, (u0, u1));
+            v = clamp(v - delta.z, (v0, v1));
+            if delta.so_small2() {
+                break;
+            }
+        }
+        let dist = curve.subs(t).distance(self.subs(u, v));
+        match dist < tol {
+            true => Some((t, (u, v), dist)),
+            false => None,
+        }
+    }
+
+    /// Returns whether the curve between the two intersections is within `tol` from the surface.
+    fn is_touching(
+        &self,
+        curve: &BSplineCurve<Vector3>,
+        (t0, (u0, v0), _): (f64, (f64, f64), f64),
+        (t1, (u1, v1), _): (f64, (f64, f64), f64),
+        tol: f64,
+    ) -> bool {
+        const N: usize = 4;
+        (1..N).all(|i| {
+            let p = i as f64 / N as f64;
+            let pt = Point3::from_vec(curve.subs(t0 * (1.0 - p) + t1 * p));
+            let hint = (u0 * (1.0 - p) + u1 * p, v0 * (1.0 - p) + v1 * p);
+            match self.search_nearest_parameter(pt, hint, INTERSECTION_TRIALS) {
+                Some((u, v)) => ParametricSurface::subs(self, u, v).distance(pt) < tol,
+                None => false,
+            }
+        })
+    }
+}
+
+/// Collects the hints of the intersections of the curve and the surface by subdividing them
+/// until the bounding boxes of the control points are smaller than `cell_size`, or the depth
+/// of the subdivision reaches `INTERSECTION_MAX_DEPTH`.
+fn sub_intersect_curve(
+    mut curve: BSplineCurve<Vector3>,
+    mut surface: BSplineSurface<Vector3>,
+    tol: f64,
+    cell_size: f64,
+    depth: usize,
+    candidates: &mut Vec<(f64, f64, f64)>,
+) {
+    let (bdb0, bdb1) = (curve.roughly_bounding_box(), surface.roughly_bounding_box());
+    let (min0, max0, min1, max1) = (bdb0.min(), bdb0.max(), bdb1.min(), bdb1.max());
+    if (0..3).any(|i| min0[i] > max1[i] + tol || min1[i] > max0[i] + tol) {
+        return;
+    }
+    let (t0, t1) = ParametricCurve::parameter_range(&curve);
+    let ((u0, u1), (v0, v1)) = surface.parameter_range();
+    let (size0, size1) = (bdb0.diameter(), bdb1.diameter());
+    if (size0 < cell_size && size1 < cell_size) || depth == INTERSECTION_MAX_DEPTH {
+        candidates.push(((t0 + t1) / 2.0, (u0 + u1) / 2.0, (v0 + v1) / 2.0));
+    } else if size0 > size1 {
+        let latter = curve.cut((t0 + t1) / 2.0);
+        sub_intersect_curve(curve, surface.clone(), tol, cell_size, depth + 1, candidates);
+        sub_intersect_curve(latter, surface, tol, cell_size, depth + 1, candidates);
+    } else {
+        // The surface is cut in the direction along which it is longer.
+        let (u, v) = ((u0 + u1) / 2.0, (v0 + v1) / 2.0);
+        let ulen = surface.uder(u, v).magnitude() * (u1 - u0);
+        let vlen = surface.vder(u, v).magnitude() * (v1 - v0);
+        let latter = match ulen > vlen {
+            true => surface.ucut(u),
+            false => surface.vcut(v),
+        };
+        sub_intersect_curve(curve.clone(), surface, tol, cell_size, depth + 1, candidates);
+        sub_intersect_curve(curve, latter, tol, cell_size, depth + 1, candidates);
+    }
+}
+
 impl IncludeCurve<BSplineCurve<Vector3>> for BSplineSurface<Vector3> {
     fn include(&self, curve: &BSplineCurve<Vector3>) -> bool {
         let pt = curve.subs(curve.knot_vec()[0]);
@@ -2514,3 +2917,22 @@ fn test_include_bspcurve3() {
     *curve.control_point_mut(2) += Vector3::new(0.0, 0.0, 0.001);
     assert!(!surface.include(&curve));
 }
+
+/// the values and the derivations of the B-spline basis functions
+fn basis_with_derivation(knot_vec: &KnotVec, degree: usize, t: f64) -> (Vec<f64>, Vec<f64>) {
+    let basis = knot_vec.bspline_basis_functions(degree, t);
+    let der = match degree {
+        0 => vec![0.0; basis.len()],
+        _ => {
+            let lower = knot_vec.bspline_basis_functions(degree - 1, t);
+            (0..basis.len())
+                .map(|i| {
+                    let coef0 = inv_or_zero(knot_vec[i + degree] - knot_vec[i]);
+                    let coef1 = inv_or_zero(knot_vec[i + degree + 1] - knot_vec[i + 1]);
+                    (lower[i] * coef0 - lower[i + 1] * coef1) * degree as f64
+                })
+                .collect()
+        }
+    };
+    (basis, der)
+}
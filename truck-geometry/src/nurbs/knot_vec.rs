@@ -521,6 +521,233 @@ impl KnotVec {
         vec.extend(std::iter::repeat(1.0).take(degree + 1));
         KnotVec(vec)
     }
+
+    /// Constructs the knot vector validated for B-splines of the degree `degree`.
+    /// # Failures
+    /// - If a knot is not finite, returns [`Error::NonFiniteKnot`].
+    /// - If the vector is not sorted, returns [`Error::NotSortedVector`].
+    /// - If the length of the vector is not more than `degree + 1`, returns [`Error::TooLargeDegree`].
+    /// - If the range of the vector is zero, returns [`Error::ZeroRange`].
+    /// - If the multiplicity of a knot is more than `degree + 1`, returns [`Error::TooLargeMultiplicity`].
+    ///
+    /// [`Error::NonFiniteKnot`]: errors/enum.Error.html#variant.NonFiniteKnot
+    /// [`Error::NotSortedVector`]: errors/enum.Error.html#variant.NotSortedVector
+    /// [`Error::TooLargeDegree`]: errors/enum.Error.html#variant.TooLargeDegree
+    /// [`Error::ZeroRange`]: errors/enum.Error.html#variant.ZeroRange
+    /// [`Error::TooLargeMultiplicity`]: errors/enum.Error.html#variant.TooLargeMultiplicity
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// use errors::Error;
+    /// let knot_vec = KnotVec::try_new(vec![0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0], 2).unwrap();
+    /// assert_eq!(knot_vec, KnotVec::from(vec![0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0]));
+    ///
+    /// assert_eq!(KnotVec::try_new(vec![0.0, f64::NAN, 1.0], 1), Err(Error::NonFiniteKnot(1)));
+    /// assert_eq!(KnotVec::try_new(vec![0.0, 1.0, 0.5], 1), Err(Error::NotSortedVector));
+    /// assert_eq!(KnotVec::try_new(vec![0.0, 1.0], 1), Err(Error::TooLargeDegree(2, 1)));
+    /// assert_eq!(
+    ///     KnotVec::try_new(vec![0.0, 0.0, 0.5, 0.5, 0.5, 1.0, 1.0], 1),
+    ///     Err(Error::TooLargeMultiplicity(0.5, 3)),
+    /// );
+    /// ```
+    pub fn try_new(vec: Vec<f64>, degree: usize) -> Result<KnotVec> {
+        if let Some(idx) = vec.iter().position(|t| !t.is_finite()) {
+            return Err(Error::NonFiniteKnot(idx));
+        }
+        let knot_vec = KnotVec::try_from(vec)?;
+        if knot_vec.len() <= degree + 1 {
+            return Err(Error::TooLargeDegree(knot_vec.len(), degree));
+        }
+        if knot_vec.range_length().so_small() {
+            return Err(Error::ZeroRange);
+        }
+        let (knots, mults) = knot_vec.to_single_multi();
+        match knots
+            .into_iter()
+            .zip(mults)
+            .find(|(_, mult)| *mult > degree + 1)
+        {
+            Some((knot, mult)) => Err(Error::TooLargeMultiplicity(knot, mult)),
+            None => Ok(knot_vec),
+        }
+    }
+
+    /// Returns the parameter domain `(self[degree], self[self.len() - degree - 1])` of
+    /// B-splines of the degree `degree`, where the basis functions are a partition of unity.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let knot_vec = KnotVec::from(vec![0.0, 1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0]);
+    /// assert_eq!(knot_vec.domain(2), (2.0, 5.0));
+    /// assert_eq!(KnotVec::bezier_knot(3).domain(3), (0.0, 1.0));
+    /// ```
+    /// # Panics
+    /// Panic occurs if the length of the knot vector is not more than `degree`.
+    #[inline(always)]
+    pub fn domain(&self, degree: usize) -> (f64, f64) {
+        (self[degree], self[self.len() - degree - 1])
+    }
+
+    /// Returns the number of the knots near `x`.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let knot_vec = KnotVec::from(vec![0.0, 0.0, 0.0, 0.5, 0.5, 1.0, 1.0, 1.0]);
+    /// assert_eq!(knot_vec.knot_multiplicity(0.5), 2);
+    /// assert_eq!(knot_vec.knot_multiplicity(0.25), 0);
+    /// ```
+    #[inline(always)]
+    pub fn knot_multiplicity(&self, x: f64) -> usize { self.iter().filter(|t| t.near(&x)).count() }
+
+    /// Returns the maximum multiplicity of the inner knots, i.e. the knots other than the first
+    /// and the last. The B-splines of the degree `degree` are `C^(degree - m)` for the result `m`.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let knot_vec = KnotVec::from(vec![0.0, 0.0, 0.0, 0.5, 0.5, 0.75, 1.0, 1.0, 1.0]);
+    /// assert_eq!(knot_vec.max_inner_multiplicity(), 2);
+    /// assert_eq!(KnotVec::bezier_knot(3).max_inner_multiplicity(), 0);
+    /// ```
+    pub fn max_inner_multiplicity(&self) -> usize {
+        let (_, mults) = self.to_single_multi();
+        match mults.len() {
+            0..=2 => 0,
+            len => mults[1..len - 1].iter().copied().max().unwrap_or(0),
+        }
+    }
+
+    /// Returns whether the first knot is 0 and the last knot is 1.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let mut knot_vec = KnotVec::from(vec![1.0, 1.0, 2.0, 3.0, 3.0]);
+    /// assert!(!knot_vec.is_normalized());
+    /// knot_vec.normalize();
+    /// assert!(knot_vec.is_normalized());
+    /// ```
+    #[inline(always)]
+    pub fn is_normalized(&self) -> bool {
+        !self.is_empty() && self[0].so_small() && self[self.len() - 1].near(&1.0)
+    }
+
+    /// Returns the parameters of the points by the chord length, normalized to `[0, 1]`.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let points = vec![
+    ///     Point2::new(0.0, 0.0),
+    ///     Point2::new(1.0, 0.0),
+    ///     Point2::new(1.0, 3.0),
+    /// ];
+    /// assert_eq!(KnotVec::chord_length_parameters(&points), vec![0.0, 0.25, 1.0]);
+    /// ```
+    #[inline(always)]
+    pub fn chord_length_parameters<P: MetricSpace<Metric = f64> + Copy>(points: &[P]) -> Vec<f64> {
+        parameters_by_exponent(points, 1.0)
+    }
+
+    /// Returns the parameters of the points by the square roots of the chord lengths,
+    /// normalized to `[0, 1]`.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let points = vec![
+    ///     Point2::new(0.0, 0.0),
+    ///     Point2::new(1.0, 0.0),
+    ///     Point2::new(1.0, 4.0),
+    /// ];
+    /// assert_eq!(KnotVec::centripetal_parameters(&points), vec![0.0, 1.0 / 3.0, 1.0]);
+    /// ```
+    #[inline(always)]
+    pub fn centripetal_parameters<P: MetricSpace<Metric = f64> + Copy>(points: &[P]) -> Vec<f64> {
+        parameters_by_exponent(points, 0.5)
+    }
+
+    /// Constructs the clamped knot vector by averaging the parameters, which is suitable for
+    /// the interpolation of the points with the parameters.
+    /// # Failures
+    /// - If the number of the parameters is not more than `degree`, returns [`Error::TooLargeDegree`].
+    /// - If the parameters are not sorted, returns [`Error::NotSortedVector`].
+    ///
+    /// [`Error::TooLargeDegree`]: errors/enum.Error.html#variant.TooLargeDegree
+    /// [`Error::NotSortedVector`]: errors/enum.Error.html#variant.NotSortedVector
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let params = vec![0.0, 0.2, 0.4, 0.8, 1.0];
+    /// let knot_vec = KnotVec::averaged(&params, 2).unwrap();
+    /// assert_eq!(knot_vec.len(), params.len() + 3);
+    /// assert_near!(knot_vec[3], 0.3);
+    /// assert_near!(knot_vec[4], 0.6);
+    /// ```
+    pub fn averaged(params: &[f64], degree: usize) -> Result<KnotVec> {
+        if params.len() <= degree {
+            return Err(Error::TooLargeDegree(params.len(), degree));
+        }
+        let (first, last) = (params[0], params[params.len() - 1]);
+        let mut vec = vec![first; degree + 1];
+        match degree {
+            0 => vec.extend(params.windows(2).map(|t| (t[0] + t[1]) / 2.0)),
+            _ => vec.extend(
+                params[1..params.len() - 1]
+                    .windows(degree)
+                    .map(|t| t.iter().sum::<f64>() / degree as f64),
+            ),
+        }
+        vec.extend(std::iter::repeat(last).take(degree + 1));
+        KnotVec::try_from(vec)
+    }
+
+    /// Constructs the knot vector for the interpolation of the points with the chord length parameters.
+    /// cf. [`KnotVec::chord_length_parameters`](#method.chord_length_parameters),
+    /// [`KnotVec::averaged`](#method.averaged)
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let points: Vec<Point3> = (0..5).map(|i| Point3::new(i as f64, 0.0, 0.0)).collect();
+    /// let knot_vec = KnotVec::chord_length(&points, 3).unwrap();
+    /// assert_eq!(knot_vec, KnotVec::from(vec![0.0, 0.0, 0.0, 0.0, 0.5, 1.0, 1.0, 1.0, 1.0]));
+    /// ```
+    #[inline(always)]
+    pub fn chord_length<P: MetricSpace<Metric = f64> + Copy>(
+        points: &[P],
+        degree: usize,
+    ) -> Result<KnotVec> {
+        KnotVec::averaged(&KnotVec::chord_length_parameters(points), degree)
+    }
+
+    /// Constructs the knot vector for the interpolation of the points with the centripetal parameters.
+    /// cf. [`KnotVec::centripetal_parameters`](#method.centripetal_parameters),
+    /// [`KnotVec::averaged`](#method.averaged)
+    #[inline(always)]
+    pub fn centripetal<P: MetricSpace<Metric = f64> + Copy>(
+        points: &[P],
+        degree: usize,
+    ) -> Result<KnotVec> {
+        KnotVec::averaged(&KnotVec::centripetal_parameters(points), degree)
+    }
+}
+
+/// the normalized cumulative sums of `distance^exponent`, or the uniform parameters
+/// if all points coincide
+fn parameters_by_exponent<P: MetricSpace<Metric = f64> + Copy>(
+    points: &[P],
+    exponent: f64,
+) -> Vec<f64> {
+    let mut params = Vec::with_capacity(points.len());
+    let mut sum = 0.0;
+    for (i, pt) in points.iter().enumerate() {
+        if i > 0 {
+            sum += points[i - 1].distance(*pt).powf(exponent);
+        }
+        params.push(sum);
+    }
+    match (points.len(), sum.so_small()) {
+        (0, _) => params,
+        (1, _) => vec![0.0],
+        (len, true) => (0..len).map(|i| i as f64 / (len - 1) as f64).collect(),
+        (_, false) => params.into_iter().map(|t| t / sum).collect(),
+    }
 }
 
 impl From<Vec<f64>> for KnotVec {
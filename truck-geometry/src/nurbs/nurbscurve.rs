@@ -266,6 +266,16 @@ impl<V: Homogeneous<f64> + Tolerance> NURBSCurve<V> {
     /// cf.[`BSplineCurve::syncro_knots`](./struct.BSplineCurve.html#method.syncro_knots)
     pub fn cut(&mut self, t: f64) -> Self { NURBSCurve(self.0.cut(t)) }
 
+    /// Separates `self` into Bezier curves by each knots.  
+    /// cf.[`BSplineCurve::bezier_decomposition`](./struct.BSplineCurve.html#method.bezier_decomposition)
+    pub fn bezier_decomposition(&self) -> Vec<Self> {
+        self.0
+            .bezier_decomposition()
+            .into_iter()
+            .map(NURBSCurve)
+            .collect()
+    }
+
     /// Concats two NURBS curves.  
     /// cf.[`BSplineCurve::try_concat`](./struct.BSplineCurve.html#method.try_concat)
     pub fn try_concat(&mut self, other: &mut Self) -> Result<&mut Self> {
@@ -0,0 +1,155 @@
+use crate::*;
+
+/// A sample point of a surface on a structured grid.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SurfaceSample {
+    /// the point on the surface
+    pub point: Point3,
+    /// the unit normal vector of the surface
+    pub normal: Vector3,
+    /// the parameter of the point
+    pub uv: Vector2,
+}
+
+/// Evaluation of surfaces on structured grids.
+pub trait SampleGrid {
+    /// Samples the surface on the uniform grid of the parameter range,
+    /// `nu` points in the `u`-direction and `nv` points in the `v`-direction.
+    ///
+    /// The `(i, j)`-element of the result is the sample at the `i`-th `u` and the `j`-th `v`.
+    /// The endpoints of the parameter range are included if the numbers are more than one.
+    fn sample_grid(&self, nu: usize, nv: usize) -> Vec<Vec<SurfaceSample>>;
+}
+
+/// Returns `n` parameters dividing the range uniformly.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// assert_eq!(grid_parameters((1.0, 2.0), 5), vec![1.0, 1.25, 1.5, 1.75, 2.0]);
+/// assert_eq!(grid_parameters((1.0, 2.0), 1), vec![1.0]);
+/// ```
+pub fn grid_parameters((t0, t1): (f64, f64), n: usize) -> Vec<f64> {
+    match n {
+        0 => Vec::new(),
+        1 => vec![t0],
+        _ => (0..n)
+            .map(|i| t0 + (t1 - t0) * i as f64 / (n - 1) as f64)
+            .collect(),
+    }
+}
+
+/// Samples the surface on the uniform grid by calling `subs` and `normal` in a double loop.
+/// This is the implementation of [`SampleGrid`] for surfaces without batch evaluation.
+///
+/// [`SampleGrid`]: ./trait.SampleGrid.html
+pub fn sample_grid_by_subs<S>(surface: &S, nu: usize, nv: usize) -> Vec<Vec<SurfaceSample>>
+where S: ParametricSurface<Point = Point3, Vector = Vector3> + BoundedSurface {
+    let (urange, vrange) = surface.parameter_range();
+    let vs = grid_parameters(vrange, nv);
+    grid_parameters(urange, nu)
+        .into_iter()
+        .map(|u| {
+            vs.iter()
+                .map(|v| SurfaceSample {
+                    point: surface.subs(u, *v),
+                    normal: surface.normal(u, *v),
+                    uv: Vector2::new(u, *v),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+impl SampleGrid for BSplineSurface<Vector3> {
+    /// Samples the surface by [`BSplineSurface::grid_derivatives`].
+    ///
+    /// [`BSplineSurface::grid_derivatives`]: ./struct.BSplineSurface.html#method.grid_derivatives
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let surface = Plane::new(
+    ///     Point3::new(0.0, 0.0, 0.0),
+    ///     Point3::new(1.0, 0.0, 0.0),
+    ///     Point3::new(0.0, 1.0, 0.0),
+    /// )
+    /// .into_bspline();
+    /// let grid = surface.sample_grid(3, 5);
+    /// assert_eq!(grid.len(), 3);
+    /// assert_eq!(grid[0].len(), 5);
+    /// assert_near!(grid[2][4].point, Point3::new(1.0, 1.0, 0.0));
+    /// assert_near!(grid[1][2].normal, Vector3::unit_z());
+    /// assert_near!(grid[1][1].uv, Vector2::new(0.5, 0.25));
+    /// ```
+    fn sample_grid(&self, nu: usize, nv: usize) -> Vec<Vec<SurfaceSample>> {
+        let (urange, vrange) = self.parameter_range();
+        let (us, vs) = (grid_parameters(urange, nu), grid_parameters(vrange, nv));
+        self.grid_derivatives(&us, &vs)
+            .into_iter()
+            .zip(&us)
+            .map(|(vec, u)| {
+                vec.into_iter()
+                    .zip(&vs)
+                    .map(|([pt, ud, vd], v)| SurfaceSample {
+                        point: Point3::from_vec(pt),
+                        normal: ud.cross(vd).normalize(),
+                        uv: Vector2::new(*u, *v),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl SampleGrid for NURBSSurface<Vector4> {
+    /// Samples the surface by the batch evaluation of the homogeneous B-spline surface.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let surface = Plane::new(
+    ///     Point3::new(0.0, 0.0, 0.0),
+    ///     Point3::new(1.0, 0.0, 0.0),
+    ///     Point3::new(0.0, 1.0, 0.0),
+    /// )
+    /// .into_nurbs();
+    /// let grid = surface.sample_grid(4, 4);
+    /// for vec in &grid {
+    ///     for sample in vec {
+    ///         assert_near!(surface.subs(sample.uv[0], sample.uv[1]), sample.point);
+    ///         assert_near!(sample.normal, Vector3::unit_z());
+    ///     }
+    /// }
+    /// ```
+    fn sample_grid(&self, nu: usize, nv: usize) -> Vec<Vec<SurfaceSample>> {
+        let (urange, vrange) = self.parameter_range();
+        let (us, vs) = (grid_parameters(urange, nu), grid_parameters(vrange, nv));
+        self.non_rationalized()
+            .grid_derivatives(&us, &vs)
+            .into_iter()
+            .zip(&us)
+            .map(|(vec, u)| {
+                vec.into_iter()
+                    .zip(&vs)
+                    .map(|([pt, ud, vd], v)| SurfaceSample {
+                        point: pt.to_point(),
+                        normal: pt.rat_der(ud).cross(pt.rat_der(vd)).normalize(),
+                        uv: Vector2::new(*u, *v),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl SampleGrid for Plane {
+    #[inline(always)]
+    fn sample_grid(&self, nu: usize, nv: usize) -> Vec<Vec<SurfaceSample>> {
+        sample_grid_by_subs(self, nu, nv)
+    }
+}
+
+impl SampleGrid for Sphere {
+    #[inline(always)]
+    fn sample_grid(&self, nu: usize, nv: usize) -> Vec<Vec<SurfaceSample>> {
+        sample_grid_by_subs(self, nu, nv)
+    }
+}
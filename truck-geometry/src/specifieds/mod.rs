@@ -63,5 +63,102 @@ pub struct Sphere {
     radius: f64,
 }
 
+/// polyline curve
+///
+/// The `i`-th vertex corresponds to the `i`-th parameter, and the curve is linearly
+/// interpolated between two vertices. By default, the parameter of the `i`-th vertex is `i`.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// let polyline = PolylineCurve::new(vec![
+///     Point2::new(0.0, 0.0),
+///     Point2::new(1.0, 0.0),
+///     Point2::new(1.0, 2.0),
+/// ]);
+/// assert_eq!(polyline.parameter_range(), (0.0, 2.0));
+/// assert_near!(polyline.subs(0.5), Point2::new(0.5, 0.0));
+/// assert_near!(polyline.subs(1.5), Point2::new(1.0, 1.0));
+/// assert_near!(polyline.der(1.5), Vector2::new(0.0, 2.0));
+/// assert_near!(polyline.length(), 3.0);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PolylineCurve<P> {
+    points: Vec<P>,
+    params: Vec<f64>,
+}
+
+/// the kind of [`Spiral`](./struct.Spiral.html)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SpiralKind {
+    /// the Archimedean spiral `r = a + b θ`
+    Archimedean,
+    /// the logarithmic spiral `r = a exp(b θ)`
+    Logarithmic,
+}
+
+/// planar spiral around a center
+///
+/// The parameter is the polar angle `θ`, and the point is `center + r(θ) (cos θ, sin θ)`, where
+/// the radius `r(θ)` is given by the [`SpiralKind`](./enum.SpiralKind.html), the radius `a` at
+/// `θ = 0` and the growth `b`.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// use std::f64::consts::PI;
+/// // the spring winding three times with the pitch 0.5
+/// let growth = 0.5 / (2.0 * PI);
+/// let spiral = Spiral::archimedean(Point2::new(1.0, 2.0), 1.0, growth, (0.0, 6.0 * PI));
+/// assert_near!(spiral.subs(0.0), Point2::new(2.0, 2.0));
+/// assert_near!(spiral.subs(2.0 * PI), Point2::new(2.5, 2.0));
+/// assert_near!(spiral.subs(6.0 * PI), Point2::new(3.5, 2.0));
+///
+/// // the approximation by the cubic B-spline curve
+/// let bspline = spiral.to_bspline(1.0e-4);
+/// for i in 0..=100 {
+///     let t = 6.0 * PI * i as f64 / 100.0;
+///     let pt = Point2::from_vec(bspline.subs(t));
+///     assert!(pt.distance(spiral.subs(t)) < 1.0e-4);
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spiral {
+    kind: SpiralKind,
+    center: Point2,
+    radius: f64,
+    growth: f64,
+    range: (f64, f64),
+}
+
+/// involute of a circle
+///
+/// The involute is traced by the end of the taut string unwound counter-clockwise from the base
+/// circle, starting at the point of the base circle at the polar angle `start_angle`. The
+/// parameter is the roll angle `t`, and the point is
+/// `center + r ((cos φ, sin φ) + t (sin φ, -cos φ))` with `φ = start_angle + t`, where `r` is the
+/// radius of the base circle. The involute is the profile of the teeth of gears.
+/// # Examples
+/// ```
+/// use truck_geometry::*;
+/// let involute = Involute::new(Point2::origin(), 2.0, 0.0, (0.0, 1.0));
+/// assert_near!(involute.subs(0.0), Point2::new(2.0, 0.0));
+/// // the string is tangent to the base circle
+/// let t = 0.7;
+/// let phi = t;
+/// let tangent_point = Point2::new(2.0 * f64::cos(phi), 2.0 * f64::sin(phi));
+/// assert_near!((involute.subs(t) - tangent_point).magnitude(), 2.0 * t);
+/// // the radius 2.5 is reached at the roll angle 0.75
+/// assert_near!(involute.parameter_at_radius(2.5).unwrap(), 0.75);
+/// assert_near!(involute.subs(0.75).to_vec().magnitude(), 2.5);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Involute {
+    center: Point2,
+    radius: f64,
+    start_angle: f64,
+    range: (f64, f64),
+}
+
 mod plane;
+mod polyline_curve;
 mod sphere;
+mod spiral;
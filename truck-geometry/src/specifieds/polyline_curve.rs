@@ -0,0 +1,311 @@
+use super::*;
+use crate::errors::Error;
+use std::cmp::Ordering;
+
+impl<P> PolylineCurve<P> {
+    /// Creates a polyline curve whose parameter of the `i`-th vertex is `i`.
+    /// # Failures
+    /// * If `points` is empty, returns
+    /// [`Error::EmptyControlPoints`](../errors/enum.Error.html#variant.EmptyControlPoints).
+    /// * If `points` has only one point, returns
+    /// [`Error::ZeroRange`](../errors/enum.Error.html#variant.ZeroRange).
+    /// ```
+    /// use truck_geometry::*;
+    /// use errors::Error;
+    /// assert_eq!(
+    ///     PolylineCurve::<Point2>::try_new(Vec::new()),
+    ///     Err(Error::EmptyControlPoints),
+    /// );
+    /// assert_eq!(
+    ///     PolylineCurve::try_new(vec![Point2::new(0.0, 0.0)]),
+    ///     Err(Error::ZeroRange),
+    /// );
+    /// ```
+    pub fn try_new(points: Vec<P>) -> Result<Self> {
+        let params = (0..points.len()).map(|i| i as f64).collect();
+        Self::try_with_parameters(points, params)
+    }
+    /// Creates a polyline curve whose parameter of the `i`-th vertex is `i`.
+    /// # Panics
+    /// Panic occurs if `points` has less than two points.
+    #[inline(always)]
+    pub fn new(points: Vec<P>) -> Self {
+        Self::try_new(points).unwrap_or_else(|error| panic!("{}", error))
+    }
+    /// Creates a polyline curve whose parameter of the `i`-th vertex is `params[i]`.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let polyline = PolylineCurve::try_with_parameters(
+    ///     vec![Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), Point2::new(1.0, 2.0)],
+    ///     vec![0.0, 1.0, 3.0],
+    /// )
+    /// .unwrap();
+    /// // the parameter is proportional to the arc length
+    /// assert_near!(polyline.subs(2.0), Point2::new(1.0, 1.0));
+    /// assert_near!(polyline.der(2.0), Vector2::new(0.0, 1.0));
+    /// ```
+    /// # Failures
+    /// * If the lengths of `points` and `params` are different, returns
+    /// [`Error::DifferentLengthParameters`](../errors/enum.Error.html#variant.DifferentLengthParameters).
+    /// * If `params` is not strictly increasing, returns
+    /// [`Error::NotSortedVector`](../errors/enum.Error.html#variant.NotSortedVector).
+    /// ```
+    /// use truck_geometry::*;
+    /// use errors::Error;
+    /// let points = vec![Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), Point2::new(1.0, 2.0)];
+    /// assert_eq!(
+    ///     PolylineCurve::try_with_parameters(points.clone(), vec![0.0, 1.0]),
+    ///     Err(Error::DifferentLengthParameters(3, 2)),
+    /// );
+    /// assert_eq!(
+    ///     PolylineCurve::try_with_parameters(points, vec![0.0, 1.0, 1.0]),
+    ///     Err(Error::NotSortedVector),
+    /// );
+    /// ```
+    pub fn try_with_parameters(points: Vec<P>, params: Vec<f64>) -> Result<Self> {
+        if points.is_empty() {
+            Err(Error::EmptyControlPoints)
+        } else if points.len() != params.len() {
+            Err(Error::DifferentLengthParameters(points.len(), params.len()))
+        } else if points.len() == 1 {
+            Err(Error::ZeroRange)
+        } else if params.windows(2).any(|a| a[0] >= a[1]) {
+            Err(Error::NotSortedVector)
+        } else {
+            Ok(PolylineCurve { points, params })
+        }
+    }
+    /// Creates a polyline curve whose parameter of the `i`-th vertex is `params[i]`.
+    /// # Panics
+    /// Panic occurs if `PolylineCurve::try_with_parameters` fails.
+    #[inline(always)]
+    pub fn with_parameters(points: Vec<P>, params: Vec<f64>) -> Self {
+        Self::try_with_parameters(points, params).unwrap_or_else(|error| panic!("{}", error))
+    }
+    /// Returns the vertices.
+    #[inline(always)]
+    pub fn points(&self) -> &Vec<P> { &self.points }
+    /// Returns the vertices.
+    #[inline(always)]
+    pub fn into_points(self) -> Vec<P> { self.points }
+    /// Returns the parameters of the vertices.
+    #[inline(always)]
+    pub fn parameters(&self) -> &Vec<f64> { &self.params }
+    /// Returns the number of the vertices.
+    #[inline(always)]
+    pub fn num_points(&self) -> usize { self.points.len() }
+    /// Returns the index `i` of the segment between the `i`-th and `(i + 1)`-th vertices
+    /// including the parameter `t`. The inner vertices are included in the latter segments.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let polyline = PolylineCurve::new(vec![
+    ///     Point2::new(0.0, 0.0),
+    ///     Point2::new(1.0, 0.0),
+    ///     Point2::new(1.0, 2.0),
+    /// ]);
+    /// assert_eq!(polyline.segment_index(-1.0), 0);
+    /// assert_eq!(polyline.segment_index(0.5), 0);
+    /// assert_eq!(polyline.segment_index(1.0), 1);
+    /// assert_eq!(polyline.segment_index(3.0), 1);
+    /// ```
+    pub fn segment_index(&self, t: f64) -> usize {
+        let n = self.params.len();
+        let idx = match self.params[1..n - 1]
+            .binary_search_by(|x| x.partial_cmp(&t).unwrap_or(Ordering::Less))
+        {
+            Ok(idx) => idx + 1,
+            Err(idx) => idx,
+        };
+        usize::min(idx, n - 2)
+    }
+}
+
+impl<P: EuclideanSpace<Scalar = f64>> PolylineCurve<P> {
+    /// Cuts the polyline curve at the parameter `t`, and returns the latter part.
+    /// The parameters of the both parts are kept.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let mut polyline = PolylineCurve::new(vec![
+    ///     Point2::new(0.0, 0.0),
+    ///     Point2::new(1.0, 0.0),
+    ///     Point2::new(1.0, 2.0),
+    /// ]);
+    /// let latter = polyline.cut(1.5);
+    /// assert_eq!(polyline.parameter_range(), (0.0, 1.5));
+    /// assert_eq!(latter.parameter_range(), (1.5, 2.0));
+    /// assert_eq!(polyline.num_points(), 3);
+    /// assert_eq!(latter.num_points(), 2);
+    /// assert_near!(polyline.back(), Point2::new(1.0, 1.0));
+    /// assert_near!(latter.front(), Point2::new(1.0, 1.0));
+    ///
+    /// // cut at a vertex
+    /// let latter = polyline.cut(1.0);
+    /// assert_eq!(polyline.points(), &vec![Point2::new(0.0, 0.0), Point2::new(1.0, 0.0)]);
+    /// assert_eq!(latter.num_points(), 2);
+    /// ```
+    /// # Panics
+    /// Panic occurs if `t` is not in the interior of the parameter range.
+    pub fn cut(&mut self, t: f64) -> Self {
+        let (t0, t1) = (self.params[0], self.params[self.params.len() - 1]);
+        assert!(
+            t0 < t && t < t1 && !t.near(&t0) && !t.near(&t1),
+            "the parameter {} is not in the interior of the range ({}, {})",
+            t,
+            t0,
+            t1
+        );
+        let i = self.segment_index(t);
+        let k = if t.near(&self.params[i]) {
+            i
+        } else if t.near(&self.params[i + 1]) {
+            i + 1
+        } else {
+            let pt = self.subs(t);
+            self.points.insert(i + 1, pt);
+            self.params.insert(i + 1, t);
+            i + 1
+        };
+        let points = self.points.split_off(k);
+        let params = self.params.split_off(k);
+        self.points.push(points[0]);
+        self.params.push(params[0]);
+        PolylineCurve { points, params }
+    }
+
+    /// Returns the B-spline curve of degree one, which is the same curve as `self`
+    /// including the parameter.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let polyline = PolylineCurve::with_parameters(
+    ///     vec![Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), Point2::new(1.0, 2.0)],
+    ///     vec![0.0, 1.0, 3.0],
+    /// );
+    /// let bspline = polyline.to_bspline();
+    /// const N: usize = 10;
+    /// for i in 0..=N {
+    ///     let t = 3.0 * i as f64 / N as f64;
+    ///     assert_near!(ParametricCurve::subs(&bspline, t), polyline.subs(t));
+    /// }
+    /// ```
+    pub fn to_bspline(&self) -> BSplineCurve<P::Diff> {
+        let n = self.params.len();
+        let mut knots = Vec::with_capacity(n + 2);
+        knots.push(self.params[0]);
+        knots.extend(self.params.iter().cloned());
+        knots.push(self.params[n - 1]);
+        let control_points = self.points.iter().map(|pt| pt.to_vec()).collect();
+        BSplineCurve::new(KnotVec::from(knots), control_points)
+    }
+}
+
+impl<P: MetricSpace<Metric = f64> + Copy> PolylineCurve<P> {
+    /// Returns the length of the polyline.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let polyline = PolylineCurve::new(vec![
+    ///     Point3::new(0.0, 0.0, 0.0),
+    ///     Point3::new(3.0, 4.0, 0.0),
+    ///     Point3::new(3.0, 4.0, 2.0),
+    /// ]);
+    /// assert_near!(polyline.length(), 7.0);
+    /// ```
+    #[inline(always)]
+    pub fn length(&self) -> f64 {
+        self.points
+            .windows(2)
+            .map(|pts| pts[0].distance(pts[1]))
+            .sum()
+    }
+}
+
+impl<P: EuclideanSpace<Scalar = f64>> ParametricCurve for PolylineCurve<P> {
+    type Point = P;
+    type Vector = P::Diff;
+    #[inline(always)]
+    fn subs(&self, t: f64) -> P {
+        let i = self.segment_index(t);
+        let (t0, t1) = (self.params[i], self.params[i + 1]);
+        let (p, q) = (self.points[i], self.points[i + 1]);
+        p + (q - p) * ((t - t0) / (t1 - t0))
+    }
+    #[inline(always)]
+    fn der(&self, t: f64) -> P::Diff {
+        let i = self.segment_index(t);
+        let (t0, t1) = (self.params[i], self.params[i + 1]);
+        (self.points[i + 1] - self.points[i]) / (t1 - t0)
+    }
+    #[inline(always)]
+    fn der2(&self, _: f64) -> P::Diff { P::Diff::zero() }
+    #[inline(always)]
+    fn parameter_range(&self) -> (f64, f64) { (self.params[0], self.params[self.params.len() - 1]) }
+}
+
+impl<P: Clone> Invertible for PolylineCurve<P> {
+    fn invert(&mut self) {
+        let (t0, t1) = (self.params[0], self.params[self.params.len() - 1]);
+        self.points.reverse();
+        self.params.reverse();
+        self.params.iter_mut().for_each(|t| *t = t0 + t1 - *t);
+    }
+    fn inverse(&self) -> Self {
+        let mut curve = self.clone();
+        curve.invert();
+        curve
+    }
+}
+
+impl<P: EuclideanSpace<Scalar = f64>, T: Transform<P>> Transformed<T> for PolylineCurve<P> {
+    #[inline(always)]
+    fn transform_by(&mut self, trans: T) {
+        self.points
+            .iter_mut()
+            .for_each(|pt| *pt = trans.transform_point(*pt));
+    }
+    #[inline(always)]
+    fn transformed(&self, trans: T) -> Self {
+        let mut curve = self.clone();
+        curve.transform_by(trans);
+        curve
+    }
+}
+
+impl<P> ParameterDivision1D for PolylineCurve<P> {
+    /// Returns the parameters of the vertices, since the polyline is exactly divided by them.
+    #[inline(always)]
+    fn parameter_division(&self, _: f64) -> Vec<f64> { self.params.clone() }
+}
+
+#[test]
+fn polyline_invert_and_cut() {
+    let polyline = PolylineCurve::with_parameters(
+        vec![
+            Point3::new(0.0, 0.0, 0.0),
+            Point3::new(1.0, 0.0, 0.0),
+            Point3::new(1.0, 1.0, 0.0),
+            Point3::new(1.0, 1.0, 1.0),
+        ],
+        vec![1.0, 2.0, 2.5, 4.0],
+    );
+    let inverse = polyline.inverse();
+    assert_eq!(inverse.parameter_range(), (1.0, 4.0));
+    let mut part0 = polyline.clone();
+    let mut part1 = part0.cut(2.2);
+    let part2 = part1.cut(3.0);
+    const N: usize = 30;
+    for i in 0..=N {
+        let t = 1.0 + 3.0 * i as f64 / N as f64;
+        assert_near!(polyline.subs(t), inverse.subs(5.0 - t));
+        let part = match t {
+            _ if t < 2.2 => &part0,
+            _ if t < 3.0 => &part1,
+            _ => &part2,
+        };
+        assert_near!(polyline.subs(t), part.subs(t));
+        assert_near!(polyline.der(t), part.der(t));
+    }
+}
@@ -0,0 +1,254 @@
+use super::*;
+use std::f64::consts::PI;
+
+impl Spiral {
+    /// Creates the Archimedean spiral `r = radius + growth * θ` with `θ` in `range`.
+    #[inline(always)]
+    pub fn archimedean(center: Point2, radius: f64, growth: f64, range: (f64, f64)) -> Spiral {
+        Spiral {
+            kind: SpiralKind::Archimedean,
+            center,
+            radius,
+            growth,
+            range,
+        }
+    }
+    /// Creates the logarithmic spiral `r = radius * exp(growth * θ)` with `θ` in `range`.
+    #[inline(always)]
+    pub fn logarithmic(center: Point2, radius: f64, growth: f64, range: (f64, f64)) -> Spiral {
+        Spiral {
+            kind: SpiralKind::Logarithmic,
+            center,
+            radius,
+            growth,
+            range,
+        }
+    }
+    /// Returns the kind of the spiral
+    #[inline(always)]
+    pub fn kind(&self) -> SpiralKind { self.kind }
+    /// Returns the center
+    #[inline(always)]
+    pub fn center(&self) -> Point2 { self.center }
+    /// Returns the radius at `θ = 0`
+    #[inline(always)]
+    pub fn radius(&self) -> f64 { self.radius }
+    /// Returns the growth of the radius
+    #[inline(always)]
+    pub fn growth(&self) -> f64 { self.growth }
+    /// Returns the radius at the polar angle `t`.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let spiral = Spiral::logarithmic(Point2::origin(), 1.0, 0.2, (0.0, 10.0));
+    /// assert_near!(spiral.radius_at(5.0), f64::exp(1.0));
+    /// assert_near!(spiral.subs(5.0).to_vec().magnitude(), f64::exp(1.0));
+    /// ```
+    #[inline(always)]
+    pub fn radius_at(&self, t: f64) -> f64 {
+        match self.kind {
+            SpiralKind::Archimedean => self.radius + self.growth * t,
+            SpiralKind::Logarithmic => self.radius * f64::exp(self.growth * t),
+        }
+    }
+    /// Returns the cubic B-spline curve approximating the spiral within the tolerance `tol`.
+    /// The parameter of the B-spline curve is the same as the one of the spiral.
+    #[inline(always)]
+    pub fn to_bspline(&self, tol: f64) -> BSplineCurve<Vector2> {
+        composition::cubic_approximation(self, quarter_turns(self.range), tol)
+    }
+}
+
+impl ParametricCurve for Spiral {
+    type Point = Point2;
+    type Vector = Vector2;
+    #[inline(always)]
+    fn subs(&self, t: f64) -> Point2 {
+        self.center + self.radius_at(t) * Vector2::new(f64::cos(t), f64::sin(t))
+    }
+    #[inline(always)]
+    fn der(&self, t: f64) -> Vector2 {
+        let (r, dr) = match self.kind {
+            SpiralKind::Archimedean => (self.radius_at(t), self.growth),
+            SpiralKind::Logarithmic => (self.radius_at(t), self.growth * self.radius_at(t)),
+        };
+        let (cos, sin) = (f64::cos(t), f64::sin(t));
+        dr * Vector2::new(cos, sin) + r * Vector2::new(-sin, cos)
+    }
+    #[inline(always)]
+    fn der2(&self, t: f64) -> Vector2 {
+        let r = self.radius_at(t);
+        let (dr, ddr) = match self.kind {
+            SpiralKind::Archimedean => (self.growth, 0.0),
+            SpiralKind::Logarithmic => (self.growth * r, self.growth * self.growth * r),
+        };
+        let (cos, sin) = (f64::cos(t), f64::sin(t));
+        (ddr - r) * Vector2::new(cos, sin) + 2.0 * dr * Vector2::new(-sin, cos)
+    }
+    #[inline(always)]
+    fn parameter_range(&self) -> (f64, f64) { self.range }
+}
+
+impl ParameterDivision1D for Spiral {
+    #[inline(always)]
+    fn parameter_division(&self, tol: f64) -> Vec<f64> { chord_division(self, tol) }
+}
+
+impl Involute {
+    /// Creates the involute of the circle with `radius` around `center`, starting at the point
+    /// of the circle at the polar angle `start_angle`, with the roll angle in `range`.
+    #[inline(always)]
+    pub fn new(center: Point2, radius: f64, start_angle: f64, range: (f64, f64)) -> Involute {
+        Involute {
+            center,
+            radius,
+            start_angle,
+            range,
+        }
+    }
+    /// Returns the center of the base circle
+    #[inline(always)]
+    pub fn center(&self) -> Point2 { self.center }
+    /// Returns the radius of the base circle
+    #[inline(always)]
+    pub fn radius(&self) -> f64 { self.radius }
+    /// Returns the polar angle of the start point on the base circle
+    #[inline(always)]
+    pub fn start_angle(&self) -> f64 { self.start_angle }
+    /// Returns the non-negative roll angle at which the distance from the center is `radius`.
+    /// If `radius` is less than the radius of the base circle, returns `None`.
+    #[inline(always)]
+    pub fn parameter_at_radius(&self, radius: f64) -> Option<f64> {
+        let ratio = radius / self.radius;
+        match ratio < 1.0 {
+            true => None,
+            false => Some(f64::sqrt(ratio * ratio - 1.0)),
+        }
+    }
+    /// Returns the polar angle of the point at the roll angle `t` around the center.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let involute = Involute::new(Point2::origin(), 1.0, 0.5, (0.0, 2.0));
+    /// let pt = involute.subs(1.5);
+    /// assert_near!(involute.polar_angle(1.5), f64::atan2(pt.y, pt.x));
+    /// ```
+    #[inline(always)]
+    pub fn polar_angle(&self, t: f64) -> f64 { self.start_angle + t - f64::atan(t) }
+    /// Returns the cubic B-spline curve approximating the involute within the tolerance `tol`.
+    /// The parameter of the B-spline curve is the same as the one of the involute.
+    /// # Examples
+    /// ```
+    /// use truck_geometry::*;
+    /// let involute = Involute::new(Point2::new(1.0, 1.0), 3.0, 1.0, (0.0, 1.5));
+    /// let bspline = involute.to_bspline(1.0e-5);
+    /// assert_eq!(bspline.degree(), 3);
+    /// for i in 0..=100 {
+    ///     let t = 1.5 * i as f64 / 100.0;
+    ///     let pt = Point2::from_vec(bspline.subs(t));
+    ///     assert!(pt.distance(involute.subs(t)) < 1.0e-5);
+    /// }
+    /// ```
+    #[inline(always)]
+    pub fn to_bspline(&self, tol: f64) -> BSplineCurve<Vector2> {
+        composition::cubic_approximation(self, quarter_turns(self.range), tol)
+    }
+}
+
+impl ParametricCurve for Involute {
+    type Point = Point2;
+    type Vector = Vector2;
+    #[inline(always)]
+    fn subs(&self, t: f64) -> Point2 {
+        let (cos, sin) = (f64::cos(self.start_angle + t), f64::sin(self.start_angle + t));
+        self.center + self.radius * Vector2::new(cos + t * sin, sin - t * cos)
+    }
+    #[inline(always)]
+    fn der(&self, t: f64) -> Vector2 {
+        let (cos, sin) = (f64::cos(self.start_angle + t), f64::sin(self.start_angle + t));
+        self.radius * t * Vector2::new(cos, sin)
+    }
+    #[inline(always)]
+    fn der2(&self, t: f64) -> Vector2 {
+        let (cos, sin) = (f64::cos(self.start_angle + t), f64::sin(self.start_angle + t));
+        self.radius * Vector2::new(cos - t * sin, sin + t * cos)
+    }
+    #[inline(always)]
+    fn parameter_range(&self) -> (f64, f64) { self.range }
+}
+
+impl ParameterDivision1D for Involute {
+    #[inline(always)]
+    fn parameter_division(&self, tol: f64) -> Vec<f64> { chord_division(self, tol) }
+}
+
+/// the number of the quarter turns in the parameter range, the initial division
+#[inline(always)]
+fn quarter_turns((t0, t1): (f64, f64)) -> usize {
+    f64::ceil((t1 - t0).abs() / (PI / 2.0)).max(1.0) as usize
+}
+
+/// the division whose chords are within the tolerance `tol` from the curve
+fn chord_division<C>(curve: &C, tol: f64) -> Vec<f64>
+where C: ParametricCurve<Point = Point2, Vector = Vector2> {
+    let range = curve.parameter_range();
+    composition::bisection_division(range, quarter_turns(range), |t0, t1| {
+        let (p0, p1) = (curve.subs(t0), curve.subs(t1));
+        (1..4).all(|i| {
+            let s = i as f64 / 4.0;
+            let pt = curve.subs(t0 + (t1 - t0) * s);
+            pt.distance(p0 + (p1 - p0) * s) < tol
+        })
+    })
+}
+
+#[test]
+fn spiral_derivation_test() {
+    let spirals = [
+        Spiral::archimedean(Point2::new(1.0, -1.0), 0.5, 0.3, (-2.0, 8.0)),
+        Spiral::logarithmic(Point2::new(1.0, -1.0), 0.5, -0.2, (-2.0, 8.0)),
+    ];
+    let involute = Involute::new(Point2::new(1.0, -1.0), 1.5, 0.3, (-2.0, 8.0));
+    const N: usize = 100;
+    const EPS: f64 = 1.0e-4;
+    for i in 0..=N {
+        let t = -2.0 + 10.0 * i as f64 / N as f64;
+        for spiral in &spirals {
+            let der = (spiral.subs(t + EPS) - spiral.subs(t - EPS)) / (2.0 * EPS);
+            let der2 = (spiral.der(t + EPS) - spiral.der(t - EPS)) / (2.0 * EPS);
+            assert!((der - spiral.der(t)).magnitude() < 1.0e-6);
+            assert!((der2 - spiral.der2(t)).magnitude() < 1.0e-6);
+        }
+        let der = (involute.subs(t + EPS) - involute.subs(t - EPS)) / (2.0 * EPS);
+        let der2 = (involute.der(t + EPS) - involute.der(t - EPS)) / (2.0 * EPS);
+        assert!((der - involute.der(t)).magnitude() < 1.0e-6);
+        assert!((der2 - involute.der2(t)).magnitude() < 1.0e-6);
+    }
+
+    let division = spirals[1].parameter_division(1.0e-3);
+    for a in division.windows(2) {
+        let (p0, p1) = (spirals[1].subs(a[0]), spirals[1].subs(a[1]));
+        let mid = spirals[1].subs((a[0] + a[1]) / 2.0);
+        assert!(mid.distance(p0.midpoint(p1)) < 1.0e-3);
+    }
+}
+
+#[test]
+fn short_spiral_test() {
+    assert_eq!(quarter_turns((1.0, 1.0)), 1);
+    assert_eq!(quarter_turns((1.0, 1.5)), 1);
+    let spirals = [
+        Spiral::archimedean(Point2::new(1.0, -1.0), 0.5, 0.3, (1.0, 1.5)),
+        Spiral::logarithmic(Point2::new(1.0, -1.0), 0.5, -0.2, (1.0, 1.01)),
+    ];
+    for spiral in &spirals {
+        let bsp = spiral.to_bspline(1.0e-4);
+        for i in 0..=10 {
+            let t = 1.0 + 0.05 * i as f64;
+            let t = f64::min(t, spiral.parameter_range().1);
+            assert!(bsp.subs(t).distance(spiral.subs(t).to_vec()) < 1.0e-4);
+        }
+        let division = spiral.parameter_division(1.0e-4);
+        assert!(division.len() >= 2);
+    }
+}
@@ -0,0 +1,19 @@
+//! Modeling a helical gear with a bore.
+//!
+//! Generated json file can be visualized by `simple-shape-viewer`, an example of `truck-rendimpl`.
+
+use truck_modeling::gear::Gear;
+use truck_modeling::*;
+
+fn main() {
+    let gear = Gear {
+        teeth: 18,
+        helix_angle: Deg(20.0).into(),
+        face_width: 4.0,
+        bore_diameter: 5.0,
+        ..Default::default()
+    };
+    let solid = gear.solid().unwrap();
+    let json = serde_json::to_vec_pretty(&solid.compress()).unwrap();
+    std::fs::write("gear.json", &json).unwrap();
+}
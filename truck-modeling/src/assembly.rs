@@ -0,0 +1,274 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::io::Write;
+use std::sync::Arc;
+
+/// The attributes of parts and assemblies, e.g. part numbers, materials or suppliers.
+pub type Attributes = BTreeMap<String, String>;
+
+/// A part, a solid with its name and attributes.
+///
+/// A part is shared by the components of assemblies through [`Arc`],
+/// and the identity of the shape is the identity of the pointer.
+///
+/// [`Arc`]: https://doc.rust-lang.org/std/sync/struct.Arc.html
+#[derive(Clone, Debug)]
+pub struct Part {
+    /// the name of the part
+    pub name: String,
+    /// the shape of the part
+    pub solid: Solid,
+    /// the attributes of the part
+    pub attributes: Attributes,
+}
+
+impl Part {
+    /// Creates a new shared part without attributes.
+    #[inline(always)]
+    pub fn new(name: &str, solid: Solid) -> Arc<Part> {
+        Arc::new(Part {
+            name: name.to_string(),
+            solid,
+            attributes: Attributes::new(),
+        })
+    }
+
+    /// Creates a new shared part with attributes.
+    #[inline(always)]
+    pub fn with_attributes(name: &str, solid: Solid, attributes: Attributes) -> Arc<Part> {
+        Arc::new(Part {
+            name: name.to_string(),
+            solid,
+            attributes,
+        })
+    }
+}
+
+/// The placed instance of a component.
+#[derive(Clone, Debug)]
+pub enum Instance {
+    /// a part
+    Part(Arc<Part>),
+    /// a sub-assembly
+    Assembly(Arc<Assembly>),
+}
+
+/// A component of an assembly, an instance placed by a transformation.
+#[derive(Clone, Debug)]
+pub struct Component {
+    /// the instance
+    pub instance: Instance,
+    /// the transformation from the local coordinate of the instance to the one of the assembly
+    pub transform: Matrix4,
+}
+
+/// A hierarchical assembly of parts.
+#[derive(Clone, Debug)]
+pub struct Assembly {
+    /// the name of the assembly
+    pub name: String,
+    /// the components of the assembly
+    pub components: Vec<Component>,
+    /// the attributes of the assembly
+    pub attributes: Attributes,
+}
+
+impl Assembly {
+    /// Creates an empty assembly.
+    #[inline(always)]
+    pub fn new(name: &str) -> Assembly {
+        Assembly {
+            name: name.to_string(),
+            components: Vec::new(),
+            attributes: Attributes::new(),
+        }
+    }
+
+    /// Places `part` by `transform`.
+    #[inline(always)]
+    pub fn add_part(&mut self, part: &Arc<Part>, transform: Matrix4) {
+        self.components.push(Component {
+            instance: Instance::Part(Arc::clone(part)),
+            transform,
+        })
+    }
+
+    /// Places `assembly` by `transform`.
+    #[inline(always)]
+    pub fn add_assembly(&mut self, assembly: &Arc<Assembly>, transform: Matrix4) {
+        self.components.push(Component {
+            instance: Instance::Assembly(Arc::clone(assembly)),
+            transform,
+        })
+    }
+
+    /// Returns the parts placed in the coordinate of the assembly,
+    /// with the transformations of all sub-assemblies composed.
+    pub fn placed_parts(&self) -> Vec<(Arc<Part>, Matrix4)> {
+        let mut res = Vec::new();
+        self.collect_parts(Matrix4::identity(), &mut res);
+        res
+    }
+
+    fn collect_parts(&self, mat: Matrix4, res: &mut Vec<(Arc<Part>, Matrix4)>) {
+        for component in &self.components {
+            let mat = mat * component.transform;
+            match &component.instance {
+                Instance::Part(part) => res.push((Arc::clone(part), mat)),
+                Instance::Assembly(assembly) => assembly.collect_parts(mat, res),
+            }
+        }
+    }
+
+    /// Returns the solids of all parts transformed into the coordinate of the assembly.
+    pub fn solids(&self) -> Vec<Solid> {
+        self.placed_parts()
+            .into_iter()
+            .map(|(part, mat)| builder::transformed(&part.solid, mat))
+            .collect()
+    }
+
+    /// Returns the bill of materials of the assembly.
+    ///
+    /// The parts are identified by the shared pointers, not by the names,
+    /// and counted over all levels of the sub-assemblies.
+    /// The rows are in the order in which the parts first appear.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::assembly::*;
+    /// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let cube = builder::tsweep(&f, Vector3::unit_z());
+    ///
+    /// let mut attributes = Attributes::new();
+    /// attributes.insert("material".to_string(), "steel".to_string());
+    /// let block = Part::with_attributes("block", cube.clone(), attributes);
+    /// let bolt = Part::new("bolt", cube);
+    ///
+    /// let mut bracket = Assembly::new("bracket");
+    /// bracket.add_part(&block, Matrix4::identity());
+    /// bracket.add_part(&bolt, Matrix4::from_translation(Vector3::unit_x()));
+    /// bracket.add_part(&bolt, Matrix4::from_translation(Vector3::unit_y()));
+    /// let bracket = std::sync::Arc::new(bracket);
+    ///
+    /// let mut frame = Assembly::new("frame");
+    /// frame.add_assembly(&bracket, Matrix4::identity());
+    /// frame.add_assembly(&bracket, Matrix4::from_translation(Vector3::unit_z()));
+    ///
+    /// let bom = frame.bill_of_materials();
+    /// assert_eq!(bom.columns, vec!["material".to_string()]);
+    /// assert_eq!(bom.items.len(), 2);
+    /// assert_eq!((bom.items[0].name.as_str(), bom.items[0].count), ("block", 2));
+    /// assert_eq!((bom.items[1].name.as_str(), bom.items[1].count), ("bolt", 4));
+    ///
+    /// let mut csv = Vec::new();
+    /// bom.write_csv(&mut csv).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(csv).unwrap(),
+    ///     "item,name,count,material\n1,block,2,steel\n2,bolt,4,\n",
+    /// );
+    ///
+    /// let json = serde_json::to_string(&bom).unwrap();
+    /// assert_eq!(serde_json::from_str::<BillOfMaterials>(&json).unwrap(), bom);
+    /// ```
+    pub fn bill_of_materials(&self) -> BillOfMaterials {
+        let mut items: Vec<(Arc<Part>, usize)> = Vec::new();
+        for (part, _) in self.placed_parts() {
+            match items.iter_mut().find(|(p, _)| Arc::ptr_eq(p, &part)) {
+                Some((_, count)) => *count += 1,
+                None => items.push((part, 1)),
+            }
+        }
+        let columns: BTreeSet<&String> = items
+            .iter()
+            .flat_map(|(part, _)| part.attributes.keys())
+            .collect();
+        BillOfMaterials {
+            columns: columns.into_iter().cloned().collect(),
+            items: items
+                .iter()
+                .map(|(part, count)| BomItem {
+                    name: part.name.clone(),
+                    count: *count,
+                    attributes: part.attributes.clone(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// A row of the bill of materials.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BomItem {
+    /// the name of the part
+    pub name: String,
+    /// the number of the instances of the part in the whole assembly
+    pub count: usize,
+    /// the attributes of the part
+    pub attributes: Attributes,
+}
+
+/// The bill of materials, the list of the parts with their numbers.
+///
+/// Serialized to JSON by `serde`, or written as CSV by [`write_csv`].
+///
+/// [`write_csv`]: #method.write_csv
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BillOfMaterials {
+    /// the keys of the attributes of all parts, sorted
+    pub columns: Vec<String>,
+    /// the rows
+    pub items: Vec<BomItem>,
+}
+
+impl BillOfMaterials {
+    /// Writes the bill of materials as CSV.
+    ///
+    /// The columns are the item number, the name, the count, and the attributes.
+    /// The missing attributes are written as empty fields.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let header: Vec<String> = ["item", "name", "count"]
+            .iter()
+            .map(|s| s.to_string())
+            .chain(self.columns.iter().map(|s| csv_field(s)))
+            .collect();
+        writer.write_fmt(format_args!("{}\n", header.join(",")))?;
+        for (i, item) in self.items.iter().enumerate() {
+            let row: Vec<String> = vec![
+                (i + 1).to_string(),
+                csv_field(&item.name),
+                item.count.to_string(),
+            ]
+            .into_iter()
+            .chain(
+                self.columns
+                    .iter()
+                    .map(|key| match item.attributes.get(key) {
+                        Some(value) => csv_field(value),
+                        None => String::new(),
+                    }),
+            )
+            .collect();
+            writer.write_fmt(format_args!("{}\n", row.join(",")))?;
+        }
+        Ok(())
+    }
+}
+
+/// Quotes the field if it contains a comma, a quotation or a line break.
+fn csv_field(field: &str) -> String {
+    match field.contains(|c| c == ',' || c == '"' || c == '\n' || c == '\r') {
+        true => format!("\"{}\"", field.replace('"', "\"\"")),
+        false => field.to_string(),
+    }
+}
+
+#[test]
+fn csv_escape() {
+    assert_eq!(csv_field("bolt"), "bolt");
+    assert_eq!(csv_field("M4, 10mm"), "\"M4, 10mm\"");
+    assert_eq!(csv_field("5\" pipe"), "\"5\"\" pipe\"");
+}
@@ -0,0 +1,438 @@
+use crate::direct_edit::{is_line, oriented_plane};
+use crate::errors::Error;
+use crate::*;
+use std::collections::HashMap;
+
+/// The shape of the cross section of the blend.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum Blend {
+    /// the rolling-ball fillet with the radius
+    Fillet(f64),
+    /// the planar chamfer with the distance from the edge on the faces
+    Chamfer(f64),
+}
+
+/// The end of a blended edge, where the vertex is split into two vertices.
+struct Corner {
+    vertex: Vertex,
+    // the end plane
+    plane: Plane,
+    // the edges on the end plane adjacent to the first and the second faces
+    edges: (Edge, Edge),
+}
+
+/// The geometry of the blend of an edge between two planes.
+struct Section {
+    // the unit direction of the edge
+    dir: Vector3,
+    // the directions on the planes from the edge to the insides of the faces
+    inner: (Vector3, Vector3),
+    // the distance from the edge to the boundaries of the blend face on the planes
+    setback: f64,
+}
+
+impl Section {
+    fn new(dir: Vector3, normals: (Vector3, Vector3), blend: Blend) -> Result<Section> {
+        let inner = (normals.0.cross(dir), dir.cross(normals.1));
+        // only the convex edges are supported
+        if inner.0.dot(normals.1) > -TOLERANCE {
+            return Err(Error::UnsupportedGeometry);
+        }
+        let half_angle = f64::acos(inner.0.dot(inner.1).clamp(-1.0, 1.0)) / 2.0;
+        let setback = match blend {
+            Blend::Fillet(radius) => radius / half_angle.tan(),
+            Blend::Chamfer(distance) => distance,
+        };
+        Ok(Section {
+            dir,
+            inner,
+            setback,
+        })
+    }
+
+    // the points of the boundaries of the blend face on the planes in the section through `pt`
+    fn boundary_points(&self, pt: Point3) -> (Point3, Point3) {
+        (
+            pt + self.inner.0 * self.setback,
+            pt + self.inner.1 * self.setback,
+        )
+    }
+
+    // the curve of the cross section through `pt`
+    fn curve(&self, pt: Point3, blend: Blend) -> BSplineCurve<Vector4> {
+        let (p, q) = self.boundary_points(pt);
+        match blend {
+            Blend::Fillet(radius) => {
+                let bisector = (self.inner.0 + self.inner.1).normalize();
+                let half_angle = f64::acos(self.inner.0.dot(bisector));
+                let center = pt + bisector * (radius / half_angle.sin());
+                let transit = center - bisector * radius;
+                geom_impls::circle_arc_by_three_points(
+                    p.to_homogeneous(),
+                    q.to_homogeneous(),
+                    transit,
+                )
+            }
+            Blend::Chamfer(_) => geom_impls::line(p.to_homogeneous(), q.to_homogeneous()),
+        }
+    }
+
+    // the affine projection along the edge onto the plane
+    fn projection(&self, plane: &Plane) -> Result<Matrix4> {
+        let (normal, dir) = (plane.normal(), self.dir);
+        let k = normal.dot(dir);
+        if k.so_small() {
+            return Err(Error::DegenerateIntersection);
+        }
+        let h = normal.dot(plane.origin().to_vec());
+        let col = |axis: Vector3, c: f64| (axis - dir * (c / k)).extend(0.0);
+        Ok(Matrix4::from_cols(
+            col(Vector3::unit_x(), normal[0]),
+            col(Vector3::unit_y(), normal[1]),
+            col(Vector3::unit_z(), normal[2]),
+            (dir * (h / k)).extend(1.0),
+        ))
+    }
+}
+
+#[inline(always)]
+fn project(mat: Matrix4, pt: Point3) -> Point3 {
+    Point3::from_homogeneous(mat * pt.to_homogeneous())
+}
+
+/// The faces of the solid with the indices of the shells.
+fn faces_of(solid: &Solid) -> Vec<(usize, &Face)> {
+    solid
+        .boundaries()
+        .iter()
+        .enumerate()
+        .flat_map(|(i, shell)| shell.iter().map(move |face| (i, face)))
+        .collect()
+}
+
+/// Returns the faces incident to the vertex.
+fn incident_faces<'a>(faces: &[(usize, &'a Face)], vertex: &Vertex) -> Vec<&'a Face> {
+    faces
+        .iter()
+        .filter(|(_, face)| {
+            face.absolute_boundaries()
+                .iter()
+                .flatten()
+                .any(|edge| edge.front() == vertex || edge.back() == vertex)
+        })
+        .map(|(_, face)| *face)
+        .collect()
+}
+
+/// Returns the edge of `face` incident to `vertex` other than `edge`.
+fn adjacent_edge(face: &Face, edge: &Edge, vertex: &Vertex) -> Result<Edge> {
+    let mut edges = face
+        .absolute_boundaries()
+        .iter()
+        .flatten()
+        .filter(|e| e.id() != edge.id() && (e.front() == vertex || e.back() == vertex));
+    match (edges.next(), edges.next()) {
+        (Some(e), None) => Ok(e.clone()),
+        _ => Err(Error::UnsupportedGeometry),
+    }
+}
+
+/// Returns whether `pt` is strictly inside the segment of the absolute edge from `vertex`.
+fn on_edge(edge: &Edge, vertex: &Vertex, pt: Point3) -> bool {
+    let (front, back) = edge.absolute_ends();
+    let other = if front == vertex { back } else { front };
+    let (p, q) = (*vertex.lock_point().unwrap(), *other.lock_point().unwrap());
+    let s = (pt - p).dot(q - p) / (q - p).magnitude2();
+    s > TOLERANCE && s < 1.0 - TOLERANCE
+}
+
+/// Replaces the edges of the solid by the blend faces.
+///
+/// The blended edges should be lines between two planes, and should be convex.
+/// Each end vertex of the blended edges should be shared by three planes, and should not be
+/// an end of another blended edge.
+pub(crate) fn blend(solid: &Solid, edges: &[Edge], blend: Blend) -> Result<Solid> {
+    let faces = faces_of(solid);
+    // the new vertices at the ends of the edges adjacent to the corners
+    let mut moved = HashMap::<(EdgeID, VertexID), Vertex>::new();
+    // the trimmed edges in the faces adjacent to the blended edges
+    let mut trimmed = HashMap::<(EdgeID, FaceID), Edge>::new();
+    // the edges connecting the split vertices on the end planes
+    let mut connectors = HashMap::<(VertexID, VertexID), Edge>::new();
+    let mut blend_faces = Vec::<(usize, Face)>::new();
+    let mut corners = Vec::<VertexID>::new();
+    let mut blended = Vec::<EdgeID>::new();
+    for edge in edges {
+        if blended.contains(&edge.id()) {
+            continue;
+        }
+        blended.push(edge.id());
+        let adjacent: Vec<(usize, &Face, Edge)> = faces
+            .iter()
+            .filter_map(|(i, face)| {
+                face.boundaries()
+                    .iter()
+                    .flatten()
+                    .find(|e| e.id() == edge.id())
+                    .map(|e| (*i, *face, e.clone()))
+            })
+            .collect();
+        let (shell_index, face0, face1, edge) = match adjacent.as_slice() {
+            [] => return Err(Error::ElementNotFound),
+            [(i, face0, edge), (_, face1, _)] => (*i, *face0, *face1, edge.clone()),
+            _ => return Err(Error::UnsupportedGeometry),
+        };
+        if !is_line(&edge.oriented_curve()) {
+            return Err(Error::UnsupportedGeometry);
+        }
+        let (plane0, plane1) = (oriented_plane(face0)?, oriented_plane(face1)?);
+        let (start, end) = (edge.front().clone(), edge.back().clone());
+        let (p, q) = (*start.lock_point().unwrap(), *end.lock_point().unwrap());
+        let section = Section::new(
+            (q - p).normalize(),
+            (plane0.normal(), plane1.normal()),
+            blend,
+        )?;
+
+        // the corners at the start and the end of the edge oriented in `face0`
+        let mut ends = Vec::new();
+        for vertex in &[start, end] {
+            if corners.contains(&vertex.id()) {
+                return Err(Error::UnsupportedGeometry);
+            }
+            corners.push(vertex.id());
+            let incident = incident_faces(&faces, vertex);
+            if incident.len() != 3 {
+                return Err(Error::UnsupportedGeometry);
+            }
+            let end_face = incident
+                .iter()
+                .find(|f| f.id() != face0.id() && f.id() != face1.id())
+                .ok_or(Error::UnsupportedGeometry)?;
+            let corner_edges = (
+                adjacent_edge(face0, &edge, vertex)?,
+                adjacent_edge(face1, &edge, vertex)?,
+            );
+            if !is_line(&corner_edges.0.oriented_curve())
+                || !is_line(&corner_edges.1.oriented_curve())
+            {
+                return Err(Error::UnsupportedGeometry);
+            }
+            ends.push(Corner {
+                vertex: vertex.clone(),
+                plane: oriented_plane(end_face)?,
+                edges: corner_edges,
+            });
+        }
+
+        // the split vertices and the cross sections on the end planes
+        let mut split = Vec::new();
+        let mut sections = Vec::new();
+        for corner in &ends {
+            let pt = *corner.vertex.lock_point().unwrap();
+            let mat = section.projection(&corner.plane)?;
+            let (p, q) = section.boundary_points(pt);
+            let (p, q) = (project(mat, p), project(mat, q));
+            if !on_edge(&corner.edges.0, &corner.vertex, p)
+                || !on_edge(&corner.edges.1, &corner.vertex, q)
+            {
+                return Err(Error::BlendTooLarge);
+            }
+            let (v0, v1) = (builder::vertex(p), builder::vertex(q));
+            moved.insert((corner.edges.0.id(), corner.vertex.id()), v0.clone());
+            moved.insert((corner.edges.1.id(), corner.vertex.id()), v1.clone());
+            let mut curve = section.curve(pt, blend);
+            curve.control_points_mut().for_each(|pt| *pt = mat * *pt);
+            let connector = match blend {
+                Blend::Fillet(_) => {
+                    Edge::new(&v0, &v1, Curve::NURBSCurve(NURBSCurve::new(curve.clone())))
+                }
+                Blend::Chamfer(_) => builder::line(&v0, &v1),
+            };
+            split.push((v0, v1, connector));
+            sections.push(curve);
+        }
+        let ((x0, x1, start_connector), (y0, y1, end_connector)) = (&split[0], &split[1]);
+        connectors.insert((x1.id(), x0.id()), start_connector.inverse());
+        connectors.insert((y0.id(), y1.id()), end_connector.clone());
+        let line0 = builder::line(x0, y0);
+        let line1 = builder::line(y1, x1);
+        trimmed.insert((edge.id(), face0.id()), line0.clone());
+        trimmed.insert((edge.id(), face1.id()), line1.clone());
+
+        let wire: Wire = vec![
+            line0.inverse(),
+            start_connector.clone(),
+            line1.inverse(),
+            end_connector.inverse(),
+        ]
+        .into();
+        let surface = blend_surface(&section, p, &sections, blend);
+        let outer = -(section.inner.0 + section.inner.1);
+        let ((u0, u1), (v0, v1)) = surface.parameter_range();
+        let surface = match surface.normal((u0 + u1) / 2.0, (v0 + v1) / 2.0).dot(outer) > 0.0 {
+            true => surface,
+            false => surface.inverse(),
+        };
+        blend_faces.push((shell_index, Face::try_new(vec![wire], surface)?));
+    }
+
+    let mut rebuilt = HashMap::<EdgeID, Edge>::new();
+    let mut shells: Vec<Shell> = solid.boundaries().iter().map(|_| Shell::new()).collect();
+    for (shell_index, face) in faces {
+        let touched = face.absolute_boundaries().iter().flatten().any(|edge| {
+            let (front, back) = edge.absolute_ends();
+            blended.contains(&edge.id())
+                || moved.contains_key(&(edge.id(), front.id()))
+                || moved.contains_key(&(edge.id(), back.id()))
+        });
+        if !touched {
+            shells[shell_index].push(face.clone());
+            continue;
+        }
+        let mut wires = Vec::new();
+        for wire in face.boundaries() {
+            let edges = wire
+                .iter()
+                .map(|edge| match trimmed.get(&(edge.id(), face.id())) {
+                    Some(edge) => edge.clone(),
+                    None => {
+                        let new_edge = rebuilt.entry(edge.id()).or_insert_with(|| {
+                            let (front, back) = edge.absolute_ends();
+                            let new_front = moved.get(&(edge.id(), front.id()));
+                            let new_back = moved.get(&(edge.id(), back.id()));
+                            match (new_front, new_back) {
+                                (None, None) => match edge.orientation() {
+                                    true => edge.clone(),
+                                    false => edge.inverse(),
+                                },
+                                _ => builder::line(
+                                    new_front.unwrap_or(front),
+                                    new_back.unwrap_or(back),
+                                ),
+                            }
+                        });
+                        match edge.orientation() {
+                            true => new_edge.clone(),
+                            false => new_edge.inverse(),
+                        }
+                    }
+                })
+                .collect::<Vec<Edge>>();
+            let mut new_wire = Wire::new();
+            for (i, edge) in edges.iter().enumerate() {
+                new_wire.push_back(edge.clone());
+                let next = &edges[(i + 1) % edges.len()];
+                if edge.back() != next.front() {
+                    let key = (edge.back().id(), next.front().id());
+                    new_wire.push_back(
+                        connectors
+                            .get(&key)
+                            .ok_or(Error::UnsupportedGeometry)?
+                            .clone(),
+                    );
+                }
+            }
+            wires.push(new_wire);
+        }
+        shells[shell_index].push(Face::try_new(wires, face.oriented_surface())?);
+    }
+    blend_faces
+        .into_iter()
+        .for_each(|(shell_index, face)| shells[shell_index].push(face));
+    Ok(Solid::try_new(shells)?)
+}
+
+/// Returns the surface of the blend face swept by the cross section along the edge.
+fn blend_surface(
+    section: &Section,
+    pt: Point3,
+    ends: &[BSplineCurve<Vector4>],
+    blend: Blend,
+) -> Surface {
+    if let Blend::Chamfer(_) = blend {
+        let (p, q) = section.boundary_points(pt);
+        return Surface::Plane(Plane::new(p, p + section.dir, q));
+    }
+    // the range of the sweep covering the sections on the end planes
+    let (s0, s1) = ends
+        .iter()
+        .flat_map(|curve| curve.control_points().iter())
+        .map(|pt0| (Point3::from_homogeneous(*pt0) - pt).dot(section.dir))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(s0, s1), s| {
+            (f64::min(s0, s), f64::max(s1, s))
+        });
+    let curve = section.curve(pt, blend);
+    let control_points = curve
+        .control_points()
+        .iter()
+        .map(|pt| {
+            [s0, s1]
+                .iter()
+                .map(|s| *pt + (section.dir * *s * pt[3]).extend(0.0))
+                .collect()
+        })
+        .collect();
+    let knot_vecs = (curve.knot_vec().clone(), KnotVec::bezier_knot(1));
+    Surface::NURBSSurface(NURBSSurface::new(BSplineSurface::new(
+        knot_vecs,
+        control_points,
+    )))
+}
+
+#[test]
+fn fillet_one_edge() {
+    use std::f64::consts::PI;
+    const RADIUS: f64 = 0.3;
+    let v = builder::vertex(Point3::origin());
+    let e = builder::tsweep(&v, 2.0 * Vector3::unit_x());
+    let f = builder::tsweep(&e, 2.0 * Vector3::unit_y());
+    let cube = builder::tsweep(&f, 2.0 * Vector3::unit_z());
+    // the edge on the x-axis
+    let edge = cube.boundaries()[0]
+        .iter()
+        .flat_map(|face| face.boundaries().into_iter().flatten())
+        .find(|edge| {
+            let p = *edge.front().lock_point().unwrap();
+            let q = *edge.back().lock_point().unwrap();
+            p[1].so_small() && p[2].so_small() && q[1].so_small() && q[2].so_small()
+        })
+        .unwrap();
+    let solid = blend(&cube, &[edge], Blend::Fillet(RADIUS)).unwrap();
+
+    assert_eq!(solid.boundaries().len(), 1);
+    let shell = &solid.boundaries()[0];
+    assert_eq!(shell.len(), 7);
+    assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+    assert!(shell
+        .iter()
+        .flat_map(|face| face.boundaries().into_iter().flatten())
+        .all(|edge| edge.is_geometric_consistent()));
+    let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+    let exact = 8.0 - 2.0 * RADIUS * RADIUS * (1.0 - PI / 4.0);
+    assert!((volume - exact).abs() < 1.0e-3, "{}", volume);
+
+    // the fillet face is on the cylinder around the line (t, RADIUS, RADIUS)
+    let fillets: Vec<&Face> = shell
+        .iter()
+        .filter(|face| {
+            face.boundaries()[0].vertex_iter().all(|v| {
+                let pt = *v.lock_point().unwrap();
+                pt[1] < RADIUS + TOLERANCE && pt[2] < RADIUS + TOLERANCE
+            })
+        })
+        .collect();
+    assert_eq!(fillets.len(), 1);
+    let surface = fillets[0].lock_surface().unwrap().clone();
+    let ((u0, u1), (v0, v1)) = surface.parameter_range();
+    const N: usize = 10;
+    for i in 0..=N {
+        for j in 0..=N {
+            let u = u0 + (u1 - u0) * i as f64 / N as f64;
+            let v = v0 + (v1 - v0) * j as f64 / N as f64;
+            let pt = surface.subs(u, v);
+            let dist = f64::hypot(pt[1] - RADIUS, pt[2] - RADIUS);
+            assert!((dist - RADIUS).abs() < 1.0e-6, "{}", dist);
+        }
+    }
+}
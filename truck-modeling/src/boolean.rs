@@ -0,0 +1,1229 @@
+use crate::errors::Error;
+use crate::face_domain::FaceDomain;
+use crate::intersection::IntersectionCache;
+use crate::tessellation::tessellate_face;
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::f64::consts::PI;
+use std::io::{Read, Write};
+use truck_geometry::curve_search_nearest_parameter;
+use truck_polymesh::PolygonMesh;
+
+const TRIALS: usize = 100;
+// the number of the alternating projections refining the points of the intersections
+const REFINEMENTS: usize = 32;
+// the division of the grid of the sample points classifying a region of a face
+const SAMPLE_DIVISION: usize = 8;
+
+/// Boolean operations of solids: union, intersection and difference.
+///
+/// The intersection curves of the faces are traced on the tessellations, and refined onto both
+/// surfaces within `tol`. The edges crossed by the intersection curves are split, the faces are
+/// divided into regions by the intersection curves, and each region is classified by the
+/// winding number of the other solid at a sample point in the region.
+///
+/// The new edges along the intersection curves are the polylines, and the other faces and
+/// edges keep their exact surfaces and curves. The unchanged faces are shared with the operands.
+/// # Remarks
+/// The solids should touch transversally. The faces on the same surface, e.g. the boxes sharing
+/// the plane of a face, and the faces wrapping around the periodic surfaces are not supported.
+pub trait BooleanOperation: Sized {
+    /// Returns the union of `self` and `other`.
+    /// # Errors
+    /// Returns `Error::InvalidIntersection` if the intersection curves are not closed or
+    /// do not end on the edges, and the topological errors if the result is not a solid.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::boolean::BooleanOperation;
+    /// let v = builder::vertex(Point3::origin());
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let cube0 = builder::tsweep(&f, Vector3::unit_z());
+    /// let cube1 = builder::translated(&cube0, Vector3::new(0.5, 0.5, 0.5));
+    ///
+    /// let union = cube0.union(&cube1, 0.01).unwrap();
+    /// assert_eq!(union.boundaries().len(), 1);
+    /// let volume = properties::mass_properties(&union, 0.01).volume;
+    /// assert!((volume - 1.875).abs() < 0.01, "{}", volume);
+    /// ```
+    fn union(&self, other: &Self, tol: f64) -> Result<Self>;
+    /// Returns the intersection of `self` and `other`.
+    /// # Errors
+    /// Returns `Error::InvalidIntersection` if the intersection curves are not closed or
+    /// do not end on the edges, and the topological errors if the result is not a solid.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::boolean::BooleanOperation;
+    /// let v = builder::vertex(Point3::origin());
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let cube0 = builder::tsweep(&f, Vector3::unit_z());
+    /// let cube1 = builder::translated(&cube0, Vector3::new(0.5, 0.5, 0.5));
+    ///
+    /// let intersection = cube0.intersection(&cube1, 0.01).unwrap();
+    /// assert_eq!(intersection.boundaries()[0].len(), 6);
+    /// let volume = properties::mass_properties(&intersection, 0.01).volume;
+    /// assert!((volume - 0.125).abs() < 0.01, "{}", volume);
+    /// ```
+    fn intersection(&self, other: &Self, tol: f64) -> Result<Self>;
+    /// Returns the difference of `other` from `self`.
+    /// # Errors
+    /// Returns `Error::InvalidIntersection` if the intersection curves are not closed or
+    /// do not end on the edges, and the topological errors if the result is not a solid.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::boolean::BooleanOperation;
+    /// use std::f64::consts::PI;
+    /// let v = builder::vertex(Point3::origin());
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let cube = builder::tsweep(&f, Vector3::unit_z());
+    ///
+    /// // a cylinder drilling through the cube
+    /// let v = builder::vertex(Point3::new(0.75, 0.5, -0.5));
+    /// let center = Point3::new(0.5, 0.5, -0.5);
+    /// let circle = builder::rsweep(&v, center, Vector3::unit_z(), Rad(2.0 * PI));
+    /// let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+    /// let cylinder = builder::tsweep(&disk, Vector3::new(0.0, 0.0, 2.0));
+    ///
+    /// let drilled = cube.subtract(&cylinder, 0.01).unwrap();
+    /// let volume = properties::mass_properties(&drilled, 0.01).volume;
+    /// assert!((volume - (1.0 - PI / 16.0)).abs() < 0.01, "{}", volume);
+    /// ```
+    fn subtract(&self, other: &Self, tol: f64) -> Result<Self>;
+}
+
+impl BooleanOperation for Solid {
+    #[inline(always)]
+    fn union(&self, other: &Solid, tol: f64) -> Result<Solid> {
+        boolean(self, other, Operation::Union, tol)
+    }
+    #[inline(always)]
+    fn intersection(&self, other: &Solid, tol: f64) -> Result<Solid> {
+        boolean(self, other, Operation::Intersection, tol)
+    }
+    #[inline(always)]
+    fn subtract(&self, other: &Solid, tol: f64) -> Result<Solid> {
+        boolean(self, other, Operation::Difference, tol)
+    }
+}
+
+/// The kinds of the boolean operations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Operation {
+    /// the union of the solids
+    Union,
+    /// the intersection of the solids
+    Intersection,
+    /// the difference of the second solid from the first one
+    Difference,
+}
+
+impl Operation {
+    // whether the region of a face of the first or the second operand is kept
+    fn keep(self, first: bool, inside: bool) -> bool {
+        match (self, first) {
+            (Operation::Union, _) => !inside,
+            (Operation::Intersection, _) => inside,
+            (Operation::Difference, true) => !inside,
+            (Operation::Difference, false) => inside,
+        }
+    }
+}
+
+// the edges to be split, with the parameters and the vertices of the splits
+type EdgeSplits = HashMap<EdgeID, (Edge, Vec<(f64, Vertex)>)>;
+
+// a face of the operands with its approximations
+struct FaceData {
+    face: Face,
+    first: bool,
+    domain: FaceDomain,
+    mesh: PolygonMesh,
+    bdd_box: BoundingBox<Point3>,
+}
+
+impl FaceData {
+    fn new(face: &Face, first: bool, tol: f64) -> FaceData {
+        let mesh = tessellate_face(face, tol);
+        let bdd_box = mesh.bounding_box();
+        FaceData {
+            face: face.clone(),
+            first,
+            domain: FaceDomain::new(face, tol),
+            mesh,
+            bdd_box,
+        }
+    }
+
+    fn triangles(&self) -> Vec<[Point3; 3]> {
+        let positions = self.mesh.positions();
+        self.mesh
+            .tri_faces()
+            .iter()
+            .map(|tri| {
+                [
+                    positions[tri[0].pos],
+                    positions[tri[1].pos],
+                    positions[tri[2].pos],
+                ]
+            })
+            .collect()
+    }
+
+    // the parameter of the foot of `pt` on the surface
+    fn parameter(&self, pt: Point3, hint: Option<(f64, f64)>) -> (f64, f64) {
+        let hint = hint.unwrap_or_else(|| self.domain.presearch(pt));
+        self.domain.search_parameter(pt, hint)
+    }
+}
+
+#[inline(always)]
+fn boolean(solid0: &Solid, solid1: &Solid, operation: Operation, tol: f64) -> Result<Solid> {
+    boolean_with_dump(solid0, solid1, operation, tol).map_err(|failure| failure.error)
+}
+
+/// Executes the boolean operation, and returns the dump of the failure with the error if
+/// the operation fails.
+///
+/// The result is the same as the one of [`BooleanOperation`]. The dump has only the pairs of
+/// the faces causing the failure with the intersection curves traced until the failure, so it
+/// is written to the artifact file of the bug report without the whole models.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::boolean::*;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube0 = builder::tsweep(&f, Vector3::unit_z());
+/// let cube1 = builder::translated(&cube0, Vector3::new(0.5, 0.5, 0.5));
+///
+/// match boolean_with_dump(&cube0, &cube1, Operation::Union, 0.01) {
+///     Ok(union) => assert_eq!(union.boundaries().len(), 1),
+///     Err(failure) => {
+///         let file = std::fs::File::create("boolean-failure.json").unwrap();
+///         failure.dump.write(file).unwrap();
+///         panic!("{}", failure.error);
+///     }
+/// }
+/// ```
+///
+/// [`BooleanOperation`]: ./trait.BooleanOperation.html
+pub fn boolean_with_dump(
+    solid0: &Solid,
+    solid1: &Solid,
+    operation: Operation,
+    tol: f64,
+) -> std::result::Result<Solid, BooleanFailure> {
+    try_boolean(solid0, solid1, operation, tol, &mut IntersectionCache::new())
+}
+
+/// Executes the boolean operation, and registers the new edges along the intersection curves
+/// to `cache` as the intersections of the surfaces of the pairs of the faces.
+///
+/// The result is the same as the one of [`BooleanOperation`]. The new edges are the polylines
+/// within `tol`, which are re-evaluated by the cache later, e.g. by
+/// [`IntersectionCache::refine_all`] with a tighter tolerance.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::boolean::*;
+/// use truck_modeling::intersection::IntersectionCache;
+/// use std::f64::consts::PI;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// // a cylinder drilling through the cube
+/// let v = builder::vertex(Point3::new(0.75, 0.5, -0.5));
+/// let center = Point3::new(0.5, 0.5, -0.5);
+/// let circle = builder::rsweep(&v, center, Vector3::unit_z(), Rad(2.0 * PI));
+/// let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+/// let cylinder = builder::tsweep(&disk, Vector3::new(0.0, 0.0, 2.0));
+///
+/// let mut cache = IntersectionCache::new();
+/// let operation = Operation::Difference;
+/// let drilled = boolean_with_cache(&cube, &cylinder, operation, 0.05, &mut cache).unwrap();
+/// assert!(!cache.is_empty());
+///
+/// // the edges of the hole are re-evaluated on the cylinder
+/// let edges: Vec<Edge> = drilled.boundaries()[0]
+///     .iter()
+///     .flat_map(|face| face.boundaries().into_iter().flatten())
+///     .collect();
+/// assert_eq!(cache.refine_all(&edges, 1.0e-4).unwrap(), cache.len());
+/// ```
+///
+/// [`BooleanOperation`]: ./trait.BooleanOperation.html
+/// [`IntersectionCache::refine_all`]: ../intersection/struct.IntersectionCache.html#method.refine_all
+pub fn boolean_with_cache(
+    solid0: &Solid,
+    solid1: &Solid,
+    operation: Operation,
+    tol: f64,
+    cache: &mut IntersectionCache,
+) -> Result<Solid> {
+    try_boolean(solid0, solid1, operation, tol, cache).map_err(|failure| failure.error)
+}
+
+fn try_boolean(
+    solid0: &Solid,
+    solid1: &Solid,
+    operation: Operation,
+    tol: f64,
+    cache: &mut IntersectionCache,
+) -> std::result::Result<Solid, BooleanFailure> {
+    let faces: Vec<FaceData> = solid0
+        .boundaries()
+        .iter()
+        .flatten()
+        .map(|face| FaceData::new(face, true, tol))
+        .chain(
+            solid1
+                .boundaries()
+                .iter()
+                .flatten()
+                .map(|face| FaceData::new(face, false, tol)),
+        )
+        .collect();
+    let mut curves = HashMap::new();
+    boolean_faces(&faces, operation, tol, &mut curves, cache).map_err(|(error, culprit)| {
+        let pairs = faces_pairs(&curves, culprit)
+            .into_iter()
+            .map(|(i, j)| {
+                FacePairDump::new(&faces[i].face, &faces[j].face, curves[&(i, j)].clone())
+            })
+            .collect();
+        let dump = FailureDump {
+            operation,
+            tolerance: tol,
+            base_tolerance: TOLERANCE,
+            error: error.to_string(),
+            pairs,
+        };
+        BooleanFailure { error, dump }
+    })
+}
+
+// the faces causing the failure of the boolean operation
+#[derive(Clone, Copy, Debug)]
+enum Culprit {
+    Pair(usize, usize),
+    Face(usize),
+    All,
+}
+
+// the sorted pairs of the faces concerned with the culprit
+fn faces_pairs(
+    curves: &HashMap<(usize, usize), Vec<Vec<Point3>>>,
+    culprit: Culprit,
+) -> Vec<(usize, usize)> {
+    let mut pairs: Vec<(usize, usize)> = curves
+        .keys()
+        .copied()
+        .filter(|(i, j)| match culprit {
+            Culprit::Pair(a, b) => (*i, *j) == (a, b),
+            Culprit::Face(k) => *i == k || *j == k,
+            Culprit::All => true,
+        })
+        .collect();
+    pairs.sort();
+    pairs
+}
+
+// The boolean operation of the faces of the operands. The traced intersection curves are
+// recorded in `curves` for the dumps of the failures, and the new edges along them are
+// registered to `cache`.
+fn boolean_faces(
+    faces: &[FaceData],
+    operation: Operation,
+    tol: f64,
+    curves: &mut HashMap<(usize, usize), Vec<Vec<Point3>>>,
+    cache: &mut IntersectionCache,
+) -> std::result::Result<Solid, (Error, Culprit)> {
+    let triangles: Vec<Vec<[Point3; 3]>> = faces.iter().map(FaceData::triangles).collect();
+
+    // the segments of the intersections of the tessellations, sorted by the pairs of faces
+    let mut welder = PointWelder::new(tol);
+    let mut pair_segments = HashMap::<(usize, usize), Vec<(usize, usize)>>::new();
+    for i in (0..faces.len()).filter(|i| faces[*i].first) {
+        for j in (0..faces.len()).filter(|j| !faces[*j].first) {
+            if !boxes_overlap(&faces[i].bdd_box, &faces[j].bdd_box, tol) {
+                continue;
+            }
+            let mut segments = HashSet::new();
+            for tri0 in &triangles[i] {
+                for tri1 in &triangles[j] {
+                    if let Some((p, q)) = triangle_intersection(tri0, tri1) {
+                        let (a, b) = (welder.index(p), welder.index(q));
+                        if a != b {
+                            segments.insert((usize::min(a, b), usize::max(a, b)));
+                        }
+                    }
+                }
+            }
+            if !segments.is_empty() {
+                let mut segments: Vec<_> = segments.into_iter().collect();
+                segments.sort();
+                pair_segments.insert((i, j), segments);
+            }
+        }
+    }
+
+    // the vertices at the ends of the intersection curves and the splits of the edges
+    let mut pairs: Vec<(usize, usize)> = pair_segments.keys().copied().collect();
+    pairs.sort();
+    let mut end_vertices = HashMap::<usize, Vertex>::new();
+    let mut splits = EdgeSplits::new();
+    let mut cuts = HashMap::<usize, Vec<Edge>>::new();
+    for (i, j) in pairs {
+        let parents = (&faces[i].domain.surface, &faces[j].domain.surface);
+        for chain in chain_segments(&pair_segments[&(i, j)]) {
+            let closed = chain.len() > 2 && chain[0] == chain[chain.len() - 1];
+            let mut points: Vec<Point3> = chain
+                .iter()
+                .map(|idx| refine_on_surfaces(welder.points[*idx], &faces[i], &faces[j]))
+                .collect();
+            curves
+                .entry((i, j))
+                .or_default()
+                .push(points.clone());
+            let edges = if closed {
+                points.pop();
+                if points.len() < 3 {
+                    continue;
+                }
+                let mid = points.len() / 2;
+                let v0 = builder::vertex(points[0]);
+                let v1 = builder::vertex(points[mid]);
+                let mut latter = points[mid..].to_vec();
+                latter.push(points[0]);
+                vec![
+                    polyline_edge(&v0, &v1, &points[..=mid], parents, cache),
+                    polyline_edge(&v1, &v0, &latter, parents, cache),
+                ]
+            } else {
+                let mut ends = Vec::new();
+                for idx in [chain[0], chain[chain.len() - 1]].iter() {
+                    if !end_vertices.contains_key(idx) {
+                        let pt = welder.points[*idx];
+                        let vertex = end_vertex(pt, &faces[i], &faces[j], &mut splits, tol)
+                            .map_err(|error| (error, Culprit::Pair(i, j)))?;
+                        end_vertices.insert(*idx, vertex);
+                    }
+                    ends.push(end_vertices[idx].clone());
+                }
+                if ends[0] == ends[1] {
+                    continue;
+                }
+                let len = points.len();
+                points[0] = *ends[0].lock_point().unwrap();
+                points[len - 1] = *ends[1].lock_point().unwrap();
+                vec![polyline_edge(&ends[0], &ends[1], &points, parents, cache)]
+            };
+            cuts.entry(i).or_default().extend(edges.clone());
+            cuts.entry(j).or_default().extend(edges);
+        }
+    }
+    let pieces: HashMap<EdgeID, Vec<Edge>> = splits
+        .into_iter()
+        .map(|(id, (edge, splits))| (id, split_edge(&edge, splits)))
+        .collect();
+
+    // the regions of the faces, classified by the other solid
+    let other_triangles = |first: bool| -> Vec<[Point3; 3]> {
+        faces
+            .iter()
+            .zip(&triangles)
+            .filter(|(data, _)| data.first != first)
+            .flat_map(|(_, triangles)| triangles.clone())
+            .collect()
+    };
+    let (others0, others1) = (other_triangles(true), other_triangles(false));
+    let mut kept = Vec::new();
+    for (idx, data) in faces.iter().enumerate() {
+        let face_cuts = cuts.get(&idx).cloned().unwrap_or_default();
+        let boundaries: Vec<Vec<Edge>> = data
+            .face
+            .boundaries()
+            .iter()
+            .map(|wire| {
+                wire.iter()
+                    .flat_map(|edge| replaced_edges(edge, &pieces))
+                    .collect()
+            })
+            .collect();
+        let untouched = face_cuts.is_empty()
+            && data
+                .face
+                .boundaries()
+                .iter()
+                .flatten()
+                .all(|edge| !pieces.contains_key(&edge.id()));
+        let others = match data.first {
+            true => &others0,
+            false => &others1,
+        };
+        let culprit = |error| (error, Culprit::Face(idx));
+        for region in face_regions(data, &boundaries, &face_cuts, tol).map_err(culprit)? {
+            let (u, v) = region.sample_point(&data.mesh).map_err(culprit)?;
+            let inside = winding_number(others, data.domain.surface.subs(u, v)) > 0.5;
+            if !operation.keep(data.first, inside) {
+                continue;
+            }
+            let face = match untouched {
+                true => data.face.clone(),
+                false => {
+                    let wires = region
+                        .loops
+                        .into_iter()
+                        .map(|(edges, _)| edges.into_iter().collect())
+                        .collect();
+                    Face::try_new(wires, data.domain.surface.clone())
+                        .map_err(|_| culprit(Error::InvalidIntersection))?
+                }
+            };
+            match (operation, data.first) {
+                (Operation::Difference, false) => kept.push(face.inverse()),
+                _ => kept.push(face),
+            }
+        }
+    }
+    let shell: Shell = kept.into_iter().collect();
+    Solid::try_new(shell.connected_components()).map_err(|error| (error.into(), Culprit::All))
+}
+
+/// The failure of the boolean operation, returned by
+/// [`boolean_with_dump`](./fn.boolean_with_dump.html).
+#[derive(Debug)]
+pub struct BooleanFailure {
+    /// the error of the operation
+    pub error: Error,
+    /// the dump of the failure
+    pub dump: FailureDump,
+}
+
+/// The reproducible dump of the failure of the boolean operation: the pairs of the faces causing
+/// the failure, the intersection curves traced until the failure, and the tolerances.
+///
+/// The dump is written to the JSON artifact file by [`write`](#method.write), loaded by
+/// [`read`](#method.read), and replayed by [`replay`](#method.replay).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FailureDump {
+    /// the kind of the boolean operation
+    pub operation: Operation,
+    /// the tolerance given to the boolean operation
+    pub tolerance: f64,
+    /// the tolerance `TOLERANCE` of the geometric comparisons of the build
+    pub base_tolerance: f64,
+    /// the message of the error
+    pub error: String,
+    /// the pairs of the faces of the first and the second operands causing the failure
+    pub pairs: Vec<FacePairDump>,
+}
+
+/// The pair of the faces in [`FailureDump`](./struct.FailureDump.html).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FacePairDump {
+    /// the face of the first operand, compressed as the shell with the only one face
+    pub face0: CompressedShell,
+    /// the face of the second operand, compressed as the shell with the only one face
+    pub face1: CompressedShell,
+    /// the polylines of the partial intersection curves of the faces
+    pub curves: Vec<Vec<Point3>>,
+}
+
+impl FacePairDump {
+    /// Creates the dump of the pair of the faces and their intersection curves.
+    #[inline(always)]
+    pub fn new(face0: &Face, face1: &Face, curves: Vec<Vec<Point3>>) -> FacePairDump {
+        FacePairDump {
+            face0: Shell::from(vec![face0.clone()]).compress(),
+            face1: Shell::from(vec![face1.clone()]).compress(),
+            curves,
+        }
+    }
+
+    /// Extracts the faces.
+    pub fn faces(&self) -> Result<(Face, Face)> {
+        let extract = |cshell: &CompressedShell| -> Result<Face> {
+            Shell::extract(cshell.clone())?
+                .face_iter()
+                .next()
+                .cloned()
+                .ok_or(Error::ElementNotFound)
+        };
+        Ok((extract(&self.face0)?, extract(&self.face1)?))
+    }
+
+    /// Traces the intersection curves of the faces again with the tolerance `tol`.
+    ///
+    /// cf. [`intersection_curves`](./fn.intersection_curves.html)
+    #[inline(always)]
+    pub fn replay(&self, tol: f64) -> Result<Vec<Vec<Point3>>> {
+        let (face0, face1) = self.faces()?;
+        intersection_curves(&face0, &face1, tol)
+    }
+}
+
+impl FailureDump {
+    /// Writes the dump in JSON.
+    pub fn write<W: Write>(&self, writer: W) -> std::io::Result<()> {
+        serde_json::to_writer_pretty(writer, self).map_err(std::io::Error::other)
+    }
+
+    /// Reads the dump written by [`write`](#method.write).
+    pub fn read<R: Read>(reader: R) -> std::io::Result<FailureDump> {
+        serde_json::from_reader(reader)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))
+    }
+
+    /// Traces the intersection curves of all the pairs of the faces again with the tolerance of
+    /// the dump. Returns the first error if the tracing of a pair fails.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::boolean::*;
+    /// // the unit square on the xy-plane
+    /// let v = builder::vertex(Point3::origin());
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let face0 = builder::tsweep(&e, Vector3::unit_y());
+    /// // the square on the plane x = 0.5 crossing `face0`
+    /// let v = builder::vertex(Point3::new(0.5, -0.5, -0.5));
+    /// let e = builder::tsweep(&v, Vector3::new(0.0, 2.0, 0.0));
+    /// let face1 = builder::tsweep(&e, Vector3::unit_z());
+    ///
+    /// let dump = FailureDump {
+    ///     operation: Operation::Union,
+    ///     tolerance: 0.01,
+    ///     base_tolerance: TOLERANCE,
+    ///     error: errors::Error::InvalidIntersection.to_string(),
+    ///     pairs: vec![FacePairDump::new(&face0, &face1, Vec::new())],
+    /// };
+    /// let mut file = Vec::<u8>::new();
+    /// dump.write(&mut file).unwrap();
+    ///
+    /// let dump = FailureDump::read(file.as_slice()).unwrap();
+    /// assert_eq!(dump.operation, Operation::Union);
+    /// let curves = dump.replay().unwrap();
+    /// assert_eq!(curves[0].len(), 1);
+    /// let curve = &curves[0][0];
+    /// assert!(curve.iter().all(|pt| pt[0].near(&0.5) && pt[2].so_small()));
+    /// ```
+    pub fn replay(&self) -> Result<Vec<Vec<Vec<Point3>>>> {
+        self.pairs
+            .iter()
+            .map(|pair| pair.replay(self.tolerance))
+            .collect()
+    }
+}
+
+/// Traces the intersection curves of the faces in the same way as the boolean operations, and
+/// returns the polylines of them. The closed polylines end with the first point.
+/// # Errors
+/// Returns `Error::InvalidIntersection` if an open polyline does not end on the boundaries of
+/// the faces.
+pub fn intersection_curves(face0: &Face, face1: &Face, tol: f64) -> Result<Vec<Vec<Point3>>> {
+    let (data0, data1) = (
+        FaceData::new(face0, true, tol),
+        FaceData::new(face1, false, tol),
+    );
+    if !boxes_overlap(&data0.bdd_box, &data1.bdd_box, tol) {
+        return Ok(Vec::new());
+    }
+    let mut welder = PointWelder::new(tol);
+    let mut segments = HashSet::new();
+    for tri0 in &data0.triangles() {
+        for tri1 in &data1.triangles() {
+            if let Some((p, q)) = triangle_intersection(tri0, tri1) {
+                let (a, b) = (welder.index(p), welder.index(q));
+                if a != b {
+                    segments.insert((usize::min(a, b), usize::max(a, b)));
+                }
+            }
+        }
+    }
+    let mut segments: Vec<_> = segments.into_iter().collect();
+    segments.sort();
+    let mut splits = HashMap::new();
+    chain_segments(&segments)
+        .into_iter()
+        .map(|chain| {
+            let mut points: Vec<Point3> = chain
+                .iter()
+                .map(|idx| refine_on_surfaces(welder.points[*idx], &data0, &data1))
+                .collect();
+            let closed = chain.len() > 2 && chain[0] == chain[chain.len() - 1];
+            if !closed {
+                let len = points.len();
+                for idx in [0, len - 1].iter() {
+                    let vertex = end_vertex(points[*idx], &data0, &data1, &mut splits, tol)?;
+                    points[*idx] = *vertex.lock_point().unwrap();
+                }
+            }
+            Ok(points)
+        })
+        .collect()
+}
+
+#[inline(always)]
+fn boxes_overlap(box0: &BoundingBox<Point3>, box1: &BoundingBox<Point3>, tol: f64) -> bool {
+    !box0.is_empty()
+        && !box1.is_empty()
+        && (0..3)
+            .all(|k| box0.min()[k] <= box1.max()[k] + tol && box1.min()[k] <= box0.max()[k] + tol)
+}
+
+// the points welded within the tolerance
+struct PointWelder {
+    points: Vec<Point3>,
+    grid: HashMap<[i64; 3], Vec<usize>>,
+    tol: f64,
+}
+
+impl PointWelder {
+    fn new(tol: f64) -> PointWelder {
+        PointWelder {
+            points: Vec::new(),
+            grid: HashMap::new(),
+            tol: f64::max(tol * 1.0e-3, TOLERANCE),
+        }
+    }
+
+    fn index(&mut self, pt: Point3) -> usize {
+        let key = [
+            (pt[0] / self.tol).floor() as i64,
+            (pt[1] / self.tol).floor() as i64,
+            (pt[2] / self.tol).floor() as i64,
+        ];
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let neighbor = [key[0] + dx, key[1] + dy, key[2] + dz];
+                    if let Some(indices) = self.grid.get(&neighbor) {
+                        if let Some(idx) = indices
+                            .iter()
+                            .find(|idx| self.points[**idx].distance(pt) < self.tol)
+                        {
+                            return *idx;
+                        }
+                    }
+                }
+            }
+        }
+        self.points.push(pt);
+        self.grid
+            .entry(key)
+            .or_default()
+            .push(self.points.len() - 1);
+        self.points.len() - 1
+    }
+}
+
+// the segment of the intersection of two triangles
+fn triangle_intersection(tri0: &[Point3; 3], tri1: &[Point3; 3]) -> Option<(Point3, Point3)> {
+    let seg0 = plane_crossing(tri0, tri1)?;
+    let seg1 = plane_crossing(tri1, tri0)?;
+    let dir = (tri0[1] - tri0[0])
+        .cross(tri0[2] - tri0[0])
+        .cross((tri1[1] - tri1[0]).cross(tri1[2] - tri1[0]));
+    let sorted = |(p, q): (Point3, Point3)| match p.to_vec().dot(dir) < q.to_vec().dot(dir) {
+        true => (p, q),
+        false => (q, p),
+    };
+    let (seg0, seg1) = (sorted(seg0), sorted(seg1));
+    let param = |p: Point3| p.to_vec().dot(dir);
+    let start = match param(seg0.0) > param(seg1.0) {
+        true => seg0.0,
+        false => seg1.0,
+    };
+    let end = match param(seg0.1) < param(seg1.1) {
+        true => seg0.1,
+        false => seg1.1,
+    };
+    match param(start) < param(end) {
+        true => Some((start, end)),
+        false => None,
+    }
+}
+
+// the segment of the intersection of `tri` and the plane of `other`
+fn plane_crossing(tri: &[Point3; 3], other: &[Point3; 3]) -> Option<(Point3, Point3)> {
+    let normal = (other[1] - other[0]).cross(other[2] - other[0]);
+    if normal.so_small() {
+        return None;
+    }
+    let dist = [
+        (tri[0] - other[0]).dot(normal),
+        (tri[1] - other[0]).dot(normal),
+        (tri[2] - other[0]).dot(normal),
+    ];
+    if dist.iter().all(|d| *d > 0.0)
+        || dist.iter().all(|d| *d < 0.0)
+        || dist.iter().all(|d| *d == 0.0)
+    {
+        return None;
+    }
+    let mut points = Vec::new();
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        if dist[i] == 0.0 {
+            points.push(tri[i]);
+        } else if (dist[i] > 0.0 && dist[j] < 0.0) || (dist[i] < 0.0 && dist[j] > 0.0) {
+            points.push(tri[i] + (tri[j] - tri[i]) * (dist[i] / (dist[i] - dist[j])));
+        }
+    }
+    match points.len() {
+        2 => Some((points[0], points[1])),
+        _ => None,
+    }
+}
+
+// Chains the segments into the polylines of the indices of the points. The closed polylines
+// end with the first index.
+fn chain_segments(segments: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let mut adjacency = HashMap::<usize, Vec<usize>>::new();
+    segments.iter().enumerate().for_each(|(k, (a, b))| {
+        adjacency.entry(*a).or_default().push(k);
+        adjacency.entry(*b).or_default().push(k);
+    });
+    let mut nodes: Vec<usize> = adjacency.keys().copied().collect();
+    nodes.sort_by_key(|node| (adjacency[node].len() == 2, *node));
+    let mut used = vec![false; segments.len()];
+    let mut res = Vec::new();
+    for start in nodes {
+        while let Some(first) = adjacency[&start].iter().find(|k| !used[**k]) {
+            let mut chain = vec![start];
+            let (mut node, mut segment) = (start, *first);
+            loop {
+                used[segment] = true;
+                let (a, b) = segments[segment];
+                node = if a == node { b } else { a };
+                chain.push(node);
+                if adjacency[&node].len() != 2 {
+                    break;
+                }
+                match adjacency[&node].iter().find(|k| !used[**k]) {
+                    Some(k) => segment = *k,
+                    None => break,
+                }
+            }
+            res.push(chain);
+        }
+    }
+    res
+}
+
+// Refines the point onto the intersection of the surfaces by the alternating projections.
+fn refine_on_surfaces(pt: Point3, face0: &FaceData, face1: &FaceData) -> Point3 {
+    let (mut hint0, mut hint1) = (None, None);
+    let mut pt = pt;
+    for _ in 0..REFINEMENTS {
+        let uv0 = face0.parameter(pt, hint0);
+        let pt0 = face0.domain.surface.subs(uv0.0, uv0.1);
+        let uv1 = face1.parameter(pt0, hint1);
+        pt = face1.domain.surface.subs(uv1.0, uv1.1);
+        hint0 = Some(uv0);
+        hint1 = Some(uv1);
+        if pt0.near(&pt) {
+            break;
+        }
+    }
+    pt
+}
+
+// The vertex at the end of an intersection curve on the boundary of `face0` or `face1`.
+// The edge is registered to be split if the end is not a vertex.
+// `Option::is_none_or` is too new for the supported compilers.
+#[allow(clippy::unnecessary_map_or)]
+fn end_vertex(
+    pt: Point3,
+    face0: &FaceData,
+    face1: &FaceData,
+    splits: &mut EdgeSplits,
+    tol: f64,
+) -> Result<Vertex> {
+    let snap = 10.0 * tol;
+    // the nearest edge, the parameter on the absolute curve, and the face of the other solid
+    let mut nearest: Option<(f64, Edge, f64, &FaceData)> = None;
+    for (face, other) in [(face0, face1), (face1, face0)].iter() {
+        for edge in face.face.boundaries().iter().flatten() {
+            let curve = edge.lock_curve().unwrap().clone();
+            let hint = curve
+                .parameter_division(tol)
+                .into_iter()
+                .min_by(|s, t| {
+                    let (ds, dt) = (curve.subs(*s).distance2(pt), curve.subs(*t).distance2(pt));
+                    ds.partial_cmp(&dt).unwrap()
+                })
+                .unwrap();
+            let t = curve_search_nearest_parameter(&curve, pt, hint, TRIALS).unwrap_or(hint);
+            let (t0, t1) = curve.parameter_range();
+            let t = f64::min(f64::max(t, t0), t1);
+            let dist = curve.subs(t).distance(pt);
+            if dist < snap && nearest.as_ref().map_or(true, |(d, ..)| dist < *d) {
+                nearest = Some((dist, edge.clone(), t, *other));
+            }
+        }
+    }
+    let (_, edge, mut t, other) = nearest.ok_or(Error::InvalidIntersection)?;
+    // the intersection of the curve and the surface of the other face
+    let curve = edge.lock_curve().unwrap().clone();
+    let (t0, t1) = curve.parameter_range();
+    let mut hint = None;
+    for _ in 0..REFINEMENTS {
+        let uv = other.parameter(curve.subs(t), hint);
+        let pt = other.domain.surface.subs(uv.0, uv.1);
+        let next = curve_search_nearest_parameter(&curve, pt, t, TRIALS).unwrap_or(t);
+        let next = f64::min(f64::max(next, t0), t1);
+        hint = Some(uv);
+        if next.near(&t) {
+            break;
+        }
+        t = next;
+    }
+    let pt = curve.subs(t);
+    let (front, back) = edge.absolute_ends();
+    for vertex in [front, back].iter() {
+        if vertex.lock_point().unwrap().distance(pt) < tol {
+            return Ok((*vertex).clone());
+        }
+    }
+    let entry = splits
+        .entry(edge.id())
+        .or_insert_with(|| (edge.clone(), Vec::new()));
+    if let Some((_, vertex)) = entry
+        .1
+        .iter()
+        .find(|(_, vertex)| vertex.lock_point().unwrap().distance(pt) < tol)
+    {
+        return Ok(vertex.clone());
+    }
+    let vertex = builder::vertex(pt);
+    entry.1.push((t, vertex.clone()));
+    Ok(vertex)
+}
+
+// the edge of the polyline, registered to `cache` as the intersection of `parents`
+fn polyline_edge(
+    v0: &Vertex,
+    v1: &Vertex,
+    points: &[Point3],
+    parents: (&Surface, &Surface),
+    cache: &mut IntersectionCache,
+) -> Edge {
+    let mut points: Vec<Point3> = points.to_vec();
+    points.dedup_by(|p, q| p.near(q));
+    let lengths: Vec<f64> = points.windows(2).map(|p| p[0].distance(p[1])).collect();
+    let total: f64 = lengths.iter().sum();
+    let mut knots = vec![0.0, 0.0];
+    let mut sum = 0.0;
+    for len in &lengths[..lengths.len() - 1] {
+        sum += len;
+        knots.push(sum / total);
+    }
+    knots.extend(&[1.0, 1.0]);
+    let control_points = points.into_iter().map(|pt| pt.to_vec()).collect();
+    let curve = Curve::BSplineCurve(BSplineCurve::new(KnotVec::from(knots), control_points));
+    cache.create_edge(v0, v1, parents.0, parents.1, curve)
+}
+
+// the pieces of the absolute edge split at the parameters
+fn split_edge(edge: &Edge, mut splits: Vec<(f64, Vertex)>) -> Vec<Edge> {
+    splits.sort_by(|(s, _), (t, _)| s.partial_cmp(t).unwrap());
+    let mut curve = edge.lock_curve().unwrap().clone();
+    let (front, back) = edge.absolute_ends();
+    let mut front = front.clone();
+    let mut pieces = Vec::new();
+    for (t, vertex) in splits {
+        let latter = curve.cut(t);
+        pieces.push(Edge::new(&front, &vertex, curve));
+        curve = latter;
+        front = vertex;
+    }
+    pieces.push(Edge::new(&front, back, curve));
+    pieces
+}
+
+// the oriented edge replaced by the pieces if it is split
+fn replaced_edges(edge: &Edge, pieces: &HashMap<EdgeID, Vec<Edge>>) -> Vec<Edge> {
+    match pieces.get(&edge.id()) {
+        None => vec![edge.clone()],
+        Some(pieces) => match edge.orientation() {
+            true => pieces.clone(),
+            false => pieces.iter().rev().map(Edge::inverse).collect(),
+        },
+    }
+}
+
+// a region of a face: the outer loop and the holes with the polylines in the parameter space
+struct Region {
+    loops: Vec<(Vec<Edge>, Vec<Vector2>)>,
+}
+
+impl Region {
+    fn include(&self, uv: Vector2) -> bool {
+        self.loops
+            .iter()
+            .filter(|(_, polygon)| crossing_parity(polygon, uv))
+            .count()
+            % 2
+            == 1
+    }
+
+    // the distance from `uv` to the boundary of the region in the parameter space
+    fn distance(&self, uv: Vector2) -> f64 {
+        self.loops
+            .iter()
+            .flat_map(|(_, polygon)| {
+                let len = polygon.len();
+                (0..len).map(move |i| segment_distance(polygon[i], polygon[(i + 1) % len], uv))
+            })
+            .fold(f64::INFINITY, f64::min)
+    }
+
+    // the sample point in the region, far from the boundary
+    fn sample_point(&self, mesh: &PolygonMesh) -> Result<(f64, f64)> {
+        let uv_coords = mesh.uv_coords();
+        let centroids = mesh.tri_faces().iter().map(|tri| {
+            (uv_coords[tri[0].uv.unwrap()]
+                + uv_coords[tri[1].uv.unwrap()]
+                + uv_coords[tri[2].uv.unwrap()])
+                / 3.0
+        });
+        let bdd_box: BoundingBox<Vector2> = self.loops[0].1.iter().collect();
+        let (min, diag) = (*bdd_box.min(), bdd_box.diagonal());
+        let grid = (0..SAMPLE_DIVISION).flat_map(move |i| {
+            (0..SAMPLE_DIVISION).map(move |j| {
+                let p = (i as f64 + 0.5) / SAMPLE_DIVISION as f64;
+                let q = (j as f64 + 0.5) / SAMPLE_DIVISION as f64;
+                min + Vector2::new(diag[0] * p, diag[1] * q)
+            })
+        });
+        centroids
+            .chain(grid)
+            .filter(|uv| self.include(*uv))
+            .map(|uv| (self.distance(uv), uv))
+            .max_by(|(d0, _), (d1, _)| d0.partial_cmp(d1).unwrap())
+            .map(|(_, uv)| (uv[0], uv[1]))
+            .ok_or(Error::InvalidIntersection)
+    }
+}
+
+// Divides the face into the regions by tracing the boundaries and the cuts in both directions
+// in the parameter space, keeping the regions on the left sides.
+fn face_regions(
+    data: &FaceData,
+    boundaries: &[Vec<Edge>],
+    cuts: &[Edge],
+    tol: f64,
+) -> Result<Vec<Region>> {
+    let edges: Vec<Edge> = boundaries
+        .iter()
+        .flatten()
+        .cloned()
+        .chain(
+            cuts.iter()
+                .flat_map(|edge| vec![edge.clone(), edge.inverse()]),
+        )
+        .collect();
+    let mut vertex_uvs = HashMap::<VertexID, Vector2>::new();
+    let polylines: Vec<Vec<Vector2>> = edges
+        .iter()
+        .map(|edge| {
+            let curve = edge.oriented_curve();
+            let division = curve.parameter_division(tol);
+            let len = division.len();
+            let front = *vertex_uvs.entry(edge.front().id()).or_insert_with(|| {
+                let uv = data.parameter(*edge.front().lock_point().unwrap(), None);
+                Vector2::new(uv.0, uv.1)
+            });
+            let mut uvs = vec![front];
+            for t in &division[1..len - 1] {
+                let prev = uvs[uvs.len() - 1];
+                let uv = data.parameter(curve.subs(*t), Some((prev[0], prev[1])));
+                uvs.push(data.domain.nearest_branch(Vector2::new(uv.0, uv.1), prev));
+            }
+            let prev = uvs[uvs.len() - 1];
+            let back = *vertex_uvs.entry(edge.back().id()).or_insert_with(|| {
+                let uv =
+                    data.parameter(*edge.back().lock_point().unwrap(), Some((prev[0], prev[1])));
+                data.domain.nearest_branch(Vector2::new(uv.0, uv.1), prev)
+            });
+            uvs.push(back);
+            uvs
+        })
+        .collect();
+    let angle = |v: Vector2| f64::atan2(v[1], v[0]);
+    let out_angles: Vec<f64> = polylines.iter().map(|uvs| angle(uvs[1] - uvs[0])).collect();
+    let in_angles: Vec<f64> = polylines
+        .iter()
+        .map(|uvs| angle(uvs[uvs.len() - 2] - uvs[uvs.len() - 1]))
+        .collect();
+    let mut outgoing = HashMap::<VertexID, Vec<usize>>::new();
+    edges.iter().enumerate().for_each(|(i, edge)| {
+        outgoing
+            .entry(edge.front().id())
+            .or_default()
+            .push(i)
+    });
+
+    let mut used = vec![false; edges.len()];
+    let mut outers = Vec::new();
+    let mut holes = Vec::new();
+    for start in 0..edges.len() {
+        if used[start] {
+            continue;
+        }
+        let mut cycle = Vec::new();
+        let mut current = start;
+        loop {
+            used[current] = true;
+            cycle.push(current);
+            let edge = &edges[current];
+            // the first outgoing edge clockwise from the backward direction
+            let next = outgoing[&edge.back().id()]
+                .iter()
+                .filter(|j| edges[**j].id() != edge.id() || edges[**j].front() != edge.back())
+                .min_by(|j, k| {
+                    let clockwise =
+                        |l: usize| (in_angles[current] - out_angles[l]).rem_euclid(2.0 * PI);
+                    let (a, b) = (clockwise(**j), clockwise(**k));
+                    let a = if a.so_small() { 2.0 * PI } else { a };
+                    let b = if b.so_small() { 2.0 * PI } else { b };
+                    a.partial_cmp(&b).unwrap()
+                })
+                .ok_or(Error::InvalidIntersection)?;
+            if *next == start {
+                break;
+            } else if used[*next] {
+                return Err(Error::InvalidIntersection);
+            }
+            current = *next;
+        }
+        let polygon: Vec<Vector2> = cycle
+            .iter()
+            .flat_map(|i| polylines[*i][..polylines[*i].len() - 1].to_vec())
+            .collect();
+        let loop_edges = cycle.iter().map(|i| edges[*i].clone()).collect();
+        match signed_area(&polygon) > 0.0 {
+            true => outers.push(Region {
+                loops: vec![(loop_edges, polygon)],
+            }),
+            false => holes.push((loop_edges, polygon)),
+        }
+    }
+    for hole in holes {
+        // The points of the hole are on the boundary of the region inside the hole, which has
+        // the same area as the hole and is excluded.
+        let hole_area = -signed_area(&hole.1);
+        let outer = outers
+            .iter_mut()
+            .filter(|region| signed_area(&region.loops[0].1) > hole_area * (1.0 + 1.0e-6))
+            .filter(|region| region.include(hole.1[0]))
+            .min_by(|r0, r1| {
+                let (a0, a1) = (signed_area(&r0.loops[0].1), signed_area(&r1.loops[0].1));
+                a0.partial_cmp(&a1).unwrap()
+            })
+            .ok_or(Error::InvalidIntersection)?;
+        outer.loops.push(hole);
+    }
+    Ok(outers)
+}
+
+fn signed_area(polygon: &[Vector2]) -> f64 {
+    let len = polygon.len();
+    (0..len).fold(0.0, |sum, i| {
+        let (p, q) = (polygon[i], polygon[(i + 1) % len]);
+        sum + (p[0] * q[1] - p[1] * q[0]) / 2.0
+    })
+}
+
+// whether the half line from `pt` to the +u direction crosses the polygon odd times
+fn crossing_parity(polygon: &[Vector2], pt: Vector2) -> bool {
+    let len = polygon.len();
+    (0..len).fold(false, |res, i| {
+        let (p, q) = (polygon[i], polygon[(i + 1) % len]);
+        if (p[1] > pt[1]) != (q[1] > pt[1]) {
+            let u = p[0] + (pt[1] - p[1]) / (q[1] - p[1]) * (q[0] - p[0]);
+            if u > pt[0] {
+                return !res;
+            }
+        }
+        res
+    })
+}
+
+fn segment_distance(p: Vector2, q: Vector2, pt: Vector2) -> f64 {
+    let dir = q - p;
+    let len2 = dir.magnitude2();
+    let t = match len2.so_small2() {
+        true => 0.0,
+        false => ((pt - p).dot(dir) / len2).clamp(0.0, 1.0),
+    };
+    (p + dir * t - pt).magnitude()
+}
+
+// the generalized winding number of the triangles around `pt`, 1 inside and 0 outside
+fn winding_number(triangles: &[[Point3; 3]], pt: Point3) -> f64 {
+    triangles
+        .iter()
+        .map(|tri| {
+            let (a, b, c) = (tri[0] - pt, tri[1] - pt, tri[2] - pt);
+            let (la, lb, lc) = (a.magnitude(), b.magnitude(), c.magnitude());
+            let det = a.dot(b.cross(c));
+            let div = la * lb * lc + a.dot(b) * lc + b.dot(c) * la + c.dot(a) * lb;
+            2.0 * f64::atan2(det, div)
+        })
+        .sum::<f64>()
+        / (4.0 * PI)
+}
+
+#[test]
+fn refine_intersection_edges() {
+    let v = builder::vertex(Point3::origin());
+    let e = builder::tsweep(&v, Vector3::unit_x());
+    let f = builder::tsweep(&e, Vector3::unit_y());
+    let cube = builder::tsweep(&f, Vector3::unit_z());
+    let v = builder::vertex(Point3::new(0.75, 0.5, -0.5));
+    let center = Point3::new(0.5, 0.5, -0.5);
+    let circle = builder::rsweep(&v, center, Vector3::unit_z(), Rad(2.0 * PI));
+    let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+    let cylinder = builder::tsweep(&disk, Vector3::new(0.0, 0.0, 2.0));
+
+    let mut cache = IntersectionCache::new();
+    let operation = Operation::Difference;
+    let drilled = boolean_with_cache(&cube, &cylinder, operation, 0.05, &mut cache).unwrap();
+    let edges: Vec<Edge> = drilled.boundaries()[0]
+        .iter()
+        .flat_map(|face| face.boundaries().into_iter().flatten())
+        .collect();
+    let traced: Vec<&Edge> = edges
+        .iter()
+        .filter(|edge| cache.record(edge).is_some())
+        .collect();
+    assert!(!traced.is_empty());
+
+    let refined = cache.refine_all(edges.iter(), 1.0e-4).unwrap();
+    assert_eq!(refined, cache.len());
+    for edge in traced {
+        let record = cache.oriented_record(edge).unwrap();
+        let (curve, leader) = (edge.oriented_curve(), record.leader());
+        let front = *edge.front().lock_point().unwrap();
+        assert!(curve.subs(curve.parameter_range().0).near(&front));
+        assert!(leader.subs(leader.parameter_range().0).near(&front));
+        let (t0, t1) = curve.parameter_range();
+        for i in 0..=10 {
+            let pt = curve.subs(t0 + (t1 - t0) * i as f64 / 10.0);
+            let radius = pt.distance(Point3::new(0.5, 0.5, pt.z));
+            assert!(f64::abs(radius - 0.25) < 1.0e-3, "{}", radius);
+            assert!(pt.z.so_small() || (pt.z - 1.0).so_small(), "{:?}", pt);
+        }
+    }
+}
@@ -101,6 +101,140 @@ pub fn bezier(vertex0: &Vertex, vertex1: &Vertex, mut inter_points: Vec<Point3>)
     Edge::new(vertex0, vertex1, Curve::NURBSCurve(NURBSCurve::new(curve)))
 }
 
+/// Returns the rectangle with the rounded corners on the work plane `plane`.
+///
+/// The points on the work plane are given by the coordinates with respect to the orthonormal
+/// frame of `plane`: the origin of `plane`, the normalized u-axis and the unit vector
+/// perpendicular to both the u-axis and the normal. The rectangle is centered at `center`, and
+/// the width is along the u-axis. The corners are the exact circle arcs with `radius`, and the
+/// rectangle has no arcs if `radius` is zero. The wire is counter-clockwise with respect to the
+/// normal of `plane`.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use std::f64::consts::PI;
+/// let plane = Plane::new(Point3::origin(), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0));
+/// let wire = builder::rounded_rectangle(&plane, Point2::new(1.0, 1.0), 4.0, 2.0, 0.5).unwrap();
+/// assert_eq!(wire.len(), 8);
+/// assert!(wire.is_closed());
+///
+/// let face = builder::try_attach_plane(&vec![wire]).unwrap();
+/// let solid = builder::tsweep(&face, Vector3::unit_z());
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert!((volume - (8.0 - (4.0 - PI) * 0.25)).abs() < 1.0e-3);
+///
+/// // the sharp rectangle
+/// let wire = builder::rounded_rectangle(&plane, Point2::new(1.0, 1.0), 4.0, 2.0, 0.0).unwrap();
+/// assert_eq!(wire.len(), 4);
+/// // the radius is too large
+/// assert!(builder::rounded_rectangle(&plane, Point2::new(1.0, 1.0), 4.0, 2.0, 1.5).is_err());
+/// ```
+/// # Failures
+/// If the width or the height is not positive, or `radius` is negative or larger than the half
+/// of the width or the height, returns
+/// [`Error::InvalidProfile`](../errors/enum.Error.html#variant.InvalidProfile).
+#[inline(always)]
+pub fn rounded_rectangle(
+    plane: &Plane,
+    center: Point2,
+    width: f64,
+    height: f64,
+    radius: f64,
+) -> Result<Wire> {
+    profile::rounded_rectangle(plane, center, width, height, radius)
+}
+
+/// Returns the slot, the stadium shape, on the work plane `plane`.
+///
+/// The slot consists of the two lines and the two exact semicircles with `radius` around
+/// `center0` and `center1`. The coordinates on the work plane and the orientation are the ones
+/// of [`rounded_rectangle`](./fn.rounded_rectangle.html).
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use std::f64::consts::PI;
+/// // the work plane is the yz-plane.
+/// let plane = Plane::new(Point3::origin(), Point3::new(0.0, 1.0, 0.0), Point3::new(0.0, 0.0, 1.0));
+/// let wire = builder::slot(&plane, Point2::new(0.0, 0.0), Point2::new(2.0, 0.0), 1.0).unwrap();
+/// assert_eq!(wire.len(), 4);
+/// for vertex in wire.vertex_iter() {
+///     assert!(vertex.lock_point().unwrap().x.so_small());
+/// }
+///
+/// let face = builder::try_attach_plane(&vec![wire]).unwrap();
+/// let solid = builder::tsweep(&face, Vector3::unit_x());
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert!((volume - (4.0 + PI)).abs() < 1.0e-3);
+/// ```
+/// # Failures
+/// If `radius` is not positive or the centers coincide, returns
+/// [`Error::InvalidProfile`](../errors/enum.Error.html#variant.InvalidProfile).
+#[inline(always)]
+pub fn slot(plane: &Plane, center0: Point2, center1: Point2, radius: f64) -> Result<Wire> {
+    profile::slot(plane, center0, center1, radius)
+}
+
+/// Returns the regular polygon with `sides` vertices on the circle with `radius` around `center`
+/// on the work plane `plane`.
+///
+/// The first vertex is in the direction of the u-axis from `center`. The coordinates on the work
+/// plane and the orientation are the ones of [`rounded_rectangle`](./fn.rounded_rectangle.html).
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let plane = Plane::new(Point3::origin(), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0));
+/// let hexagon = builder::regular_polygon(&plane, Point2::origin(), 1.0, 6).unwrap();
+/// assert_eq!(hexagon.len(), 6);
+/// assert_near!(*hexagon[0].front().lock_point().unwrap(), Point3::new(1.0, 0.0, 0.0));
+///
+/// let face = builder::try_attach_plane(&vec![hexagon]).unwrap();
+/// let solid = builder::tsweep(&face, Vector3::unit_z());
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert!((volume - 1.5 * f64::sqrt(3.0)).abs() < 1.0e-3);
+/// ```
+/// # Failures
+/// If `radius` is not positive or `sides` is less than three, returns
+/// [`Error::InvalidProfile`](../errors/enum.Error.html#variant.InvalidProfile).
+#[inline(always)]
+pub fn regular_polygon(plane: &Plane, center: Point2, radius: f64, sides: usize) -> Result<Wire> {
+    profile::regular_polygon(plane, center, radius, sides)
+}
+
+/// Returns the ellipse around `center` on the work plane `plane`, whose semi-axes along the
+/// u-axis and the perpendicular one are `semi_axis0` and `semi_axis1` respectively.
+///
+/// The ellipse consists of the four exact elliptic arcs, the rational quadratic curves.
+/// The coordinates on the work plane and the orientation are the ones of
+/// [`rounded_rectangle`](./fn.rounded_rectangle.html).
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use std::f64::consts::PI;
+/// let plane = Plane::new(Point3::origin(), Point3::new(1.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0));
+/// let ellipse = builder::ellipse(&plane, Point2::origin(), 2.0, 1.0).unwrap();
+/// assert_eq!(ellipse.len(), 4);
+/// for edge in &ellipse {
+///     let curve = edge.oriented_curve();
+///     for i in 0..=10 {
+///         let (t0, t1) = curve.parameter_range();
+///         let p = curve.subs(t0 + (t1 - t0) * i as f64 / 10.0);
+///         assert!((p.x * p.x / 4.0 + p.y * p.y).near(&1.0));
+///     }
+/// }
+///
+/// let face = builder::try_attach_plane(&vec![ellipse]).unwrap();
+/// let solid = builder::tsweep(&face, Vector3::unit_z());
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert!((volume - 2.0 * PI).abs() < 1.0e-3);
+/// ```
+/// # Failures
+/// If a semi-axis is not positive, returns
+/// [`Error::InvalidProfile`](../errors/enum.Error.html#variant.InvalidProfile).
+#[inline(always)]
+pub fn ellipse(plane: &Plane, center: Point2, semi_axis0: f64, semi_axis1: f64) -> Result<Wire> {
+    profile::ellipse(plane, center, semi_axis0, semi_axis1)
+}
+
 /// Returns a homotopic face from `edge0` to `edge1`.
 /// # Examples
 /// ```
@@ -143,6 +277,150 @@ pub fn homotopy(edge0: &Edge, edge1: &Edge) -> Face {
     )
 }
 
+/// Creates the shell skinning the sections `wires` by the B-spline surfaces.
+///
+/// The wires should have the same number of edges, and the `j`-th edges of the wires are
+/// skinned into one face interpolating the edges at the parameters by the mean distances
+/// between the sections. The sections other than the first and the last ones are contained
+/// in the interiors of the faces.
+/// # Failures
+/// Returns [`Error::IncompatibleSections`] if there are less than two wires, the numbers of
+/// the edges of the wires are different, or the consecutive sections coincide.
+///
+/// [`Error::IncompatibleSections`]: ../errors/enum.Error.html#variant.IncompatibleSections
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// // a waisted tube lofted between three circles
+/// let circle = |radius: f64, height: f64| -> Wire {
+///     let v = builder::vertex(Point3::new(radius, height, 0.0));
+///     builder::rsweep(&v, Point3::new(0.0, height, 0.0), -Vector3::unit_y(), Rad(7.0))
+/// };
+/// let wires = vec![circle(1.0, 0.0), circle(0.5, 1.0), circle(1.0, 2.0)];
+/// let shell = builder::loft(&wires).unwrap();
+/// assert_eq!(shell.len(), 2);
+/// assert_eq!(shell.shell_condition(), ShellCondition::Oriented);
+///
+/// // the middle section is on the surface
+/// let surface = shell[0].lock_surface().unwrap().clone();
+/// let pt = surface.subs(surface.parameter_range().0 .0, 0.5);
+/// assert_near!(pt, Point3::new(0.5, 1.0, 0.0));
+///
+/// // the numbers of the edges are different
+/// let v = builder::vertex(Point3::new(1.0, 3.0, 0.0));
+/// let wire: Wire = builder::rsweep(&v, Point3::new(0.0, 3.0, 0.0), -Vector3::unit_y(), Rad(3.0));
+/// assert!(builder::loft(&[wires[2].clone(), wire]).is_err());
+/// ```
+pub fn loft(wires: &[Wire]) -> Result<Shell> {
+    let len = match wires.first() {
+        Some(wire) if wires.len() > 1 && !wire.is_empty() => wire.len(),
+        _ => return Err(Error::IncompatibleSections),
+    };
+    if wires.iter().any(|wire| wire.len() != len) {
+        return Err(Error::IncompatibleSections);
+    }
+    let closed = wires[0].is_closed();
+    let vertices: Vec<Vec<Vertex>> = wires
+        .iter()
+        .map(|wire| {
+            let mut vertices: Vec<Vertex> = wire.iter().map(|edge| edge.front().clone()).collect();
+            if !closed {
+                vertices.push(wire.back_vertex().unwrap().clone());
+            }
+            vertices
+        })
+        .collect();
+    let mut params = vec![0.0];
+    for pair in vertices.windows(2) {
+        let dist = pair[0]
+            .iter()
+            .zip(&pair[1])
+            .map(|(v0, v1)| v0.lock_point().unwrap().distance(*v1.lock_point().unwrap()))
+            .sum::<f64>()
+            / pair[0].len() as f64;
+        params.push(params[params.len() - 1] + dist);
+    }
+    let total = params[params.len() - 1];
+    params.iter_mut().for_each(|t| *t /= total);
+    let surfaces = (0..len)
+        .map(|j| {
+            let curves = wires
+                .iter()
+                .map(|wire| wire[j].oriented_curve().lift_up())
+                .collect();
+            BSplineSurface::skin(curves, &params).map_err(|_| Error::IncompatibleSections)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let (first, last) = (&vertices[0], &vertices[vertices.len() - 1]);
+    let rails: Vec<Edge> = (0..first.len())
+        .map(|j| {
+            let curve = match j < len {
+                true => surfaces[j].column_curve(0),
+                false => {
+                    let surface = &surfaces[len - 1];
+                    surface.column_curve(surface.control_points().len() - 1)
+                }
+            };
+            Edge::new(&first[j], &last[j], Curve::NURBSCurve(NURBSCurve::new(curve)))
+        })
+        .collect();
+    let shell = surfaces
+        .into_iter()
+        .enumerate()
+        .map(|(j, surface)| {
+            let wire: Wire = vec![
+                wires[0][j].clone(),
+                rails[(j + 1) % rails.len()].clone(),
+                wires[wires.len() - 1][j].inverse(),
+                rails[j].inverse(),
+            ]
+            .into();
+            Face::new(vec![wire], Surface::NURBSSurface(NURBSSurface::new(surface)))
+        })
+        .collect();
+    Ok(shell)
+}
+
+/// Creates the solid by capping the both ends of the loft by the planes.
+///
+/// The wires should be closed and planar, and be oriented counterclockwise seen from the
+/// direction of the loft, i.e. the normal of the plane attached to the first wire should be
+/// directed to the next section, as the face swept by [`tsweep`].
+///
+/// [`tsweep`]: ./fn.tsweep.html
+/// # Failures
+/// - Returns [`Error::IncompatibleSections`] if the wires cannot be lofted. cf. [`loft`].
+/// - Returns [`Error::WireNotInOnePlane`] if the first or the last wire is not planar.
+///
+/// [`loft`]: ./fn.loft.html
+/// [`Error::IncompatibleSections`]: ../errors/enum.Error.html#variant.IncompatibleSections
+/// [`Error::WireNotInOnePlane`]: ../errors/enum.Error.html#variant.WireNotInOnePlane
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// // a frustum lofted from a square to a smaller square
+/// let square = |size: f64, height: f64| -> Wire {
+///     let v = builder::vertex(Point3::new(-size / 2.0, -size / 2.0, height));
+///     let e = builder::tsweep(&v, Vector3::new(size, 0.0, 0.0));
+///     let f: Face = builder::tsweep(&e, Vector3::new(0.0, size, 0.0));
+///     f.into_boundaries().pop().unwrap()
+/// };
+/// let solid = builder::loft_solid(&[square(2.0, 0.0), square(1.0, 1.0)]).unwrap();
+/// assert_eq!(solid.boundaries()[0].len(), 6);
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert_near!(volume, 7.0 / 3.0);
+/// ```
+pub fn loft_solid(wires: &[Wire]) -> Result<Solid> {
+    let mut shell = loft(wires)?;
+    let bottom = try_attach_plane(&vec![wires[0].clone()])?;
+    let top = try_attach_plane(&vec![wires[wires.len() - 1].clone()])?;
+    shell.push(bottom.inverse());
+    shell.push(top);
+    Ok(Solid::try_new(vec![shell])?)
+}
+
 /// Creates a cone by R-sweeping.
 /// # Examples
 /// ```
@@ -306,6 +584,199 @@ pub fn try_attach_plane(wires: &Vec<Wire>) -> Result<Face> {
     Ok(Face::try_new(wires.clone(), surface)?)
 }
 
+/// Returns the solid whose `edges` are replaced by the rolling-ball fillets with `radius`.
+///
+/// The fillet faces are the cylindrical NURBS surfaces. The faces adjacent to the edges are
+/// trimmed, and the fillets are stitched to the faces at the ends of the edges.
+/// The original solid is not modified, and the unchanged faces are shared.
+/// # Failures
+/// - Returns [`Error::ElementNotFound`] if an edge is not an edge of `solid`.
+/// - Returns [`Error::UnsupportedGeometry`] if an edge is not a convex line between two planes,
+/// the end vertices are not shared by three planes, or two edges share an end vertex.
+/// - Returns [`Error::BlendTooLarge`] if the fillet runs over the adjacent edges.
+///
+/// [`Error::ElementNotFound`]: ../errors/enum.Error.html#variant.ElementNotFound
+/// [`Error::UnsupportedGeometry`]: ../errors/enum.Error.html#variant.UnsupportedGeometry
+/// [`Error::BlendTooLarge`]: ../errors/enum.Error.html#variant.BlendTooLarge
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use std::f64::consts::PI;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let edge = cube.boundaries()[0][0].boundaries()[0][0].clone();
+/// let solid = builder::fillet(&cube, &[edge.clone()], 0.2).unwrap();
+/// assert_eq!(solid.boundaries()[0].len(), 7);
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert!((volume - (1.0 - 0.04 * (1.0 - PI / 4.0))).abs() < 1.0e-3, "{}", volume);
+///
+/// // too large fillet
+/// assert!(builder::fillet(&cube, &[edge], 2.0).is_err());
+/// ```
+pub fn fillet(solid: &Solid, edges: &[Edge], radius: f64) -> Result<Solid> {
+    blend::blend(solid, edges, blend::Blend::Fillet(radius))
+}
+
+/// Returns the solid whose `edges` are cut by the planar bevels at `distance` from the edges.
+///
+/// The faces adjacent to the edges are trimmed at `distance` from the edges, and the bevels
+/// are stitched to the faces at the ends of the edges.
+/// The original solid is not modified, and the unchanged faces are shared.
+/// # Failures
+/// - Returns [`Error::ElementNotFound`] if an edge is not an edge of `solid`.
+/// - Returns [`Error::UnsupportedGeometry`] if an edge is not a convex line between two planes,
+/// the end vertices are not shared by three planes, or two edges share an end vertex.
+/// - Returns [`Error::BlendTooLarge`] if the bevel runs over the adjacent edges.
+///
+/// [`Error::ElementNotFound`]: ../errors/enum.Error.html#variant.ElementNotFound
+/// [`Error::UnsupportedGeometry`]: ../errors/enum.Error.html#variant.UnsupportedGeometry
+/// [`Error::BlendTooLarge`]: ../errors/enum.Error.html#variant.BlendTooLarge
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let edge = cube.boundaries()[0][0].boundaries()[0][0].clone();
+/// let solid = builder::chamfer(&cube, &[edge], 0.2).unwrap();
+/// assert_eq!(solid.boundaries()[0].len(), 7);
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert_near!(volume, 1.0 - 0.02);
+/// ```
+pub fn chamfer(solid: &Solid, edges: &[Edge], distance: f64) -> Result<Solid> {
+    blend::blend(solid, edges, blend::Blend::Chamfer(distance))
+}
+
+/// Returns the hollow solid whose walls have `thickness`, opened at `faces_to_remove`.
+///
+/// All faces except `faces_to_remove` are offset inward by `thickness` to the inner faces, and
+/// the rims on the planes of the removed faces connect the outer and the inner faces.
+/// If no faces are removed, the inner faces are the boundary of the closed cavity.
+/// The original solid is not modified, and the outer faces are shared.
+/// # Failures
+/// - Returns [`Error::ElementNotFound`] if a face to be removed is not a face of `solid`.
+/// - Returns [`Error::UnsupportedGeometry`] if the faces and the edges are not planes
+/// and lines, respectively.
+/// - Returns [`Error::DegenerateIntersection`] if the offset planes at a vertex do not meet
+/// at one point, e.g. at the apex of a square pyramid.
+/// - Returns [`Error::InvalidThickness`] if `thickness` is not positive, or an inner edge is
+/// turned over by the too large thickness.
+///
+/// [`Error::ElementNotFound`]: ../errors/enum.Error.html#variant.ElementNotFound
+/// [`Error::UnsupportedGeometry`]: ../errors/enum.Error.html#variant.UnsupportedGeometry
+/// [`Error::DegenerateIntersection`]: ../errors/enum.Error.html#variant.DegenerateIntersection
+/// [`Error::InvalidThickness`]: ../errors/enum.Error.html#variant.InvalidThickness
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::errors::Error;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// // the box opened at the top
+/// let top = cube.boundaries()[0]
+///     .iter()
+///     .find(|face| face.oriented_surface().normal(0.5, 0.5).near(&Vector3::unit_z()))
+///     .unwrap();
+/// let solid = builder::hollow(&cube, 0.1, &[top.clone()]).unwrap();
+/// assert_eq!(solid.boundaries().len(), 1);
+/// assert_eq!(solid.boundaries()[0].len(), 14);
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert_near!(volume, 1.0 - 0.8 * 0.8 * 0.9);
+///
+/// // the closed cavity
+/// let solid = builder::hollow(&cube, 0.1, &[]).unwrap();
+/// assert_eq!(solid.boundaries().len(), 2);
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert_near!(volume, 1.0 - 0.8 * 0.8 * 0.8);
+///
+/// // too thick walls
+/// assert_eq!(builder::hollow(&cube, 0.6, &[]).unwrap_err(), Error::InvalidThickness);
+/// ```
+pub fn hollow(solid: &Solid, thickness: f64, faces_to_remove: &[Face]) -> Result<Solid> {
+    hollow::hollow(solid, thickness, faces_to_remove)
+}
+
+/// Creates the ISO metric thread on the cylindrical face.
+///
+/// The thread is external if the face is convex, i.e. a shaft, and internal if the face is
+/// concave, i.e. a hole. The crests are on the face, and the roots are `depth` inside of the
+/// material. The thread starts at the end of the face on the opposite side of `axis` of the
+/// returned [`Thread`].
+///
+/// The cosmetic thread has only the annotations and the overlay of [`Thread`]. The modeled
+/// thread has also the helical groove as `body`, which is lofted through the trapezoidal
+/// sections moved by the screw motion and is subtracted from the solid by
+/// [`BooleanOperation::subtract`]. The groove runs out of the end of the face, or runs out of
+/// the face radially in the last half turn if the thread is shorter than the face. The helical
+/// faces of the groove cross the ends of the face at shallow angles, so the tolerance of the
+/// subtraction should be small compared with the pitch.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::thread::*;
+///
+/// // a shaft of diameter 10
+/// let v = builder::vertex(Point3::new(5.0, 0.0, 0.0));
+/// let circle: Wire = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+/// let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+/// let shaft = builder::tsweep(&disk, Vector3::new(0.0, 0.0, 4.0));
+/// let face = shaft.boundaries()[0].face_iter().find(|face| {
+///     !matches!(*face.lock_surface().unwrap(), Surface::Plane(_))
+/// }).unwrap();
+///
+/// let spec = ThreadSpec {
+///     pitch: 1.5,
+///     kind: ThreadKind::Modeled,
+///     ..Default::default()
+/// };
+/// let thread = builder::thread(face, &spec).unwrap();
+/// assert!(!thread.internal);
+/// assert_near!(thread.major_diameter, 10.0);
+/// assert!((thread.minor_diameter - (10.0 - 2.0 * 0.8119)).abs() < 1.0e-3);
+/// assert_near!(thread.length, 4.0);
+/// assert_eq!(thread.attributes["hand"], "right");
+///
+/// // the groove is a solid
+/// let groove = thread.body.as_ref().unwrap();
+/// let volume = properties::mass_properties(groove, 1.0e-2).volume;
+/// assert!(volume > 0.0);
+///
+/// // the overlay is on the crests
+/// let overlay = thread.overlay(1.0e-3);
+/// assert_eq!(overlay.len(), 3);
+/// for pt in &overlay[0] {
+///     let radial = (pt - thread.origin) - thread.axis * (pt - thread.origin).dot(thread.axis);
+///     assert!((radial.magnitude() - 5.0).abs() < 1.0e-3);
+/// }
+///
+/// // a thread cannot be created on a planar face
+/// let planar = &shaft.boundaries()[0][0];
+/// let error = builder::thread(planar, &spec).unwrap_err();
+/// assert_eq!(error, errors::Error::UnsupportedGeometry);
+/// ```
+/// # Failures
+/// - Returns [`Error::UnsupportedGeometry`] if the face is not cylindrical.
+/// - Returns [`Error::InvalidThread`] if the pitch or the depth is not positive, the flanks
+///   meet each other, the depth is not less than the radius of the shaft, or the thread is
+///   longer than the face.
+///
+/// [`Thread`]: ../thread/struct.Thread.html
+/// [`BooleanOperation::subtract`]: ../boolean/trait.BooleanOperation.html#tymethod.subtract
+/// [`Error::UnsupportedGeometry`]: ../errors/enum.Error.html#variant.UnsupportedGeometry
+/// [`Error::InvalidThread`]: ../errors/enum.Error.html#variant.InvalidThread
+#[inline(always)]
+pub fn thread(face: &Face, spec: &thread::ThreadSpec) -> Result<thread::Thread> {
+    thread::thread(face, spec)
+}
+
 /// Returns another topology whose points, curves, and surfaces are cloned.
 #[inline(always)]
 pub fn clone<T: Mapped<Point3, Curve, Surface>>(elem: &T) -> T { elem.topological_clone() }
@@ -402,6 +873,74 @@ pub fn tsweep<T: Sweep<Point3, Curve, Surface>>(elem: &T, vector: Vector3) -> T:
     )
 }
 
+/// Sweeps a vertex, an edge, a wire, or a face along the spine curve.
+///
+/// The element is moved by the rotation-minimizing frame along `spine`, i.e. the frame does not
+/// twist around the tangent of the spine. The element should be put at the start of the spine,
+/// and the normal of a face should be directed to the tangent of the spine at the start.
+///
+/// The swept surfaces are the NURBS surfaces whose sections along the spine are exactly the
+/// transformed curves, and the motion along the spine is approximated by a cubic B-spline.
+/// # Failures
+/// - Returns [`Error::UnsupportedGeometry`] if the derivation of the spine vanishes.
+/// - Returns [`Error::ApproximationFailed`] if the motion along the spine cannot be approximated.
+///
+/// [`Error::UnsupportedGeometry`]: ../errors/enum.Error.html#variant.UnsupportedGeometry
+/// [`Error::ApproximationFailed`]: ../errors/enum.Error.html#variant.ApproximationFailed
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+///
+/// // a bent tube whose section is the disk of radius 0.2
+/// let v = builder::vertex(Point3::new(0.2, 0.0, 0.0));
+/// let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+/// let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+/// let spine = BSplineCurve::new(
+///     KnotVec::bezier_knot(3),
+///     vec![
+///         Vector3::new(0.0, 0.0, 0.0),
+///         Vector3::new(0.0, 0.0, 2.0),
+///         Vector3::new(2.0, 0.0, 2.0),
+///         Vector3::new(2.0, 1.0, 4.0),
+///     ],
+/// );
+/// let tube: Solid = builder::pipe(&disk, &spine).unwrap();
+/// // the start disk, the two side faces of the semicircles and the end disk
+/// assert_eq!(tube.boundaries()[0].len(), 4);
+///
+/// // the end face is on the end of the spine
+/// let end_face = &tube.boundaries()[0][3];
+/// let center = end_face
+///     .boundaries()[0]
+///     .vertex_iter()
+///     .map(|v| v.lock_point().unwrap().to_vec())
+///     .sum::<Vector3>()
+///     / end_face.boundaries()[0].len() as f64;
+/// assert!(center.distance(Vector3::new(2.0, 1.0, 4.0)) < 0.2 + 1.0e-3);
+///
+/// // the side faces are in the distance 0.2 from the spine
+/// const N: usize = 10;
+/// for face in &tube.boundaries()[0][1..3] {
+///     let surface = face.lock_surface().unwrap().clone();
+///     for i in 0..=N {
+///         for j in 0..=N {
+///             let ((u0, u1), (v0, v1)) = surface.parameter_range();
+///             let u = u0 + (u1 - u0) * i as f64 / N as f64;
+///             let v = v0 + (v1 - v0) * j as f64 / N as f64;
+///             let dist = surface.subs(u, v).to_vec().distance(spine.subs(v));
+///             assert!((dist - 0.2).abs() < 1.0e-3, "{}", dist);
+///         }
+///     }
+/// }
+/// ```
+#[inline(always)]
+pub fn pipe<T: Sweep<Point3, Curve, Surface>>(
+    elem: &T,
+    spine: &BSplineCurve<Vector3>,
+) -> Result<T::Swept> {
+    pipe::pipe(elem, spine)
+}
+
 /// Sweeps a vertex, an edge, a wire, a face, or a shell by the rotation.
 /// # Details
 /// If the absolute value of `angle` is more than 2π rad, then the result is closed shape.
@@ -0,0 +1,111 @@
+use crate::face_domain::FaceDomain;
+use crate::*;
+
+/// The position of a point relative to a face.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum PointClassification {
+    /// The point is in the interior of the face.
+    Inside,
+    /// The point is out of the face.
+    Outside,
+    /// The point is on the boundary of the face within the tolerance.
+    OnBoundary,
+}
+
+/// The classifier of the points in the parameter space of a face.
+///
+/// The boundaries of the face are projected to the parameter space of the oriented surface
+/// `Face::oriented_surface()` at the construction, so the classifier should be reused
+/// for classifying many points on the same face.
+#[derive(Clone, Debug)]
+pub struct FaceClassifier {
+    domain: FaceDomain,
+    tol: f64,
+}
+
+impl FaceClassifier {
+    /// Creates the classifier of `face`.
+    /// `tol` is the tolerance of the polyline approximation of the boundaries,
+    /// and the points whose distances from the boundaries are less than `tol`
+    /// are classified as `OnBoundary`.
+    #[inline(always)]
+    pub fn new(face: &Face, tol: f64) -> FaceClassifier {
+        FaceClassifier {
+            domain: FaceDomain::new(face, tol),
+            tol,
+        }
+    }
+
+    /// Classifies the point of the parameter `(u, v)` of the oriented surface.
+    ///
+    /// The boundaries are oriented along the oriented surface, so the holes of the face
+    /// are classified as `Outside`. For a periodic surface, the parameter is shifted by
+    /// the period.
+    pub fn classify(&self, (u, v): (f64, f64)) -> PointClassification {
+        let pt = self.domain.surface.subs(u, v);
+        let on_boundary = self
+            .domain
+            .boundary_points
+            .iter()
+            .any(|polygon| closed_polyline_distance(polygon, pt) < self.tol);
+        if on_boundary {
+            PointClassification::OnBoundary
+        } else if self.domain.include(Vector2::new(u, v)) {
+            PointClassification::Inside
+        } else {
+            PointClassification::Outside
+        }
+    }
+}
+
+/// Classification of the points on faces.
+pub trait ClassifyPoint {
+    /// Classifies the point of the parameter `uv` of the oriented surface.
+    /// cf. [`FaceClassifier::classify`](./struct.FaceClassifier.html#method.classify)
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::classification::{ClassifyPoint, PointClassification};
+    /// let square = |min: f64, max: f64| -> Wire {
+    ///     let v = builder::vertex(Point3::new(min, min, 0.0));
+    ///     let e = builder::tsweep(&v, Vector3::new(max - min, 0.0, 0.0));
+    ///     let face = builder::tsweep(&e, Vector3::new(0.0, max - min, 0.0));
+    ///     face.boundaries().pop().unwrap()
+    /// };
+    /// // the square [0, 4] x [0, 4] with the square hole [1, 3] x [1, 3]
+    /// let outer = square(0.0, 4.0);
+    /// let hole = square(1.0, 3.0).inverse();
+    /// let face = builder::try_attach_plane(&vec![outer, hole]).unwrap();
+    ///
+    /// // the parameter space of the plane is fitted to the outer boundary
+    /// assert_eq!(face.classify((0.1, 0.5), 1.0e-3), PointClassification::Inside);
+    /// assert_eq!(face.classify((0.5, 0.5), 1.0e-3), PointClassification::Outside);
+    /// assert_eq!(face.classify((1.5, 0.5), 1.0e-3), PointClassification::Outside);
+    /// assert_eq!(face.classify((0.0, 0.5), 1.0e-3), PointClassification::OnBoundary);
+    /// assert_eq!(face.classify((0.25, 0.5), 1.0e-3), PointClassification::OnBoundary);
+    /// ```
+    fn classify(&self, uv: (f64, f64), tol: f64) -> PointClassification;
+}
+
+impl ClassifyPoint for Face {
+    #[inline(always)]
+    fn classify(&self, uv: (f64, f64), tol: f64) -> PointClassification {
+        FaceClassifier::new(self, tol).classify(uv)
+    }
+}
+
+/// Returns the distance between the point and the closed polyline.
+fn closed_polyline_distance(polygon: &[Point3], pt: Point3) -> f64 {
+    let len = polygon.len();
+    (0..len)
+        .map(|i| {
+            let (p, q) = (polygon[i], polygon[(i + 1) % len]);
+            let dir = q - p;
+            let t = match dir.so_small() {
+                true => 0.0,
+                false => f64::min(f64::max((pt - p).dot(dir) / dir.magnitude2(), 0.0), 1.0),
+            };
+            (p + dir * t).distance(pt)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
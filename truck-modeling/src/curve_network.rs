@@ -0,0 +1,751 @@
+use crate::errors::Error;
+use crate::*;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::f64::consts::PI;
+use std::iter::FromIterator;
+
+// the maximum number of the iterations of the Gauss-Newton method refining the intersections
+const REFINEMENT_TRIALS: usize = 16;
+
+/// The network of curves, split at the intersections of the curves.
+///
+/// The ends of the curves and the intersection points are merged into the common vertices, so
+/// that the closed loops in a soup of curves, e.g. the profiles traced from DXF files or from the
+/// results of intersections, can be assembled into wires automatically.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::curve_network::CurveNetwork;
+/// // three overshooting lines sketching a triangle
+/// let line = |p: Point3, q: Point3| builder::line(&builder::vertex(p), &builder::vertex(q));
+/// let edges = vec![
+///     line(Point3::new(-0.5, 0.0, 0.0), Point3::new(1.5, 0.0, 0.0)),
+///     line(Point3::new(1.0, -0.5, 0.0), Point3::new(1.0, 1.5, 0.0)),
+///     line(Point3::new(1.5, 1.5, 0.0), Point3::new(-0.5, -0.5, 0.0)),
+/// ];
+///
+/// let network = CurveNetwork::new(&edges, 1.0e-6);
+/// // each line is split into three edges
+/// assert_eq!(network.edges().len(), 9);
+/// // the six ends of the lines and the three corners of the triangle
+/// assert_eq!(network.vertices().len(), 9);
+///
+/// // the overshoots are dangling, and only the triangle is closed.
+/// let wires = network.closed_wires();
+/// assert_eq!(wires.len(), 1);
+/// assert_eq!(wires[0].len(), 3);
+/// assert!(wires[0].is_closed());
+/// assert!(builder::try_attach_plane(&wires).is_ok());
+/// ```
+#[derive(Clone, Debug)]
+pub struct CurveNetwork {
+    vertices: Vec<Vertex>,
+    edges: Vec<Edge>,
+    tol: f64,
+}
+
+impl CurveNetwork {
+    /// Creates the network of the curves of `edges`.
+    ///
+    /// - The vertices whose points are near within `tol` are merged into one vertex.
+    /// - Each curve is approximated by the polyline within `tol`, and the intersections of the
+    ///   curves are refined from the nearest points of the polylines. The curves are split at the
+    ///   intersections, including the ones where an end of a curve touches another curve.
+    /// - The edges which are neither split nor merged are kept as they are. The others are
+    ///   re-created by the split curves.
+    ///
+    /// The self-intersections of each curve and the overlapping curves are not detected.
+    pub fn new(edges: &[Edge], tol: f64) -> CurveNetwork {
+        let mut pool = VertexPool::new(tol);
+        let ends: Vec<(Vertex, Vertex)> = edges
+            .iter()
+            .map(|edge| (pool.get(edge.front()), pool.get(edge.back())))
+            .collect();
+        let curves: Vec<Curve> = edges.iter().map(|edge| edge.oriented_curve()).collect();
+        let polylines: Vec<_> = curves.iter().map(|curve| Polyline::new(curve, tol)).collect();
+        let mut splits = vec![Vec::new(); edges.len()];
+        for i in 0..curves.len() {
+            for j in i + 1..curves.len() {
+                let candidates = polylines[i].candidates(&polylines[j], tol);
+                for (s, t) in candidates {
+                    let (s, t) = refine_intersection(&curves[i], &curves[j], s, t);
+                    let (pt0, pt1) = (curves[i].subs(s), curves[j].subs(t));
+                    if pt0.distance(pt1) < tol {
+                        let vertex = pool.get_by_point(pt0.midpoint(pt1));
+                        splits[i].push((s, vertex.clone()));
+                        splits[j].push((t, vertex));
+                    }
+                }
+            }
+        }
+        let mut network_edges = Vec::new();
+        for (i, mut split) in splits.into_iter().enumerate() {
+            let (front, back) = &ends[i];
+            split.sort_by(|x, y| x.0.partial_cmp(&y.0).unwrap());
+            let (t0, t1) = curves[i].parameter_range();
+            let mut nodes = vec![(t0, front.clone())];
+            for (t, vertex) in split {
+                if &vertex != back && vertex != nodes.last().unwrap().1 {
+                    nodes.push((t, vertex));
+                }
+            }
+            nodes.push((t1, back.clone()));
+            let kept = nodes.len() == 2 && front == edges[i].front() && back == edges[i].back();
+            match kept {
+                true => network_edges.push(edges[i].clone()),
+                false => split_curve(&curves[i], &nodes, &mut pool, &mut network_edges),
+            }
+        }
+        CurveNetwork {
+            vertices: pool.into_vertices(),
+            edges: network_edges,
+            tol,
+        }
+    }
+
+    /// Creates the network of `curves`.
+    /// cf. [`CurveNetwork::new`](#method.new)
+    pub fn from_curves(curves: &[Curve], tol: f64) -> CurveNetwork {
+        let edges: Vec<Edge> = curves
+            .iter()
+            .map(|curve| {
+                let front = Vertex::new(curve.front());
+                let back = Vertex::new(curve.back());
+                Edge::new(&front, &back, curve.clone())
+            })
+            .collect();
+        CurveNetwork::new(&edges, tol)
+    }
+
+    /// Returns the vertices of the network.
+    #[inline(always)]
+    pub fn vertices(&self) -> &[Vertex] { &self.vertices }
+
+    /// Returns the edges of the network.
+    #[inline(always)]
+    pub fn edges(&self) -> &[Edge] { &self.edges }
+
+    /// Returns the closed loops in the network.
+    ///
+    /// The dangling edges, which do not lie on any loop, are removed. If a connected component
+    /// of the rest is a cycle, the cycle is returned. If the component branches, the fundamental
+    /// cycles with respect to the breadth-first spanning tree are returned, so that every
+    /// remaining edge is contained in at least one wire.
+    pub fn closed_wires(&self) -> Vec<Wire> {
+        let alive = self.loop_edges();
+        let mut adjacency = HashMap::<VertexID, Vec<usize>>::new();
+        for (i, edge) in self.edges.iter().enumerate().filter(|(i, _)| alive[*i]) {
+            adjacency.entry(edge.front().id()).or_default().push(i);
+            adjacency.entry(edge.back().id()).or_default().push(i);
+        }
+        // the parent edge and the depth of each vertex in the spanning forest
+        let mut tree = HashMap::<VertexID, (Option<usize>, usize)>::new();
+        for root in &self.vertices {
+            if tree.contains_key(&root.id()) || !adjacency.contains_key(&root.id()) {
+                continue;
+            }
+            tree.insert(root.id(), (None, 0));
+            let mut queue = VecDeque::from_iter(vec![root.clone()]);
+            while let Some(vertex) = queue.pop_front() {
+                let depth = tree[&vertex.id()].1;
+                for i in &adjacency[&vertex.id()] {
+                    let next = opposite(&self.edges[*i], &vertex);
+                    if !tree.contains_key(&next.id()) {
+                        tree.insert(next.id(), (Some(*i), depth + 1));
+                        queue.push_back(next.clone());
+                    }
+                }
+            }
+        }
+        let tree_edges: HashSet<usize> = tree.values().filter_map(|(i, _)| *i).collect();
+        (0..self.edges.len())
+            .filter(|i| alive[*i] && !tree_edges.contains(i))
+            .map(|i| {
+                let edge = &self.edges[i];
+                // the paths from the ends of the edge to their lowest common ancestor
+                let (mut path0, mut path1) = (Vec::new(), Vec::new());
+                let (mut vertex0, mut vertex1) = (edge.back().clone(), edge.front().clone());
+                while vertex0 != vertex1 {
+                    let (depth0, depth1) = (tree[&vertex0.id()].1, tree[&vertex1.id()].1);
+                    if depth0 >= depth1 {
+                        vertex0 = self.climb(&vertex0, &tree, &mut path0);
+                    } else {
+                        vertex1 = self.climb(&vertex1, &tree, &mut path1);
+                    }
+                }
+                let mut wire = Wire::with_capacity(1 + path0.len() + path1.len());
+                wire.push_back(edge.clone());
+                wire.extend(path0);
+                wire.extend(path1.into_iter().rev().map(|edge| edge.inverse()));
+                wire
+            })
+            .collect()
+    }
+
+    // pushes the edge from `vertex` to its parent in the spanning tree, and returns the parent
+    fn climb(
+        &self,
+        vertex: &Vertex,
+        tree: &HashMap<VertexID, (Option<usize>, usize)>,
+        path: &mut Vec<Edge>,
+    ) -> Vertex {
+        let edge = &self.edges[tree[&vertex.id()].0.unwrap()];
+        let edge = match edge.front() == vertex {
+            true => edge.clone(),
+            false => edge.inverse(),
+        };
+        let parent = edge.back().clone();
+        path.push(edge);
+        parent
+    }
+
+    // the flags of the edges remaining after the dangling edges are removed repeatedly
+    fn loop_edges(&self) -> Vec<bool> {
+        let mut degrees = HashMap::<VertexID, usize>::new();
+        for edge in &self.edges {
+            *degrees.entry(edge.front().id()).or_default() += 1;
+            *degrees.entry(edge.back().id()).or_default() += 1;
+        }
+        let mut alive = vec![true; self.edges.len()];
+        let mut removed = true;
+        while removed {
+            removed = false;
+            for (i, edge) in self.edges.iter().enumerate() {
+                let (front, back) = (edge.front().id(), edge.back().id());
+                if alive[i] && (degrees[&front] == 1 || degrees[&back] == 1) {
+                    alive[i] = false;
+                    removed = true;
+                    *degrees.get_mut(&front).unwrap() -= 1;
+                    *degrees.get_mut(&back).unwrap() -= 1;
+                }
+            }
+        }
+        alive
+    }
+}
+
+impl CurveNetwork {
+    /// Returns the regions enclosed by the curves in a plane, like the regions of the sketches in
+    /// the MCAD packages, as the planar faces.
+    ///
+    /// The dangling edges and the bridges are ignored. Each face is bounded by the loop of the
+    /// edges around the region, and has the loops of the separate islands inside the region as
+    /// the inner boundaries. The islands themselves are also returned as regions.
+    ///
+    /// The plane is fitted to the points on the curves, and its normal is oriented so that the
+    /// component of the normal with the largest absolute value is positive, e.g. the normal of
+    /// the regions in the xy-plane is `Vector3::unit_z()`. The outer boundaries of the faces are
+    /// counter-clockwise with respect to the normal, and the inner boundaries are clockwise.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::curve_network::CurveNetwork;
+    /// let v = |x: f64, y: f64| builder::vertex(Point3::new(x, y, 0.0));
+    /// let polygon = |v: &[Vertex]| -> Vec<Edge> {
+    ///     (0..v.len()).map(|i| builder::line(&v[i], &v[(i + 1) % v.len()])).collect()
+    /// };
+    /// // the square divided by an overshooting line, with an island in the left half
+    /// let mut edges = polygon(&[v(0.0, 0.0), v(2.0, 0.0), v(2.0, 2.0), v(0.0, 2.0)]);
+    /// edges.push(builder::line(&v(1.0, -0.5), &v(1.0, 2.5)));
+    /// edges.extend(polygon(&[v(0.25, 0.25), v(0.25, 0.75), v(0.75, 0.75), v(0.75, 0.25)]));
+    ///
+    /// let network = CurveNetwork::new(&edges, 1.0e-6);
+    /// let regions = network.planar_regions().unwrap();
+    /// assert_eq!(regions.len(), 3);
+    /// // the left half has the island as the inner boundary.
+    /// let left = regions.iter().find(|face| face.boundaries().len() == 2).unwrap();
+    /// let pad = builder::tsweep(left, Vector3::unit_z());
+    /// let volume = properties::mass_properties(&pad, 1.0e-3).volume;
+    /// assert!((volume - 1.75).abs() < 1.0e-3);
+    ///
+    /// // the regions are padded to the positive z direction.
+    /// for face in &regions {
+    ///     assert!(face.oriented_surface().normal(0.0, 0.0).near(&Vector3::unit_z()));
+    /// }
+    /// ```
+    /// # Failures
+    /// If the curves are not in one plane within the tolerance of the network, returns
+    /// [`Error::WireNotInOnePlane`](../errors/enum.Error.html#variant.WireNotInOnePlane).
+    pub fn planar_regions(&self) -> Result<Vec<Face>> {
+        let plane = self.fitted_plane().ok_or(Error::WireNotInOnePlane)?;
+        let mut alive = self.loop_edges();
+        let mut cycles = self.trace_cycles(&alive, &plane);
+        // the bridges are the edges whose both sides are in the same cycle.
+        let bridges: Vec<usize> = cycles
+            .iter()
+            .flat_map(|cycle| {
+                let mut set = HashSet::new();
+                cycle.iter().filter(move |h| !set.insert(*h / 2)).map(|h| h / 2).collect::<Vec<_>>()
+            })
+            .collect();
+        if !bridges.is_empty() {
+            bridges.into_iter().for_each(|i| alive[i] = false);
+            cycles = self.trace_cycles(&alive, &plane);
+        }
+        let components = self.components(&alive);
+        let polygons: Vec<Vec<Point2>> = cycles
+            .iter()
+            .map(|cycle| self.cycle_polygon(cycle, &plane))
+            .collect();
+        let areas: Vec<f64> = polygons.iter().map(|polygon| signed_area(polygon)).collect();
+        let component = |cycle: &[usize]| components[&self.half_edge(cycle[0]).front().id()];
+        let mut boundaries: Vec<(usize, Vec<usize>)> = (0..cycles.len())
+            .filter(|i| areas[*i] > 0.0)
+            .map(|i| (i, Vec::new()))
+            .collect();
+        for i in (0..cycles.len()).filter(|i| areas[*i] <= 0.0) {
+            // the smallest region of the other components containing the clockwise cycle
+            let point = polygons[i][0];
+            let outer = boundaries
+                .iter_mut()
+                .filter(|(j, _)| component(&cycles[*j]) != component(&cycles[i]))
+                .filter(|(j, _)| winding_number(&polygons[*j], point) != 0)
+                .min_by(|(j, _), (k, _)| areas[*j].partial_cmp(&areas[*k]).unwrap());
+            if let Some((_, holes)) = outer {
+                holes.push(i);
+            }
+        }
+        let surface = Surface::Plane(plane);
+        boundaries
+            .into_iter()
+            .map(|(outer, holes)| {
+                let wires: Vec<Wire> = std::iter::once(outer)
+                    .chain(holes)
+                    .map(|i| cycles[i].iter().map(|h| self.half_edge(*h)).collect())
+                    .collect();
+                Ok(Face::try_new(wires, surface.clone())?)
+            })
+            .collect()
+    }
+
+    // the plane fitted to the points on the curves
+    fn fitted_plane(&self) -> Option<Plane> {
+        let points: Vec<Point3> = self
+            .edges
+            .iter()
+            .flat_map(|edge| {
+                let curve = edge.oriented_curve();
+                let division = curve.parameter_division(self.tol);
+                division.into_iter().map(move |t| curve.subs(t))
+            })
+            .collect();
+        let origin = *points.first()?;
+        let farthest = |points: &[Point3], distance: &dyn Fn(&Point3) -> f64| {
+            points
+                .iter()
+                .max_by(|p, q| distance(p).partial_cmp(&distance(q)).unwrap())
+                .copied()
+        };
+        let one = farthest(&points, &|p| p.distance2(origin))?;
+        let u = (one - origin).normalize();
+        let another = farthest(&points, &|p| (p - origin).cross(u).magnitude2())?;
+        let mut normal = u.cross(another - origin);
+        if normal.magnitude() < self.tol {
+            return None;
+        }
+        normal = normal.normalize();
+        let k = (0..3).max_by(|i, j| normal[*i].abs().partial_cmp(&normal[*j].abs()).unwrap())?;
+        if normal[k] < 0.0 {
+            normal = -normal;
+        }
+        if points.iter().any(|p| (p - origin).dot(normal).abs() > self.tol) {
+            return None;
+        }
+        let v = normal.cross(u);
+        Some(Plane::new(origin, origin + u, origin + v))
+    }
+
+    // the oriented edge of the half-edge: the edge `h / 2` inverted if `h` is odd
+    #[inline(always)]
+    fn half_edge(&self, h: usize) -> Edge {
+        match h % 2 {
+            0 => self.edges[h / 2].clone(),
+            _ => self.edges[h / 2].inverse(),
+        }
+    }
+
+    // the cycles of the half-edges with the regions on their left
+    fn trace_cycles(&self, alive: &[bool], plane: &Plane) -> Vec<Vec<usize>> {
+        let (u, v) = (plane.u_axis(), plane.v_axis());
+        let mut outgoing = HashMap::<VertexID, Vec<(usize, f64, f64)>>::new();
+        for h in (0..2 * self.edges.len()).filter(|h| alive[h / 2]) {
+            let edge = self.half_edge(h);
+            let curve = edge.oriented_curve();
+            let t = curve.parameter_range().0;
+            let (der, der2) = (curve.der(t), curve.der2(t));
+            let angle = f64::atan2(der.dot(v), der.dot(u)).rem_euclid(2.0 * PI);
+            // the signed curvature breaking the ties of the tangent curves
+            let curvature = der.cross(der2).dot(u.cross(v)) / der.magnitude().powi(3);
+            outgoing.entry(edge.front().id()).or_default().push((h, angle, curvature));
+        }
+        // sorts the outgoing half-edges counter-clockwise around each vertex
+        for list in outgoing.values_mut() {
+            list.sort_by(|(_, angle0, curvature0), (_, angle1, curvature1)| {
+                match (angle0 - angle1).abs() < TOLERANCE {
+                    true => curvature0.partial_cmp(curvature1).unwrap(),
+                    false => angle0.partial_cmp(angle1).unwrap(),
+                }
+            })
+        }
+        // the next half-edge turns the most clockwise from the twin of the previous one.
+        let next = |h: usize| {
+            let list = &outgoing[&self.half_edge(h).back().id()];
+            let pos = list.iter().position(|(g, _, _)| *g == h ^ 1).unwrap();
+            list[(pos + list.len() - 1) % list.len()].0
+        };
+        let mut visited = vec![false; 2 * self.edges.len()];
+        let mut cycles = Vec::new();
+        for h in (0..2 * self.edges.len()).filter(|h| alive[h / 2]) {
+            let mut cycle = Vec::new();
+            let mut g = h;
+            while !visited[g] {
+                visited[g] = true;
+                cycle.push(g);
+                g = next(g);
+            }
+            if !cycle.is_empty() {
+                cycles.push(cycle);
+            }
+        }
+        cycles
+    }
+
+    // the indices of the connected components of the vertices
+    fn components(&self, alive: &[bool]) -> HashMap<VertexID, usize> {
+        let mut adjacency = HashMap::<VertexID, Vec<&Vertex>>::new();
+        for (edge, _) in self.edges.iter().zip(alive).filter(|(_, alive)| **alive) {
+            adjacency.entry(edge.front().id()).or_default().push(edge.back());
+            adjacency.entry(edge.back().id()).or_default().push(edge.front());
+        }
+        let mut components = HashMap::new();
+        for (i, root) in self.vertices.iter().enumerate() {
+            if components.contains_key(&root.id()) {
+                continue;
+            }
+            components.insert(root.id(), i);
+            let mut stack = vec![root];
+            while let Some(vertex) = stack.pop() {
+                for next in adjacency.get(&vertex.id()).into_iter().flatten() {
+                    if components.insert(next.id(), i).is_none() {
+                        stack.push(next);
+                    }
+                }
+            }
+        }
+        components
+    }
+
+    // the polygon approximating the cycle in the coordinates of the plane
+    fn cycle_polygon(&self, cycle: &[usize], plane: &Plane) -> Vec<Point2> {
+        let (o, u, v) = (plane.origin(), plane.u_axis(), plane.v_axis());
+        cycle
+            .iter()
+            .flat_map(|h| {
+                let curve = self.half_edge(*h).oriented_curve();
+                let mut division = curve.parameter_division(self.tol);
+                division.pop();
+                division.into_iter().map(move |t| {
+                    let p = curve.subs(t) - o;
+                    Point2::new(p.dot(u), p.dot(v))
+                })
+            })
+            .collect()
+    }
+}
+
+// the signed area of the closed polygon, positive if counter-clockwise
+fn signed_area(polygon: &[Point2]) -> f64 {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| polygon[i].to_vec().perp_dot(polygon[(i + 1) % n].to_vec()))
+        .sum::<f64>()
+        / 2.0
+}
+
+// the winding number of the closed polygon around `point`
+fn winding_number(polygon: &[Point2], point: Point2) -> i32 {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| {
+            let (a, b) = (polygon[i] - point, polygon[(i + 1) % n] - point);
+            match (a[1] <= 0.0, b[1] <= 0.0) {
+                (true, false) if a.perp_dot(b) > 0.0 => 1,
+                (false, true) if a.perp_dot(b) < 0.0 => -1,
+                _ => 0,
+            }
+        })
+        .sum()
+}
+
+#[inline(always)]
+fn opposite<'a>(edge: &'a Edge, vertex: &Vertex) -> &'a Vertex {
+    match edge.front() == vertex {
+        true => edge.back(),
+        false => edge.front(),
+    }
+}
+
+// the vertices of the network, merged by the distances of the points
+#[derive(Debug)]
+struct VertexPool {
+    vertices: Vec<(Vertex, Point3)>,
+    tol: f64,
+}
+
+impl VertexPool {
+    #[inline(always)]
+    fn new(tol: f64) -> VertexPool {
+        VertexPool {
+            vertices: Vec::new(),
+            tol,
+        }
+    }
+
+    // returns the vertex in the pool which is `vertex` or is near `vertex`
+    fn get(&mut self, vertex: &Vertex) -> Vertex {
+        let point = *vertex.lock_point().unwrap();
+        let tol = self.tol;
+        let found = self
+            .vertices
+            .iter()
+            .find(|(v, pt)| v == vertex || pt.distance(point) < tol);
+        match found {
+            Some((v, _)) => v.clone(),
+            None => {
+                self.vertices.push((vertex.clone(), point));
+                vertex.clone()
+            }
+        }
+    }
+
+    // returns the vertex in the pool which is near `point`
+    fn get_by_point(&mut self, point: Point3) -> Vertex {
+        let tol = self.tol;
+        match self.vertices.iter().find(|(_, pt)| pt.distance(point) < tol) {
+            Some((v, _)) => v.clone(),
+            None => {
+                let vertex = Vertex::new(point);
+                self.vertices.push((vertex.clone(), point));
+                vertex
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn into_vertices(self) -> Vec<Vertex> { self.vertices.into_iter().map(|(v, _)| v).collect() }
+}
+
+// splits `curve` at the parameters of `nodes` and pushes the edges between the nodes
+fn split_curve(
+    curve: &Curve,
+    nodes: &[(f64, Vertex)],
+    pool: &mut VertexPool,
+    edges: &mut Vec<Edge>,
+) {
+    let mut rest = curve.clone();
+    let mut pieces = Vec::new();
+    for (t, _) in &nodes[1..nodes.len() - 1] {
+        let tail = rest.cut(*t);
+        pieces.push(rest);
+        rest = tail;
+    }
+    pieces.push(rest);
+    for (piece, node) in pieces.into_iter().zip(nodes.windows(2)) {
+        let ((t0, vertex0), (t1, vertex1)) = (&node[0], &node[1]);
+        if vertex0 != vertex1 {
+            edges.push(Edge::new(vertex0, vertex1, piece));
+        } else if piece.front().distance(piece.subs((t0 + t1) / 2.0)) >= pool.tol {
+            // the closed piece is split at the middle
+            let mut piece = piece;
+            let t = (t0 + t1) / 2.0;
+            let middle = pool.get_by_point(piece.subs(t));
+            let tail = piece.cut(t);
+            edges.push(Edge::new(vertex0, &middle, piece));
+            edges.push(Edge::new(&middle, vertex1, tail));
+        }
+    }
+}
+
+// the polyline approximating a curve
+#[derive(Debug)]
+struct Polyline {
+    parameters: Vec<f64>,
+    points: Vec<Point3>,
+    segment_boxes: Vec<BoundingBox<Point3>>,
+    bounding_box: BoundingBox<Point3>,
+}
+
+impl Polyline {
+    fn new(curve: &Curve, tol: f64) -> Polyline {
+        let parameters = curve.parameter_division(tol);
+        let points: Vec<Point3> = parameters.iter().map(|t| curve.subs(*t)).collect();
+        let segment_boxes: Vec<BoundingBox<Point3>> =
+            points.windows(2).map(|segment| segment.iter().collect()).collect();
+        let bounding_box = points.iter().collect();
+        Polyline {
+            parameters,
+            points,
+            segment_boxes,
+            bounding_box,
+        }
+    }
+
+    // the initial parameters of the intersections from the nearest points of the segments
+    fn candidates(&self, other: &Polyline, tol: f64) -> Vec<(f64, f64)> {
+        // the polylines deviate from the curves at most `tol`
+        let margin = 3.0 * tol;
+        if separated(&self.bounding_box, &other.bounding_box, margin) {
+            return Vec::new();
+        }
+        let mut res = Vec::new();
+        for (i, bdb0) in self.segment_boxes.iter().enumerate() {
+            for (j, bdb1) in other.segment_boxes.iter().enumerate() {
+                if separated(bdb0, bdb1, margin) {
+                    continue;
+                }
+                let segment0 = (self.points[i], self.points[i + 1]);
+                let segment1 = (other.points[j], other.points[j + 1]);
+                let (dist, s, t) = segment_distance(segment0, segment1);
+                if dist < margin {
+                    let (s0, s1) = (self.parameters[i], self.parameters[i + 1]);
+                    let (t0, t1) = (other.parameters[j], other.parameters[j + 1]);
+                    res.push((s0 + (s1 - s0) * s, t0 + (t1 - t0) * t));
+                }
+            }
+        }
+        res
+    }
+}
+
+// whether the bounding boxes are apart more than `margin`
+#[inline(always)]
+fn separated(bdb0: &BoundingBox<Point3>, bdb1: &BoundingBox<Point3>, margin: f64) -> bool {
+    (0..3).any(|k| bdb0.min()[k] > bdb1.max()[k] + margin || bdb1.min()[k] > bdb0.max()[k] + margin)
+}
+
+// Returns the distance between two segments and the parameters of the nearest points.
+fn segment_distance((p0, p1): (Point3, Point3), (q0, q1): (Point3, Point3)) -> (f64, f64, f64) {
+    let clamp = |x: f64| f64::min(f64::max(x, 0.0), 1.0);
+    let (d0, d1, r) = (p1 - p0, q1 - q0, p0 - q0);
+    let (a, e, f) = (d0.magnitude2(), d1.magnitude2(), d1.dot(r));
+    let (s, t) = if a < TOLERANCE2 && e < TOLERANCE2 {
+        (0.0, 0.0)
+    } else if a < TOLERANCE2 {
+        (0.0, clamp(f / e))
+    } else {
+        let c = d0.dot(r);
+        if e < TOLERANCE2 {
+            (clamp(-c / a), 0.0)
+        } else {
+            let b = d0.dot(d1);
+            let denom = a * e - b * b;
+            let s = match denom > TOLERANCE2 {
+                true => clamp((b * f - c * e) / denom),
+                false => 0.0,
+            };
+            let t = (b * s + f) / e;
+            if t < 0.0 {
+                (clamp(-c / a), 0.0)
+            } else if t > 1.0 {
+                (clamp((b - c) / a), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+    ((q0 + d1 * t).distance(p0 + d0 * s), s, t)
+}
+
+// Refines the parameters of the nearest points of two curves by the Gauss-Newton method.
+fn refine_intersection(curve0: &Curve, curve1: &Curve, mut s: f64, mut t: f64) -> (f64, f64) {
+    let clamp = |x: f64, (t0, t1): (f64, f64)| f64::min(f64::max(x, t0), t1);
+    let (range0, range1) = (curve0.parameter_range(), curve1.parameter_range());
+    for _ in 0..REFINEMENT_TRIALS {
+        let diff = curve0.subs(s) - curve1.subs(t);
+        let (der0, der1) = (curve0.der(s), curve1.der(t));
+        let (a, b, c) = (der0.magnitude2(), -der0.dot(der1), der1.magnitude2());
+        let det = a * c - b * b;
+        if det.so_small2() {
+            break;
+        }
+        let (r0, r1) = (-der0.dot(diff), der1.dot(diff));
+        let (ds, dt) = ((c * r0 - b * r1) / det, (a * r1 - b * r0) / det);
+        let (new_s, new_t) = (clamp(s + ds, range0), clamp(t + dt, range1));
+        let converged = (new_s - s).so_small() && (new_t - t).so_small();
+        s = new_s;
+        t = new_t;
+        if converged {
+            break;
+        }
+    }
+    (s, t)
+}
+
+#[test]
+fn closed_wires_of_curves() {
+    use std::f64::consts::PI;
+    // two crossing circles
+    let circle = |x: f64| {
+        let v = builder::vertex(Point3::new(x + 1.0, 0.0, 0.0));
+        let wire = builder::rsweep(&v, Point3::new(x, 0.0, 0.0), Vector3::unit_z(), Rad(2.0 * PI));
+        wire.edge_iter().map(|edge| edge.oriented_curve()).collect::<Vec<_>>()
+    };
+    let mut curves = circle(0.0);
+    curves.extend(circle(1.0));
+    let network = CurveNetwork::from_curves(&curves, 1.0e-6);
+    let wires = network.closed_wires();
+    assert_eq!(wires.len(), 3);
+    for wire in &wires {
+        assert!(wire.is_closed() && wire.is_simple());
+        assert!(wire.is_geometric_consistent());
+    }
+    let crossing = Point3::new(0.5, f64::sqrt(3.0) / 2.0, 0.0);
+    assert!(network.vertices().iter().any(|v| v.lock_point().unwrap().near(&crossing)));
+
+    // the closed polyline is split at the middle
+    let square = Curve::BSplineCurve(BSplineCurve::new(
+        KnotVec::uniform_knot(1, 4),
+        vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(1.0, 0.0, 0.0),
+            Vector3::new(1.0, 1.0, 0.0),
+            Vector3::new(0.0, 1.0, 0.0),
+            Vector3::new(0.0, 0.0, 0.0),
+        ],
+    ));
+    let network = CurveNetwork::from_curves(&[square], 1.0e-6);
+    assert_eq!(network.edges().len(), 2);
+    let wires = network.closed_wires();
+    assert_eq!(wires.len(), 1);
+    assert!(wires[0].is_closed() && wires[0].is_geometric_consistent());
+}
+
+#[test]
+fn planar_regions_of_circles() {
+    use std::f64::consts::PI;
+    let circle = |center: Point3, radius: f64| {
+        let v = builder::vertex(center + Vector3::new(radius, 0.0, 0.0));
+        let wire = builder::rsweep(&v, center, Vector3::unit_z(), Rad(2.0 * PI));
+        wire.edge_iter().map(|edge| edge.oriented_curve()).collect::<Vec<_>>()
+    };
+    let area = |face: &Face| {
+        let pad = builder::tsweep(face, Vector3::unit_z());
+        properties::mass_properties(&pad, 1.0e-3).volume
+    };
+    // the annulus and the disk in it
+    let mut curves = circle(Point3::origin(), 2.0);
+    curves.extend(circle(Point3::origin(), 1.0));
+    let regions = CurveNetwork::from_curves(&curves, 1.0e-6).planar_regions().unwrap();
+    assert_eq!(regions.len(), 2);
+    let annulus = regions.iter().find(|face| face.boundaries().len() == 2).unwrap();
+    assert!((area(annulus) - 3.0 * PI).abs() < 0.01, "{}", area(annulus));
+
+    // two crossing circles
+    let mut curves = circle(Point3::origin(), 1.0);
+    curves.extend(circle(Point3::new(1.0, 0.0, 0.0), 1.0));
+    let regions = CurveNetwork::from_curves(&curves, 1.0e-6).planar_regions().unwrap();
+    assert_eq!(regions.len(), 3);
+    let total: f64 = regions.iter().map(area).sum();
+    let lens = 2.0 * PI / 3.0 - f64::sqrt(3.0) / 2.0;
+    assert!((total - (2.0 * PI - lens)).abs() < 0.01, "{}", total);
+}
@@ -0,0 +1,302 @@
+use crate::direct_edit::{intersect_planes, is_line};
+use crate::features::{recognize_features, Feature, Fillet, Hole};
+use crate::*;
+use std::collections::{HashMap, HashSet};
+
+/// The options of defeaturing.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DefeatureOptions {
+    /// the holes whose diameters are less than this value are removed.
+    pub max_hole_diameter: f64,
+    /// the fillets whose radii are less than this value are removed.
+    pub max_fillet_radius: f64,
+    /// the tolerance of the feature recognition
+    pub tolerance: f64,
+}
+
+impl Default for DefeatureOptions {
+    #[inline(always)]
+    fn default() -> DefeatureOptions {
+        DefeatureOptions {
+            max_hole_diameter: 0.0,
+            max_fillet_radius: 0.0,
+            tolerance: 1.0e-3,
+        }
+    }
+}
+
+/// The topological modification healing the faces around removed features.
+#[derive(Clone, Default)]
+struct Healing {
+    removed_faces: HashSet<FaceID>,
+    surface_map: HashMap<FaceID, Surface>,
+    vertex_map: HashMap<VertexID, Vertex>,
+    edge_map: HashMap<EdgeID, Option<Edge>>,
+}
+
+/// Removes the small holes and fillets recognized by
+/// [`features::recognize_features`](../features/fn.recognize_features.html).
+///
+/// - The faces of a hole, and the bottom face if the hole is blind, are deleted,
+///   and the boundaries of the hole in the adjacent faces are removed.
+/// - A fillet face between two planar faces and bounded by two planar end faces is deleted,
+///   and the adjacent faces are extended to the intersection line of the two planes.
+///
+/// The features which cannot be removed are left in the result.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::defeature::*;
+/// const PI: Rad<f64> = Rad(std::f64::consts::PI);
+///
+/// // a profile with a rounded corner
+/// let v0 = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let v1 = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+/// let v2 = builder::vertex(Point3::new(1.0, 0.75, 0.0));
+/// let v3 = builder::vertex(Point3::new(0.0, 1.0, 0.0));
+/// let arc: Wire = builder::rsweep(&v2, Point3::new(0.75, 0.75, 0.0), Vector3::unit_z(), PI / 2.0);
+/// let mut wire: Wire = vec![builder::line(&v0, &v1), builder::line(&v1, arc.front_vertex().unwrap())].into();
+/// wire.extend(arc.iter().cloned());
+/// wire.push_back(builder::line(arc.back_vertex().unwrap(), &v3));
+/// wire.push_back(builder::line(&v3, &v0));
+/// let face = builder::try_attach_plane(&vec![wire]).unwrap();
+/// let solid = builder::tsweep(&face, Vector3::unit_z());
+///
+/// let options = DefeatureOptions {
+///     max_fillet_radius: 0.5,
+///     ..Default::default()
+/// };
+/// let solid = defeature(&solid, &options);
+/// assert_eq!(solid.boundaries()[0].len(), 6);
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert!((volume - 1.0).abs() < 1.0e-3);
+/// ```
+pub fn defeature(solid: &Solid, options: &DefeatureOptions) -> Solid {
+    let mut solid = solid.clone();
+    // Each removal changes the topology, so the features are recognized again.
+    'outer: loop {
+        for feature in recognize_features(&solid, options.tolerance) {
+            let healing = match &feature {
+                Feature::Hole(hole) if hole.diameter < options.max_hole_diameter => {
+                    Some(hole_healing(hole))
+                }
+                Feature::Fillet(fillet) if fillet.radius < options.max_fillet_radius => {
+                    fillet_healing(&solid, fillet)
+                }
+                _ => None,
+            };
+            if let Some(res) = healing.and_then(|healing| heal(&solid, &healing)) {
+                solid = res;
+                continue 'outer;
+            }
+        }
+        return solid;
+    }
+}
+
+fn hole_healing(hole: &Hole) -> Healing {
+    let mut healing = Healing::default();
+    healing.removed_faces.extend(hole.faces.iter().cloned());
+    healing.removed_faces.extend(hole.bottom.iter().cloned());
+    healing
+}
+
+fn fillet_healing(solid: &Solid, fillet: &Fillet) -> Option<Healing> {
+    if fillet.faces.len() != 1 {
+        return None;
+    }
+    let faces: Vec<&Face> = solid
+        .boundaries()
+        .iter()
+        .flat_map(|shell| shell.iter())
+        .collect();
+    let face = faces.iter().find(|face| face.id() == fillet.faces[0])?;
+    let boundaries = face.absolute_boundaries();
+    if boundaries.len() != 1 || boundaries[0].len() != 4 {
+        return None;
+    }
+    let edges: Vec<&Edge> = boundaries[0].iter().collect();
+    let neighbor = |edge: &Edge| {
+        faces.iter().find(|other| {
+            other.id() != face.id()
+                && other
+                    .absolute_boundaries()
+                    .iter()
+                    .flat_map(|wire| wire.iter())
+                    .any(|e| e.id() == edge.id())
+        })
+    };
+    // the lines along the fillet and the arcs at the ends
+    let offset = match is_line(&edges[0].lock_curve().unwrap()) {
+        true => 0,
+        false => 1,
+    };
+    let (line0, arc0, line1, arc1) = (
+        edges[offset],
+        edges[offset + 1],
+        edges[(offset + 2) % 4],
+        edges[(offset + 3) % 4],
+    );
+    if !is_line(&line1.lock_curve().unwrap()) {
+        return None;
+    }
+    let neighbors = [
+        neighbor(line0)?,
+        neighbor(line1)?,
+        neighbor(arc0)?,
+        neighbor(arc1)?,
+    ];
+    let planes = neighbors
+        .iter()
+        .map(|face| planar_surface(face))
+        .collect::<Option<Vec<Plane>>>()?;
+    let p = builder::vertex(intersect_planes(&[planes[0], planes[1], planes[2]])?);
+    let q = builder::vertex(intersect_planes(&[planes[0], planes[1], planes[3]])?);
+
+    let mut healing = Healing::default();
+    healing.removed_faces.insert(face.id());
+    // The extended faces are replaced by the planes.
+    for (face, plane) in neighbors.iter().zip(planes) {
+        let surface = match face.orientation() {
+            true => Surface::Plane(plane),
+            false => Surface::Plane(plane.inverse()),
+        };
+        healing.surface_map.insert(face.id(), surface);
+    }
+    for (arc, vertex) in [(arc0, &p), (arc1, &q)] {
+        healing.edge_map.insert(arc.id(), None);
+        healing.vertex_map.insert(arc.front().id(), vertex.clone());
+        healing.vertex_map.insert(arc.back().id(), vertex.clone());
+    }
+    let new_line = builder::line(&p, &q);
+    healing.edge_map.insert(line0.id(), Some(new_line.clone()));
+    healing.edge_map.insert(line1.id(), Some(new_line));
+    Some(healing)
+}
+
+/// Returns the plane directed to the orientation of the face if the surface is planar.
+fn planar_surface(face: &Face) -> Option<Plane> {
+    let surface = face.oriented_surface();
+    if let Surface::Plane(plane) = surface {
+        return Some(plane);
+    }
+    let ((u0, u1), (v0, v1)) = surface.parameter_range();
+    let (u, v) = ((u0 + u1) / 2.0, (v0 + v1) / 2.0);
+    let (center, normal) = (surface.subs(u, v), surface.normal(u, v));
+    const N: usize = 4;
+    let planar = (0..=N)
+        .flat_map(|i| (0..=N).map(move |j| (i, j)))
+        .all(|(i, j)| {
+            let pt = surface.subs(
+                u0 + (u1 - u0) * i as f64 / N as f64,
+                v0 + (v1 - v0) * j as f64 / N as f64,
+            );
+            (pt - center).dot(normal).so_small()
+        });
+    if !planar {
+        return None;
+    }
+    let uder = surface.uder(u, v);
+    let u_axis = (uder - normal * uder.dot(normal)).normalize();
+    let v_axis = normal.cross(u_axis);
+    Some(Plane::new(center, center + u_axis, center + v_axis))
+}
+
+/// Returns the mapped vertex.
+fn mapped_vertex(healing: &Healing, vertex: &Vertex) -> Vertex {
+    match healing.vertex_map.get(&vertex.id()) {
+        Some(got) => got.clone(),
+        None => vertex.clone(),
+    }
+}
+
+/// Rebuilds the solid by the healing. Returns `None` if the result is not a valid solid.
+fn heal(solid: &Solid, healing: &Healing) -> Option<Solid> {
+    let mut healing = healing.clone();
+    let faces = solid.boundaries().iter().flat_map(|shell| shell.iter());
+    // the edges of the removed faces are removed, and the edges incident to the mapped vertices are rebuilt.
+    for face in faces {
+        for edge in face
+            .absolute_boundaries()
+            .iter()
+            .flat_map(|wire| wire.iter())
+        {
+            if healing.edge_map.contains_key(&edge.id()) {
+                continue;
+            }
+            let (front, back) = (edge.absolute_front(), edge.absolute_back());
+            let mapped = healing.vertex_map.contains_key(&front.id())
+                || healing.vertex_map.contains_key(&back.id());
+            if mapped {
+                if !is_line(&edge.lock_curve().unwrap()) {
+                    return None;
+                }
+                let (v0, v1) = (
+                    mapped_vertex(&healing, front),
+                    mapped_vertex(&healing, back),
+                );
+                if v0 == v1 {
+                    return None;
+                }
+                healing
+                    .edge_map
+                    .insert(edge.id(), Some(builder::line(&v0, &v1)));
+            } else if healing.removed_faces.contains(&face.id()) {
+                healing.edge_map.insert(edge.id(), None);
+            }
+        }
+    }
+    let shells = solid
+        .boundaries()
+        .iter()
+        .map(|shell| {
+            shell
+                .iter()
+                .filter(|face| !healing.removed_faces.contains(&face.id()))
+                .map(|face| heal_face(face, &healing))
+                .collect::<Option<Vec<Face>>>()
+                .map(Shell::from)
+        })
+        .collect::<Option<Vec<Shell>>>()?;
+    Solid::try_new(shells).ok()
+}
+
+fn heal_face(face: &Face, healing: &Healing) -> Option<Face> {
+    let affected = face
+        .absolute_boundaries()
+        .iter()
+        .flat_map(|wire| wire.iter())
+        .any(|edge| healing.edge_map.contains_key(&edge.id()))
+        || healing.surface_map.contains_key(&face.id());
+    if !affected {
+        return Some(face.clone());
+    }
+    let wires: Vec<Wire> = face
+        .absolute_boundaries()
+        .iter()
+        .map(|wire| {
+            wire.iter()
+                .filter_map(|edge| match healing.edge_map.get(&edge.id()) {
+                    Some(Some(new_edge)) => {
+                        match new_edge.absolute_front() == &mapped_vertex(healing, edge.front()) {
+                            true => Some(new_edge.clone()),
+                            false => Some(new_edge.inverse()),
+                        }
+                    }
+                    Some(None) => None,
+                    None => Some(edge.clone()),
+                })
+                .collect::<Wire>()
+        })
+        .filter(|wire| !wire.is_empty())
+        .collect();
+    let surface = match healing.surface_map.get(&face.id()) {
+        Some(surface) => surface.clone(),
+        None => face.lock_surface().unwrap().clone(),
+    };
+    let mut res = Face::try_new(wires, surface).ok()?;
+    if !face.orientation() {
+        res.invert();
+    }
+    Some(res)
+}
@@ -0,0 +1,302 @@
+use crate::errors::Error;
+use crate::topo_traits::Mapped;
+use crate::*;
+use std::cell::Cell;
+
+/// The maximum number of the spans of an approximating curve.
+const MAX_CURVE_SPANS: usize = 1024;
+/// The maximum number of the spans of an approximating surface in each direction.
+const MAX_SURFACE_SPANS: usize = 64;
+
+/// Global deformations of the space.
+///
+/// The axis and the direction need not be normalized.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Deformation {
+    /// Scales the section orthogonal to the axis by `1 + rate * h`,
+    /// where `h` is the height of the point along the axis.
+    Taper {
+        /// the origin of the axis
+        origin: Point3,
+        /// the direction of the axis
+        axis: Vector3,
+        /// the rate of the scaling per unit length
+        rate: f64,
+    },
+    /// Rotates the section orthogonal to the axis by the angle `rate * h`,
+    /// where `h` is the height of the point along the axis.
+    Twist {
+        /// the origin of the axis
+        origin: Point3,
+        /// the direction of the axis
+        axis: Vector3,
+        /// the angle of the rotation per unit length
+        rate: Rad<f64>,
+    },
+    /// Bends the spine through `origin` along `axis` to the circle arc of radius `radius`,
+    /// whose center is in the side of `direction`.
+    /// The length along the spine is preserved.
+    Bend {
+        /// the origin of the spine
+        origin: Point3,
+        /// the direction of the spine
+        axis: Vector3,
+        /// the direction to the center of the bending
+        direction: Vector3,
+        /// the radius of the bent spine
+        radius: f64,
+    },
+}
+
+impl Deformation {
+    /// Returns the deformed point.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::deformation::Deformation;
+    /// use std::f64::consts::PI;
+    /// let twist = Deformation::Twist {
+    ///     origin: Point3::origin(),
+    ///     axis: Vector3::unit_z(),
+    ///     rate: Rad(PI / 2.0),
+    /// };
+    /// assert_near!(twist.apply(Point3::new(1.0, 0.0, 1.0)), Point3::new(0.0, 1.0, 1.0));
+    ///
+    /// let bend = Deformation::Bend {
+    ///     origin: Point3::origin(),
+    ///     axis: Vector3::unit_x(),
+    ///     direction: Vector3::unit_y(),
+    ///     radius: 2.0,
+    /// };
+    /// // a quarter of the circle
+    /// assert_near!(bend.apply(Point3::new(PI, 0.0, 0.5)), Point3::new(2.0, 2.0, 0.5));
+    /// ```
+    pub fn apply(&self, pt: Point3) -> Point3 {
+        match *self {
+            Deformation::Taper { origin, axis, rate } => {
+                let axis = axis.normalize();
+                let h = (pt - origin).dot(axis);
+                let radial = (pt - origin) - axis * h;
+                origin + axis * h + radial * (1.0 + rate * h)
+            }
+            Deformation::Twist { origin, axis, rate } => {
+                let axis = axis.normalize();
+                let h = (pt - origin).dot(axis);
+                let rot = Matrix3::from_axis_angle(axis, rate * h);
+                origin + rot * (pt - origin)
+            }
+            Deformation::Bend {
+                origin,
+                axis,
+                direction,
+                radius,
+            } => {
+                let axis = axis.normalize();
+                let direction = (direction - axis * direction.dot(axis)).normalize();
+                let vec = pt - origin;
+                let (x, y) = (vec.dot(axis), vec.dot(direction));
+                let rest = vec - axis * x - direction * y;
+                let theta = x / radius;
+                let r = radius - y;
+                origin + axis * (r * theta.sin()) + direction * (radius - r * theta.cos()) + rest
+            }
+        }
+    }
+
+    /// Returns the deformed solid. cf. [`deformed`](./fn.deformed.html)
+    #[inline(always)]
+    pub fn deform(&self, solid: &Solid, tol: f64) -> Result<Solid> {
+        deformed(solid, |pt| self.apply(pt), tol)
+    }
+}
+
+/// Returns the solid deformed by `mapping`.
+///
+/// The points of the vertices are mapped exactly, and the curves and the surfaces are
+/// re-approximated by cubic B-splines within the tolerance `tol`.
+/// The planar faces are approximated on the parameter range of their boundaries.
+/// The mapping is assumed to be smooth and to preserve the orientation.
+/// # Failures
+/// Returns [`Error::ApproximationFailed`] if some curves or surfaces cannot be approximated
+/// within the tolerance.
+///
+/// [`Error::ApproximationFailed`]: ../errors/enum.Error.html#variant.ApproximationFailed
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::deformation::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let taper = Deformation::Taper {
+///     origin: Point3::new(0.5, 0.5, 0.0),
+///     axis: Vector3::unit_z(),
+///     rate: -0.5,
+/// };
+/// let solid = taper.deform(&cube, 1.0e-3).unwrap();
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// // the area of the section at the height `z` is `(1 - z / 2)^2`.
+/// assert!((volume - 7.0 / 12.0).abs() < 1.0e-2);
+///
+/// let twist = Deformation::Twist {
+///     origin: Point3::new(0.5, 0.5, 0.0),
+///     axis: Vector3::unit_z(),
+///     rate: Rad(1.0),
+/// };
+/// let solid = twist.deform(&cube, 1.0e-3).unwrap();
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert!((volume - 1.0).abs() < 1.0e-2);
+/// ```
+pub fn deformed<F: Fn(Point3) -> Point3>(solid: &Solid, mapping: F, tol: f64) -> Result<Solid> {
+    let solid = bounded_planes(solid, tol);
+    let failed = Cell::new(false);
+    let res = solid.mapped(
+        &|pt: &Point3| mapping(*pt),
+        &|curve: &Curve| match approx_curve(curve, &mapping, tol) {
+            Some(curve) => Curve::BSplineCurve(curve),
+            None => {
+                failed.set(true);
+                curve.clone()
+            }
+        },
+        &|surface: &Surface| match approx_surface(surface, &mapping, tol) {
+            Some(surface) => Surface::BSplineSurface(surface),
+            None => {
+                failed.set(true);
+                surface.clone()
+            }
+        },
+    );
+    match failed.get() {
+        true => Err(Error::ApproximationFailed),
+        false => Ok(res),
+    }
+}
+
+/// Returns the clone of the solid whose planes are replaced by the bilinear surfaces
+/// covering the boundaries of the faces.
+pub(crate) fn bounded_planes(solid: &Solid, tol: f64) -> Solid {
+    let solid = builder::clone(solid);
+    for face in solid.boundaries().iter().flat_map(|shell| shell.iter()) {
+        let plane = match &*face.lock_surface().unwrap() {
+            Surface::Plane(plane) => *plane,
+            _ => continue,
+        };
+        let bdd: BoundingBox<Vector2> = face
+            .absolute_boundaries()
+            .iter()
+            .flat_map(|wire| wire.iter())
+            .flat_map(|edge| {
+                let curve = edge.lock_curve().unwrap().clone();
+                curve
+                    .parameter_division(tol)
+                    .into_iter()
+                    .map(move |t| curve.subs(t))
+                    .collect::<Vec<_>>()
+            })
+            .map(|pt| plane.get_parameter(pt).truncate())
+            .collect();
+        let (min, max) = (*bdd.min(), *bdd.max());
+        let knot_vecs = (
+            KnotVec::from(vec![min[0], min[0], max[0], max[0]]),
+            KnotVec::from(vec![min[1], min[1], max[1], max[1]]),
+        );
+        let control_points = vec![
+            vec![
+                plane.subs(min[0], min[1]).to_vec(),
+                plane.subs(min[0], max[1]).to_vec(),
+            ],
+            vec![
+                plane.subs(max[0], min[1]).to_vec(),
+                plane.subs(max[0], max[1]).to_vec(),
+            ],
+        ];
+        let surface = BSplineSurface::new(knot_vecs, control_points);
+        *face.lock_surface().unwrap() = Surface::BSplineSurface(surface);
+    }
+    solid
+}
+
+/// the clamped uniform knot vector of cubic B-spline on the range
+pub(crate) fn cubic_knot_vec((t0, t1): (f64, f64), division: usize) -> KnotVec {
+    KnotVec::uniform_knot(3, division)
+        .iter()
+        .map(|t| t0 + (t1 - t0) * t)
+        .collect()
+}
+
+/// the Greville abscissae of the cubic knot vector
+pub(crate) fn greville_abscissae(knot_vec: &KnotVec) -> Vec<f64> {
+    (0..knot_vec.len() - 4)
+        .map(|i| (knot_vec[i + 1] + knot_vec[i + 2] + knot_vec[i + 3]) / 3.0)
+        .collect()
+}
+
+/// Approximates the mapped curve by the Schoenberg variation diminishing spline,
+/// refining the knots until the error is within the tolerance.
+fn approx_curve<F: Fn(Point3) -> Point3>(
+    curve: &Curve,
+    mapping: &F,
+    tol: f64,
+) -> Option<BSplineCurve<Vector3>> {
+    let range = curve.parameter_range();
+    let target = |t: f64| mapping(curve.subs(t));
+    let mut division = 4;
+    while division <= MAX_CURVE_SPANS {
+        let knot_vec = cubic_knot_vec(range, division);
+        let control_points = greville_abscissae(&knot_vec)
+            .into_iter()
+            .map(|t| target(t).to_vec())
+            .collect();
+        let res = BSplineCurve::new(knot_vec, control_points);
+        let n = 2 * division;
+        let within = (0..n).all(|i| {
+            let t = range.0 + (range.1 - range.0) * (i as f64 + 0.5) / n as f64;
+            target(t).distance(Point3::from_vec(res.subs(t))) < tol
+        });
+        if within {
+            return Some(res);
+        }
+        division *= 2;
+    }
+    None
+}
+
+/// Approximates the mapped surface by the Schoenberg variation diminishing spline,
+/// refining the knots until the error is within the tolerance.
+pub(crate) fn approx_surface<F: Fn(Point3) -> Point3>(
+    surface: &Surface,
+    mapping: &F,
+    tol: f64,
+) -> Option<BSplineSurface<Vector3>> {
+    let (urange, vrange) = surface.parameter_range();
+    let target = |u: f64, v: f64| mapping(surface.subs(u, v));
+    let mut division = 4;
+    while division <= MAX_SURFACE_SPANS {
+        let knot_vecs = (
+            cubic_knot_vec(urange, division),
+            cubic_knot_vec(vrange, division),
+        );
+        let vs = greville_abscissae(&knot_vecs.1);
+        let control_points = greville_abscissae(&knot_vecs.0)
+            .into_iter()
+            .map(|u| vs.iter().map(|v| target(u, *v).to_vec()).collect())
+            .collect();
+        let res = BSplineSurface::new(knot_vecs, control_points);
+        let n = 2 * division;
+        let param = |(t0, t1): (f64, f64), i: usize| t0 + (t1 - t0) * (i as f64 + 0.5) / n as f64;
+        let within = (0..n).all(|i| {
+            (0..n).all(|j| {
+                let (u, v) = (param(urange, i), param(vrange, j));
+                target(u, v).distance(Point3::from_vec(res.subs(u, v))) < tol
+            })
+        });
+        if within {
+            return Some(res);
+        }
+        division *= 2;
+    }
+    None
+}
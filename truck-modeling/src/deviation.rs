@@ -0,0 +1,209 @@
+use crate::*;
+use crate::face_domain::FaceDomain;
+use truck_polymesh::PolygonMesh;
+
+/// The statistics of the deviations.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DeviationStatistics {
+    /// the minimum of the signed distances
+    pub min: f64,
+    /// the maximum of the signed distances
+    pub max: f64,
+    /// the average of the signed distances
+    pub mean: f64,
+    /// the root mean square of the signed distances
+    pub rms: f64,
+}
+
+/// The signed distances from the vertices of a mesh to a B-rep.
+///
+/// The distance is positive if the vertex is in the outside of the B-rep,
+/// i.e. on the side which the normals of the faces are directed to.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeviationMap {
+    /// the signed distance of each position of the mesh
+    pub values: Vec<f64>,
+    /// the statistics of `values`
+    pub statistics: DeviationStatistics,
+}
+
+impl DeviationMap {
+    /// Returns the number of values whose absolute value is not greater than `tol`.
+    #[inline(always)]
+    pub fn count_within(&self, tol: f64) -> usize {
+        self.values.iter().filter(|value| value.abs() <= tol).count()
+    }
+
+    /// Returns the color of each position in the color map which assigns
+    /// blue to `-range`, green to `0.0` and red to `range`.
+    /// The values out of the range are clamped.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::deviation::*;
+    /// let map = DeviationMap {
+    ///     values: vec![-1.0, 0.0, 0.5, 3.0],
+    ///     statistics: DeviationStatistics { min: -1.0, max: 3.0, mean: 0.625, rms: 1.6 },
+    /// };
+    /// let colors = map.colors(1.0);
+    /// assert_near!(colors[0], Vector3::new(0.0, 0.0, 1.0));
+    /// assert_near!(colors[1], Vector3::new(0.0, 1.0, 0.0));
+    /// assert_near!(colors[2], Vector3::new(0.5, 0.5, 0.0));
+    /// assert_near!(colors[3], Vector3::new(1.0, 0.0, 0.0));
+    /// ```
+    pub fn colors(&self, range: f64) -> Vec<Vector3> {
+        self.values
+            .iter()
+            .map(|value| {
+                let t = normalize_value(*value, range) * 2.0 - 1.0;
+                match t < 0.0 {
+                    true => Vector3::new(0.0, 1.0 + t, -t),
+                    false => Vector3::new(t, 1.0 - t, 0.0),
+                }
+            })
+            .collect()
+    }
+
+    /// Sets the uv coordinates of `mesh` so that the mesh is rendered as the color map
+    /// by a horizontal gradient texture.
+    ///
+    /// The u-coordinate of each vertex is `0.0` if the value is `-range`, `0.5` if the value is `0.0`,
+    /// and `1.0` if the value is `range`. The v-coordinate is always `0.5`.
+    /// # Panics
+    /// Panic occurs if the number of the positions of `mesh` is not the one of the values.
+    pub fn set_colormap_uv(&self, mesh: &mut PolygonMesh, range: f64) {
+        assert_eq!(
+            mesh.positions().len(),
+            self.values.len(),
+            "the mesh is not the one of the deviation map."
+        );
+        let editor = mesh.editor();
+        *editor.uv_coords = self
+            .values
+            .iter()
+            .map(|value| Vector2::new(normalize_value(*value, range), 0.5))
+            .collect();
+        editor
+            .faces
+            .face_iter_mut()
+            .flatten()
+            .for_each(|v| v.uv = Some(v.pos));
+    }
+}
+
+fn normalize_value(value: f64, range: f64) -> f64 {
+    match range.so_small() {
+        true => 0.5,
+        false => (value / range * 0.5 + 0.5).clamp(0.0, 1.0),
+    }
+}
+
+/// Computes the signed distances from all positions of `mesh` to the boundary of `solid`.
+///
+/// The distance from a point to a face is the distance to the foot on the surface
+/// if the foot is in the face, or the distance to the boundary of the face otherwise.
+/// The sign is determined by the normal of the nearest face.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::deviation::*;
+/// use truck_polymesh::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let positions = vec![
+///     Point3::new(0.5, 0.5, 1.1),
+///     Point3::new(0.5, 0.4, 0.8),
+///     Point3::new(1.2, 0.5, 1.2),
+/// ];
+/// let faces = Faces::from_iter(&[[0, 1, 2]]);
+/// let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+/// let map = deviation::deviation_map(&mesh, &cube);
+/// assert_near!(map.values[0], 0.1);
+/// assert_near!(map.values[1], -0.2);
+/// assert_near!(map.values[2], f64::sqrt(0.08));
+/// assert_near!(map.statistics.min, -0.2);
+/// assert_near!(map.statistics.max, f64::sqrt(0.08));
+/// ```
+pub fn deviation_map(mesh: &PolygonMesh, solid: &Solid) -> DeviationMap {
+    let domains: Vec<FaceDomain> = solid
+        .boundaries()
+        .iter()
+        .flatten()
+        .map(|face| FaceDomain::new(face, BOUNDARY_TOLERANCE))
+        .collect();
+    let values: Vec<f64> = mesh
+        .positions()
+        .iter()
+        .map(|pt| {
+            domains
+                .iter()
+                .map(|domain| signed_distance(domain, *pt))
+                .fold(None, |res: Option<f64>, dist| match res {
+                    Some(res) if res.abs() <= dist.abs() => Some(res),
+                    _ => Some(dist),
+                })
+                .unwrap_or(f64::INFINITY)
+        })
+        .collect();
+    let statistics = match values.is_empty() {
+        true => DeviationStatistics {
+            min: 0.0,
+            max: 0.0,
+            mean: 0.0,
+            rms: 0.0,
+        },
+        false => {
+            let len = values.len() as f64;
+            DeviationStatistics {
+                min: values.iter().fold(f64::INFINITY, |min, x| f64::min(min, *x)),
+                max: values.iter().fold(f64::NEG_INFINITY, |max, x| f64::max(max, *x)),
+                mean: values.iter().sum::<f64>() / len,
+                rms: f64::sqrt(values.iter().map(|x| x * x).sum::<f64>() / len),
+            }
+        }
+    };
+    DeviationMap { values, statistics }
+}
+
+const BOUNDARY_TOLERANCE: f64 = 0.01;
+
+fn signed_distance(domain: &FaceDomain, pt: Point3) -> f64 {
+    let (u, v) = domain.search_parameter(pt, domain.presearch(pt));
+    let foot = domain.surface.subs(u, v);
+    let normal = domain.surface.normal(u, v);
+    let (dist, nearest) = match domain.include(Vector2::new(u, v)) {
+        true => (pt.distance(foot), foot),
+        false => domain
+            .boundary_points
+            .iter()
+            .flat_map(|points| {
+                let len = points.len();
+                (0..len).map(move |i| nearest_on_segment(pt, points[i], points[(i + 1) % len]))
+            })
+            .fold((f64::INFINITY, foot), |(min, res), q| {
+                let dist = pt.distance(q);
+                match dist < min {
+                    true => (dist, q),
+                    false => (min, res),
+                }
+            }),
+    };
+    match (pt - nearest).dot(normal) < 0.0 {
+        true => -dist,
+        false => dist,
+    }
+}
+
+fn nearest_on_segment(pt: Point3, p: Point3, q: Point3) -> Point3 {
+    let vec = q - p;
+    match vec.so_small() {
+        true => p,
+        false => {
+            let t = ((pt - p).dot(vec) / vec.magnitude2()).clamp(0.0, 1.0);
+            p + vec * t
+        }
+    }
+}
@@ -0,0 +1,394 @@
+use crate::errors::Error;
+use crate::*;
+use std::collections::HashMap;
+
+/// The new geometry of the tweaked face.
+#[derive(Clone, Debug)]
+pub enum Tweak {
+    /// offsets the plane along the normal of the face
+    Offset(f64),
+    /// replaces the surface by the plane, whose normal is directed to the outside
+    Plane(Plane),
+}
+
+/// The correspondence between a solid and its topological clone.
+struct CloneMap {
+    solid: Solid,
+    vertices: HashMap<VertexID, Vertex>,
+    faces: HashMap<FaceID, Face>,
+}
+
+impl CloneMap {
+    fn new(solid: &Solid) -> CloneMap {
+        let clone = builder::clone(solid);
+        let mut res = CloneMap {
+            solid: clone.clone(),
+            vertices: HashMap::new(),
+            faces: HashMap::new(),
+        };
+        let shells = solid.boundaries().iter().zip(clone.boundaries());
+        for (face0, face1) in shells.flat_map(|(shell0, shell1)| shell0.iter().zip(shell1.iter())) {
+            res.faces.insert(face0.id(), face1.clone());
+            let wires = face0
+                .absolute_boundaries()
+                .iter()
+                .zip(face1.absolute_boundaries());
+            for (edge0, edge1) in wires.flat_map(|(wire0, wire1)| wire0.iter().zip(wire1.iter())) {
+                let (v0, v1) = (edge0.absolute_front(), edge0.absolute_back());
+                res.vertices.insert(v0.id(), edge1.absolute_front().clone());
+                res.vertices.insert(v1.id(), edge1.absolute_back().clone());
+            }
+        }
+        res
+    }
+
+    /// the faces incident to the vertex
+    fn incident_faces(&self, vertex: &Vertex) -> Vec<Face> {
+        self.solid
+            .boundaries()
+            .iter()
+            .flat_map(|shell| shell.iter())
+            .filter(|face| {
+                face.absolute_boundaries()
+                    .iter()
+                    .flat_map(|wire| wire.vertex_iter())
+                    .any(|v| v == *vertex)
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// the edges incident to the vertex
+    fn incident_edges(&self, vertex: &Vertex) -> Vec<Edge> {
+        let mut edges: Vec<Edge> = Vec::new();
+        self.solid
+            .boundaries()
+            .iter()
+            .flat_map(|shell| shell.iter())
+            .flat_map(|face| {
+                face.absolute_boundaries()
+                    .iter()
+                    .flat_map(|wire| wire.iter())
+            })
+            .filter(|edge| edge.absolute_front() == vertex || edge.absolute_back() == vertex)
+            .for_each(|edge| {
+                if edges.iter().all(|e| e.id() != edge.id()) {
+                    edges.push(edge.clone());
+                }
+            });
+        edges
+    }
+}
+
+/// Returns the plane of the face in the direction of the face orientation.
+///
+/// The flat B-spline or NURBS surfaces, e.g. the ones created by `builder::tsweep`,
+/// are also converted to the planes with the same parameters at the corners.
+pub(crate) fn oriented_plane(face: &Face) -> Result<Plane> {
+    let surface = match face.oriented_surface() {
+        Surface::Plane(plane) => return Ok(plane),
+        surface => surface,
+    };
+    let ((u0, u1), (v0, v1)) = surface.parameter_range();
+    let plane = Plane::new(
+        surface.subs(u0, v0),
+        surface.subs(u1, v0),
+        surface.subs(u0, v1),
+    );
+    let normal = plane.u_axis().cross(plane.v_axis());
+    if normal.so_small() {
+        return Err(Error::UnsupportedGeometry);
+    }
+    const N: usize = 4;
+    let flat = (0..=N).all(|i| {
+        (0..=N).all(|j| {
+            let u = u0 + (u1 - u0) * i as f64 / N as f64;
+            let v = v0 + (v1 - v0) * j as f64 / N as f64;
+            let pt = surface.subs(u, v);
+            (pt - plane.origin()).dot(plane.normal()).so_small()
+                && surface.normal(u, v).near(&plane.normal())
+        })
+    });
+    match flat {
+        true => Ok(plane),
+        false => Err(Error::UnsupportedGeometry),
+    }
+}
+
+/// Sets the plane of the face, which is directed to the face orientation.
+fn set_oriented_plane(face: &Face, plane: Plane) {
+    let surface = match face.orientation() {
+        true => Surface::Plane(plane),
+        false => Surface::Plane(plane.inverse()),
+    };
+    *face.lock_surface().unwrap() = surface;
+}
+
+/// Returns whether the curve is a line segment.
+pub(crate) fn is_line(curve: &Curve) -> bool {
+    let (t0, t1) = curve.parameter_range();
+    let (p, q) = (curve.subs(t0), curve.subs(t1));
+    (1..4).all(|i| {
+        let t = t0 + (t1 - t0) * i as f64 / 4.0;
+        let pt = curve.subs(t);
+        let vec = q - p;
+        let s = (pt - p).dot(vec) / vec.magnitude2();
+        pt.near(&(p + vec * s))
+    })
+}
+
+/// Returns `Error::UnsupportedGeometry` if some edges are not lines.
+fn check_lines(edges: &[Edge]) -> Result<()> {
+    match edges
+        .iter()
+        .all(|edge| is_line(&edge.lock_curve().unwrap()))
+    {
+        true => Ok(()),
+        false => Err(Error::UnsupportedGeometry),
+    }
+}
+
+/// Replaces the curves of the edges by the lines between the end points.
+fn update_lines(edges: &[Edge]) {
+    for edge in edges {
+        let p = *edge.absolute_front().lock_point().unwrap();
+        let q = *edge.absolute_back().lock_point().unwrap();
+        let mut curve = edge.lock_curve().unwrap();
+        *curve = match &*curve {
+            Curve::BSplineCurve(_) => Curve::BSplineCurve(geom_impls::line(p.to_vec(), q.to_vec())),
+            Curve::NURBSCurve(_) => Curve::NURBSCurve(NURBSCurve::new(geom_impls::line(
+                p.to_homogeneous(),
+                q.to_homogeneous(),
+            ))),
+        };
+    }
+}
+
+/// Returns the intersection point of the planes, or `None` if the point is not determined.
+pub(crate) fn intersect_planes(planes: &[Plane]) -> Option<Point3> {
+    let det = |i: usize, j: usize, k: usize| {
+        let (n0, n1, n2) = (planes[i].normal(), planes[j].normal(), planes[k].normal());
+        n0.dot(n1.cross(n2))
+    };
+    // the most independent triple
+    let len = planes.len();
+    let (i, j, k) = (0..len)
+        .flat_map(|i| (i + 1..len).flat_map(move |j| (j + 1..len).map(move |k| (i, j, k))))
+        .max_by(|a, b| {
+            let (x, y) = (det(a.0, a.1, a.2).abs(), det(b.0, b.1, b.2).abs());
+            x.partial_cmp(&y).unwrap()
+        })?;
+    let d = det(i, j, k);
+    if d.so_small() {
+        return None;
+    }
+    let (n0, n1, n2) = (planes[i].normal(), planes[j].normal(), planes[k].normal());
+    let (d0, d1, d2) = (
+        n0.dot(planes[i].origin().to_vec()),
+        n1.dot(planes[j].origin().to_vec()),
+        n2.dot(planes[k].origin().to_vec()),
+    );
+    let pt = Point3::from_vec((n1.cross(n2) * d0 + n2.cross(n0) * d1 + n0.cross(n1) * d2) / d);
+    match planes
+        .iter()
+        .all(|plane| (pt - plane.origin()).dot(plane.normal()).so_small())
+    {
+        true => Some(pt),
+        false => None,
+    }
+}
+
+/// Moves the planar face of the solid, and re-intersects the adjacent planar faces.
+///
+/// The vertices of the face are moved to the intersection points of the new plane and the
+/// planes of the adjacent faces, and the edges incident to them are replaced by the lines.
+/// The original solid is not modified.
+/// # Failures
+/// - Returns [`Error::ElementNotFound`] if `face` is not a face of `solid`.
+/// - Returns [`Error::UnsupportedGeometry`] if the face, the faces adjacent to the vertices
+///   of the face, or the edges incident to them are not planes and lines, respectively.
+/// - Returns [`Error::DegenerateIntersection`] if the planes do not meet at one point.
+///
+/// [`Error::ElementNotFound`]: ../errors/enum.Error.html#variant.ElementNotFound
+/// [`Error::UnsupportedGeometry`]: ../errors/enum.Error.html#variant.UnsupportedGeometry
+/// [`Error::DegenerateIntersection`]: ../errors/enum.Error.html#variant.DegenerateIntersection
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::direct_edit::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// // the top face
+/// let top = cube.boundaries()[0]
+///     .iter()
+///     .find(|face| face.oriented_surface().normal(0.5, 0.5).near(&Vector3::unit_z()))
+///     .unwrap();
+/// let solid = tweak_face(&cube, top, Tweak::Offset(0.5)).unwrap();
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert_near!(volume, 1.5);
+/// // the original solid is not modified
+/// assert_near!(properties::mass_properties(&cube, 1.0e-3).volume, 1.0);
+/// ```
+pub fn tweak_face(solid: &Solid, face: &Face, tweak: Tweak) -> Result<Solid> {
+    let map = CloneMap::new(solid);
+    let target = map.faces.get(&face.id()).ok_or(Error::ElementNotFound)?;
+    let plane = oriented_plane(target)?;
+    let new_plane = match tweak {
+        Tweak::Offset(offset) => {
+            plane.transformed(Matrix4::from_translation(plane.normal() * offset))
+        }
+        Tweak::Plane(plane) => plane,
+    };
+    let vertices: Vec<Vertex> = target
+        .absolute_boundaries()
+        .iter()
+        .flat_map(|wire| wire.vertex_iter())
+        .collect();
+    let mut points = Vec::new();
+    for vertex in &vertices {
+        let mut planes = vec![new_plane];
+        for adjacent in map.incident_faces(vertex) {
+            if adjacent.id() != target.id() {
+                planes.push(oriented_plane(&adjacent)?);
+            }
+        }
+        points.push(intersect_planes(&planes).ok_or(Error::DegenerateIntersection)?);
+    }
+    let edges: Vec<Edge> = vertices
+        .iter()
+        .flat_map(|vertex| map.incident_edges(vertex))
+        .collect();
+    check_lines(&edges)?;
+    vertices
+        .iter()
+        .zip(points)
+        .for_each(|(vertex, pt)| *vertex.lock_point().unwrap() = pt);
+    update_lines(&edges);
+    set_oriented_plane(target, new_plane);
+    Ok(map.solid)
+}
+
+/// Moves the vertex of the solid, and re-fits the incident lines and planes.
+///
+/// The original solid is not modified.
+/// # Failures
+/// - Returns [`Error::ElementNotFound`] if `vertex` is not a vertex of `solid`.
+/// - Returns [`Error::UnsupportedGeometry`] if the incident edges and faces are not
+///   lines and planes, respectively.
+/// - Returns [`Error::NonPlanarFace`] if the vertices of an incident face are no longer in one plane.
+///
+/// [`Error::ElementNotFound`]: ../errors/enum.Error.html#variant.ElementNotFound
+/// [`Error::UnsupportedGeometry`]: ../errors/enum.Error.html#variant.UnsupportedGeometry
+/// [`Error::NonPlanarFace`]: ../errors/enum.Error.html#variant.NonPlanarFace
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::direct_edit::*;
+/// use truck_modeling::errors::Error;
+/// // a triangular pyramid
+/// let v: Vec<Vertex> = vec![
+///     builder::vertex(Point3::new(0.0, 0.0, 0.0)),
+///     builder::vertex(Point3::new(1.0, 0.0, 0.0)),
+///     builder::vertex(Point3::new(0.0, 1.0, 0.0)),
+///     builder::vertex(Point3::new(0.0, 0.0, 1.0)),
+/// ];
+/// let edge = |i: usize, j: usize| builder::line(&v[i], &v[j]);
+/// let (e01, e12, e20) = (edge(0, 1), edge(1, 2), edge(2, 0));
+/// let (e03, e13, e23) = (edge(0, 3), edge(1, 3), edge(2, 3));
+/// let wires: Vec<Wire> = vec![
+///     vec![e01.inverse(), e20.inverse(), e12.inverse()].into(),
+///     vec![e01.clone(), e13.clone(), e03.inverse()].into(),
+///     vec![e12.clone(), e23.clone(), e13.inverse()].into(),
+///     vec![e20.clone(), e03.clone(), e23.inverse()].into(),
+/// ];
+/// let shell: Shell = wires
+///     .iter()
+///     .map(|wire| builder::try_attach_plane(&vec![wire.clone()]).unwrap())
+///     .collect();
+/// let pyramid = Solid::new(vec![shell]);
+///
+/// let solid = move_vertex(&pyramid, &v[3], Point3::new(0.0, 0.0, 2.0)).unwrap();
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert_near!(volume, 1.0 / 3.0);
+///
+/// // The vertex of a cube cannot be moved freely.
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+/// let other = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// assert_eq!(
+///     move_vertex(&cube, &other, Point3::new(-0.5, -0.5, -0.5)).unwrap_err(),
+///     Error::ElementNotFound,
+/// );
+/// let vertex = cube.boundaries()[0][0].boundaries()[0].front_vertex().unwrap().clone();
+/// assert_eq!(
+///     move_vertex(&cube, &vertex, Point3::new(-0.5, -0.5, -0.5)).unwrap_err(),
+///     Error::NonPlanarFace,
+/// );
+/// ```
+pub fn move_vertex(solid: &Solid, vertex: &Vertex, point: Point3) -> Result<Solid> {
+    let map = CloneMap::new(solid);
+    let target = map
+        .vertices
+        .get(&vertex.id())
+        .ok_or(Error::ElementNotFound)?;
+    let faces = map.incident_faces(target);
+    let edges = map.incident_edges(target);
+    check_lines(&edges)?;
+    let planes = faces
+        .iter()
+        .map(oriented_plane)
+        .collect::<Result<Vec<Plane>>>()?;
+    *target.lock_point().unwrap() = point;
+    let mut new_planes = Vec::new();
+    for (face, plane) in faces.iter().zip(planes) {
+        let points: Vec<Point3> = face
+            .absolute_boundaries()
+            .iter()
+            .flat_map(|wire| wire.vertex_iter())
+            .map(|v| *v.lock_point().unwrap())
+            .collect();
+        new_planes.push(fit_plane(&points, &plane).ok_or(Error::NonPlanarFace)?);
+    }
+    update_lines(&edges);
+    faces
+        .iter()
+        .zip(new_planes)
+        .for_each(|(face, plane)| set_oriented_plane(face, plane));
+    Ok(map.solid)
+}
+
+/// Returns the plane through the points, whose parameterization is the projection of `plane`.
+fn fit_plane(points: &[Point3], plane: &Plane) -> Option<Plane> {
+    let center = points
+        .iter()
+        .fold(Point3::origin(), |sum, pt| sum + pt.to_vec())
+        / points.len() as f64;
+    let len = points.len();
+    let normal = (0..len).fold(Vector3::zero(), |sum, i| {
+        sum + (points[i] - center).cross(points[(i + 1) % len] - center)
+    });
+    if normal.so_small() {
+        return None;
+    }
+    let normal = normal.normalize();
+    let normal = match normal.dot(plane.normal()) < 0.0 {
+        true => -normal,
+        false => normal,
+    };
+    if points
+        .iter()
+        .any(|pt| !(pt - center).dot(normal).so_small())
+    {
+        return None;
+    }
+    let project = |pt: Point3| pt - normal * (pt - center).dot(normal);
+    let o = project(plane.origin());
+    let p = project(plane.origin() + plane.u_axis());
+    let q = project(plane.origin() + plane.v_axis());
+    Some(Plane::new(o, p, q))
+}
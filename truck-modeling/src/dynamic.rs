@@ -0,0 +1,418 @@
+use crate::errors::Error;
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::io::Write;
+use truck_base::batch;
+use truck_polymesh::{obj, PolygonMesh, StructuredMesh};
+use truck_topology::{CompressedShell, CompressedSolid};
+
+/// Type-erased curve
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum AnyCurve {
+    /// parametric curve
+    Curve(Curve),
+    /// polyline
+    Polyline(Vec<Point3>),
+}
+
+/// Type-erased surface
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub enum AnySurface {
+    /// parametric surface
+    Surface(Surface),
+    /// polygon mesh
+    Mesh(PolygonMesh),
+}
+
+/// Type-erased shape, topological elements or a polygon mesh.
+///
+/// The shape is serialized with the compressed topology by `serde`.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::dynamic::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let mut shape = AnyShape::from(builder::tsweep(&f, Vector3::unit_z()));
+/// assert_eq!(shape.type_name(), "Solid");
+///
+/// shape.transform_by(Matrix4::from_translation(Vector3::new(1.0, 2.0, 3.0)));
+/// let bdd = shape.tessellate(0.01).bounding_box();
+/// assert_near!(*bdd.min(), Point3::new(1.0, 2.0, 3.0));
+/// assert_near!(*bdd.max(), Point3::new(2.0, 3.0, 4.0));
+///
+/// let json = serde_json::to_string(&shape).unwrap();
+/// let shape: AnyShape = serde_json::from_str(&json).unwrap();
+/// assert_eq!(shape.type_name(), "Solid");
+///
+/// let mut obj = Vec::new();
+/// shape.write_obj(0.01, &mut obj).unwrap();
+/// assert!(String::from_utf8(obj).unwrap().contains("\nf "));
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(into = "AnyShapeData", try_from = "AnyShapeData")]
+pub enum AnyShape {
+    /// vertex
+    Vertex(Vertex),
+    /// edge
+    Edge(Edge),
+    /// wire
+    Wire(Wire),
+    /// face
+    Face(Face),
+    /// shell
+    Shell(Shell),
+    /// solid
+    Solid(Solid),
+    /// polygon mesh
+    Mesh(PolygonMesh),
+}
+
+/// The result of tessellation of type-erased objects.
+#[derive(Clone, Debug)]
+pub enum Tessellation {
+    /// points
+    Points(Vec<Point3>),
+    /// polylines
+    Polylines(Vec<Vec<Point3>>),
+    /// polygon mesh
+    Mesh(PolygonMesh),
+}
+
+impl Tessellation {
+    /// Returns the bounding box of the tessellation.
+    pub fn bounding_box(&self) -> BoundingBox<Point3> {
+        match self {
+            Tessellation::Points(points) => points.iter().collect(),
+            Tessellation::Polylines(polylines) => polylines.iter().flatten().collect(),
+            Tessellation::Mesh(mesh) => mesh.bounding_box(),
+        }
+    }
+
+    /// Writes the tessellation in the wavefront obj format.
+    /// Points and polylines are written as `p` and `l` elements, respectively.
+    pub fn write_obj<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        match self {
+            Tessellation::Points(points) => {
+                write_obj_vertices(&mut writer, points)?;
+                (1..=points.len()).try_for_each(|i| writer.write_fmt(format_args!("p {}\n", i)))
+            }
+            Tessellation::Polylines(polylines) => {
+                let mut offset = 0;
+                for polyline in polylines {
+                    write_obj_vertices(&mut writer, polyline)?;
+                    writer.write_all(b"l")?;
+                    for i in 1..=polyline.len() {
+                        writer.write_fmt(format_args!(" {}", offset + i))?;
+                    }
+                    writer.write_all(b"\n")?;
+                    offset += polyline.len();
+                }
+                Ok(())
+            }
+            Tessellation::Mesh(mesh) => obj::write(mesh, writer).map_err(|error| match error {
+                truck_polymesh::errors::Error::FromIO(error) => error,
+                error => std::io::Error::other(error.to_string()),
+            }),
+        }
+    }
+}
+
+fn write_obj_vertices<W: Write>(writer: &mut W, points: &[Point3]) -> std::io::Result<()> {
+    points
+        .iter()
+        .try_for_each(|pt| writer.write_fmt(format_args!("v {} {} {}\n", pt[0], pt[1], pt[2])))
+}
+
+fn transform_mesh(mesh: &mut PolygonMesh, mat: Matrix4) {
+    let editor = mesh.editor();
+    batch::transform_points(&mat, &mut editor.positions[..]);
+    batch::transform_normals(&mat, &mut editor.normals[..]);
+}
+
+fn polyline(curve: &Curve, tol: f64) -> Vec<Point3> {
+    curve
+        .parameter_division(tol)
+        .into_iter()
+        .map(|t| curve.subs(t))
+        .collect()
+}
+
+impl AnyCurve {
+    /// Returns the name of the type.
+    #[inline(always)]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            AnyCurve::Curve(_) => "Curve",
+            AnyCurve::Polyline(_) => "Polyline",
+        }
+    }
+
+    /// Transforms the curve by `mat`.
+    pub fn transform_by(&mut self, mat: Matrix4) {
+        match self {
+            AnyCurve::Curve(curve) => curve.transform_by(mat),
+            AnyCurve::Polyline(points) => batch::transform_points(&mat, points),
+        }
+    }
+
+    /// Returns the curve transformed by `mat`.
+    #[inline(always)]
+    pub fn transformed(&self, mat: Matrix4) -> AnyCurve {
+        let mut res = self.clone();
+        res.transform_by(mat);
+        res
+    }
+
+    /// Returns the polyline approximating the curve within `tol`.
+    pub fn tessellate(&self, tol: f64) -> Tessellation {
+        match self {
+            AnyCurve::Curve(curve) => Tessellation::Polylines(vec![polyline(curve, tol)]),
+            AnyCurve::Polyline(points) => Tessellation::Polylines(vec![points.clone()]),
+        }
+    }
+
+    /// Writes the polyline approximating the curve in the wavefront obj format.
+    #[inline(always)]
+    pub fn write_obj<W: Write>(&self, tol: f64, writer: W) -> std::io::Result<()> {
+        self.tessellate(tol).write_obj(writer)
+    }
+}
+
+impl AnySurface {
+    /// Returns the name of the type.
+    #[inline(always)]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            AnySurface::Surface(_) => "Surface",
+            AnySurface::Mesh(_) => "Mesh",
+        }
+    }
+
+    /// Transforms the surface by `mat`.
+    pub fn transform_by(&mut self, mat: Matrix4) {
+        match self {
+            AnySurface::Surface(surface) => surface.transform_by(mat),
+            AnySurface::Mesh(mesh) => transform_mesh(mesh, mat),
+        }
+    }
+
+    /// Returns the surface transformed by `mat`.
+    #[inline(always)]
+    pub fn transformed(&self, mat: Matrix4) -> AnySurface {
+        let mut res = self.clone();
+        res.transform_by(mat);
+        res
+    }
+
+    /// Returns the mesh approximating the whole parameter range of the surface within `tol`.
+    pub fn tessellate(&self, tol: f64) -> Tessellation {
+        match self {
+            AnySurface::Surface(surface) => {
+                Tessellation::Mesh(StructuredMesh::from_surface(surface, tol).destruct())
+            }
+            AnySurface::Mesh(mesh) => Tessellation::Mesh(mesh.clone()),
+        }
+    }
+
+    /// Writes the mesh approximating the surface in the wavefront obj format.
+    #[inline(always)]
+    pub fn write_obj<W: Write>(&self, tol: f64, writer: W) -> std::io::Result<()> {
+        self.tessellate(tol).write_obj(writer)
+    }
+}
+
+impl AnyShape {
+    /// Returns the name of the type.
+    #[inline(always)]
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            AnyShape::Vertex(_) => "Vertex",
+            AnyShape::Edge(_) => "Edge",
+            AnyShape::Wire(_) => "Wire",
+            AnyShape::Face(_) => "Face",
+            AnyShape::Shell(_) => "Shell",
+            AnyShape::Solid(_) => "Solid",
+            AnyShape::Mesh(_) => "Mesh",
+        }
+    }
+
+    /// Returns the shape transformed by `mat`.
+    /// The topological elements are cloned, and the geometries are not shared with `self`.
+    pub fn transformed(&self, mat: Matrix4) -> AnyShape {
+        match self {
+            AnyShape::Vertex(vertex) => AnyShape::Vertex(builder::transformed(vertex, mat)),
+            AnyShape::Edge(edge) => AnyShape::Edge(builder::transformed(edge, mat)),
+            AnyShape::Wire(wire) => AnyShape::Wire(builder::transformed(wire, mat)),
+            AnyShape::Face(face) => AnyShape::Face(builder::transformed(face, mat)),
+            AnyShape::Shell(shell) => AnyShape::Shell(builder::transformed(shell, mat)),
+            AnyShape::Solid(solid) => AnyShape::Solid(builder::transformed(solid, mat)),
+            AnyShape::Mesh(mesh) => {
+                let mut mesh = mesh.clone();
+                transform_mesh(&mut mesh, mat);
+                AnyShape::Mesh(mesh)
+            }
+        }
+    }
+
+    /// Transforms the shape by `mat`.
+    #[inline(always)]
+    pub fn transform_by(&mut self, mat: Matrix4) { *self = self.transformed(mat) }
+
+    /// Returns the tessellation of the shape.
+    /// Vertices are tessellated to points, edges and wires to polylines,
+    /// and the others to polygon meshes by the module [`tessellation`].
+    ///
+    /// [`tessellation`]: ../tessellation/index.html
+    pub fn tessellate(&self, tol: f64) -> Tessellation {
+        match self {
+            AnyShape::Vertex(vertex) => Tessellation::Points(vec![*vertex.lock_point().unwrap()]),
+            AnyShape::Edge(edge) => {
+                Tessellation::Polylines(vec![polyline(&edge.oriented_curve(), tol)])
+            }
+            AnyShape::Wire(wire) => Tessellation::Polylines(
+                wire.iter()
+                    .map(|edge| polyline(&edge.oriented_curve(), tol))
+                    .collect(),
+            ),
+            AnyShape::Face(face) => Tessellation::Mesh(tessellation::tessellate_face(face, tol)),
+            AnyShape::Shell(shell) => {
+                Tessellation::Mesh(tessellation::tessellate_shell(shell, tol))
+            }
+            AnyShape::Solid(solid) => {
+                Tessellation::Mesh(tessellation::tessellate_solid(solid, tol))
+            }
+            AnyShape::Mesh(mesh) => Tessellation::Mesh(mesh.clone()),
+        }
+    }
+
+    /// Writes the tessellation of the shape in the wavefront obj format.
+    #[inline(always)]
+    pub fn write_obj<W: Write>(&self, tol: f64, writer: W) -> std::io::Result<()> {
+        self.tessellate(tol).write_obj(writer)
+    }
+}
+
+macro_rules! impl_from_for_any {
+    ($any: ident, $($variant: ident => $typename: ty),*) => {
+        $(
+            impl From<$typename> for $any {
+                #[inline(always)]
+                fn from(elem: $typename) -> $any { $any::$variant(elem) }
+            }
+        )*
+    };
+}
+
+impl_from_for_any!(AnyCurve, Curve => Curve, Polyline => Vec<Point3>);
+impl_from_for_any!(AnySurface, Surface => Surface, Mesh => PolygonMesh);
+impl_from_for_any!(
+    AnyShape,
+    Vertex => Vertex,
+    Edge => Edge,
+    Wire => Wire,
+    Face => Face,
+    Shell => Shell,
+    Solid => Solid,
+    Mesh => PolygonMesh
+);
+
+/// serialized edge: the front point, the back point, and the oriented curve
+type EdgeData = (Point3, Point3, Curve);
+
+#[derive(Debug, Serialize, Deserialize)]
+enum AnyShapeData {
+    Vertex(Point3),
+    Edge(EdgeData),
+    Wire(Vec<EdgeData>),
+    Face(CompressedShell<Point3, Curve, Surface>),
+    Shell(CompressedShell<Point3, Curve, Surface>),
+    Solid(CompressedSolid<Point3, Curve, Surface>),
+    Mesh(PolygonMesh),
+}
+
+fn edge_data(edge: &Edge) -> EdgeData {
+    (
+        *edge.front().lock_point().unwrap(),
+        *edge.back().lock_point().unwrap(),
+        edge.oriented_curve(),
+    )
+}
+
+impl From<AnyShape> for AnyShapeData {
+    fn from(shape: AnyShape) -> AnyShapeData {
+        match shape {
+            AnyShape::Vertex(vertex) => AnyShapeData::Vertex(*vertex.lock_point().unwrap()),
+            AnyShape::Edge(edge) => AnyShapeData::Edge(edge_data(&edge)),
+            AnyShape::Wire(wire) => AnyShapeData::Wire(wire.iter().map(edge_data).collect()),
+            AnyShape::Face(face) => AnyShapeData::Face(Shell::from(vec![face]).compress()),
+            AnyShape::Shell(shell) => AnyShapeData::Shell(shell.compress()),
+            AnyShape::Solid(solid) => AnyShapeData::Solid(solid.compress()),
+            AnyShape::Mesh(mesh) => AnyShapeData::Mesh(mesh),
+        }
+    }
+}
+
+/// Creates the wire whose consecutive edges share the vertices if the points coincide.
+fn wire_from_data(edges: Vec<EdgeData>) -> Result<Wire> {
+    let mut wire = Wire::new();
+    let mut first: Option<Vertex> = None;
+    let len = edges.len();
+    for (i, (front, back, curve)) in edges.into_iter().enumerate() {
+        let v0 = match wire.back() {
+            Some(edge) if edge.back().lock_point().unwrap().near(&front) => edge.back().clone(),
+            _ => Vertex::new(front),
+        };
+        let v1 = match &first {
+            Some(v) if i + 1 == len && v.lock_point().unwrap().near(&back) => v.clone(),
+            _ => Vertex::new(back),
+        };
+        if first.is_none() {
+            first = Some(v0.clone());
+        }
+        wire.push_back(Edge::try_new(&v0, &v1, curve)?);
+    }
+    Ok(wire)
+}
+
+impl TryFrom<AnyShapeData> for AnyShape {
+    type Error = Error;
+    fn try_from(data: AnyShapeData) -> Result<AnyShape> {
+        Ok(match data {
+            AnyShapeData::Vertex(pt) => AnyShape::Vertex(Vertex::new(pt)),
+            AnyShapeData::Edge(data) => {
+                let mut wire = wire_from_data(vec![data])?;
+                AnyShape::Edge(wire.pop_back().unwrap())
+            }
+            AnyShapeData::Wire(data) => AnyShape::Wire(wire_from_data(data)?),
+            AnyShapeData::Face(data) => {
+                let mut faces: Vec<Face> = Shell::extract(data)?.into();
+                match faces.pop() {
+                    Some(face) => AnyShape::Face(face),
+                    None => return Err(truck_topology::errors::Error::EmptyShell.into()),
+                }
+            }
+            AnyShapeData::Shell(data) => AnyShape::Shell(Shell::extract(data)?),
+            AnyShapeData::Solid(data) => AnyShape::Solid(Solid::extract(data)?),
+            AnyShapeData::Mesh(mesh) => AnyShape::Mesh(mesh),
+        })
+    }
+}
+
+#[test]
+fn serialize_wire() {
+    let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+    let e = builder::tsweep(&v, Vector3::unit_x());
+    let face = builder::tsweep(&e, Vector3::unit_y());
+    let shape = AnyShape::Wire(face.boundaries()[0].clone());
+    let json = serde_json::to_string(&shape).unwrap();
+    match serde_json::from_str::<AnyShape>(&json).unwrap() {
+        AnyShape::Wire(wire) => {
+            assert_eq!(wire.len(), 4);
+            assert!(wire.is_closed());
+        }
+        shape => panic!("{} is deserialized.", shape.type_name()),
+    }
+}
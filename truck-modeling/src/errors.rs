@@ -6,6 +6,69 @@ pub enum Error {
     /// tried to attach a plane to a wire that was not on one plane.
     /// cf. [`builder::try_attach_plane`](../builder/fn.try_attach_plane.html)
     WireNotInOnePlane,
+    /// the material is not registered in the material table.
+    /// cf. [`quantity::QuantityReport::add_part`](../quantity/struct.QuantityReport.html#method.add_part)
+    UnknownMaterial(String),
+    /// failed to parse an expression.
+    /// cf. [`parameters::Expr`](../parameters/enum.Expr.html)
+    InvalidExpression(String),
+    /// the parameter in an expression is not defined.
+    /// cf. [`parameters::Expr::eval`](../parameters/enum.Expr.html#method.eval)
+    UndefinedParameter(String),
+    /// the topological element is not contained in the shape.
+    /// cf. [`direct_edit::tweak_face`](../direct_edit/fn.tweak_face.html)
+    ElementNotFound,
+    /// the geometry is not supported by the operation, e.g. a curved face in direct editing.
+    /// cf. [`direct_edit::tweak_face`](../direct_edit/fn.tweak_face.html),
+    /// [`builder::thread`](../builder/fn.thread.html)
+    UnsupportedGeometry,
+    /// the planes do not meet at one point.
+    /// cf. [`direct_edit::tweak_face`](../direct_edit/fn.tweak_face.html)
+    DegenerateIntersection,
+    /// the vertices of a planar face are no longer in one plane.
+    /// cf. [`direct_edit::move_vertex`](../direct_edit/fn.move_vertex.html)
+    NonPlanarFace,
+    /// failed to approximate the curves or the surfaces within the tolerance.
+    /// cf. [`deformation::deformed`](../deformation/fn.deformed.html)
+    ApproximationFailed,
+    /// the joint refers to a component out of range, drives an already driven component,
+    /// or makes a loop. cf. [`kinematics::Mechanism::pose`](../kinematics/struct.Mechanism.html#method.pose)
+    InvalidJoint(usize),
+    /// the length of the joint vector is not the number of the joints.
+    /// cf. [`kinematics::Mechanism::pose`](../kinematics/struct.Mechanism.html#method.pose)
+    InvalidJointValues,
+    /// the orientations of the faces of the shell cannot be made consistent.
+    /// cf. [`healing::orient_shell`](../healing/fn.orient_shell.html)
+    NonOrientableShell,
+    /// a boundary of the shell is not closed or cannot be capped.
+    /// cf. [`healing::cap_boundaries`](../healing/fn.cap_boundaries.html)
+    UncappableBoundary,
+    /// the faces do not share an edge, or are not on the same surface.
+    /// cf. [`healing::merge_faces`](../healing/fn.merge_faces.html)
+    FacesNotMergeable,
+    /// the intersection curves are not closed or do not end on the boundaries.
+    /// cf. [`boolean::BooleanOperation`](../boolean/trait.BooleanOperation.html)
+    InvalidIntersection,
+    /// the blend runs over the edges adjacent to the blended edge.
+    /// cf. [`builder::fillet`](../builder/fn.fillet.html),
+    /// [`builder::chamfer`](../builder/fn.chamfer.html)
+    BlendTooLarge,
+    /// the sections of the loft are less than two, have the different numbers of edges,
+    /// or coincide with each other.
+    /// cf. [`builder::loft`](../builder/fn.loft.html)
+    IncompatibleSections,
+    /// the thickness is not positive, or is too large so that the offset faces are turned over.
+    /// cf. [`builder::hollow`](../builder/fn.hollow.html)
+    InvalidThickness,
+    /// the dimensions of the profile are not positive or are inconsistent with each other.
+    /// cf. [`builder::rounded_rectangle`](../builder/fn.rounded_rectangle.html),
+    /// [`builder::slot`](../builder/fn.slot.html),
+    /// [`gear::Gear::profile`](../gear/struct.Gear.html#method.profile)
+    InvalidProfile,
+    /// the pitch or the depth of the thread is not positive, the flanks of the thread meet
+    /// each other, or the thread is longer than the face.
+    /// cf. [`builder::thread`](../builder/fn.thread.html)
+    InvalidThread,
 }
 
 impl std::fmt::Display for Error {
@@ -13,6 +76,25 @@ impl std::fmt::Display for Error {
         match self {
             Error::FromTopology(error) => error.fmt(f),
             Error::WireNotInOnePlane => f.pad("cannot attach a plane to a wire that is not on one plane."),
+            Error::UnknownMaterial(name) => f.write_fmt(format_args!("the material \"{}\" is not registered.", name)),
+            Error::InvalidExpression(message) => f.write_fmt(format_args!("invalid expression: {}", message)),
+            Error::UndefinedParameter(name) => f.write_fmt(format_args!("the parameter \"{}\" is not defined.", name)),
+            Error::ElementNotFound => f.pad("the element is not contained in the shape."),
+            Error::UnsupportedGeometry => f.pad("the geometry is not supported by the operation."),
+            Error::DegenerateIntersection => f.pad("the planes do not meet at one point."),
+            Error::NonPlanarFace => f.pad("the vertices of a planar face are not in one plane."),
+            Error::ApproximationFailed => f.pad("failed to approximate the geometry within the tolerance."),
+            Error::InvalidJoint(index) => f.write_fmt(format_args!("the joint {} is invalid in the mechanism.", index)),
+            Error::InvalidJointValues => f.pad("the length of the joint vector is not the number of the joints."),
+            Error::NonOrientableShell => f.pad("the shell is not orientable."),
+            Error::UncappableBoundary => f.pad("the boundary of the shell cannot be capped."),
+            Error::FacesNotMergeable => f.pad("the faces cannot be merged into one face."),
+            Error::InvalidIntersection => f.pad("the intersection curves of the solids cannot be traced."),
+            Error::BlendTooLarge => f.pad("the blend is too large for the adjacent edges."),
+            Error::IncompatibleSections => f.pad("the sections cannot be lofted."),
+            Error::InvalidThickness => f.pad("the thickness is not positive or too large for the solid."),
+            Error::InvalidProfile => f.pad("the dimensions of the profile are invalid."),
+            Error::InvalidThread => f.pad("the dimensions of the thread are invalid for the face."),
         }
     }
 }
@@ -30,5 +112,24 @@ fn print_messages() {
     writeln!(&mut std::io::stderr(), "****** test of the expressions of error messages ******\n").unwrap();
     writeln!(&mut std::io::stderr(), "{}\n", Error::FromTopology(truck_topology::errors::Error::SameVertex)).unwrap();
     writeln!(&mut std::io::stderr(), "{}\n", Error::WireNotInOnePlane).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::UnknownMaterial("steel".to_string())).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::InvalidExpression("unknown function log()".to_string())).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::UndefinedParameter("width".to_string())).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::ElementNotFound).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::UnsupportedGeometry).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::DegenerateIntersection).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::NonPlanarFace).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::ApproximationFailed).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::InvalidJoint(2)).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::InvalidJointValues).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::NonOrientableShell).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::UncappableBoundary).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::FacesNotMergeable).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::InvalidIntersection).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::BlendTooLarge).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::IncompatibleSections).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::InvalidThickness).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::InvalidProfile).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::InvalidThread).unwrap();
     writeln!(&mut std::io::stderr(), "*******************************************************").unwrap();
 }
@@ -0,0 +1,185 @@
+use crate::*;
+use truck_geometry::surface_search_nearest_parameter;
+
+const GRID_DIVISION: usize = 16;
+const TRIALS: usize = 100;
+
+/// The domain of a face in the parameter space of its surface.
+///
+/// The boundaries are approximated by polylines in both the space and the parameter space.
+/// The parameters of the boundary of a periodic surface are continuously shifted by the period,
+/// so a boundary wire winding around the surface may not be closed in the parameter space.
+#[derive(Clone, Debug)]
+pub(crate) struct FaceDomain {
+    pub surface: Surface,
+    pub periods: (Option<f64>, Option<f64>),
+    pub boundary_uvs: Vec<Vec<Vector2>>,
+    pub boundary_points: Vec<Vec<Point3>>,
+    grid: Vec<((f64, f64), Point3)>,
+}
+
+impl FaceDomain {
+    /// `tol` is the tolerance of the polyline approximation of the boundaries.
+    pub fn new(face: &Face, tol: f64) -> FaceDomain {
+        let surface = face.oriented_surface();
+        let ((u0, u1), (v0, v1)) = surface.parameter_range();
+        let grid: Vec<_> = (0..=GRID_DIVISION)
+            .flat_map(|i| (0..=GRID_DIVISION).map(move |j| (i, j)))
+            .map(|(i, j)| {
+                let p = i as f64 / GRID_DIVISION as f64;
+                let q = j as f64 / GRID_DIVISION as f64;
+                let uv = (u0 * (1.0 - p) + u1 * p, v0 * (1.0 - q) + v1 * q);
+                (uv, surface.subs(uv.0, uv.1))
+            })
+            .collect();
+        let closed_u = (0..=2).all(|i| {
+            let v = v0 + (v1 - v0) * i as f64 / 2.0;
+            surface.subs(u0, v).near(&surface.subs(u1, v))
+        });
+        let closed_v = (0..=2).all(|i| {
+            let u = u0 + (u1 - u0) * i as f64 / 2.0;
+            surface.subs(u, v0).near(&surface.subs(u, v1))
+        });
+        let periods = (
+            if closed_u { Some(u1 - u0) } else { None },
+            if closed_v { Some(v1 - v0) } else { None },
+        );
+        let mut res = FaceDomain {
+            surface,
+            periods,
+            boundary_uvs: Vec::new(),
+            boundary_points: Vec::new(),
+            grid,
+        };
+        for wire in face.boundaries() {
+            let points: Vec<Point3> = wire
+                .iter()
+                .flat_map(|edge| {
+                    let curve = edge.oriented_curve();
+                    let division = curve.parameter_division(tol);
+                    let len = division.len();
+                    division
+                        .into_iter()
+                        .take(len - 1)
+                        .map(move |t| curve.subs(t))
+                })
+                .collect();
+            let mut uvs: Vec<Vector2> = Vec::with_capacity(points.len());
+            for pt in &points {
+                let uv = match uvs.last() {
+                    Some(prev) => {
+                        let uv = res.search_parameter(*pt, (prev[0], prev[1]));
+                        res.nearest_branch(Vector2::new(uv.0, uv.1), *prev)
+                    }
+                    None => {
+                        let uv = res.search_parameter(*pt, res.presearch(*pt));
+                        Vector2::new(uv.0, uv.1)
+                    }
+                };
+                uvs.push(uv);
+            }
+            res.boundary_uvs.push(uvs);
+            res.boundary_points.push(points);
+        }
+        res
+    }
+
+    /// Returns the parameter of the nearest sample point.
+    pub fn presearch(&self, pt: Point3) -> (f64, f64) {
+        self.grid
+            .iter()
+            .fold((std::f64::INFINITY, (0.0, 0.0)), |(min, res), (uv, p)| {
+                let dist2 = p.distance2(pt);
+                match dist2 < min {
+                    true => (dist2, *uv),
+                    false => (min, res),
+                }
+            })
+            .1
+    }
+
+    /// Returns the parameter of the foot of `pt` on the surface.
+    ///
+    /// If the search from `hint` does not reach `pt`, e.g. it converges to a saddle point of
+    /// the distance on a twisted surface, the search is retried from the nearest sample point.
+    pub fn search_parameter(&self, pt: Point3, hint: (f64, f64)) -> (f64, f64) {
+        let search = |hint: (f64, f64)| {
+            let uv = surface_search_nearest_parameter(&self.surface, pt, hint, TRIALS).unwrap_or(hint);
+            (uv, self.surface.subs(uv.0, uv.1).distance2(pt))
+        };
+        let (uv, dist2) = search(hint);
+        if dist2.so_small2() {
+            return uv;
+        }
+        let (uv1, dist2_1) = search(self.presearch(pt));
+        match dist2_1 < dist2 {
+            true => uv1,
+            false => uv,
+        }
+    }
+
+    /// Shifts `uv` by the periods so that it is nearest to `target`.
+    pub fn nearest_branch(&self, mut uv: Vector2, target: Vector2) -> Vector2 {
+        if let Some(period) = self.periods.0 {
+            uv[0] += ((target[0] - uv[0]) / period).round() * period;
+        }
+        if let Some(period) = self.periods.1 {
+            uv[1] += ((target[1] - uv[1]) / period).round() * period;
+        }
+        uv
+    }
+
+    /// Returns whether the parameter `uv` is in the face or not.
+    pub fn include(&self, uv: Vector2) -> bool {
+        let shifts = |period: Option<f64>| match period {
+            Some(period) => vec![0.0, -period, period],
+            None => vec![0.0],
+        };
+        let (ushifts, vshifts) = (shifts(self.periods.0), shifts(self.periods.1));
+        ushifts.iter().any(|du| {
+            vshifts.iter().any(|dv| {
+                let uv = uv + Vector2::new(*du, *dv);
+                self.boundary_uvs
+                    .iter()
+                    .filter(|polygon| crossing_parity(polygon, uv))
+                    .count()
+                    % 2
+                    == 1
+            })
+        })
+    }
+
+    /// Returns the closed polylines of the boundaries in the parameter space.
+    ///
+    /// If a boundary is not closed in the parameter space because of the period,
+    /// the last segment is directed to the first point shifted by the period.
+    pub fn closed_boundary_uvs(&self) -> Vec<Vec<Vector2>> {
+        self.boundary_uvs
+            .iter()
+            .filter(|polygon| !polygon.is_empty())
+            .map(|polygon| {
+                let mut polygon = polygon.clone();
+                let last = polygon[polygon.len() - 1];
+                let first = self.nearest_branch(polygon[0], last);
+                polygon.push(first);
+                polygon
+            })
+            .collect()
+    }
+}
+
+/// Returns whether the half line from `pt` to the +u direction crosses the polygon odd times.
+fn crossing_parity(polygon: &[Vector2], pt: Vector2) -> bool {
+    let len = polygon.len();
+    (0..len).fold(false, |res, i| {
+        let (p, q) = (polygon[i], polygon[(i + 1) % len]);
+        if (p[1] > pt[1]) != (q[1] > pt[1]) {
+            let u = p[0] + (pt[1] - p[1]) / (q[1] - p[1]) * (q[0] - p[0]);
+            if u > pt[0] {
+                return !res;
+            }
+        }
+        res
+    })
+}
+
@@ -0,0 +1,476 @@
+use crate::*;
+use std::collections::HashMap;
+
+/// The number of the samples in each direction to classify surfaces.
+const SURFACE_SAMPLES: usize = 6;
+/// The maximum angle gap of the closed cylindrical faces around the axis.
+const CLOSED_GAP: f64 = std::f64::consts::PI / 3.0;
+
+/// Cylindrical hole.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hole {
+    /// the cylindrical faces of the hole
+    pub faces: Vec<FaceID>,
+    /// the center of the entrance of the hole
+    pub origin: Point3,
+    /// the unit direction from the entrance to the bottom
+    pub axis: Vector3,
+    /// the diameter of the hole
+    pub diameter: f64,
+    /// the depth of the hole
+    pub depth: f64,
+    /// the bottom face of the blind hole, `None` if the hole is a through hole
+    pub bottom: Option<FaceID>,
+}
+
+/// Planar pocket, a planar floor surrounded by walls with concave edges.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pocket {
+    /// the planar floor
+    pub floor: FaceID,
+    /// the faces adjacent to the floor
+    pub walls: Vec<FaceID>,
+    /// the unit normal of the floor directed to the opening
+    pub normal: Vector3,
+    /// the depth of the pocket, the maximum height of the walls from the floor
+    pub depth: f64,
+}
+
+/// Constant radius fillet.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Fillet {
+    /// the cylindrical faces of the fillet
+    pub faces: Vec<FaceID>,
+    /// a point on the axis of the fillet
+    pub origin: Point3,
+    /// the unit direction of the axis
+    pub axis: Vector3,
+    /// the radius of the fillet
+    pub radius: f64,
+    /// `true` if the fillet rounds a convex edge, and `false` if it fills a concave edge
+    pub convex: bool,
+}
+
+/// Recognized features.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Feature {
+    /// cylindrical hole
+    Hole(Hole),
+    /// planar pocket
+    Pocket(Pocket),
+    /// constant radius fillet
+    Fillet(Fillet),
+}
+
+/// The geometric type of the oriented surface.
+#[derive(Clone, Copy, Debug)]
+pub(crate) enum SurfaceKind {
+    Plane {
+        origin: Point3,
+        normal: Vector3,
+    },
+    Cylinder {
+        origin: Point3,
+        axis: Vector3,
+        radius: f64,
+        convex: bool,
+    },
+    Other,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Convexity {
+    Convex,
+    Concave,
+    Smooth,
+}
+
+pub(crate) struct FaceInfo {
+    face: Face,
+    surface: Surface,
+    pub(crate) kind: SurfaceKind,
+    pub(crate) points: Vec<Point3>,
+}
+
+/// Recognizes cylindrical holes, planar pockets and constant radius fillets in the solid.
+///
+/// The faces are classified by sampling, and `tol` is the geometric tolerance of the classification.
+/// The adjacent coaxial cylindrical faces with the same radius are regarded as one feature.
+/// - A concave cylinder closed around its axis is a hole. The hole is blind if there is
+/// an adjacent planar face perpendicular to the axis inside the cylinder.
+/// - A planar face whose edges are concave or smooth is the floor of a pocket.
+/// - A cylinder not closed around its axis and smoothly connected to the adjacent face is a fillet.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::features::*;
+/// const PI: Rad<f64> = Rad(std::f64::consts::PI);
+///
+/// // a plate with a hole
+/// let v = builder::vertex(Point3::new(-1.0, -1.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::new(2.0, 0.0, 0.0));
+/// let square = builder::tsweep(&e, Vector3::new(0.0, 2.0, 0.0));
+/// let outer = square.boundaries()[0].clone();
+/// let v = builder::vertex(Point3::new(0.5, 0.0, 0.0));
+/// let circle: Wire = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), PI * 2.0);
+/// let face = builder::try_attach_plane(&vec![outer, circle.inverse()]).unwrap();
+/// let plate = builder::tsweep(&face, Vector3::new(0.0, 0.0, 0.5));
+///
+/// let features = recognize_features(&plate, 1.0e-3);
+/// assert_eq!(features.len(), 1);
+/// match &features[0] {
+///     Feature::Hole(hole) => {
+///         assert!((hole.diameter - 1.0).abs() < 1.0e-3);
+///         assert!((hole.depth - 0.5).abs() < 1.0e-3);
+///         assert!(hole.bottom.is_none());
+///         assert!(hole.axis.cross(Vector3::unit_z()).magnitude() < 1.0e-3);
+///     }
+///     _ => panic!("the hole is not recognized."),
+/// }
+/// ```
+pub fn recognize_features(solid: &Solid, tol: f64) -> Vec<Feature> {
+    let infos: Vec<FaceInfo> = solid
+        .boundaries()
+        .iter()
+        .flat_map(|shell| shell.iter())
+        .map(|face| face_info(face, tol))
+        .collect();
+    let adjacency = adjacency(&infos, tol);
+    let mut features = Vec::new();
+    let mut bottoms = Vec::new();
+    for group in cylinder_groups(&infos, &adjacency, tol) {
+        let (origin, axis, radius, convex) = match infos[group[0]].kind {
+            SurfaceKind::Cylinder {
+                origin,
+                axis,
+                radius,
+                convex,
+            } => (origin, axis, radius, convex),
+            _ => continue,
+        };
+        let faces: Vec<FaceID> = group.iter().map(|i| infos[*i].face.id()).collect();
+        let neighbors: Vec<(usize, Convexity)> = group
+            .iter()
+            .flat_map(|i| adjacency[*i].iter())
+            .filter(|(j, _)| !group.contains(j))
+            .cloned()
+            .collect();
+        if is_closed(&infos, &group, origin, axis) {
+            if convex {
+                continue;
+            }
+            let bottom = neighbors
+                .iter()
+                .map(|(j, _)| *j)
+                .find(|j| match infos[*j].kind {
+                    SurfaceKind::Plane { normal, .. } => {
+                        normal.cross(axis).magnitude() < tol
+                            && infos[*j]
+                                .points
+                                .iter()
+                                .all(|pt| distance_to_axis(*pt, origin, axis) < radius + tol)
+                    }
+                    _ => false,
+                });
+            let axis = match bottom {
+                Some(j) => match infos[j].kind {
+                    SurfaceKind::Plane { normal, .. } => -normal,
+                    _ => axis,
+                },
+                None => axis,
+            };
+            let (t0, t1) = group
+                .iter()
+                .flat_map(|i| infos[*i].points.iter())
+                .map(|pt| (pt - origin).dot(axis))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(t0, t1), t| {
+                    (t0.min(t), t1.max(t))
+                });
+            if let Some(j) = bottom {
+                bottoms.push(j);
+            }
+            features.push(Feature::Hole(Hole {
+                faces,
+                origin: origin + axis * t0,
+                axis,
+                diameter: 2.0 * radius,
+                depth: t1 - t0,
+                bottom: bottom.map(|j| infos[j].face.id()),
+            }));
+        } else if neighbors.iter().any(|(_, c)| *c == Convexity::Smooth) {
+            features.push(Feature::Fillet(Fillet {
+                faces,
+                origin,
+                axis,
+                radius,
+                convex,
+            }));
+        }
+    }
+    for (i, info) in infos.iter().enumerate() {
+        let (origin, normal) = match info.kind {
+            SurfaceKind::Plane { origin, normal } => (origin, normal),
+            _ => continue,
+        };
+        let edges = &adjacency[i];
+        let is_floor = !bottoms.contains(&i)
+            && edges.iter().any(|(_, c)| *c == Convexity::Concave)
+            && edges.iter().all(|(_, c)| *c != Convexity::Convex);
+        if !is_floor {
+            continue;
+        }
+        let mut walls: Vec<usize> = edges.iter().map(|(j, _)| *j).collect();
+        walls.sort();
+        walls.dedup();
+        let depth = walls
+            .iter()
+            .flat_map(|j| infos[*j].points.iter())
+            .map(|pt| (pt - origin).dot(normal))
+            .fold(0.0, f64::max);
+        features.push(Feature::Pocket(Pocket {
+            floor: info.face.id(),
+            walls: walls.into_iter().map(|j| infos[j].face.id()).collect(),
+            normal,
+            depth,
+        }));
+    }
+    features
+}
+
+pub(crate) fn face_info(face: &Face, tol: f64) -> FaceInfo {
+    let surface = face.oriented_surface();
+    let points: Vec<Point3> = face
+        .boundaries()
+        .iter()
+        .flat_map(|wire| wire.iter())
+        .flat_map(|edge| {
+            let curve = edge.oriented_curve();
+            curve
+                .parameter_division(tol)
+                .into_iter()
+                .map(move |t| curve.subs(t))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let kind = match &surface {
+        Surface::Plane(plane) => SurfaceKind::Plane {
+            origin: plane.origin(),
+            normal: plane.normal(),
+        },
+        _ => classify(&surface, tol),
+    };
+    FaceInfo {
+        face: face.clone(),
+        surface,
+        kind,
+        points,
+    }
+}
+
+/// Classifies the surface to a plane or a cylinder by the sampled points and normals.
+fn classify(surface: &Surface, tol: f64) -> SurfaceKind {
+    let ((u0, u1), (v0, v1)) = surface.parameter_range();
+    let n = SURFACE_SAMPLES;
+    let samples: Vec<(Point3, Vector3)> = (0..=n)
+        .flat_map(|i| (0..=n).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            let u = u0 + (u1 - u0) * i as f64 / n as f64;
+            let v = v0 + (v1 - v0) * j as f64 / n as f64;
+            (surface.subs(u, v), surface.normal(u, v))
+        })
+        .collect();
+    let (a, b) = samples
+        .iter()
+        .enumerate()
+        .flat_map(|(i, _)| (i + 1..samples.len()).map(move |j| (i, j)))
+        .max_by(|(i0, j0), (i1, j1)| {
+            let x = samples[*i0].1.cross(samples[*j0].1).magnitude2();
+            let y = samples[*i1].1.cross(samples[*j1].1).magnitude2();
+            x.partial_cmp(&y).unwrap()
+        })
+        .unwrap();
+    let ((p0, n0), (p1, n1)) = (samples[a], samples[b]);
+    let cross = n0.cross(n1);
+    if cross.magnitude() < tol {
+        let planar = samples
+            .iter()
+            .all(|(p, n)| n.dot(n0) > 0.0 && (p - p0).dot(n0).abs() < tol);
+        return match planar {
+            true => SurfaceKind::Plane {
+                origin: p0,
+                normal: n0,
+            },
+            false => SurfaceKind::Other,
+        };
+    }
+    let axis = cross.normalize();
+    // the intersection of the lines `p0 + s * n0` and `p1 + u * n1`
+    let s = (p1 - p0).cross(n1).dot(cross) / cross.magnitude2();
+    let origin = p0 + n0 * s;
+    let (radius, convex) = (s.abs(), s < 0.0);
+    let cylindrical = samples.iter().all(|(p, n)| {
+        let radial = (p - origin) - axis * (p - origin).dot(axis);
+        n.dot(axis).abs() < tol
+            && (radial.magnitude() - radius).abs() < tol
+            && (radial.dot(*n) > 0.0) == convex
+    });
+    match cylindrical {
+        true => SurfaceKind::Cylinder {
+            origin,
+            axis,
+            radius,
+            convex,
+        },
+        false => SurfaceKind::Other,
+    }
+}
+
+fn distance_to_axis(pt: Point3, origin: Point3, axis: Vector3) -> f64 {
+    let vec = pt - origin;
+    (vec - axis * vec.dot(axis)).magnitude()
+}
+
+/// Returns the adjacent faces and the convexities of the shared edges for each face.
+fn adjacency(infos: &[FaceInfo], tol: f64) -> Vec<Vec<(usize, Convexity)>> {
+    let mut edge_map: HashMap<EdgeID, Vec<(usize, Edge)>> = HashMap::new();
+    for (i, info) in infos.iter().enumerate() {
+        for edge in info.face.boundaries().iter().flat_map(|wire| wire.iter()) {
+            edge_map
+                .entry(edge.id())
+                .or_insert_with(Vec::new)
+                .push((i, edge.clone()));
+        }
+    }
+    let mut res = vec![Vec::new(); infos.len()];
+    for pairs in edge_map.values() {
+        if pairs.len() != 2 || pairs[0].0 == pairs[1].0 {
+            continue;
+        }
+        let ((i, edge), (j, _)) = (&pairs[0], &pairs[1]);
+        if let Some(convexity) = convexity(&infos[*i], edge, &infos[*j], tol) {
+            res[*i].push((*j, convexity));
+            res[*j].push((*i, convexity));
+        }
+    }
+    res
+}
+
+/// Returns the convexity of the edge, oriented as the boundary of `info0`, at its middle point.
+fn convexity(info0: &FaceInfo, edge: &Edge, info1: &FaceInfo, tol: f64) -> Option<Convexity> {
+    let curve = edge.oriented_curve();
+    let (t0, t1) = curve.parameter_range();
+    let t = (t0 + t1) / 2.0;
+    let (pt, tangent) = (curve.subs(t), curve.der(t));
+    let n0 = normal_at(&info0.surface, pt)?;
+    let n1 = normal_at(&info1.surface, pt)?;
+    if n0.cross(n1).magnitude() < tol && n0.dot(n1) > 0.0 {
+        return Some(Convexity::Smooth);
+    }
+    // `n0.cross(tangent)` is directed to the inside of the face, since the boundary is
+    // counter-clockwise with respect to the normal.
+    match n1.dot(n0.cross(tangent)) > 0.0 {
+        true => Some(Convexity::Concave),
+        false => Some(Convexity::Convex),
+    }
+}
+
+/// Returns the normal of the surface at the point on the surface.
+fn normal_at(surface: &Surface, pt: Point3) -> Option<Vector3> {
+    if let Surface::Plane(plane) = surface {
+        return Some(plane.normal());
+    }
+    let ((u0, u1), (v0, v1)) = surface.parameter_range();
+    let n = 2 * SURFACE_SAMPLES;
+    let hint = (0..=n)
+        .flat_map(|i| (0..=n).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            let u = u0 + (u1 - u0) * i as f64 / n as f64;
+            let v = v0 + (v1 - v0) * j as f64 / n as f64;
+            (u, v)
+        })
+        .min_by(|(u, v), (s, t)| {
+            let x = surface.subs(*u, *v).distance2(pt);
+            let y = surface.subs(*s, *t).distance2(pt);
+            x.partial_cmp(&y).unwrap()
+        })?;
+    let (u, v) = surface.search_parameter(pt, hint, 100)?;
+    Some(surface.normal(u, v))
+}
+
+/// Returns the groups of the adjacent faces on the same cylinder.
+fn cylinder_groups(
+    infos: &[FaceInfo],
+    adjacency: &[Vec<(usize, Convexity)>],
+    tol: f64,
+) -> Vec<Vec<usize>> {
+    let same_cylinder = |i: usize, j: usize| match (infos[i].kind, infos[j].kind) {
+        (
+            SurfaceKind::Cylinder {
+                origin: o0,
+                axis: a0,
+                radius: r0,
+                convex: c0,
+            },
+            SurfaceKind::Cylinder {
+                origin: o1,
+                axis: a1,
+                radius: r1,
+                convex: c1,
+            },
+        ) => {
+            c0 == c1
+                && (r0 - r1).abs() < tol
+                && a0.cross(a1).magnitude() < tol
+                && distance_to_axis(o1, o0, a0) < tol
+        }
+        _ => false,
+    };
+    let mut grouped = vec![false; infos.len()];
+    let mut groups = Vec::new();
+    for i in 0..infos.len() {
+        if grouped[i] || !matches!(infos[i].kind, SurfaceKind::Cylinder { .. }) {
+            continue;
+        }
+        grouped[i] = true;
+        let mut group = vec![i];
+        let mut idx = 0;
+        while idx < group.len() {
+            for (j, _) in &adjacency[group[idx]] {
+                if !grouped[*j] && same_cylinder(i, *j) {
+                    grouped[*j] = true;
+                    group.push(*j);
+                }
+            }
+            idx += 1;
+        }
+        groups.push(group);
+    }
+    groups
+}
+
+/// Returns whether the faces are closed around the axis.
+fn is_closed(infos: &[FaceInfo], group: &[usize], origin: Point3, axis: Vector3) -> bool {
+    let x = match axis.cross(Vector3::unit_x()).magnitude() > 0.5 {
+        true => axis.cross(Vector3::unit_x()).normalize(),
+        false => axis.cross(Vector3::unit_y()).normalize(),
+    };
+    let y = axis.cross(x);
+    let mut angles: Vec<f64> = group
+        .iter()
+        .flat_map(|i| infos[*i].points.iter())
+        .map(|pt| {
+            let vec = pt - origin;
+            f64::atan2(vec.dot(y), vec.dot(x))
+        })
+        .collect();
+    if angles.is_empty() {
+        return false;
+    }
+    angles.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    let last_gap = angles[0] + 2.0 * std::f64::consts::PI - angles[angles.len() - 1];
+    angles
+        .windows(2)
+        .map(|a| a[1] - a[0])
+        .chain(std::iter::once(last_gap))
+        .all(|gap| gap < CLOSED_GAP)
+}
@@ -0,0 +1,433 @@
+use crate::deformation;
+use crate::errors::Error;
+use crate::topo_traits::Mapped;
+use crate::*;
+use std::cell::Cell;
+use truck_polymesh::PolygonMesh;
+
+/// The maximum number of the knot refinements in approximating B-rep geometries.
+const MAX_REFINEMENT: usize = 6;
+
+/// Free-form deformation lattice, a trivariate B-spline volume on a box.
+///
+/// The points in the box are mapped by the volume, and the points outside the box are not moved.
+/// The lattice is initialized to the identity mapping, and the deformation is specified by
+/// moving the control points. In order that the deformation is continuous, the control points
+/// on the boundary of the lattice should not be moved.
+/// The lattice of the degree `n` without inner knots is the Bézier lattice.
+#[derive(Clone, Debug)]
+pub struct FFDLattice {
+    bounding_box: BoundingBox<Point3>,
+    degree: usize,
+    knot_vecs: [KnotVec; 3],
+    control_points: Vec<Vec<Vec<Point3>>>,
+}
+
+impl FFDLattice {
+    /// Creates the identity lattice on the bounding box whose knot vectors are clamped and uniform.
+    /// # Panics
+    /// Panic occurs if the bounding box is empty or flat, or `degree` or some divisions are zero.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::ffd::FFDLattice;
+    /// let bdd: BoundingBox<Point3> = vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 2.0, 3.0)]
+    ///     .into_iter()
+    ///     .collect();
+    /// let lattice = FFDLattice::new(bdd, [1, 2, 3], 2);
+    /// assert_eq!(lattice.numbers_of_control_points(), [3, 4, 5]);
+    /// let pt = Point3::new(0.3, 1.2, 2.5);
+    /// assert_near!(lattice.apply(pt), pt);
+    /// ```
+    pub fn new(
+        bounding_box: BoundingBox<Point3>,
+        divisions: [usize; 3],
+        degree: usize,
+    ) -> FFDLattice {
+        let diag = bounding_box.diagonal();
+        if bounding_box.is_empty() || (0..3).any(|i| diag[i].so_small()) {
+            panic!("the bounding box of the lattice is empty or flat.");
+        }
+        if degree == 0 || divisions.contains(&0) {
+            panic!("the degree and the divisions of the lattice must be positive.");
+        }
+        let knot_vecs = [
+            KnotVec::uniform_knot(degree, divisions[0]),
+            KnotVec::uniform_knot(degree, divisions[1]),
+            KnotVec::uniform_knot(degree, divisions[2]),
+        ];
+        let [xs, ys, zs] = [
+            greville_abscissae(&knot_vecs[0], degree),
+            greville_abscissae(&knot_vecs[1], degree),
+            greville_abscissae(&knot_vecs[2], degree),
+        ];
+        let min = *bounding_box.min();
+        let control_points = xs
+            .iter()
+            .map(|x| {
+                ys.iter()
+                    .map(|y| {
+                        zs.iter()
+                            .map(|z| min + Vector3::new(diag[0] * x, diag[1] * y, diag[2] * z))
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+        FFDLattice {
+            bounding_box,
+            degree,
+            knot_vecs,
+            control_points,
+        }
+    }
+
+    /// Returns the bounding box of the lattice.
+    #[inline(always)]
+    pub fn bounding_box(&self) -> &BoundingBox<Point3> { &self.bounding_box }
+
+    /// Returns the degree of the lattice.
+    #[inline(always)]
+    pub fn degree(&self) -> usize { self.degree }
+
+    /// Returns the numbers of the control points in each direction.
+    #[inline(always)]
+    pub fn numbers_of_control_points(&self) -> [usize; 3] {
+        [
+            self.control_points.len(),
+            self.control_points[0].len(),
+            self.control_points[0][0].len(),
+        ]
+    }
+
+    /// Returns the reference of the control points.
+    #[inline(always)]
+    pub fn control_points(&self) -> &Vec<Vec<Vec<Point3>>> { &self.control_points }
+
+    /// Returns the control point.
+    #[inline(always)]
+    pub fn control_point(&self, i: usize, j: usize, k: usize) -> Point3 {
+        self.control_points[i][j][k]
+    }
+
+    /// Returns the mutable reference of the control point.
+    #[inline(always)]
+    pub fn control_point_mut(&mut self, i: usize, j: usize, k: usize) -> &mut Point3 {
+        &mut self.control_points[i][j][k]
+    }
+
+    /// Returns the coordinates of the point in the box normalized to `[0, 1]^3`.
+    /// If the point is outside of the box, returns `None`.
+    pub fn local_coordinates(&self, pt: Point3) -> Option<Vector3> {
+        let (min, diag) = (*self.bounding_box.min(), self.bounding_box.diagonal());
+        let vec = pt - min;
+        let res = Vector3::new(vec[0] / diag[0], vec[1] / diag[1], vec[2] / diag[2]);
+        match (0..3).all(|i| 0.0 <= res[i] && res[i] <= 1.0) {
+            true => Some(res),
+            false => None,
+        }
+    }
+
+    /// Returns the deformed point.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::ffd::FFDLattice;
+    /// let bdd: BoundingBox<Point3> = vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 1.0, 1.0)]
+    ///     .into_iter()
+    ///     .collect();
+    /// // the Bézier lattice of degree 2
+    /// let mut lattice = FFDLattice::new(bdd, [1, 1, 1], 2);
+    /// *lattice.control_point_mut(1, 1, 2) += Vector3::new(0.0, 0.0, 1.0);
+    /// // the Bernstein polynomials of degree 2 at 0.5 are 1/4, 1/2, 1/4.
+    /// let pt = lattice.apply(Point3::new(0.5, 0.5, 1.0));
+    /// assert_near!(pt, Point3::new(0.5, 0.5, 1.25));
+    /// // outside the lattice
+    /// assert_near!(lattice.apply(Point3::new(2.0, 0.5, 0.5)), Point3::new(2.0, 0.5, 0.5));
+    /// ```
+    pub fn apply(&self, pt: Point3) -> Point3 {
+        let coord = match self.local_coordinates(pt) {
+            Some(coord) => coord,
+            None => return pt,
+        };
+        let degree = self.degree;
+        let bx = basis_functions(&self.knot_vecs[0], degree, coord[0]);
+        let by = basis_functions(&self.knot_vecs[1], degree, coord[1]);
+        let bz = basis_functions(&self.knot_vecs[2], degree, coord[2]);
+        let mut res = Vector3::zero();
+        for (i, a) in bx.iter().enumerate().filter(|(_, a)| **a != 0.0) {
+            for (j, b) in by.iter().enumerate().filter(|(_, b)| **b != 0.0) {
+                for (k, c) in bz.iter().enumerate().filter(|(_, c)| **c != 0.0) {
+                    res += self.control_points[i][j][k].to_vec() * (a * b * c);
+                }
+            }
+        }
+        Point3::from_vec(res)
+    }
+
+    /// Returns the Jacobian matrix of the deformation at the point by the differences
+    /// in the box. If the point is outside of the box, returns the identity.
+    fn jacobian(&self, pt: Point3) -> Matrix3 {
+        if self.local_coordinates(pt).is_none() {
+            return Matrix3::identity();
+        }
+        let (min, max) = (*self.bounding_box.min(), *self.bounding_box.max());
+        let clamp = |pt: Point3| {
+            Point3::new(
+                pt[0].max(min[0]).min(max[0]),
+                pt[1].max(min[1]).min(max[1]),
+                pt[2].max(min[2]).min(max[2]),
+            )
+        };
+        let diag = self.bounding_box.diagonal();
+        let h = diag[0].max(diag[1]).max(diag[2]) * 1.0e-6;
+        let der = |vec: Vector3| {
+            let (p, q) = (clamp(pt + vec * h), clamp(pt - vec * h));
+            (self.apply(p) - self.apply(q)) / (p - q).dot(vec)
+        };
+        Matrix3::from_cols(
+            der(Vector3::unit_x()),
+            der(Vector3::unit_y()),
+            der(Vector3::unit_z()),
+        )
+    }
+
+    /// Returns the deformed polygon mesh.
+    ///
+    /// The positions are mapped exactly, and the normals are transformed by
+    /// the inverse transpose of the Jacobian matrix.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::ffd::FFDLattice;
+    /// use truck_polymesh::*;
+    /// let positions = vec![
+    ///     Point3::new(0.0, 0.0, 0.0),
+    ///     Point3::new(1.0, 0.0, 0.0),
+    ///     Point3::new(0.0, 1.0, 0.0),
+    /// ];
+    /// let normals = vec![Vector3::unit_z()];
+    /// let faces = Faces::from_iter(&[[(0, None, Some(0)), (1, None, Some(0)), (2, None, Some(0))]]);
+    /// let mesh = PolygonMesh::new(positions, Vec::new(), normals, faces);
+    ///
+    /// let bdd: BoundingBox<Point3> = vec![Point3::new(0.0, 0.0, -1.0), Point3::new(1.0, 1.0, 1.0)]
+    ///     .into_iter()
+    ///     .collect();
+    /// let mut lattice = FFDLattice::new(bdd, [1, 1, 1], 1);
+    /// // shear: z += x
+    /// for j in 0..2 {
+    ///     for k in 0..2 {
+    ///         *lattice.control_point_mut(1, j, k) += Vector3::unit_z();
+    ///     }
+    /// }
+    /// let mesh = lattice.deform_mesh(&mesh);
+    /// assert_near!(mesh.positions()[1], Point3::new(1.0, 0.0, 1.0));
+    /// let n = Vector3::new(-1.0, 0.0, 1.0).normalize();
+    /// assert_near!(mesh.normals()[0], n);
+    /// ```
+    pub fn deform_mesh(&self, mesh: &PolygonMesh) -> PolygonMesh {
+        let mut res = mesh.clone();
+        let editor = res.editor();
+        let mut transformed = vec![false; editor.normals.len()];
+        for face in editor.faces.face_iter() {
+            for v in face {
+                let nor = match v.nor {
+                    Some(nor) if !transformed[nor] => nor,
+                    _ => continue,
+                };
+                transformed[nor] = true;
+                let normal = &mut editor.normals[nor];
+                if let Some(inv) = self.jacobian(mesh.positions()[v.pos]).invert() {
+                    let vec = inv.transpose() * *normal;
+                    if !vec.so_small() {
+                        *normal = vec.normalize();
+                    }
+                }
+            }
+        }
+        editor
+            .positions
+            .iter_mut()
+            .for_each(|pt| *pt = self.apply(*pt));
+        drop(editor);
+        res
+    }
+
+    /// Returns the deformed solid.
+    ///
+    /// The points of the vertices are mapped exactly. The control points of the B-spline and
+    /// NURBS geometries are mapped with the knot refinement until the error is within `tol`.
+    /// The planes and the other surfaces are re-approximated as in
+    /// [`deformation::deformed`](../deformation/fn.deformed.html).
+    /// # Failures
+    /// Returns [`Error::ApproximationFailed`] if some curves or surfaces cannot be approximated
+    /// within the tolerance.
+    ///
+    /// [`Error::ApproximationFailed`]: ../errors/enum.Error.html#variant.ApproximationFailed
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::ffd::FFDLattice;
+    /// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let cube = builder::tsweep(&f, Vector3::unit_z());
+    ///
+    /// let bdd: BoundingBox<Point3> = vec![Point3::new(-1.0, -1.0, -1.0), Point3::new(2.0, 2.0, 2.0)]
+    ///     .into_iter()
+    ///     .collect();
+    /// let mut lattice = FFDLattice::new(bdd, [1, 1, 1], 2);
+    /// *lattice.control_point_mut(1, 1, 1) += Vector3::new(0.0, 0.0, 0.5);
+    /// let solid = lattice.deform_solid(&cube, 1.0e-3).unwrap();
+    /// // The deformation is injective, so the volume is preserved roughly.
+    /// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+    /// assert!(volume > 0.5 && volume < 1.5);
+    /// ```
+    pub fn deform_solid(&self, solid: &Solid, tol: f64) -> Result<Solid> {
+        let solid = deformation::bounded_planes(solid, tol);
+        let failed = Cell::new(false);
+        let res = solid.mapped(
+            &|pt: &Point3| self.apply(*pt),
+            &|curve: &Curve| match self.deform_curve(curve, tol) {
+                Some(curve) => curve,
+                None => {
+                    failed.set(true);
+                    curve.clone()
+                }
+            },
+            &|surface: &Surface| match self.deform_surface(surface, tol) {
+                Some(surface) => surface,
+                None => {
+                    failed.set(true);
+                    surface.clone()
+                }
+            },
+        );
+        match failed.get() {
+            true => Err(Error::ApproximationFailed),
+            false => Ok(res),
+        }
+    }
+
+    fn deform_curve(&self, curve: &Curve, tol: f64) -> Option<Curve> {
+        let mut curve = curve.clone();
+        for _ in 0..=MAX_REFINEMENT {
+            let mut res = curve.clone();
+            match &mut res {
+                Curve::BSplineCurve(bsp) => bsp.transform_control_points(|pt| {
+                    *pt = self.apply(Point3::from_vec(*pt)).to_vec()
+                }),
+                Curve::NURBSCurve(nurbs) => nurbs.transform_control_points(|pt| {
+                    *pt = self.apply(pt.to_point()).to_homogeneous() * pt[3]
+                }),
+            }
+            let (t0, t1) = curve.parameter_range();
+            let n = 2 * (curve.knot_vec().len() + 1);
+            let within = (0..n).all(|i| {
+                let t = t0 + (t1 - t0) * (i as f64 + 0.5) / n as f64;
+                self.apply(curve.subs(t)).distance(res.subs(t)) < tol
+            });
+            if within {
+                return Some(res);
+            }
+            match &mut curve {
+                Curve::BSplineCurve(bsp) => {
+                    refine_knots(bsp.knot_vec()).into_iter().for_each(|t| {
+                        bsp.add_knot(t);
+                    })
+                }
+                Curve::NURBSCurve(nurbs) => {
+                    refine_knots(nurbs.knot_vec()).into_iter().for_each(|t| {
+                        nurbs.add_knot(t);
+                    })
+                }
+            }
+        }
+        None
+    }
+
+    fn deform_surface(&self, surface: &Surface, tol: f64) -> Option<Surface> {
+        let mut surface = match surface {
+            Surface::BSplineSurface(_) | Surface::NURBSSurface(_) => surface.clone(),
+            _ => {
+                let mapping = |pt| self.apply(pt);
+                return deformation::approx_surface(surface, &mapping, tol)
+                    .map(Surface::BSplineSurface);
+            }
+        };
+        for _ in 0..=MAX_REFINEMENT {
+            let mut res = surface.clone();
+            let knot_vecs = match &mut res {
+                Surface::BSplineSurface(bsp) => {
+                    bsp.transform_control_points(|pt| {
+                        *pt = self.apply(Point3::from_vec(*pt)).to_vec()
+                    });
+                    bsp.knot_vecs().clone()
+                }
+                Surface::NURBSSurface(nurbs) => {
+                    nurbs.transform_control_points(|pt| {
+                        *pt = self.apply(pt.to_point()).to_homogeneous() * pt[3]
+                    });
+                    nurbs.knot_vecs().clone()
+                }
+                _ => unreachable!(),
+            };
+            let ((u0, u1), (v0, v1)) = surface.parameter_range();
+            let n = 2 * (knot_vecs.0.len().max(knot_vecs.1.len()) + 1);
+            let param = |t0: f64, t1: f64, i: usize| t0 + (t1 - t0) * (i as f64 + 0.5) / n as f64;
+            let within = (0..n).all(|i| {
+                (0..n).all(|j| {
+                    let (u, v) = (param(u0, u1, i), param(v0, v1, j));
+                    self.apply(surface.subs(u, v)).distance(res.subs(u, v)) < tol
+                })
+            });
+            if within {
+                return Some(res);
+            }
+            match &mut surface {
+                Surface::BSplineSurface(bsp) => {
+                    refine_knots(&knot_vecs.0).into_iter().for_each(|u| {
+                        bsp.add_uknot(u);
+                    });
+                    refine_knots(&knot_vecs.1).into_iter().for_each(|v| {
+                        bsp.add_vknot(v);
+                    });
+                }
+                Surface::NURBSSurface(nurbs) => {
+                    refine_knots(&knot_vecs.0).into_iter().for_each(|u| {
+                        nurbs.add_uknot(u);
+                    });
+                    refine_knots(&knot_vecs.1).into_iter().for_each(|v| {
+                        nurbs.add_vknot(v);
+                    });
+                }
+                _ => unreachable!(),
+            }
+        }
+        None
+    }
+}
+
+/// the Greville abscissae of the knot vector
+fn greville_abscissae(knot_vec: &KnotVec, degree: usize) -> Vec<f64> {
+    (0..knot_vec.len() - degree - 1)
+        .map(|i| (1..=degree).map(|j| knot_vec[i + j]).sum::<f64>() / degree as f64)
+        .collect()
+}
+
+/// the B-spline basis functions, in which the end point of the clamped knot vector is included
+fn basis_functions(knot_vec: &KnotVec, degree: usize, t: f64) -> Vec<f64> {
+    let len = knot_vec.len() - degree - 1;
+    match t >= knot_vec[knot_vec.len() - 1] {
+        true => (0..len)
+            .map(|i| if i + 1 == len { 1.0 } else { 0.0 })
+            .collect(),
+        false => knot_vec.bspline_basis_functions(degree, t),
+    }
+}
+
+/// the middle points of the spans of the knot vector
+fn refine_knots(knot_vec: &KnotVec) -> Vec<f64> {
+    let (knots, _) = knot_vec.to_single_multi();
+    knots.windows(2).map(|a| (a[0] + a[1]) / 2.0).collect()
+}
@@ -0,0 +1,312 @@
+use crate::errors::Error;
+use crate::*;
+use std::f64::consts::PI;
+use truck_geometry::composition;
+
+/// the tolerance of the approximations of the involutes relative to the module
+const INVOLUTE_TOLERANCE: f64 = 1.0e-4;
+/// the maximal twist of the tooth between the sections of the loft of helical gears
+const SECTION_TWIST: f64 = PI / 64.0;
+
+/// Parameters of the involute spur and helical gears.
+///
+/// The gear is standard: the addendum is the module and the dedendum is `1.25` times the
+/// module. The teeth are the involutes of the base circle between the root and the tip
+/// circles, and the flanks are extended to the root circle by the radial lines if the root
+/// circle is smaller than the base circle. The axis of the gear is the z-axis, the first
+/// tooth is centered on the x-axis, and the gear extends from `z = 0` to `z = face_width`.
+///
+/// For helical gears, `module` and `pressure_angle` are the ones in the normal plane of the
+/// teeth, and the transverse ones are derived from the helix angle.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::gear::Gear;
+/// use std::f64::consts::PI;
+///
+/// let gear = Gear {
+///     teeth: 12,
+///     face_width: 2.0,
+///     bore_diameter: 4.0,
+///     ..Default::default()
+/// };
+/// assert_near!(gear.pitch_radius(), 6.0);
+/// assert_near!(gear.tip_radius(), 7.0);
+/// assert_near!(gear.root_radius(), 4.75);
+///
+/// let solid = gear.solid().unwrap();
+/// // the side faces of the teeth, the two halves of the bore and both ends
+/// assert_eq!(solid.boundaries()[0].len(), 12 * 6 + 2 + 2);
+/// let volume = properties::mass_properties(&solid, 1.0e-2).volume;
+/// let root_cylinder = PI * (4.75 * 4.75 - 2.0 * 2.0) * 2.0;
+/// let tip_cylinder = PI * (7.0 * 7.0 - 2.0 * 2.0) * 2.0;
+/// assert!(root_cylinder < volume && volume < tip_cylinder);
+///
+/// // the points of the tips are on the tip circle
+/// let profile = gear.profile().unwrap();
+/// for vertex in profile.vertex_iter() {
+///     let radius = vertex.lock_point().unwrap().to_vec().magnitude();
+///     assert!(radius > 4.75 - TOLERANCE && radius < 7.0 + TOLERANCE);
+/// }
+///
+/// // the teeth are pointed
+/// let gear = Gear {
+///     teeth: 6,
+///     pressure_angle: Deg(45.0).into(),
+///     ..Default::default()
+/// };
+/// assert!(gear.solid().is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Gear {
+    /// the (normal) module, the pitch diameter per a tooth. The default is `1.0`.
+    pub module: f64,
+    /// the number of the teeth. The default is `20`.
+    pub teeth: usize,
+    /// the (normal) pressure angle. The default is 20 degrees.
+    pub pressure_angle: Rad<f64>,
+    /// the helix angle. The helix is right-handed if the angle is positive, and the gear is
+    /// a spur gear if the angle is zero. The default is zero.
+    pub helix_angle: Rad<f64>,
+    /// the width of the gear along the axis. The default is `10.0`.
+    pub face_width: f64,
+    /// the diameter of the bore around the axis. If zero, the gear has no bore.
+    /// The default is zero.
+    pub bore_diameter: f64,
+}
+
+impl Default for Gear {
+    #[inline(always)]
+    fn default() -> Gear {
+        Gear {
+            module: 1.0,
+            teeth: 20,
+            pressure_angle: Deg(20.0).into(),
+            helix_angle: Rad(0.0),
+            face_width: 10.0,
+            bore_diameter: 0.0,
+        }
+    }
+}
+
+impl Gear {
+    /// Returns the module in the transverse plane, perpendicular to the axis.
+    #[inline(always)]
+    pub fn transverse_module(&self) -> f64 { self.module / self.helix_angle.0.cos() }
+    /// Returns the pressure angle in the transverse plane, perpendicular to the axis.
+    #[inline(always)]
+    pub fn transverse_pressure_angle(&self) -> Rad<f64> {
+        Rad(f64::atan(self.pressure_angle.0.tan() / self.helix_angle.0.cos()))
+    }
+    /// Returns the radius of the pitch circle.
+    #[inline(always)]
+    pub fn pitch_radius(&self) -> f64 { self.transverse_module() * self.teeth as f64 / 2.0 }
+    /// Returns the radius of the base circle of the involutes.
+    #[inline(always)]
+    pub fn base_radius(&self) -> f64 {
+        self.pitch_radius() * self.transverse_pressure_angle().0.cos()
+    }
+    /// Returns the radius of the tip circle.
+    #[inline(always)]
+    pub fn tip_radius(&self) -> f64 { self.pitch_radius() + self.module }
+    /// Returns the radius of the root circle.
+    #[inline(always)]
+    pub fn root_radius(&self) -> f64 { self.pitch_radius() - 1.25 * self.module }
+
+    /// Returns the counter-clockwise outline of the teeth in the transverse plane `z = 0`.
+    /// # Failures
+    /// Returns [`Error::InvalidProfile`] if the parameters are not positive, the angles are
+    /// not in `(-π/2, π/2)`, or the teeth are pointed or interfere with each other.
+    ///
+    /// [`Error::InvalidProfile`]: ../errors/enum.Error.html#variant.InvalidProfile
+    pub fn profile(&self) -> Result<Wire> {
+        let invalid = self.module <= 0.0
+            || self.teeth < 3
+            || self.pressure_angle.0 <= 0.0
+            || self.pressure_angle.0 >= PI / 2.0
+            || self.helix_angle.0.abs() >= PI / 2.0
+            || self.root_radius() <= 0.0;
+        if invalid {
+            return Err(Error::InvalidProfile);
+        }
+        let tooth = self.tooth()?;
+        let pitch_angle = 2.0 * PI / self.teeth as f64;
+        let teeth: Vec<Wire> = (0..self.teeth)
+            .map(|k| {
+                let angle = Rad(pitch_angle * k as f64);
+                builder::rotated(&tooth, Point3::origin(), Vector3::unit_z(), angle)
+            })
+            .collect();
+        let mut wire = Wire::new();
+        for k in 0..self.teeth {
+            let previous = &teeth[(k + self.teeth - 1) % self.teeth];
+            let (v0, v1) = (previous.back_vertex(), teeth[k].front_vertex());
+            let angle = pitch_angle * (k as f64 - 0.5);
+            let transit = polar(self.root_radius(), angle).to_vec().extend(0.0);
+            let root = builder::circle_arc(v0.unwrap(), v1.unwrap(), Point3::from_vec(transit));
+            wire.push_back(root);
+            wire.extend(teeth[k].iter().cloned());
+        }
+        Ok(wire)
+    }
+
+    /// Returns the solid of the gear. The spur gear is swept along the axis, and the helical
+    /// gear is lofted through the profiles twisted along the helix.
+    /// # Failures
+    /// Returns [`Error::InvalidProfile`] if the profile is invalid, the face width is not
+    /// positive, or the bore is not inside of the root circle.
+    ///
+    /// [`Error::InvalidProfile`]: ../errors/enum.Error.html#variant.InvalidProfile
+    pub fn solid(&self) -> Result<Solid> {
+        let profile = self.profile()?;
+        let bore_radius = self.bore_diameter / 2.0;
+        if self.face_width <= 0.0 || bore_radius < 0.0 || bore_radius >= self.root_radius() {
+            return Err(Error::InvalidProfile);
+        }
+        let bore = match bore_radius > 0.0 {
+            true => {
+                let v = builder::vertex(Point3::new(bore_radius, 0.0, 0.0));
+                let circle: Wire =
+                    builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(2.0 * PI));
+                Some(circle.inverse())
+            }
+            false => None,
+        };
+        let twist = self.face_width * self.helix_angle.0.tan() / self.pitch_radius();
+        if twist.so_small() {
+            let mut wires = vec![profile];
+            wires.extend(bore);
+            let face = builder::try_attach_plane(&wires)?;
+            return Ok(builder::tsweep(&face, self.face_width * Vector3::unit_z()));
+        }
+        let sections = f64::ceil(twist.abs() / SECTION_TWIST) as usize + 1;
+        let section = |wire: &Wire, i: usize| -> Wire {
+            let ratio = i as f64 / sections as f64;
+            let mat = Matrix4::from_translation(self.face_width * ratio * Vector3::unit_z())
+                * Matrix4::from_angle_z(Rad(twist * ratio));
+            builder::transformed(wire, mat)
+        };
+        let profiles: Vec<Wire> = (0..=sections).map(|i| section(&profile, i)).collect();
+        let mut shell = builder::loft(&profiles)?;
+        let mut bottom = vec![profiles[0].clone()];
+        let mut top = vec![profiles[sections].clone()];
+        if let Some(bore) = bore {
+            let bores: Vec<Wire> = (0..=sections).map(|i| section(&bore, i)).collect();
+            shell.extend(builder::loft(&bores)?);
+            bottom.push(bores[0].clone());
+            top.push(bores[sections].clone());
+        }
+        shell.push(builder::try_attach_plane(&bottom)?.inverse());
+        shell.push(builder::try_attach_plane(&top)?);
+        Ok(Solid::try_new(vec![shell])?)
+    }
+
+    /// Returns the open wire of the first tooth from the root of the flank below the x-axis to
+    /// the root of the flank above the x-axis.
+    fn tooth(&self) -> Result<Wire> {
+        let (base_radius, root_radius) = (self.base_radius(), self.root_radius());
+        let pressure_angle = self.transverse_pressure_angle().0;
+        // the flank below the x-axis unwinds counter-clockwise and passes through the pitch
+        // circle at the half of the angular thickness of the tooth.
+        let start_angle = -PI / (2.0 * self.teeth as f64) - (pressure_angle.tan() - pressure_angle);
+        let involute = Involute::new(Point2::origin(), base_radius, start_angle, (0.0, 1.0));
+        let tip = involute.parameter_at_radius(self.tip_radius()).unwrap();
+        let start = involute
+            .parameter_at_radius(root_radius)
+            .unwrap_or(0.0);
+        let (root_angle, tip_angle) = (involute.polar_angle(start), involute.polar_angle(tip));
+        if tip_angle >= 0.0 || root_angle <= -PI / self.teeth as f64 {
+            return Err(Error::InvalidProfile);
+        }
+        let flank = Flank {
+            involute,
+            range: (start * start, tip * tip),
+        };
+        let tol = self.module * INVOLUTE_TOLERANCE;
+        let bspline = composition::cubic_approximation(&flank, 1, tol);
+        let lift = |sign: f64| {
+            let control_points = bspline
+                .control_points()
+                .iter()
+                .map(|pt| Vector3::new(pt.x, sign * pt.y, 0.0))
+                .collect();
+            BSplineCurve::new(bspline.knot_vec().clone(), control_points)
+        };
+        let vertex = |pt: Point2| builder::vertex(Point3::new(pt.x, pt.y, 0.0));
+        let mirror = |pt: Point2| Point2::new(pt.x, -pt.y);
+        let (flank_root, flank_tip) = (flank.front(), flank.back());
+        let (v1, v2) = (vertex(flank_root), vertex(flank_tip));
+        let (v3, v4) = (vertex(mirror(flank_tip)), vertex(mirror(flank_root)));
+        let mut wire = Wire::new();
+        let radial = root_radius < base_radius;
+        if radial {
+            let v0 = vertex(polar(root_radius, root_angle));
+            wire.push_back(builder::line(&v0, &v1));
+        }
+        wire.push_back(Edge::new(&v1, &v2, Curve::BSplineCurve(lift(1.0))));
+        let transit = Point3::new(self.tip_radius(), 0.0, 0.0);
+        wire.push_back(builder::circle_arc(&v2, &v3, transit));
+        wire.push_back(Edge::new(&v4, &v3, Curve::BSplineCurve(lift(-1.0))).inverse());
+        if radial {
+            let v5 = vertex(polar(root_radius, -root_angle));
+            wire.push_back(builder::line(&v4, &v5));
+        }
+        Ok(wire)
+    }
+}
+
+/// the involute parameterized by the square `s = t * t` of the roll angle `t`, which is regular
+/// at the cusp on the base circle, so that the flank can be lofted
+#[derive(Clone, Copy, Debug)]
+struct Flank {
+    involute: Involute,
+    range: (f64, f64),
+}
+
+impl ParametricCurve for Flank {
+    type Point = Point2;
+    type Vector = Vector2;
+    #[inline(always)]
+    fn subs(&self, s: f64) -> Point2 { self.involute.subs(f64::sqrt(s.max(0.0))) }
+    #[inline(always)]
+    fn der(&self, s: f64) -> Vector2 {
+        let angle = self.involute.start_angle() + f64::sqrt(s.max(0.0));
+        self.involute.radius() / 2.0 * Vector2::new(angle.cos(), angle.sin())
+    }
+    #[inline(always)]
+    fn der2(&self, s: f64) -> Vector2 {
+        let t = f64::sqrt(s.max(0.0));
+        let angle = self.involute.start_angle() + t;
+        match t.so_small() {
+            true => Vector2::zero(),
+            false => self.involute.radius() / (4.0 * t) * Vector2::new(-angle.sin(), angle.cos()),
+        }
+    }
+    #[inline(always)]
+    fn parameter_range(&self) -> (f64, f64) { self.range }
+}
+
+#[inline(always)]
+fn polar(radius: f64, angle: f64) -> Point2 {
+    Point2::new(radius * angle.cos(), radius * angle.sin())
+}
+
+#[test]
+fn helical_gear_volume() {
+    let gear = Gear {
+        teeth: 16,
+        face_width: 3.0,
+        bore_diameter: 6.0,
+        helix_angle: Deg(15.0).into(),
+        ..Default::default()
+    };
+    let helical = gear.solid().unwrap();
+    assert_eq!(helical.boundaries()[0].len(), 16 * 6 + 2 + 2);
+    let face = builder::try_attach_plane(&vec![gear.profile().unwrap()]).unwrap();
+    let extruded = builder::tsweep(&face, 3.0 * Vector3::unit_z());
+    // the twist of the sections keeps the volume
+    let volume = properties::mass_properties(&helical, 1.0e-2).volume;
+    let bore = PI * 9.0 * 3.0;
+    let expected = properties::mass_properties(&extruded, 1.0e-2).volume - bore;
+    assert!((volume - expected).abs() < expected * 1.0e-2, "{} {}", volume, expected);
+}
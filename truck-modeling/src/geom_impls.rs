@@ -91,8 +91,7 @@ pub(super) fn attach_plane(mut pts: Vec<Point3>) -> Option<Plane> {
         false => Vector3::new(n[1], -n[0], 0.0).normalize(),
     };
     let mat: Matrix4 = Matrix3::from_cols(a, n.cross(a), n).into();
-    pts.iter_mut()
-        .for_each(|pt| *pt = mat.invert().unwrap().transform_point(*pt));
+    truck_base::batch::transform_points(&mat.invert().unwrap(), &mut pts);
     let bnd_box: BoundingBox<Point3> = pts.iter().collect();
     let diag = bnd_box.diagonal();
     if !diag[2].so_small() {
@@ -1,7 +1,9 @@
 use super::*;
 use serde::{Serialize, Deserialize};
 use truck_base::geom_traits::{Invertible, ParametricSurface};
-pub use truck_geometry::{decorators::*, nurbs::*, specifieds::*};
+use std::str::SplitWhitespace;
+use truck_topology::TextGeometry;
+pub use truck_geometry::{decorators::*, nurbs::*, sample_grid::*, specifieds::*};
 #[doc(hidden)]
 pub use truck_geometry::{inv_or_zero, presearch};
 
@@ -162,6 +164,20 @@ impl ParametricSurface for Surface {
     }
 }
 
+impl BoundedSurface for Surface {
+    #[inline(always)]
+    fn parameter_range(&self) -> ((f64, f64), (f64, f64)) {
+        derive_surface_method!(self, BoundedSurface::parameter_range,)
+    }
+}
+
+impl ParameterDivision2D for Surface {
+    #[inline(always)]
+    fn parameter_division(&self, tol: f64) -> (Vec<f64>, Vec<f64>) {
+        derive_surface_method!(self, ParameterDivision2D::parameter_division, tol)
+    }
+}
+
 impl Invertible for Surface {
     fn invert(&mut self) { derive_surface_method!(self, Invertible::invert,) }
     fn inverse(&self) -> Self { derive_surface_self_method!(self, Invertible::inverse,) }
@@ -220,6 +236,33 @@ impl IncludeCurve<Curve> for Surface {
     }
 }
 
+impl SampleGrid for Surface {
+    /// Samples the surface on the grid. The B-spline and NURBS surfaces are evaluated in batch.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let face = builder::tsweep(&e, Vector3::unit_y());
+    /// let surface = face.lock_surface().unwrap().clone();
+    /// let grid = surface.sample_grid(11, 11);
+    /// for vec in &grid {
+    ///     for sample in vec {
+    ///         assert_near!(surface.subs(sample.uv[0], sample.uv[1]), sample.point);
+    ///         assert_near!(surface.normal(sample.uv[0], sample.uv[1]), sample.normal);
+    ///     }
+    /// }
+    /// ```
+    fn sample_grid(&self, nu: usize, nv: usize) -> Vec<Vec<SurfaceSample>> {
+        match self {
+            Surface::Plane(surface) => surface.sample_grid(nu, nv),
+            Surface::BSplineSurface(surface) => surface.sample_grid(nu, nv),
+            Surface::NURBSSurface(surface) => surface.sample_grid(nu, nv),
+            Surface::RevolutedCurve(_) => sample_grid_by_subs(self, nu, nv),
+        }
+    }
+}
+
 impl Surface {
     /// Serach the parameter `(u, v)` such that `self.subs(u, v).rational_projection()` is near `pt`.
     /// If cannot find, then return `None`.
@@ -238,3 +281,123 @@ impl Surface {
         }
     }
 }
+
+/// The tokens of the curves in the text format "truck brep v1": `bspline` or `nurbs`,
+/// the knot vector and the control points.
+impl TextGeometry for Curve {
+    fn write_tokens(&self, tokens: &mut Vec<String>) {
+        match self {
+            Curve::BSplineCurve(curve) => {
+                tokens.push("bspline".into());
+                curve.knot_vec().as_slice().to_vec().write_tokens(tokens);
+                curve.control_points().write_tokens(tokens);
+            }
+            Curve::NURBSCurve(curve) => {
+                tokens.push("nurbs".into());
+                curve.knot_vec().as_slice().to_vec().write_tokens(tokens);
+                curve.control_points().write_tokens(tokens);
+            }
+        }
+    }
+    fn read_tokens(tokens: &mut SplitWhitespace) -> Option<Curve> {
+        match tokens.next()? {
+            "bspline" => {
+                let knot_vec = KnotVec::try_from(Vec::read_tokens(tokens)?).ok()?;
+                let curve = BSplineCurve::try_new(knot_vec, Vec::read_tokens(tokens)?).ok()?;
+                Some(Curve::BSplineCurve(curve))
+            }
+            "nurbs" => {
+                let knot_vec = KnotVec::try_from(Vec::read_tokens(tokens)?).ok()?;
+                let curve = BSplineCurve::try_new(knot_vec, Vec::read_tokens(tokens)?).ok()?;
+                Some(Curve::NURBSCurve(NURBSCurve::new(curve)))
+            }
+            _ => None,
+        }
+    }
+}
+
+/// The tokens of the surfaces in the text format "truck brep v1": `plane` and the origin and
+/// the axes, `bspline` or `nurbs` and the knot vectors and the control points, or `revolution`
+/// and the origin, the axis, the transform, the orientation and the curve.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_y());
+/// let cylinder = builder::rsweep(&e, Point3::origin(), Vector3::unit_y(), Rad(7.0));
+///
+/// // golden file regression test
+/// let text = cylinder.compress().to_text();
+/// let shell = Shell::extract(CompressedShell::from_text(&text).unwrap()).unwrap();
+/// assert_eq!(shell.compress().to_text(), text);
+/// ```
+impl TextGeometry for Surface {
+    fn write_tokens(&self, tokens: &mut Vec<String>) {
+        match self {
+            Surface::Plane(plane) => {
+                tokens.push("plane".into());
+                plane.origin().write_tokens(tokens);
+                plane.u_axis().write_tokens(tokens);
+                plane.v_axis().write_tokens(tokens);
+            }
+            Surface::BSplineSurface(surface) => {
+                tokens.push("bspline".into());
+                surface.knot_vecs().0.as_slice().to_vec().write_tokens(tokens);
+                surface.knot_vecs().1.as_slice().to_vec().write_tokens(tokens);
+                surface.control_points().write_tokens(tokens);
+            }
+            Surface::NURBSSurface(surface) => {
+                tokens.push("nurbs".into());
+                surface.knot_vecs().0.as_slice().to_vec().write_tokens(tokens);
+                surface.knot_vecs().1.as_slice().to_vec().write_tokens(tokens);
+                surface.control_points().write_tokens(tokens);
+            }
+            Surface::RevolutedCurve(surface) => {
+                tokens.push("revolution".into());
+                surface.entity().origin().write_tokens(tokens);
+                surface.entity().axis().write_tokens(tokens);
+                surface.transform().write_tokens(tokens);
+                surface.orientation().write_tokens(tokens);
+                surface.entity().entity_curve().write_tokens(tokens);
+            }
+        }
+    }
+    fn read_tokens(tokens: &mut SplitWhitespace) -> Option<Surface> {
+        match tokens.next()? {
+            "plane" => {
+                let origin = Point3::read_tokens(tokens)?;
+                let u_axis = Vector3::read_tokens(tokens)?;
+                let v_axis = Vector3::read_tokens(tokens)?;
+                Some(Surface::Plane(Plane::new(origin, origin + u_axis, origin + v_axis)))
+            }
+            "bspline" => {
+                let uknot_vec = KnotVec::try_from(Vec::read_tokens(tokens)?).ok()?;
+                let vknot_vec = KnotVec::try_from(Vec::read_tokens(tokens)?).ok()?;
+                let control_points = Vec::read_tokens(tokens)?;
+                let surface = BSplineSurface::try_new((uknot_vec, vknot_vec), control_points).ok()?;
+                Some(Surface::BSplineSurface(surface))
+            }
+            "nurbs" => {
+                let uknot_vec = KnotVec::try_from(Vec::read_tokens(tokens)?).ok()?;
+                let vknot_vec = KnotVec::try_from(Vec::read_tokens(tokens)?).ok()?;
+                let control_points = Vec::read_tokens(tokens)?;
+                let surface = BSplineSurface::try_new((uknot_vec, vknot_vec), control_points).ok()?;
+                Some(Surface::NURBSSurface(NURBSSurface::new(surface)))
+            }
+            "revolution" => {
+                let origin = Point3::read_tokens(tokens)?;
+                let axis = Vector3::read_tokens(tokens)?;
+                let transform = Matrix4::read_tokens(tokens)?;
+                let orientation = bool::read_tokens(tokens)?;
+                let curve = Curve::read_tokens(tokens)?;
+                let mut surface = Processor::new(RevolutedCurve::by_revolution(curve, origin, axis));
+                surface.transform_by(transform);
+                if !orientation {
+                    surface.invert();
+                }
+                Some(Surface::RevolutedCurve(surface))
+            }
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,376 @@
+use crate::errors::Error;
+use crate::*;
+use std::collections::HashMap;
+
+// the number of the sample points on each edge checking the planarity of the boundaries
+const EDGE_SAMPLES: usize = 8;
+
+/// Constructing solids from the imperfect shells, e.g. the imported ones.
+pub trait SolidHealing: Sized {
+    /// Heals `shell` and constructs the solid.
+    ///
+    /// - The orientations of the faces are made consistent.
+    /// - Each open boundary is capped by a planar face if the boundary is in one plane
+    ///   within `tol`, otherwise by the fan of the ruled faces to the centroid of the boundary.
+    /// - Each connected component is oriented outward. The components inside the bounding
+    ///   box of the largest component are regarded as voids, and oriented inward.
+    ///
+    /// Only then the solid is constructed, so the shells which do not satisfy the strict
+    /// requirements of [`Solid::new`] can be converted.
+    /// # Errors
+    /// - Returns `Error::NonOrientableShell` if the orientations cannot be made consistent.
+    /// - Returns `Error::UncappableBoundary` if a boundary is not closed or cannot be capped.
+    /// - Returns the errors of [`Solid::try_new`] if the healed shell is still not closed,
+    ///   e.g. an edge is shared by three faces.
+    ///
+    /// [`Solid::new`]: ../struct.Solid.html#method.new
+    /// [`Solid::try_new`]: ../struct.Solid.html#method.try_new
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::healing::SolidHealing;
+    /// let v = builder::vertex(Point3::origin());
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let cube = builder::tsweep(&f, Vector3::unit_z());
+    ///
+    /// // a shell with a hole and an inverted face, as often imported
+    /// let mut shell = cube.into_boundaries().pop().unwrap();
+    /// shell.remove(5);
+    /// shell[2].invert();
+    /// assert!(Solid::try_new(vec![shell.clone()]).is_err());
+    ///
+    /// let solid = Solid::try_from_shell_healed(shell, 1.0e-6).unwrap();
+    /// assert_eq!(solid.boundaries()[0].len(), 6);
+    /// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+    /// assert!((volume - 1.0).abs() < 1.0e-3);
+    /// ```
+    fn try_from_shell_healed(shell: Shell, tol: f64) -> Result<Self>;
+}
+
+impl SolidHealing for Solid {
+    fn try_from_shell_healed(shell: Shell, tol: f64) -> Result<Solid> {
+        let shell = orient_shell(&shell).ok_or(Error::NonOrientableShell)?;
+        let shell = cap_boundaries(&shell, tol)?;
+        let mut components: Vec<(Shell, f64, BoundingBox<Point3>)> = shell
+            .connected_components()
+            .into_iter()
+            .map(|component| {
+                let solid = Solid::new_unchecked(vec![component.clone()]);
+                let volume = properties::mass_properties(&solid, f64::max(tol, 1.0e-3)).volume;
+                let bdd_box = component
+                    .face_iter()
+                    .flat_map(|face| face.boundaries())
+                    .flatten()
+                    .map(|edge| *edge.front().lock_point().unwrap())
+                    .collect();
+                (component, volume, bdd_box)
+            })
+            .collect();
+        let outer = components
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.1.abs().partial_cmp(&b.1.abs()).unwrap())
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        let outer_box = components.get(outer).map(|(_, _, bdd_box)| bdd_box.clone());
+        let boundaries = components
+            .drain(..)
+            .enumerate()
+            .map(|(i, (mut component, volume, bdd_box))| {
+                let is_void = i != outer
+                    && matches!(&outer_box, Some(outer_box) if (0..3).all(|k| {
+                        outer_box.min()[k] <= bdd_box.min()[k] + tol
+                            && bdd_box.max()[k] <= outer_box.max()[k] + tol
+                    }));
+                if (volume < 0.0) != is_void {
+                    component.face_iter_mut().for_each(|face| {
+                        face.invert();
+                    });
+                }
+                component
+            })
+            .collect();
+        Ok(Solid::try_new(boundaries)?)
+    }
+}
+
+/// Returns the shell whose faces are inverted so that the orientations are consistent,
+/// i.e. each edge shared by two faces is used in the opposite directions.
+///
+/// Returns `None` if the shell is not orientable, e.g. a Möbius strip. The edges shared by
+/// three or more faces are ignored.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let mut shell = builder::tsweep(&f, Vector3::unit_z()).into_boundaries().pop().unwrap();
+/// shell[0].invert();
+/// shell[3].invert();
+/// assert_ne!(shell.shell_condition(), ShellCondition::Closed);
+///
+/// let shell = healing::orient_shell(&shell).unwrap();
+/// assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+/// ```
+pub fn orient_shell(shell: &Shell) -> Option<Shell> {
+    let mut edge_faces = HashMap::<EdgeID, Vec<(usize, bool)>>::new();
+    shell.face_iter().enumerate().for_each(|(i, face)| {
+        face.boundary_iters()
+            .into_iter()
+            .flatten()
+            .for_each(|edge| {
+                let direction = edge.front() == edge.absolute_front();
+                edge_faces
+                    .entry(edge.id())
+                    .or_default()
+                    .push((i, direction));
+            })
+    });
+    // the adjacent faces and whether the shared edge is used in the same direction
+    let mut adjacency = vec![Vec::new(); shell.len()];
+    edge_faces
+        .values()
+        .filter(|faces| faces.len() == 2)
+        .for_each(|faces| {
+            let ((i, dir0), (j, dir1)) = (faces[0], faces[1]);
+            adjacency[i].push((j, dir0 == dir1));
+            adjacency[j].push((i, dir0 == dir1));
+        });
+    let mut flips: Vec<Option<bool>> = vec![None; shell.len()];
+    for start in 0..shell.len() {
+        if flips[start].is_some() {
+            continue;
+        }
+        flips[start] = Some(false);
+        let mut stack = vec![start];
+        while let Some(i) = stack.pop() {
+            let flip = flips[i].unwrap();
+            for (j, same) in &adjacency[i] {
+                let required = flip ^ same;
+                match flips[*j] {
+                    None => {
+                        flips[*j] = Some(required);
+                        stack.push(*j);
+                    }
+                    Some(got) if got != required => return None,
+                    Some(_) => {}
+                }
+            }
+        }
+    }
+    let mut shell = shell.clone();
+    shell.face_iter_mut().zip(flips).for_each(|(face, flip)| {
+        if flip == Some(true) {
+            face.invert();
+        }
+    });
+    Some(shell)
+}
+
+/// Returns the shell whose open boundaries are capped.
+///
+/// The shell should be oriented, cf. [`orient_shell`]. Each boundary is capped by a planar
+/// face if it is in one plane within `tol`, otherwise by the fan of the ruled faces from the
+/// edges to the centroid of the boundary, which is a reasonable patch for small holes.
+/// # Errors
+/// Returns `Error::UncappableBoundary` if a boundary is not closed or cannot be capped.
+///
+/// [`orient_shell`]: ./fn.orient_shell.html
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let mut shell = builder::tsweep(&f, Vector3::unit_z()).into_boundaries().pop().unwrap();
+/// shell.remove(0);
+/// shell.remove(0);
+/// assert_eq!(shell.extract_boundaries().len(), 1);
+///
+/// // The boundary of the two removed faces is not in one plane.
+/// let shell = healing::cap_boundaries(&shell, 1.0e-6).unwrap();
+/// assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+/// ```
+pub fn cap_boundaries(shell: &Shell, tol: f64) -> Result<Shell> {
+    let mut res = shell.clone();
+    for boundary in shell.extract_boundaries() {
+        if !boundary.is_closed() {
+            return Err(Error::UncappableBoundary);
+        }
+        let wire = boundary.inverse();
+        match planar_cap(&wire, tol) {
+            Some(face) => res.push(face),
+            None => res.extend(fan_cap(&wire)?),
+        }
+    }
+    Ok(res)
+}
+
+// the planar face bounded by `wire`, if `wire` is in one plane within `tol`
+fn planar_cap(wire: &Wire, tol: f64) -> Option<Face> {
+    let pts: Vec<Point3> = wire
+        .edge_iter()
+        .flat_map(|edge| {
+            let curve = edge.oriented_curve();
+            let (t0, t1) = curve.parameter_range();
+            (0..EDGE_SAMPLES)
+                .map(|i| curve.subs(t0 + (t1 - t0) * i as f64 / EDGE_SAMPLES as f64))
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    let center = pts
+        .iter()
+        .fold(Point3::origin(), |sum, pt| sum + pt.to_vec())
+        / pts.len() as f64;
+    // the normal by Newell's method, oriented by the direction of the wire
+    let normal = (0..pts.len()).fold(Vector3::zero(), |sum, i| {
+        let (p, q) = (pts[i], pts[(i + 1) % pts.len()]);
+        sum + (p - center).cross(q - center)
+    });
+    if normal.so_small() {
+        return None;
+    }
+    let n = normal.normalize();
+    if pts.iter().any(|pt| (pt - center).dot(n).abs() > tol) {
+        return None;
+    }
+    let a = match (n[2].abs() - 1.0).so_small() {
+        true => Vector3::new(0.0, n[2], -n[1]).normalize(),
+        false => Vector3::new(n[1], -n[0], 0.0).normalize(),
+    };
+    let plane = Plane::new(center, center + a, center + n.cross(a));
+    Face::try_new(vec![wire.clone()], Surface::Plane(plane)).ok()
+}
+
+// the faces of the fan from the edges of `wire` to the centroid of the vertices
+fn fan_cap(wire: &Wire) -> Result<Vec<Face>> {
+    let vertices: Vec<Vertex> = wire.edge_iter().map(|edge| edge.front().clone()).collect();
+    if vertices.len() < 2 {
+        return Err(Error::UncappableBoundary);
+    }
+    let center = vertices.iter().fold(Point3::origin(), |sum, v| {
+        sum + v.lock_point().unwrap().to_vec()
+    }) / vertices.len() as f64;
+    let apex = builder::vertex(center);
+    let mut spokes = HashMap::<VertexID, Edge>::new();
+    vertices.iter().for_each(|v| {
+        spokes
+            .entry(v.id())
+            .or_insert_with(|| builder::line(v, &apex));
+    });
+    let apex_curve = BSplineCurve::new(KnotVec::bezier_knot(1), vec![center.to_homogeneous(); 2]);
+    wire.edge_iter()
+        .map(|edge| {
+            let boundary: Wire = vec![
+                edge.clone(),
+                spokes[&edge.back().id()].clone(),
+                spokes[&edge.front().id()].inverse(),
+            ]
+            .into();
+            let curve = edge.oriented_curve().lift_up();
+            let surface = BSplineSurface::homotopy(curve, apex_curve.clone());
+            Face::try_new(
+                vec![boundary],
+                Surface::NURBSSurface(NURBSSurface::new(surface)),
+            )
+            .map_err(|_| Error::UncappableBoundary)
+        })
+        .collect()
+}
+
+/// Merges two faces lying on the same underlying surface into one face, dissolving the
+/// shared edges, e.g. two halves of an imported cylinder split by the seams.
+///
+/// The faces are on the same surface if the surface of each face includes the boundary
+/// curves of the other face within `TOLERANCE`. The merged face has the surface of `face_a`,
+/// and the other edges and vertices are shared with the original faces, so the merged face
+/// can replace them in the shell. If the shared edges are all the seams of a closed surface,
+/// the merged face is bounded by the separated loops.
+/// # Errors
+/// Returns `Error::FacesNotMergeable` if the faces do not share an edge, are not on the
+/// same surface, or are not oriented consistently.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use std::f64::consts::PI;
+/// let v0 = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+/// let v1 = builder::vertex(Point3::new(1.0, 1.0, 0.0));
+/// let edge = builder::line(&v0, &v1);
+/// // The sweep of the angle more than PI is divided into two faces.
+/// let shell = builder::rsweep(&edge, Point3::origin(), Vector3::unit_y(), Rad(1.5 * PI));
+/// assert_eq!(shell.len(), 2);
+///
+/// let face = healing::merge_faces(&shell[0], &shell[1]).unwrap();
+/// assert_eq!(face.boundaries().len(), 1);
+/// assert_eq!(face.boundaries()[0].len(), 6);
+/// ```
+pub fn merge_faces(face_a: &Face, face_b: &Face) -> Result<Face> {
+    let wires_a = face_a.boundaries();
+    let wires_b = face_b.boundaries();
+    let edges_a: HashMap<EdgeID, Edge> = wires_a
+        .iter()
+        .flatten()
+        .map(|edge| (edge.id(), edge.clone()))
+        .collect();
+    let edges_b: HashMap<EdgeID, Edge> = wires_b
+        .iter()
+        .flatten()
+        .map(|edge| (edge.id(), edge.clone()))
+        .collect();
+    let mut shared = 0;
+    for (id, edge) in &edges_a {
+        if let Some(other) = edges_b.get(id) {
+            // The consistent faces use the shared edge in the opposite directions.
+            if edge.front() == other.front() {
+                return Err(Error::FacesNotMergeable);
+            }
+            shared += 1;
+        }
+    }
+    let surface_a = face_a.oriented_surface();
+    let surface_b = face_b.oriented_surface();
+    let same_surface = wires_b
+        .iter()
+        .flatten()
+        .all(|edge| surface_a.include(&edge.oriented_curve()))
+        && wires_a
+            .iter()
+            .flatten()
+            .all(|edge| surface_b.include(&edge.oriented_curve()));
+    if shared == 0 || !same_surface {
+        return Err(Error::FacesNotMergeable);
+    }
+    let mut remained = HashMap::<VertexID, Vec<Edge>>::new();
+    wires_a
+        .iter()
+        .flatten()
+        .filter(|edge| !edges_b.contains_key(&edge.id()))
+        .chain(
+            wires_b
+                .iter()
+                .flatten()
+                .filter(|edge| !edges_a.contains_key(&edge.id())),
+        )
+        .for_each(|edge| {
+            remained
+                .entry(edge.front().id())
+                .or_default()
+                .push(edge.clone())
+        });
+    let mut wires = Vec::new();
+    while let Some(start) = remained.keys().next().copied() {
+        let mut wire = Wire::new();
+        let mut cursor = start;
+        while let Some(edge) = remained.get_mut(&cursor).and_then(Vec::pop) {
+            if remained[&cursor].is_empty() {
+                remained.remove(&cursor);
+            }
+            cursor = edge.back().id();
+            wire.push_back(edge);
+        }
+        wires.push(wire);
+    }
+    Face::try_new(wires, surface_a).map_err(|_| Error::FacesNotMergeable)
+}
@@ -0,0 +1,203 @@
+use crate::direct_edit::{intersect_planes, is_line, oriented_plane};
+use crate::errors::Error;
+use crate::*;
+use std::collections::HashMap;
+
+/// Returns the plane moved by `thickness` to the opposite of the normal.
+fn offset_inward(plane: &Plane, thickness: f64) -> Plane {
+    plane.transformed(Matrix4::from_translation(-plane.normal() * thickness))
+}
+
+/// Returns the planes of the faces incident to the vertex, where the planes of the kept faces
+/// are offset inward by `thickness` and the ones of the removed faces are not.
+fn offset_planes(
+    faces: &[(usize, &Face)],
+    removed: &[FaceID],
+    vertex: &Vertex,
+    thickness: f64,
+) -> Result<Vec<Plane>> {
+    let incident = |face: &Face| {
+        face.absolute_boundaries()
+            .iter()
+            .flatten()
+            .any(|edge| edge.front() == vertex || edge.back() == vertex)
+    };
+    faces
+        .iter()
+        .filter(|(_, face)| incident(face))
+        .map(|(_, face)| {
+            let plane = oriented_plane(face)?;
+            Ok(match removed.contains(&face.id()) {
+                true => plane,
+                false => offset_inward(&plane, thickness),
+            })
+        })
+        .collect()
+}
+
+/// Hollows out the solid by the walls of `thickness`, and opens the removed faces.
+///
+/// The solid should be bounded by planes and lines, and each vertex should be shared by
+/// the planes whose offsets meet at one point.
+pub(crate) fn hollow(solid: &Solid, thickness: f64, faces_to_remove: &[Face]) -> Result<Solid> {
+    if thickness <= 0.0 {
+        return Err(Error::InvalidThickness);
+    }
+    let faces: Vec<(usize, &Face)> = solid
+        .boundaries()
+        .iter()
+        .enumerate()
+        .flat_map(|(i, shell)| shell.iter().map(move |face| (i, face)))
+        .collect();
+    let removed: Vec<FaceID> = faces_to_remove.iter().map(|face| face.id()).collect();
+    if removed
+        .iter()
+        .any(|id| faces.iter().all(|(_, face)| face.id() != *id))
+    {
+        return Err(Error::ElementNotFound);
+    }
+    let kept: Vec<(usize, &Face)> = faces
+        .iter()
+        .filter(|(_, face)| !removed.contains(&face.id()))
+        .cloned()
+        .collect();
+
+    // the vertices of the inner faces
+    let mut inner_vertices = HashMap::<VertexID, Vertex>::new();
+    for (_, face) in &kept {
+        for vertex in face.absolute_boundaries().iter().flat_map(|wire| wire.vertex_iter()) {
+            if inner_vertices.contains_key(&vertex.id()) {
+                continue;
+            }
+            let planes = offset_planes(&faces, &removed, &vertex, thickness)?;
+            let pt = intersect_planes(&planes).ok_or(Error::DegenerateIntersection)?;
+            inner_vertices.insert(vertex.id(), builder::vertex(pt));
+        }
+    }
+    // the inner edges in the absolute directions of the original edges
+    let mut inner_edges = HashMap::<EdgeID, Edge>::new();
+    for edge in kept
+        .iter()
+        .flat_map(|(_, face)| face.absolute_boundaries().iter().flatten())
+    {
+        if inner_edges.contains_key(&edge.id()) {
+            continue;
+        }
+        if !is_line(&edge.lock_curve().unwrap()) {
+            return Err(Error::UnsupportedGeometry);
+        }
+        let (front, back) = edge.absolute_ends();
+        let (v0, v1) = (&inner_vertices[&front.id()], &inner_vertices[&back.id()]);
+        let (p, q) = (*front.lock_point().unwrap(), *back.lock_point().unwrap());
+        let (p0, q0) = (*v0.lock_point().unwrap(), *v1.lock_point().unwrap());
+        // The inner edge is turned over if the thickness is too large.
+        if (q0 - p0).dot(q - p) <= 0.0 {
+            return Err(Error::InvalidThickness);
+        }
+        inner_edges.insert(edge.id(), builder::line(v0, v1));
+    }
+    let inner_edge = |edge: &Edge| match edge.orientation() {
+        true => inner_edges[&edge.id()].clone(),
+        false => inner_edges[&edge.id()].inverse(),
+    };
+
+    let mut shells: Vec<Shell> = solid.boundaries().iter().map(|_| Shell::new()).collect();
+    let mut inner_shells: Vec<Shell> = solid.boundaries().iter().map(|_| Shell::new()).collect();
+    for (shell_index, face) in &kept {
+        shells[*shell_index].push((*face).clone());
+        let wires: Vec<Wire> = face
+            .boundaries()
+            .iter()
+            .map(|wire| wire.iter().rev().map(|edge| inner_edge(edge).inverse()).collect())
+            .collect();
+        let plane = offset_inward(&oriented_plane(face)?, thickness);
+        inner_shells[*shell_index].push(Face::try_new(wires, Surface::Plane(plane.inverse()))?);
+    }
+
+    // the rims of the openings, on the planes of the removed faces
+    let mut connectors = HashMap::<VertexID, Edge>::new();
+    let mut connector = |vertex: &Vertex| {
+        connectors
+            .entry(vertex.id())
+            .or_insert_with(|| builder::line(vertex, &inner_vertices[&vertex.id()]))
+            .clone()
+    };
+    let mut opened = vec![false; shells.len()];
+    for (shell_index, face) in faces
+        .iter()
+        .filter(|(_, face)| removed.contains(&face.id()))
+    {
+        opened[*shell_index] = true;
+        let plane = oriented_plane(face)?;
+        for edge in face.boundaries().iter().flatten() {
+            if !inner_edges.contains_key(&edge.id()) {
+                continue;
+            }
+            let wire: Wire = vec![
+                edge.clone(),
+                connector(edge.back()),
+                inner_edge(edge).inverse(),
+                connector(edge.front()).inverse(),
+            ]
+            .into();
+            shells[*shell_index].push(Face::try_new(vec![wire], Surface::Plane(plane))?);
+        }
+    }
+
+    let mut boundaries = Vec::new();
+    for ((mut shell, inner), opened) in shells.into_iter().zip(inner_shells).zip(opened) {
+        match opened {
+            true => {
+                shell.extend(inner);
+                boundaries.push(shell);
+            }
+            false => {
+                boundaries.push(shell);
+                boundaries.push(inner);
+            }
+        }
+    }
+    Ok(Solid::try_new(boundaries)?)
+}
+
+#[test]
+fn hollow_cube() {
+    let v = builder::vertex(Point3::new(-1.0, -1.0, -1.0));
+    let e = builder::tsweep(&v, 2.0 * Vector3::unit_x());
+    let f = builder::tsweep(&e, 2.0 * Vector3::unit_y());
+    let cube = builder::tsweep(&f, 2.0 * Vector3::unit_z());
+    let is_valid = |solid: &Solid| {
+        solid.boundaries().iter().all(|shell| {
+            shell.shell_condition() == ShellCondition::Closed
+                && shell
+                    .iter()
+                    .flat_map(|face| face.boundaries().into_iter().flatten())
+                    .all(|edge| edge.is_geometric_consistent())
+        })
+    };
+
+    // the closed cavity: the outer cube minus the inner cube
+    let solid = hollow(&cube, 0.25, &[]).unwrap();
+    assert_eq!(solid.boundaries().len(), 2);
+    assert!(is_valid(&solid));
+    let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+    assert_near!(volume, 8.0 - 1.5 * 1.5 * 1.5);
+    let inner = Solid::new(vec![solid.boundaries()[1].clone()]);
+    assert_near!(properties::mass_properties(&inner, 1.0e-3).volume, -1.5 * 1.5 * 1.5);
+
+    // the box opened at the top and the bottom
+    let opened: Vec<Face> = cube.boundaries()[0]
+        .iter()
+        .filter(|face| {
+            let normal = face.oriented_surface().normal(0.5, 0.5);
+            normal.near(&Vector3::unit_z()) || normal.near(&-Vector3::unit_z())
+        })
+        .cloned()
+        .collect();
+    assert_eq!(opened.len(), 2);
+    let solid = hollow(&cube, 0.25, &opened).unwrap();
+    assert_eq!(solid.boundaries().len(), 1);
+    assert!(is_valid(&solid));
+    let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+    assert_near!(volume, 8.0 - 1.5 * 1.5 * 2.0);
+}
@@ -0,0 +1,202 @@
+use crate::tessellation::tessellate_face;
+use crate::*;
+use std::collections::HashSet;
+
+/// The interference of two faces of a shell.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaceInterference {
+    /// the indices of the two faces in the shell
+    pub faces: (usize, usize),
+    /// a point on the intersection of the two faces
+    pub point: Point3,
+}
+
+/// Returns the pairs of faces of the shell which penetrate each other.
+///
+/// The faces are tessellated with the tolerance `tol`, and the triangles of each pair of
+/// faces are checked for the transversal intersections. The contacts along the common edges
+/// and at the common vertices of adjacent faces are not interferences.
+/// At most one interference is reported for each pair of faces.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// // the unit square on the plane z = 0
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let face0 = builder::tsweep(&e, Vector3::unit_y());
+/// // the square on the plane x = 0.5 crossing the above square
+/// let v = builder::vertex(Point3::new(0.5, 0.0, -0.5));
+/// let e = builder::tsweep(&v, Vector3::unit_y());
+/// let face1 = builder::tsweep(&e, Vector3::unit_z());
+///
+/// let shell = Shell::from(vec![face0, face1]);
+/// let interferences = interference::face_interferences(&shell, 0.01);
+/// assert_eq!(interferences.len(), 1);
+/// assert_eq!(interferences[0].faces, (0, 1));
+/// assert_near!(interferences[0].point.x, 0.5);
+/// assert_near!(interferences[0].point.z, 0.0);
+///
+/// // the faces of a cube do not interfere with each other
+/// let cube = builder::tsweep(&shell[0], Vector3::unit_z());
+/// assert!(!interference::has_face_interference(&cube.boundaries()[0], 0.01));
+/// ```
+pub fn face_interferences(shell: &Shell, tol: f64) -> Vec<FaceInterference> {
+    let meshes: Vec<FaceMesh> = shell.iter().map(|face| FaceMesh::new(face, tol)).collect();
+    let mut res = Vec::new();
+    for (i, mesh0) in meshes.iter().enumerate() {
+        for (j, mesh1) in meshes.iter().enumerate().skip(i + 1) {
+            if !bbox_overlap(&mesh0.bdd_box, &mesh1.bdd_box, tol) {
+                continue;
+            }
+            let common = common_boundary(mesh0, mesh1, tol);
+            if let Some(point) = mesh0.interference(mesh1, &common, tol) {
+                res.push(FaceInterference {
+                    faces: (i, j),
+                    point,
+                });
+            }
+        }
+    }
+    res
+}
+
+/// Returns whether some faces of the shell penetrate each other.
+/// cf. [`face_interferences`](./fn.face_interferences.html)
+#[inline(always)]
+pub fn has_face_interference(shell: &Shell, tol: f64) -> bool {
+    !face_interferences(shell, tol).is_empty()
+}
+
+/// The triangles of a tessellated face and the boundary elements.
+struct FaceMesh<'a> {
+    triangles: Vec<([Point3; 3], BoundingBox<Point3>)>,
+    bdd_box: BoundingBox<Point3>,
+    vertices: HashSet<VertexID>,
+    edges: Vec<&'a Edge>,
+}
+
+impl<'a> FaceMesh<'a> {
+    fn new(face: &'a Face, tol: f64) -> FaceMesh<'a> {
+        let mesh = tessellate_face(face, tol);
+        let positions = mesh.positions();
+        let triangles: Vec<_> = mesh
+            .tri_faces()
+            .iter()
+            .map(|tri| {
+                let tri = [
+                    positions[tri[0].pos],
+                    positions[tri[1].pos],
+                    positions[tri[2].pos],
+                ];
+                (tri, tri.iter().collect())
+            })
+            .collect();
+        let edges: Vec<&Edge> = face.absolute_boundaries().iter().flatten().collect();
+        FaceMesh {
+            bdd_box: positions.iter().collect(),
+            triangles,
+            vertices: edges.iter().map(|edge| edge.front().id()).collect(),
+            edges,
+        }
+    }
+
+    /// Returns a point of the transversal intersection of the triangles
+    /// apart from the common boundary.
+    fn interference(&self, other: &FaceMesh, common: &[Vec<Point3>], tol: f64) -> Option<Point3> {
+        for (tri0, bdd0) in &self.triangles {
+            for (tri1, bdd1) in &other.triangles {
+                if !bbox_overlap(bdd0, bdd1, tol) {
+                    continue;
+                }
+                let pt = triangle_intersection(tri0, tri1).filter(|pt| {
+                    common
+                        .iter()
+                        .all(|polyline| polyline_distance(polyline, *pt) >= tol)
+                });
+                if pt.is_some() {
+                    return pt;
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Returns the polylines of the common edges and the points of the common vertices.
+fn common_boundary(mesh0: &FaceMesh, mesh1: &FaceMesh, tol: f64) -> Vec<Vec<Point3>> {
+    let edge_ids: HashSet<EdgeID> = mesh1.edges.iter().map(|edge| edge.id()).collect();
+    let polylines = mesh0
+        .edges
+        .iter()
+        .filter(|edge| edge_ids.contains(&edge.id()))
+        .map(|edge| {
+            let curve = edge.lock_curve().unwrap();
+            curve
+                .parameter_division(tol)
+                .into_iter()
+                .map(|t| curve.subs(t))
+                .collect::<Vec<_>>()
+        });
+    let points = mesh0
+        .edges
+        .iter()
+        .map(|edge| edge.front())
+        .filter(|v| mesh1.vertices.contains(&v.id()))
+        .map(|v| vec![*v.lock_point().unwrap()]);
+    polylines.chain(points).collect()
+}
+
+#[inline(always)]
+fn bbox_overlap(bdd0: &BoundingBox<Point3>, bdd1: &BoundingBox<Point3>, tol: f64) -> bool {
+    (0..3).all(|k| bdd0.min()[k] <= bdd1.max()[k] + tol && bdd1.min()[k] <= bdd0.max()[k] + tol)
+}
+
+/// Returns a point on the intersection of two triangles if the edge of one of them
+/// penetrates the other.
+fn triangle_intersection(tri0: &[Point3; 3], tri1: &[Point3; 3]) -> Option<Point3> {
+    (0..3)
+        .find_map(|k| segment_triangle(tri0[k], tri0[(k + 1) % 3], tri1))
+        .or_else(|| (0..3).find_map(|k| segment_triangle(tri1[k], tri1[(k + 1) % 3], tri0)))
+}
+
+/// Returns the intersection of the segment and the triangle by the Möller-Trumbore algorithm.
+/// Returns `None` if the segment is parallel to the triangle.
+fn segment_triangle(p0: Point3, p1: Point3, tri: &[Point3; 3]) -> Option<Point3> {
+    let dir = p1 - p0;
+    let (e0, e1) = (tri[1] - tri[0], tri[2] - tri[0]);
+    let h = dir.cross(e1);
+    let det = e0.dot(h);
+    if det.abs() <= TOLERANCE * dir.magnitude() * e0.magnitude() * e1.magnitude() {
+        return None;
+    }
+    let s = p0 - tri[0];
+    let u = s.dot(h) / det;
+    let q = s.cross(e0);
+    let v = dir.dot(q) / det;
+    let t = e1.dot(q) / det;
+    match u >= 0.0 && v >= 0.0 && u + v <= 1.0 && (0.0..=1.0).contains(&t) {
+        true => Some(p0 + dir * t),
+        false => None,
+    }
+}
+
+/// Returns the distance between the point and the polyline.
+fn polyline_distance(polyline: &[Point3], pt: Point3) -> f64 {
+    if polyline.len() == 1 {
+        return polyline[0].distance(pt);
+    }
+    polyline
+        .windows(2)
+        .map(|seg| {
+            let dir = seg[1] - seg[0];
+            let t = match dir.so_small() {
+                true => 0.0,
+                false => f64::min(
+                    f64::max((pt - seg[0]).dot(dir) / dir.magnitude2(), 0.0),
+                    1.0,
+                ),
+            };
+            (seg[0] + dir * t).distance(pt)
+        })
+        .fold(f64::INFINITY, f64::min)
+}
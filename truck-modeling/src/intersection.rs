@@ -0,0 +1,162 @@
+use crate::errors::Error;
+use crate::*;
+use std::collections::{HashMap, HashSet};
+
+/// The record of a surface-surface intersection: the two parent surfaces and the
+/// approximation of the intersection curve.
+pub type IntersectionRecord = IntersectionCurve<Curve, Surface>;
+
+/// The cache of the intersection records of the edges created by intersections of surfaces.
+///
+/// The curve of an edge created by an intersection is only an approximation. The cache keeps
+/// the parent surfaces of the edge, so that the exact intersection can be re-evaluated later,
+/// e.g. when the tolerance is tightened or the model is exported.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::intersection::IntersectionCache;
+/// // the plane z = 0
+/// let surface0 = Surface::Plane(Plane::new(
+///     Point3::new(-1.0, -1.0, 0.0),
+///     Point3::new(2.0, -1.0, 0.0),
+///     Point3::new(-1.0, 2.0, 0.0),
+/// ));
+/// // the parabolic cylinder z = x^2 - 0.25
+/// let surface1 = Surface::BSplineSurface(BSplineSurface::new(
+///     (KnotVec::bezier_knot(2), KnotVec::bezier_knot(1)),
+///     vec![
+///         vec![Vector3::new(0.0, 0.0, -0.25), Vector3::new(0.0, 1.0, -0.25)],
+///         vec![Vector3::new(0.5, 0.0, -0.25), Vector3::new(0.5, 1.0, -0.25)],
+///         vec![Vector3::new(1.0, 0.0, 0.75), Vector3::new(1.0, 1.0, 0.75)],
+///     ],
+/// ));
+/// // the stale approximation of the intersection line x = 0.5, z = 0
+/// let leader = Curve::BSplineCurve(BSplineCurve::new(
+///     KnotVec::bezier_knot(1),
+///     vec![Vector3::new(0.5, 0.0, 0.01), Vector3::new(0.5, 1.0, 0.01)],
+/// ));
+/// let v0 = builder::vertex(Point3::new(0.5, 0.0, 0.0));
+/// let v1 = builder::vertex(Point3::new(0.5, 1.0, 0.0));
+///
+/// let mut cache = IntersectionCache::new();
+/// let edge = cache.create_edge(&v0, &v1, &surface0, &surface1, leader);
+/// assert_eq!(cache.len(), 1);
+/// assert!(!edge.oriented_curve().subs(0.5).near(&Point3::new(0.5, 0.5, 0.0)));
+///
+/// // re-evaluate the exact intersection
+/// cache.refine(&edge, 1.0e-4).unwrap();
+/// assert_near!(edge.oriented_curve().subs(0.5), Point3::new(0.5, 0.5, 0.0));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct IntersectionCache {
+    records: HashMap<EdgeID, IntersectionRecord>,
+}
+
+impl IntersectionCache {
+    /// Creates an empty cache.
+    #[inline(always)]
+    pub fn new() -> IntersectionCache { IntersectionCache::default() }
+
+    /// Returns the number of the records.
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.records.len() }
+
+    /// Returns whether the cache has no records.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.records.is_empty() }
+
+    /// Creates the edge from `v0` to `v1` along the intersection of `surface0` and `surface1`,
+    /// approximated by `leader`, and registers the record of the intersection.
+    pub fn create_edge(
+        &mut self,
+        v0: &Vertex,
+        v1: &Vertex,
+        surface0: &Surface,
+        surface1: &Surface,
+        leader: Curve,
+    ) -> Edge {
+        let edge = Edge::new(v0, v1, leader);
+        self.insert(&edge, surface0.clone(), surface1.clone());
+        edge
+    }
+
+    /// Registers `edge` as the intersection of `surface0` and `surface1`.
+    /// The current curve of the edge is recorded as the approximation.
+    /// Returns the old record if the edge has been already registered.
+    pub fn insert(
+        &mut self,
+        edge: &Edge,
+        surface0: Surface,
+        surface1: Surface,
+    ) -> Option<IntersectionRecord> {
+        let leader = edge.lock_curve().unwrap().clone();
+        self.records.insert(
+            edge.id(),
+            IntersectionCurve::new(surface0, surface1, leader),
+        )
+    }
+
+    /// Returns the record of `edge`. The leader of the record is the curve of the edge
+    /// regardless of the orientation of the edge.
+    #[inline(always)]
+    pub fn record(&self, edge: &Edge) -> Option<&IntersectionRecord> {
+        self.records.get(&edge.id())
+    }
+
+    /// Returns the record of `edge` whose leader is oriented along `edge`.
+    pub fn oriented_record(&self, edge: &Edge) -> Option<IntersectionRecord> {
+        self.records
+            .get(&edge.id())
+            .map(|record| match edge.orientation() {
+                true => record.clone(),
+                false => record.inverse(),
+            })
+    }
+
+    /// Removes the record of `edge`.
+    #[inline(always)]
+    pub fn remove(&mut self, edge: &Edge) -> Option<IntersectionRecord> {
+        self.records.remove(&edge.id())
+    }
+
+    /// Re-evaluates the exact intersection of the record of `edge`, and replaces the curve of
+    /// the edge and the leader of the record by the polyline whose midpoints of the segments
+    /// are within `tol` from the intersection. The ends of the curve are fixed to the vertices.
+    /// # Failures
+    /// - Returns [`Error::ElementNotFound`] if `edge` is not registered.
+    /// - Returns [`Error::ApproximationFailed`] if the search of the intersection fails.
+    ///
+    /// [`Error::ElementNotFound`]: ../errors/enum.Error.html#variant.ElementNotFound
+    /// [`Error::ApproximationFailed`]: ../errors/enum.Error.html#variant.ApproximationFailed
+    pub fn refine(&mut self, edge: &Edge, tol: f64) -> Result<()> {
+        let record = self
+            .records
+            .get_mut(&edge.id())
+            .ok_or(Error::ElementNotFound)?;
+        let polyline = record.polyline(tol).ok_or(Error::ApproximationFailed)?;
+        let mut points = polyline.points().clone();
+        let n = points.len();
+        points[0] = *edge.absolute_front().lock_point().unwrap();
+        points[n - 1] = *edge.absolute_back().lock_point().unwrap();
+        let polyline = PolylineCurve::with_parameters(points, polyline.parameters().clone());
+        let curve = Curve::BSplineCurve(polyline.to_bspline());
+        *record.leader_mut() = curve.clone();
+        *edge.lock_curve().unwrap() = curve;
+        Ok(())
+    }
+
+    /// Refines all registered edges in `edges` by [`IntersectionCache::refine`], and returns the
+    /// number of the refined edges. The edges which are not registered are skipped.
+    ///
+    /// [`IntersectionCache::refine`]: ./struct.IntersectionCache.html#method.refine
+    pub fn refine_all<'a, I>(&mut self, edges: I, tol: f64) -> Result<usize>
+    where I: IntoIterator<Item = &'a Edge> {
+        let mut refined = HashSet::new();
+        for edge in edges {
+            if self.records.contains_key(&edge.id()) && refined.insert(edge.id()) {
+                self.refine(edge, tol)?;
+            }
+        }
+        Ok(refined.len())
+    }
+}
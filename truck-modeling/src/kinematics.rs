@@ -0,0 +1,263 @@
+use crate::assembly::Assembly;
+use crate::errors::Error;
+use crate::*;
+
+/// The kind of a joint. The axes are given in the coordinate of the assembly at the rest pose,
+/// i.e. the pose where all joint values are zero.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum JointKind {
+    /// rotation around the line through `origin` along `axis`. The joint value is the angle
+    /// in radians.
+    Revolute {
+        /// a point on the rotation axis
+        origin: Point3,
+        /// the direction of the rotation axis
+        axis: Vector3,
+    },
+    /// translation along `axis`. The joint value is the distance.
+    Prismatic {
+        /// the direction of the translation
+        axis: Vector3,
+    },
+}
+
+/// A joint moving the component `child` relative to the component `parent`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Joint {
+    /// the index of the parent component in `Assembly::components`,
+    /// or `None` for the fixed base, i.e. the coordinate of the assembly.
+    pub parent: Option<usize>,
+    /// the index of the child component in `Assembly::components`
+    pub child: usize,
+    /// the kind and the axis of the joint
+    pub kind: JointKind,
+    /// the lower and the upper limits of the joint value. Default is `None`, no limits.
+    pub limits: Option<(f64, f64)>,
+}
+
+impl Joint {
+    /// Creates a revolute joint without limits.
+    #[inline(always)]
+    pub fn revolute(parent: Option<usize>, child: usize, origin: Point3, axis: Vector3) -> Joint {
+        Joint {
+            parent,
+            child,
+            kind: JointKind::Revolute { origin, axis },
+            limits: None,
+        }
+    }
+
+    /// Creates a prismatic joint without limits.
+    #[inline(always)]
+    pub fn prismatic(parent: Option<usize>, child: usize, axis: Vector3) -> Joint {
+        Joint {
+            parent,
+            child,
+            kind: JointKind::Prismatic { axis },
+            limits: None,
+        }
+    }
+
+    /// Returns the joint with the limits `[lower, upper]`.
+    #[inline(always)]
+    pub fn with_limits(self, lower: f64, upper: f64) -> Joint {
+        Joint {
+            limits: Some((lower, upper)),
+            ..self
+        }
+    }
+
+    /// Returns `value` clamped into the limits.
+    #[inline(always)]
+    pub fn clamp(&self, value: f64) -> f64 {
+        match self.limits {
+            Some((lower, upper)) => f64::min(f64::max(value, lower), upper),
+            None => value,
+        }
+    }
+
+    /// Returns the displacement of the child relative to the parent by the joint value `value`,
+    /// which is clamped into the limits.
+    pub fn motion(&self, value: f64) -> Matrix4 {
+        let value = self.clamp(value);
+        match self.kind {
+            JointKind::Revolute { origin, axis } => {
+                Matrix4::from_translation(origin.to_vec())
+                    * Matrix4::from_axis_angle(axis.normalize(), Rad(value))
+                    * Matrix4::from_translation(-origin.to_vec())
+            }
+            JointKind::Prismatic { axis } => Matrix4::from_translation(axis.normalize() * value),
+        }
+    }
+}
+
+/// A mechanism, the joints between the components of an assembly.
+///
+/// The joints must form a forest: each component is driven by at most one joint, and the
+/// parents of the joints do not form loops. The components without joints are fixed unless
+/// they are the descendants of the driven components.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Mechanism {
+    /// the joints. The joint vector is given in this order.
+    pub joints: Vec<Joint>,
+}
+
+impl Mechanism {
+    /// Creates a mechanism without joints.
+    #[inline(always)]
+    pub fn new() -> Mechanism { Mechanism::default() }
+
+    /// Adds `joint` and returns its index in the joint vector.
+    #[inline(always)]
+    pub fn add_joint(&mut self, joint: Joint) -> usize {
+        self.joints.push(joint);
+        self.joints.len() - 1
+    }
+
+    /// Returns the joint vector clamped into the limits of the joints.
+    #[inline(always)]
+    pub fn clamp(&self, values: &[f64]) -> Vec<f64> {
+        self.joints
+            .iter()
+            .zip(values)
+            .map(|(joint, value)| joint.clamp(*value))
+            .collect()
+    }
+
+    /// Returns the joint vector linearly interpolated between `from` and `to` at `t` in
+    /// `[0, 1]`, e.g. the frames of the motion preview.
+    #[inline(always)]
+    pub fn interpolate(&self, from: &[f64], to: &[f64], t: f64) -> Vec<f64> {
+        let values: Vec<f64> = from.iter().zip(to).map(|(a, b)| a + (b - a) * t).collect();
+        self.clamp(&values)
+    }
+
+    /// Returns the displacements of `num_components` components in the coordinate of the
+    /// assembly for the joint vector `values`.
+    ///
+    /// The displacement of a component is the product of the displacement of the parent and
+    /// the motion of the joint driving it. The values are clamped into the limits.
+    ///
+    /// # Errors
+    /// - Returns `Error::InvalidJointValues` if the length of `values` is not the number of joints.
+    /// - Returns `Error::InvalidJoint` if a joint refers to a component out of range, drives an
+    /// already driven component, or makes a loop.
+    pub fn displacements(&self, num_components: usize, values: &[f64]) -> Result<Vec<Matrix4>> {
+        if values.len() != self.joints.len() {
+            return Err(Error::InvalidJointValues);
+        }
+        let mut driver = vec![None; num_components];
+        for (i, joint) in self.joints.iter().enumerate() {
+            let out_of_range = joint.child >= num_components
+                || joint.parent.map_or(false, |p| p >= num_components);
+            if out_of_range || driver[joint.child].is_some() {
+                return Err(Error::InvalidJoint(i));
+            }
+            driver[joint.child] = Some(i);
+        }
+        let mut res: Vec<Option<Matrix4>> = vec![None; num_components];
+        for component in 0..num_components {
+            self.displacement(component, values, &driver, &mut res, 0)?;
+        }
+        Ok(res.into_iter().map(Option::unwrap).collect())
+    }
+
+    fn displacement(
+        &self,
+        component: usize,
+        values: &[f64],
+        driver: &[Option<usize>],
+        res: &mut [Option<Matrix4>],
+        depth: usize,
+    ) -> Result<Matrix4> {
+        if let Some(mat) = res[component] {
+            return Ok(mat);
+        }
+        let mat = match driver[component] {
+            Some(i) => {
+                // a chain longer than the number of the joints must contain a loop.
+                if depth > self.joints.len() {
+                    return Err(Error::InvalidJoint(i));
+                }
+                let joint = &self.joints[i];
+                let parent = match joint.parent {
+                    Some(parent) => self.displacement(parent, values, driver, res, depth + 1)?,
+                    None => Matrix4::identity(),
+                };
+                parent * joint.motion(values[i])
+            }
+            None => Matrix4::identity(),
+        };
+        res[component] = Some(mat);
+        Ok(mat)
+    }
+
+    /// Returns the transforms of the components of `assembly` posed by the joint vector
+    /// `values`, i.e. the displacements composed with `Component::transform`.
+    ///
+    /// For the motion preview, set the transforms to the matrices of the instances, e.g.
+    /// `TransformNode::set_matrix` of `truck-rendimpl`, at every frame.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::assembly::*;
+    /// use truck_modeling::kinematics::*;
+    /// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let link = Part::new("link", builder::tsweep(&f, Vector3::unit_z()));
+    ///
+    /// // the arm: the slider on the base, and the link hinged on the slider
+    /// let mut arm = Assembly::new("arm");
+    /// arm.add_part(&link, Matrix4::identity());
+    /// arm.add_part(&link, Matrix4::from_translation(Vector3::unit_x()));
+    /// let mut mechanism = Mechanism::new();
+    /// mechanism.add_joint(Joint::prismatic(None, 0, Vector3::unit_y()).with_limits(0.0, 2.0));
+    /// mechanism.add_joint(Joint::revolute(
+    ///     Some(0),
+    ///     1,
+    ///     Point3::new(1.0, 0.0, 0.0),
+    ///     Vector3::unit_z(),
+    /// ));
+    ///
+    /// let pose = mechanism
+    ///     .pose(&arm, &[3.0, std::f64::consts::PI / 2.0])
+    ///     .unwrap();
+    /// // the slider stops at the upper limit
+    /// let p = pose[0].transform_point(Point3::origin());
+    /// assert!(p.near(&Point3::new(0.0, 2.0, 0.0)));
+    /// // the link is rotated around the hinge on the slider
+    /// let p = pose[1].transform_point(Point3::new(1.0, 0.0, 0.0));
+    /// assert!(p.near(&Point3::new(1.0, 3.0, 0.0)));
+    ///
+    /// let frames: Vec<Vec<f64>> = (0..=10)
+    ///     .map(|i| mechanism.interpolate(&[0.0, 0.0], &[2.0, 1.0], i as f64 / 10.0))
+    ///     .collect();
+    /// assert_eq!(frames[10], vec![2.0, 1.0]);
+    ///
+    /// // a loop of the joints
+    /// mechanism.add_joint(Joint::revolute(Some(1), 0, Point3::origin(), Vector3::unit_z()));
+    /// assert!(mechanism.pose(&arm, &[0.0; 3]).is_err());
+    /// ```
+    #[inline(always)]
+    pub fn pose(&self, assembly: &Assembly, values: &[f64]) -> Result<Vec<Matrix4>> {
+        let displacements = self.displacements(assembly.components.len(), values)?;
+        Ok(displacements
+            .into_iter()
+            .zip(&assembly.components)
+            .map(|(mat, component)| mat * component.transform)
+            .collect())
+    }
+
+    /// Returns the copy of `assembly` posed by the joint vector `values`.
+    #[inline(always)]
+    pub fn posed_assembly(&self, assembly: &Assembly, values: &[f64]) -> Result<Assembly> {
+        let pose = self.pose(assembly, values)?;
+        let mut res = assembly.clone();
+        res.components
+            .iter_mut()
+            .zip(pose)
+            .for_each(|(component, mat)| component.transform = mat);
+        Ok(res)
+    }
+}
@@ -46,7 +46,12 @@ pub mod topology {
     /// The id that does not depend on the direction of the face.
     pub type FaceID = truck_topology::FaceID<Surface>;
 
-    pub use truck_topology::shell::ShellCondition;
+    /// The compressed shell, which is serialized or written in the text format "truck brep v1".
+    pub type CompressedShell = truck_topology::CompressedShell<Point3, Curve, Surface>;
+    /// The compressed solid, which is serialized or written in the text format "truck brep v1".
+    pub type CompressedSolid = truck_topology::CompressedSolid<Point3, Curve, Surface>;
+
+    pub use truck_topology::shell::{ShellCondition, ShellReport};
 }
 pub use topology::*;
 
@@ -146,13 +151,68 @@ pub use topo_traits::*;
 /// `Result` with crate's errors.
 pub type Result<T> = std::result::Result<T, errors::Error>;
 
+/// assemblies of parts and bills of materials
+pub mod assembly;
+mod blend;
+/// Boolean operations of solids: union, intersection and difference
+pub mod boolean;
 /// the building model utility API
 pub mod builder;
+/// classification of the points in the parameter spaces of faces
+pub mod classification;
 mod closed_sweep;
+/// networks of curves split at their intersections, and the closed loops in the networks
+pub mod curve_network;
+/// removing small holes and fillets from solids
+pub mod defeature;
+/// global deformations of solids: taper, twist and bend
+pub mod deformation;
+/// deviation analysis between polygon meshes and B-reps
+pub mod deviation;
+/// local modifications of polyhedral solids: tweaking faces and moving vertices
+pub mod direct_edit;
+/// type-erased curves, surfaces and shapes for scripting and plugins
+pub mod dynamic;
 /// declare errors
 pub mod errors;
+mod face_domain;
+/// feature recognition: holes, pockets and fillets
+pub mod features;
+/// free-form deformation lattices for polygon meshes and solids
+pub mod ffd;
+/// involute spur and helical gears
+pub mod gear;
 mod geom_impls;
+/// healing of the imperfect shells, e.g. the imported ones: hole capping and face merging
+pub mod healing;
+mod hollow;
+/// detection of the interferences between the faces of shells
+pub mod interference;
+/// surface-surface intersection records of edges for the re-evaluation of exact intersections
+pub mod intersection;
+/// kinematic joints between the components of assemblies and the motion preview
+pub mod kinematics;
 mod mapped;
+/// reverse engineering: fitting analytic surfaces to polygon meshes
+pub mod mesh_fitting;
 mod multi_sweep;
+mod pipe;
+mod profile;
+/// named parameters and expressions for parametric models
+pub mod parameters;
+/// mass properties of solids
+pub mod properties;
+/// quantity-of-material and cost reports
+pub mod quantity;
+/// headless geometry service by the JSON command protocol
+pub mod service;
+/// statistics of the boundary representations: counts, sizes and degenerate geometries
+pub mod statistics;
 mod sweep;
+/// swept volumes of the moving components and the clearances in the motions
+pub mod swept_volume;
+/// tessellation of faces, shells and solids into polygon meshes
+pub mod tessellation;
+/// cosmetic and modeled ISO metric threads on cylindrical faces
+pub mod thread;
 mod topo_impls;
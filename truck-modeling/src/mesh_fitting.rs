@@ -0,0 +1,743 @@
+use crate::*;
+use std::collections::{HashMap, HashSet};
+use truck_polymesh::PolygonMesh;
+
+/// The analytic surface fitted to a region of a polygon mesh.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FittedSurface {
+    /// plane
+    Plane {
+        /// a point on the plane
+        origin: Point3,
+        /// the unit normal of the plane
+        normal: Vector3,
+    },
+    /// cylinder
+    Cylinder {
+        /// a point on the axis
+        origin: Point3,
+        /// the unit direction of the axis
+        axis: Vector3,
+        /// the radius
+        radius: f64,
+    },
+    /// sphere
+    Sphere {
+        /// the center
+        center: Point3,
+        /// the radius
+        radius: f64,
+    },
+}
+
+impl FittedSurface {
+    /// Returns the distance between `pt` and the surface.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::mesh_fitting::FittedSurface;
+    /// let cylinder = FittedSurface::Cylinder {
+    ///     origin: Point3::origin(),
+    ///     axis: Vector3::unit_z(),
+    ///     radius: 1.0,
+    /// };
+    /// assert_near!(cylinder.distance(Point3::new(3.0, 0.0, 5.0)), 2.0);
+    /// ```
+    #[inline(always)]
+    pub fn distance(&self, pt: Point3) -> f64 {
+        match self {
+            FittedSurface::Plane { origin, normal } => (pt - origin).dot(*normal).abs(),
+            FittedSurface::Cylinder {
+                origin,
+                axis,
+                radius,
+            } => {
+                let vec = pt - origin;
+                ((vec - axis * vec.dot(*axis)).magnitude() - radius).abs()
+            }
+            FittedSurface::Sphere { center, radius } => ((pt - center).magnitude() - radius).abs(),
+        }
+    }
+
+    /// Returns the normal of the surface at the foot of `pt`,
+    /// directed to the outside of the cylinder or the sphere.
+    fn outer_normal(&self, pt: Point3) -> Vector3 {
+        match self {
+            FittedSurface::Plane { normal, .. } => *normal,
+            FittedSurface::Cylinder { origin, axis, .. } => {
+                let vec = pt - origin;
+                (vec - axis * vec.dot(*axis)).normalize()
+            }
+            FittedSurface::Sphere { center, .. } => (pt - center).normalize(),
+        }
+    }
+}
+
+/// A region of a polygon mesh and the analytic surface fitted to it.
+#[derive(Clone, Debug)]
+pub struct MeshRegion {
+    /// the indices of the faces, in the order of `PolygonMesh::face_iter`.
+    pub faces: Vec<usize>,
+    /// the fitted surface
+    pub surface: FittedSurface,
+    /// the maximum distance between the vertices of the faces and the fitted surface
+    pub deviation: f64,
+}
+
+/// Divides the mesh into regions each of which is approximated by a plane, a cylinder or a sphere.
+/// # Arguments
+/// * `mesh` - the polygon mesh, e.g. a scanned or tessellated model.
+/// * `feature_angle` - two adjacent faces are separated if the angle between their normals
+///   is larger than `feature_angle`.
+/// * `tol` - the tolerance of the distance between the vertices and the fitted surface.
+/// # Remarks
+/// A smooth region which cannot be fitted by one surface within `tol`
+/// is subdivided into small planar regions.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::mesh_fitting::*;
+/// use truck_polymesh::*;
+/// // the unit cube
+/// let positions = vec![
+///     Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(1.0, 1.0, 0.0), Point3::new(0.0, 1.0, 0.0),
+///     Point3::new(0.0, 0.0, 1.0), Point3::new(1.0, 0.0, 1.0),
+///     Point3::new(1.0, 1.0, 1.0), Point3::new(0.0, 1.0, 1.0),
+/// ];
+/// let faces = Faces::from_iter(&[
+///     [0, 3, 2, 1], [0, 1, 5, 4], [1, 2, 6, 5],
+///     [2, 3, 7, 6], [3, 0, 4, 7], [4, 5, 6, 7],
+/// ]);
+/// let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+/// let regions = segment_mesh(&mesh, 0.5, 1.0e-3);
+/// assert_eq!(regions.len(), 6);
+/// for region in &regions {
+///     match region.surface {
+///         FittedSurface::Plane { .. } => {}
+///         _ => panic!("the region is not planar."),
+///     }
+/// }
+/// ```
+pub fn segment_mesh(mesh: &PolygonMesh, feature_angle: f64, tol: f64) -> Vec<MeshRegion> {
+    let positions = mesh.positions();
+    let faces: Vec<&[truck_polymesh::Vertex]> = mesh.face_iter().collect();
+    let normals: Vec<Vector3> = faces
+        .iter()
+        .map(|face| newell_normal(positions, face))
+        .collect();
+    let adjacency = face_adjacency(&faces);
+    let cos = feature_angle.cos();
+    let mut checked = vec![false; faces.len()];
+    let mut regions = Vec::new();
+    for i in 0..faces.len() {
+        if checked[i] {
+            continue;
+        }
+        let component = grow_region(i, &adjacency, &mut checked, |cursor, j| {
+            let (n0, n1) = (normals[cursor], normals[j]);
+            match n0.so_small() || n1.so_small() {
+                true => true,
+                false => n0.dot(n1) >= cos * n0.magnitude() * n1.magnitude(),
+            }
+        });
+        let region = fit_region(positions, &faces, &normals, component);
+        match region.deviation > tol {
+            true => regions.extend(planar_subdivision(
+                positions, &faces, &normals, &adjacency, region.faces, tol,
+            )),
+            false => regions.push(region),
+        }
+    }
+    regions
+}
+
+/// Creates the shell approximating the mesh by fitted analytic surfaces.
+///
+/// This is the composition of [`segment_mesh`] and [`shell_from_regions`].
+///
+/// [`segment_mesh`]: ./fn.segment_mesh.html
+/// [`shell_from_regions`]: ./fn.shell_from_regions.html
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::mesh_fitting::*;
+/// use truck_polymesh::*;
+/// // the unit cube
+/// let positions = vec![
+///     Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(1.0, 1.0, 0.0), Point3::new(0.0, 1.0, 0.0),
+///     Point3::new(0.0, 0.0, 1.0), Point3::new(1.0, 0.0, 1.0),
+///     Point3::new(1.0, 1.0, 1.0), Point3::new(0.0, 1.0, 1.0),
+/// ];
+/// let faces = Faces::from_iter(&[
+///     [0, 3, 2, 1], [0, 1, 5, 4], [1, 2, 6, 5],
+///     [2, 3, 7, 6], [3, 0, 4, 7], [4, 5, 6, 7],
+/// ]);
+/// let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+/// let shell = shell_from_mesh(&mesh, 0.5, 1.0e-3).unwrap();
+/// assert_eq!(shell.len(), 6);
+/// assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+/// ```
+#[inline(always)]
+pub fn shell_from_mesh(mesh: &PolygonMesh, feature_angle: f64, tol: f64) -> Result<Shell> {
+    let regions = segment_mesh(mesh, feature_angle, tol);
+    shell_from_regions(mesh, &regions)
+}
+
+/// Creates the shell whose faces correspond to `regions`.
+///
+/// The boundary of each region is divided at the vertices shared by three or more regions
+/// (or by the boundary of the mesh), and each piece becomes a polyline edge.
+/// The pieces shared by two regions are shared by the two faces, too.
+/// The faces of the mesh not contained in any region are ignored.
+/// # Failures
+/// Returns the error if some region is not a simple polygon in the sense of topology,
+/// i.e. `Face::try_new` fails.
+pub fn shell_from_regions(mesh: &PolygonMesh, regions: &[MeshRegion]) -> Result<Shell> {
+    let positions = mesh.positions();
+    let faces: Vec<&[truck_polymesh::Vertex]> = mesh.face_iter().collect();
+    let mut region_of_face = vec![None; faces.len()];
+    regions.iter().enumerate().for_each(|(i, region)| {
+        region.faces.iter().for_each(|j| region_of_face[*j] = Some(i))
+    });
+    let mut half_edges = HashMap::new();
+    faces.iter().enumerate().for_each(|(i, face)| {
+        if region_of_face[i].is_some() {
+            half_edge_iter(face).for_each(|half_edge| {
+                half_edges.insert(half_edge, i);
+            })
+        }
+    });
+
+    // boundary half edges of each region, and the regions around each vertex
+    let mut boundaries = vec![Vec::new(); regions.len()];
+    let mut sides: HashMap<usize, HashSet<Option<usize>>> = HashMap::new();
+    faces.iter().enumerate().for_each(|(i, face)| {
+        let region = match region_of_face[i] {
+            Some(region) => region,
+            None => return,
+        };
+        half_edge_iter(face).for_each(|(a, b)| {
+            let opposite = half_edges.get(&(b, a)).and_then(|j| region_of_face[*j]);
+            if opposite != Some(region) {
+                boundaries[region].push((a, b));
+                for v in &[a, b] {
+                    let entry = sides.entry(*v).or_default();
+                    entry.insert(Some(region));
+                    entry.insert(opposite);
+                }
+            }
+        })
+    });
+    let is_corner = |v: usize| sides.get(&v).map(|set| set.len() > 2).unwrap_or(false);
+
+    let mut vertices: HashMap<usize, Vertex> = HashMap::new();
+    let mut edges: HashMap<Vec<usize>, Edge> = HashMap::new();
+    let mut get_edge = |chain: &[usize]| -> Edge {
+        let reversed = chain[0] > chain[chain.len() - 1];
+        let mut key = chain.to_vec();
+        if reversed {
+            key.reverse();
+        }
+        let edge = edges.entry(key.clone()).or_insert_with(|| {
+            let v0 = vertices
+                .entry(key[0])
+                .or_insert_with(|| Vertex::new(positions[key[0]]))
+                .clone();
+            let v1 = vertices
+                .entry(key[key.len() - 1])
+                .or_insert_with(|| Vertex::new(positions[key[key.len() - 1]]))
+                .clone();
+            let points = key.iter().map(|i| positions[*i]).collect();
+            Edge::new(&v0, &v1, polyline(points))
+        });
+        match reversed {
+            true => edge.inverse(),
+            false => edge.clone(),
+        }
+    };
+
+    let mut shell = Shell::new();
+    for (region, boundary) in regions.iter().zip(boundaries) {
+        let wires = boundary_loops(boundary)
+            .into_iter()
+            .map(|closed| {
+                split_loop(closed, &is_corner)
+                    .iter()
+                    .map(|chain| get_edge(chain))
+                    .collect::<Wire>()
+            })
+            .collect::<Vec<_>>();
+        let surface = create_surface(positions, &faces, region);
+        shell.push(Face::try_new(wires, surface)?);
+    }
+    Ok(shell)
+}
+
+fn half_edge_iter<'a>(
+    face: &'a [truck_polymesh::Vertex],
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let len = face.len();
+    (0..len)
+        .map(move |i| (face[i].pos, face[(i + 1) % len].pos))
+        .filter(|(a, b)| a != b)
+}
+
+/// the normal vector whose magnitude is twice the area of the face
+fn newell_normal(positions: &[Point3], face: &[truck_polymesh::Vertex]) -> Vector3 {
+    let center = face
+        .iter()
+        .fold(Vector3::zero(), |sum, v| sum + positions[v.pos].to_vec())
+        / face.len() as f64;
+    half_edge_iter(face).fold(Vector3::zero(), |sum, (a, b)| {
+        sum + (positions[a].to_vec() - center).cross(positions[b].to_vec() - center)
+    })
+}
+
+fn face_adjacency(faces: &[&[truck_polymesh::Vertex]]) -> Vec<Vec<usize>> {
+    let mut edge_faces: HashMap<(usize, usize), Vec<usize>> = HashMap::new();
+    faces.iter().enumerate().for_each(|(i, face)| {
+        half_edge_iter(face).for_each(|(a, b)| {
+            let key = (usize::min(a, b), usize::max(a, b));
+            edge_faces.entry(key).or_default().push(i);
+        })
+    });
+    let mut adjacency = vec![Vec::new(); faces.len()];
+    edge_faces.values().for_each(|vec| {
+        for i in vec {
+            for j in vec {
+                if i != j && !adjacency[*i].contains(j) {
+                    adjacency[*i].push(*j);
+                }
+            }
+        }
+    });
+    adjacency
+}
+
+fn grow_region<F: Fn(usize, usize) -> bool>(
+    seed: usize,
+    adjacency: &[Vec<usize>],
+    checked: &mut [bool],
+    connectable: F,
+) -> Vec<usize> {
+    checked[seed] = true;
+    let mut stack = vec![seed];
+    let mut component = vec![seed];
+    while let Some(cursor) = stack.pop() {
+        for j in &adjacency[cursor] {
+            if !checked[*j] && connectable(cursor, *j) {
+                checked[*j] = true;
+                component.push(*j);
+                stack.push(*j);
+            }
+        }
+    }
+    component
+}
+
+fn planar_subdivision(
+    positions: &[Point3],
+    faces: &[&[truck_polymesh::Vertex]],
+    normals: &[Vector3],
+    adjacency: &[Vec<usize>],
+    component: Vec<usize>,
+    tol: f64,
+) -> Vec<MeshRegion> {
+    let mut checked = vec![true; faces.len()];
+    component.iter().for_each(|i| checked[*i] = false);
+    let mut regions = Vec::new();
+    for seed in component {
+        if checked[seed] {
+            continue;
+        }
+        let origin = positions[faces[seed][0].pos];
+        let normal = match normals[seed].so_small() {
+            true => Vector3::zero(),
+            false => normals[seed].normalize(),
+        };
+        let plane = FittedSurface::Plane { origin, normal };
+        let sub = grow_region(seed, adjacency, &mut checked, |_, j| {
+            faces[j].iter().all(|v| plane.distance(positions[v.pos]) <= tol)
+        });
+        regions.push(fit_region(positions, faces, normals, sub));
+    }
+    regions
+}
+
+fn fit_region(
+    positions: &[Point3],
+    faces: &[&[truck_polymesh::Vertex]],
+    normals: &[Vector3],
+    component: Vec<usize>,
+) -> MeshRegion {
+    let mut indices: Vec<usize> = component
+        .iter()
+        .flat_map(|i| faces[*i].iter().map(|v| v.pos))
+        .collect();
+    indices.sort();
+    indices.dedup();
+    let points: Vec<Point3> = indices.iter().map(|i| positions[*i]).collect();
+    let center = points
+        .iter()
+        .fold(Point3::origin(), |sum, pt| sum + pt.to_vec())
+        / points.len() as f64;
+    let normal_sum = component
+        .iter()
+        .fold(Vector3::zero(), |sum, i| sum + normals[*i]);
+    let normal_matrix = component.iter().fold(Matrix3::zero(), |sum, i| {
+        let n = normals[*i];
+        match n.so_small() {
+            true => sum,
+            false => sum + Matrix3::from_cols(n * n[0], n * n[1], n * n[2]) / n.magnitude(),
+        }
+    });
+    let candidates = vec![
+        fit_plane(center, normal_sum),
+        fit_cylinder(&points, center, normal_matrix),
+        fit_sphere(&points, center),
+    ];
+    let (surface, deviation) = candidates
+        .into_iter()
+        .flatten()
+        .map(|surface| {
+            let deviation = points
+                .iter()
+                .fold(0.0, |max, pt| f64::max(max, surface.distance(*pt)));
+            (surface, deviation)
+        })
+        .fold(None, |best: Option<(FittedSurface, f64)>, (surface, deviation)| {
+            match best {
+                Some(best) if best.1 <= deviation + TOLERANCE => Some(best),
+                _ => Some((surface, deviation)),
+            }
+        })
+        .unwrap_or((
+            FittedSurface::Plane {
+                origin: center,
+                normal: Vector3::unit_z(),
+            },
+            f64::INFINITY,
+        ));
+    MeshRegion {
+        faces: component,
+        surface,
+        deviation,
+    }
+}
+
+fn fit_plane(center: Point3, normal_sum: Vector3) -> Option<FittedSurface> {
+    match normal_sum.so_small() {
+        true => None,
+        false => Some(FittedSurface::Plane {
+            origin: center,
+            normal: normal_sum.normalize(),
+        }),
+    }
+}
+
+/// The axis is the eigenvector of the least eigenvalue of the area-weighted sum of `n n^T`,
+/// and the radius and the position of the axis are given by the circle fitting of the
+/// projected points.
+fn fit_cylinder(points: &[Point3], center: Point3, normal_matrix: Matrix3) -> Option<FittedSurface> {
+    let axis = least_eigenvector(normal_matrix)?;
+    let (u, v) = orthonormal_basis(axis);
+    let projected: Vec<Vector2> = points
+        .iter()
+        .map(|pt| Vector2::new((pt - center).dot(u), (pt - center).dot(v)))
+        .collect();
+    let (circle_center, radius) = fit_circle(&projected)?;
+    Some(FittedSurface::Cylinder {
+        origin: center + u * circle_center[0] + v * circle_center[1],
+        axis,
+        radius,
+    })
+}
+
+/// algebraic fitting: minimizes sum of (|p|^2 + a p + b)^2.
+fn fit_sphere(points: &[Point3], center: Point3) -> Option<FittedSurface> {
+    let (mat, vec) = points.iter().fold(
+        (Matrix4::zero(), Vector4::zero()),
+        |(mat, vec), pt| {
+            let p = pt - center;
+            let row = p.extend(1.0);
+            let mat = mat + Matrix4::from_cols(row * row[0], row * row[1], row * row[2], row * row[3]);
+            (mat, vec - row * p.magnitude2())
+        },
+    );
+    let sol = mat.invert()? * vec;
+    let vec = -sol.truncate() / 2.0;
+    let radius2 = vec.magnitude2() - sol[3];
+    match radius2 > 0.0 && radius2.is_finite() {
+        true => Some(FittedSurface::Sphere {
+            center: center + vec,
+            radius: radius2.sqrt(),
+        }),
+        false => None,
+    }
+}
+
+/// algebraic fitting: minimizes sum of (|p|^2 + a p + b)^2.
+fn fit_circle(points: &[Vector2]) -> Option<(Vector2, f64)> {
+    let (mat, vec) = points.iter().fold(
+        (Matrix3::zero(), Vector3::zero()),
+        |(mat, vec), p| {
+            let row = p.extend(1.0);
+            let mat = mat + Matrix3::from_cols(row * row[0], row * row[1], row * row[2]);
+            (mat, vec - row * p.magnitude2())
+        },
+    );
+    let sol = mat.invert()? * vec;
+    let center = -sol.truncate() / 2.0;
+    let radius2 = center.magnitude2() - sol[2];
+    match radius2 > 0.0 && radius2.is_finite() {
+        true => Some((center, radius2.sqrt())),
+        false => None,
+    }
+}
+
+/// the eigenvector of the least eigenvalue of the positive semi-definite symmetric matrix
+fn least_eigenvector(mat: Matrix3) -> Option<Vector3> {
+    let trace = mat[0][0] + mat[1][1] + mat[2][2];
+    if trace.so_small() {
+        return None;
+    }
+    // the power iteration for the matrix whose greatest eigenvalue is `trace - (least one)`.
+    let shifted = Matrix3::from_value(trace) - mat;
+    let mut vec = (0..3)
+        .map(|i| shifted[i])
+        .fold(Vector3::zero(), |max, col| match col.magnitude2() > max.magnitude2() {
+            true => col,
+            false => max,
+        });
+    if vec.so_small() {
+        return None;
+    }
+    for _ in 0..100 {
+        let next = (shifted * vec).normalize();
+        if next.near(&vec) {
+            return Some(next);
+        }
+        vec = next;
+    }
+    Some(vec.normalize())
+}
+
+fn orthonormal_basis(n: Vector3) -> (Vector3, Vector3) {
+    let a = match n[0].abs() < 0.5 {
+        true => Vector3::unit_x(),
+        false => Vector3::unit_y(),
+    };
+    let u = (a - n * n.dot(a)).normalize();
+    (u, n.cross(u))
+}
+
+/// Chains the boundary half edges of a region into closed loops of vertices.
+fn boundary_loops(boundary: Vec<(usize, usize)>) -> Vec<Vec<usize>> {
+    let mut next: HashMap<usize, Vec<usize>> = HashMap::new();
+    boundary.iter().for_each(|(a, b)| next.entry(*a).or_default().push(*b));
+    let mut loops = Vec::new();
+    for (a, _) in boundary {
+        let mut closed = vec![a];
+        let mut cursor = a;
+        while let Some(b) = next.get_mut(&cursor).and_then(|vec| vec.pop()) {
+            if b == a {
+                break;
+            }
+            closed.push(b);
+            cursor = b;
+        }
+        if closed.len() > 1 {
+            loops.push(closed);
+        }
+    }
+    loops
+}
+
+/// Splits a closed loop into chains at corners.
+fn split_loop<F: Fn(usize) -> bool>(closed: Vec<usize>, is_corner: &F) -> Vec<Vec<usize>> {
+    let len = closed.len();
+    let mut splits: Vec<usize> = (0..len).filter(|i| is_corner(closed[*i])).collect();
+    if splits.len() < 2 {
+        let max = (0..len).max_by_key(|i| closed[*i]).unwrap();
+        let min = (0..len).min_by_key(|i| closed[*i]).unwrap();
+        splits = match splits.first() {
+            Some(corner) if *corner == max => vec![min, max],
+            Some(corner) => {
+                // the vertex of the max index except the corner
+                let other = (0..len)
+                    .filter(|i| i != corner)
+                    .max_by_key(|i| closed[*i])
+                    .unwrap();
+                vec![*corner, other]
+            }
+            None => vec![min, max],
+        };
+        splits.sort();
+    }
+    (0..splits.len())
+        .map(|k| {
+            let (start, end) = (splits[k], splits[(k + 1) % splits.len()]);
+            let end = if end <= start { end + len } else { end };
+            (start..=end).map(|i| closed[i % len]).collect()
+        })
+        .collect()
+}
+
+fn polyline(points: Vec<Point3>) -> Curve {
+    let knot_vec = KnotVec::uniform_knot(1, points.len() - 1);
+    let control_points = points.into_iter().map(|pt| pt.to_vec()).collect();
+    Curve::BSplineCurve(BSplineCurve::new(knot_vec, control_points))
+}
+
+fn create_surface(
+    positions: &[Point3],
+    faces: &[&[truck_polymesh::Vertex]],
+    region: &MeshRegion,
+) -> Surface {
+    let points = || {
+        region
+            .faces
+            .iter()
+            .flat_map(move |i| faces[*i].iter().map(move |v| positions[v.pos]))
+    };
+    let mut surface = match region.surface {
+        FittedSurface::Plane { origin, normal } => {
+            let (u, v) = orthonormal_basis(normal);
+            return Surface::Plane(Plane::new(origin, origin + u, origin + v));
+        }
+        FittedSurface::Cylinder {
+            origin,
+            axis,
+            radius,
+        } => {
+            let (min, max) = points().fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), pt| {
+                let t = (pt - origin).dot(axis);
+                (f64::min(min, t), f64::max(max, t))
+            });
+            let u = orthonormal_basis(axis).0;
+            let curve = Curve::BSplineCurve(geom_impls::line(
+                (origin + axis * max + u * radius).to_vec(),
+                (origin + axis * min + u * radius).to_vec(),
+            ));
+            Surface::RevolutedCurve(Processor::new(RevolutedCurve::by_revolution(
+                curve, origin, axis,
+            )))
+        }
+        FittedSurface::Sphere { center, radius } => {
+            let axis = Vector3::unit_z();
+            let north = center + axis * radius;
+            let semicircle = geom_impls::circle_arc(
+                north.to_homogeneous(),
+                center,
+                Vector3::unit_y(),
+                Rad(std::f64::consts::PI),
+            );
+            Surface::RevolutedCurve(Processor::new(RevolutedCurve::by_revolution(
+                Curve::NURBSCurve(NURBSCurve::new(semicircle)),
+                center,
+                axis,
+            )))
+        }
+    };
+    // orient the surface along the normals of the mesh
+    let outward = region.faces.iter().fold(0.0, |sum, i| {
+        let face = faces[*i];
+        let center = face
+            .iter()
+            .fold(Point3::origin(), |sum, v| sum + positions[v.pos].to_vec())
+            / face.len() as f64;
+        sum + newell_normal(positions, face).dot(region.surface.outer_normal(center))
+    }) >= 0.0;
+    let pt = surface.subs(0.5, 0.0);
+    let normal = surface.normal(0.5, 0.0);
+    if (normal.dot(region.surface.outer_normal(pt)) >= 0.0) != outward {
+        surface.invert();
+    }
+    surface
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::PI;
+    use truck_polymesh::Faces;
+
+    fn cylinder_mesh(div: usize, rings: usize) -> PolygonMesh {
+        let positions: Vec<Point3> = (0..rings)
+            .flat_map(|k| {
+                (0..div).map(move |i| {
+                    let theta = 2.0 * PI * i as f64 / div as f64;
+                    Point3::new(theta.cos(), theta.sin(), k as f64)
+                })
+            })
+            .collect();
+        let mut faces = Faces::from_iter((0..rings - 1).flat_map(|k| {
+            (0..div).map(move |i| {
+                let (i0, i1) = (k * div + i, k * div + (i + 1) % div);
+                [i0, i1, i1 + div, i0 + div]
+            })
+        }));
+        faces.push((0..div).rev().collect::<Vec<_>>());
+        faces.push(((rings - 1) * div..rings * div).collect::<Vec<_>>());
+        PolygonMesh::new(positions, Vec::new(), Vec::new(), faces)
+    }
+
+    #[test]
+    fn fit_cylinder_regions() {
+        let mesh = cylinder_mesh(32, 4);
+        let regions = segment_mesh(&mesh, 0.5, 1.0e-3);
+        assert_eq!(regions.len(), 3);
+        let cylinders: Vec<_> = regions
+            .iter()
+            .filter_map(|region| match region.surface {
+                FittedSurface::Cylinder { axis, radius, .. } => Some((axis, radius)),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(cylinders.len(), 1);
+        assert!(cylinders[0].0.cross(Vector3::unit_z()).so_small());
+        assert_near!(cylinders[0].1, 1.0);
+
+        let shell = shell_from_regions(&mesh, &regions).unwrap();
+        assert_eq!(shell.len(), 3);
+        assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+        assert!(Solid::try_new(vec![shell]).is_ok());
+    }
+
+    #[test]
+    fn fit_sphere_region() {
+        let (udiv, vdiv) = (16, 32);
+        let mut positions = vec![Point3::new(0.0, 0.0, 2.0), Point3::new(0.0, 0.0, -2.0)];
+        for i in 1..udiv {
+            let phi = PI * i as f64 / udiv as f64;
+            for j in 0..vdiv {
+                let theta = 2.0 * PI * j as f64 / vdiv as f64;
+                let vec = Vector3::new(phi.sin() * theta.cos(), phi.sin() * theta.sin(), phi.cos());
+                positions.push(Point3::new(1.0, 1.0, 1.0) + vec * 2.0);
+            }
+        }
+        positions[0] += Vector3::new(1.0, 1.0, 1.0);
+        positions[1] += Vector3::new(1.0, 1.0, 1.0);
+        let idx = |i: usize, j: usize| 2 + (i - 1) * vdiv + j % vdiv;
+        let mut faces = Faces::default();
+        for j in 0..vdiv {
+            faces.push([0, idx(1, j), idx(1, j + 1)]);
+            faces.push([1, idx(udiv - 1, j + 1), idx(udiv - 1, j)]);
+            for i in 1..udiv - 1 {
+                faces.push([idx(i, j), idx(i + 1, j), idx(i + 1, j + 1), idx(i, j + 1)]);
+            }
+        }
+        let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+        let regions = segment_mesh(&mesh, 0.5, 1.0e-3);
+        assert_eq!(regions.len(), 1);
+        match regions[0].surface {
+            FittedSurface::Sphere { center, radius } => {
+                assert_near!(center, Point3::new(1.0, 1.0, 1.0));
+                assert_near!(radius, 2.0);
+            }
+            _ => panic!("the region is not spherical."),
+        }
+    }
+}
@@ -0,0 +1,525 @@
+use crate::errors::Error;
+use crate::Result;
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Named parameters which drive models.
+///
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::parameters::*;
+///
+/// // the configurator: the model is re-built from the parameters.
+/// fn build(params: &Parameters) -> Result<Solid> {
+///     let width = params.eval_str("width")?;
+///     let depth = params.eval_str("width * 2 + 5")?;
+///     let height = params.eval_str("max(height, width / 4)")?;
+///     let v = builder::vertex(Point3::origin());
+///     let e = builder::tsweep(&v, Vector3::new(width, 0.0, 0.0));
+///     let f = builder::tsweep(&e, Vector3::new(0.0, depth, 0.0));
+///     Ok(builder::tsweep(&f, Vector3::new(0.0, 0.0, height)))
+/// }
+///
+/// let mut params = Parameters::new();
+/// params.set("width", 40.0);
+/// params.set("height", 5.0);
+/// let solid = build(&params).unwrap();
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert_near!(volume, 40.0 * 85.0 * 10.0);
+///
+/// params.set("width", 10.0);
+/// let solid = build(&params).unwrap();
+/// let volume = properties::mass_properties(&solid, 1.0e-3).volume;
+/// assert_near!(volume, 10.0 * 25.0 * 5.0);
+///
+/// params.remove("height");
+/// assert_eq!(
+///     build(&params).unwrap_err(),
+///     errors::Error::UndefinedParameter("height".to_string()),
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Parameters(HashMap<String, f64>);
+
+impl Parameters {
+    /// Creates an empty parameter set.
+    #[inline(always)]
+    pub fn new() -> Parameters { Parameters::default() }
+
+    /// Sets the value of the parameter `name`, and returns the previous value.
+    #[inline(always)]
+    pub fn set(&mut self, name: &str, value: f64) -> Option<f64> {
+        self.0.insert(name.to_string(), value)
+    }
+
+    /// Returns the value of the parameter `name`.
+    #[inline(always)]
+    pub fn get(&self, name: &str) -> Option<f64> { self.0.get(name).copied() }
+
+    /// Removes the parameter `name`, and returns its value.
+    #[inline(always)]
+    pub fn remove(&mut self, name: &str) -> Option<f64> { self.0.remove(name) }
+
+    /// Returns the iterator over the names and the values of the parameters.
+    #[inline(always)]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, f64)> {
+        self.0.iter().map(|(name, value)| (name.as_str(), *value))
+    }
+
+    /// Evaluates the expression by the parameters.
+    #[inline(always)]
+    pub fn eval(&self, expr: &Expr) -> Result<f64> { expr.eval(self) }
+
+    /// Parses and evaluates the expression by the parameters.
+    #[inline(always)]
+    pub fn eval_str(&self, expr: &str) -> Result<f64> { Expr::from_str(expr)?.eval(self) }
+}
+
+/// Binary operators of expressions
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BinaryOperator {
+    /// `+`
+    Add,
+    /// `-`
+    Sub,
+    /// `*`
+    Mul,
+    /// `/`
+    Div,
+    /// `^`
+    Pow,
+}
+
+/// Arithmetic expressions of parameters.
+///
+/// The expression consists of numbers, parameter names, `+`, `-`, `*`, `/`, `^`, parentheses,
+/// the constant `pi`, and the functions `sin`, `cos`, `tan`, `asin`, `acos`, `atan`, `atan2`,
+/// `sqrt`, `abs`, `min` and `max`. The angles are in radians.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::parameters::*;
+/// let expr: Expr = "width * 2 + 5".parse().unwrap();
+/// assert_eq!(expr.variables(), vec!["width"]);
+///
+/// let mut params = Parameters::new();
+/// params.set("width", 40.0);
+/// assert_eq!(expr.eval(&params), Ok(85.0));
+///
+/// let expr: Expr = "-2^2 + sqrt(r^2 - 9) * cos(pi)".parse().unwrap();
+/// params.set("r", 5.0);
+/// assert_near!(expr.eval(&params).unwrap(), -8.0);
+///
+/// assert!("width * (2 + 5".parse::<Expr>().is_err());
+/// assert!("log(width)".parse::<Expr>().is_err());
+/// ```
+///
+/// The expressions nested more than 256 levels, counting the parentheses, the signs and the
+/// chained binary operators, are rejected so that the parser does not overflow the stack.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Expr {
+    /// the literal
+    Number(f64),
+    /// the parameter
+    Variable(String),
+    /// the negation
+    Neg(Box<Expr>),
+    /// the binary operation
+    Binary(BinaryOperator, Box<Expr>, Box<Expr>),
+    /// the function call
+    Call(String, Vec<Expr>),
+}
+
+impl Expr {
+    /// Evaluates the expression by the parameters.
+    /// # Failures
+    /// - Returns [`Error::UndefinedParameter`] if the expression contains a parameter not in `params`.
+    /// - Returns [`Error::InvalidExpression`] if some value in the evaluation is not finite,
+    ///   e.g. `1 / 0` or `sqrt(-1)`.
+    ///
+    /// [`Error::UndefinedParameter`]: ../errors/enum.Error.html#variant.UndefinedParameter
+    /// [`Error::InvalidExpression`]: ../errors/enum.Error.html#variant.InvalidExpression
+    pub fn eval(&self, params: &Parameters) -> Result<f64> {
+        let value = self.eval_unchecked(params)?;
+        match value.is_finite() {
+            true => Ok(value),
+            false => Err(Error::InvalidExpression(format!(
+                "the value {} is not finite",
+                value
+            ))),
+        }
+    }
+
+    fn eval_unchecked(&self, params: &Parameters) -> Result<f64> {
+        match self {
+            Expr::Number(x) => Ok(*x),
+            Expr::Variable(name) => match name.as_str() {
+                "pi" => Ok(std::f64::consts::PI),
+                _ => params
+                    .get(name)
+                    .ok_or_else(|| Error::UndefinedParameter(name.clone())),
+            },
+            Expr::Neg(expr) => Ok(-expr.eval(params)?),
+            Expr::Binary(op, lhs, rhs) => {
+                let (x, y) = (lhs.eval(params)?, rhs.eval(params)?);
+                Ok(match op {
+                    BinaryOperator::Add => x + y,
+                    BinaryOperator::Sub => x - y,
+                    BinaryOperator::Mul => x * y,
+                    BinaryOperator::Div => x / y,
+                    BinaryOperator::Pow => x.powf(y),
+                })
+            }
+            Expr::Call(name, args) => {
+                let args = args
+                    .iter()
+                    .map(|arg| arg.eval(params))
+                    .collect::<Result<Vec<f64>>>()?;
+                Ok(match (name.as_str(), args.as_slice()) {
+                    ("sin", [x]) => x.sin(),
+                    ("cos", [x]) => x.cos(),
+                    ("tan", [x]) => x.tan(),
+                    ("asin", [x]) => x.asin(),
+                    ("acos", [x]) => x.acos(),
+                    ("atan", [x]) => x.atan(),
+                    ("atan2", [y, x]) => y.atan2(*x),
+                    ("sqrt", [x]) => x.sqrt(),
+                    ("abs", [x]) => x.abs(),
+                    ("min", [x, y]) => f64::min(*x, *y),
+                    ("max", [x, y]) => f64::max(*x, *y),
+                    _ => return Err(Error::InvalidExpression(format!("{}()", name))),
+                })
+            }
+        }
+    }
+
+    /// Returns the names of the parameters in the expression, sorted and without duplicates.
+    pub fn variables(&self) -> Vec<&str> {
+        let mut res = Vec::new();
+        self.collect_variables(&mut res);
+        res.sort_unstable();
+        res.dedup();
+        res
+    }
+
+    fn collect_variables<'a>(&'a self, res: &mut Vec<&'a str>) {
+        match self {
+            Expr::Number(_) => {}
+            Expr::Variable(name) => {
+                if name != "pi" {
+                    res.push(name)
+                }
+            }
+            Expr::Neg(expr) => expr.collect_variables(res),
+            Expr::Binary(_, lhs, rhs) => {
+                lhs.collect_variables(res);
+                rhs.collect_variables(res);
+            }
+            Expr::Call(_, args) => args.iter().for_each(|arg| arg.collect_variables(res)),
+        }
+    }
+}
+
+impl From<f64> for Expr {
+    #[inline(always)]
+    fn from(x: f64) -> Expr { Expr::Number(x) }
+}
+
+impl FromStr for Expr {
+    type Err = Error;
+    fn from_str(s: &str) -> Result<Expr> {
+        let tokens = tokenize(s)?;
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.expr()?;
+        match parser.tokens.get(parser.pos) {
+            None => Ok(expr),
+            Some(token) => Err(Error::InvalidExpression(format!(
+                "unexpected {} in \"{}\"",
+                token, s
+            ))),
+        }
+    }
+}
+
+const FUNCTIONS: [(&str, usize); 11] = [
+    ("sin", 1),
+    ("cos", 1),
+    ("tan", 1),
+    ("asin", 1),
+    ("acos", 1),
+    ("atan", 1),
+    ("atan2", 2),
+    ("sqrt", 1),
+    ("abs", 1),
+    ("min", 2),
+    ("max", 2),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Symbol(char),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Token::Number(x) => f.write_fmt(format_args!("{}", x)),
+            Token::Ident(name) => f.write_fmt(format_args!("\"{}\"", name)),
+            Token::Symbol(c) => f.write_fmt(format_args!("'{}'", c)),
+        }
+    }
+}
+
+fn describe(token: Option<&Token>) -> String {
+    match token {
+        Some(token) => token.to_string(),
+        None => "end of expression".to_string(),
+    }
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if c.is_whitespace() {
+            continue;
+        } else if c.is_ascii_digit() || c == '.' {
+            let mut end = i + c.len_utf8();
+            let mut prev = c;
+            while let Some((j, c)) = chars.peek().copied() {
+                let exponent_sign = (c == '+' || c == '-') && (prev == 'e' || prev == 'E');
+                if !(c.is_ascii_digit() || c == '.' || c == 'e' || c == 'E' || exponent_sign) {
+                    break;
+                }
+                end = j + c.len_utf8();
+                prev = c;
+                chars.next();
+            }
+            let number = s[i..end]
+                .parse::<f64>()
+                .ok()
+                .filter(|x| x.is_finite())
+                .ok_or_else(|| {
+                    Error::InvalidExpression(format!("invalid number \"{}\"", &s[i..end]))
+                })?;
+            tokens.push(Token::Number(number));
+        } else if c.is_alphabetic() || c == '_' {
+            let mut end = i + c.len_utf8();
+            while let Some((j, c)) = chars.peek().copied() {
+                if !(c.is_alphanumeric() || c == '_') {
+                    break;
+                }
+                end = j + c.len_utf8();
+                chars.next();
+            }
+            tokens.push(Token::Ident(s[i..end].to_string()));
+        } else if "+-*/^(),".contains(c) {
+            tokens.push(Token::Symbol(c));
+        } else {
+            return Err(Error::InvalidExpression(format!(
+                "unexpected '{}' in \"{}\"",
+                c, s
+            )));
+        }
+    }
+    Ok(tokens)
+}
+
+/// the maximum nesting depth of expressions
+const MAX_DEPTH: usize = 256;
+
+/// recursive descent parser
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn nest(&mut self) -> Result<()> {
+        self.depth += 1;
+        match self.depth > MAX_DEPTH {
+            true => Err(Error::InvalidExpression(format!(
+                "the expression is nested more than {} levels",
+                MAX_DEPTH
+            ))),
+            false => Ok(()),
+        }
+    }
+
+    fn next_symbol(&mut self, symbols: &str) -> Option<char> {
+        match self.tokens.get(self.pos) {
+            Some(Token::Symbol(c)) if symbols.contains(*c) => {
+                self.pos += 1;
+                Some(*c)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, symbol: char) -> Result<()> {
+        match self.next_symbol(&symbol.to_string()) {
+            Some(_) => Ok(()),
+            None => Err(Error::InvalidExpression(format!(
+                "expected '{}', found {}",
+                symbol,
+                describe(self.tokens.get(self.pos))
+            ))),
+        }
+    }
+
+    // expr = term { ("+" | "-") term }
+    fn expr(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut res = self.term()?;
+        while let Some(c) = self.next_symbol("+-") {
+            let op = match c {
+                '+' => BinaryOperator::Add,
+                _ => BinaryOperator::Sub,
+            };
+            self.nest()?;
+            res = Expr::Binary(op, Box::new(res), Box::new(self.term()?));
+        }
+        self.depth = depth;
+        Ok(res)
+    }
+
+    // term = unary { ("*" | "/") unary }
+    fn term(&mut self) -> Result<Expr> {
+        let depth = self.depth;
+        let mut res = self.unary()?;
+        while let Some(c) = self.next_symbol("*/") {
+            let op = match c {
+                '*' => BinaryOperator::Mul,
+                _ => BinaryOperator::Div,
+            };
+            self.nest()?;
+            res = Expr::Binary(op, Box::new(res), Box::new(self.unary()?));
+        }
+        self.depth = depth;
+        Ok(res)
+    }
+
+    // unary = ("-" | "+") unary | power
+    fn unary(&mut self) -> Result<Expr> {
+        self.nest()?;
+        let res = match self.next_symbol("+-") {
+            Some('-') => Expr::Neg(Box::new(self.unary()?)),
+            Some(_) => self.unary()?,
+            None => self.power()?,
+        };
+        self.depth -= 1;
+        Ok(res)
+    }
+
+    // power = primary [ "^" unary ]
+    fn power(&mut self) -> Result<Expr> {
+        let base = self.primary()?;
+        match self.next_symbol("^") {
+            Some(_) => Ok(Expr::Binary(
+                BinaryOperator::Pow,
+                Box::new(base),
+                Box::new(self.unary()?),
+            )),
+            None => Ok(base),
+        }
+    }
+
+    // primary = number | ident [ "(" expr { "," expr } ")" ] | "(" expr ")"
+    fn primary(&mut self) -> Result<Expr> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Number(x)) => Ok(Expr::Number(x)),
+            Some(Token::Ident(name)) => {
+                if self.next_symbol("(").is_none() {
+                    return Ok(Expr::Variable(name));
+                }
+                let mut args = vec![self.expr()?];
+                while self.next_symbol(",").is_some() {
+                    args.push(self.expr()?);
+                }
+                self.expect(')')?;
+                match FUNCTIONS.iter().find(|(f, _)| *f == name) {
+                    Some((_, arity)) if *arity == args.len() => Ok(Expr::Call(name, args)),
+                    Some((_, arity)) => Err(Error::InvalidExpression(format!(
+                        "{}() takes {} arguments, but {} given",
+                        name,
+                        arity,
+                        args.len()
+                    ))),
+                    None => Err(Error::InvalidExpression(format!(
+                        "unknown function {}()",
+                        name
+                    ))),
+                }
+            }
+            Some(Token::Symbol('(')) => {
+                let expr = self.expr()?;
+                self.expect(')')?;
+                Ok(expr)
+            }
+            token => Err(Error::InvalidExpression(format!(
+                "unexpected {}",
+                describe(token.as_ref())
+            ))),
+        }
+    }
+}
+
+#[test]
+fn operator_precedence() {
+    let mut params = Parameters::new();
+    params.set("a", 2.0);
+    params.set("b_1", 3.0);
+    let eval = |s: &str| params.eval_str(s).unwrap();
+    assert_eq!(eval("1 + a * b_1"), 7.0);
+    assert_eq!(eval("(1 + a) * b_1"), 9.0);
+    assert_eq!(eval("12 / a / b_1"), 2.0);
+    assert_eq!(eval("a ^ b_1 ^ a"), 512.0);
+    assert_eq!(eval("-a ^ 2"), -4.0);
+    assert_eq!(eval("a ^ -1"), 0.5);
+    assert_eq!(eval("1.5e1 - 2.5E-1 * 4"), 14.0);
+    assert_eq!(eval("min(a, b_1) + max(a, -b_1)"), 4.0);
+    assert_eq!(
+        params.eval_str("a + c"),
+        Err(Error::UndefinedParameter("c".to_string()))
+    );
+    assert!(params.eval_str("a +").is_err());
+    assert!(params.eval_str("a b_1").is_err());
+    assert!(params.eval_str("min(a)").is_err());
+    assert!(params.eval_str("a # 2").is_err());
+}
+
+#[test]
+fn invalid_expressions() {
+    let params = Parameters::new();
+    let message = |s: &str| match params.eval_str(s) {
+        Err(Error::InvalidExpression(message)) => message,
+        res => panic!("{:?} is not an invalid expression", res),
+    };
+    assert_eq!(message("1 +"), "unexpected end of expression");
+    assert_eq!(message("1 + )"), "unexpected ')'");
+    assert_eq!(message("(1 + 2"), "expected ')', found end of expression");
+    assert_eq!(message("max(1, 2 3)"), "expected ')', found 3");
+    assert_eq!(message("1 2"), "unexpected 2 in \"1 2\"");
+    assert_eq!(message("1 / 0"), "the value inf is not finite");
+    assert_eq!(message("sqrt(-1)"), "the value NaN is not finite");
+    assert_eq!(message("1e400"), "invalid number \"1e400\"");
+
+    let nested = "(".repeat(100_000) + "1" + &")".repeat(100_000);
+    assert!(message(&nested).contains("nested"));
+    let signs = "-".repeat(100_000) + "1";
+    assert!(message(&signs).contains("nested"));
+    let sum = vec!["1"; 100_000].join(" + ");
+    assert!(message(&sum).contains("nested"));
+    let nested = "(".repeat(100) + "1" + &")".repeat(100);
+    assert_eq!(params.eval_str(&nested), Ok(1.0));
+}
@@ -0,0 +1,193 @@
+use crate::deformation::{cubic_knot_vec, greville_abscissae};
+use crate::errors::Error;
+use crate::*;
+
+/// the maximum number of the spans of the sweeping direction
+const MAX_SPANS: usize = 1024;
+/// the number of the steps of the double reflection on the whole spine
+const REFLECTION_STEPS: usize = 1024;
+/// the precision of the approximated motion relative to the size of the spine
+const PIPE_PRECISION: f64 = 1.0e-4;
+
+/// The motion along the spine: the transformations at the Greville abscissae of the
+/// cubic knot vector, which are the control points of the variation diminishing spline.
+struct Motion {
+    knot_vec: KnotVec,
+    matrices: Vec<Matrix4>,
+}
+
+impl Motion {
+    /// Approximates the motion by the rotation-minimizing frames along `spine`,
+    /// refining the knots until the error is within the precision.
+    fn new(spine: &BSplineCurve<Vector3>) -> Result<Motion> {
+        let range = spine.parameter_range();
+        let bdb: BoundingBox<Vector3> = spine.control_points().iter().collect();
+        let tol = bdb.diameter() * PIPE_PRECISION;
+        let mut division = 4;
+        while division <= MAX_SPANS {
+            let knot_vec = cubic_knot_vec(range, division);
+            let params = greville_abscissae(&knot_vec);
+            let matrices = rotation_minimizing_frames(spine, &params)?;
+            let n = 2 * division;
+            let checks: Vec<f64> = (0..n)
+                .map(|i| range.0 + (range.1 - range.0) * (i as f64 + 0.5) / n as f64)
+                .collect();
+            let exacts = rotation_minimizing_frames(spine, &checks)?;
+            let motion = Motion { knot_vec, matrices };
+            let within = checks.iter().zip(exacts).all(|(t, exact)| {
+                let diff = motion.matrix(*t) - exact;
+                let linear = (0..3).all(|i| diff[i].truncate().magnitude() < PIPE_PRECISION);
+                linear && diff[3].truncate().magnitude() < tol
+            });
+            if within {
+                return Ok(motion);
+            }
+            division *= 2;
+        }
+        Err(Error::ApproximationFailed)
+    }
+
+    /// the approximated transformation at the parameter `t`
+    fn matrix(&self, t: f64) -> Matrix4 {
+        let basis = self.knot_vec.bspline_basis_functions(3, t);
+        self.matrices
+            .iter()
+            .zip(basis)
+            .fold(Matrix4::zero(), |sum, (mat, b)| sum + mat * b)
+    }
+
+    /// the trajectory of the point
+    fn connect_points(&self, pt: Point3) -> Curve {
+        let control_points = self
+            .matrices
+            .iter()
+            .map(|mat| mat.transform_point(pt).to_vec())
+            .collect();
+        Curve::BSplineCurve(BSplineCurve::new(self.knot_vec.clone(), control_points))
+    }
+
+    /// the surface swept by the curve, whose u-parameter is of the curve
+    fn connect_curve(&self, curve: &Curve) -> Surface {
+        let curve = curve.clone().lift_up();
+        let control_points = curve
+            .control_points()
+            .iter()
+            .map(|pt| self.matrices.iter().map(|mat| mat * pt).collect())
+            .collect();
+        let knot_vecs = (curve.knot_vec().clone(), self.knot_vec.clone());
+        Surface::NURBSSurface(NURBSSurface::new(BSplineSurface::new(
+            knot_vecs,
+            control_points,
+        )))
+    }
+}
+
+/// The transformations moving the start of `spine` to `spine.subs(t)` for `t` in `params`,
+/// by the rotation-minimizing frames computed by the double reflection method.
+/// `params` must be sorted.
+fn rotation_minimizing_frames(spine: &BSplineCurve<Vector3>, params: &[f64]) -> Result<Vec<Matrix4>> {
+    let range = spine.parameter_range();
+    let step = (range.1 - range.0) / REFLECTION_STEPS as f64;
+    let tangent = |t: f64| {
+        let der = spine.der(t);
+        match der.magnitude().so_small() {
+            true => Err(Error::UnsupportedGeometry),
+            false => Ok(der.normalize()),
+        }
+    };
+    let frame = |t: f64, r: Vector3, tan: Vector3| {
+        let rot = Matrix3::from_cols(r, tan.cross(r), tan);
+        (Matrix4::from_translation(spine.subs(t)), Matrix4::from(rot))
+    };
+    let mut t = range.0;
+    let mut tan = tangent(t)?;
+    let axis = match tan[0].abs() < 0.5 {
+        true => Vector3::unit_x(),
+        false => Vector3::unit_y(),
+    };
+    let mut r = (axis - tan * tan.dot(axis)).normalize();
+    let (trsl, rot) = frame(t, r, tan);
+    let start = rot.transpose() * trsl.invert().unwrap();
+    let mut pt = spine.subs(t);
+    params
+        .iter()
+        .map(|param| {
+            let n = f64::ceil((param - t) / step) as usize;
+            for i in 1..=n {
+                let new_t = t + (param - t) * i as f64 / n as f64;
+                let (new_pt, new_tan) = (spine.subs(new_t), tangent(new_t)?);
+                let v1 = new_pt - pt;
+                let c1 = v1.dot(v1);
+                let (r_l, tan_l) = match c1.so_small() {
+                    true => (r, tan),
+                    false => (
+                        r - v1 * (2.0 / c1 * v1.dot(r)),
+                        tan - v1 * (2.0 / c1 * v1.dot(tan)),
+                    ),
+                };
+                let v2 = new_tan - tan_l;
+                let c2 = v2.dot(v2);
+                r = match c2.so_small() {
+                    true => r_l,
+                    false => r_l - v2 * (2.0 / c2 * v2.dot(r_l)),
+                };
+                r = (r - new_tan * new_tan.dot(r)).normalize();
+                pt = new_pt;
+                tan = new_tan;
+            }
+            t = f64::max(t, *param);
+            let (trsl, rot) = frame(t, r, tan);
+            Ok(trsl * rot * start)
+        })
+        .collect()
+}
+
+pub(crate) fn pipe<T: Sweep<Point3, Curve, Surface>>(
+    elem: &T,
+    spine: &BSplineCurve<Vector3>,
+) -> Result<T::Swept> {
+    let motion = Motion::new(spine)?;
+    let end = motion.matrices[motion.matrices.len() - 1];
+    Ok(elem.sweep(
+        &move |pt| end.transform_point(*pt),
+        &move |curve| curve.transformed(end),
+        &move |surface| surface.transformed(end),
+        &|pt, _| motion.connect_points(*pt),
+        &|curve, _| motion.connect_curve(curve),
+    ))
+}
+
+#[test]
+fn pipe_along_planar_curve() {
+    let v = builder::vertex(Point3::new(0.2, 0.0, 0.0));
+    let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+    // a curved spine on the xz-plane
+    let spine = BSplineCurve::new(
+        KnotVec::bezier_knot(3),
+        vec![
+            Vector3::new(0.0, 0.0, 0.0),
+            Vector3::new(0.0, 0.0, 2.0),
+            Vector3::new(2.0, 0.0, 2.0),
+            Vector3::new(2.0, 0.0, 4.0),
+        ],
+    );
+    let tube: Solid = pipe(&disk, &spine).unwrap();
+    let shell = &tube.boundaries()[0];
+    assert_eq!(shell.shell_condition(), ShellCondition::Closed);
+    assert!(shell
+        .iter()
+        .flat_map(|face| face.boundaries().into_iter().flatten())
+        .all(|edge| edge.is_geometric_consistent()));
+
+    // the rotation-minimizing frames along a planar spine never twist around the tangent:
+    // the normal of the plane is fixed.
+    let params: Vec<f64> = (0..=16).map(|i| i as f64 / 16.0).collect();
+    let frames = rotation_minimizing_frames(&spine, &params).unwrap();
+    frames.iter().zip(&params).for_each(|(mat, t)| {
+        let normal = mat.transform_vector(Vector3::unit_y());
+        assert!(normal.near(&Vector3::unit_y()), "{}: {:?}", t, normal);
+        let tangent = mat.transform_vector(spine.der(0.0).normalize());
+        assert!(tangent.near(&spine.der(*t).normalize()), "{}: {:?}", t, tangent);
+    });
+}
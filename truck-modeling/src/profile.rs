@@ -0,0 +1,237 @@
+use crate::errors::Error;
+use crate::*;
+use std::f64::consts::PI;
+
+/// The segment of a closed profile from `start` to the start of the next segment.
+/// If `center` is `Some`, the segment is the counter-clockwise circle arc around `center`.
+#[derive(Clone, Copy, Debug)]
+struct Segment {
+    start: Point2,
+    center: Option<Point2>,
+}
+
+/// Returns the matrix mapping the xy-plane to the work plane, where the x-axis is mapped to
+/// the direction of the u-axis of `plane` and the z-axis to the normal of `plane`.
+fn work_plane_matrix(plane: &Plane) -> Matrix4 {
+    let normal = plane.normal();
+    let u = plane.u_axis().normalize();
+    Matrix4::from_cols(
+        u.extend(0.0),
+        normal.cross(u).extend(0.0),
+        normal.extend(0.0),
+        plane.origin().to_homogeneous(),
+    )
+}
+
+/// Returns the closed wire through the segments mapped by `mat`, skipping the segments of
+/// length zero.
+fn closed_profile(mat: Matrix4, segments: &[Segment]) -> Wire {
+    let segments: Vec<Segment> = (0..segments.len())
+        .filter(|i| !segments[*i].start.near(&segments[(i + 1) % segments.len()].start))
+        .map(|i| segments[i])
+        .collect();
+    let point = |p: Point2| mat.transform_point(Point3::new(p.x, p.y, 0.0));
+    let vertices: Vec<Vertex> = segments
+        .iter()
+        .map(|segment| builder::vertex(point(segment.start)))
+        .collect();
+    (0..segments.len())
+        .map(|i| {
+            let j = (i + 1) % segments.len();
+            match segments[i].center {
+                Some(center) => {
+                    let (a, b) = (segments[i].start - center, segments[j].start - center);
+                    let start_angle = f64::atan2(a.y, a.x);
+                    let sweep = (f64::atan2(b.y, b.x) - start_angle).rem_euclid(2.0 * PI);
+                    let angle = start_angle + sweep / 2.0;
+                    let transit = center + a.magnitude() * Vector2::new(angle.cos(), angle.sin());
+                    builder::circle_arc(&vertices[i], &vertices[j], point(transit))
+                }
+                None => builder::line(&vertices[i], &vertices[j]),
+            }
+        })
+        .collect()
+}
+
+pub(super) fn rounded_rectangle(
+    plane: &Plane,
+    center: Point2,
+    width: f64,
+    height: f64,
+    radius: f64,
+) -> Result<Wire> {
+    let invalid = width <= 0.0 || height <= 0.0 || radius < 0.0;
+    if invalid || 2.0 * radius > width + TOLERANCE || 2.0 * radius > height + TOLERANCE {
+        return Err(Error::InvalidProfile);
+    }
+    let (x, y) = (width / 2.0 - radius, height / 2.0 - radius);
+    let corners = [(x, y), (-x, y), (-x, -y), (x, -y)];
+    let segments = match radius.so_small() {
+        true => corners
+            .iter()
+            .map(|(x, y)| Segment {
+                start: center + Vector2::new(*x, *y),
+                center: None,
+            })
+            .collect::<Vec<_>>(),
+        false => corners
+            .iter()
+            .enumerate()
+            .flat_map(|(i, (x, y))| {
+                let corner = center + Vector2::new(*x, *y);
+                let angle = PI / 2.0 * i as f64;
+                let (cos, sin) = (angle.cos(), angle.sin());
+                vec![
+                    Segment {
+                        start: corner + radius * Vector2::new(cos, sin),
+                        center: Some(corner),
+                    },
+                    Segment {
+                        start: corner + radius * Vector2::new(-sin, cos),
+                        center: None,
+                    },
+                ]
+            })
+            .collect(),
+    };
+    Ok(closed_profile(work_plane_matrix(plane), &segments))
+}
+
+pub(super) fn slot(plane: &Plane, center0: Point2, center1: Point2, radius: f64) -> Result<Wire> {
+    let dir = center1 - center0;
+    if radius <= 0.0 || dir.so_small() {
+        return Err(Error::InvalidProfile);
+    }
+    let normal = Vector2::new(-dir.y, dir.x).normalize() * radius;
+    let segments = [
+        Segment {
+            start: center0 - normal,
+            center: None,
+        },
+        Segment {
+            start: center1 - normal,
+            center: Some(center1),
+        },
+        Segment {
+            start: center1 + normal,
+            center: None,
+        },
+        Segment {
+            start: center0 + normal,
+            center: Some(center0),
+        },
+    ];
+    Ok(closed_profile(work_plane_matrix(plane), &segments))
+}
+
+pub(super) fn regular_polygon(
+    plane: &Plane,
+    center: Point2,
+    radius: f64,
+    sides: usize,
+) -> Result<Wire> {
+    if radius <= 0.0 || sides < 3 {
+        return Err(Error::InvalidProfile);
+    }
+    let segments: Vec<Segment> = (0..sides)
+        .map(|i| {
+            let angle = 2.0 * PI * i as f64 / sides as f64;
+            Segment {
+                start: center + radius * Vector2::new(angle.cos(), angle.sin()),
+                center: None,
+            }
+        })
+        .collect();
+    Ok(closed_profile(work_plane_matrix(plane), &segments))
+}
+
+pub(super) fn ellipse(
+    plane: &Plane,
+    center: Point2,
+    semi_axis0: f64,
+    semi_axis1: f64,
+) -> Result<Wire> {
+    if semi_axis0 <= 0.0 || semi_axis1 <= 0.0 {
+        return Err(Error::InvalidProfile);
+    }
+    // the unit circle is mapped to the ellipse by the affine transformation.
+    let segments: Vec<Segment> = (0..4)
+        .map(|i| {
+            let angle = PI / 2.0 * i as f64;
+            Segment {
+                start: Point2::new(angle.cos(), angle.sin()),
+                center: Some(Point2::origin()),
+            }
+        })
+        .collect();
+    let circle = closed_profile(Matrix4::identity(), &segments);
+    let mat = work_plane_matrix(plane)
+        * Matrix4::from_translation(Vector3::new(center.x, center.y, 0.0))
+        * Matrix4::from_nonuniform_scale(semi_axis0, semi_axis1, 1.0);
+    Ok(builder::transformed(&circle, mat))
+}
+
+#[test]
+fn profiles_on_tilted_plane() {
+    let plane = Plane::new(
+        Point3::new(1.0, 2.0, 3.0),
+        Point3::new(2.0, 2.0, 4.0),
+        Point3::new(1.0, 3.0, 3.0),
+    );
+    let inv = work_plane_matrix(&plane).invert().unwrap();
+    let profiles = [
+        rounded_rectangle(&plane, Point2::new(1.0, -1.0), 4.0, 2.0, 0.5).unwrap(),
+        rounded_rectangle(&plane, Point2::new(1.0, -1.0), 4.0, 2.0, 0.0).unwrap(),
+        // the semicircles at the short sides
+        rounded_rectangle(&plane, Point2::new(1.0, -1.0), 4.0, 2.0, 1.0).unwrap(),
+        slot(&plane, Point2::new(-1.0, 0.0), Point2::new(1.0, 1.0), 0.5).unwrap(),
+        regular_polygon(&plane, Point2::new(0.5, 0.5), 1.0, 5).unwrap(),
+        ellipse(&plane, Point2::new(0.5, 0.5), 2.0, 1.0).unwrap(),
+    ];
+    let lens = [8, 4, 6, 4, 5, 4];
+    for (wire, len) in profiles.iter().zip(lens) {
+        assert_eq!(wire.len(), len);
+        assert!(wire.is_closed() && wire.is_simple());
+        assert!(wire.iter().all(|edge| edge.is_geometric_consistent()));
+        // the points on the work plane
+        let points: Vec<Point3> = wire
+            .vertex_iter()
+            .map(|v| inv.transform_point(*v.lock_point().unwrap()))
+            .collect();
+        assert!(points.iter().all(|pt| pt.z.so_small()));
+        // counter-clockwise with respect to the normal of the plane
+        let area: f64 = (0..points.len())
+            .map(|i| {
+                let (p, q) = (points[i], points[(i + 1) % points.len()]);
+                p.x * q.y - p.y * q.x
+            })
+            .sum();
+        assert!(area > 0.0, "{}", area);
+    }
+}
+
+#[test]
+fn invalid_profiles() {
+    let plane = Plane::new(
+        Point3::origin(),
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+    );
+    let center = Point2::origin();
+    let results = [
+        // the arcs overlap each other and the wire intersects itself
+        rounded_rectangle(&plane, center, 4.0, 2.0, 1.5),
+        rounded_rectangle(&plane, center, 0.0, 2.0, 0.0),
+        rounded_rectangle(&plane, center, 4.0, 2.0, -0.5),
+        // the slot is not closed by the two semicircles
+        slot(&plane, center, center, 1.0),
+        slot(&plane, center, Point2::new(1.0, 0.0), 0.0),
+        // a segment is not a closed profile
+        regular_polygon(&plane, center, 1.0, 2),
+        regular_polygon(&plane, center, -1.0, 4),
+        ellipse(&plane, center, 1.0, 0.0),
+    ];
+    for result in results {
+        assert_eq!(result.unwrap_err(), Error::InvalidProfile);
+    }
+}
@@ -0,0 +1,128 @@
+use crate::face_domain::FaceDomain;
+use crate::*;
+use serde::{Deserialize, Serialize};
+
+/// The mass properties of a solid whose density is `1.0`.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct MassProperties {
+    /// the volume
+    pub volume: f64,
+    /// the area of the boundary
+    pub area: f64,
+    /// the center of mass
+    pub center: Point3,
+}
+
+/// Computes the volume, the area, and the center of mass of `solid`.
+///
+/// The integrals on the faces are reduced to the integrals on the boundaries in the parameter
+/// spaces by Green's theorem, and evaluated by Gauss-Legendre quadrature.
+/// `tol` is the tolerance of the polyline approximation of the boundaries.
+/// # Remarks
+/// The result is meaningful only if the boundaries of `solid` are oriented, i.e.
+/// the normals of all faces are directed to the outside of the solid.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::new(2.0, 0.0, 0.0));
+/// let f = builder::tsweep(&e, Vector3::new(0.0, 3.0, 0.0));
+/// let cube = builder::tsweep(&f, Vector3::new(0.0, 0.0, 4.0));
+///
+/// let properties = properties::mass_properties(&cube, 1.0e-3);
+/// assert_near!(properties.volume, 24.0);
+/// assert_near!(properties.area, 52.0);
+/// assert_near!(properties.center, Point3::new(1.0, 1.5, 2.0));
+/// ```
+pub fn mass_properties(solid: &Solid, tol: f64) -> MassProperties {
+    let integral = solid
+        .boundaries()
+        .iter()
+        .flatten()
+        .map(|face| face_integral(&FaceDomain::new(face, tol)))
+        .fold([0.0; 5], |sum, x| add(sum, x));
+    let volume = integral[1];
+    let center = match volume.so_small() {
+        true => Point3::origin(),
+        false => Point3::new(integral[2], integral[3], integral[4]) / volume,
+    };
+    MassProperties {
+        volume,
+        area: integral[0],
+        center,
+    }
+}
+
+/// the nodes and the weights of the Gauss-Legendre quadrature on `[0, 1]`
+const GAUSS_LEGENDRE: [(f64, f64); 5] = [
+    (0.046_910_077_030_668, 0.118_463_442_528_095),
+    (0.230_765_344_947_158, 0.239_314_335_249_683),
+    (0.5, 0.284_444_444_444_444),
+    (0.769_234_655_052_842, 0.239_314_335_249_683),
+    (0.953_089_922_969_332, 0.118_463_442_528_095),
+];
+const INNER_DIVISION: usize = 8;
+
+/// The integrand of (area, volume, first moments).
+/// By the divergence theorem, the volume is 1/3 of the integral of `x n`
+/// and the first moment `\int x_i dV` is 1/2 of the integral of `x_i^2 n_i`.
+fn integrand(surface: &Surface, u: f64, v: f64) -> [f64; 5] {
+    let pt = surface.subs(u, v);
+    let n = surface.uder(u, v).cross(surface.vder(u, v));
+    [
+        n.magnitude(),
+        pt.to_vec().dot(n) / 3.0,
+        pt[0] * pt[0] * n[0] / 2.0,
+        pt[1] * pt[1] * n[1] / 2.0,
+        pt[2] * pt[2] * n[2] / 2.0,
+    ]
+}
+
+fn add(a: [f64; 5], b: [f64; 5]) -> [f64; 5] {
+    [a[0] + b[0], a[1] + b[1], a[2] + b[2], a[3] + b[3], a[4] + b[4]]
+}
+
+fn mul(a: [f64; 5], s: f64) -> [f64; 5] { [a[0] * s, a[1] * s, a[2] * s, a[3] * s, a[4] * s] }
+
+/// The integral on the face is the line integral of `F dv` on the boundary,
+/// where `F(u, v)` is the integral of the integrand from `u0` to `u`.
+fn face_integral(domain: &FaceDomain) -> [f64; 5] {
+    let ((u0, _), _) = domain.surface.parameter_range();
+    let antiderivative = |u: f64, v: f64| {
+        let delta = (u - u0) / INNER_DIVISION as f64;
+        (0..INNER_DIVISION).fold([0.0; 5], |sum, i| {
+            GAUSS_LEGENDRE.iter().fold(sum, |sum, (t, w)| {
+                let s = u0 + delta * (i as f64 + t);
+                add(sum, mul(integrand(&domain.surface, s, v), w * delta))
+            })
+        })
+    };
+    domain
+        .closed_boundary_uvs()
+        .iter()
+        .flat_map(|polygon| polygon.windows(2))
+        .fold([0.0; 5], |sum, segment| {
+            let (p, q) = (segment[0], segment[1]);
+            let dv = q[1] - p[1];
+            if dv == 0.0 {
+                return sum;
+            }
+            GAUSS_LEGENDRE.iter().fold(sum, |sum, (t, w)| {
+                let uv = p + (q - p) * *t;
+                add(sum, mul(antiderivative(uv[0], uv[1]), w * dv))
+            })
+        })
+}
+
+#[test]
+fn cylinder_mass_properties() {
+    use std::f64::consts::PI;
+    let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+    let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(2.0 * PI));
+    let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+    let cylinder = builder::tsweep(&disk, Vector3::new(0.0, 0.0, 2.0));
+    let properties = mass_properties(&cylinder, 1.0e-4);
+    assert!((properties.volume - 2.0 * PI).abs() < 1.0e-3, "{:?}", properties);
+    assert!((properties.area - 6.0 * PI).abs() < 1.0e-3, "{:?}", properties);
+    assert!(properties.center.distance(Point3::new(0.0, 0.0, 1.0)) < 1.0e-3, "{:?}", properties);
+}
@@ -0,0 +1,187 @@
+use crate::errors::Error;
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+
+/// The tolerance used for computing the volumes of parts.
+pub const QUANTITY_TOLERANCE: f64 = 1.0e-3;
+
+/// Material of parts
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Material {
+    /// mass per unit volume
+    pub density: f64,
+    /// price per unit mass
+    pub price: f64,
+}
+
+/// The table from the names of materials to the materials.
+pub type MaterialTable = HashMap<String, Material>;
+
+/// The quantity of a part.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct PartQuantity {
+    /// the name of the part
+    pub name: String,
+    /// the name of the material
+    pub material: String,
+    /// the number of the parts
+    pub count: usize,
+    /// the volume of one part
+    pub volume: f64,
+    /// the total mass of `count` parts
+    pub mass: f64,
+    /// the total cost of `count` parts
+    pub cost: f64,
+}
+
+/// The quantity-of-material report of an assembly.
+///
+/// A report consists of parts and sub-assemblies, and the totals are summed up recursively.
+/// The report can be serialized to JSON by `serde`, or exported as CSV by [`write_csv`].
+///
+/// [`write_csv`]: #method.write_csv
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::quantity::*;
+/// let mut table = MaterialTable::new();
+/// table.insert("steel".to_string(), Material { density: 7.85, price: 0.1 });
+/// table.insert("aluminium".to_string(), Material { density: 2.7, price: 0.3 });
+///
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let mut bracket = QuantityReport::new("bracket");
+/// bracket.add_part("plate", &cube, "aluminium", 2, &table).unwrap();
+/// let mut frame = QuantityReport::new("frame");
+/// frame.add_part("block", &cube, "steel", 1, &table).unwrap();
+/// frame.add_assembly(bracket);
+///
+/// assert_near!(frame.total_mass(), 7.85 + 5.4);
+/// assert_near!(frame.total_cost(), 0.785 + 1.62);
+/// assert!(frame.add_part("pin", &cube, "brass", 1, &table).is_err());
+///
+/// let mut csv = Vec::new();
+/// frame.write_csv(&mut csv).unwrap();
+/// let csv = String::from_utf8(csv).unwrap();
+/// assert!(csv.starts_with("path,material,count,volume,mass,cost\n"));
+/// assert!(csv.contains("frame/bracket/plate,aluminium,2,"));
+///
+/// let json = serde_json::to_string(&frame).unwrap();
+/// let report: QuantityReport = serde_json::from_str(&json).unwrap();
+/// assert_eq!(report, frame);
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct QuantityReport {
+    /// the name of the assembly
+    pub name: String,
+    /// the parts directly contained in the assembly
+    pub parts: Vec<PartQuantity>,
+    /// the sub-assemblies
+    pub assemblies: Vec<QuantityReport>,
+}
+
+impl QuantityReport {
+    /// Creates an empty report.
+    #[inline(always)]
+    pub fn new(name: &str) -> QuantityReport {
+        QuantityReport {
+            name: name.to_string(),
+            parts: Vec::new(),
+            assemblies: Vec::new(),
+        }
+    }
+
+    /// Adds `count` parts whose shape is `solid` and whose material is `material`.
+    /// # Failures
+    /// Returns [`Error::UnknownMaterial`] if `material` is not in `table`.
+    ///
+    /// [`Error::UnknownMaterial`]: ../errors/enum.Error.html#variant.UnknownMaterial
+    pub fn add_part(
+        &mut self,
+        name: &str,
+        solid: &Solid,
+        material: &str,
+        count: usize,
+        table: &MaterialTable,
+    ) -> Result<&PartQuantity> {
+        let mat = table
+            .get(material)
+            .ok_or_else(|| Error::UnknownMaterial(material.to_string()))?;
+        let volume = properties::mass_properties(solid, QUANTITY_TOLERANCE).volume;
+        let mass = volume * mat.density * count as f64;
+        self.parts.push(PartQuantity {
+            name: name.to_string(),
+            material: material.to_string(),
+            count,
+            volume,
+            mass,
+            cost: mass * mat.price,
+        });
+        Ok(&self.parts[self.parts.len() - 1])
+    }
+
+    /// Adds a sub-assembly.
+    #[inline(always)]
+    pub fn add_assembly(&mut self, report: QuantityReport) { self.assemblies.push(report) }
+
+    /// Returns the total mass of all parts contained in the assembly and its sub-assemblies.
+    pub fn total_mass(&self) -> f64 {
+        self.parts.iter().map(|part| part.mass).sum::<f64>()
+            + self.assemblies.iter().map(QuantityReport::total_mass).sum::<f64>()
+    }
+
+    /// Returns the total cost of all parts contained in the assembly and its sub-assemblies.
+    pub fn total_cost(&self) -> f64 {
+        self.parts.iter().map(|part| part.cost).sum::<f64>()
+            + self.assemblies.iter().map(QuantityReport::total_cost).sum::<f64>()
+    }
+
+    /// Returns the total mass of each material.
+    pub fn mass_by_material(&self) -> HashMap<String, f64> {
+        let mut res = HashMap::new();
+        self.add_mass_by_material(&mut res);
+        res
+    }
+
+    fn add_mass_by_material(&self, res: &mut HashMap<String, f64>) {
+        self.parts.iter().for_each(|part| {
+            *res.entry(part.material.clone()).or_insert(0.0) += part.mass;
+        });
+        self.assemblies
+            .iter()
+            .for_each(|assembly| assembly.add_mass_by_material(res));
+    }
+
+    /// Writes the report as CSV.
+    ///
+    /// Each part is written in a row whose path is joined by `/`,
+    /// and the total of each assembly is written in the row with the material `TOTAL`.
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        writer.write_all(b"path,material,count,volume,mass,cost\n")?;
+        self.write_csv_rows(&mut writer, "")
+    }
+
+    fn write_csv_rows<W: Write>(&self, writer: &mut W, prefix: &str) -> std::io::Result<()> {
+        let path = format!("{}{}", prefix, self.name);
+        for part in &self.parts {
+            writer.write_fmt(format_args!(
+                "{}/{},{},{},{},{},{}\n",
+                path, part.name, part.material, part.count, part.volume, part.mass, part.cost
+            ))?;
+        }
+        for assembly in &self.assemblies {
+            assembly.write_csv_rows(writer, &format!("{}/", path))?;
+        }
+        writer.write_fmt(format_args!(
+            "{},TOTAL,,,{},{}\n",
+            path,
+            self.total_mass(),
+            self.total_cost()
+        ))
+    }
+}
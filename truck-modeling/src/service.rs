@@ -0,0 +1,384 @@
+use crate::dynamic::{AnyShape, Tessellation};
+use crate::*;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// The handle of a shape registered in the service.
+pub type Handle = u64;
+
+/// The commands of the geometry service.
+///
+/// A command is given in JSON as `{"operation": <snake case name>, "params": {...}}`.
+/// The points and the vectors are arrays `[x, y, z]`, and the angles are in radians.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "operation", content = "params", rename_all = "snake_case")]
+pub enum Command {
+    /// Creates a vertex. Returns the handle.
+    Vertex {
+        /// the point of the vertex
+        point: [f64; 3],
+    },
+    /// Sweeps the shape along the vector. Returns the handle.
+    Tsweep {
+        /// the swept shape
+        shape: Handle,
+        /// the sweeping vector
+        vector: [f64; 3],
+    },
+    /// Sweeps the shape around the axis. Returns the handle.
+    Rsweep {
+        /// the swept shape
+        shape: Handle,
+        /// the origin of the axis
+        origin: [f64; 3],
+        /// the direction of the axis
+        axis: [f64; 3],
+        /// the angle of rotation
+        angle: f64,
+    },
+    /// Attaches a plane to the closed wires. Returns the handle of the face.
+    AttachPlane {
+        /// the boundary wires
+        wires: Vec<Handle>,
+    },
+    /// Creates the transformed shape. Returns the handle.
+    Transform {
+        /// the transformed shape
+        shape: Handle,
+        /// the column major 4x4 matrix
+        matrix: [f64; 16],
+    },
+    /// Creates the translated shape. Returns the handle.
+    Translate {
+        /// the translated shape
+        shape: Handle,
+        /// the translation vector
+        vector: [f64; 3],
+    },
+    /// Creates the rotated shape. Returns the handle.
+    Rotate {
+        /// the rotated shape
+        shape: Handle,
+        /// the origin of the axis
+        origin: [f64; 3],
+        /// the direction of the axis
+        axis: [f64; 3],
+        /// the angle of rotation
+        angle: f64,
+    },
+    /// Tessellates the shape. Returns the positions, the normals and the triangle indices
+    /// if the shape is tessellated to a mesh, otherwise the points or the polylines.
+    Tessellate {
+        /// the tessellated shape
+        shape: Handle,
+        /// the tolerance of tessellation
+        tolerance: f64,
+    },
+    /// Returns the mass properties of the solid.
+    MassProperties {
+        /// the solid
+        shape: Handle,
+    },
+    /// Returns the serialized shape.
+    Export {
+        /// the exported shape
+        shape: Handle,
+    },
+    /// Registers the serialized shape. Returns the handle.
+    Import {
+        /// the serialized shape
+        data: Value,
+    },
+    /// Returns the type of the shape.
+    Describe {
+        /// the shape
+        shape: Handle,
+    },
+    /// Returns the handles of all shapes.
+    List,
+    /// Removes the shape from the service.
+    Delete {
+        /// the removed shape
+        shape: Handle,
+    },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Value,
+    #[serde(flatten)]
+    command: Command,
+}
+
+/// Headless geometry service holding shapes by handles.
+///
+/// The service is driven by JSON requests `{"id": ..., "operation": ..., "params": ...}`,
+/// and replies `{"id": ..., "result": ...}` or `{"id": ..., "error": ...}`.
+/// The transport, e.g. HTTP or standard I/O, is left to the application.
+/// # Examples
+/// ```
+/// use truck_modeling::service::GeometryService;
+/// use serde_json::{json, Value};
+/// let mut service = GeometryService::new();
+/// let mut call = |request: Value| -> Value {
+///     let response = service.handle_json(&request.to_string());
+///     serde_json::from_str(&response).unwrap()
+/// };
+///
+/// let v = call(json!({"id": 0, "operation": "vertex", "params": {"point": [0.0, 0.0, 0.0]}}));
+/// assert_eq!(v["id"], 0);
+/// let v = &v["result"];
+/// let e = call(json!({"operation": "tsweep", "params": {"shape": v, "vector": [1.0, 0.0, 0.0]}}));
+/// let e = &e["result"];
+/// let f = call(json!({"operation": "tsweep", "params": {"shape": e, "vector": [0.0, 1.0, 0.0]}}));
+/// let f = &f["result"];
+/// let s = call(json!({"operation": "tsweep", "params": {"shape": f, "vector": [0.0, 0.0, 1.0]}}));
+/// let s = &s["result"];
+///
+/// let res = call(json!({"operation": "describe", "params": {"shape": s}}));
+/// assert_eq!(res["result"]["type"], "Solid");
+/// let res = call(json!({"operation": "mass_properties", "params": {"shape": s}}));
+/// assert!((res["result"]["volume"].as_f64().unwrap() - 1.0).abs() < 1.0e-6);
+/// let res = call(json!({"operation": "tessellate", "params": {"shape": s, "tolerance": 0.01}}));
+/// assert!(!res["result"]["indices"].as_array().unwrap().is_empty());
+///
+/// let res = call(json!({"operation": "mass_properties", "params": {"shape": f}}));
+/// assert!(res["error"].is_string());
+/// let res = call(json!({"operation": "unknown"}));
+/// assert!(res["error"].is_string());
+/// ```
+#[derive(Debug, Default)]
+pub struct GeometryService {
+    shapes: BTreeMap<Handle, AnyShape>,
+    next_handle: Handle,
+}
+
+/// the error of a command, replied as a message
+type CommandResult = std::result::Result<Value, String>;
+
+fn point(p: [f64; 3]) -> Point3 { Point3::new(p[0], p[1], p[2]) }
+
+fn vector(v: [f64; 3]) -> Vector3 { Vector3::new(v[0], v[1], v[2]) }
+
+fn type_error(shape: &AnyShape, operation: &str) -> String {
+    format!("{} cannot be applied to {}.", operation, shape.type_name())
+}
+
+impl GeometryService {
+    /// Creates an empty service.
+    #[inline(always)]
+    pub fn new() -> GeometryService { GeometryService::default() }
+
+    /// Returns the shape of the handle.
+    #[inline(always)]
+    pub fn shape(&self, handle: Handle) -> Option<&AnyShape> { self.shapes.get(&handle) }
+
+    /// Registers the shape, and returns its handle.
+    pub fn insert(&mut self, shape: AnyShape) -> Handle {
+        let handle = self.next_handle;
+        self.next_handle += 1;
+        self.shapes.insert(handle, shape);
+        handle
+    }
+
+    /// Handles the JSON request, and returns the JSON response.
+    pub fn handle_json(&mut self, request: &str) -> String {
+        let response = match serde_json::from_str::<Request>(request) {
+            Ok(Request { id, command }) => match self.execute(command) {
+                Ok(result) => json!({ "id": id, "result": result }),
+                Err(error) => json!({ "id": id, "error": error }),
+            },
+            Err(error) => {
+                let id = serde_json::from_str::<Value>(request)
+                    .ok()
+                    .and_then(|value| value.get("id").cloned())
+                    .unwrap_or(Value::Null);
+                json!({ "id": id, "error": format!("invalid request: {}", error) })
+            }
+        };
+        response.to_string()
+    }
+
+    fn get(&self, handle: Handle) -> std::result::Result<&AnyShape, String> {
+        self.shapes
+            .get(&handle)
+            .ok_or_else(|| format!("the shape {} is not found.", handle))
+    }
+
+    fn register(&mut self, shape: AnyShape) -> CommandResult { Ok(json!(self.insert(shape))) }
+
+    /// Executes the command, and returns the result or the error message.
+    pub fn execute(&mut self, command: Command) -> CommandResult {
+        match command {
+            Command::Vertex { point: p } => self.register(builder::vertex(point(p)).into()),
+            Command::Tsweep { shape, vector: v } => {
+                let v = vector(v);
+                let swept: AnyShape = match self.get(shape)? {
+                    AnyShape::Vertex(vertex) => builder::tsweep(vertex, v).into(),
+                    AnyShape::Edge(edge) => builder::tsweep(edge, v).into(),
+                    AnyShape::Wire(wire) => builder::tsweep(wire, v).into(),
+                    AnyShape::Face(face) => builder::tsweep(face, v).into(),
+                    shape => return Err(type_error(shape, "tsweep")),
+                };
+                self.register(swept)
+            }
+            Command::Rsweep {
+                shape,
+                origin,
+                axis,
+                angle,
+            } => {
+                let (o, a, angle) = (point(origin), vector(axis), Rad(angle));
+                if a.so_small() {
+                    return Err("the axis is zero vector.".to_string());
+                }
+                let a = a.normalize();
+                let swept: AnyShape = match self.get(shape)? {
+                    AnyShape::Vertex(vertex) => builder::rsweep(vertex, o, a, angle).into(),
+                    AnyShape::Edge(edge) => builder::rsweep(edge, o, a, angle).into(),
+                    AnyShape::Wire(wire) => builder::rsweep(wire, o, a, angle).into(),
+                    AnyShape::Face(face) => builder::rsweep(face, o, a, angle).into(),
+                    shape => return Err(type_error(shape, "rsweep")),
+                };
+                self.register(swept)
+            }
+            Command::AttachPlane { wires } => {
+                let wires = wires
+                    .into_iter()
+                    .map(|handle| match self.get(handle)? {
+                        AnyShape::Wire(wire) => Ok(wire.clone()),
+                        AnyShape::Edge(edge) => Ok(vec![edge.clone()].into()),
+                        shape => Err(type_error(shape, "attach_plane")),
+                    })
+                    .collect::<std::result::Result<Vec<Wire>, String>>()?;
+                let face = builder::try_attach_plane(&wires).map_err(|e| e.to_string())?;
+                self.register(face.into())
+            }
+            Command::Transform { shape, matrix } => {
+                let m = matrix;
+                let mat = Matrix4::new(
+                    m[0], m[1], m[2], m[3], m[4], m[5], m[6], m[7], m[8], m[9], m[10], m[11],
+                    m[12], m[13], m[14], m[15],
+                );
+                let shape = self.get(shape)?.transformed(mat);
+                self.register(shape)
+            }
+            Command::Translate { shape, vector: v } => {
+                let mat = Matrix4::from_translation(vector(v));
+                let shape = self.get(shape)?.transformed(mat);
+                self.register(shape)
+            }
+            Command::Rotate {
+                shape,
+                origin,
+                axis,
+                angle,
+            } => {
+                let (o, a) = (point(origin).to_vec(), vector(axis));
+                if a.so_small() {
+                    return Err("the axis is zero vector.".to_string());
+                }
+                let mat = Matrix4::from_translation(o)
+                    * Matrix4::from_axis_angle(a.normalize(), Rad(angle))
+                    * Matrix4::from_translation(-o);
+                let shape = self.get(shape)?.transformed(mat);
+                self.register(shape)
+            }
+            Command::Tessellate { shape, tolerance } => {
+                if tolerance <= 0.0 {
+                    return Err("the tolerance must be positive.".to_string());
+                }
+                Ok(match self.get(shape)?.tessellate(tolerance) {
+                    Tessellation::Points(points) => json!({ "points": points_json(&points) }),
+                    Tessellation::Polylines(polylines) => json!({
+                        "polylines": polylines.iter().map(|p| points_json(p)).collect::<Vec<_>>()
+                    }),
+                    Tessellation::Mesh(mesh) => {
+                        let positions = mesh.positions();
+                        let indices: Vec<usize> = mesh
+                            .face_iter()
+                            .flat_map(|face| {
+                                (2..face.len()).flat_map(move |i| {
+                                    vec![face[0].pos, face[i - 1].pos, face[i].pos]
+                                })
+                            })
+                            .collect();
+                        let normals: Vec<[f64; 3]> = match mesh.normals().len() == positions.len() {
+                            true => mesh.normals().iter().map(|n| [n[0], n[1], n[2]]).collect(),
+                            false => Vec::new(),
+                        };
+                        json!({
+                            "positions": points_json(positions),
+                            "normals": normals,
+                            "indices": indices,
+                        })
+                    }
+                })
+            }
+            Command::MassProperties { shape } => match self.get(shape)? {
+                AnyShape::Solid(solid) => {
+                    let props = properties::mass_properties(solid, 1.0e-3);
+                    Ok(json!({
+                        "volume": props.volume,
+                        "area": props.area,
+                        "center": [props.center[0], props.center[1], props.center[2]],
+                    }))
+                }
+                shape => Err(type_error(shape, "mass_properties")),
+            },
+            Command::Export { shape } => {
+                serde_json::to_value(self.get(shape)?).map_err(|e| e.to_string())
+            }
+            Command::Import { data } => {
+                let shape: AnyShape = serde_json::from_value(data).map_err(|e| e.to_string())?;
+                self.register(shape)
+            }
+            Command::Describe { shape } => {
+                let shape = self.get(shape)?;
+                let bdd = shape.tessellate(1.0e-2).bounding_box();
+                let bounding_box = match bdd.is_empty() {
+                    true => Value::Null,
+                    false => {
+                        let (min, max) = (bdd.min(), bdd.max());
+                        json!([[min[0], min[1], min[2]], [max[0], max[1], max[2]]])
+                    }
+                };
+                Ok(json!({ "type": shape.type_name(), "bounding_box": bounding_box }))
+            }
+            Command::List => Ok(json!(self
+                .shapes
+                .iter()
+                .map(|(handle, shape)| json!({ "handle": handle, "type": shape.type_name() }))
+                .collect::<Vec<_>>())),
+            Command::Delete { shape } => match self.shapes.remove(&shape) {
+                Some(_) => Ok(Value::Null),
+                None => Err(format!("the shape {} is not found.", shape)),
+            },
+        }
+    }
+}
+
+fn points_json(points: &[Point3]) -> Vec<[f64; 3]> {
+    points.iter().map(|p| [p[0], p[1], p[2]]).collect()
+}
+
+#[test]
+fn service_errors() {
+    let mut service = GeometryService::new();
+    let response: Value = serde_json::from_str(&service.handle_json("not json")).unwrap();
+    assert!(response["error"].is_string());
+    let response: Value = serde_json::from_str(&service.handle_json(
+        r#"{"id": "a", "operation": "translate", "params": {"shape": 3, "vector": [0, 0, 1]}}"#,
+    ))
+    .unwrap();
+    assert_eq!(response["id"], "a");
+    assert_eq!(response["error"], "the shape 3 is not found.");
+    let response: Value = serde_json::from_str(
+        &service.handle_json(r#"{"id": 1, "operation": "vertex", "params": {"point": [0, 0]}}"#),
+    )
+    .unwrap();
+    assert_eq!(response["id"], 1);
+    assert!(response["error"].is_string());
+}
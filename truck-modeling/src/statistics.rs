@@ -0,0 +1,310 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+
+// the number of the sample points on each edge estimating the length
+const EDGE_SAMPLES: usize = 16;
+// the division of the grid of the sample points checking the normals of the surfaces
+const SURFACE_SAMPLES: usize = 4;
+// the estimated overhead of an `Arc<Mutex<_>>` allocation: the reference counts and the lock
+const SHARED_OVERHEAD: usize = 24;
+
+/// The statistics of the boundary representation of a solid.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Statistics {
+    /// the number of the shells
+    pub shells: usize,
+    /// the number of the faces
+    pub faces: usize,
+    /// the number of the boundary wires of the faces
+    pub wires: usize,
+    /// the number of the distinct edges
+    pub edges: usize,
+    /// the number of the distinct vertices
+    pub vertices: usize,
+    /// the total number of the control points of the distinct curves and surfaces
+    pub control_points: usize,
+    /// the estimated memory usage in bytes
+    pub memory: usize,
+    /// the most complex face, `None` if the solid has no faces
+    pub max_face_complexity: Option<FaceComplexity>,
+    /// the warnings of the degenerate geometries
+    pub warnings: Vec<GeometryWarning>,
+}
+
+/// The complexity of a face.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FaceComplexity {
+    /// the index of the face in the order of `solid.boundaries().iter().flatten()`
+    pub face: usize,
+    /// the number of the boundary edges
+    pub edges: usize,
+    /// the number of the control points of the surface and the boundary curves
+    pub control_points: usize,
+}
+
+/// The warnings of the degenerate geometries.
+///
+/// The faces are indexed in the order of `solid.boundaries().iter().flatten()`,
+/// and the edges are indexed in the order of their first appearances in the faces.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum GeometryWarning {
+    /// the edge is shorter than `TOLERANCE`
+    ShortEdge {
+        /// the index of the edge
+        edge: usize,
+        /// the length of the edge
+        length: f64,
+    },
+    /// the normal of the surface vanishes at the parameter, e.g. at the poles of spheres
+    DegenerateSurface {
+        /// the index of the face
+        face: usize,
+        /// the parameter at which the normal vanishes
+        parameter: (f64, f64),
+    },
+    /// the NURBS curve of the edge has a non-positive weight
+    NonPositiveCurveWeight {
+        /// the index of the edge
+        edge: usize,
+    },
+    /// the NURBS surface of the face has a non-positive weight
+    NonPositiveSurfaceWeight {
+        /// the index of the face
+        face: usize,
+    },
+}
+
+/// Reporting the statistics of solids.
+pub trait SolidStatistics {
+    /// Returns the counts of the topological elements, the total number of the control points,
+    /// the estimated memory usage, the most complex face, and the warnings of the degenerate
+    /// geometries, which helps to find why a model is slow before profiling.
+    /// # Examples
+    /// ```
+    /// use truck_modeling::*;
+    /// use truck_modeling::statistics::SolidStatistics;
+    /// let v = builder::vertex(Point3::origin());
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let cube = builder::tsweep(&f, Vector3::unit_z());
+    ///
+    /// let stats = cube.stats();
+    /// assert_eq!(stats.shells, 1);
+    /// assert_eq!(stats.faces, 6);
+    /// assert_eq!(stats.wires, 6);
+    /// assert_eq!(stats.edges, 12);
+    /// assert_eq!(stats.vertices, 8);
+    /// assert_eq!(stats.max_face_complexity.unwrap().edges, 4);
+    /// assert!(stats.memory > 0);
+    /// assert!(stats.warnings.is_empty());
+    /// println!("{}", stats);
+    /// ```
+    fn stats(&self) -> Statistics;
+}
+
+impl SolidStatistics for Solid {
+    fn stats(&self) -> Statistics {
+        let mut edge_indices = HashMap::<EdgeID, usize>::new();
+        let mut vertex_ids = HashSet::<VertexID>::new();
+        let mut control_points = 0;
+        let mut memory = size_of::<Solid>();
+        let mut max_face_complexity: Option<FaceComplexity> = None;
+        let mut warnings = Vec::new();
+        let faces: Vec<&Face> = self.boundaries().iter().flatten().collect();
+        memory += self.boundaries().len() * size_of::<Shell>();
+        for (face_index, face) in faces.iter().enumerate() {
+            let surface = face.lock_surface().unwrap().clone();
+            let surface_points = surface_control_points(&surface);
+            let mut complexity = FaceComplexity {
+                face: face_index,
+                edges: 0,
+                control_points: surface_points,
+            };
+            control_points += surface_points;
+            memory += size_of::<Face>() + SHARED_OVERHEAD + surface_memory(&surface);
+            warnings.extend(surface_warnings(&surface, face_index));
+            for wire in face.absolute_boundaries() {
+                memory += size_of::<Wire>() + wire.len() * size_of::<Edge>();
+                for edge in wire {
+                    let curve = edge.lock_curve().unwrap().clone();
+                    let curve_points = curve_control_points(&curve);
+                    complexity.edges += 1;
+                    complexity.control_points += curve_points;
+                    if edge_indices.contains_key(&edge.id()) {
+                        continue;
+                    }
+                    let edge_index = edge_indices.len();
+                    edge_indices.insert(edge.id(), edge_index);
+                    control_points += curve_points;
+                    memory += SHARED_OVERHEAD + curve_memory(&curve);
+                    warnings.extend(curve_warnings(&curve, edge_index));
+                    for vertex in [edge.front(), edge.back()].iter() {
+                        if vertex_ids.insert(vertex.id()) {
+                            memory += size_of::<Vertex>() + SHARED_OVERHEAD + size_of::<Point3>();
+                        }
+                    }
+                }
+            }
+            let more_complex = max_face_complexity.map_or(true, |max| {
+                (complexity.control_points, complexity.edges) > (max.control_points, max.edges)
+            });
+            if more_complex {
+                max_face_complexity = Some(complexity);
+            }
+        }
+        Statistics {
+            shells: self.boundaries().len(),
+            faces: faces.len(),
+            wires: faces
+                .iter()
+                .map(|face| face.absolute_boundaries().len())
+                .sum(),
+            edges: edge_indices.len(),
+            vertices: vertex_ids.len(),
+            control_points,
+            memory,
+            max_face_complexity,
+            warnings,
+        }
+    }
+}
+
+impl std::fmt::Display for Statistics {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        writeln!(f, "shells: {}", self.shells)?;
+        writeln!(f, "faces: {}", self.faces)?;
+        writeln!(f, "wires: {}", self.wires)?;
+        writeln!(f, "edges: {}", self.edges)?;
+        writeln!(f, "vertices: {}", self.vertices)?;
+        writeln!(f, "control points: {}", self.control_points)?;
+        writeln!(f, "estimated memory: {} bytes", self.memory)?;
+        if let Some(max) = &self.max_face_complexity {
+            writeln!(
+                f,
+                "most complex face: #{} ({} edges, {} control points)",
+                max.face, max.edges, max.control_points
+            )?;
+        }
+        for warning in &self.warnings {
+            match warning {
+                GeometryWarning::ShortEdge { edge, length } => {
+                    writeln!(f, "warning: the edge #{} is too short: {}", edge, length)?
+                }
+                GeometryWarning::DegenerateSurface { face, parameter } => writeln!(
+                    f,
+                    "warning: the normal of the face #{} vanishes at {:?}",
+                    face, parameter
+                )?,
+                GeometryWarning::NonPositiveCurveWeight { edge } => {
+                    writeln!(f, "warning: the edge #{} has a non-positive weight", edge)?
+                }
+                GeometryWarning::NonPositiveSurfaceWeight { face } => {
+                    writeln!(f, "warning: the face #{} has a non-positive weight", face)?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+fn curve_control_points(curve: &Curve) -> usize {
+    match curve {
+        Curve::BSplineCurve(curve) => curve.control_points().len(),
+        Curve::NURBSCurve(curve) => curve.control_points().len(),
+    }
+}
+
+fn curve_memory(curve: &Curve) -> usize {
+    let points = match curve {
+        Curve::BSplineCurve(curve) => curve.control_points().len() * size_of::<Vector3>(),
+        Curve::NURBSCurve(curve) => curve.control_points().len() * size_of::<Vector4>(),
+    };
+    size_of::<Curve>() + points + curve.knot_vec().len() * size_of::<f64>()
+}
+
+fn surface_control_points(surface: &Surface) -> usize {
+    match surface {
+        Surface::Plane(_) => 3,
+        Surface::BSplineSurface(surface) => surface_control_points_len(surface.control_points()),
+        Surface::NURBSSurface(surface) => surface_control_points_len(surface.control_points()),
+        Surface::RevolutedCurve(surface) => curve_control_points(surface.entity().entity_curve()),
+    }
+}
+
+fn surface_memory(surface: &Surface) -> usize {
+    let extra = match surface {
+        Surface::Plane(_) => 0,
+        Surface::BSplineSurface(surface) => {
+            let (uknots, vknots) = surface.knot_vecs();
+            surface_control_points_len(surface.control_points()) * size_of::<Vector3>()
+                + (uknots.len() + vknots.len()) * size_of::<f64>()
+        }
+        Surface::NURBSSurface(surface) => {
+            let (uknots, vknots) = surface.knot_vecs();
+            surface_control_points_len(surface.control_points()) * size_of::<Vector4>()
+                + (uknots.len() + vknots.len()) * size_of::<f64>()
+        }
+        Surface::RevolutedCurve(surface) => curve_memory(surface.entity().entity_curve()),
+    };
+    size_of::<Surface>() + extra
+}
+
+#[inline(always)]
+fn surface_control_points_len<V>(control_points: &[Vec<V>]) -> usize {
+    control_points.iter().map(Vec::len).sum()
+}
+
+fn curve_warnings(curve: &Curve, edge: usize) -> Vec<GeometryWarning> {
+    let mut warnings = Vec::new();
+    let (t0, t1) = curve.parameter_range();
+    let length: f64 = (0..EDGE_SAMPLES)
+        .map(|i| {
+            let s = t0 + (t1 - t0) * i as f64 / EDGE_SAMPLES as f64;
+            let t = t0 + (t1 - t0) * (i + 1) as f64 / EDGE_SAMPLES as f64;
+            curve.subs(s).distance(curve.subs(t))
+        })
+        .sum();
+    if length < TOLERANCE {
+        warnings.push(GeometryWarning::ShortEdge { edge, length });
+    }
+    if let Curve::NURBSCurve(curve) = curve {
+        if curve.control_points().iter().any(|pt| pt[3] <= 0.0) {
+            warnings.push(GeometryWarning::NonPositiveCurveWeight { edge });
+        }
+    }
+    warnings
+}
+
+fn surface_warnings(surface: &Surface, face: usize) -> Vec<GeometryWarning> {
+    let mut warnings = Vec::new();
+    let ((u0, u1), (v0, v1)) = surface.parameter_range();
+    let samples = (0..=SURFACE_SAMPLES).flat_map(move |i| {
+        (0..=SURFACE_SAMPLES).map(move |j| {
+            let u = u0 + (u1 - u0) * i as f64 / SURFACE_SAMPLES as f64;
+            let v = v0 + (v1 - v0) * j as f64 / SURFACE_SAMPLES as f64;
+            (u, v)
+        })
+    });
+    for (u, v) in samples {
+        if surface.uder(u, v).cross(surface.vder(u, v)).so_small() {
+            warnings.push(GeometryWarning::DegenerateSurface {
+                face,
+                parameter: (u, v),
+            });
+            break;
+        }
+    }
+    if let Surface::NURBSSurface(surface) = surface {
+        if surface
+            .control_points()
+            .iter()
+            .flatten()
+            .any(|pt| pt[3] <= 0.0)
+        {
+            warnings.push(GeometryWarning::NonPositiveSurfaceWeight { face });
+        }
+    }
+    warnings
+}
@@ -0,0 +1,560 @@
+use crate::assembly::{Assembly, Instance};
+use crate::errors::Error;
+use crate::kinematics::Mechanism;
+use crate::tessellation::tessellate_solid;
+use crate::*;
+use std::collections::HashMap;
+use truck_polymesh::{Faces, PolygonMesh};
+
+/// The options of the swept volumes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SweptVolumeOption {
+    /// the edge length of the voxels. Default is `0.05`.
+    pub voxel_size: f64,
+    /// the tolerance of the tessellation of the solids. Default is `0.01`.
+    pub tolerance: f64,
+    /// the number of the divisions of the motion. Default is `32`.
+    ///
+    /// The steps should be small enough compared with `voxel_size`, since the positions
+    /// between the steps are not swept.
+    pub steps: usize,
+}
+
+impl Default for SweptVolumeOption {
+    #[inline(always)]
+    fn default() -> SweptVolumeOption {
+        SweptVolumeOption {
+            voxel_size: 0.05,
+            tolerance: 0.01,
+            steps: 32,
+        }
+    }
+}
+
+/// The volume approximated by the voxels on the regular grid.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VoxelVolume {
+    origin: Point3,
+    voxel_size: f64,
+    dims: [usize; 3],
+    cells: Vec<bool>,
+}
+
+/// The clearance between the swept volume of the moving component and a static component.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ClearanceReport {
+    /// the index of the static component in `Assembly::components`
+    pub component: usize,
+    /// the minimum distance between the swept volume and the component, `0.0` if they interfere
+    pub clearance: f64,
+}
+
+impl ClearanceReport {
+    /// Returns whether the moving component collides with the static component.
+    #[inline(always)]
+    pub fn interferes(&self) -> bool { self.clearance <= 0.0 }
+}
+
+impl VoxelVolume {
+    fn new(bdd_box: &BoundingBox<Point3>, voxel_size: f64) -> VoxelVolume {
+        let origin = *bdd_box.min() - Vector3::new(voxel_size, voxel_size, voxel_size);
+        let diag = bdd_box.diagonal();
+        let mut dims = [0; 3];
+        (0..3).for_each(|k| dims[k] = (diag[k] / voxel_size).ceil() as usize + 2);
+        VoxelVolume {
+            origin,
+            voxel_size,
+            dims,
+            cells: vec![false; dims[0] * dims[1] * dims[2]],
+        }
+    }
+
+    /// Returns the edge length of the voxels.
+    #[inline(always)]
+    pub fn voxel_size(&self) -> f64 { self.voxel_size }
+
+    /// Returns the number of the occupied voxels.
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.cells.iter().filter(|x| **x).count() }
+
+    /// Returns whether there are no occupied voxels.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { !self.cells.iter().any(|x| *x) }
+
+    /// Returns the volume of the occupied voxels.
+    #[inline(always)]
+    pub fn volume(&self) -> f64 { self.len() as f64 * self.voxel_size.powi(3) }
+
+    /// Returns whether the voxel containing `point` is occupied.
+    #[inline(always)]
+    pub fn contains(&self, point: Point3) -> bool {
+        self.voxel_index(point)
+            .map(|idx| self.cells[self.cell(idx)])
+            .unwrap_or(false)
+    }
+
+    #[inline(always)]
+    fn cell(&self, [i, j, k]: [usize; 3]) -> usize { (k * self.dims[1] + j) * self.dims[0] + i }
+
+    #[inline(always)]
+    fn occupied(&self, idx: [isize; 3]) -> bool {
+        let in_range = (0..3).all(|k| idx[k] >= 0 && (idx[k] as usize) < self.dims[k]);
+        in_range && self.cells[self.cell([idx[0] as usize, idx[1] as usize, idx[2] as usize])]
+    }
+
+    #[inline(always)]
+    fn voxel_index(&self, point: Point3) -> Option<[usize; 3]> {
+        let mut idx = [0; 3];
+        for (k, i) in idx.iter_mut().enumerate() {
+            let x = ((point[k] - self.origin[k]) / self.voxel_size).floor();
+            if x < 0.0 || x >= self.dims[k] as f64 {
+                return None;
+            }
+            *i = x as usize;
+        }
+        Some(idx)
+    }
+
+    #[inline(always)]
+    fn center(&self, idx: [usize; 3]) -> Point3 {
+        self.origin
+            + Vector3::new(
+                idx[0] as f64 + 0.5,
+                idx[1] as f64 + 0.5,
+                idx[2] as f64 + 0.5,
+            ) * self.voxel_size
+    }
+
+    // Fills the voxels whose centers are inside the closed triangles, and the voxels
+    // containing the sample points on the triangles, so that the thin parts do not vanish.
+    fn fill(&mut self, triangles: &[[Point3; 3]]) {
+        for k in 0..self.dims[2] {
+            for j in 0..self.dims[1] {
+                let p = self.center([0, j, k]);
+                let crossings = ray_crossings(triangles, p[1], p[2]);
+                for pair in crossings.chunks_exact(2) {
+                    let i0 = ((pair[0] - self.origin[0]) / self.voxel_size - 0.5).ceil();
+                    let i1 = ((pair[1] - self.origin[0]) / self.voxel_size - 0.5).floor();
+                    let i0 = f64::max(i0, 0.0) as usize;
+                    let i1 = f64::min(i1, self.dims[0] as f64 - 1.0);
+                    if i1 < 0.0 {
+                        continue;
+                    }
+                    for i in i0..=i1 as usize {
+                        let cell = self.cell([i, j, k]);
+                        self.cells[cell] = true;
+                    }
+                }
+            }
+        }
+        for tri in triangles {
+            for p in sample_triangle(tri, self.voxel_size) {
+                if let Some(idx) = self.voxel_index(p) {
+                    let cell = self.cell(idx);
+                    self.cells[cell] = true;
+                }
+            }
+        }
+    }
+
+    // the occupied voxels adjacent to the empty voxels
+    fn boundary_voxels(&self) -> Vec<[usize; 3]> {
+        let mut res = Vec::new();
+        for k in 0..self.dims[2] {
+            for j in 0..self.dims[1] {
+                for i in 0..self.dims[0] {
+                    if !self.cells[self.cell([i, j, k])] {
+                        continue;
+                    }
+                    let idx = [i as isize, j as isize, k as isize];
+                    let boundary = (0..3).any(|a| {
+                        let (mut prev, mut next) = (idx, idx);
+                        prev[a] -= 1;
+                        next[a] += 1;
+                        !self.occupied(prev) || !self.occupied(next)
+                    });
+                    if boundary {
+                        res.push([i, j, k]);
+                    }
+                }
+            }
+        }
+        res
+    }
+
+    /// Returns the minimum distance between the occupied voxels and the closed polygon mesh.
+    ///
+    /// The distance is measured from the centers of the boundary voxels and decreased by the
+    /// half of the diagonal of a voxel, so it does not exceed the exact clearance.
+    /// Returns `0.0` if the mesh crosses the occupied voxels or contains them.
+    pub fn clearance(&self, mesh: &PolygonMesh) -> f64 {
+        let triangles = mesh_triangles(mesh, Matrix4::identity());
+        let intersect = triangles.iter().any(|tri| {
+            sample_triangle(tri, self.voxel_size)
+                .into_iter()
+                .any(|p| self.contains(p))
+        });
+        if intersect {
+            return 0.0;
+        }
+        let boundary = self.boundary_voxels();
+        let contained = boundary.first().map_or(false, |idx| {
+            let p = self.center(*idx);
+            let crossings = ray_crossings(&triangles, p[1], p[2]);
+            crossings.iter().filter(|x| **x > p[0]).count() % 2 == 1
+        });
+        if contained {
+            return 0.0;
+        }
+        let half_diagonal = self.voxel_size * f64::sqrt(3.0) / 2.0;
+        let distance = boundary
+            .into_iter()
+            .flat_map(|idx| {
+                let p = self.center(idx);
+                triangles.iter().map(move |tri| triangle_distance(tri, p))
+            })
+            .fold(f64::INFINITY, f64::min);
+        f64::max(distance - half_diagonal, 0.0)
+    }
+
+    /// Returns the polygon mesh of the boundary of the occupied voxels.
+    pub fn to_polygon_mesh(&self) -> PolygonMesh {
+        let normals = vec![
+            Vector3::unit_x(),
+            Vector3::unit_y(),
+            Vector3::unit_z(),
+            -Vector3::unit_x(),
+            -Vector3::unit_y(),
+            -Vector3::unit_z(),
+        ];
+        let mut positions = Vec::new();
+        let mut map = HashMap::<[usize; 3], usize>::new();
+        let mut faces = Faces::default();
+        for idx in self.boundary_voxels() {
+            let iidx = [idx[0] as isize, idx[1] as isize, idx[2] as isize];
+            for a in 0..3 {
+                let (u, v) = ((a + 1) % 3, (a + 2) % 3);
+                for &positive in &[true, false] {
+                    let mut neighbor = iidx;
+                    neighbor[a] += if positive { 1 } else { -1 };
+                    if self.occupied(neighbor) {
+                        continue;
+                    }
+                    let mut corners: Vec<[usize; 3]> = [(0, 0), (1, 0), (1, 1), (0, 1)]
+                        .iter()
+                        .map(|(du, dv)| {
+                            let mut corner = idx;
+                            corner[a] += positive as usize;
+                            corner[u] += du;
+                            corner[v] += dv;
+                            corner
+                        })
+                        .collect();
+                    if !positive {
+                        corners.reverse();
+                    }
+                    let normal = if positive { a } else { a + 3 };
+                    let face: Vec<(usize, Option<usize>, Option<usize>)> = corners
+                        .into_iter()
+                        .map(|corner| {
+                            let pos = *map.entry(corner).or_insert_with(|| {
+                                positions.push(
+                                    self.origin
+                                        + Vector3::new(
+                                            corner[0] as f64,
+                                            corner[1] as f64,
+                                            corner[2] as f64,
+                                        ) * self.voxel_size,
+                                );
+                                positions.len() - 1
+                            });
+                            (pos, None, Some(normal))
+                        })
+                        .collect();
+                    faces.push(face);
+                }
+            }
+        }
+        PolygonMesh::new(positions, Vec::new(), normals, faces)
+    }
+}
+
+/// Returns the volume swept by the closed polygon mesh `mesh` moved by the transforms `path`.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::swept_volume::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+/// let mesh = tessellation::tessellate_solid(&cube, 0.01);
+///
+/// // the cube moves by 0.5 along the x-axis
+/// let path: Vec<Matrix4> = (0..=10)
+///     .map(|i| Matrix4::from_translation(Vector3::new(0.05 * i as f64, 0.0, 0.0)))
+///     .collect();
+/// let option = SweptVolumeOption {
+///     voxel_size: 0.1,
+///     ..Default::default()
+/// };
+/// let volume = swept_volume(&mesh, &path, &option);
+/// assert!(volume.contains(Point3::new(1.45, 0.5, 0.5)));
+/// assert!(!volume.contains(Point3::new(1.75, 0.5, 0.5)));
+/// // The voxels on the surface are also occupied.
+/// assert!(volume.volume() >= 1.5 && volume.volume() < 2.4);
+///
+/// let polygon = volume.to_polygon_mesh();
+/// assert_eq!(polygon.quad_faces().len(), polygon.face_iter().count());
+/// ```
+pub fn swept_volume(
+    mesh: &PolygonMesh,
+    path: &[Matrix4],
+    option: &SweptVolumeOption,
+) -> VoxelVolume {
+    let triangles = mesh_triangles(mesh, Matrix4::identity());
+    sweep_triangles(&triangles, path, option)
+}
+
+fn sweep_triangles(
+    triangles: &[[Point3; 3]],
+    path: &[Matrix4],
+    option: &SweptVolumeOption,
+) -> VoxelVolume {
+    let moved: Vec<Vec<[Point3; 3]>> = path
+        .iter()
+        .map(|mat| {
+            triangles
+                .iter()
+                .map(|tri| {
+                    [
+                        mat.transform_point(tri[0]),
+                        mat.transform_point(tri[1]),
+                        mat.transform_point(tri[2]),
+                    ]
+                })
+                .collect()
+        })
+        .collect();
+    let bdd_box: BoundingBox<Point3> = moved.iter().flatten().flatten().collect();
+    let mut volume = VoxelVolume::new(&bdd_box, option.voxel_size);
+    if !bdd_box.is_empty() {
+        moved.iter().for_each(|triangles| volume.fill(triangles));
+    }
+    volume
+}
+
+/// Returns the volume swept by the component `moving` of `assembly` in the motion of
+/// `mechanism` from the joint vector `from` to `to`, interpolated linearly.
+///
+/// # Errors
+/// - Returns `Error::ElementNotFound` if `moving` is out of range.
+/// - Returns the errors of [`Mechanism::pose`] if the joints or the joint vectors are invalid.
+///
+/// [`Mechanism::pose`]: ../kinematics/struct.Mechanism.html#method.pose
+pub fn motion_swept_volume(
+    assembly: &Assembly,
+    mechanism: &Mechanism,
+    moving: usize,
+    from: &[f64],
+    to: &[f64],
+    option: &SweptVolumeOption,
+) -> Result<VoxelVolume> {
+    let poses = motion_poses(assembly, mechanism, from, to, option.steps)?;
+    let triangles = component_triangles(assembly, moving, option.tolerance)?;
+    let path: Vec<Matrix4> = poses.iter().map(|pose| pose[moving]).collect();
+    Ok(sweep_triangles(&triangles, &path, option))
+}
+
+/// Returns the clearances between the volume swept by the component `moving` and the static
+/// components, the components which do not move during the motion.
+/// cf. [`motion_swept_volume`](./fn.motion_swept_volume.html)
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::assembly::*;
+/// use truck_modeling::kinematics::*;
+/// use truck_modeling::swept_volume::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let block = Part::new("block", builder::tsweep(&f, Vector3::unit_z()));
+///
+/// // the slider in [0, 1]^3 and the stopper in [2, 3] x [0, 1] x [0, 1]
+/// let mut assembly = Assembly::new("slide");
+/// assembly.add_part(&block, Matrix4::identity());
+/// assembly.add_part(&block, Matrix4::from_translation(Vector3::new(2.0, 0.0, 0.0)));
+/// let mut mechanism = Mechanism::new();
+/// mechanism.add_joint(Joint::prismatic(None, 0, Vector3::unit_x()));
+///
+/// let option = SweptVolumeOption {
+///     voxel_size: 0.1,
+///     steps: 10,
+///     ..Default::default()
+/// };
+/// let reports =
+///     motion_clearances(&assembly, &mechanism, 0, &[0.0], &[0.5], &option).unwrap();
+/// assert_eq!(reports.len(), 1);
+/// assert_eq!(reports[0].component, 1);
+/// assert!(reports[0].clearance > 0.3 && reports[0].clearance <= 0.5);
+///
+/// let reports =
+///     motion_clearances(&assembly, &mechanism, 0, &[0.0], &[1.5], &option).unwrap();
+/// assert!(reports[0].interferes());
+/// ```
+pub fn motion_clearances(
+    assembly: &Assembly,
+    mechanism: &Mechanism,
+    moving: usize,
+    from: &[f64],
+    to: &[f64],
+    option: &SweptVolumeOption,
+) -> Result<Vec<ClearanceReport>> {
+    let poses = motion_poses(assembly, mechanism, from, to, option.steps)?;
+    let triangles = component_triangles(assembly, moving, option.tolerance)?;
+    let path: Vec<Matrix4> = poses.iter().map(|pose| pose[moving]).collect();
+    let volume = sweep_triangles(&triangles, &path, option);
+    let mut res = Vec::new();
+    for component in 0..assembly.components.len() {
+        let fixed = poses
+            .iter()
+            .all(|pose| pose[component].near(&poses[0][component]));
+        if component == moving || !fixed {
+            continue;
+        }
+        let triangles = component_triangles(assembly, component, option.tolerance)?;
+        let mat = poses[0][component];
+        let positions: Vec<Point3> = triangles
+            .iter()
+            .flatten()
+            .map(|p| mat.transform_point(*p))
+            .collect();
+        let faces: Vec<[usize; 3]> = (0..triangles.len())
+            .map(|i| [3 * i, 3 * i + 1, 3 * i + 2])
+            .collect();
+        let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), Faces::from_iter(&faces));
+        res.push(ClearanceReport {
+            component,
+            clearance: volume.clearance(&mesh),
+        });
+    }
+    Ok(res)
+}
+
+fn motion_poses(
+    assembly: &Assembly,
+    mechanism: &Mechanism,
+    from: &[f64],
+    to: &[f64],
+    steps: usize,
+) -> Result<Vec<Vec<Matrix4>>> {
+    let steps = usize::max(steps, 1);
+    (0..=steps)
+        .map(|i| {
+            let values = mechanism.interpolate(from, to, i as f64 / steps as f64);
+            mechanism.pose(assembly, &values)
+        })
+        .collect()
+}
+
+// the triangles of the component in the local coordinate of the component
+fn component_triangles(assembly: &Assembly, idx: usize, tol: f64) -> Result<Vec<[Point3; 3]>> {
+    let component = assembly.components.get(idx).ok_or(Error::ElementNotFound)?;
+    let parts = match &component.instance {
+        Instance::Part(part) => vec![(part.clone(), Matrix4::identity())],
+        Instance::Assembly(assembly) => assembly.placed_parts(),
+    };
+    Ok(parts
+        .into_iter()
+        .flat_map(|(part, mat)| mesh_triangles(&tessellate_solid(&part.solid, tol), mat))
+        .collect())
+}
+
+fn mesh_triangles(mesh: &PolygonMesh, mat: Matrix4) -> Vec<[Point3; 3]> {
+    let positions = mesh.positions();
+    mesh.face_iter()
+        .flat_map(|face| {
+            (2..face.len()).map(move |i| {
+                [
+                    mat.transform_point(positions[face[0].pos]),
+                    mat.transform_point(positions[face[i - 1].pos]),
+                    mat.transform_point(positions[face[i].pos]),
+                ]
+            })
+        })
+        .collect()
+}
+
+// the sorted x-coordinates of the intersections of the triangles and the line parallel to
+// the x-axis through `(y, z)`. The duplicated intersections on the common edges are merged.
+fn ray_crossings(triangles: &[[Point3; 3]], y: f64, z: f64) -> Vec<f64> {
+    let mut res: Vec<f64> = triangles
+        .iter()
+        .filter_map(|[a, b, c]| {
+            let det = (b[1] - a[1]) * (c[2] - a[2]) - (c[1] - a[1]) * (b[2] - a[2]);
+            if det.abs() < TOLERANCE * TOLERANCE {
+                return None;
+            }
+            let l1 = ((y - a[1]) * (c[2] - a[2]) - (c[1] - a[1]) * (z - a[2])) / det;
+            let l2 = ((b[1] - a[1]) * (z - a[2]) - (y - a[1]) * (b[2] - a[2])) / det;
+            let l0 = 1.0 - l1 - l2;
+            match l0 >= 0.0 && l1 >= 0.0 && l2 >= 0.0 {
+                true => Some(l0 * a[0] + l1 * b[0] + l2 * c[0]),
+                false => None,
+            }
+        })
+        .collect();
+    res.sort_by(|x, y| x.partial_cmp(y).unwrap());
+    res.dedup_by(|x, y| f64::abs(*x - *y) < TOLERANCE);
+    res
+}
+
+// the points on the triangle whose intervals are smaller than `interval`
+fn sample_triangle(tri: &[Point3; 3], interval: f64) -> Vec<Point3> {
+    let length = f64::max(
+        f64::max(tri[0].distance(tri[1]), tri[1].distance(tri[2])),
+        tri[2].distance(tri[0]),
+    );
+    let n = (length / interval).ceil() as usize + 1;
+    (0..=n)
+        .flat_map(|i| (0..=n - i).map(move |j| (i, j)))
+        .map(|(i, j)| {
+            let (s, t) = (i as f64 / n as f64, j as f64 / n as f64);
+            tri[0] + (tri[1] - tri[0]) * s + (tri[2] - tri[0]) * t
+        })
+        .collect()
+}
+
+// the distance between the point and the triangle
+fn triangle_distance(tri: &[Point3; 3], p: Point3) -> f64 {
+    let (a, b, c) = (tri[0], tri[1], tri[2]);
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return p.distance(a);
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return p.distance(b);
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return p.distance(a + ab * (d1 / (d1 - d3)));
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return p.distance(c);
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return p.distance(a + ac * (d2 / (d2 - d6)));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return p.distance(b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6))));
+    }
+    let denom = 1.0 / (va + vb + vc);
+    p.distance(a + ab * (vb * denom) + ac * (vc * denom))
+}
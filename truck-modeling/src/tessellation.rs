@@ -0,0 +1,915 @@
+use crate::face_domain::FaceDomain;
+use crate::*;
+use std::collections::HashSet;
+use truck_polymesh::tetrahedral::{self, TetrahedralMesh, TetrahedralizationOption};
+use truck_polymesh::{Faces, PolygonMesh};
+
+/// the maximum number of the refinements of the boundaries
+const MAX_REFINEMENT: usize = 8;
+/// the maximum number of the rounds of the adaptive refinement
+const MAX_ADAPTIVE_ROUNDS: usize = 16;
+/// the number of the vertices of a face by which the adaptive refinement stops
+const MAX_ADAPTIVE_VERTICES: usize = 10_000;
+
+/// Configures of the adaptive tessellation by [`tessellate_face_adaptive`].
+///
+/// The triangles are refined until all criteria are satisfied, so the flat regions get
+/// a few large triangles and the highly curved regions get many small ones.
+///
+/// [`tessellate_face_adaptive`]: ./fn.tessellate_face_adaptive.html
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TessellationOptions {
+    /// the maximum distance between each triangle and the surface, which is also the tolerance
+    /// of the polyline approximations of the boundaries. Default is `0.01`.
+    pub chord_tolerance: f64,
+    /// the maximum angle between the normals of the surface at the vertices of each triangle.
+    /// Default is `Rad(PI / 8.0)`.
+    pub angle_tolerance: Rad<f64>,
+    /// the maximum length of the edges of the triangles. Default is `f64::INFINITY`.
+    pub max_edge_length: f64,
+}
+
+impl Default for TessellationOptions {
+    #[inline(always)]
+    fn default() -> TessellationOptions {
+        TessellationOptions {
+            chord_tolerance: 0.01,
+            angle_tolerance: Rad(std::f64::consts::PI / 8.0),
+            max_edge_length: f64::INFINITY,
+        }
+    }
+}
+
+/// Tessellates the face into a triangle mesh.
+///
+/// The parameter domain of the face is divided by the parameter division of the surface,
+/// and triangulated by the Delaunay triangulation conforming the boundary polylines.
+/// The mesh has positions, uv coordinates and normals, and the triangles are
+/// directed to the normals of the oriented surface.
+/// # Arguments
+/// * `face` - the face to be tessellated
+/// * `tol` - the tolerance of the polyline approximations of the boundaries and the surface
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let face = builder::tsweep(&e, Vector3::unit_y());
+/// let mesh = tessellation::tessellate_face(&face, 0.01);
+/// let area: f64 = mesh
+///     .tri_faces()
+///     .iter()
+///     .map(|tri| {
+///         let p = mesh.positions();
+///         (p[tri[1].pos] - p[tri[0].pos]).cross(p[tri[2].pos] - p[tri[0].pos])[2] / 2.0
+///     })
+///     .sum();
+/// assert_near!(area, 1.0);
+/// ```
+pub fn tessellate_face(face: &Face, tol: f64) -> PolygonMesh {
+    let domain = FaceDomain::new(face, tol);
+    DomainTriangulation::new(&domain, tol).polygon_mesh(&domain)
+}
+
+/// Tessellates the face adaptively by the curvature of the surface.
+///
+/// Starting from the triangulation of the boundaries, the triangles deviating from
+/// the surface more than `options.chord_tolerance`, the ones whose normals at the vertices
+/// differ more than `options.angle_tolerance`, and the ones with the edges longer than
+/// `options.max_edge_length` are refined by inserting their centers. The attributes and
+/// the orientation of the mesh are the same as the ones of [`tessellate_face`].
+///
+/// [`tessellate_face`]: ./fn.tessellate_face.html
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use tessellation::TessellationOptions;
+/// use std::f64::consts::PI;
+/// let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+/// let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(2.0 * PI));
+/// let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+/// let cylinder = builder::tsweep(&disk, Vector3::new(0.0, 0.0, 2.0));
+///
+/// // The flat disks get only the triangles between the boundary points.
+/// let options = TessellationOptions::default();
+/// let mesh = tessellation::tessellate_face_adaptive(&cylinder.boundaries()[0][0], &options);
+/// let num_boundary_points = mesh.positions().len();
+/// assert_eq!(mesh.tri_faces().len(), num_boundary_points - 2);
+///
+/// // The longer edges are divided.
+/// let options = TessellationOptions {
+///     max_edge_length: 0.1,
+///     ..Default::default()
+/// };
+/// let mesh = tessellation::tessellate_solid_adaptive(&cylinder, &options);
+/// for tri in mesh.tri_faces() {
+///     let p = mesh.positions();
+///     for k in 0..3 {
+///         assert!(p[tri[k].pos].distance(p[tri[(k + 1) % 3].pos]) <= 0.1 + 1.0e-6);
+///     }
+/// }
+/// let volume: f64 = mesh
+///     .tri_faces()
+///     .iter()
+///     .map(|tri| {
+///         let p = mesh.positions();
+///         let (a, b, c) = (p[tri[0].pos], p[tri[1].pos], p[tri[2].pos]);
+///         a.to_vec().dot(b.to_vec().cross(c.to_vec())) / 6.0
+///     })
+///     .sum();
+/// assert!((volume - 2.0 * PI).abs() < 0.05, "{}", volume);
+/// ```
+pub fn tessellate_face_adaptive(face: &Face, options: &TessellationOptions) -> PolygonMesh {
+    let domain = FaceDomain::new(face, options.chord_tolerance);
+    DomainTriangulation::adaptive(&domain, options).polygon_mesh(&domain)
+}
+
+/// Tessellates all faces of the shell adaptively, and merges the meshes.
+/// cf. [`tessellate_face_adaptive`](./fn.tessellate_face_adaptive.html)
+pub fn tessellate_shell_adaptive(shell: &Shell, options: &TessellationOptions) -> PolygonMesh {
+    shell.iter().fold(PolygonMesh::default(), |mut mesh, face| {
+        mesh.merge(tessellate_face_adaptive(face, options));
+        mesh
+    })
+}
+
+/// Tessellates all faces of the solid adaptively, and merges the meshes.
+/// cf. [`tessellate_face_adaptive`](./fn.tessellate_face_adaptive.html)
+pub fn tessellate_solid_adaptive(solid: &Solid, options: &TessellationOptions) -> PolygonMesh {
+    solid
+        .boundaries()
+        .iter()
+        .fold(PolygonMesh::default(), |mut mesh, shell| {
+            mesh.merge(tessellate_shell_adaptive(shell, options));
+            mesh
+        })
+}
+
+/// A triangle of the tessellation, passed to the callback of
+/// [`tessellate_with`](./fn.tessellate_with.html).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TessellatedTriangle {
+    /// the positions of the vertices
+    pub positions: [Point3; 3],
+    /// the parameters of the vertices on the oriented surface
+    pub uv_coords: [Vector2; 3],
+    /// the normals of the oriented surface at the vertices
+    pub normals: [Vector3; 3],
+}
+
+/// Tessellates the face, and calls `callback` for each triangle instead of collecting them
+/// into a `PolygonMesh`.
+///
+/// The triangles are the same as the ones of [`tessellate_face`], and are directed to
+/// the normals of the oriented surface. The vertices are not shared, so this is suitable for
+/// streaming the triangles to the exporters or the GPU buffers.
+///
+/// [`tessellate_face`]: ./fn.tessellate_face.html
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let face = builder::tsweep(&e, Vector3::unit_y());
+///
+/// let (mut count, mut area) = (0, 0.0);
+/// tessellation::tessellate_with(&face, 0.01, |tri| {
+///     let [p, q, r] = tri.positions;
+///     area += (q - p).cross(r - p)[2] / 2.0;
+///     assert!(tri.normals.iter().all(|n| n.near(&Vector3::unit_z())));
+///     count += 1;
+/// });
+/// assert_near!(area, 1.0);
+/// assert_eq!(count, tessellation::tessellate_face(&face, 0.01).tri_faces().len());
+/// ```
+pub fn tessellate_with<F: FnMut(&TessellatedTriangle)>(face: &Face, tol: f64, mut callback: F) {
+    let domain = FaceDomain::new(face, tol);
+    let triangulation = DomainTriangulation::new(&domain, tol);
+    let surface = &domain.surface;
+    // the normals of the vertices except the super triangle
+    let normals: Vec<Vector3> = triangulation.vertices[3..]
+        .iter()
+        .map(|(uv, _)| surface.normal(uv[0], uv[1]))
+        .collect();
+    triangulation.face_triangles(&domain).for_each(|tri| {
+        let vertex = |k: usize| triangulation.vertices[tri[k]];
+        callback(&TessellatedTriangle {
+            positions: [vertex(0).1, vertex(1).1, vertex(2).1],
+            uv_coords: [vertex(0).0, vertex(1).0, vertex(2).0],
+            normals: [normals[tri[0] - 3], normals[tri[1] - 3], normals[tri[2] - 3]],
+        })
+    });
+}
+
+/// Tessellates all faces of the shell, and merges the meshes.
+///
+/// The meshes of the faces are not welded.
+/// Use `OptimizingFilter::put_together_same_attrs` for welding the vertices.
+pub fn tessellate_shell(shell: &Shell, tol: f64) -> PolygonMesh {
+    shell.iter().fold(PolygonMesh::default(), |mut mesh, face| {
+        mesh.merge(tessellate_face(face, tol));
+        mesh
+    })
+}
+
+/// Tessellates all faces of the solid, and merges the meshes.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use std::f64::consts::PI;
+/// let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+/// let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(2.0 * PI));
+/// let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+/// let cylinder = builder::tsweep(&disk, Vector3::new(0.0, 0.0, 2.0));
+/// let mesh = tessellation::tessellate_solid(&cylinder, 0.01);
+///
+/// // the volume by the divergence theorem
+/// let volume: f64 = mesh
+///     .tri_faces()
+///     .iter()
+///     .map(|tri| {
+///         let p = mesh.positions();
+///         let (a, b, c) = (p[tri[0].pos], p[tri[1].pos], p[tri[2].pos]);
+///         a.to_vec().dot(b.to_vec().cross(c.to_vec())) / 6.0
+///     })
+///     .sum();
+/// assert!((volume - 2.0 * PI).abs() < 0.05, "{}", volume);
+/// ```
+pub fn tessellate_solid(solid: &Solid, tol: f64) -> PolygonMesh {
+    solid
+        .boundaries()
+        .iter()
+        .fold(PolygonMesh::default(), |mut mesh, shell| {
+            mesh.merge(tessellate_shell(shell, tol));
+            mesh
+        })
+}
+
+/// Tessellates the solid and fills the interior by tetrahedra.
+/// cf. [`tetrahedralize`](../../truck_polymesh/tetrahedral/fn.tetrahedralize.html)
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_polymesh::tetrahedral::TetrahedralizationOption;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let option = TetrahedralizationOption {
+///     max_edge_length: 0.5,
+///     ..Default::default()
+/// };
+/// let tet_mesh = tessellation::tetrahedralize_solid(&cube, 0.01, &option);
+/// assert!((tet_mesh.volume() - 1.0).abs() < 1.0e-6);
+/// ```
+pub fn tetrahedralize_solid(
+    solid: &Solid,
+    tol: f64,
+    option: &TetrahedralizationOption,
+) -> TetrahedralMesh {
+    tetrahedral::tetrahedralize(&tessellate_solid(solid, tol), option)
+}
+
+/// The parameter domain of a face and its tessellation in the parameter space of the surface,
+/// for debugging the trimming and the tessellation.
+///
+/// The domain is created by [`parameter_domain`](./fn.parameter_domain.html), and drawn to
+/// an image by [`to_svg`](#method.to_svg).
+#[derive(Clone, Debug)]
+pub struct ParameterDomain {
+    /// the parameter range of the oriented surface of the face
+    pub parameter_range: ((f64, f64), (f64, f64)),
+    /// the trimming loops, i.e. the polylines of the boundary wires in the parameter space.
+    ///
+    /// The last point of each loop is the first one, which is shifted by the period if the loop
+    /// winds around the periodic surface.
+    pub boundaries: Vec<Vec<Vector2>>,
+    /// the parameters of the vertices of the boundary wires, i.e. the end points of
+    /// the parameter curves of the edges
+    pub vertices: Vec<Vector2>,
+    /// the triangles of the tessellation by [`tessellate_face`](./fn.tessellate_face.html)
+    /// in the parameter space
+    pub triangles: Vec<[Vector2; 3]>,
+}
+
+/// Returns the parameter domain of the face, and its tessellation by `tessellate_face` with
+/// the tolerance `tol`.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let face = builder::tsweep(&e, Vector3::unit_y());
+/// let domain = tessellation::parameter_domain(&face, 0.01);
+/// assert_eq!(domain.boundaries.len(), 1);
+/// assert_eq!(domain.vertices.len(), 4);
+///
+/// // The triangles cover the domain.
+/// let area = |a: Vector2, b: Vector2, c: Vector2| (b - a).perp_dot(c - a) / 2.0;
+/// let loop_area: f64 = domain.boundaries[0]
+///     .windows(2)
+///     .map(|w| area(Vector2::zero(), w[0], w[1]))
+///     .sum();
+/// let triangles_area: f64 = domain
+///     .triangles
+///     .iter()
+///     .map(|tri| area(tri[0], tri[1], tri[2]))
+///     .sum();
+/// assert_near!(loop_area, triangles_area);
+///
+/// let svg = domain.to_svg(256);
+/// assert!(svg.starts_with("<svg"));
+/// assert_eq!(svg.matches("<polygon").count(), domain.triangles.len());
+/// ```
+pub fn parameter_domain(face: &Face, tol: f64) -> ParameterDomain {
+    let domain = FaceDomain::new(face, tol);
+    let triangulation = DomainTriangulation::new(&domain, tol);
+    let triangles = triangulation
+        .face_triangles(&domain)
+        .map(|tri| {
+            [
+                triangulation.vertices[tri[0]].0,
+                triangulation.vertices[tri[1]].0,
+                triangulation.vertices[tri[2]].0,
+            ]
+        })
+        .collect();
+    // The polyline of each edge starts at the front vertex, and has the points of the parameter
+    // division except the last one, in the same way as `FaceDomain`.
+    let vertices = face
+        .boundaries()
+        .iter()
+        .zip(&domain.boundary_uvs)
+        .flat_map(|(wire, uvs)| {
+            let mut counter = 0;
+            wire.iter()
+                .map(|edge| {
+                    let len = edge.oriented_curve().parameter_division(tol).len();
+                    counter += len - 1;
+                    counter - (len - 1)
+                })
+                .filter_map(|idx| uvs.get(idx).copied())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    ParameterDomain {
+        parameter_range: domain.surface.parameter_range(),
+        boundaries: domain.closed_boundary_uvs(),
+        vertices,
+        triangles,
+    }
+}
+
+/// Returns the oriented surface of the face trimmed by the boundaries in the parameter space,
+/// which are approximated by the polylines within the tolerance `tol`.
+///
+/// Returns `None` if a boundary winds around the periodic surface, since such a boundary is not
+/// closed in the parameter space.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::new(0.0, 0.0, 0.0));
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let face = builder::tsweep(&e, Vector3::unit_y());
+/// let surface = tessellation::trimmed_surface(&face, 0.01).unwrap();
+/// assert_eq!(surface.boundaries().len(), 1);
+///
+/// // the center of the boundary is in the domain
+/// let points = surface.boundaries()[0].points();
+/// let len = points.len() - 1;
+/// let center = points[1..]
+///     .iter()
+///     .fold(Point2::origin(), |sum, pt| sum + pt.to_vec() / len as f64);
+/// assert!(surface.contains_parameter(center, 0.01));
+/// assert_near!(surface.subs(center[0], center[1]), Point3::new(0.5, 0.5, 0.0));
+/// ```
+pub fn trimmed_surface(
+    face: &Face,
+    tol: f64,
+) -> Option<TrimmedSurface<Surface, PolylineCurve<Point2>>> {
+    let domain = FaceDomain::new(face, tol);
+    let boundaries = domain
+        .closed_boundary_uvs()
+        .into_iter()
+        .map(|polygon| PolylineCurve::new(polygon.into_iter().map(Point2::from_vec).collect()))
+        .collect();
+    TrimmedSurface::try_new(domain.surface, boundaries).ok()
+}
+
+impl ParameterDomain {
+    /// Draws the domain to the SVG image, whose longer side is `size` pixels.
+    ///
+    /// The u-axis is directed to the right, and the v-axis is directed to the top.
+    /// - The parameter range of the surface is the dashed rectangle, if it is finite.
+    /// - The triangles of the tessellation are gray.
+    /// - The counter-clockwise loops are blue, and the clockwise loops, i.e. the holes, are red.
+    /// - The vertices are the black dots.
+    pub fn to_svg(&self, size: u32) -> String {
+        const MARGIN: f64 = 10.0;
+        let ((u0, u1), (v0, v1)) = self.parameter_range;
+        let range_finite = [u0, u1, v0, v1].iter().all(|x| x.is_finite());
+        let mut bdd: BoundingBox<Vector2> = self
+            .boundaries
+            .iter()
+            .flatten()
+            .chain(self.triangles.iter().flatten())
+            .collect();
+        if range_finite {
+            bdd.push(&Vector2::new(u0, v0));
+            bdd.push(&Vector2::new(u1, v1));
+        }
+        if bdd.is_empty() {
+            bdd.push(&Vector2::zero());
+        }
+        let (min, max) = (*bdd.min(), *bdd.max());
+        let extent = match bdd.size().so_small() {
+            true => 1.0,
+            false => bdd.size(),
+        };
+        let scale = f64::max(size as f64 - 2.0 * MARGIN, 1.0) / extent;
+        let width = (max[0] - min[0]) * scale + 2.0 * MARGIN;
+        let height = (max[1] - min[1]) * scale + 2.0 * MARGIN;
+        let coord = |uv: &Vector2| {
+            (
+                MARGIN + (uv[0] - min[0]) * scale,
+                MARGIN + (max[1] - uv[1]) * scale,
+            )
+        };
+        let points = |uvs: &[Vector2]| {
+            uvs.iter()
+                .map(|uv| {
+                    let (x, y) = coord(uv);
+                    format!("{:.3},{:.3}", x, y)
+                })
+                .collect::<Vec<_>>()
+                .join(" ")
+        };
+
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" \
+             viewBox=\"0 0 {:.3} {:.3}\">\n",
+            width.ceil(),
+            height.ceil(),
+            width,
+            height
+        );
+        svg += &format!(
+            "<rect width=\"{:.3}\" height=\"{:.3}\" fill=\"white\"/>\n",
+            width, height
+        );
+        if range_finite {
+            let (x0, y0) = coord(&Vector2::new(u0, v1));
+            let (x1, y1) = coord(&Vector2::new(u1, v0));
+            svg += &format!(
+                "<rect x=\"{:.3}\" y=\"{:.3}\" width=\"{:.3}\" height=\"{:.3}\" fill=\"none\" \
+                 stroke=\"gray\" stroke-dasharray=\"4 4\"/>\n",
+                x0,
+                y0,
+                x1 - x0,
+                y1 - y0
+            );
+        }
+        for tri in &self.triangles {
+            svg += &format!(
+                "<polygon points=\"{}\" fill=\"none\" stroke=\"silver\" stroke-width=\"0.5\"/>\n",
+                points(tri)
+            );
+        }
+        for boundary in &self.boundaries {
+            let area: f64 = boundary.windows(2).map(|w| w[0].perp_dot(w[1])).sum();
+            let color = match area >= 0.0 {
+                true => "blue",
+                false => "red",
+            };
+            svg += &format!(
+                "<polyline points=\"{}\" fill=\"none\" stroke=\"{}\" stroke-width=\"1.5\"/>\n",
+                points(boundary),
+                color
+            );
+        }
+        for vertex in &self.vertices {
+            let (x, y) = coord(vertex);
+            svg += &format!("<circle cx=\"{:.3}\" cy=\"{:.3}\" r=\"3\"/>\n", x, y);
+        }
+        svg + "</svg>\n"
+    }
+}
+
+/// Delaunay triangulation of the parameter domain of a face.
+/// The first three vertices are the ones of the super triangle.
+struct DomainTriangulation {
+    /// the parameters and the points of the vertices
+    vertices: Vec<(Vector2, Point3)>,
+    /// the counter-clockwise triangles
+    triangles: Vec<[usize; 3]>,
+    /// the scale of the parameter space approximating the metric of the surface
+    scale: Vector2,
+}
+
+impl DomainTriangulation {
+    fn new(domain: &FaceDomain, tol: f64) -> DomainTriangulation {
+        let (mut res, segments) = DomainTriangulation::with_boundaries(domain, f64::INFINITY);
+        res.insert_grid(domain, tol);
+        res.recover_boundaries(domain, segments);
+        res
+    }
+
+    /// Triangulates the boundaries, whose segments are divided so that the lengths in the space
+    /// are at most `max_edge_length`, and returns the segments of the boundaries.
+    fn with_boundaries(
+        domain: &FaceDomain,
+        max_edge_length: f64,
+    ) -> (DomainTriangulation, Vec<(usize, usize)>) {
+        let (min, max) = domain_range(domain);
+        let center = (min + max) / 2.0;
+        let length = |vec: Vector3| match vec.magnitude().so_small() {
+            true => 1.0,
+            false => vec.magnitude(),
+        };
+        let scale = Vector2::new(
+            length(domain.surface.uder(center[0], center[1])),
+            length(domain.surface.vder(center[0], center[1])),
+        );
+        let mut res = DomainTriangulation {
+            vertices: Vec::new(),
+            triangles: Vec::new(),
+            scale,
+        };
+        res.init_super_triangle(min, max);
+
+        let mut segments = Vec::new();
+        for (uvs, points) in domain.boundary_uvs.iter().zip(&domain.boundary_points) {
+            if uvs.is_empty() {
+                continue;
+            }
+            let mut indices: Vec<usize> = uvs
+                .iter()
+                .zip(points)
+                .map(|(uv, pt)| res.insert(*uv, *pt))
+                .collect();
+            let closing = domain.nearest_branch(uvs[0], uvs[uvs.len() - 1]);
+            match res.to_scaled(closing - uvs[0]).so_small() {
+                true => indices.push(indices[0]),
+                false => indices.push(res.insert(closing, points[0])),
+            }
+            for w in indices.windows(2).filter(|w| w[0] != w[1]) {
+                let (i, j) = (w[0], w[1]);
+                let ((uv0, p0), (uv1, p1)) = (res.vertices[i], res.vertices[j]);
+                let division = match max_edge_length.is_finite() {
+                    true => f64::ceil(p0.distance(p1) / max_edge_length).max(1.0) as usize,
+                    false => 1,
+                };
+                let mut prev = i;
+                for k in 1..division {
+                    let uv = uv0 + (uv1 - uv0) * (k as f64 / division as f64);
+                    let idx = res.insert(uv, domain.surface.subs(uv[0], uv[1]));
+                    segments.push((prev, idx));
+                    prev = idx;
+                }
+                segments.push((prev, j));
+            }
+        }
+        segments.retain(|(i, j)| i != j);
+        (res, segments)
+    }
+
+    /// Inserts the interior points of the grid by the parameter division of the surface.
+    fn insert_grid(&mut self, domain: &FaceDomain, tol: f64) {
+        let (min, max) = domain_range(domain);
+        let scale = self.scale;
+        let (udiv, vdiv) = domain.surface.parameter_division(tol);
+        let udiv = grid_lines(udiv, domain.periods.0, (min[0], max[0]));
+        let vdiv = grid_lines(vdiv, domain.periods.1, (min[1], max[1]));
+        let boundary_polylines: Vec<Vec<Vector2>> = domain.closed_boundary_uvs();
+        for i in 1..udiv.len().saturating_sub(1) {
+            for j in 1..vdiv.len().saturating_sub(1) {
+                let uv = Vector2::new(udiv[i], vdiv[j]);
+                let spacing = f64::min(
+                    (udiv[i + 1] - udiv[i - 1]) * scale[0],
+                    (vdiv[j + 1] - vdiv[j - 1]) * scale[1],
+                ) / 4.0;
+                let near_boundary = boundary_polylines
+                    .iter()
+                    .flat_map(|polyline| polyline.windows(2))
+                    .any(|w| self.segment_distance(uv, w[0], w[1]) < spacing);
+                if !near_boundary && domain.include(uv) {
+                    self.insert(uv, domain.surface.subs(uv[0], uv[1]));
+                }
+            }
+        }
+    }
+
+    /// Recovers the boundary segments by inserting the midpoints, and returns the divided segments.
+    fn recover_boundaries(
+        &mut self,
+        domain: &FaceDomain,
+        mut segments: Vec<(usize, usize)>,
+    ) -> Vec<(usize, usize)> {
+        for _ in 0..MAX_REFINEMENT {
+            let edges: HashSet<(usize, usize)> = self
+                .triangles
+                .iter()
+                .flat_map(|tri| (0..3).map(move |k| (tri[k], tri[(k + 1) % 3])))
+                .collect();
+            let mut refined = false;
+            segments = segments
+                .into_iter()
+                .flat_map(|(i, j)| {
+                    if edges.contains(&(i, j)) || edges.contains(&(j, i)) {
+                        return vec![(i, j)];
+                    }
+                    refined = true;
+                    let uv = (self.vertices[i].0 + self.vertices[j].0) / 2.0;
+                    let pt = domain.surface.subs(uv[0], uv[1]);
+                    let k = self.insert(uv, pt);
+                    vec![(i, k), (k, j)]
+                })
+                .collect();
+            if !refined {
+                break;
+            }
+        }
+        segments
+    }
+
+    /// Triangulates the domain adaptively by the criteria of `options`.
+    fn adaptive(domain: &FaceDomain, options: &TessellationOptions) -> DomainTriangulation {
+        let (mut res, segments) =
+            DomainTriangulation::with_boundaries(domain, options.max_edge_length);
+        let mut segments = res.recover_boundaries(domain, segments);
+        for _ in 0..MAX_ADAPTIVE_ROUNDS {
+            let len = res.vertices.len();
+            if len >= MAX_ADAPTIVE_VERTICES {
+                break;
+            }
+            let coarse: Vec<[usize; 3]> = res
+                .face_triangles(domain)
+                .filter(|tri| !res.is_fine(domain, *tri, options))
+                .collect();
+            coarse
+                .into_iter()
+                .for_each(|tri| res.refine(domain, tri, &mut segments));
+            segments = res.recover_boundaries(domain, segments);
+            if res.vertices.len() == len {
+                break;
+            }
+        }
+        res
+    }
+
+    /// Returns whether the triangle satisfies the criteria of `options`.
+    fn is_fine(&self, domain: &FaceDomain, tri: [usize; 3], options: &TessellationOptions) -> bool {
+        let surface = &domain.surface;
+        let vertices = [
+            self.vertices[tri[0]],
+            self.vertices[tri[1]],
+            self.vertices[tri[2]],
+        ];
+        let too_long = (0..3).any(|k| {
+            let (p, q) = (vertices[k].1, vertices[(k + 1) % 3].1);
+            p.distance(q) > options.max_edge_length
+        });
+        if too_long {
+            return false;
+        }
+        let normals = vertices.iter().map(|(uv, _)| surface.normal(uv[0], uv[1]));
+        let normals: Vec<Vector3> = normals.collect();
+        if (0..3).any(|k| normals[k].angle(normals[(k + 1) % 3]) > options.angle_tolerance) {
+            return false;
+        }
+        // the deviations at the center and at the midpoints of the edges
+        let [(a, p), (b, q), (c, r)] = vertices;
+        let normal = (q - p).cross(r - p);
+        let samples = [
+            ((a + b + c) / 3.0, Point3::centroid(&[p, q, r])),
+            ((a + b) / 2.0, p.midpoint(q)),
+            ((b + c) / 2.0, q.midpoint(r)),
+            ((c + a) / 2.0, r.midpoint(p)),
+        ];
+        samples.iter().all(|(uv, pt)| {
+            let diff = surface.subs(uv[0], uv[1]) - pt;
+            let deviation = match normal.so_small() {
+                true => diff.magnitude(),
+                false => diff.dot(normal.normalize()).abs(),
+            };
+            deviation <= options.chord_tolerance
+        })
+    }
+
+    /// Inserts the center of the triangle. If the center is near a boundary segment,
+    /// the segment is divided at its midpoint instead.
+    fn refine(&mut self, domain: &FaceDomain, tri: [usize; 3], segments: &mut Vec<(usize, usize)>) {
+        let uvs = [
+            self.vertices[tri[0]].0,
+            self.vertices[tri[1]].0,
+            self.vertices[tri[2]].0,
+        ];
+        let center = (uvs[0] + uvs[1] + uvs[2]) / 3.0;
+        let radius = uvs
+            .iter()
+            .map(|uv| self.to_scaled(uv - center).magnitude())
+            .fold(f64::INFINITY, f64::min);
+        let near_segment = segments.iter().position(|(i, j)| {
+            let (p, q) = (self.vertices[*i].0, self.vertices[*j].0);
+            self.segment_distance(center, p, q) < radius / 2.0
+        });
+        match near_segment {
+            Some(idx) => {
+                let (i, j) = segments[idx];
+                let uv = (self.vertices[i].0 + self.vertices[j].0) / 2.0;
+                let k = self.insert(uv, domain.surface.subs(uv[0], uv[1]));
+                if k != i && k != j {
+                    segments.splice(idx..=idx, vec![(i, k), (k, j)]);
+                }
+            }
+            None => {
+                if domain.include(center) {
+                    self.insert(center, domain.surface.subs(center[0], center[1]));
+                }
+            }
+        }
+    }
+
+    /// the mesh of the triangles in the face
+    fn polygon_mesh(&self, domain: &FaceDomain) -> PolygonMesh {
+        let vertices = &self.vertices[3..];
+        let positions = vertices.iter().map(|(_, pt)| *pt).collect();
+        let uv_coords = vertices.iter().map(|(uv, _)| *uv).collect();
+        let normals = vertices
+            .iter()
+            .map(|(uv, _)| domain.surface.normal(uv[0], uv[1]))
+            .collect();
+        let faces = Faces::from_iter(self.face_triangles(domain).map(|tri| {
+            [
+                [tri[0] - 3, tri[0] - 3, tri[0] - 3],
+                [tri[1] - 3, tri[1] - 3, tri[1] - 3],
+                [tri[2] - 3, tri[2] - 3, tri[2] - 3],
+            ]
+        }));
+        PolygonMesh::new(positions, uv_coords, normals, faces)
+    }
+
+    #[inline(always)]
+    /// the triangles in the face, which do not have the vertices of the super triangle
+    fn face_triangles<'a>(
+        &'a self,
+        domain: &'a FaceDomain,
+    ) -> impl Iterator<Item = [usize; 3]> + 'a {
+        self.triangles.iter().copied().filter(move |tri| {
+            if tri.iter().any(|i| *i < 3) {
+                return false;
+            }
+            let (a, b, c) = (
+                self.vertices[tri[0]].0,
+                self.vertices[tri[1]].0,
+                self.vertices[tri[2]].0,
+            );
+            domain.include((a + b + c) / 3.0)
+        })
+    }
+
+    fn to_scaled(&self, uv: Vector2) -> Vector2 {
+        Vector2::new(uv[0] * self.scale[0], uv[1] * self.scale[1])
+    }
+
+    /// the scaled distance between `uv` and the segment `p`-`q`
+    fn segment_distance(&self, uv: Vector2, p: Vector2, q: Vector2) -> f64 {
+        let (uv, p, q) = (self.to_scaled(uv), self.to_scaled(p), self.to_scaled(q));
+        let vec = q - p;
+        let t = match vec.so_small() {
+            true => 0.0,
+            false => f64::min(f64::max((uv - p).dot(vec) / vec.magnitude2(), 0.0), 1.0),
+        };
+        (uv - p - vec * t).magnitude()
+    }
+
+    fn init_super_triangle(&mut self, min: Vector2, max: Vector2) {
+        let center = (min + max) / 2.0;
+        let size = f64::max(
+            (max[0] - min[0]) * self.scale[0],
+            (max[1] - min[1]) * self.scale[1],
+        ) + 1.0;
+        let r = Vector2::new(size / self.scale[0], size / self.scale[1]) * 100.0;
+        let dummy = Point3::origin();
+        self.vertices = vec![
+            (center + Vector2::new(-r[0], -r[1]), dummy),
+            (center + Vector2::new(2.0 * r[0], -r[1]), dummy),
+            (center + Vector2::new(-r[0], 2.0 * r[1]), dummy),
+        ];
+        self.triangles = vec![[0, 1, 2]];
+    }
+
+    /// Inserts the vertex by the Bowyer-Watson algorithm, and returns its index.
+    /// If there is the same vertex, returns its index.
+    fn insert(&mut self, uv: Vector2, pt: Point3) -> usize {
+        let p = self.to_scaled(uv);
+        if let Some(idx) = self
+            .vertices
+            .iter()
+            .position(|(uv, _)| self.to_scaled(*uv).near(&p))
+        {
+            return idx;
+        }
+        let idx = self.vertices.len();
+        self.vertices.push((uv, pt));
+        let (bad, good): (Vec<[usize; 3]>, Vec<[usize; 3]>) =
+            self.triangles.iter().partition(|tri| {
+                let a = self.to_scaled(self.vertices[tri[0]].0);
+                let b = self.to_scaled(self.vertices[tri[1]].0);
+                let c = self.to_scaled(self.vertices[tri[2]].0);
+                in_circle(a, b, c, p)
+            });
+        let edges: HashSet<(usize, usize)> = bad
+            .iter()
+            .flat_map(|tri| (0..3).map(move |k| (tri[k], tri[(k + 1) % 3])))
+            .collect();
+        self.triangles = good;
+        self.triangles.extend(
+            edges
+                .iter()
+                .filter(|(i, j)| !edges.contains(&(*j, *i)))
+                .map(|(i, j)| [*i, *j, idx]),
+        );
+        idx
+    }
+}
+
+/// Returns the range of the parameters of the boundaries, or the parameter range of the surface
+/// if there are no boundaries.
+fn domain_range(domain: &FaceDomain) -> (Vector2, Vector2) {
+    let bdd: BoundingBox<Vector2> = domain.boundary_uvs.iter().flatten().collect();
+    match bdd.is_empty() {
+        true => {
+            let ((u0, u1), (v0, v1)) = domain.surface.parameter_range();
+            (Vector2::new(u0, v0), Vector2::new(u1, v1))
+        }
+        false => (*bdd.min(), *bdd.max()),
+    }
+}
+
+/// Returns whether `d` is in the circumcircle of the counter-clockwise triangle `a`, `b`, `c`.
+///
+/// The points on the circumcircle up to the rounding errors are regarded as outside,
+/// since the cavity of the Bowyer-Watson algorithm is broken by the co-circular points,
+/// e.g. the points on a circular boundary.
+fn in_circle(a: Vector2, b: Vector2, c: Vector2, d: Vector2) -> bool {
+    let (a, b, c) = (a - d, b - d, c - d);
+    let terms = [
+        a.magnitude2() * (b[0] * c[1] - b[1] * c[0]),
+        -b.magnitude2() * (a[0] * c[1] - a[1] * c[0]),
+        c.magnitude2() * (a[0] * b[1] - a[1] * b[0]),
+    ];
+    let det: f64 = terms.iter().sum();
+    let scale: f64 = terms.iter().map(|x| x.abs()).sum();
+    det > scale * 1.0e-10
+}
+
+/// Returns the parameters of the grid lines in `(min, max)`.
+/// The division is repeated by the period if the surface is closed.
+fn grid_lines(division: Vec<f64>, period: Option<f64>, (min, max): (f64, f64)) -> Vec<f64> {
+    let mut res: Vec<f64> = match (period, division.first()) {
+        (Some(period), Some(first)) => {
+            let k0 = ((min - first) / period).floor() as isize;
+            let k1 = ((max - first) / period).ceil() as isize;
+            (k0..=k1)
+                .flat_map(|k| {
+                    division
+                        .iter()
+                        .take(division.len() - 1)
+                        .map(move |t| t + k as f64 * period)
+                })
+                .collect()
+        }
+        _ => division,
+    };
+    res.retain(|t| min < *t && *t < max);
+    res.insert(0, min);
+    res.push(max);
+    res
+}
+
+#[test]
+fn tessellate_face_with_hole() {
+    use std::f64::consts::PI;
+    let v = builder::vertex(Point3::new(-2.0, -2.0, 0.0));
+    let e = builder::tsweep(&v, Vector3::new(4.0, 0.0, 0.0));
+    let square = builder::tsweep(&e, Vector3::new(0.0, 4.0, 0.0));
+    let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+    let circle = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(2.0 * PI));
+    let mut wires = square.boundaries();
+    wires.push(circle.inverse());
+    let face = builder::try_attach_plane(&wires).unwrap();
+    let mesh = tessellate_face(&face, 0.01);
+    let area: f64 = mesh
+        .tri_faces()
+        .iter()
+        .map(|tri| {
+            let p = mesh.positions();
+            let n = (p[tri[1].pos] - p[tri[0].pos]).cross(p[tri[2].pos] - p[tri[0].pos]);
+            assert!(n[2] > 0.0);
+            n[2] / 2.0
+        })
+        .sum();
+    assert!((area - (16.0 - PI)).abs() < 0.05, "{}", area);
+}
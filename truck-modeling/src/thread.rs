@@ -0,0 +1,407 @@
+use crate::assembly::Attributes;
+use crate::errors::Error;
+use crate::features::{face_info, SurfaceKind};
+use crate::*;
+use std::f64::consts::PI;
+
+/// the tolerance of the classification of the cylindrical faces
+const CYLINDER_TOLERANCE: f64 = 1.0e-4;
+/// the number of the sections of the loft of the groove per one turn
+const SECTIONS_PER_TURN: usize = 24;
+/// the number of the candidates of the start angle of the thread in a turn
+const START_CANDIDATES: usize = 72;
+/// `tan(30°)`, the slope of the flanks of the ISO metric threads
+const FLANK_SLOPE: f64 = 0.577_350_269_189_625_8;
+
+/// The kinds of the threads.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThreadKind {
+    /// The thread is only annotated on the face, and is displayed by the overlay.
+    Cosmetic,
+    /// The helical groove is modeled in addition to the annotation.
+    Modeled,
+}
+
+/// The specification of the ISO metric thread.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ThreadSpec {
+    /// the axial distance between the adjacent crests. The default is `1.0`.
+    pub pitch: f64,
+    /// the radial depth of the thread. If `None`, the depth of the ISO basic profile,
+    /// `5√3 / 16` times the pitch, is used. The default is `None`.
+    pub depth: Option<f64>,
+    /// the axial length of the thread. If `None`, the thread runs over the whole face.
+    /// The default is `None`.
+    pub length: Option<f64>,
+    /// If `true`, the thread is left-handed. The default is `false`.
+    pub left_handed: bool,
+    /// the kind of the thread. The default is `ThreadKind::Cosmetic`.
+    pub kind: ThreadKind,
+}
+
+impl Default for ThreadSpec {
+    #[inline(always)]
+    fn default() -> ThreadSpec {
+        ThreadSpec {
+            pitch: 1.0,
+            depth: None,
+            length: None,
+            left_handed: false,
+            kind: ThreadKind::Cosmetic,
+        }
+    }
+}
+
+/// The thread on a cylindrical face, created by [`builder::thread`].
+///
+/// [`builder::thread`]: ../builder/fn.thread.html
+#[derive(Clone, Debug)]
+pub struct Thread {
+    /// the threaded face
+    pub face: FaceID,
+    /// the center of the start of the thread on the axis
+    pub origin: Point3,
+    /// the unit direction of the axis, along which the thread advances
+    pub axis: Vector3,
+    /// the unit direction from the axis to the start of the helix of the crests
+    pub start: Vector3,
+    /// the major diameter, the one of the crests of the external threads
+    pub major_diameter: f64,
+    /// the minor diameter, the one of the crests of the internal threads
+    pub minor_diameter: f64,
+    /// the axial distance between the adjacent crests
+    pub pitch: f64,
+    /// the axial length
+    pub length: f64,
+    /// `true` if the thread is left-handed
+    pub left_handed: bool,
+    /// `true` if the thread is in a hole, and `false` if the thread is on a shaft
+    pub internal: bool,
+    /// the annotations of the thread: `thread`, `pitch`, `major_diameter`, `minor_diameter`,
+    /// `length` and `hand`
+    pub attributes: Attributes,
+    /// the helical groove of the modeled thread, which is subtracted from the solid
+    pub body: Option<Solid>,
+}
+
+impl Thread {
+    /// Returns the polylines of the cosmetic thread: the helix of the crests on the face and
+    /// the circles of the roots at both ends. The polylines are within `tol` from the exact
+    /// curves.
+    pub fn overlay(&self, tol: f64) -> Vec<Vec<Point3>> {
+        let (crest, root) = match self.internal {
+            true => (self.minor_diameter / 2.0, self.major_diameter / 2.0),
+            false => (self.major_diameter / 2.0, self.minor_diameter / 2.0),
+        };
+        let turns = self.length / self.pitch;
+        let hand = if self.left_handed { -1.0 } else { 1.0 };
+        let frame = frame(self.origin, self.axis, self.start);
+        let point = |radius: f64, angle: f64, height: f64| {
+            let local = Point3::new(radius * angle.cos(), radius * angle.sin(), height);
+            frame.transform_point(local)
+        };
+        let helix_division = division(crest, tol, 2.0 * PI * turns);
+        let helix = (0..=helix_division)
+            .map(|i| {
+                let t = i as f64 / helix_division as f64;
+                point(crest, hand * 2.0 * PI * turns * t, self.length * t)
+            })
+            .collect();
+        let circle_division = division(root, tol, 2.0 * PI);
+        let circle = |height: f64| -> Vec<Point3> {
+            (0..=circle_division)
+                .map(|i| point(root, 2.0 * PI * i as f64 / circle_division as f64, height))
+                .collect()
+        };
+        vec![helix, circle(0.0), circle(self.length)]
+    }
+}
+
+pub(super) fn thread(face: &Face, spec: &ThreadSpec) -> Result<Thread> {
+    let info = face_info(face, CYLINDER_TOLERANCE);
+    let (origin, axis, radius, convex) = match info.kind {
+        SurfaceKind::Cylinder {
+            origin,
+            axis,
+            radius,
+            convex,
+        } => (origin, axis, radius, convex),
+        _ => return Err(Error::UnsupportedGeometry),
+    };
+    let (t0, t1) = info
+        .points
+        .iter()
+        .map(|pt| (pt - origin).dot(axis))
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(t0, t1), t| {
+            (t0.min(t), t1.max(t))
+        });
+    let pitch = spec.pitch;
+    let depth = spec.depth.unwrap_or(5.0 * f64::sqrt(3.0) / 16.0 * pitch);
+    let length = spec.length.unwrap_or(t1 - t0);
+    // The crests of the external threads are `P / 8` wide, and the ones of the internal
+    // threads are `P / 4` wide.
+    let internal = !convex;
+    let crest_half_width = if internal { 3.0 * pitch / 8.0 } else { 7.0 * pitch / 16.0 };
+    let root_half_width = crest_half_width - depth * FLANK_SLOPE;
+    let invalid = pitch <= 0.0
+        || depth <= 0.0
+        || length <= 0.0
+        || length > t1 - t0 + TOLERANCE
+        || root_half_width <= 0.0
+        || (!internal && depth >= radius);
+    if invalid {
+        return Err(Error::InvalidThread);
+    }
+    let (major_radius, minor_radius) = match internal {
+        true => (radius + depth, radius),
+        false => (radius, radius - depth),
+    };
+    // the angles from the start direction of the divisions of the groove into the quarter
+    // turns, and of the crossings of the flanks of the groove and the ends of the face
+    let turn = |height: f64| {
+        let sign = if spec.left_handed { -1.0 } else { 1.0 };
+        PI + sign * 2.0 * PI * height / pitch
+    };
+    let mut critical: Vec<f64> = (0..4).map(|i| PI / 2.0 * i as f64).collect();
+    critical.extend(&[
+        turn(-crest_half_width),
+        turn(crest_half_width),
+        turn(length - crest_half_width),
+        turn(length + crest_half_width),
+    ]);
+    let start = start_direction(face, origin, axis, &critical);
+    let hand = if spec.left_handed { "left" } else { "right" };
+    let attributes: Attributes = vec![
+        ("thread", if internal { "internal" } else { "external" }.to_string()),
+        ("pitch", pitch.to_string()),
+        ("major_diameter", (2.0 * major_radius).to_string()),
+        ("minor_diameter", (2.0 * minor_radius).to_string()),
+        ("length", length.to_string()),
+        ("hand", hand.to_string()),
+    ]
+    .into_iter()
+    .map(|(key, value)| (key.to_string(), value))
+    .collect();
+    let mut thread = Thread {
+        face: face.id(),
+        origin: origin + axis * t0,
+        axis,
+        start,
+        major_diameter: 2.0 * major_radius,
+        minor_diameter: 2.0 * minor_radius,
+        pitch,
+        length,
+        left_handed: spec.left_handed,
+        internal,
+        attributes,
+        body: None,
+    };
+    if spec.kind == ThreadKind::Modeled {
+        let through = length > t1 - t0 - TOLERANCE;
+        thread.body = Some(groove(&thread, radius, depth, crest_half_width, through)?);
+    }
+    Ok(thread)
+}
+
+/// Returns the helical groove, whose section is the trapezoid between the face and the root.
+/// The section is extended outside of the material so that the groove cuts the face cleanly.
+/// If `through` is `true`, the thread runs over the whole face.
+fn groove(
+    thread: &Thread,
+    radius: f64,
+    depth: f64,
+    crest_half_width: f64,
+    through: bool,
+) -> Result<Solid> {
+    let pitch = thread.pitch;
+    let margin = pitch / 16.0;
+    let (outer, root) = match thread.internal {
+        true => (radius - margin, radius + depth),
+        false => (radius + margin, radius - depth),
+    };
+    let outer_half_width = crest_half_width + margin * FLANK_SLOPE;
+    let root_half_width = crest_half_width - depth * FLANK_SLOPE;
+    let mut points = [
+        Point3::new(outer, 0.0, -outer_half_width),
+        Point3::new(root, 0.0, -root_half_width),
+        Point3::new(root, 0.0, root_half_width),
+        Point3::new(outer, 0.0, outer_half_width),
+    ];
+    // The section is counter-clockwise with respect to the direction of the screw motion.
+    if thread.internal != thread.left_handed {
+        points.reverse();
+    }
+    let vertices: Vec<Vertex> = points.iter().map(|pt| builder::vertex(*pt)).collect();
+    let section: Wire = (0..4)
+        .map(|i| builder::line(&vertices[i], &vertices[(i + 1) % 4]))
+        .collect();
+
+    // The groove runs out of the ends of the face by one pitch, or runs out of the face
+    // radially in the last half turn, so that the caps do not cut the solid.
+    let start = -pitch;
+    let end = match through {
+        true => thread.length + pitch,
+        false => thread.length,
+    };
+    let turns = (end - start) / pitch;
+    let sections = f64::ceil(turns * 4.0) as usize * SECTIONS_PER_TURN / 4;
+    let hand = if thread.left_handed { -1.0 } else { 1.0 };
+    let frame = frame(thread.origin, thread.axis, thread.start);
+    let sections: Vec<Wire> = (0..=sections)
+        .map(|i| {
+            let height = start + (end - start) * i as f64 / sections as f64;
+            // The groove is a half turn behind the helix of the crests.
+            let angle = PI + hand * 2.0 * PI * height / pitch;
+            let runout = match through {
+                true => 0.0,
+                false => {
+                    let s = (height - thread.length + pitch / 2.0) / (pitch / 2.0);
+                    let s = s.clamp(0.0, 1.0);
+                    s * s * (3.0 - 2.0 * s) * (depth + 2.0 * margin)
+                }
+            };
+            let runout = if thread.internal { -runout } else { runout };
+            let mat = frame
+                * Matrix4::from_translation(height * Vector3::unit_z())
+                * Matrix4::from_angle_z(Rad(angle))
+                * Matrix4::from_translation(runout * Vector3::unit_x());
+            builder::transformed(&section, mat)
+        })
+        .collect();
+    // The groove is lofted by the quarter turns, so that the points on the faces are projected
+    // onto the right turns.
+    let mut shell = Shell::new();
+    for chunk in sections.windows(SECTIONS_PER_TURN / 4 + 1).step_by(SECTIONS_PER_TURN / 4) {
+        shell.extend(builder::loft(chunk)?);
+    }
+    let start = builder::try_attach_plane(&vec![sections[0].clone()])?;
+    let end = builder::try_attach_plane(&vec![sections[sections.len() - 1].clone()])?;
+    shell.push(start.inverse());
+    shell.push(end);
+    Ok(Solid::try_new(vec![shell])?)
+}
+
+/// Returns the frame whose origin is `origin`, whose x-axis is `x` and whose z-axis is `axis`.
+fn frame(origin: Point3, axis: Vector3, x: Vector3) -> Matrix4 {
+    Matrix4::from_cols(
+        x.extend(0.0),
+        axis.cross(x).extend(0.0),
+        axis.extend(0.0),
+        origin.to_homogeneous(),
+    )
+}
+
+/// Returns the start direction of the thread, such that the `critical` angles from it are kept
+/// away from the vertices of the face, e.g. the ends of the seams, where the intersections with
+/// the groove would be degenerate.
+fn start_direction(face: &Face, origin: Point3, axis: Vector3, critical: &[f64]) -> Vector3 {
+    let seed = match axis.x.abs() < 0.5 {
+        true => Vector3::unit_x(),
+        false => Vector3::unit_y(),
+    };
+    let x = (seed - axis * seed.dot(axis)).normalize();
+    let y = axis.cross(x);
+    let angles: Vec<f64> = face
+        .boundaries()
+        .iter()
+        .flatten()
+        .map(|edge| {
+            let vec = *edge.front().lock_point().unwrap() - origin;
+            f64::atan2(vec.dot(y), vec.dot(x))
+        })
+        .collect();
+    // the angular distance from the nearest vertex to the nearest critical angle
+    let clearance = |start: f64| {
+        let distance = |angle: f64| {
+            let rem = angle.rem_euclid(2.0 * PI);
+            f64::min(rem, 2.0 * PI - rem)
+        };
+        angles
+            .iter()
+            .flat_map(|t| critical.iter().map(move |c| distance(t - start - c)))
+            .fold(PI, f64::min)
+    };
+    let angle = (0..START_CANDIDATES)
+        .map(|i| 2.0 * PI * i as f64 / START_CANDIDATES as f64)
+        .max_by(|a, b| clearance(*a).partial_cmp(&clearance(*b)).unwrap())
+        .unwrap();
+    x * angle.cos() + y * angle.sin()
+}
+
+/// Returns the number of the chords of the arc with `radius` and `angle` within `tol`.
+fn division(radius: f64, tol: f64, angle: f64) -> usize {
+    let step = 2.0 * f64::acos(f64::max(1.0 - tol / radius, -1.0));
+    usize::max(f64::ceil(angle.abs() / step) as usize, 1)
+}
+
+#[test]
+fn modeled_thread() {
+    use crate::boolean::BooleanOperation;
+    let v = builder::vertex(Point3::new(5.0, 0.0, 0.0));
+    let circle: Wire = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+    let shaft = builder::tsweep(&disk, Vector3::new(0.0, 0.0, 4.0));
+    let face = shaft.boundaries()[0]
+        .face_iter()
+        .find(|face| !matches!(*face.lock_surface().unwrap(), Surface::Plane(_)))
+        .unwrap();
+    let spec = ThreadSpec {
+        pitch: 1.5,
+        left_handed: true,
+        kind: ThreadKind::Modeled,
+        ..Default::default()
+    };
+    let thread = builder::thread(face, &spec).unwrap();
+    let overlay = thread.overlay(1.0e-3);
+    let start = thread.origin + 5.0 * thread.start;
+    assert!(overlay[0][0].distance(start) < 1.0e-6);
+
+    // the trapezoid between the radii 5 and 5 - depth moved along the helix, by Pappus
+    let depth = 5.0 * f64::sqrt(3.0) / 16.0 * 1.5;
+    let (crest, root) = (7.0 * 1.5 / 8.0, 7.0 * 1.5 / 8.0 - 2.0 * depth * FLANK_SLOPE);
+    let area = (crest + root) / 2.0 * depth;
+    let centroid = 5.0 - depth * (crest + 2.0 * root) / (3.0 * (crest + root));
+    let removed = area * 2.0 * PI * centroid * 4.0 / 1.5;
+    let body = thread.body.as_ref().unwrap();
+    assert_eq!(body.boundaries()[0].shell_condition(), ShellCondition::Closed);
+    let threaded = shaft.subtract(body, 0.1).unwrap();
+    assert_eq!(threaded.boundaries().len(), 1);
+    assert_eq!(threaded.boundaries()[0].shell_condition(), ShellCondition::Closed);
+    // the curves of the edges end at the vertices
+    assert!(threaded.boundaries()[0]
+        .iter()
+        .flat_map(|face| face.boundaries().into_iter().flatten())
+        .all(|edge| edge.is_geometric_consistent()));
+    let volume = properties::mass_properties(&threaded, 1.0e-2).volume;
+    let expected = PI * 25.0 * 4.0 - removed;
+    assert!((volume - expected).abs() < 0.01 * removed, "{} {}", volume, expected);
+}
+
+#[test]
+fn cosmetic_thread() {
+    let v = builder::vertex(Point3::new(5.0, 0.0, 0.0));
+    let circle: Wire = builder::rsweep(&v, Point3::origin(), Vector3::unit_z(), Rad(7.0));
+    let disk = builder::try_attach_plane(&vec![circle]).unwrap();
+    let shaft = builder::tsweep(&disk, Vector3::new(0.0, 0.0, 4.0));
+    let face = shaft.boundaries()[0]
+        .face_iter()
+        .find(|face| !matches!(*face.lock_surface().unwrap(), Surface::Plane(_)))
+        .unwrap();
+    let boundaries = face.boundaries();
+    let len = shaft.boundaries()[0].len();
+    let surface = face.lock_surface().unwrap().clone();
+    let thread = builder::thread(face, &ThreadSpec::default()).unwrap();
+    assert_eq!(thread.face, face.id());
+    assert!(thread.body.is_none());
+    assert_eq!(thread.overlay(1.0e-3).len(), 3);
+
+    // the face and the solid are untouched
+    assert_eq!(face.boundaries(), boundaries);
+    let ((u0, u1), (v0, v1)) = surface.parameter_range();
+    for (u, v) in [(u0, v0), (u1, v1), ((u0 + u1) / 2.0, (v0 + v1) / 2.0)].iter() {
+        let pt = face.lock_surface().unwrap().subs(*u, *v);
+        assert!(pt.near(&surface.subs(*u, *v)));
+    }
+    assert_eq!(shaft.boundaries()[0].len(), len);
+    assert_eq!(shaft.boundaries()[0].shell_condition(), ShellCondition::Closed);
+}
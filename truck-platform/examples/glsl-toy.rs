@@ -329,10 +329,8 @@ fn main() {
             }
             Event::WindowEvent { event, .. } => match event {
                 WindowEvent::Resized(size) => {
-                    let mut sc_desc = handler.lock_sc_desc().unwrap();
-                    sc_desc.width = size.width;
-                    sc_desc.height = size.height;
-                    swap_chain = handler.device().create_swap_chain(&surface, &sc_desc);
+                    let sc_desc = scene.resize(size.width, size.height);
+                    swap_chain = scene.device().create_swap_chain(&surface, &sc_desc);
                     ControlFlow::Poll
                 }
                 WindowEvent::CloseRequested => ControlFlow::Exit,
@@ -0,0 +1,114 @@
+use crate::*;
+
+// the order of preference of the adapters in the fallback
+fn preference(device_type: &DeviceType) -> usize {
+    match device_type {
+        DeviceType::DiscreteGpu => 0,
+        DeviceType::IntegratedGpu => 1,
+        DeviceType::VirtualGpu => 2,
+        DeviceType::Other => 3,
+        DeviceType::Cpu => 4,
+    }
+}
+
+impl DeviceHandler {
+    /// Requests the device and the queue of a suitable adapter.
+    ///
+    /// The adapter is requested by `Instance::request_adapter` with the default power preference.
+    /// With the feature `software-fallback`, if no adapter is found, all adapters of `instance`
+    /// are enumerated and the software adapters, e.g. SwiftShader and lavapipe, are also
+    /// accepted. If the device with the default limits cannot be created by the software adapter,
+    /// the limits are downgraded to the ones of the adapter. One can check whether the software
+    /// adapter is selected by the returned `AdapterInfo::device_type`.
+    ///
+    /// The surface is not necessarily compatible with the adapter selected in the fallback,
+    /// which is mainly for the offscreen rendering on the CI machines and the servers.
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_platform::*;
+    /// use wgpu::*;
+    /// let instance = Instance::new(BackendBit::all());
+    /// let (device, queue, info) =
+    ///     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// let sc_desc = SwapChainDescriptor {
+    ///     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    ///     format: TextureFormat::Rgba8UnormSrgb,
+    ///     width: 512,
+    ///     height: 512,
+    ///     present_mode: PresentMode::Mailbox,
+    /// };
+    /// let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// // The MSAA is downgraded on the software adapters.
+    /// let scene_desc = SceneDescriptor {
+    ///     render_settings: RenderSettings {
+    ///         sample_count: match info.device_type {
+    ///             DeviceType::Cpu => 1,
+    ///             _ => 4,
+    ///         },
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    /// let scene = Scene::new(handler, &scene_desc);
+    /// ```
+    pub async fn request_device(
+        instance: &Instance,
+        compatible_surface: Option<&Surface>,
+    ) -> Result<(Arc<Device>, Arc<Queue>, AdapterInfo), RequestError> {
+        let adapter = instance
+            .request_adapter(&RequestAdapterOptions {
+                power_preference: PowerPreference::Default,
+                compatible_surface,
+            })
+            .await;
+        let (adapter, fallback) = match adapter {
+            Some(adapter) => (adapter, false),
+            None if cfg!(feature = "software-fallback") => {
+                let adapter = instance
+                    .enumerate_adapters(BackendBit::all())
+                    .min_by_key(|adapter| preference(&adapter.get_info().device_type))
+                    .ok_or(RequestError::AdapterNotFound)?;
+                (adapter, true)
+            }
+            None => return Err(RequestError::AdapterNotFound),
+        };
+        let info = adapter.get_info();
+        let request = |limits: Limits| {
+            adapter.request_device(
+                &DeviceDescriptor {
+                    features: Default::default(),
+                    limits,
+                    shader_validation: true,
+                },
+                None,
+            )
+        };
+        let (device, queue) = match request(Limits::default()).await {
+            Ok(got) => got,
+            Err(_) if fallback || info.device_type == DeviceType::Cpu => {
+                request(adapter.limits()).await?
+            }
+            Err(error) => return Err(error.into()),
+        };
+        Ok((Arc::new(device), Arc::new(queue), info))
+    }
+}
+
+impl From<RequestDeviceError> for RequestError {
+    #[inline(always)]
+    fn from(error: RequestDeviceError) -> RequestError { RequestError::RequestDevice(error) }
+}
+
+impl std::fmt::Display for RequestError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            RequestError::AdapterNotFound => f.pad("no suitable adapter is found"),
+            RequestError::RequestDevice(error) => {
+                f.write_fmt(format_args!("failed to request the device: {:?}", error))
+            }
+        }
+    }
+}
+
+impl std::error::Error for RequestError {}
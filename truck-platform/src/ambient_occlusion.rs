@@ -0,0 +1,196 @@
+use crate::*;
+
+impl Default for AmbientOcclusion {
+    #[inline(always)]
+    fn default() -> AmbientOcclusion {
+        AmbientOcclusion {
+            radius: 0.5,
+            intensity: 1.0,
+        }
+    }
+}
+
+impl AmbientOcclusion {
+    #[inline(always)]
+    fn ambient_occlusion_info(&self, viewport: [f32; 4], farthest: f32) -> AmbientOcclusionInfo {
+        AmbientOcclusionInfo {
+            viewport,
+            parameters: [self.radius as f32, self.intensity as f32, farthest, 0.0],
+        }
+    }
+
+    #[inline(always)]
+    fn bgl_entries(multisampled: bool) -> [PreBindGroupLayoutEntry; 3] {
+        [
+            PreBindGroupLayoutEntry {
+                visibility: ShaderStage::FRAGMENT,
+                ty: BindingType::UniformBuffer {
+                    dynamic: false,
+                    min_binding_size: None,
+                },
+                count: None,
+            },
+            PreBindGroupLayoutEntry {
+                visibility: ShaderStage::FRAGMENT,
+                ty: BindingType::SampledTexture {
+                    dimension: TextureViewDimension::D2,
+                    component_type: TextureComponentType::Float,
+                    multisampled,
+                },
+                count: None,
+            },
+            PreBindGroupLayoutEntry {
+                visibility: ShaderStage::FRAGMENT,
+                ty: BindingType::Sampler { comparison: false },
+                count: None,
+            },
+        ]
+    }
+}
+
+impl Scene {
+    // the pipeline darkening the target by the ambient occlusion
+    fn ambient_occlusion_pipeline(&self, layout: &BindGroupLayout) -> Arc<RenderPipeline> {
+        let handler = self.device_handler();
+        let multisampled = self.scene_desc.effective_sample_count() != 1;
+        // the target is multiplied by the output color.
+        let color_state = ColorStateDescriptor {
+            format: self.sc_desc().format,
+            color_blend: BlendDescriptor {
+                src_factor: BlendFactor::Zero,
+                dst_factor: BlendFactor::SrcColor,
+                operation: BlendOperation::Add,
+            },
+            alpha_blend: BlendDescriptor {
+                src_factor: BlendFactor::Zero,
+                dst_factor: BlendFactor::One,
+                operation: BlendOperation::Add,
+            },
+            write_mask: ColorWrite::ALL,
+        };
+        let shader = match multisampled {
+            true => "truck-platform/ambient-occlusion-ms",
+            false => "truck-platform/ambient-occlusion",
+        };
+        let key = PipelineKey {
+            shader: shader.to_string(),
+            vertex_layout: String::new(),
+            primitive_topology: PrimitiveTopology::TriangleList,
+            index_format: IndexFormat::Uint16,
+            cull_mode: CullMode::None,
+            depth_bias: (0, 0, 0),
+            color_states: vec![color_state.clone()],
+            depth_stencil_state: None,
+            sample_count: 1,
+        };
+        handler.cached_pipeline(&key, || {
+            let device = handler.device();
+            let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+                bind_group_layouts: &[&self.bind_group_layout, layout],
+                push_constant_ranges: &[],
+                label: None,
+            });
+            let vertex_module =
+                device.create_shader_module(include_spirv!("shaders/ambient-occlusion.vert.spv"));
+            let fragment_module = match multisampled {
+                true => device
+                    .create_shader_module(include_spirv!("shaders/ambient-occlusion-ms.frag.spv")),
+                false => device
+                    .create_shader_module(include_spirv!("shaders/ambient-occlusion.frag.spv")),
+            };
+            Arc::new(device.create_render_pipeline(&RenderPipelineDescriptor {
+                layout: Some(&pipeline_layout),
+                vertex_stage: ProgrammableStageDescriptor {
+                    module: &vertex_module,
+                    entry_point: "main",
+                },
+                fragment_stage: Some(ProgrammableStageDescriptor {
+                    module: &fragment_module,
+                    entry_point: "main",
+                }),
+                rasterization_state: Some(RasterizationStateDescriptor {
+                    front_face: FrontFace::Ccw,
+                    cull_mode: CullMode::None,
+                    depth_bias: 0,
+                    depth_bias_slope_scale: 0.0,
+                    depth_bias_clamp: 0.0,
+                    clamp_depth: false,
+                }),
+                primitive_topology: PrimitiveTopology::TriangleList,
+                color_states: &[color_state],
+                depth_stencil_state: None,
+                vertex_state: VertexStateDescriptor {
+                    index_format: IndexFormat::Uint16,
+                    vertex_buffers: &[],
+                },
+                sample_count: 1,
+                sample_mask: !0,
+                alpha_to_coverage_enabled: false,
+                label: None,
+            }))
+        })
+    }
+
+    // darkens the resolved `view` by the depths of the objects rendered in each viewport,
+    // if the ambient occlusion is enabled
+    pub(super) fn render_ambient_occlusion(
+        &self,
+        encoder: &mut CommandEncoder,
+        view: &TextureView,
+        viewports: &[(BindGroup, Option<[f32; 4]>)],
+    ) {
+        let ambient_occlusion = match self.scene_desc.render_settings.ambient_occlusion {
+            Some(ambient_occlusion) => ambient_occlusion,
+            None => return,
+        };
+        let handler = self.device_handler();
+        let multisampled = self.scene_desc.effective_sample_count() != 1;
+        let layout = handler.cached_bind_group_layout(&AmbientOcclusion::bgl_entries(multisampled));
+        let pipeline = self.ambient_occlusion_pipeline(&layout);
+        let depth_view = self.foward_depth.create_view(&Default::default());
+        let sampler = self.device().create_sampler(&SamplerDescriptor {
+            mag_filter: FilterMode::Nearest,
+            min_filter: FilterMode::Nearest,
+            ..Default::default()
+        });
+        let sc_desc = self.sc_desc();
+        let farthest = handler.farthest_depth();
+        let whole = [0.0, 0.0, sc_desc.width as f32, sc_desc.height as f32];
+        let bind_groups: Vec<BindGroup> = viewports
+            .iter()
+            .map(|(_, viewport)| {
+                let viewport = viewport.unwrap_or(whole);
+                let info = ambient_occlusion.ambient_occlusion_info(viewport, farthest);
+                let info = handler.ring_buffer_slice(&[info]);
+                bind_group_util::create_bind_group(
+                    self.device(),
+                    &layout,
+                    vec![
+                        info.binding_resource(),
+                        BindingResource::TextureView(&depth_view),
+                        BindingResource::Sampler(&sampler),
+                    ],
+                )
+            })
+            .collect();
+        let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
+            color_attachments: &[RenderPassColorAttachmentDescriptor {
+                attachment: view,
+                resolve_target: None,
+                ops: Operations {
+                    load: LoadOp::Load,
+                    store: true,
+                },
+            }],
+            depth_stencil_attachment: None,
+        });
+        rpass.set_pipeline(&pipeline);
+        for ((scene_bind_group, viewport), bind_group) in viewports.iter().zip(&bind_groups) {
+            let [x, y, width, height] = viewport.unwrap_or(whole);
+            rpass.set_viewport(x, y, width, height, 0.0, 1.0);
+            rpass.set_bind_group(0, scene_bind_group, &[]);
+            rpass.set_bind_group(1, bind_group, &[]);
+            rpass.draw(0..3, 0..1);
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use crate::*;
+
+impl Default for AutoClip {
+    #[inline(always)]
+    fn default() -> AutoClip {
+        AutoClip {
+            margin: 0.05,
+            min_near_ratio: 1.0e-4,
+        }
+    }
+}
+
+// the eight corners of the box
+fn corners(min: Point3, max: Point3) -> impl Iterator<Item = Point3> {
+    (0..8).map(move |i| {
+        Point3::new(
+            if i & 1 == 0 { min[0] } else { max[0] },
+            if i & 2 == 0 { min[1] } else { max[1] },
+            if i & 4 == 0 { min[2] } else { max[2] },
+        )
+    })
+}
+
+impl AutoClip {
+    /// Returns the camera whose rendered view volume is fitted to `bdd_box` along the eye
+    /// direction.
+    ///
+    /// The front and the back of the rendered view volume are the ones of the ray returned by
+    /// [`Camera::ray`]. Note that the front of the perspective camera is farther than the near
+    /// clip, so the near clip is solved from the front. The other configures of the camera are
+    /// kept. If the bounding box is empty or behind the perspective camera, returns the clone
+    /// of `camera`.
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use std::iter::FromIterator;
+    /// use truck_base::{bounding_box::BoundingBox, cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let matrix = Matrix4::from_translation(Vector3::new(0.0, 0.0, 10.0));
+    /// let camera = Camera::perspective_camera(matrix, Rad(PI / 4.0), 0.1, 1000.0);
+    /// let auto_clip = AutoClip {
+    ///     margin: 0.0,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// // the whole model
+    /// let bdd_box = BoundingBox::from_iter(&[
+    ///     Point3::new(-1.0, -1.0, -1.0),
+    ///     Point3::new(1.0, 1.0, 1.0),
+    /// ]);
+    /// let fitted = auto_clip.fitted_camera(&camera, &bdd_box);
+    /// let (front, direction) = fitted.ray(0.0, 0.0, 1.0);
+    /// assert!(front.near(&Point3::new(0.0, 0.0, 1.0)));
+    /// assert!((front + direction).near(&Point3::new(0.0, 0.0, -1.0)));
+    /// assert_eq!(fitted.matrix, camera.matrix);
+    ///
+    /// // The camera is in the model, and the near clip is bounded by the far clip.
+    /// let bdd_box = BoundingBox::from_iter(&[
+    ///     Point3::new(-20.0, -20.0, -20.0),
+    ///     Point3::new(20.0, 20.0, 20.0),
+    /// ]);
+    /// let fitted = auto_clip.fitted_camera(&camera, &bdd_box);
+    /// let (front, direction) = fitted.ray(0.0, 0.0, 1.0);
+    /// assert!(front.near(&Point3::new(0.0, 0.0, 10.0 - 30.0 * auto_clip.min_near_ratio)));
+    /// assert!((front + direction).near(&Point3::new(0.0, 0.0, -20.0)));
+    /// ```
+    ///
+    /// [`Camera::ray`]: ./struct.Camera.html#method.ray
+    pub fn fitted_camera(&self, camera: &Camera, bdd_box: &BoundingBox<Point3>) -> Camera {
+        if bdd_box.is_empty() {
+            return camera.clone();
+        }
+        let (min, max) = (bdd_box.min(), bdd_box.max());
+        let (position, direction) = (camera.position(), camera.eye_direction());
+        let init = (f64::INFINITY, f64::NEG_INFINITY);
+        let (near, far) = corners(*min, *max).fold(init, |(near, far), corner| {
+            let depth = (corner - position).dot(direction);
+            (f64::min(near, depth), f64::max(far, depth))
+        });
+        let margin = self.margin * bdd_box.diameter();
+        let (front, far) = (near - margin, far + margin);
+        let near = match camera.projection_type() {
+            // The front `2 * near * far / (near + far)` is the depth 0 of the projection.
+            ProjectionType::Perspective => {
+                let front = f64::max(front, far * self.min_near_ratio);
+                front * far / (2.0 * far - front)
+            }
+            ProjectionType::Parallel => front,
+        };
+        match near < far && far > 0.0 {
+            true => Camera {
+                near_clip: near,
+                far_clip: far,
+                ..camera.clone()
+            },
+            false => camera.clone(),
+        }
+    }
+}
+
+impl Scene {
+    /// Returns the bounding box of the objects in the world coordinate.
+    ///
+    /// The objects without the meshes returned by [`Rendered::picking_mesh`] are ignored.
+    ///
+    /// [`Rendered::picking_mesh`]: ./trait.Rendered.html#method.picking_mesh
+    pub fn bounding_box(&self) -> BoundingBox<Point3> {
+        self.objects
+            .values()
+            .filter_map(|object| object.picking.as_ref())
+            .filter(|(mesh, _)| !mesh.bounding_box.is_empty())
+            .flat_map(|(mesh, matrix)| {
+                let (min, max) = (*mesh.bounding_box.min(), *mesh.bounding_box.max());
+                corners(min, max).map(move |corner| matrix.transform_point(corner))
+            })
+            .collect()
+    }
+
+    /// Returns the camera by which the scene is rendered, i.e. the camera of the descriptor
+    /// whose clips are fitted to [`Scene::bounding_box`] if [`SceneDescriptor::auto_clip`]
+    /// is `Some`.
+    ///
+    /// [`Scene::bounding_box`]: ./struct.Scene.html#method.bounding_box
+    /// [`SceneDescriptor::auto_clip`]: ./struct.SceneDescriptor.html#structfield.auto_clip
+    #[inline(always)]
+    pub fn rendered_camera(&self) -> Camera { self.fitted_camera(&self.scene_desc.camera) }
+
+    // `camera` whose clips are fitted to the objects if the automatic clipping is enabled
+    pub(super) fn fitted_camera(&self, camera: &Camera) -> Camera {
+        match &self.scene_desc.auto_clip {
+            Some(auto_clip) => auto_clip.fitted_camera(camera, &self.bounding_box()),
+            None => camera.clone(),
+        }
+    }
+}
@@ -93,11 +93,17 @@ impl Camera {
         near_clip: f64,
         far_clip: f64,
     ) -> Camera {
-        let projection = perspective(field_of_view.into(), 1.0, near_clip, far_clip);
+        let field_of_view: Rad<f64> = field_of_view.into();
+        let screen_size = 2.0 * (field_of_view / 2.0).tan();
         Camera {
             matrix,
-            projection,
             projection_type: ProjectionType::Perspective,
+            screen_size,
+            off_center: Vector2::zero(),
+            oblique: Vector2::zero(),
+            near_clip,
+            far_clip,
+            tile: (0, 0, 1),
         }
     }
 
@@ -134,20 +140,392 @@ impl Camera {
         near_clip: f64,
         far_clip: f64,
     ) -> Camera {
-        let a = screen_size / 2.0;
-        let projection = Matrix4::new(
-            1.0 / a, 0.0, 0.0, 0.0,
-            0.0, 1.0 / a, 0.0, 0.0,
-            0.0, 0.0, -1.0 / (far_clip - near_clip), 0.0,
-            0.0, 0.0, -near_clip / (far_clip - near_clip), 1.0,
-        );
         Camera {
             matrix,
-            projection,
             projection_type: ProjectionType::Parallel,
+            screen_size,
+            off_center: Vector2::zero(),
+            oblique: Vector2::zero(),
+            near_clip,
+            far_clip,
+            tile: (0, 0, 1),
+        }
+    }
+
+    /// Creates a camera whose view volume is through the window on the screen.
+    /// # Arguments
+    /// * `matrix`:  camera matrix
+    /// * `projection_type`: perspective or parallel
+    /// * `window`: `[left, right, bottom, top]` of the window. In the perspective camera,
+    /// the window is on the plane at the distance 1 from the camera, i.e. the tangents of
+    /// the angles of the sides of the view volume.
+    /// * `near_clip`: distance to the nearest face of the view volume
+    /// * `far_clip`: distance to the farthest face of the view volume
+    ///
+    /// The projection is based on the vertical direction of the screen, so the window is
+    /// extended horizontally around its center if the aspect ratio of the target is not
+    /// `(right - left) / (top - bottom)`.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let camera = Camera::window_camera(
+    ///     Matrix4::identity(),
+    ///     ProjectionType::Perspective,
+    ///     [0.0, 2.0, -0.5, 1.5],
+    ///     0.1,
+    ///     10.0,
+    /// );
+    /// assert_eq!(camera.window(1.0), [0.0, 2.0, -0.5, 1.5]);
+    /// assert_eq!(camera.window(2.0), [-1.0, 3.0, -0.5, 1.5]);
+    /// // the corners of the window
+    /// let uv = camera.projection(1.0).transform_point(Point3::new(0.0, -0.5, -1.0));
+    /// assert!(uv.x.near(&-1.0) && uv.y.near(&-1.0));
+    /// let uv = camera.projection(1.0).transform_point(Point3::new(2.0, 1.5, -1.0));
+    /// assert!(uv.x.near(&1.0) && uv.y.near(&1.0));
+    /// ```
+    #[inline(always)]
+    pub fn window_camera(
+        matrix: Matrix4,
+        projection_type: ProjectionType,
+        window: [f64; 4],
+        near_clip: f64,
+        far_clip: f64,
+    ) -> Camera {
+        let [left, right, bottom, top] = window;
+        Camera {
+            matrix,
+            projection_type,
+            screen_size: top - bottom,
+            off_center: Vector2::new(left + right, bottom + top) / 2.0,
+            oblique: Vector2::zero(),
+            near_clip,
+            far_clip,
+            tile: (0, 0, 1),
+        }
+    }
+
+    /// Creates a parallel camera with the oblique projection.
+    /// # Arguments
+    /// * `matrix`:  camera matrix
+    /// * `screen_size`: screen size, based on the vertical direction of the screen.
+    /// * `oblique`: the shift of the projected point per unit depth from the camera.
+    /// e.g. the cabinet projection is given by `Vector2::new(0.5, 0.5) / 2.0_f64.sqrt()`.
+    /// * `near_clip`: distance to the nearest face of the view volume
+    /// * `far_clip`: distance to the farthest face of the view volume
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let oblique = Vector2::new(0.5, 0.5) / 2.0_f64.sqrt();
+    /// let camera = Camera::oblique_camera(Matrix4::identity(), 2.0, oblique, 0.1, 10.0);
+    /// assert_eq!(camera.projection_type(), ProjectionType::Parallel);
+    /// assert_eq!(camera.oblique(), oblique);
+    /// // the deeper point is shifted to the upper right.
+    /// let uv = camera.projection(1.0).transform_point(Point3::new(0.0, 0.0, -2.0));
+    /// assert!(uv.x.near(&(2.0 * oblique.x)) && uv.y.near(&(2.0 * oblique.y)));
+    /// ```
+    #[inline(always)]
+    pub fn oblique_camera(
+        matrix: Matrix4,
+        screen_size: f64,
+        oblique: Vector2,
+        near_clip: f64,
+        far_clip: f64,
+    ) -> Camera {
+        Camera {
+            oblique,
+            ..Camera::parallel_camera(matrix, screen_size, near_clip, far_clip)
+        }
+    }
+
+    /// Returns the camera rendering the tile of the view volume divided into `n` x `n` tiles,
+    /// for rendering the high-resolution image by tiles.
+    ///
+    /// `tile_i` is the column from the left, and `tile_j` is the row from the top. The tile
+    /// should be rendered with the same aspect ratio as the whole image. The tile of the tile
+    /// is also available.
+    /// # Panics
+    /// Panic occurs if `tile_i >= n` or `tile_j >= n`.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let camera = Camera::default();
+    /// let pt = Point3::new(0.3, 0.2, -2.0);
+    /// let uv = camera.projection(1.5).transform_point(pt);
+    /// // the point is in the top right tile of 2 x 2 tiles.
+    /// let tile = camera.sub_frustum(1, 0, 2);
+    /// assert_eq!(tile.tile(), (1, 0, 2));
+    /// let tile_uv = tile.projection(1.5).transform_point(pt);
+    /// assert!(tile_uv.x.near(&(2.0 * uv.x - 1.0)));
+    /// assert!(tile_uv.y.near(&(2.0 * uv.y - 1.0)));
+    /// assert!(tile_uv.z.near(&uv.z));
+    /// ```
+    #[inline(always)]
+    pub fn sub_frustum(&self, tile_i: usize, tile_j: usize, n: usize) -> Camera {
+        assert!(
+            tile_i < n && tile_j < n,
+            "The tile ({}, {}) is out of {} x {} tiles.",
+            tile_i,
+            tile_j,
+            n,
+            n
+        );
+        let (i, j, m) = self.tile;
+        Camera {
+            tile: (i * n + tile_i, j * n + tile_j, m * n),
+            ..self.clone()
         }
     }
 
+    /// Interpolates the cameras `a` and `b` by the parameter `t` in `[0, 1]`,
+    /// for the smooth transition between the saved viewpoints.
+    ///
+    /// The positions and the parameters of the projections are linearly interpolated, and
+    /// the rotations are interpolated by the spherical linear interpolation of the quaternions.
+    /// The projection type and the tile are the ones of `a` if `t < 0.5`, otherwise the ones
+    /// of `b`.
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let a = Camera::default();
+    /// let matrix = Matrix4::from_translation(Vector3::new(2.0, 0.0, 0.0))
+    ///     * Matrix4::from_angle_y(Rad(PI / 2.0));
+    /// let b = Camera::perspective_camera(matrix, Rad(PI / 3.0), 0.1, 10.0);
+    /// let camera = Camera::interpolate(&a, &b, 0.5);
+    /// assert!(camera.position().near(&Point3::new(1.0, 0.0, 0.0)));
+    /// let dir = Vector3::new(-1.0, 0.0, -1.0).normalize();
+    /// assert!(camera.eye_direction().near(&dir));
+    /// let camera = Camera::interpolate(&a, &b, 1.0);
+    /// assert!(camera.eye_direction().near(&b.eye_direction()));
+    /// ```
+    pub fn interpolate(a: &Camera, b: &Camera, t: f64) -> Camera {
+        let rotation = |matrix: &Matrix4| {
+            Quaternion::from(Matrix3::from_cols(
+                matrix[0].truncate(),
+                matrix[1].truncate(),
+                matrix[2].truncate(),
+            ))
+        };
+        let rotation = rotation(&a.matrix).slerp(rotation(&b.matrix), t);
+        let position = a.position() + (b.position() - a.position()) * t;
+        let lerp = |x: f64, y: f64| x + (y - x) * t;
+        let (projection_type, tile) = match t < 0.5 {
+            true => (a.projection_type, a.tile),
+            false => (b.projection_type, b.tile),
+        };
+        Camera {
+            matrix: Matrix4::from_translation(position.to_vec()) * Matrix4::from(rotation),
+            projection_type,
+            screen_size: lerp(a.screen_size, b.screen_size),
+            off_center: a.off_center.lerp(b.off_center, t),
+            oblique: a.oblique.lerp(b.oblique, t),
+            near_clip: lerp(a.near_clip, b.near_clip),
+            far_clip: lerp(a.far_clip, b.far_clip),
+            tile,
+        }
+    }
+
+    /// Interpolates the cameras `a` and `b` by the parameter `t`, the same as
+    /// [`Camera::interpolate`](#method.interpolate).
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let a = Camera::default();
+    /// let matrix = Matrix4::from_translation(Vector3::new(2.0, 0.0, 0.0));
+    /// let b = Camera::perspective_camera(matrix, Rad(1.0), 0.1, 10.0);
+    /// let camera = Camera::tween(&a, &b, 0.25);
+    /// assert!(camera.position().near(&Camera::interpolate(&a, &b, 0.25).position()));
+    /// ```
+    #[inline(always)]
+    pub fn tween(a: &Camera, b: &Camera, t: f64) -> Camera { Camera::interpolate(a, b, t) }
+
+    /// Moves the camera around `target` to the right by `horizontal` and up by `vertical`,
+    /// keeping the distance and the direction to `target` seen from the camera.
+    ///
+    /// The rotations are around the head direction and the right direction of the camera,
+    /// i.e. the orbit is the free rotation of the trackball.
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let mut camera = Camera::default();
+    /// camera.matrix = Matrix4::from_translation(Vector3::new(0.0, 0.0, 5.0));
+    /// camera.orbit(Point3::origin(), Rad(PI / 2.0), Rad(0.0));
+    /// assert!(camera.position().near(&Point3::new(5.0, 0.0, 0.0)));
+    /// assert!(camera.eye_direction().near(&-Vector3::unit_x()));
+    /// camera.orbit(Point3::origin(), Rad(0.0), Rad(PI / 2.0));
+    /// assert!(camera.position().near(&Point3::new(0.0, 5.0, 0.0)));
+    /// assert!(camera.eye_direction().near(&-Vector3::unit_y()));
+    /// ```
+    pub fn orbit<R: Into<Rad<f64>>>(&mut self, target: Point3, horizontal: R, vertical: R) {
+        let (horizontal, vertical) = (horizontal.into(), vertical.into());
+        let right = self.matrix[0].truncate();
+        let rotation = Matrix4::from_axis_angle(self.head_direction(), horizontal)
+            * Matrix4::from_axis_angle(right, -vertical);
+        self.matrix = Matrix4::from_translation(target.to_vec())
+            * rotation
+            * Matrix4::from_translation(-target.to_vec())
+            * self.matrix;
+    }
+
+    /// Moves the camera to the right by `right` and up by `up`, keeping the direction of the
+    /// camera.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let mut camera = Camera::default();
+    /// camera.orbit(Point3::new(0.0, 0.0, -5.0), Rad(1.0), Rad(0.5));
+    /// let (position, direction) = (camera.position(), camera.eye_direction());
+    /// camera.pan(1.0, 2.0);
+    /// let right = direction.cross(camera.head_direction());
+    /// assert!((camera.position() - position).near(&(right + 2.0 * camera.head_direction())));
+    /// assert!(camera.eye_direction().near(&direction));
+    /// ```
+    #[inline(always)]
+    pub fn pan(&mut self, right: f64, up: f64) {
+        let delta = self.matrix[0].truncate() * right + self.matrix[1].truncate() * up;
+        self.matrix = Matrix4::from_translation(delta) * self.matrix;
+    }
+
+    /// Zooms in to `target` by `ratio`, where the view is enlarged if `ratio` is less than `1`.
+    ///
+    /// The perspective camera moves toward `target` so that the distance becomes `ratio` times.
+    /// The parallel camera scales the screen size by `ratio` and moves in the plane of the
+    /// screen. In both cases, `target` stays at the same point on the screen.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let mut camera = Camera::default();
+    /// camera.matrix = Matrix4::from_translation(Vector3::new(0.0, 0.0, 4.0));
+    /// let target = Point3::new(1.0, 0.0, 0.0);
+    /// let uv = camera.projection(1.0).transform_point(target);
+    /// camera.zoom(target, 0.5);
+    /// assert!(camera.position().near(&Point3::new(0.5, 0.0, 2.0)));
+    /// let zoomed = camera.projection(1.0).transform_point(target);
+    /// assert!(zoomed.x.near(&uv.x) && zoomed.y.near(&uv.y));
+    ///
+    /// let mut camera = Camera::parallel_camera(Matrix4::identity(), 2.0, 0.1, 10.0);
+    /// camera.zoom(target, 0.5);
+    /// assert!(camera.screen_size().near(&1.0));
+    /// assert!(camera.position().near(&Point3::new(0.5, 0.0, 0.0)));
+    /// ```
+    pub fn zoom(&mut self, target: Point3, ratio: f64) {
+        let vec = self.position() - target;
+        let vec = match self.projection_type {
+            ProjectionType::Perspective => vec * ratio,
+            ProjectionType::Parallel => {
+                self.screen_size *= ratio;
+                let depth = self.eye_direction() * vec.dot(self.eye_direction());
+                depth + (vec - depth) * ratio
+            }
+        };
+        let delta = target + vec - self.position();
+        self.matrix = Matrix4::from_translation(delta) * self.matrix;
+    }
+
+    /// Returns the distance to the nearest face of the view volume.
+    #[inline(always)]
+    pub fn near_clip(&self) -> f64 { self.near_clip }
+    /// Returns the distance to the farthest face of the view volume.
+    #[inline(always)]
+    pub fn far_clip(&self) -> f64 { self.far_clip }
+    /// Returns the vertical field of view of the perspective camera.
+    /// Returns `None` if the camera is parallel.
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let camera = Camera::perspective_camera(Matrix4::identity(), Rad(PI / 3.0), 0.1, 10.0);
+    /// assert!(camera.field_of_view().unwrap().0.near(&(PI / 3.0)));
+    /// assert_eq!(camera.near_clip(), 0.1);
+    /// assert_eq!(camera.far_clip(), 10.0);
+    /// ```
+    #[inline(always)]
+    pub fn field_of_view(&self) -> Option<Rad<f64>> {
+        match self.projection_type {
+            ProjectionType::Perspective => Some(Rad(2.0 * (self.screen_size / 2.0).atan())),
+            ProjectionType::Parallel => None,
+        }
+    }
+    /// Returns the vertical size of the window of the view volume. In the perspective camera,
+    /// the window is on the plane at the distance 1 from the camera.
+    #[inline(always)]
+    pub fn screen_size(&self) -> f64 { self.screen_size }
+    /// Returns the center of the window of the view volume, which is zero if the camera
+    /// is not off-center.
+    #[inline(always)]
+    pub fn off_center(&self) -> Vector2 { self.off_center }
+    /// Returns the shift of the window per unit depth of the oblique projection.
+    #[inline(always)]
+    pub fn oblique(&self) -> Vector2 { self.oblique }
+    /// Returns the tile (column, row, number of divisions) of the sub frustum.
+    /// The whole view volume is `(0, 0, 1)`.
+    #[inline(always)]
+    pub fn tile(&self) -> (usize, usize, usize) { self.tile }
+    /// Returns the window `[left, right, bottom, top]` of the view volume with the aspect
+    /// ratio `as_rat`, without the division into the tiles.
+    #[inline(always)]
+    pub fn window(&self, as_rat: f64) -> [f64; 4] {
+        let (half_width, half_height) = (self.screen_size * as_rat / 2.0, self.screen_size / 2.0);
+        [
+            self.off_center.x - half_width,
+            self.off_center.x + half_width,
+            self.off_center.y - half_height,
+            self.off_center.y + half_height,
+        ]
+    }
+
+    // the projection from the camera space into the normalized view volume with the aspect 1
+    fn view_projection(&self) -> Matrix4 {
+        let (near_clip, far_clip) = (self.near_clip, self.far_clip);
+        let a = self.screen_size / 2.0;
+        let c = self.off_center;
+        let projection = match self.projection_type {
+            ProjectionType::Perspective => frustum(
+                near_clip * (c.x - a),
+                near_clip * (c.x + a),
+                near_clip * (c.y - a),
+                near_clip * (c.y + a),
+                near_clip,
+                far_clip,
+            ),
+            ProjectionType::Parallel => Matrix4::new(
+                1.0 / a, 0.0, 0.0, 0.0,
+                0.0, 1.0 / a, 0.0, 0.0,
+                0.0, 0.0, -1.0 / (far_clip - near_clip), 0.0,
+                -c.x / a, -c.y / a, -near_clip / (far_clip - near_clip), 1.0,
+            ),
+        };
+        let oblique = Matrix4::new(
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            -self.oblique.x, -self.oblique.y, 1.0, 0.0,
+            0.0, 0.0, 0.0, 1.0,
+        );
+        projection * oblique
+    }
+
+    // the map from the whole normalized view volume into the one of the tile
+    fn tile_matrix(&self) -> Matrix4 {
+        let (i, j, n) = self.tile;
+        let (i, j, n) = (i as f64, j as f64, n as f64);
+        Matrix4::new(
+            n, 0.0, 0.0, 0.0,
+            0.0, n, 0.0, 0.0,
+            0.0, 0.0, 1.0, 0.0,
+            n - 2.0 * i - 1.0, 2.0 * j + 1.0 - n, 0.0, 1.0,
+        )
+    }
+
     /// Returns the projection matrix into the normalized view volume.
     /// # Arguments
     /// `as_rat`: the aspect ratio, x-resolution / y-resulution.
@@ -225,15 +603,86 @@ impl Camera {
     /// ```
     #[inline(always)]
     pub fn projection(&self, as_rat: f64) -> Matrix4 {
-        Matrix4::from_nonuniform_scale(1.0 / as_rat, 1.0, 1.0)
-            * self.projection
+        self.tile_matrix()
+            * Matrix4::from_nonuniform_scale(1.0 / as_rat, 1.0, 1.0)
+            * self.view_projection()
             * self.matrix.invert().unwrap()
     }
 
-    fn camera_info(&self, as_rat: f64) -> CameraInfo {
+    /// Returns the ray through the point `(x, y)` in the normalized device coordinate,
+    /// e.g. the one of the cursor, as the pair of the origin and the direction.
+    ///
+    /// The origin is on the front of the rendered view volume, and the origin plus
+    /// the direction is on the back, i.e. the points of the parameters in `[0, 1]` are rendered.
+    /// # Arguments
+    /// `as_rat`: the aspect ratio, x-resolution / y-resulution.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::*};
+    /// use truck_platform::*;
+    /// let matrix = Matrix4::look_at(
+    ///     Point3::new(0.0, 0.0, 5.0),
+    ///     Point3::origin(),
+    ///     Vector3::new(0.0, 1.0, 0.0),
+    /// );
+    /// let camera = Camera::parallel_camera(matrix.invert().unwrap(), 2.0, 0.1, 10.0);
+    /// let (origin, dir) = camera.ray(0.5, -0.5, 1.0);
+    /// // The ray is parallel to the eye direction.
+    /// assert!(dir.normalize().near(&camera.eye_direction()));
+    /// assert!(f64::near(&origin.x, &0.5));
+    /// assert!(f64::near(&origin.y, &-0.5));
+    /// ```
+    pub fn ray(&self, x: f64, y: f64, as_rat: f64) -> (Point3, Vector3) {
+        let inverse = self.projection(as_rat).invert().unwrap();
+        let front = inverse.transform_point(Point3::new(x, y, 0.0));
+        let back = inverse.transform_point(Point3::new(x, y, 1.0));
+        (front, back - front)
+    }
+
+    /// Returns the projection matrix into the normalized view volume with the reversed depths,
+    /// i.e. the depth `z` of [`Camera::projection`] is replaced by `1 - z`, and the front of
+    /// the rendered view volume is mapped to the depth 1 and the back to 0.
+    /// cf. [`SceneDescriptor::reversed_z`]
+    /// # Arguments
+    /// `as_rat`: the aspect ratio, x-resolution / y-resulution.
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use truck_base::{cgmath64::*, tolerance::*};
+    /// use truck_platform::*;
+    /// let camera = Camera::perspective_camera(Matrix4::identity(), Rad(PI / 4.0), 0.1, 10.0);
+    /// let (front, direction) = camera.ray(0.0, 0.0, 1.0);
+    /// let projection = camera.reversed_z_projection(1.0);
+    /// assert!(f64::near(&projection.transform_point(front).z, &1.0));
+    /// assert!(f64::near(&projection.transform_point(front + direction).z, &0.0));
+    /// let pt = Point3::new(0.0, 0.0, -1.0);
+    /// let z = camera.projection(1.0).transform_point(pt).z;
+    /// assert!(f64::near(&projection.transform_point(pt).z, &(1.0 - z)));
+    /// ```
+    ///
+    /// [`Camera::projection`]: ./struct.Camera.html#method.projection
+    /// [`SceneDescriptor::reversed_z`]: ./struct.SceneDescriptor.html#structfield.reversed_z
+    #[inline(always)]
+    pub fn reversed_z_projection(&self, as_rat: f64) -> Matrix4 {
+        // z' = w - z
+        #[rustfmt::skip]
+        let reverse = Matrix4::new(
+            1.0, 0.0, 0.0, 0.0,
+            0.0, 1.0, 0.0, 0.0,
+            0.0, 0.0, -1.0, 0.0,
+            0.0, 0.0, 1.0, 1.0,
+        );
+        reverse * self.projection(as_rat)
+    }
+
+    pub(super) fn camera_info(&self, as_rat: f64, reversed_z: bool) -> CameraInfo {
+        let projection = match reversed_z {
+            true => self.reversed_z_projection(as_rat),
+            false => self.projection(as_rat),
+        };
         CameraInfo {
             camera_matrix: (&self.matrix).cast().unwrap().into(),
-            camera_projection: self.projection(as_rat).cast().unwrap().into(),
+            camera_projection: projection.cast().unwrap().into(),
         }
     }
 
@@ -249,7 +698,7 @@ impl Camera {
     /// };
     /// ```
     pub fn buffer(&self, as_rat: f64, device: &Device) -> BufferHandler {
-        BufferHandler::from_slice(&[self.camera_info(as_rat)], device, BufferUsage::UNIFORM)
+        BufferHandler::from_slice(&[self.camera_info(as_rat, false)], device, BufferUsage::UNIFORM)
     }
 }
 
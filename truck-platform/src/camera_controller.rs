@@ -0,0 +1,161 @@
+use crate::*;
+
+// the pixels of the scroll regarded as a line of the mouse wheel
+#[cfg(feature = "winit")]
+const PIXELS_PER_LINE: f64 = 20.0;
+
+impl CameraController {
+    /// Creates the controller orbiting around `target`.
+    #[inline(always)]
+    pub fn new(target: Point3) -> CameraController {
+        CameraController {
+            target,
+            orbit_speed: 0.01,
+            zoom_speed: 0.9,
+            window_height: 512.0,
+            drag: None,
+            cursor: None,
+        }
+    }
+
+    /// Returns the motion of the current dragging.
+    #[inline(always)]
+    pub fn drag(&self) -> Option<CameraDrag> { self.drag }
+
+    /// Starts dragging the cursor by `drag`, e.g. when a mouse button is pressed.
+    #[inline(always)]
+    pub fn begin_drag(&mut self, drag: CameraDrag) { self.drag = Some(drag); }
+
+    /// Stops dragging the cursor, e.g. when the mouse button is released.
+    #[inline(always)]
+    pub fn end_drag(&mut self) { self.drag = None; }
+
+    /// Moves the camera by the cursor moved to `position` in pixels, whose y-coordinate is
+    /// from the top of the window. Returns `true` if the camera is moved.
+    ///
+    /// In orbiting, the scene rotates in the direction of the motion of the cursor. In panning,
+    /// the scene moves along with the cursor, and the target moves together with the camera.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let mut camera = Camera::parallel_camera(Matrix4::identity(), 2.0, 0.1, 10.0);
+    /// let mut controller = CameraController::new(Point3::new(0.0, 0.0, -5.0));
+    /// controller.window_height = 400.0;
+    ///
+    /// // the first position of the cursor is only recorded.
+    /// assert!(!controller.cursor_moved(&mut camera, Vector2::new(100.0, 100.0)));
+    /// controller.begin_drag(CameraDrag::Pan);
+    /// // the cursor moves by a half of the height of the window.
+    /// assert!(controller.cursor_moved(&mut camera, Vector2::new(300.0, 100.0)));
+    /// assert!(camera.position().near(&Point3::new(-1.0, 0.0, 0.0)));
+    /// assert!(controller.target.near(&Point3::new(-1.0, 0.0, -5.0)));
+    ///
+    /// controller.begin_drag(CameraDrag::Orbit);
+    /// controller.cursor_moved(&mut camera, Vector2::new(400.0, 100.0));
+    /// // The camera still looks at the target.
+    /// let vec = controller.target - camera.position();
+    /// assert!(vec.magnitude().near(&5.0));
+    /// assert!(vec.normalize().near(&camera.eye_direction()));
+    /// controller.end_drag();
+    /// assert!(!controller.cursor_moved(&mut camera, Vector2::new(100.0, 100.0)));
+    /// ```
+    pub fn cursor_moved(&mut self, camera: &mut Camera, position: Vector2) -> bool {
+        let delta = match self.cursor.replace(position) {
+            Some(cursor) => position - cursor,
+            None => return false,
+        };
+        match self.drag {
+            Some(CameraDrag::Orbit) => {
+                let (horizontal, vertical) = (-delta.x, delta.y);
+                let speed = self.orbit_speed;
+                camera.orbit(self.target, Rad(horizontal * speed), Rad(vertical * speed));
+            }
+            Some(CameraDrag::Pan) => {
+                let height = match camera.projection_type() {
+                    ProjectionType::Perspective => {
+                        let depth = (self.target - camera.position()).dot(camera.eye_direction());
+                        camera.screen_size() * depth
+                    }
+                    ProjectionType::Parallel => camera.screen_size(),
+                };
+                let scale = height / self.window_height;
+                let position = camera.position();
+                camera.pan(-delta.x * scale, delta.y * scale);
+                self.target += camera.position() - position;
+            }
+            None => return false,
+        }
+        true
+    }
+
+    /// Zooms in to the target by the mouse wheel rolled forward by `lines`.
+    /// cf. [`Camera::zoom`]
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let mut camera = Camera::default();
+    /// camera.matrix = Matrix4::from_translation(Vector3::new(0.0, 0.0, 5.0));
+    /// let controller = CameraController::new(Point3::origin());
+    /// controller.wheel(&mut camera, 2.0);
+    /// assert!(camera.position().near(&Point3::new(0.0, 0.0, 5.0 * 0.81)));
+    /// controller.wheel(&mut camera, -2.0);
+    /// assert!(camera.position().near(&Point3::new(0.0, 0.0, 5.0)));
+    /// ```
+    ///
+    /// [`Camera::zoom`]: ./struct.Camera.html#method.zoom
+    #[inline(always)]
+    pub fn wheel(&self, camera: &mut Camera, lines: f64) {
+        camera.zoom(self.target, self.zoom_speed.powf(lines));
+    }
+
+    /// Moves the camera by the window event of `winit`. Returns `true` if the camera is moved.
+    ///
+    /// The left button orbits the camera, the right and the middle buttons pan the camera,
+    /// and the mouse wheel zooms the camera. The height of the window is updated by
+    /// the resizing of the window.
+    #[cfg(feature = "winit")]
+    pub fn handle_event(
+        &mut self,
+        camera: &mut Camera,
+        event: &winit::event::WindowEvent,
+    ) -> bool {
+        use winit::event::*;
+        match event {
+            WindowEvent::Resized(size) => {
+                self.window_height = f64::max(size.height as f64, 1.0);
+                false
+            }
+            WindowEvent::MouseInput { state, button, .. } => {
+                let drag = match button {
+                    MouseButton::Left => CameraDrag::Orbit,
+                    MouseButton::Right | MouseButton::Middle => CameraDrag::Pan,
+                    MouseButton::Other(_) => return false,
+                };
+                match state {
+                    ElementState::Pressed => self.begin_drag(drag),
+                    ElementState::Released if self.drag == Some(drag) => self.end_drag(),
+                    ElementState::Released => {}
+                }
+                false
+            }
+            WindowEvent::CursorMoved { position, .. } => {
+                self.cursor_moved(camera, Vector2::new(position.x, position.y))
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let lines = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y as f64,
+                    MouseScrollDelta::PixelDelta(position) => position.y / PIXELS_PER_LINE,
+                };
+                self.wheel(camera, lines);
+                true
+            }
+            WindowEvent::CursorLeft { .. } => {
+                self.cursor = None;
+                false
+            }
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,147 @@
+use crate::*;
+
+impl GroundShadow {
+    /// Creates the shadow under the models in the bounding box.
+    ///
+    /// The ground is the bottom of the bounding box, and the radius is 1.2 times the half of
+    /// the diagonal of the bounding box projected to the ground.
+    /// # Examples
+    /// ```
+    /// use std::iter::FromIterator;
+    /// use truck_base::{bounding_box::BoundingBox, cgmath64::*};
+    /// use truck_platform::*;
+    /// let bdd_box = BoundingBox::from_iter(&[
+    ///     Point3::new(-3.0, 1.0, -4.0),
+    ///     Point3::new(3.0, 2.0, 4.0),
+    /// ]);
+    /// let shadow = GroundShadow::from_bounding_box(&bdd_box, 0.5);
+    /// assert_eq!(shadow.center, Point3::new(0.0, 1.0, 0.0));
+    /// assert_eq!(shadow.radius, 6.0);
+    /// ```
+    #[inline(always)]
+    pub fn from_bounding_box(bdd_box: &BoundingBox<Point3>, opacity: f64) -> GroundShadow {
+        let (min, max) = (bdd_box.min(), bdd_box.max());
+        let (dx, dz) = (max[0] - min[0], max[2] - min[2]);
+        GroundShadow {
+            center: Point3::new((min[0] + max[0]) / 2.0, min[1], (min[2] + max[2]) / 2.0),
+            radius: 1.2 * f64::sqrt(dx * dx + dz * dz) / 2.0,
+            opacity,
+        }
+    }
+
+    #[inline(always)]
+    fn ground_shadow_info(&self) -> GroundShadowInfo {
+        GroundShadowInfo {
+            center: self.center.to_homogeneous().cast().unwrap().into(),
+            parameters: [self.radius as f32, self.opacity as f32, 0.0, 0.0],
+        }
+    }
+
+    #[inline(always)]
+    fn bgl_entry() -> PreBindGroupLayoutEntry {
+        PreBindGroupLayoutEntry {
+            visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+            ty: BindingType::UniformBuffer {
+                dynamic: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }
+    }
+}
+
+impl Scene {
+    // the pipeline and the bind group in `set = 1` of the ground shadow, if it is enabled
+    pub(super) fn ground_shadow_object(&self) -> Option<(Arc<RenderPipeline>, BindGroup)> {
+        let ground_shadow = self.scene_desc.ground_shadow?;
+        let handler = self.device_handler();
+        let layout = handler.cached_bind_group_layout(&[GroundShadow::bgl_entry()]);
+        let info = handler.ring_buffer_slice(&[ground_shadow.ground_shadow_info()]);
+        let bind_group = bind_group_util::create_bind_group(
+            self.device(),
+            &layout,
+            vec![info.binding_resource()],
+        );
+        let color_state = ColorStateDescriptor {
+            format: self.sc_desc().format,
+            color_blend: BlendDescriptor {
+                src_factor: BlendFactor::SrcAlpha,
+                dst_factor: BlendFactor::OneMinusSrcAlpha,
+                operation: BlendOperation::Add,
+            },
+            alpha_blend: BlendDescriptor {
+                src_factor: BlendFactor::One,
+                dst_factor: BlendFactor::OneMinusSrcAlpha,
+                operation: BlendOperation::Add,
+            },
+            write_mask: ColorWrite::ALL,
+        };
+        // the shadow does not hide the objects drawn later.
+        let depth_stencil_state = DepthStencilStateDescriptor {
+            format: TextureFormat::Depth32Float,
+            depth_write_enabled: false,
+            depth_compare: handler.depth_compare(CompareFunction::Less),
+            stencil: StencilStateDescriptor {
+                front: StencilStateFaceDescriptor::IGNORE,
+                back: StencilStateFaceDescriptor::IGNORE,
+                read_mask: 0,
+                write_mask: 0,
+            },
+        };
+        let sample_count = self.scene_desc.effective_sample_count();
+        let key = PipelineKey {
+            shader: "truck-platform/ground-shadow".to_string(),
+            vertex_layout: String::new(),
+            primitive_topology: PrimitiveTopology::TriangleStrip,
+            index_format: IndexFormat::Uint16,
+            cull_mode: CullMode::None,
+            depth_bias: (0, 0, 0),
+            color_states: vec![color_state.clone()],
+            depth_stencil_state: Some(depth_stencil_state.clone()),
+            sample_count,
+        };
+        let pipeline = handler.cached_pipeline(&key, || {
+            let device = handler.device();
+            let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
+                bind_group_layouts: &[&self.bind_group_layout, &layout],
+                push_constant_ranges: &[],
+                label: None,
+            });
+            let vertex_module =
+                device.create_shader_module(include_spirv!("shaders/ground-shadow.vert.spv"));
+            let fragment_module =
+                device.create_shader_module(include_spirv!("shaders/ground-shadow.frag.spv"));
+            Arc::new(device.create_render_pipeline(&RenderPipelineDescriptor {
+                layout: Some(&pipeline_layout),
+                vertex_stage: ProgrammableStageDescriptor {
+                    module: &vertex_module,
+                    entry_point: "main",
+                },
+                fragment_stage: Some(ProgrammableStageDescriptor {
+                    module: &fragment_module,
+                    entry_point: "main",
+                }),
+                rasterization_state: Some(RasterizationStateDescriptor {
+                    front_face: FrontFace::Ccw,
+                    cull_mode: CullMode::None,
+                    depth_bias: 0,
+                    depth_bias_slope_scale: 0.0,
+                    depth_bias_clamp: 0.0,
+                    clamp_depth: false,
+                }),
+                primitive_topology: PrimitiveTopology::TriangleStrip,
+                color_states: &[color_state],
+                depth_stencil_state: Some(depth_stencil_state),
+                vertex_state: VertexStateDescriptor {
+                    index_format: IndexFormat::Uint16,
+                    vertex_buffers: &[],
+                },
+                sample_count,
+                sample_mask: !0,
+                alpha_to_coverage_enabled: false,
+                label: None,
+            }))
+        });
+        Some((pipeline, bind_group))
+    }
+}
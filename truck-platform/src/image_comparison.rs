@@ -0,0 +1,81 @@
+use crate::*;
+
+impl ImageComparison {
+    /// Compares the image `actual` with the image `expected` in `Rgba8`.
+    /// # Panics
+    /// Panic occurs if the lengths of the images are different or not multiples of 4.
+    /// # Examples
+    /// ```
+    /// use truck_platform::ImageComparison;
+    /// let expected = vec![10, 20, 30, 255, 0, 0, 0, 255];
+    /// let actual = vec![12, 20, 30, 255, 0, 100, 0, 255];
+    /// let comparison = ImageComparison::new(&expected, &actual, [2, 2, 2, 0]);
+    /// assert_eq!(comparison.max_difference(), [2, 100, 0, 0]);
+    /// assert_eq!(comparison.num_different_pixels(), 1);
+    /// assert!(!comparison.is_within_tolerance());
+    /// // The different pixels are red in the diff image.
+    /// assert_eq!(&comparison.diff_image()[4..8], &[255, 0, 0, 255]);
+    ///
+    /// let comparison = ImageComparison::new(&expected, &expected, [0; 4]);
+    /// assert!(comparison.is_within_tolerance());
+    /// ```
+    pub fn new(expected: &[u8], actual: &[u8], tolerance: [u8; 4]) -> ImageComparison {
+        assert_eq!(
+            expected.len(),
+            actual.len(),
+            "the sizes of the images are different."
+        );
+        assert_eq!(expected.len() % 4, 0, "the images are not in Rgba8.");
+        let mut max_difference = [0; 4];
+        let mut num_different_pixels = 0;
+        let diff_image = expected
+            .chunks(4)
+            .zip(actual.chunks(4))
+            .flat_map(|(pixel0, pixel1)| {
+                let mut different = false;
+                for i in 0..4 {
+                    let diff = u8::max(pixel0[i], pixel1[i]) - u8::min(pixel0[i], pixel1[i]);
+                    max_difference[i] = u8::max(max_difference[i], diff);
+                    different = different || diff > tolerance[i];
+                }
+                match different {
+                    true => {
+                        num_different_pixels += 1;
+                        [255, 0, 0, 255]
+                    }
+                    false => {
+                        // the dark gray scale of the expected image
+                        let sum = pixel0[0] as u32 + pixel0[1] as u32 + pixel0[2] as u32;
+                        let gray = (sum / 12) as u8;
+                        [gray, gray, gray, 255]
+                    }
+                }
+                .to_vec()
+            })
+            .collect();
+        ImageComparison {
+            max_difference,
+            num_different_pixels,
+            diff_image,
+        }
+    }
+
+    /// Returns the maximum differences of the channels.
+    #[inline(always)]
+    pub fn max_difference(&self) -> [u8; 4] { self.max_difference }
+
+    /// Returns the number of the pixels whose differences exceed the tolerance.
+    #[inline(always)]
+    pub fn num_different_pixels(&self) -> usize { self.num_different_pixels }
+
+    /// Returns whether all pixels are within the tolerance.
+    #[inline(always)]
+    pub fn is_within_tolerance(&self) -> bool { self.num_different_pixels == 0 }
+
+    /// Returns the diff image in `Rgba8` with the same size as the compared images.
+    ///
+    /// The pixels exceeding the tolerance are red, and the others are
+    /// the dark gray scale of the expected image.
+    #[inline(always)]
+    pub fn diff_image(&self) -> &[u8] { &self.diff_image }
+}
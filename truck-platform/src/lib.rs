@@ -46,7 +46,7 @@ pub extern crate wgpu;
 use bytemuck::{Pod, Zeroable};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
-use truck_base::cgmath64::*;
+use truck_base::{bounding_box::BoundingBox, cgmath64::*};
 use wgpu::util::{BufferInitDescriptor, DeviceExt};
 use wgpu::*;
 
@@ -65,11 +65,33 @@ struct LightInfo {
     light_type: [u32; 4],
 }
 
+// the number of the clip planes sent to the shaders
+const MAX_CLIP_PLANES: usize = 6;
+
 #[repr(C)]
 #[derive(Clone, Copy, Debug, Zeroable, Pod)]
 struct SceneInfo {
     time: f32,
     num_of_lights: u32,
+    exposure: f32,
+    tone_mapping: u32,
+    num_of_clip_planes: u32,
+    _padding: [u32; 3],
+    clip_planes: [[f32; 4]; MAX_CLIP_PLANES],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod)]
+struct AmbientOcclusionInfo {
+    viewport: [f32; 4],
+    parameters: [f32; 4],
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod)]
+struct GroundShadowInfo {
+    center: [f32; 4],
+    parameters: [f32; 4],
 }
 
 /// safe handler of GPU buffer
@@ -147,13 +169,30 @@ pub struct PreBindGroupLayoutEntry {
 #[doc(hidden)]
 #[derive(Debug, Clone)]
 pub struct RenderObject {
-    vertex_buffer: Arc<BufferHandler>,
-    index_buffer: Option<Arc<BufferHandler>>,
+    vertex_buffers: Vec<(Arc<BufferHandler>, Option<Arc<BufferHandler>>)>,
     pipeline: Arc<RenderPipeline>,
     bind_group_layout: Arc<BindGroupLayout>,
     bind_group: Arc<BindGroup>,
+    picking: Option<(Arc<PickingMesh>, Matrix4)>,
+    // the pipeline without MSAA for the depth passes of the shadow maps
+    shadow_pipeline: Arc<RenderPipeline>,
+    // the sample count of `pipeline`
+    sample_count: u32,
+    draw_order: i32,
+    // re-creates the pipelines when the sample count is changed
+    pipeline_builder: Option<PipelineBuilder>,
 }
 
+/// The function creating the render pipeline of an object for a sample count of MSAA.
+///
+/// cf. [`Rendered::pipeline_builder`]
+///
+/// [`Rendered::pipeline_builder`]: ./trait.Rendered.html#method.pipeline_builder
+#[derive(Clone)]
+pub struct PipelineBuilder(
+    Arc<dyn Fn(&DeviceHandler, &PipelineLayout, u32) -> Arc<RenderPipeline> + Send + Sync>,
+);
+
 /// the projection type of camera
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ProjectionType {
@@ -172,8 +211,106 @@ pub struct Camera {
     ///
     /// This matrix must be in the Euclidean momentum group, the semi-direct product of O(3) and R^3.
     pub matrix: Matrix4,
-    projection: Matrix4,
     projection_type: ProjectionType,
+    // the height of the window of the view volume, on the plane at the distance 1 from
+    // the camera in the perspective camera
+    screen_size: f64,
+    // the center of the window, in the same unit as `screen_size`
+    off_center: Vector2,
+    // the shift of the window per unit depth of the oblique projection
+    oblique: Vector2,
+    near_clip: f64,
+    far_clip: f64,
+    // the tile (column, row, number of divisions) of the sub frustum
+    tile: (usize, usize, usize),
+}
+
+/// The field of view by the four angles from the eye direction, e.g. the one of a view of
+/// the OpenXR swapchain.
+///
+/// The angles are in radians, and `angle_left` and `angle_down` are usually negative.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FieldOfView {
+    /// the angle of the left side of the view volume
+    pub angle_left: f64,
+    /// the angle of the right side of the view volume
+    pub angle_right: f64,
+    /// the angle of the top side of the view volume
+    pub angle_up: f64,
+    /// the angle of the bottom side of the view volume
+    pub angle_down: f64,
+}
+
+/// The pair of the cameras of the left and right eyes for the stereo rendering.
+///
+/// The both eyes share the objects of a [`Scene`](./struct.Scene.html), and are rendered
+/// side by side to a texture or to the per-eye textures.
+#[derive(Debug, Clone)]
+pub struct StereoCamera {
+    /// the camera of the left eye
+    pub left: Camera,
+    /// the camera of the right eye
+    pub right: Camera,
+}
+
+/// First-person camera controller walking through the scene, for the architectural-scale
+/// model review.
+///
+/// The walker is a sphere colliding with the obstacles, the bounding boxes of the objects
+/// in the scene. The up direction is the y-axis. The camera matrix is created by
+/// [`WalkthroughController::camera_matrix`].
+///
+/// [`WalkthroughController::camera_matrix`]: ./struct.WalkthroughController.html#method.camera_matrix
+#[derive(Debug, Clone)]
+pub struct WalkthroughController {
+    position: Point3,
+    yaw: Rad<f64>,
+    pitch: Rad<f64>,
+    /// the radius of the sphere of the walker colliding with the obstacles
+    pub radius: f64,
+    /// the bounding boxes of the obstacles
+    pub obstacles: Vec<BoundingBox<Point3>>,
+}
+
+/// The motions of the camera by dragging the cursor in [`CameraController`].
+///
+/// [`CameraController`]: ./struct.CameraController.html
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CameraDrag {
+    /// orbits the camera around the target, cf. [`Camera::orbit`]
+    ///
+    /// [`Camera::orbit`]: ./struct.Camera.html#method.orbit
+    Orbit,
+    /// pans the camera along with the cursor, cf. [`Camera::pan`]
+    ///
+    /// [`Camera::pan`]: ./struct.Camera.html#method.pan
+    Pan,
+}
+
+/// Arcball camera controller, orbiting and panning by dragging the cursor, and zooming by
+/// the mouse wheel.
+///
+/// The controller is fed the inputs in pixels by [`CameraController::begin_drag`],
+/// [`CameraController::cursor_moved`] and [`CameraController::wheel`], or, with the feature
+/// `winit`, by the window events by [`CameraController::handle_event`].
+///
+/// [`CameraController::begin_drag`]: ./struct.CameraController.html#method.begin_drag
+/// [`CameraController::cursor_moved`]: ./struct.CameraController.html#method.cursor_moved
+/// [`CameraController::wheel`]: ./struct.CameraController.html#method.wheel
+/// [`CameraController::handle_event`]: ./struct.CameraController.html#method.handle_event
+#[derive(Debug, Clone)]
+pub struct CameraController {
+    /// the center of the orbit and the zoom
+    pub target: Point3,
+    /// the angle of the orbit in radians per pixel of the cursor. Default is `0.01`.
+    pub orbit_speed: f64,
+    /// the ratio of the zoom per line of the mouse wheel. Default is `0.9`.
+    pub zoom_speed: f64,
+    /// the height of the window in pixels, by which the scene is panned along with the cursor.
+    /// Default is `512`.
+    pub window_height: f64,
+    drag: Option<CameraDrag>,
+    cursor: Option<Vector2>,
 }
 
 /// the kinds of light sources: point or uniform
@@ -198,6 +335,106 @@ pub struct Light {
     pub color: Vector3,
     /// type of light source: point or uniform
     pub light_type: LightType,
+    /// If `true`, the point light casts the shadows in the scene with the shadow maps.
+    /// The uniform lights cast no shadows. Default is `false`.  
+    /// cf. [`RenderSettings::shadow_map`](./struct.RenderSettings.html#structfield.shadow_map)
+    pub cast_shadow: bool,
+}
+
+/// Configures of the shadow maps of the point lights.
+///
+/// The depths from each point light casting shadows are rendered to the six square faces of the
+/// cube around the light before the objects are rendered, and the default shaders of
+/// `truck-rendimpl` darken the pixels behind the depths by the 3x3 percentage-closer filtering.
+/// cf. [`ShadowMap::cube_cameras`](./struct.ShadowMap.html#method.cube_cameras)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShadowMap {
+    /// the width and the height of each face of the cube. Default is `1024`.
+    pub resolution: u32,
+    /// the distance from the light to the nearest face of the view volume of each face.
+    /// Default is `0.1`.
+    pub near_clip: f64,
+    /// the distance from the light to the farthest face of the view volume of each face.
+    /// Default is `100.0`.
+    pub far_clip: f64,
+}
+
+/// The curves mapping the exposed linear colors to the displayed range `[0, 1]`.
+///
+/// The tone mapping is applied by the default shaders of `truck-rendimpl` after the colors are
+/// multiplied by [`RenderSettings::exposure`].
+///
+/// [`RenderSettings::exposure`]: ./struct.RenderSettings.html#structfield.exposure
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ToneMapping {
+    /// The colors are clamped to `[0, 1]`.
+    Linear,
+    /// The Reinhard operator `c / (1 + c)`, which compresses the highlights softly.
+    Reinhard,
+    /// The fitted curve of the ACES filmic tone mapping by Krzysztof Narkowicz.
+    Aces,
+}
+
+/// Configures of the screen space ambient occlusion.
+///
+/// After the objects are rendered, each pixel is darkened according to the depths around it:
+/// the more the nearby surfaces cover the hemisphere above the pixel, the darker it gets.
+/// The background is not darkened.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AmbientOcclusion {
+    /// the radius of the sampled sphere in the world coordinate. Default is `0.5`.
+    pub radius: f64,
+    /// the strength of the darkening. Default is `1.0`.
+    pub intensity: f64,
+}
+
+/// The settings of the rendering of [`Scene`], which can be changed at runtime by
+/// [`Scene::set_render_settings`].
+///
+/// [`Scene`]: ./struct.Scene.html
+/// [`Scene::set_render_settings`]: ./struct.Scene.html#method.set_render_settings
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderSettings {
+    /// background color. Default is `Color::BLACK`.  
+    /// If the format of the target is `*Srgb`, the color is in the linear space.
+    pub background: Color,
+    /// sample count for anti-aliasing by MSAA. 1, 2, 4, 8, or 16. Default is `1`.
+    pub sample_count: u32,
+    /// the factor multiplied to the linear colors before the tone mapping. Default is `1.0`.
+    pub exposure: f64,
+    /// the tone mapping of the exposed colors. Default is `ToneMapping::Linear`.
+    pub tone_mapping: ToneMapping,
+    /// If `Some`, the creases of the models are darkened by the screen space ambient
+    /// occlusion. Default is `None`.
+    pub ambient_occlusion: Option<AmbientOcclusion>,
+    /// If `Some`, the point lights whose `cast_shadow` is `true` cast the shadows by the shadow
+    /// mapping. Default is `None`.
+    pub shadow_map: Option<ShadowMap>,
+    /// the clip planes `a` in the world coordinate, by which the points `p` with
+    /// `a.dot(p.to_homogeneous()) < 0` are cut away in the default shaders of the faces and
+    /// the polygons of `truck-rendimpl`. At most 6 planes are applied. Default is empty.  
+    /// cf. [`RenderSettings::clip_plane`](./struct.RenderSettings.html#method.clip_plane)
+    pub clip_planes: Vec<Vector4>,
+}
+
+/// Configures of the automatic fitting of the near and far clips to the visible models.
+///
+/// The back of the rendered view volume is put just behind the farthest corner of the bounding
+/// box of the objects, and the front just in front of the nearest one. Since the precision of
+/// the depths of the perspective camera is lost as the ratio of the back to the front grows,
+/// the distance to the front is bounded below by `min_near_ratio` times the one to the back,
+/// so that zooming into a small part of a large model keeps its precision. The ratio can be much smaller in
+/// the scene with the reversed depths. cf. [`AutoClip::fitted_camera`]
+///
+/// [`AutoClip::fitted_camera`]: ./struct.AutoClip.html#method.fitted_camera
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AutoClip {
+    /// the margin added to both sides of the range of the depths, relative to the diameter
+    /// of the bounding box. Default is `0.05`.
+    pub margin: f64,
+    /// the lower bound of the ratio of the distance to the front of the view volume to
+    /// the one to the back, for the perspective camera. Default is `1.0e-4`.
+    pub min_near_ratio: f64,
 }
 
 /// Chain that holds [`Device`], [`Queue`] and [`SwapChainDescriptor`].
@@ -205,6 +442,12 @@ pub struct Light {
 /// This struct is used for creating [`Scene`].
 /// [`Device`] and [`Queue`] must be wrapped `Arc`,
 /// and [`SwapChainDescriptor`] `Arc<Mutex>`.
+///
+/// The colors output by the shaders should be in the linear space, and be encoded to sRGB
+/// by the hardware with the `*Srgb` format of [`SwapChainDescriptor`],
+/// e.g. `TextureFormat::Bgra8UnormSrgb`. The same format should be used for the offscreen
+/// target textures. With the `*Unorm` formats, the linear colors are displayed as they are,
+/// and the images get darker.
 /// # Examples
 /// ```
 /// use std::sync::{Arc, Mutex};
@@ -255,6 +498,88 @@ pub struct DeviceHandler {
     device: Arc<Device>,
     queue: Arc<Queue>,
     sc_desc: Arc<Mutex<SwapChainDescriptor>>,
+    pipeline_cache: Arc<Mutex<HashMap<PipelineKey, Arc<RenderPipeline>>>>,
+    bind_group_layouts: Arc<Mutex<HashMap<Vec<BindGroupLayoutEntry>, Arc<BindGroupLayout>>>>,
+    uniform_ring: Arc<Mutex<UniformRing>>,
+    // whether the depths are reversed in the scene holding this handler
+    reversed_z: bool,
+}
+
+/// The suballocating ring buffer for the small buffers updated every frame.
+#[derive(Debug, Default)]
+struct UniformRing {
+    buffer: Option<Arc<Buffer>>,
+    capacity: u64,
+    cursor: u64,
+    // the bytes allocated since the last release
+    used: u64,
+}
+
+/// A slice of the ring buffer in [`DeviceHandler`] holding the uniform or storage data.
+///
+/// The slice is valid until the commands using it are submitted and
+/// the ring buffer is released by [`DeviceHandler::release_ring_buffer`].
+///
+/// [`DeviceHandler`]: ./struct.DeviceHandler.html
+/// [`DeviceHandler::release_ring_buffer`]: ./struct.DeviceHandler.html#method.release_ring_buffer
+#[derive(Debug, Clone)]
+pub struct RingBufferSlice {
+    buffer: Arc<Buffer>,
+    offset: u64,
+    size: u64,
+}
+
+/// The comparison of two images in `Rgba8` with the tolerance for each channel,
+/// for the golden-image tests of the scenes in the deterministic mode.
+///
+/// The pixels are regarded as the same if the differences of all channels
+/// are within the tolerance.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImageComparison {
+    max_difference: [u8; 4],
+    num_different_pixels: usize,
+    diff_image: Vec<u8>,
+}
+
+/// The error of [`DeviceHandler::request_device`].
+///
+/// [`DeviceHandler::request_device`]: ./struct.DeviceHandler.html#method.request_device
+#[derive(Debug)]
+pub enum RequestError {
+    /// No adapter is found.
+    AdapterNotFound,
+    /// The adapter fails to create the device.
+    RequestDevice(RequestDeviceError),
+}
+
+/// The key of the render pipelines cached in [`DeviceHandler`].
+///
+/// The pipelines with the same key are regarded as the same, so the identifiers `shader` and
+/// `vertex_layout` must determine the shader modules, the bind group layouts and the layout of
+/// the vertex buffers. The pipelines with the custom shader modules should not be cached
+/// unless the identifiers are unique to the modules.
+///
+/// [`DeviceHandler`]: ./struct.DeviceHandler.html
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PipelineKey {
+    /// identifier of the shader modules and the bind group layouts
+    pub shader: String,
+    /// identifier of the layout of the vertex buffers
+    pub vertex_layout: String,
+    /// primitive topology
+    pub primitive_topology: PrimitiveTopology,
+    /// the format of the indices, which determines the primitive restart value of the strips
+    pub index_format: IndexFormat,
+    /// culling mode
+    pub cull_mode: CullMode,
+    /// depth bias: the constant bias and the bits of the slope scale and the clamp in `f32`
+    pub depth_bias: (i32, u32, u32),
+    /// the states of the color targets: the formats and the blendings
+    pub color_states: Vec<ColorStateDescriptor>,
+    /// the state of the depth and stencil buffer
+    pub depth_stencil_state: Option<DepthStencilStateDescriptor>,
+    /// sample count for MSAA
+    pub sample_count: u32,
 }
 
 /// The unique ID for `Rendered` struct.
@@ -275,14 +600,81 @@ pub struct RenderID(usize);
 /// Configures of [`Scene`](./struct.Scene.html).
 #[derive(Debug, Clone)]
 pub struct SceneDescriptor {
-    /// background color. Default is `Color::BLACK`.
-    pub background: Color,
+    /// the settings of the rendering: the background, the sample count, the tone mapping,
+    /// the ambient occlusion, the shadow maps and the clip planes.
+    /// Default is `RenderSettings::default()`.  
+    /// cf. [`Scene::set_render_settings`](./struct.Scene.html#method.set_render_settings)
+    pub render_settings: RenderSettings,
     /// camera of the scene. Default is `Camera::default()`.
     pub camera: Camera,
     /// All lights in the scene. Default is `vec![Light::default()]`.
     pub lights: Vec<Light>,
-    /// sample count for anti-aliasing by MSAA. 1, 2, 4, 8, or 16.
-    pub sample_count: u32,
+    /// If `true`, the scene is rendered deterministically for the golden-image tests:
+    /// the time is fixed to zero, and MSAA is disabled since the sample positions depend on
+    /// the devices. Default is `false`.  
+    /// cf. [`ImageComparison`](./struct.ImageComparison.html)
+    pub deterministic: bool,
+    /// the named camera bookmarks switched by [`Scene::apply_view`] and
+    /// [`Scene::animate_view`]. Default is the standard views of the default camera
+    /// created by [`SceneDescriptor::standard_views`].
+    ///
+    /// [`Scene::apply_view`]: ./struct.Scene.html#method.apply_view
+    /// [`Scene::animate_view`]: ./struct.Scene.html#method.animate_view
+    /// [`SceneDescriptor::standard_views`]: ./struct.SceneDescriptor.html#method.standard_views
+    pub views: HashMap<String, Camera>,
+    /// If `Some`, the blurred contact shadow is rendered on the ground plane under the models.
+    /// Default is `None`.
+    pub ground_shadow: Option<GroundShadow>,
+    /// If `true`, the depths are reversed: the front of the rendered view volume is mapped to 1
+    /// and the back to 0, the depth buffers are cleared by 0, and the depth tests pass
+    /// the greater depths.
+    /// Since the precision of the floating point depths is concentrated around 0, the reversed
+    /// depths are almost uniformly precise and prevent the z-fighting of the long and thin
+    /// models viewed at a distance. Default is `false`.
+    ///
+    /// The pipelines are created according to [`DeviceHandler::depth_compare`], so the objects
+    /// added before switching this flag must be updated by [`Scene::update_pipelines`].
+    ///
+    /// [`DeviceHandler::depth_compare`]: ./struct.DeviceHandler.html#method.depth_compare
+    /// [`Scene::update_pipelines`]: ./struct.Scene.html#method.update_pipelines
+    pub reversed_z: bool,
+    /// If `Some`, the near and far clips of the camera are fitted to the bounding box of
+    /// the objects in each frame, instead of the ones of `camera`. Default is `None`.  
+    /// cf. [`Scene::rendered_camera`](./struct.Scene.html#method.rendered_camera)
+    pub auto_clip: Option<AutoClip>,
+}
+
+/// The blurred contact shadow on the ground plane, the cheap substitute of the shadow mapping
+/// for improving the spatial perception.
+///
+/// The shadow is the disk on the horizontal plane, which is darkest at the center and fades
+/// out to the circumference. It is rendered after the objects with the depth test, so that
+/// the objects on the ground hide the shadow. cf. [`GroundShadow::from_bounding_box`]
+///
+/// [`GroundShadow::from_bounding_box`]: ./struct.GroundShadow.html#method.from_bounding_box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GroundShadow {
+    /// the center of the shadow, whose y-coordinate is the height of the ground
+    pub center: Point3,
+    /// the radius of the shadow
+    pub radius: f64,
+    /// the opacity at the center of the shadow, in `[0, 1]`
+    pub opacity: f64,
+}
+
+/// The triangles of a rendered object in its local coordinate, for the picking by
+/// [`Scene::pick`].
+///
+/// The mesh is shared by the render object in the scene, and is returned by
+/// [`Rendered::picking_mesh`] together with the world matrix of the object.
+///
+/// [`Scene::pick`]: ./struct.Scene.html#method.pick
+/// [`Rendered::picking_mesh`]: ./trait.Rendered.html#method.picking_mesh
+#[derive(Debug, Clone)]
+pub struct PickingMesh {
+    positions: Vec<Point3>,
+    triangles: Vec<[usize; 3]>,
+    bounding_box: BoundingBox<Point3>,
 }
 
 /// Wraps `wgpu` and provides an intuitive graphics API.
@@ -294,13 +686,47 @@ pub struct SceneDescriptor {
 pub struct Scene {
     device_handler: DeviceHandler,
     objects: HashMap<RenderID, RenderObject>,
-    bind_group_layout: BindGroupLayout,
+    bind_group_layout: Arc<BindGroupLayout>,
     foward_depth: Texture,
     depth_texture_size: (u32, u32), // (width, height)
     sampling_buffer: Texture,
     previous_sample_count: u32,
     clock: std::time::Instant,
     scene_desc: SceneDescriptor,
+    device_lost_callback: Option<DeviceLostCallback>,
+    view_transition: Option<ViewTransition>,
+    // the internal target of the offscreen scene
+    offscreen_target: Option<Texture>,
+    shadow_textures: ShadowTextures,
+}
+
+// the textures of the shadow maps
+#[derive(Debug)]
+struct ShadowTextures {
+    // the depths of the shadow maps, whose six layers are the faces of the cube of each light
+    maps: Texture,
+    // (the resolution, the number of the layers)
+    size: (u32, u32),
+    // the unused color target of the depth passes
+    color: Texture,
+    color_format: TextureFormat,
+    // the maps bound in the depth passes instead of the rendered ones
+    dummy: Texture,
+    sampler: Sampler,
+    // whether the sampler compares the reversed depths
+    reversed_z: bool,
+}
+
+// the callback re-creating the render objects after the device is recovered
+struct DeviceLostCallback(Box<dyn FnMut(&mut Scene) + Send + Sync>);
+
+// the animated switching of the camera to a view
+#[derive(Debug, Clone)]
+struct ViewTransition {
+    from: Camera,
+    to: Camera,
+    start: std::time::Instant,
+    duration: std::time::Duration,
 }
 
 /// Rendered objects in the scene.
@@ -311,10 +737,26 @@ pub trait Rendered {
     /// Each object must have a RenderID to ensure that there are no duplicates.
     fn render_id(&self) -> RenderID;
     /// Creates the pair (vertex buffer, index buffer).
+    ///
+    /// The number of the drawn indices is determined by the stride of the index buffer,
+    /// so the index buffer can be created from the slice of `u16` or `u32`.
+    /// The format must coincide with the `index_format` of the pipeline.
     fn vertex_buffer(
         &self,
         device_handler: &DeviceHandler,
     ) -> (Arc<BufferHandler>, Option<Arc<BufferHandler>>);
+    /// Creates the chunks of the pairs (vertex buffer, index buffer).
+    ///
+    /// Each chunk is drawn by a separate draw call with the same pipeline and bind groups,
+    /// so a mesh exceeding the limits of the device can be rendered by splitting it into chunks.
+    /// The default implementation returns the only chunk created by `vertex_buffer`.
+    #[inline(always)]
+    fn vertex_buffers(
+        &self,
+        device_handler: &DeviceHandler,
+    ) -> Vec<(Arc<BufferHandler>, Option<Arc<BufferHandler>>)> {
+        vec![self.vertex_buffer(device_handler)]
+    }
     /// Creates the bind group layout.
     fn bind_group_layout(&self, device_handler: &DeviceHandler) -> Arc<BindGroupLayout>;
     /// Creates the bind group in `set = 1`.
@@ -330,9 +772,36 @@ pub trait Rendered {
         layout: &PipelineLayout,
         sample_count: u32,
     ) -> Arc<RenderPipeline>;
+    /// Returns the triangles in the local coordinate and the world matrix for the picking by
+    /// [`Scene::pick`]. The default implementation returns `None`, i.e. the object is not
+    /// picked.
+    ///
+    /// The matrix is updated by `Scene::update_bind_group`, and the mesh is updated by
+    /// `Scene::update_vertex_buffer`.
+    ///
+    /// [`Scene::pick`]: ./struct.Scene.html#method.pick
+    #[inline(always)]
+    fn picking_mesh(&self) -> Option<(Arc<PickingMesh>, Matrix4)> { None }
+    /// Returns the order of drawing in the render pass. The objects are drawn in the ascending
+    /// order, and the order of the objects of the same value is unspecified. Default is `0`.
+    ///
+    /// The order matters only for the objects referring the results of the others, e.g. the
+    /// lines tested against the depths of the invisible faces drawn in advance.
+    #[inline(always)]
+    fn draw_order(&self) -> i32 { 0 }
+    /// Returns the function creating the render pipeline of `self` for a sample count, e.g.
+    /// the one calling `Rendered::pipeline` of a clone of `self`. The scene keeps the function,
+    /// and re-creates the pipeline by it when the sample count of MSAA is changed by
+    /// [`Scene::set_render_settings`]. The default implementation returns `None`, i.e.
+    /// the pipeline must be updated by [`Scene::update_pipeline`].
+    ///
+    /// [`Scene::set_render_settings`]: ./struct.Scene.html#method.set_render_settings
+    /// [`Scene::update_pipeline`]: ./struct.Scene.html#method.update_pipeline
+    #[inline(always)]
+    fn pipeline_builder(&self) -> Option<PipelineBuilder> { None }
     #[doc(hidden)]
     fn render_object(&self, scene: &Scene) -> RenderObject {
-        let (vertex_buffer, index_buffer) = self.vertex_buffer(scene.device_handler());
+        let vertex_buffers = self.vertex_buffers(scene.device_handler());
         let bind_group_layout = self.bind_group_layout(scene.device_handler());
         let bind_group = self.bind_group(scene.device_handler(), &bind_group_layout);
         let pipeline_layout = scene
@@ -342,27 +811,46 @@ pub trait Rendered {
                 push_constant_ranges: &[],
                 label: None,
             });
-        let pipeline = self.pipeline(
-            &scene.device_handler(),
-            &pipeline_layout,
-            scene.scene_desc.sample_count,
-        );
+        let sample_count = scene.scene_desc.effective_sample_count();
+        let pipeline = self.pipeline(&scene.device_handler(), &pipeline_layout, sample_count);
+        let shadow_pipeline = match sample_count {
+            1 => Arc::clone(&pipeline),
+            _ => self.pipeline(&scene.device_handler(), &pipeline_layout, 1),
+        };
         RenderObject {
-            vertex_buffer,
-            index_buffer,
+            vertex_buffers,
             bind_group_layout,
             bind_group,
             pipeline,
+            picking: self.picking_mesh(),
+            shadow_pipeline,
+            sample_count,
+            draw_order: self.draw_order(),
+            pipeline_builder: self.pipeline_builder(),
         }
     }
 }
 
+mod adapter;
+mod ambient_occlusion;
+mod auto_clip;
 mod buffer_handler;
 mod camera;
+mod camera_controller;
+mod ground_shadow;
+mod image_comparison;
 mod light;
+mod offscreen;
+mod picking;
+mod render_settings;
+mod ring_buffer;
 #[doc(hidden)]
 pub mod rendered_macros;
 mod scene;
+mod shadow_map;
+mod stereo;
+mod views;
+mod walkthrough;
 
 #[doc(hidden)]
 pub mod bind_group_util {
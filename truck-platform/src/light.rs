@@ -20,7 +20,8 @@ impl Light {
     /// layout(// binding info //) uniform Light {
     ///     vec4 position;      // the position of light, position.w == 1.0
     ///     vec4 color;         // the color of light, color.w == 1.0
-    ///     uvec4 light_type;   // Point => uvec4(0, 0, 0, 0), Uniform => uvec4(1, 0, 0, 0)
+    ///     uvec4 light_type;   // Point => uvec4(0, 0, 0, 0), Uniform => uvec4(1, 0, 0, 0),
+    ///                         // the shadows are not configured in this buffer
     /// };
     /// ```
     #[inline(always)]
@@ -36,6 +37,7 @@ impl Default for Light {
             position: Point3::origin(),
             color: Vector3::new(1.0, 1.0, 1.0),
             light_type: LightType::Point,
+            cast_shadow: false,
         }
     }
 }
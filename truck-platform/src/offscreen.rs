@@ -0,0 +1,162 @@
+use crate::*;
+
+impl Scene {
+    #[inline(always)]
+    pub(super) fn offscreen_target(device: &Device, sc_desc: &SwapChainDescriptor) -> Texture {
+        device.create_texture(&TextureDescriptor {
+            size: Extent3d {
+                width: sc_desc.width,
+                height: sc_desc.height,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: sc_desc.format,
+            usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::COPY_SRC,
+            label: None,
+        })
+    }
+
+    /// Creates the headless scene rendering to the internal texture, e.g. for generating
+    /// the thumbnails on the servers.
+    ///
+    /// The new scene shares the device and the queue with `device_handler`, and has its own
+    /// swap chain descriptor of `width`, `height` and `format`, which is only the configure of
+    /// the internal texture. The scene descriptor is the default one, and is configured by
+    /// `Scene::descriptor_mut`. The rendered image is read by [`Scene::read_pixels`].
+    /// The zero sizes are replaced by one.
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_platform::*;
+    /// use wgpu::*;
+    /// let instance = Instance::new(BackendBit::PRIMARY);
+    /// let (device, queue, _) =
+    ///     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// let sc_desc = SwapChainDescriptor {
+    ///     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    ///     format: TextureFormat::Bgra8UnormSrgb,
+    ///     width: 1,
+    ///     height: 1,
+    ///     present_mode: PresentMode::Mailbox,
+    /// };
+    /// let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    ///
+    /// let mut scene = Scene::new_offscreen(&handler, 64, 32, TextureFormat::Bgra8Unorm);
+    /// scene.descriptor_mut().render_settings.background = Color::RED;
+    /// let pixels = scene.read_pixels();
+    /// assert_eq!(pixels.len(), 64 * 32 * 4);
+    /// // The pixels are in RGBA even if the format is BGRA.
+    /// assert_eq!(&pixels[..4], &[255, 0, 0, 255]);
+    /// ```
+    ///
+    /// [`Scene::read_pixels`]: ./struct.Scene.html#method.read_pixels
+    pub fn new_offscreen(
+        device_handler: &DeviceHandler,
+        width: u32,
+        height: u32,
+        format: TextureFormat,
+    ) -> Scene {
+        let sc_desc = SwapChainDescriptor {
+            usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::COPY_SRC,
+            format,
+            width: u32::max(width, 1),
+            height: u32::max(height, 1),
+            present_mode: PresentMode::Fifo,
+        };
+        let handler = DeviceHandler::new(
+            Arc::clone(device_handler.device()),
+            Arc::clone(device_handler.queue()),
+            Arc::new(Mutex::new(sc_desc)),
+        );
+        let mut scene = Scene::new(handler, &Default::default());
+        scene.offscreen_target = Some(Self::offscreen_target(scene.device(), &scene.sc_desc()));
+        scene
+    }
+
+    /// Returns `true` if the scene is created by [`Scene::new_offscreen`].
+    ///
+    /// [`Scene::new_offscreen`]: ./struct.Scene.html#method.new_offscreen
+    #[inline(always)]
+    pub fn is_offscreen(&self) -> bool { self.offscreen_target.is_some() }
+
+    /// Renders the scene to the offscreen texture, and reads back the pixels in `Rgba8`.
+    ///
+    /// Returns the pixels in the row-major order without the row paddings, whose size is the
+    /// one of the swap chain descriptor. The headless scene renders to its internal texture,
+    /// and the other scenes render to a temporary texture, e.g. for the screenshots of
+    /// the windows.
+    /// # Panics
+    /// Panic occurs if the format of the swap chain descriptor is not the one of the 8-bit
+    /// RGBA or BGRA.
+    pub fn read_pixels(&mut self) -> Vec<u8> {
+        let sc_desc = self.sc_desc();
+        let bgra = match sc_desc.format {
+            TextureFormat::Rgba8Unorm | TextureFormat::Rgba8UnormSrgb => false,
+            TextureFormat::Bgra8Unorm | TextureFormat::Bgra8UnormSrgb => true,
+            _ => panic!("The format of the scene is not the 8-bit RGBA or BGRA."),
+        };
+        // The internal texture is re-created in advance if the scene is resized.
+        self.update_textures();
+        let (texture, headless) = match self.offscreen_target.take() {
+            Some(texture) => (texture, true),
+            None => (Self::offscreen_target(self.device(), &sc_desc), false),
+        };
+        self.render_scene(&texture.create_view(&Default::default()));
+        let mut pixels = self.read_texture(&texture, &sc_desc);
+        if headless {
+            self.offscreen_target = Some(texture);
+        }
+        if bgra {
+            pixels.chunks_mut(4).for_each(|pixel| pixel.swap(0, 2));
+        }
+        pixels
+    }
+
+    fn read_texture(&self, texture: &Texture, sc_desc: &SwapChainDescriptor) -> Vec<u8> {
+        let (device, queue) = (self.device(), self.queue());
+        let size = Extent3d {
+            width: sc_desc.width,
+            height: sc_desc.height,
+            depth: 1,
+        };
+        let row_size = sc_desc.width * 4;
+        let bytes_per_row = (row_size + COPY_BYTES_PER_ROW_ALIGNMENT - 1)
+            / COPY_BYTES_PER_ROW_ALIGNMENT
+            * COPY_BYTES_PER_ROW_ALIGNMENT;
+        let buffer = device.create_buffer(&BufferDescriptor {
+            label: None,
+            size: (bytes_per_row * sc_desc.height) as u64,
+            usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+            mapped_at_creation: false,
+        });
+        let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+        encoder.copy_texture_to_buffer(
+            TextureCopyView {
+                texture,
+                mip_level: 0,
+                origin: Origin3d::ZERO,
+            },
+            BufferCopyView {
+                buffer: &buffer,
+                layout: TextureDataLayout {
+                    offset: 0,
+                    bytes_per_row,
+                    rows_per_image: sc_desc.height,
+                },
+            },
+            size,
+        );
+        queue.submit(vec![encoder.finish()]);
+
+        let buffer_slice = buffer.slice(..);
+        let buffer_future = buffer_slice.map_async(MapMode::Read);
+        device.poll(Maintain::Wait);
+        futures::executor::block_on(buffer_future).expect("failed to read the offscreen buffer.");
+        let data = buffer_slice.get_mapped_range();
+        data.chunks(bytes_per_row as usize)
+            .flat_map(|row| row[..row_size as usize].iter().copied())
+            .collect()
+    }
+}
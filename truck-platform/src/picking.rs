@@ -0,0 +1,157 @@
+use crate::*;
+
+impl PickingMesh {
+    /// Creates the picking mesh from the positions and the triangles of the indices.
+    /// # Panics
+    /// Panic occurs if an index is out of the range of the positions.
+    pub fn new(positions: Vec<Point3>, triangles: Vec<[usize; 3]>) -> PickingMesh {
+        assert!(
+            triangles.iter().flatten().all(|i| *i < positions.len()),
+            "the index of the triangle is out of range."
+        );
+        let bounding_box = positions.iter().collect();
+        PickingMesh {
+            positions,
+            triangles,
+            bounding_box,
+        }
+    }
+    /// Returns the positions.
+    #[inline(always)]
+    pub fn positions(&self) -> &[Point3] { &self.positions }
+    /// Returns the triangles of the indices.
+    #[inline(always)]
+    pub fn triangles(&self) -> &[[usize; 3]] { &self.triangles }
+
+    /// Returns the least parameter `t >= 0` of the points `origin + t * direction` on
+    /// the triangles. Both sides of the triangles are hit.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::*};
+    /// use truck_platform::PickingMesh;
+    /// let mesh = PickingMesh::new(
+    ///     vec![
+    ///         Point3::new(0.0, 0.0, 0.0),
+    ///         Point3::new(1.0, 0.0, 0.0),
+    ///         Point3::new(0.0, 1.0, 0.0),
+    ///         Point3::new(0.0, 0.0, -1.0),
+    ///         Point3::new(1.0, 0.0, -1.0),
+    ///         Point3::new(0.0, 1.0, -1.0),
+    ///     ],
+    ///     vec![[0, 1, 2], [3, 4, 5]],
+    /// );
+    /// let origin = Point3::new(0.25, 0.25, 2.0);
+    /// let t = mesh.ray_cast(origin, Vector3::new(0.0, 0.0, -2.0)).unwrap();
+    /// assert!(f64::near(&t, &1.0));
+    /// // The triangles behind the origin are not hit.
+    /// let t = mesh.ray_cast(origin, Vector3::new(0.0, 0.0, 2.0));
+    /// assert!(t.is_none());
+    /// // outside of the triangles
+    /// let t = mesh.ray_cast(Point3::new(0.75, 0.75, 2.0), Vector3::new(0.0, 0.0, -1.0));
+    /// assert!(t.is_none());
+    /// ```
+    pub fn ray_cast(&self, origin: Point3, direction: Vector3) -> Option<f64> {
+        if !self.hit_bounding_box(origin, direction) {
+            return None;
+        }
+        self.triangles
+            .iter()
+            .filter_map(|tri| {
+                let tri = [
+                    self.positions[tri[0]],
+                    self.positions[tri[1]],
+                    self.positions[tri[2]],
+                ];
+                ray_triangle(origin, direction, tri)
+            })
+            .fold(None, |min, t| match min {
+                Some(min) if min <= t => Some(min),
+                _ => Some(t),
+            })
+    }
+
+    // the slab test of the ray and the bounding box
+    fn hit_bounding_box(&self, origin: Point3, direction: Vector3) -> bool {
+        if self.bounding_box.is_empty() {
+            return false;
+        }
+        let (min, max) = (self.bounding_box.min(), self.bounding_box.max());
+        let (mut t0, mut t1) = (0.0, f64::INFINITY);
+        for i in 0..3 {
+            if direction[i] == 0.0 {
+                if origin[i] < min[i] || max[i] < origin[i] {
+                    return false;
+                }
+                continue;
+            }
+            let s0 = (min[i] - origin[i]) / direction[i];
+            let s1 = (max[i] - origin[i]) / direction[i];
+            t0 = f64::max(t0, f64::min(s0, s1));
+            t1 = f64::min(t1, f64::max(s0, s1));
+        }
+        t0 <= t1
+    }
+}
+
+// the Moller-Trumbore intersection
+fn ray_triangle(origin: Point3, direction: Vector3, [p0, p1, p2]: [Point3; 3]) -> Option<f64> {
+    let (edge0, edge1) = (p1 - p0, p2 - p0);
+    let h = direction.cross(edge1);
+    let det = edge0.dot(h);
+    if det.abs() <= f64::EPSILON * edge0.magnitude() * h.magnitude() {
+        return None;
+    }
+    let s = origin - p0;
+    let u = s.dot(h) / det;
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+    let q = s.cross(edge0);
+    let v = direction.dot(q) / det;
+    if v < 0.0 || u + v > 1.0 {
+        return None;
+    }
+    let t = edge1.dot(q) / det;
+    match t >= 0.0 {
+        true => Some(t),
+        false => None,
+    }
+}
+
+impl Scene {
+    /// Picks the object at `cursor` by the CPU ray casting, and returns the id of the object
+    /// and the picked point in the world coordinate.
+    ///
+    /// `cursor` is the position in pixels from the top-left corner of the target, e.g. the
+    /// position of the cursor in the window. The ray from [`Scene::rendered_camera`] is cast
+    /// against the meshes returned by [`Rendered::picking_mesh`], and the nearest object in
+    /// the rendered view volume is picked. Returns `None` if no object is hit.
+    ///
+    /// [`Scene::rendered_camera`]: ./struct.Scene.html#method.rendered_camera
+    /// [`Rendered::picking_mesh`]: ./trait.Rendered.html#method.picking_mesh
+    pub fn pick(&self, cursor: (f32, f32)) -> Option<(RenderID, Point3)> {
+        let sc_desc = self.sc_desc();
+        let (width, height) = (sc_desc.width as f64, sc_desc.height as f64);
+        let x = 2.0 * cursor.0 as f64 / width - 1.0;
+        let y = 1.0 - 2.0 * cursor.1 as f64 / height;
+        let (origin, direction) = self.rendered_camera().ray(x, y, width / height);
+        self.objects
+            .iter()
+            .filter_map(|(id, object)| {
+                let (mesh, matrix) = object.picking.as_ref()?;
+                let inverse = matrix.invert()?;
+                let local_origin = inverse.transform_point(origin);
+                let local_direction = inverse.transform_vector(direction);
+                let t = mesh.ray_cast(local_origin, local_direction)?;
+                match t <= 1.0 {
+                    true => Some((*id, t)),
+                    false => None,
+                }
+            })
+            .fold(None, |min: Option<(RenderID, f64)>, (id, t)| match min {
+                Some(min) if min.1 <= t => Some(min),
+                _ => Some((id, t)),
+            })
+            .map(|(id, t)| (id, origin + direction * t))
+    }
+}
@@ -0,0 +1,108 @@
+use crate::*;
+
+impl Default for RenderSettings {
+    #[inline(always)]
+    fn default() -> RenderSettings {
+        RenderSettings {
+            background: Color::BLACK,
+            sample_count: 1,
+            exposure: 1.0,
+            tone_mapping: ToneMapping::Linear,
+            ambient_occlusion: None,
+            shadow_map: None,
+            clip_planes: Vec::new(),
+        }
+    }
+}
+
+impl RenderSettings {
+    /// Returns the clip plane cutting away the points on the side of `normal` from
+    /// the plane through `origin`.
+    /// # Examples
+    /// ```
+    /// use truck_base::cgmath64::*;
+    /// use truck_platform::*;
+    /// let plane = RenderSettings::clip_plane(Point3::new(0.0, 1.0, 0.0), Vector3::unit_y());
+    /// // the point below the plane is kept
+    /// assert!(plane.dot(Vector4::new(0.0, 0.5, 0.0, 1.0)) > 0.0);
+    /// // the point above the plane is cut away
+    /// assert!(plane.dot(Vector4::new(3.0, 1.5, -2.0, 1.0)) < 0.0);
+    /// ```
+    #[inline(always)]
+    pub fn clip_plane(origin: Point3, normal: Vector3) -> Vector4 {
+        let normal = normal.normalize();
+        (-normal).extend(normal.dot(origin.to_vec()))
+    }
+
+    // the number of the applied clip planes and the planes sent to the shaders
+    pub(super) fn clip_plane_infos(&self) -> (u32, [[f32; 4]; MAX_CLIP_PLANES]) {
+        let mut infos = [[0.0; 4]; MAX_CLIP_PLANES];
+        let planes = self.clip_planes.iter().take(MAX_CLIP_PLANES);
+        infos
+            .iter_mut()
+            .zip(planes)
+            .for_each(|(info, plane)| *info = plane.cast().unwrap().into());
+        let len = usize::min(self.clip_planes.len(), MAX_CLIP_PLANES);
+        (len as u32, infos)
+    }
+}
+
+impl Scene {
+    /// Returns the current settings of the rendering.
+    #[inline(always)]
+    pub fn render_settings(&self) -> &RenderSettings { &self.scene_desc.render_settings }
+
+    /// Replaces the settings of the rendering, and re-creates the depth buffer, the MSAA
+    /// buffer and the shadow maps if they are changed.
+    ///
+    /// The background, the exposure, the tone mapping, the ambient occlusion and the clip
+    /// planes are applied from the next frame. Returns `true` if the sample count of MSAA is
+    /// changed, where the pipelines of the objects in the scene are re-created by
+    /// [`Rendered::pipeline_builder`]. The objects without the builders are not rendered until
+    /// their pipelines are updated by [`Scene::update_pipelines`].
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_platform::*;
+    /// use wgpu::*;
+    /// let instance = Instance::new(BackendBit::PRIMARY);
+    /// let (device, queue, _) =
+    ///     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// let sc_desc = SwapChainDescriptor {
+    ///     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    ///     format: TextureFormat::Bgra8UnormSrgb,
+    ///     width: 1,
+    ///     height: 1,
+    ///     present_mode: PresentMode::Mailbox,
+    /// };
+    /// let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// let mut scene = Scene::new_offscreen(&handler, 64, 32, TextureFormat::Rgba8Unorm);
+    ///
+    /// // The background is changed without rebuilding the pipelines.
+    /// let settings = RenderSettings {
+    ///     background: Color::RED,
+    ///     tone_mapping: ToneMapping::Aces,
+    ///     ..scene.render_settings().clone()
+    /// };
+    /// assert!(!scene.set_render_settings(settings));
+    /// assert_eq!(&scene.read_pixels()[..4], &[255, 0, 0, 255]);
+    ///
+    /// // The pipelines of the objects are re-created for the new sample count.
+    /// let settings = RenderSettings {
+    ///     sample_count: 4,
+    ///     ..scene.render_settings().clone()
+    /// };
+    /// assert!(scene.set_render_settings(settings));
+    /// ```
+    ///
+    /// [`Rendered::pipeline_builder`]: ./trait.Rendered.html#method.pipeline_builder
+    /// [`Scene::update_pipelines`]: ./struct.Scene.html#method.update_pipelines
+    pub fn set_render_settings(&mut self, settings: RenderSettings) -> bool {
+        let previous = self.scene_desc.effective_sample_count();
+        self.scene_desc.render_settings = settings;
+        self.update_textures();
+        let sample_count = self.scene_desc.effective_sample_count();
+        self.rebuild_pipelines();
+        sample_count != previous
+    }
+}
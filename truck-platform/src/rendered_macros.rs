@@ -21,6 +21,7 @@ macro_rules! derive_render_id {
 }
 
 /// Derives [`Rendred::vertex_buffer()`](./trait.Rendered.html#tymethod.vertex_buffer)
+/// and [`Rendred::vertex_buffers()`](./trait.Rendered.html#method.vertex_buffers)
 /// # Arguments
 /// `id_member`: the member variant of the super `Rendered` struct.
 #[macro_export]
@@ -34,6 +35,14 @@ macro_rules! derive_vertex_buffer {
         {
             self.$($id_member)*.vertex_buffer(device_handler)
         }
+        #[inline(always)]
+        fn vertex_buffers(
+            &self,
+            device_handler: &DeviceHandler,
+        ) -> Vec<(Arc<BufferHandler>, Option<Arc<BufferHandler>>)>
+        {
+            self.$($id_member)*.vertex_buffers(device_handler)
+        }
     };
 }
 
@@ -84,3 +93,33 @@ macro_rules! derive_pipeline {
         }
     };
 }
+
+/// Derives [`Rendred::picking_mesh()`](./trait.Rendered.html#method.picking_mesh)
+/// # Arguments
+/// `id_member`: the member variant of the super `Rendered` struct.
+#[macro_export]
+macro_rules! derive_picking_mesh {
+    ($($id_member: tt).*) => {
+        #[inline(always)]
+        fn picking_mesh(&self) -> Option<(Arc<PickingMesh>, Matrix4)> {
+            self.$($id_member)*.picking_mesh()
+        }
+    };
+}
+
+/// Implements [`Rendred::pipeline_builder()`](./trait.Rendered.html#method.pipeline_builder)
+/// by the pipeline of the duplication of the object.
+/// # Arguments
+/// `clone_method`: the method duplicating the object with the same pipeline.
+#[macro_export]
+macro_rules! impl_pipeline_builder {
+    ($clone_method: ident) => {
+        #[inline(always)]
+        fn pipeline_builder(&self) -> Option<PipelineBuilder> {
+            let object = self.$clone_method();
+            Some(PipelineBuilder::new(move |device_handler, layout, sample_count| {
+                object.pipeline(device_handler, layout, sample_count)
+            }))
+        }
+    };
+}
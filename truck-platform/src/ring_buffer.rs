@@ -0,0 +1,181 @@
+use crate::*;
+
+// the initial capacity of the ring buffer
+const INITIAL_CAPACITY: u64 = 1 << 16;
+// the minimum size of the slices, binding the empty arrays
+const MINIMUM_SIZE: u64 = 16;
+
+// `u64::div_ceil` and `u64::is_multiple_of` are too new for the supported compilers.
+#[inline(always)]
+#[allow(clippy::manual_div_ceil)]
+fn align(size: u64, alignment: u64) -> u64 { (size + alignment - 1) / alignment * alignment }
+
+impl UniformRing {
+    /// Returns the offset of the slice with `size` in the current buffer,
+    /// or `None` if the slice does not fit in the bytes released so far.
+    fn reserve(&mut self, size: u64) -> Option<u64> {
+        let tail = self.capacity - self.cursor;
+        // the tail is skipped if the slice does not fit it.
+        let need = match size <= tail {
+            true => size,
+            false => tail + size,
+        };
+        if self.capacity == 0 || self.used + need > self.capacity {
+            return None;
+        }
+        if size > tail {
+            self.used += tail;
+            self.cursor = 0;
+        }
+        let offset = self.cursor;
+        self.cursor += size;
+        self.used += size;
+        Some(offset)
+    }
+
+    /// Returns the capacity of the new buffer holding the slice with `size`.
+    fn grown_capacity(&self, size: u64) -> u64 {
+        let mut capacity = u64::max(self.capacity * 2, INITIAL_CAPACITY);
+        while capacity < size {
+            capacity *= 2;
+        }
+        capacity
+    }
+
+    /// Returns the buffer and the offset of the allocated slice.
+    fn allocate(&mut self, device: &Device, size: u64) -> (Arc<Buffer>, u64) {
+        let size = align(size, BIND_BUFFER_ALIGNMENT);
+        let offset = match self.reserve(size) {
+            Some(offset) => offset,
+            None => {
+                // The slices allocated since the last release may be in use,
+                // so the new buffer is created instead of overwriting them.
+                let capacity = self.grown_capacity(size);
+                self.buffer = Some(Arc::new(device.create_buffer(&BufferDescriptor {
+                    label: None,
+                    size: capacity,
+                    usage: BufferUsage::UNIFORM | BufferUsage::STORAGE | BufferUsage::COPY_DST,
+                    mapped_at_creation: false,
+                })));
+                self.capacity = capacity;
+                self.cursor = 0;
+                self.used = 0;
+                self.reserve(size).unwrap()
+            }
+        };
+        (Arc::clone(self.buffer.as_ref().unwrap()), offset)
+    }
+}
+
+impl DeviceHandler {
+    /// Writes `data` to a slice of the ring buffer shared by the clones of the device handler.
+    ///
+    /// The ring buffer suballocates the small uniform and storage data updated every frame,
+    /// e.g. the camera and the lights, instead of creating a new buffer for each of them.
+    /// The allocated slices are not overwritten until [`DeviceHandler::release_ring_buffer`]
+    /// is called. If the ring buffer is full, the larger buffer is created.
+    ///
+    /// [`DeviceHandler::release_ring_buffer`]: ./struct.DeviceHandler.html#method.release_ring_buffer
+    #[allow(clippy::manual_is_multiple_of)]
+    pub fn ring_buffer_slice<T: Pod>(&self, data: &[T]) -> RingBufferSlice {
+        let bytes: &[u8] = bytemuck::cast_slice(data);
+        let size = u64::max(
+            align(bytes.len() as u64, COPY_BUFFER_ALIGNMENT),
+            MINIMUM_SIZE,
+        );
+        let (buffer, offset) = self
+            .uniform_ring
+            .lock()
+            .unwrap()
+            .allocate(&self.device, size);
+        if bytes.len() as u64 % COPY_BUFFER_ALIGNMENT == 0 {
+            self.queue.write_buffer(&buffer, offset, bytes);
+        } else {
+            let mut padded = bytes.to_vec();
+            padded.resize(align(bytes.len() as u64, COPY_BUFFER_ALIGNMENT) as usize, 0);
+            self.queue.write_buffer(&buffer, offset, &padded);
+        }
+        RingBufferSlice {
+            buffer,
+            offset,
+            size,
+        }
+    }
+
+    /// Releases the slices of the ring buffer allocated so far, and makes them reusable.
+    ///
+    /// This method must be called after the commands using the slices are submitted to the queue.
+    /// `Scene::render_scene` calls this method after the submission.
+    #[inline(always)]
+    pub fn release_ring_buffer(&self) { self.uniform_ring.lock().unwrap().used = 0; }
+}
+
+impl RingBufferSlice {
+    /// Returns the reference of the whole ring buffer.
+    #[inline(always)]
+    pub fn buffer(&self) -> &Buffer { &self.buffer }
+
+    /// Returns the offset of the slice in the ring buffer.
+    #[inline(always)]
+    pub fn offset(&self) -> u64 { self.offset }
+
+    /// Returns the size of the slice.
+    #[inline(always)]
+    pub fn size(&self) -> u64 { self.size }
+
+    /// Creates a binding resource of the slice.
+    #[inline(always)]
+    pub fn binding_resource<'a>(&'a self) -> BindingResource<'a> {
+        BindingResource::Buffer(self.buffer.slice(self.offset..self.offset + self.size))
+    }
+}
+
+#[test]
+fn align_test() {
+    assert_eq!(align(0, 256), 0);
+    assert_eq!(align(1, 256), 256);
+    assert_eq!(align(256, 256), 256);
+    assert_eq!(align(257, 256), 512);
+    assert_eq!(align(6, 4), 8);
+}
+
+#[test]
+fn wrap_around_test() {
+    let mut ring = UniformRing {
+        capacity: 1024,
+        ..Default::default()
+    };
+    assert_eq!(ring.reserve(256), Some(0));
+    assert_eq!(ring.reserve(256), Some(256));
+    assert_eq!(ring.reserve(256), Some(512));
+    ring.used = 0;
+    // the tail of 256 bytes is skipped, and the slice starts at the head.
+    assert_eq!(ring.reserve(512), Some(0));
+    assert_eq!((ring.cursor, ring.used), (512, 768));
+    assert_eq!(ring.reserve(256), Some(512));
+    assert_eq!(ring.reserve(256), None);
+}
+
+#[test]
+fn exhaustion_test() {
+    let mut ring = UniformRing::default();
+    // no buffer yet
+    assert_eq!(ring.reserve(16), None);
+    assert_eq!(ring.grown_capacity(16), INITIAL_CAPACITY);
+
+    ring.capacity = 1024;
+    assert_eq!(ring.reserve(512), Some(0));
+    assert_eq!(ring.reserve(256), Some(512));
+    // the slices since the last release are not overwritten.
+    assert_eq!(ring.reserve(512), None);
+    assert_eq!(ring.reserve(256), Some(768));
+    assert_eq!(ring.reserve(256), None);
+    // the released bytes are reused.
+    ring.used = 0;
+    assert_eq!(ring.reserve(1024), Some(0));
+    assert_eq!(ring.reserve(256), None);
+
+    ring.capacity = INITIAL_CAPACITY;
+    assert_eq!(ring.grown_capacity(256), 2 * INITIAL_CAPACITY);
+    assert_eq!(ring.grown_capacity(5 * INITIAL_CAPACITY), 8 * INITIAL_CAPACITY);
+}
@@ -22,6 +22,10 @@ impl DeviceHandler {
             device,
             queue,
             sc_desc,
+            pipeline_cache: Default::default(),
+            bind_group_layouts: Default::default(),
+            uniform_ring: Default::default(),
+            reversed_z: false,
         }
     }
     /// Returns the reference of the device.
@@ -38,21 +42,152 @@ impl DeviceHandler {
     pub fn lock_sc_desc(&self) -> LockResult<MutexGuard<SwapChainDescriptor>> {
         self.sc_desc.lock()
     }
+    /// Returns the pipeline of `key` in the cache. If there is no pipeline of `key`,
+    /// creates the pipeline by `create` and caches it.
+    ///
+    /// The cache is shared by the clones of the device handler, so the instances with the same
+    /// states share the same pipeline. cf. [`PipelineKey`](./struct.PipelineKey.html)
+    pub fn cached_pipeline<F>(&self, key: &PipelineKey, create: F) -> Arc<RenderPipeline>
+    where F: FnOnce() -> Arc<RenderPipeline> {
+        if let Some(pipeline) = self.pipeline_cache.lock().unwrap().get(key) {
+            return Arc::clone(pipeline);
+        }
+        // The lock is released during the creation, since `create` may use the cache.
+        let pipeline = create();
+        let mut cache = self.pipeline_cache.lock().unwrap();
+        Arc::clone(cache.entry(key.clone()).or_insert(pipeline))
+    }
+    /// Returns whether the depths are reversed in the scene holding this handler.
+    ///
+    /// The flag is synchronized with [`SceneDescriptor::reversed_z`] by the scene.
+    ///
+    /// [`SceneDescriptor::reversed_z`]: ./struct.SceneDescriptor.html#structfield.reversed_z
+    #[inline(always)]
+    pub fn reversed_z(&self) -> bool { self.reversed_z }
+    /// Returns the depth comparison of the pipelines for `compare` in the standard depths,
+    /// i.e. `Less` passes the nearer fragments. If the depths are reversed, the comparison
+    /// is turned over, e.g. `Less` is replaced by `Greater`.
+    /// # Examples
+    /// ```
+    /// use truck_platform::*;
+    /// use wgpu::*;
+    /// use std::sync::{Arc, Mutex};
+    /// let instance = Instance::new(BackendBit::PRIMARY);
+    /// let (device, queue) = futures::executor::block_on(async {
+    ///     let adapter = instance
+    ///         .request_adapter(&RequestAdapterOptions {
+    ///             power_preference: PowerPreference::Default,
+    ///             compatible_surface: None,
+    ///         })
+    ///         .await
+    ///         .unwrap();
+    ///     adapter
+    ///         .request_device(&Default::default(), None)
+    ///         .await
+    ///         .unwrap()
+    /// });
+    /// let sc_desc = SwapChainDescriptor {
+    ///     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    ///     format: TextureFormat::Bgra8UnormSrgb,
+    ///     width: 512,
+    ///     height: 512,
+    ///     present_mode: PresentMode::Mailbox,
+    /// };
+    /// let handler = DeviceHandler::new(
+    ///     Arc::new(device),
+    ///     Arc::new(queue),
+    ///     Arc::new(Mutex::new(sc_desc)),
+    /// );
+    /// let scene_desc = SceneDescriptor {
+    ///     reversed_z: true,
+    ///     ..Default::default()
+    /// };
+    /// let scene = Scene::new(handler, &scene_desc);
+    /// let handler = scene.device_handler();
+    /// assert_eq!(handler.depth_compare(CompareFunction::Less), CompareFunction::Greater);
+    /// assert_eq!(handler.depth_compare(CompareFunction::Always), CompareFunction::Always);
+    /// ```
+    pub fn depth_compare(&self, compare: CompareFunction) -> CompareFunction {
+        match (self.reversed_z, compare) {
+            (false, _) => compare,
+            (true, CompareFunction::Less) => CompareFunction::Greater,
+            (true, CompareFunction::LessEqual) => CompareFunction::GreaterEqual,
+            (true, CompareFunction::Greater) => CompareFunction::Less,
+            (true, CompareFunction::GreaterEqual) => CompareFunction::LessEqual,
+            (true, _) => compare,
+        }
+    }
+    /// Returns the depth value to which the depth buffers are cleared, the farthest depth.
+    #[inline(always)]
+    pub fn farthest_depth(&self) -> f32 {
+        match self.reversed_z {
+            true => 0.0,
+            false => 1.0,
+        }
+    }
+    /// Returns the number of the cached pipelines.
+    #[inline(always)]
+    pub fn num_cached_pipelines(&self) -> usize { self.pipeline_cache.lock().unwrap().len() }
+    /// Clears the cache of the pipelines.
+    #[inline(always)]
+    pub fn clear_pipeline_cache(&self) { self.pipeline_cache.lock().unwrap().clear() }
+    /// Returns the bind group layout of `entries`. The layouts of the same entries are
+    /// created only once and shared by the clones of the device handler.
+    /// The binding numbers are the indices of the entries, as `bind_group_util::create_bind_group_layout`.
+    pub fn cached_bind_group_layout<'a, T>(&self, entries: T) -> Arc<BindGroupLayout>
+    where T: IntoIterator<Item = &'a PreBindGroupLayoutEntry> {
+        let entries: Vec<BindGroupLayoutEntry> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, e)| BindGroupLayoutEntry {
+                binding: i as u32,
+                visibility: e.visibility,
+                ty: e.ty.clone(),
+                count: e.count,
+            })
+            .collect();
+        let mut layouts = self.bind_group_layouts.lock().unwrap();
+        if let Some(layout) = layouts.get(&entries) {
+            return Arc::clone(layout);
+        }
+        let layout = Arc::new(
+            self.device
+                .create_bind_group_layout(&BindGroupLayoutDescriptor {
+                    label: None,
+                    entries: &entries,
+                }),
+        );
+        layouts.insert(entries, Arc::clone(&layout));
+        layout
+    }
 }
 
 impl Default for SceneDescriptor {
     #[inline(always)]
     fn default() -> SceneDescriptor {
         SceneDescriptor {
-            background: Color::BLACK,
+            render_settings: RenderSettings::default(),
             camera: Camera::default(),
             lights: vec![Light::default()],
-            sample_count: 1,
+            deterministic: false,
+            views: SceneDescriptor::standard_views(&Camera::default(), Point3::origin(), 5.0),
+            ground_shadow: None,
+            reversed_z: false,
+            auto_clip: None,
         }
     }
 }
 
 impl SceneDescriptor {
+    /// Returns the sample count of MSAA, which is 1 in the deterministic mode.
+    #[inline(always)]
+    pub(crate) fn effective_sample_count(&self) -> u32 {
+        match self.deterministic {
+            true => 1,
+            false => self.render_settings.sample_count,
+        }
+    }
+
     /// Creates a `UNIFORM` buffer of camera.
     ///
     /// The bind group provides [`Scene`] holds this uniform buffer.
@@ -68,7 +203,8 @@ impl SceneDescriptor {
     pub fn camera_buffer(&self, handler: &DeviceHandler) -> BufferHandler {
         let sc_desc = handler.sc_desc();
         let as_rat = sc_desc.width as f64 / sc_desc.height as f64;
-        self.camera.buffer(as_rat, handler.device())
+        let camera_info = self.camera.camera_info(as_rat, self.reversed_z);
+        BufferHandler::from_slice(&[camera_info], handler.device(), BufferUsage::UNIFORM)
     }
 
     /// Creates a `STORAGE` buffer of all lights.
@@ -80,7 +216,8 @@ impl SceneDescriptor {
     /// struct Light {
     ///     vec4 position;      // the position of light, position.w == 1.0
     ///     vec4 color;         // the color of light, color.w == 1.0
-    ///     uvec4 light_type;   // Point => uvec4(0, 0, 0, 0), Uniform => uvec4(1, 0, 0, 0)
+    ///     uvec4 light_type;   // [0]: Point => 0, Uniform => 1, [1]: 1 if casting shadows,
+    ///                         // [2]: the first layer of the shadow maps, [3]: the resolution
     /// };
     ///
     /// layout(set = 0, binding = 1) buffer Lights {
@@ -89,8 +226,7 @@ impl SceneDescriptor {
     /// ```
     #[inline(always)]
     pub fn lights_buffer(&self, device: &Device) -> BufferHandler {
-        let light_vec: Vec<_> = self.lights.iter().map(Light::light_info).collect();
-        BufferHandler::from_slice(&light_vec, device, BufferUsage::STORAGE)
+        BufferHandler::from_slice(&self.light_infos(), device, BufferUsage::STORAGE)
     }
 }
 
@@ -133,15 +269,16 @@ impl Scene {
     }
 
     #[inline(always)]
-    fn init_scene_bind_group_layout(device: &Device) -> BindGroupLayout {
-        bind_group_util::create_bind_group_layout(
-            device,
-            &[
-                Self::camera_bgl_entry(),
-                Self::lights_bgl_entry(),
-                Self::scene_bgl_entry(),
-            ],
-        )
+    fn init_scene_bind_group_layout(handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        let [maps, sampler, matrices] = Self::shadow_bgl_entries();
+        handler.cached_bind_group_layout(&[
+            Self::camera_bgl_entry(),
+            Self::lights_bgl_entry(),
+            Self::scene_bgl_entry(),
+            maps,
+            sampler,
+            matrices,
+        ])
     }
 
     #[inline(always)]
@@ -177,15 +314,17 @@ impl Scene {
             sample_count,
             dimension: TextureDimension::D2,
             format: TextureFormat::Depth32Float,
-            usage: TextureUsage::OUTPUT_ATTACHMENT,
+            // The depths are sampled by the ambient occlusion.
+            usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::SAMPLED,
             label: None,
         })
     }
 
     #[inline(always)]
-    fn update_textures(&mut self) {
+    pub(super) fn update_textures(&mut self) {
+        self.device_handler.reversed_z = self.scene_desc.reversed_z;
         let sc_desc = self.sc_desc();
-        let sample_count = self.scene_desc.sample_count;
+        let sample_count = self.scene_desc.effective_sample_count();
         if self.depth_texture_size != (sc_desc.width, sc_desc.height)
             || sample_count != self.previous_sample_count
         {
@@ -193,6 +332,25 @@ impl Scene {
             self.previous_sample_count = sample_count;
             self.foward_depth = Self::depth_texture(self.device(), &sc_desc, sample_count);
             self.sampling_buffer = Self::sampling_buffer(self.device(), &sc_desc, sample_count);
+            if self.offscreen_target.is_some() {
+                self.offscreen_target = Some(Self::offscreen_target(self.device(), &sc_desc));
+            }
+        }
+        self.update_shadow_textures();
+    }
+
+    // re-creates the pipelines of the objects by their builders if the sample count of MSAA
+    // is changed, e.g. by `Scene::descriptor_mut`
+    pub(super) fn rebuild_pipelines(&mut self) {
+        let sample_count = self.scene_desc.effective_sample_count();
+        let (handler, layout) = (&self.device_handler, &self.bind_group_layout);
+        for render_object in self.objects.values_mut() {
+            if render_object.sample_count == sample_count {
+                continue;
+            }
+            if let Some(builder) = render_object.pipeline_builder.clone() {
+                render_object.set_pipelines(handler, layout, sample_count, &*builder.0);
+            }
         }
     }
 
@@ -200,19 +358,85 @@ impl Scene {
     // About `scene_desc`, entity is better than reference for the performance.
     // This is referece because only for as wgpu is.
     #[inline(always)]
-    pub fn new(device_handler: DeviceHandler, scene_desc: &SceneDescriptor) -> Scene {
+    pub fn new(mut device_handler: DeviceHandler, scene_desc: &SceneDescriptor) -> Scene {
+        device_handler.reversed_z = scene_desc.reversed_z;
         let (device, sc_desc) = (device_handler.device(), device_handler.sc_desc());
-        let bind_group_layout = Self::init_scene_bind_group_layout(device);
+        let bind_group_layout = Self::init_scene_bind_group_layout(&device_handler);
+        let sample_count = scene_desc.effective_sample_count();
+        let shadow_textures = ShadowTextures::new(device, &sc_desc, scene_desc);
         Scene {
             objects: Default::default(),
             bind_group_layout,
-            foward_depth: Self::depth_texture(device, &sc_desc, scene_desc.sample_count),
+            foward_depth: Self::depth_texture(device, &sc_desc, sample_count),
             depth_texture_size: (sc_desc.width, sc_desc.height),
-            sampling_buffer: Self::sampling_buffer(device, &sc_desc, scene_desc.sample_count),
-            previous_sample_count: scene_desc.sample_count,
+            sampling_buffer: Self::sampling_buffer(device, &sc_desc, sample_count),
+            previous_sample_count: sample_count,
             clock: std::time::Instant::now(),
             scene_desc: scene_desc.clone(),
             device_handler,
+            device_lost_callback: None,
+            view_transition: None,
+            offscreen_target: None,
+            shadow_textures,
+        }
+    }
+
+    /// Resizes the swap chain descriptor, and re-creates the depth buffer and the MSAA buffer.
+    ///
+    /// The descriptor shared by the clones of the device handler is updated under the lock,
+    /// and the updated descriptor is returned for re-creating the swap chain.
+    /// The zero sizes, e.g. of the minimized windows, are replaced by one.
+    pub fn resize(&mut self, width: u32, height: u32) -> SwapChainDescriptor {
+        let sc_desc = {
+            let mut sc_desc = self.lock_sc_desc().unwrap();
+            sc_desc.width = u32::max(width, 1);
+            sc_desc.height = u32::max(height, 1);
+            sc_desc.clone()
+        };
+        self.update_textures();
+        sc_desc
+    }
+
+    /// Sets the callback invoked by [`Scene::recover_device`] for re-creating the render objects.
+    ///
+    /// [`Scene::recover_device`]: ./struct.Scene.html#method.recover_device
+    #[inline(always)]
+    pub fn set_device_lost_callback<F>(&mut self, callback: F)
+    where F: FnMut(&mut Scene) + Send + Sync + 'static {
+        self.device_lost_callback = Some(DeviceLostCallback(Box::new(callback)));
+    }
+
+    /// Recovers the scene from the lost device by the new device and queue.
+    ///
+    /// All resources created by the lost device are invalid, so the caches of pipelines and
+    /// bind group layouts are dropped, the attachments and the bind group layout of the scene
+    /// are re-created, and all render objects are removed. Then, the callback set by
+    /// [`Scene::set_device_lost_callback`] is invoked, which should re-create the objects by
+    /// the new device handler `Scene::device_handler()` and add them to the scene.
+    ///
+    /// The swap chain descriptor is shared with the old device handler.
+    ///
+    /// [`Scene::set_device_lost_callback`]: ./struct.Scene.html#method.set_device_lost_callback
+    pub fn recover_device(&mut self, device: Arc<Device>, queue: Arc<Queue>) {
+        let sc_desc = Arc::clone(&self.device_handler.sc_desc);
+        self.device_handler = DeviceHandler::new(device, queue, sc_desc);
+        self.device_handler.reversed_z = self.scene_desc.reversed_z;
+        self.bind_group_layout = Self::init_scene_bind_group_layout(&self.device_handler);
+        self.objects.clear();
+        let (sc_desc, sample_count) = (self.sc_desc(), self.scene_desc.effective_sample_count());
+        self.depth_texture_size = (sc_desc.width, sc_desc.height);
+        self.previous_sample_count = sample_count;
+        self.foward_depth = Self::depth_texture(self.device(), &sc_desc, sample_count);
+        self.sampling_buffer = Self::sampling_buffer(self.device(), &sc_desc, sample_count);
+        if self.offscreen_target.is_some() {
+            self.offscreen_target = Some(Self::offscreen_target(self.device(), &sc_desc));
+        }
+        self.shadow_textures = ShadowTextures::new(self.device(), &sc_desc, &self.scene_desc);
+        if let Some(mut callback) = self.device_lost_callback.take() {
+            (callback.0)(self);
+            if self.device_lost_callback.is_none() {
+                self.device_lost_callback = Some(callback);
+            }
         }
     }
 
@@ -237,14 +461,25 @@ impl Scene {
         self.device_handler.lock_sc_desc()
     }
     /// Returns the elapsed time since the scene was created.
+    /// In the deterministic mode, returns zero.
     #[inline(always)]
-    pub fn elapsed(&self) -> std::time::Duration { self.clock.elapsed() }
+    pub fn elapsed(&self) -> std::time::Duration {
+        match self.scene_desc.deterministic {
+            true => std::time::Duration::from_secs(0),
+            false => self.clock.elapsed(),
+        }
+    }
 
     /// Returns the reference of the descriptor.
     #[inline(always)]
     pub fn descriptor(&self) -> &SceneDescriptor { &self.scene_desc }
 
     /// Returns the mutable reference of the descriptor.
+    ///
+    /// If the sample count of MSAA is changed, the pipelines of the objects are re-created by
+    /// [`Rendered::pipeline_builder`] in the next rendering.
+    ///
+    /// [`Rendered::pipeline_builder`]: ./trait.Rendered.html#method.pipeline_builder
     #[inline(always)]
     pub fn descriptor_mut(&mut self) -> &mut SceneDescriptor { &mut self.scene_desc }
     /// Returns the bind group layout in the scene.
@@ -276,7 +511,8 @@ impl Scene {
     /// struct Light {
     ///     vec4 position;      // the position of light, position.w == 1.0
     ///     vec4 color;         // the color of light, color.w == 1.0
-    ///     uvec4 light_type;   // Point => uvec4(0, 0, 0, 0), Uniform => uvec4(1, 0, 0, 0)
+    ///     uvec4 light_type;   // [0]: Point => 0, Uniform => 1, [1]: 1 if casting shadows,
+    ///                         // [2]: the first layer of the shadow maps, [3]: the resolution
     /// };
     ///
     /// layout(set = 0, binding = 1) buffer Lights {
@@ -293,17 +529,32 @@ impl Scene {
     /// # Shader Example
     /// ```glsl
     /// layout(set = 0, binding = 2) uniform Scene {
-    ///     float time;     // elapsed time since the scene was created.
-    ///     uint nlights;   // the number of lights
+    ///     float time;             // elapsed time since the scene was created.
+    ///     uint nlights;           // the number of lights
+    ///     float exposure;         // the exposure of the render settings
+    ///     uint tone_mapping;      // Linear => 0, Reinhard => 1, Aces => 2
+    ///     uint nclip_planes;      // the number of the clip planes, at most 6
+    ///     vec4 clip_planes[6];    // the clip planes in the world coordinate
     /// };
     /// ```
     #[inline(always)]
     pub fn scene_status_buffer(&self) -> BufferHandler {
-        let scene_info = SceneInfo {
+        BufferHandler::from_slice(&[self.scene_info()], self.device(), BufferUsage::UNIFORM)
+    }
+
+    #[inline(always)]
+    fn scene_info(&self) -> SceneInfo {
+        let settings = &self.scene_desc.render_settings;
+        let (num_of_clip_planes, clip_planes) = settings.clip_plane_infos();
+        SceneInfo {
             time: self.elapsed().as_secs_f32(),
             num_of_lights: self.scene_desc.lights.len() as u32,
-        };
-        BufferHandler::from_slice(&[scene_info], self.device(), BufferUsage::UNIFORM)
+            exposure: settings.exposure as f32,
+            tone_mapping: settings.tone_mapping as u32,
+            num_of_clip_planes,
+            _padding: [0; 3],
+            clip_planes,
+        }
     }
 
     /// Creates bind group.
@@ -318,7 +569,8 @@ impl Scene {
     /// struct Light {
     ///     vec4 position;      // the position of light, position.w == 1.0
     ///     vec4 color;         // the color of light, color.w == 1.0
-    ///     uvec4 light_type;   // Point => uvec4(0, 0, 0, 0), Uniform => uvec4(1, 0, 0, 0)
+    ///     uvec4 light_type;   // [0]: Point => 0, Uniform => 1, [1]: 1 if casting shadows,
+    ///                         // [2]: the first layer of the shadow maps, [3]: the resolution
     /// };
     ///
     /// layout(set = 0, binding = 1) buffer Lights {
@@ -326,19 +578,61 @@ impl Scene {
     /// };
     ///
     /// layout(set = 0, binding = 2) uniform Scene {
-    ///     float time;     // elapsed time since the scene was created.
-    ///     uint nlights;   // the number of lights
+    ///     float time;             // elapsed time since the scene was created.
+    ///     uint nlights;           // the number of lights
+    ///     float exposure;         // the exposure of the render settings
+    ///     uint tone_mapping;      // Linear => 0, Reinhard => 1, Aces => 2
+    ///     uint nclip_planes;      // the number of the clip planes, at most 6
+    ///     vec4 clip_planes[6];    // the clip planes in the world coordinate
+    /// };
+    ///
+    /// // the depths of the shadow maps, whose six layers from `light_type[2]` are the faces of
+    /// // the cube around the light, cf. `ShadowMap::cube_cameras`
+    /// layout(set = 0, binding = 3) uniform texture2DArray shadow_maps;
+    /// // the sampler comparing the depths by `LessEqual`,
+    /// // or by `GreaterEqual` if the depths are reversed
+    /// layout(set = 0, binding = 4) uniform samplerShadow shadow_sampler;
+    ///
+    /// layout(set = 0, binding = 5) buffer ShadowMatrices {
+    ///     mat4 shadow_matrices[]; // the projections of the layers of the shadow maps
     /// };
     /// ```
     #[inline(always)]
     pub fn scene_bind_group(&self) -> BindGroup {
+        let sc_desc = self.sc_desc();
+        let as_rat = sc_desc.width as f64 / sc_desc.height as f64;
+        self.scene_bind_group_with_camera(&self.scene_desc.camera, as_rat)
+    }
+
+    // the scene bind group with `camera` instead of the one of the scene descriptor
+    pub(super) fn scene_bind_group_with_camera(&self, camera: &Camera, as_rat: f64) -> BindGroup {
+        let shadow_maps = self.shadow_textures.maps_view();
+        let camera = self.fitted_camera(camera);
+        let camera_info = camera.camera_info(as_rat, self.scene_desc.reversed_z);
+        self.scene_bind_group_with_info(camera_info, &shadow_maps)
+    }
+
+    // the scene bind group with the camera info and the view of the shadow maps
+    pub(super) fn scene_bind_group_with_info(
+        &self,
+        camera_info: CameraInfo,
+        shadow_maps: &TextureView,
+    ) -> BindGroup {
+        let handler = self.device_handler();
+        let camera = handler.ring_buffer_slice(&[camera_info]);
+        let lights = handler.ring_buffer_slice(&self.scene_desc.light_infos());
+        let scene_status = handler.ring_buffer_slice(&[self.scene_info()]);
+        let shadow_matrices = handler.ring_buffer_slice(&self.scene_desc.shadow_matrices());
         bind_group_util::create_bind_group(
             self.device(),
             &self.bind_group_layout,
             vec![
-                self.camera_buffer().binding_resource(),
-                self.lights_buffer().binding_resource(),
-                self.scene_status_buffer().binding_resource(),
+                camera.binding_resource(),
+                lights.binding_resource(),
+                scene_status.binding_resource(),
+                BindingResource::TextureView(shadow_maps),
+                BindingResource::Sampler(&self.shadow_textures.sampler),
+                shadow_matrices.binding_resource(),
             ],
         )
     }
@@ -349,6 +643,7 @@ impl Scene {
     /// replaces the render object and returns false.
     #[inline(always)]
     pub fn add_object<R: Rendered>(&mut self, object: &R) -> bool {
+        self.device_handler.reversed_z = self.scene_desc.reversed_z;
         let render_object = object.render_object(self);
         self.objects
             .insert(object.render_id(), render_object)
@@ -403,9 +698,8 @@ impl Scene {
         match objects.get_mut(&object.render_id()) {
             None => false,
             Some(render_object) => {
-                let (vb, ib) = object.vertex_buffer(handler);
-                render_object.vertex_buffer = vb;
-                render_object.index_buffer = ib;
+                render_object.vertex_buffers = object.vertex_buffers(handler);
+                render_object.picking = object.picking_mesh();
                 true
             }
         }
@@ -435,6 +729,7 @@ impl Scene {
             Some(render_object) => {
                 let bind_group = object.bind_group(handler, &render_object.bind_group_layout);
                 render_object.bind_group = bind_group;
+                render_object.picking = object.picking_mesh();
                 true
             }
             _ => false,
@@ -458,20 +753,17 @@ impl Scene {
     /// If there does not exist the render object in the scene, does nothing and returns false.
     #[inline(always)]
     pub fn update_pipeline<R: Rendered>(&mut self, object: &R) -> bool {
+        self.device_handler.reversed_z = self.scene_desc.reversed_z;
         let (handler, objects) = (&self.device_handler, &mut self.objects);
         match objects.get_mut(&object.render_id()) {
             Some(render_object) => {
-                let device = handler.device();
-                let pipeline_layout = device.create_pipeline_layout(&PipelineLayoutDescriptor {
-                    bind_group_layouts: &[
-                        &self.bind_group_layout,
-                        &render_object.bind_group_layout,
-                    ],
-                    push_constant_ranges: &[],
-                    label: None,
-                });
-                render_object.pipeline =
-                    object.pipeline(handler, &pipeline_layout, self.scene_desc.sample_count);
+                let sample_count = self.scene_desc.effective_sample_count();
+                let pipeline = |handler: &DeviceHandler, layout: &PipelineLayout, sample_count| {
+                    object.pipeline(handler, layout, sample_count)
+                };
+                let layout = &self.bind_group_layout;
+                render_object.set_pipelines(handler, layout, sample_count, &pipeline);
+                render_object.pipeline_builder = object.pipeline_builder();
                 true
             }
             _ => false,
@@ -492,11 +784,12 @@ impl Scene {
     #[inline(always)]
     fn depth_stencil_attachment_descriptor(
         depth_view: &TextureView,
+        farthest_depth: f32,
     ) -> RenderPassDepthStencilAttachmentDescriptor {
         RenderPassDepthStencilAttachmentDescriptor {
             attachment: depth_view,
             depth_ops: Some(Operations {
-                load: LoadOp::Clear(1.0),
+                load: LoadOp::Clear(farthest_depth),
                 store: true,
             }),
             stencil_ops: Some(Operations {
@@ -507,16 +800,37 @@ impl Scene {
     }
 
     /// Renders the scene to `view`.
+    ///
+    /// The animated switching of the camera by [`Scene::animate_view`] is updated in advance.
+    ///
+    /// [`Scene::animate_view`]: ./struct.Scene.html#method.animate_view
     pub fn render_scene(&mut self, view: &TextureView) {
-        self.update_textures();
+        self.update_view_transition();
         let bind_group = self.scene_bind_group();
+        self.render_viewports(view, &[(bind_group, None)]);
+    }
+
+    // Renders the objects to `view` for each pair of the scene bind group and the viewport
+    // `[x, y, width, height]` in one render pass. `None` is the whole target.
+    pub(super) fn render_viewports(
+        &mut self,
+        view: &TextureView,
+        viewports: &[(BindGroup, Option<[f32; 4]>)],
+    ) {
+        self.update_textures();
+        self.rebuild_pipelines();
+        let ground_shadow = self.ground_shadow_object();
         let depth_view = self.foward_depth.create_view(&Default::default());
         let sampled_view = self.sampling_buffer.create_view(&Default::default());
         let mut encoder = self
             .device()
             .create_command_encoder(&CommandEncoderDescriptor { label: None });
+        self.render_shadow_maps(&mut encoder);
+        let sample_count = self.scene_desc.effective_sample_count();
+        let mut objects: Vec<_> = self.objects.values().collect();
+        objects.sort_by_key(|object| object.draw_order);
         {
-            let (attachment, resolve_target) = match self.scene_desc.sample_count != 1 {
+            let (attachment, resolve_target) = match sample_count != 1 {
                 true => (&sampled_view, Some(view)),
                 false => (view, None),
             };
@@ -525,37 +839,100 @@ impl Scene {
                     attachment,
                     resolve_target,
                     ops: Operations {
-                        load: LoadOp::Clear(self.scene_desc.background),
+                        load: LoadOp::Clear(self.scene_desc.render_settings.background),
                         store: true,
                     },
                 }],
                 depth_stencil_attachment: Some(Self::depth_stencil_attachment_descriptor(
                     &depth_view,
+                    self.device_handler.farthest_depth(),
                 )),
             });
-            rpass.set_bind_group(0, &bind_group, &[]);
-            for (_, object) in self.objects.iter() {
-                rpass.set_pipeline(&object.pipeline);
-                rpass.set_bind_group(1, &object.bind_group, &[]);
-                rpass.set_vertex_buffer(0, object.vertex_buffer.buffer.slice(..));
-                match object.index_buffer {
-                    Some(ref index_buffer) => {
-                        rpass.set_index_buffer(index_buffer.buffer.slice(..));
-                        let index_size =
-                            index_buffer.size as u32 / std::mem::size_of::<u32>() as u32;
-                        rpass.draw_indexed(0..index_size, 0, 0..1);
-                    }
-                    None => {
-                        let len = object.vertex_buffer.size / object.vertex_buffer.stride;
-                        rpass.draw(0..len as u32, 0..1);
-                    }
+            for (bind_group, viewport) in viewports {
+                if let Some([x, y, width, height]) = viewport {
+                    rpass.set_viewport(*x, *y, *width, *height, 0.0, 1.0);
+                }
+                rpass.set_bind_group(0, bind_group, &[]);
+                // The objects without the pipeline builders are skipped until their pipelines
+                // are updated to the sample count.
+                for object in objects.iter().filter(|object| object.sample_count == sample_count) {
+                    rpass.set_pipeline(&object.pipeline);
+                    rpass.set_bind_group(1, &object.bind_group, &[]);
+                    object.draw(&mut rpass);
+                }
+                if let Some((pipeline, bind_group)) = &ground_shadow {
+                    rpass.set_pipeline(pipeline);
+                    rpass.set_bind_group(1, bind_group, &[]);
+                    rpass.draw(0..4, 0..1);
                 }
             }
         }
+        self.render_ambient_occlusion(&mut encoder, view, viewports);
         self.queue().submit(vec![encoder.finish()]);
+        self.device_handler.release_ring_buffer();
+    }
+}
+
+impl RenderObject {
+    // re-creates the pipelines by `pipeline` for `sample_count`
+    pub(super) fn set_pipelines(
+        &mut self,
+        handler: &DeviceHandler,
+        scene_layout: &BindGroupLayout,
+        sample_count: u32,
+        pipeline: &dyn Fn(&DeviceHandler, &PipelineLayout, u32) -> Arc<RenderPipeline>,
+    ) {
+        let pipeline_layout = handler.device().create_pipeline_layout(&PipelineLayoutDescriptor {
+            bind_group_layouts: &[scene_layout, &self.bind_group_layout],
+            push_constant_ranges: &[],
+            label: None,
+        });
+        self.pipeline = pipeline(handler, &pipeline_layout, sample_count);
+        self.shadow_pipeline = match sample_count {
+            1 => Arc::clone(&self.pipeline),
+            _ => pipeline(handler, &pipeline_layout, 1),
+        };
+        self.sample_count = sample_count;
+    }
+
+    // draws the vertex buffers after the pipeline and the bind groups are set
+    pub(super) fn draw<'a>(&'a self, rpass: &mut RenderPass<'a>) {
+        for (vertex_buffer, index_buffer) in &self.vertex_buffers {
+            rpass.set_vertex_buffer(0, vertex_buffer.buffer.slice(..));
+            match index_buffer {
+                Some(index_buffer) => {
+                    rpass.set_index_buffer(index_buffer.buffer.slice(..));
+                    let index_size = index_buffer.size / index_buffer.stride;
+                    rpass.draw_indexed(0..index_size as u32, 0, 0..1);
+                }
+                None => {
+                    let len = vertex_buffer.size / vertex_buffer.stride;
+                    rpass.draw(0..len as u32, 0..1);
+                }
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for DeviceLostCallback {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { f.pad("DeviceLostCallback") }
+}
+
+impl PipelineBuilder {
+    /// Creates the builder from the function returning the pipeline for
+    /// the pipeline layout and the sample count.
+    #[inline(always)]
+    pub fn new<F>(f: F) -> PipelineBuilder
+    where
+        F: Fn(&DeviceHandler, &PipelineLayout, u32) -> Arc<RenderPipeline> + Send + Sync + 'static, {
+        PipelineBuilder(Arc::new(f))
     }
 }
 
+impl std::fmt::Debug for PipelineBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { f.pad("PipelineBuilder") }
+}
+
 #[test]
 fn render_id_test() {
     use std::collections::HashSet;
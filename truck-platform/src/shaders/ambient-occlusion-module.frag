@@ -0,0 +1,94 @@
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 camera_matrix;
+    mat4 camera_projection;
+};
+
+layout(set = 1, binding = 0) uniform AmbientOcclusion {
+    vec4 viewport;      // (x, y, width, height) in the pixels
+    vec4 parameters;    // (radius, intensity, the depth of the background, 0.0)
+};
+
+#ifdef MULTISAMPLED
+layout(set = 1, binding = 1) uniform texture2DMS depths;
+#else
+layout(set = 1, binding = 1) uniform texture2D depths;
+#endif
+layout(set = 1, binding = 2) uniform sampler depth_sampler;
+
+layout(location = 0) out vec4 color;
+
+const int NUM_SAMPLES = 16;
+const float GOLDEN_ANGLE = 2.39996323;
+
+// the depth of the first sample of the pixel, or -1.0 if the pixel is out of the viewport
+float fetch_depth(ivec2 coord) {
+    vec2 rel = vec2(coord) - viewport.xy;
+    if (rel.x < 0.0 || rel.y < 0.0 || rel.x >= viewport.z || rel.y >= viewport.w) return -1.0;
+#ifdef MULTISAMPLED
+    return texelFetch(sampler2DMS(depths, depth_sampler), coord, 0).r;
+#else
+    return texelFetch(sampler2D(depths, depth_sampler), coord, 0).r;
+#endif
+}
+
+// Returns the world position of the pixel at `coord` whose depth is `depth`.
+vec3 world_position(mat4 inv_projection, ivec2 coord, float depth) {
+    vec2 ndc = (vec2(coord) + 0.5 - viewport.xy) / viewport.zw * 2.0 - 1.0;
+    vec4 position = inv_projection * vec4(ndc.x, -ndc.y, depth, 1.0);
+    return position.xyz / position.w;
+}
+
+// Returns the shorter difference to the neighbors on the axis, which avoids the silhouettes.
+vec3 tangent(mat4 inv_projection, ivec2 coord, vec3 position, ivec2 axis) {
+    vec3 diff = vec3(1.0e30);
+    float depth = fetch_depth(coord + axis);
+    if (depth >= 0.0) diff = world_position(inv_projection, coord + axis, depth) - position;
+    depth = fetch_depth(coord - axis);
+    if (depth >= 0.0) {
+        vec3 back = position - world_position(inv_projection, coord - axis, depth);
+        if (dot(back, back) < dot(diff, diff)) diff = back;
+    }
+    return diff;
+}
+
+bool is_background(float depth) { return depth < 0.0 || depth == parameters.z; }
+
+void main() {
+    ivec2 coord = ivec2(gl_FragCoord.xy);
+    float depth = fetch_depth(coord);
+    float radius = parameters.x;
+    color = vec4(1.0);
+    if (is_background(depth) || radius <= 0.0) return;
+
+    mat4 inv_projection = inverse(camera_projection);
+    vec3 position = world_position(inv_projection, coord, depth);
+    vec3 normal = normalize(cross(
+        tangent(inv_projection, coord, position, ivec2(1, 0)),
+        tangent(inv_projection, coord, position, ivec2(0, 1))
+    ));
+    if (dot(normal, camera_matrix[3].xyz - position) < 0.0) normal = -normal;
+
+    // the radius projected to the pixels
+    vec4 center = camera_projection * vec4(position, 1.0);
+    vec4 side = camera_projection * vec4(position + camera_matrix[0].xyz * radius, 1.0);
+    float pixel_radius = length(side.xy / side.w - center.xy / center.w) * 0.5 * viewport.z;
+    pixel_radius = clamp(pixel_radius, 2.0, 0.25 * viewport.w);
+
+    // the spiral of the samples rotated by the interleaved gradient noise
+    float noise = fract(52.9829189 * fract(dot(gl_FragCoord.xy, vec2(0.06711056, 0.00583715))));
+    float angle = 6.2831853 * noise;
+    float occlusion = 0.0;
+    for (int i = 0; i < NUM_SAMPLES; i++) {
+        float t = (float(i) + 0.5) / float(NUM_SAMPLES);
+        float theta = angle + float(i) * GOLDEN_ANGLE;
+        ivec2 offset = ivec2(round(vec2(cos(theta), sin(theta)) * t * pixel_radius));
+        float sample_depth = fetch_depth(coord + offset);
+        if (is_background(sample_depth)) continue;
+        vec3 v = world_position(inv_projection, coord + offset, sample_depth) - position;
+        float vv = dot(v, v);
+        float falloff = max(1.0 - vv / (radius * radius), 0.0);
+        occlusion += max(dot(v, normal) - 0.02 * radius, 0.0) / sqrt(vv + 1.0e-6) * falloff;
+    }
+    float ao = max(1.0 - parameters.y * 2.0 * occlusion / float(NUM_SAMPLES), 0.0);
+    color = vec4(ao, ao, ao, 1.0);
+}
@@ -0,0 +1,4 @@
+#version 450
+
+#define MULTISAMPLED
+#include "ambient-occlusion-module.frag"
@@ -0,0 +1,15 @@
+#version 450
+
+layout(set = 1, binding = 0) uniform GroundShadow {
+    vec4 center;        // the center of the shadow on the ground, center.w == 1.0
+    vec4 parameters;    // (radius, opacity, 0.0, 0.0)
+};
+
+layout(location = 0) in vec2 uv;
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    float d = clamp(1.0 - length(uv), 0.0, 1.0);
+    color = vec4(0.0, 0.0, 0.0, parameters.y * d * d);
+}
@@ -0,0 +1,22 @@
+#version 450
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 camera_matrix;
+    mat4 camera_projection;
+};
+
+layout(set = 1, binding = 0) uniform GroundShadow {
+    vec4 center;        // the center of the shadow on the ground, center.w == 1.0
+    vec4 parameters;    // (radius, opacity, 0.0, 0.0)
+};
+
+layout(location = 0) out vec2 uv;
+
+void main() {
+    // the corners of the quad drawn as the triangle strip
+    float x = float(gl_VertexIndex & 1) * 2.0 - 1.0;
+    float y = float((gl_VertexIndex >> 1) & 1) * 2.0 - 1.0;
+    vec4 position = center + vec4(x * parameters.x, 0.0, y * parameters.x, 0.0);
+    gl_Position = camera_projection * position;
+    uv = vec2(x, y);
+}
@@ -0,0 +1,275 @@
+use crate::*;
+use std::f64::consts::PI;
+use std::num::NonZeroU32;
+
+impl Default for ShadowMap {
+    #[inline(always)]
+    fn default() -> ShadowMap {
+        ShadowMap {
+            resolution: 1024,
+            near_clip: 0.1,
+            far_clip: 100.0,
+        }
+    }
+}
+
+impl ShadowMap {
+    /// Returns the cameras rendering the six faces of the cube around the light at `position`.
+    ///
+    /// The cameras look at +x, -x, +y, -y, +z and -z in turn, and each field of view is the
+    /// right angle.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let cameras = ShadowMap::default().cube_cameras(Point3::new(1.0, 2.0, 3.0));
+    /// assert!(cameras[0].eye_direction().near(&Vector3::unit_x()));
+    /// assert!(cameras[3].eye_direction().near(&-Vector3::unit_y()));
+    /// assert!(cameras[5].position().near(&Point3::new(1.0, 2.0, 3.0)));
+    /// ```
+    pub fn cube_cameras(&self, position: Point3) -> [Camera; 6] {
+        let camera = |dir: Vector3, up: Vector3| {
+            let matrix = Matrix4::look_at_rh(position, position + dir, up);
+            Camera::perspective_camera(
+                matrix.invert().unwrap(),
+                Rad(PI / 2.0),
+                self.near_clip,
+                self.far_clip,
+            )
+        };
+        [
+            camera(Vector3::unit_x(), Vector3::unit_y()),
+            camera(-Vector3::unit_x(), Vector3::unit_y()),
+            camera(Vector3::unit_y(), Vector3::unit_z()),
+            camera(-Vector3::unit_y(), Vector3::unit_z()),
+            camera(Vector3::unit_z(), Vector3::unit_y()),
+            camera(-Vector3::unit_z(), Vector3::unit_y()),
+        ]
+    }
+}
+
+impl SceneDescriptor {
+    // the point lights casting shadows, if the shadow maps are enabled
+    fn shadow_lights(&self) -> impl Iterator<Item = &Light> {
+        let enabled = self.render_settings.shadow_map.is_some();
+        self.lights.iter().filter(move |light| {
+            enabled && light.cast_shadow && light.light_type == LightType::Point
+        })
+    }
+
+    #[inline(always)]
+    fn shadow_layers(&self) -> u32 { u32::max(6 * self.shadow_lights().count() as u32, 6) }
+
+    // the cameras of all layers of the shadow maps
+    fn shadow_cameras(&self) -> Vec<Camera> {
+        match self.render_settings.shadow_map {
+            Some(shadow_map) => self
+                .shadow_lights()
+                .flat_map(|light| shadow_map.cube_cameras(light.position).to_vec())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub(super) fn light_infos(&self) -> Vec<LightInfo> {
+        let resolution = self
+            .render_settings
+            .shadow_map
+            .map(|shadow_map| shadow_map.resolution);
+        let mut layer = 0;
+        self.lights
+            .iter()
+            .map(|light| {
+                let mut info = light.light_info();
+                if let (Some(resolution), true) = (resolution, light.cast_shadow) {
+                    if light.light_type == LightType::Point {
+                        info.light_type = [info.light_type[0], 1, layer, resolution];
+                        layer += 6;
+                    }
+                }
+                info
+            })
+            .collect()
+    }
+
+    // the projections of the layers, padded by the identities since the buffer must not be empty
+    pub(super) fn shadow_matrices(&self) -> Vec<[[f32; 4]; 4]> {
+        let mut matrices: Vec<_> = self
+            .shadow_cameras()
+            .iter()
+            .map(|camera| camera.camera_info(1.0, self.reversed_z).camera_projection)
+            .collect();
+        let len = usize::max(matrices.len(), 6);
+        matrices.resize(len, Matrix4::identity().cast().unwrap().into());
+        matrices
+    }
+}
+
+impl ShadowTextures {
+    fn depth_texture(device: &Device, resolution: u32, layers: u32) -> Texture {
+        device.create_texture(&TextureDescriptor {
+            size: Extent3d {
+                width: resolution,
+                height: resolution,
+                depth: layers,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: TextureFormat::Depth32Float,
+            usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::SAMPLED,
+            label: None,
+        })
+    }
+
+    pub(super) fn new(
+        device: &Device,
+        sc_desc: &SwapChainDescriptor,
+        scene_desc: &SceneDescriptor,
+    ) -> ShadowTextures {
+        let resolution = scene_desc
+            .render_settings
+            .shadow_map
+            .map_or(1, |shadow_map| shadow_map.resolution);
+        let layers = scene_desc.shadow_layers();
+        let color = device.create_texture(&TextureDescriptor {
+            size: Extent3d {
+                width: resolution,
+                height: resolution,
+                depth: 1,
+            },
+            mip_level_count: 1,
+            sample_count: 1,
+            dimension: TextureDimension::D2,
+            format: sc_desc.format,
+            usage: TextureUsage::OUTPUT_ATTACHMENT,
+            label: None,
+        });
+        let sampler = device.create_sampler(&SamplerDescriptor {
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_w: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: FilterMode::Nearest,
+            lod_min_clamp: -100.0,
+            lod_max_clamp: 100.0,
+            compare: Some(match scene_desc.reversed_z {
+                true => CompareFunction::GreaterEqual,
+                false => CompareFunction::LessEqual,
+            }),
+            anisotropy_clamp: None,
+            label: None,
+        });
+        ShadowTextures {
+            maps: Self::depth_texture(device, resolution, layers),
+            size: (resolution, layers),
+            color,
+            color_format: sc_desc.format,
+            dummy: Self::depth_texture(device, 1, 6),
+            sampler,
+            reversed_z: scene_desc.reversed_z,
+        }
+    }
+
+    #[inline(always)]
+    fn array_view(texture: &Texture) -> TextureView {
+        texture.create_view(&TextureViewDescriptor {
+            dimension: Some(TextureViewDimension::D2Array),
+            ..Default::default()
+        })
+    }
+
+    #[inline(always)]
+    pub(super) fn maps_view(&self) -> TextureView { Self::array_view(&self.maps) }
+}
+
+impl Scene {
+    #[inline(always)]
+    pub(super) fn shadow_bgl_entries() -> [PreBindGroupLayoutEntry; 3] {
+        [
+            PreBindGroupLayoutEntry {
+                visibility: ShaderStage::FRAGMENT,
+                ty: BindingType::SampledTexture {
+                    dimension: TextureViewDimension::D2Array,
+                    component_type: TextureComponentType::Float,
+                    multisampled: false,
+                },
+                count: None,
+            },
+            PreBindGroupLayoutEntry {
+                visibility: ShaderStage::FRAGMENT,
+                ty: BindingType::Sampler { comparison: true },
+                count: None,
+            },
+            PreBindGroupLayoutEntry {
+                visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                ty: BindingType::StorageBuffer {
+                    dynamic: false,
+                    min_binding_size: None,
+                    readonly: true,
+                },
+                count: None,
+            },
+        ]
+    }
+
+    // re-creates the shadow maps if the resolution, the number of layers, the format or
+    // the direction of the depths is changed
+    pub(super) fn update_shadow_textures(&mut self) {
+        let resolution = self
+            .scene_desc
+            .render_settings
+            .shadow_map
+            .map_or(1, |shadow_map| shadow_map.resolution);
+        let size = (resolution, self.scene_desc.shadow_layers());
+        let sc_desc = self.sc_desc();
+        if self.shadow_textures.size != size
+            || self.shadow_textures.color_format != sc_desc.format
+            || self.shadow_textures.reversed_z != self.scene_desc.reversed_z
+        {
+            self.shadow_textures = ShadowTextures::new(self.device(), &sc_desc, &self.scene_desc);
+        }
+    }
+
+    // renders the depths from the lights to the layers of the shadow maps
+    pub(super) fn render_shadow_maps(&self, encoder: &mut CommandEncoder) {
+        let textures = &self.shadow_textures;
+        let dummy = ShadowTextures::array_view(&textures.dummy);
+        let color = textures.color.create_view(&Default::default());
+        for (layer, camera) in self.scene_desc.shadow_cameras().iter().enumerate() {
+            let camera_info = camera.camera_info(1.0, self.scene_desc.reversed_z);
+            let bind_group = self.scene_bind_group_with_info(camera_info, &dummy);
+            let depth = textures.maps.create_view(&TextureViewDescriptor {
+                dimension: Some(TextureViewDimension::D2),
+                base_array_layer: layer as u32,
+                array_layer_count: NonZeroU32::new(1),
+                ..Default::default()
+            });
+            let mut rpass = encoder.begin_render_pass(&RenderPassDescriptor {
+                color_attachments: &[RenderPassColorAttachmentDescriptor {
+                    attachment: &color,
+                    resolve_target: None,
+                    ops: Operations {
+                        load: LoadOp::Clear(Color::BLACK),
+                        store: false,
+                    },
+                }],
+                depth_stencil_attachment: Some(RenderPassDepthStencilAttachmentDescriptor {
+                    attachment: &depth,
+                    depth_ops: Some(Operations {
+                        load: LoadOp::Clear(self.device_handler.farthest_depth()),
+                        store: true,
+                    }),
+                    stencil_ops: None,
+                }),
+            });
+            rpass.set_bind_group(0, &bind_group, &[]);
+            for object in self.objects.values() {
+                rpass.set_pipeline(&object.shadow_pipeline);
+                rpass.set_bind_group(1, &object.bind_group, &[]);
+                object.draw(&mut rpass);
+            }
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use crate::*;
+
+impl FieldOfView {
+    /// Returns the aspect ratio, x-resolution / y-resolution, of the view volume.
+    ///
+    /// The texture of the eye should have this aspect ratio, since the projection is
+    /// based on the vertical direction of the screen.
+    /// # Examples
+    /// ```
+    /// use truck_platform::*;
+    /// let fov = FieldOfView {
+    ///     angle_left: -0.5,
+    ///     angle_right: 0.5,
+    ///     angle_up: 0.5,
+    ///     angle_down: -0.5,
+    /// };
+    /// assert_eq!(fov.aspect_ratio(), 1.0);
+    /// ```
+    #[inline(always)]
+    pub fn aspect_ratio(&self) -> f64 {
+        (self.angle_right.tan() - self.angle_left.tan())
+            / (self.angle_up.tan() - self.angle_down.tan())
+    }
+}
+
+impl Camera {
+    /// Creates a perspective camera with the asymmetric view volume, e.g. the one of
+    /// a view of the OpenXR swapchain.
+    /// # Arguments
+    /// * `matrix`: camera matrix, e.g. the pose of the view
+    /// * `fov`: the field of view
+    /// * `near_clip`: distance to the nearest face of the view volume
+    /// * `far_clip`: distance to the farthest face of the view volume
+    ///
+    /// The projection is based on the vertical direction of the screen, so the aspect ratio
+    /// of the target should be `fov.aspect_ratio()`.
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let fov = FieldOfView {
+    ///     angle_left: -PI / 8.0,
+    ///     angle_right: PI / 8.0,
+    ///     angle_up: PI / 8.0,
+    ///     angle_down: -PI / 8.0,
+    /// };
+    /// let camera = Camera::frustum_camera(Matrix4::identity(), fov, 0.1, 10.0);
+    /// // the symmetric view volume is the one of the perspective camera.
+    /// let perspective = Camera::perspective_camera(Matrix4::identity(), Rad(PI / 4.0), 0.1, 10.0);
+    /// assert!(camera.projection(1.0).near(&perspective.projection(1.0)));
+    /// assert_eq!(camera.projection_type(), ProjectionType::Perspective);
+    /// ```
+    #[inline(always)]
+    pub fn frustum_camera(
+        matrix: Matrix4,
+        fov: FieldOfView,
+        near_clip: f64,
+        far_clip: f64,
+    ) -> Camera {
+        let window = [
+            fov.angle_left.tan(),
+            fov.angle_right.tan(),
+            fov.angle_down.tan(),
+            fov.angle_up.tan(),
+        ];
+        Camera::window_camera(
+            matrix,
+            ProjectionType::Perspective,
+            window,
+            near_clip,
+            far_clip,
+        )
+    }
+}
+
+impl StereoCamera {
+    /// Creates the stereo pair with the parallel eye directions from the camera of the center.
+    ///
+    /// The eyes are translated by the half of `interpupillary_distance` along the x-axis
+    /// of the camera matrix, and have the same projection as `camera`.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let stereo = StereoCamera::new(&Camera::default(), 0.064);
+    /// assert!(stereo.left.position().near(&Point3::new(-0.032, 0.0, 0.0)));
+    /// assert!(stereo.right.position().near(&Point3::new(0.032, 0.0, 0.0)));
+    /// assert!(stereo.left.eye_direction().near(&stereo.right.eye_direction()));
+    /// ```
+    #[inline(always)]
+    pub fn new(camera: &Camera, interpupillary_distance: f64) -> StereoCamera {
+        let eye = |x: f64| Camera {
+            matrix: camera.matrix * Matrix4::from_translation(Vector3::new(x, 0.0, 0.0)),
+            ..camera.clone()
+        };
+        StereoCamera {
+            left: eye(-interpupillary_distance / 2.0),
+            right: eye(interpupillary_distance / 2.0),
+        }
+    }
+
+    /// Creates the stereo pair from the poses and the fields of view of the eyes,
+    /// e.g. the views located by OpenXR.
+    ///
+    /// The pose of OpenXR, the position `p` and the orientation `q`, is converted to
+    /// the camera matrix by `Matrix4::from_translation(p) * Matrix4::from(q)`.
+    #[inline(always)]
+    pub fn from_views(
+        left: (Matrix4, FieldOfView),
+        right: (Matrix4, FieldOfView),
+        near_clip: f64,
+        far_clip: f64,
+    ) -> StereoCamera {
+        StereoCamera {
+            left: Camera::frustum_camera(left.0, left.1, near_clip, far_clip),
+            right: Camera::frustum_camera(right.0, right.1, near_clip, far_clip),
+        }
+    }
+}
+
+impl Scene {
+    /// Renders the stereo pair side by side to `view` in one render pass.
+    ///
+    /// The left half of the target is the left eye, and the right half is the right eye.
+    /// The cameras of the scene descriptor are not used, and the aspect ratio of each eye
+    /// is the one of the half of the swap chain descriptor.
+    pub fn render_stereo_side_by_side(&mut self, view: &TextureView, stereo: &StereoCamera) {
+        self.update_textures();
+        let sc_desc = self.sc_desc();
+        let (width, height) = (sc_desc.width as f32 / 2.0, sc_desc.height as f32);
+        let as_rat = width as f64 / height as f64;
+        let viewports = [
+            (
+                self.scene_bind_group_with_camera(&stereo.left, as_rat),
+                Some([0.0, 0.0, width, height]),
+            ),
+            (
+                self.scene_bind_group_with_camera(&stereo.right, as_rat),
+                Some([width, 0.0, width, height]),
+            ),
+        ];
+        self.render_viewports(view, &viewports);
+    }
+
+    /// Renders the left and right eyes of the stereo pair to `left_view` and `right_view`
+    /// respectively, e.g. the images of the OpenXR swapchains.
+    ///
+    /// The size of the textures must be the one of the swap chain descriptor.
+    /// The cameras of the scene descriptor are not used.
+    pub fn render_stereo(
+        &mut self,
+        left_view: &TextureView,
+        right_view: &TextureView,
+        stereo: &StereoCamera,
+    ) {
+        self.update_textures();
+        let sc_desc = self.sc_desc();
+        let as_rat = sc_desc.width as f64 / sc_desc.height as f64;
+        let bind_group = self.scene_bind_group_with_camera(&stereo.left, as_rat);
+        self.render_viewports(left_view, &[(bind_group, None)]);
+        let bind_group = self.scene_bind_group_with_camera(&stereo.right, as_rat);
+        self.render_viewports(right_view, &[(bind_group, None)]);
+    }
+}
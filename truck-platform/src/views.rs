@@ -0,0 +1,154 @@
+use crate::*;
+use std::time::{Duration, Instant};
+
+impl SceneDescriptor {
+    /// Creates the standard views "front", "back", "top", "bottom", "left", "right" and "iso"
+    /// looking at `target` from `distance`.
+    ///
+    /// The views have the projection of `camera`. The front view looks at the negative
+    /// direction of the z-axis, the top view looks down with the head to the negative direction
+    /// of the z-axis, and the iso view looks from the direction `(1, 1, 1)`.
+    /// # Examples
+    /// ```
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let views = SceneDescriptor::standard_views(&Camera::default(), Point3::origin(), 5.0);
+    /// assert_eq!(views.len(), 7);
+    /// let top = &views["top"];
+    /// assert!(top.position().near(&Point3::new(0.0, 5.0, 0.0)));
+    /// assert!(top.eye_direction().near(&-Vector3::unit_y()));
+    /// assert!(top.head_direction().near(&-Vector3::unit_z()));
+    /// ```
+    pub fn standard_views(
+        camera: &Camera,
+        target: Point3,
+        distance: f64,
+    ) -> HashMap<String, Camera> {
+        let view = |direction: Vector3, up: Vector3| {
+            let eye = target + direction.normalize() * distance;
+            Camera {
+                matrix: Matrix4::look_at_rh(eye, target, up).invert().unwrap(),
+                ..camera.clone()
+            }
+        };
+        vec![
+            ("front", view(Vector3::unit_z(), Vector3::unit_y())),
+            ("back", view(-Vector3::unit_z(), Vector3::unit_y())),
+            ("top", view(Vector3::unit_y(), -Vector3::unit_z())),
+            ("bottom", view(-Vector3::unit_y(), Vector3::unit_z())),
+            ("left", view(-Vector3::unit_x(), Vector3::unit_y())),
+            ("right", view(Vector3::unit_x(), Vector3::unit_y())),
+            ("iso", view(Vector3::new(1.0, 1.0, 1.0), Vector3::unit_y())),
+        ]
+        .into_iter()
+        .map(|(name, camera)| (name.to_string(), camera))
+        .collect()
+    }
+}
+
+impl Scene {
+    /// Switches the camera to the view named `name` instantly.
+    ///
+    /// Returns `false` and does nothing if there is no such view in the descriptor.
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// use wgpu::*;
+    /// let instance = Instance::new(BackendBit::PRIMARY);
+    /// let (device, queue, _) =
+    ///     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// let sc_desc = SwapChainDescriptor {
+    ///     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    ///     format: TextureFormat::Rgba8UnormSrgb,
+    ///     width: 512,
+    ///     height: 512,
+    ///     present_mode: PresentMode::Mailbox,
+    /// };
+    /// let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// let mut scene = Scene::new(handler, &Default::default());
+    /// assert!(scene.apply_view("iso"));
+    /// let dir = -Vector3::new(1.0, 1.0, 1.0).normalize();
+    /// assert!(scene.descriptor().camera.eye_direction().near(&dir));
+    /// // user-defined views
+    /// scene.descriptor_mut().camera.matrix = Matrix4::from_translation(Vector3::unit_x());
+    /// scene.save_view("my view");
+    /// assert!(scene.apply_view("front"));
+    /// assert!(scene.apply_view("my view"));
+    /// assert_eq!(scene.descriptor().camera.position(), Point3::new(1.0, 0.0, 0.0));
+    /// assert!(!scene.apply_view("no view"));
+    /// ```
+    pub fn apply_view(&mut self, name: &str) -> bool {
+        match self.scene_desc.views.get(name) {
+            Some(camera) => {
+                self.scene_desc.camera = camera.clone();
+                self.view_transition = None;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Switches the camera to the view named `name` smoothly in `duration`.
+    ///
+    /// The camera is interpolated by [`Camera::interpolate`] with the ease-in-out in each
+    /// [`Scene::render_scene`] or [`Scene::update_view_transition`]. In the deterministic mode,
+    /// the camera is switched instantly. Returns `false` and does nothing if there is no such
+    /// view in the descriptor.
+    ///
+    /// [`Camera::interpolate`]: ./struct.Camera.html#method.interpolate
+    /// [`Scene::render_scene`]: ./struct.Scene.html#method.render_scene
+    /// [`Scene::update_view_transition`]: ./struct.Scene.html#method.update_view_transition
+    pub fn animate_view(&mut self, name: &str, duration: Duration) -> bool {
+        let camera = match self.scene_desc.views.get(name) {
+            Some(camera) => camera.clone(),
+            None => return false,
+        };
+        match self.scene_desc.deterministic {
+            true => {
+                self.scene_desc.camera = camera;
+                self.view_transition = None;
+            }
+            false => {
+                self.view_transition = Some(ViewTransition {
+                    from: self.scene_desc.camera.clone(),
+                    to: camera,
+                    start: Instant::now(),
+                    duration,
+                });
+            }
+        }
+        true
+    }
+
+    /// Saves the current camera as the view named `name`.
+    /// If there already exists the view with the same name, the view is overwritten.
+    #[inline(always)]
+    pub fn save_view<S: Into<String>>(&mut self, name: S) {
+        let camera = self.scene_desc.camera.clone();
+        self.scene_desc.views.insert(name.into(), camera);
+    }
+
+    /// Updates the camera by the animated switching of [`Scene::animate_view`].
+    ///
+    /// Returns `true` if the switching is in progress, i.e. the scene should be rendered again.
+    ///
+    /// [`Scene::animate_view`]: ./struct.Scene.html#method.animate_view
+    pub fn update_view_transition(&mut self) -> bool {
+        let transition = match &self.view_transition {
+            Some(transition) => transition,
+            None => return false,
+        };
+        let elapsed = transition.start.elapsed();
+        if elapsed >= transition.duration {
+            self.scene_desc.camera = transition.to.clone();
+            self.view_transition = None;
+            return false;
+        }
+        let t = elapsed.as_secs_f64() / transition.duration.as_secs_f64();
+        let t = t * t * (3.0 - 2.0 * t);
+        self.scene_desc.camera = Camera::interpolate(&transition.from, &transition.to, t);
+        true
+    }
+}
@@ -0,0 +1,127 @@
+use crate::*;
+
+// the limit of the pitch avoiding the degeneration at the zenith and the nadir
+const PITCH_LIMIT: f64 = std::f64::consts::PI / 2.0 - 1.0e-3;
+
+impl WalkthroughController {
+    /// Creates the controller at `position` looking at the negative direction of the z-axis.
+    ///
+    /// The radius of the walker is `0.3`, and there are no obstacles.
+    #[inline(always)]
+    pub fn new(position: Point3) -> WalkthroughController {
+        WalkthroughController {
+            position,
+            yaw: Rad(0.0),
+            pitch: Rad(0.0),
+            radius: 0.3,
+            obstacles: Vec::new(),
+        }
+    }
+
+    /// Returns the position of the eye.
+    #[inline(always)]
+    pub fn position(&self) -> Point3 { self.position }
+    /// Returns the angle of the rotation around the y-axis.
+    #[inline(always)]
+    pub fn yaw(&self) -> Rad<f64> { self.yaw }
+    /// Returns the angle of looking up.
+    #[inline(always)]
+    pub fn pitch(&self) -> Rad<f64> { self.pitch }
+
+    /// Returns the horizontal direction of walking forward.
+    #[inline(always)]
+    pub fn forward(&self) -> Vector3 { Vector3::new(-self.yaw.0.sin(), 0.0, -self.yaw.0.cos()) }
+    /// Returns the horizontal direction of walking right.
+    #[inline(always)]
+    pub fn right(&self) -> Vector3 { Vector3::new(self.yaw.0.cos(), 0.0, -self.yaw.0.sin()) }
+
+    /// Turns the eye by `yaw` around the y-axis and looks up by `pitch`.
+    /// The pitch is clamped so that the eye does not look at the zenith or the nadir.
+    #[inline(always)]
+    pub fn turn(&mut self, yaw: Rad<f64>, pitch: Rad<f64>) {
+        self.yaw += yaw;
+        self.pitch = Rad(f64::max(
+            -PITCH_LIMIT,
+            f64::min(PITCH_LIMIT, self.pitch.0 + pitch.0),
+        ));
+    }
+
+    /// Walks `forward` and `right` in the horizontal plane, see [`translate`](#method.translate).
+    #[inline(always)]
+    pub fn walk(&mut self, forward: f64, right: f64) -> bool {
+        self.translate(self.forward() * forward + self.right() * right)
+    }
+
+    /// Moves the walker by `delta` avoiding the obstacles.
+    ///
+    /// If the walker collides with an obstacle, the walker slides along the obstacle, i.e. moves
+    /// by the components of `delta` along the axes which do not cause the collision.
+    /// Returns `false` if the walker cannot move by the whole `delta`.
+    /// # Examples
+    /// ```
+    /// use std::iter::FromIterator;
+    /// use truck_base::{bounding_box::BoundingBox, cgmath64::*};
+    /// use truck_platform::*;
+    /// let mut walker = WalkthroughController::new(Point3::new(0.0, 1.5, 0.0));
+    /// // the wall in front of the walker
+    /// walker.obstacles.push(BoundingBox::from_iter(&[
+    ///     Point3::new(-10.0, 0.0, -2.1),
+    ///     Point3::new(10.0, 3.0, -2.0),
+    /// ]));
+    /// assert!(walker.walk(1.0, 0.0));
+    /// assert_eq!(walker.position(), Point3::new(0.0, 1.5, -1.0));
+    /// // the walker stops at the wall and slides along it.
+    /// assert!(!walker.translate(Vector3::new(1.0, 0.0, -1.0)));
+    /// assert_eq!(walker.position(), Point3::new(1.0, 1.5, -1.0));
+    /// ```
+    pub fn translate(&mut self, delta: Vector3) -> bool {
+        let position = self.position + delta;
+        if !self.collides(position) {
+            self.position = position;
+            return true;
+        }
+        (0..3).for_each(|i| {
+            let mut position = self.position;
+            position[i] += delta[i];
+            if !self.collides(position) {
+                self.position = position;
+            }
+        });
+        false
+    }
+
+    /// Returns whether the walker at `position` collides with an obstacle.
+    #[inline(always)]
+    pub fn collides(&self, position: Point3) -> bool {
+        self.obstacles.iter().any(|bdd_box| {
+            let (min, max) = (bdd_box.min(), bdd_box.max());
+            let nearest = Point3::new(
+                f64::max(min[0], f64::min(max[0], position[0])),
+                f64::max(min[1], f64::min(max[1], position[1])),
+                f64::max(min[2], f64::min(max[2], position[2])),
+            );
+            !bdd_box.is_empty() && position.distance2(nearest) < self.radius * self.radius
+        })
+    }
+
+    /// Returns the camera matrix of the eye.
+    /// # Examples
+    /// ```
+    /// use std::f64::consts::PI;
+    /// use truck_base::{cgmath64::*, tolerance::Tolerance};
+    /// use truck_platform::*;
+    /// let mut walker = WalkthroughController::new(Point3::new(0.0, 1.5, 0.0));
+    /// walker.turn(Rad(PI / 2.0), Rad(0.0));
+    /// let mut camera = Camera::default();
+    /// camera.matrix = walker.camera_matrix();
+    /// assert!(camera.eye_direction().near(&-Vector3::unit_x()));
+    /// assert!(camera.head_direction().near(&Vector3::unit_y()));
+    /// assert_eq!(camera.position(), walker.position());
+    /// ```
+    #[inline(always)]
+    pub fn camera_matrix(&self) -> Matrix4 {
+        Matrix4::from_translation(self.position.to_vec())
+            * Matrix4::from_angle_y(self.yaw)
+            * Matrix4::from_angle_x(self.pitch)
+    }
+}
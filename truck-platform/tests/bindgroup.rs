@@ -23,11 +23,13 @@ const POINT_LIGHT: Light = Light {
     position: Point3::new(0.1, 0.2, 0.3),
     color: Vector3::new(0.4, 0.5, 0.6),
     light_type: LightType::Point,
+    cast_shadow: false,
 };
 const UNIFORM_LIGHT: Light = Light {
     position: Point3::new(1.1, 1.2, 1.3),
     color: Vector3::new(1.4, 1.5, 1.6),
     light_type: LightType::Uniform,
+    cast_shadow: false,
 };
 
 fn save_buffer<P: AsRef<std::path::Path>>(path: P, vec: &Vec<u8>) {
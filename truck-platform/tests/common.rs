@@ -1,7 +1,6 @@
 #![allow(dead_code)]
 
 use glsl_to_spirv::ShaderType;
-use rayon::prelude::*;
 use std::io::{Read, Write};
 use std::sync::Arc;
 use truck_platform::*;
@@ -26,28 +25,10 @@ macro_rules! new_plane {
 }
 
 pub fn init_device(instance: &Instance) -> (Arc<Device>, Arc<Queue>) {
-    futures::executor::block_on(async {
-        let adapter = instance
-            .request_adapter(&RequestAdapterOptions {
-                power_preference: PowerPreference::Default,
-                compatible_surface: None,
-            })
-            .await
-            .unwrap();
-        writeln!(&mut std::io::stderr(), "{:?}", adapter.get_info()).unwrap();
-        let (device, queue) = adapter
-            .request_device(
-                &DeviceDescriptor {
-                    features: Default::default(),
-                    limits: Default::default(),
-                    shader_validation: true,
-                },
-                None,
-            )
-            .await
-            .unwrap();
-        (Arc::new(device), Arc::new(queue))
-    })
+    let (device, queue, info) =
+        futures::executor::block_on(DeviceHandler::request_device(instance, None)).unwrap();
+    writeln!(&mut std::io::stderr(), "{:?}", info).unwrap();
+    (device, queue)
 }
 
 impl<'a> Rendered for Plane<'a> {
@@ -229,7 +210,5 @@ pub fn read_texture(handler: &DeviceHandler, texture: &Texture) -> Vec<u8> {
 }
 
 pub fn same_buffer(vec0: &Vec<u8>, vec1: &Vec<u8>) -> bool {
-    vec0.par_iter()
-        .zip(vec1)
-        .all(move |(i, j)| std::cmp::max(i, j) - std::cmp::min(i, j) < 3)
+    ImageComparison::new(vec0, vec1, [2; 4]).is_within_tolerance()
 }
@@ -8,6 +8,19 @@ use wgpu::*;
 pub const PICTURE_WIDTH: u32 = 512;
 pub const PICTURE_HEIGHT: u32 = 512;
 
+// the plane whose pipeline is re-created by the scene
+#[derive(Clone, Debug)]
+struct RebuiltPlane(Plane<'static>);
+
+impl Rendered for RebuiltPlane {
+    derive_render_id!(0);
+    derive_vertex_buffer!(0);
+    derive_bind_group_layout!(0);
+    derive_bind_group!(0);
+    derive_pipeline!(0);
+    impl_pipeline_builder!(clone);
+}
+
 fn save_buffer<P: AsRef<std::path::Path>>(path: P, vec: &Vec<u8>) {
     image::save_buffer(
         path,
@@ -38,7 +51,10 @@ fn exec_msaa_test(backend: BackendBit, out_dir: &str) {
     let mut scene = Scene::new(
         handler.clone(),
         &SceneDescriptor {
-            sample_count: 1,
+            render_settings: RenderSettings {
+                sample_count: 1,
+                ..Default::default()
+            },
             ..Default::default()
         },
     );
@@ -46,12 +62,26 @@ fn exec_msaa_test(backend: BackendBit, out_dir: &str) {
     common::render_one(&mut scene, &texture0, &plane);
     let buffer0 = common::read_texture(&handler, &texture0);
     save_buffer(out_dir.clone() + "sample_count_one.png", &buffer0);
-    scene.descriptor_mut().sample_count = 2;
+    let settings = RenderSettings {
+        sample_count: 2,
+        ..scene.render_settings().clone()
+    };
+    assert!(scene.set_render_settings(settings));
     let plane = new_plane!("shaders/trapezoid.vert", "shaders/trapezoid.frag");
     common::render_one(&mut scene, &texture1, &plane);
     let buffer1 = common::read_texture(&handler, &texture1);
     save_buffer(out_dir.clone() + "sample_count_two.png", &buffer1);
     assert!(!common::same_buffer(&buffer0, &buffer1));
+
+    // The pipeline is re-created in rendering if the sample count is changed by the descriptor.
+    let plane = RebuiltPlane(new_plane!("shaders/trapezoid.vert", "shaders/trapezoid.frag"));
+    scene.add_object(&plane);
+    scene.descriptor_mut().render_settings.sample_count = 1;
+    scene.render_scene(&texture0.create_view(&Default::default()));
+    assert!(common::same_buffer(&buffer0, &common::read_texture(&handler, &texture0)));
+    scene.descriptor_mut().render_settings.sample_count = 2;
+    scene.render_scene(&texture1.create_view(&Default::default()));
+    assert!(common::same_buffer(&buffer1, &common::read_texture(&handler, &texture1)));
 }
 
 #[test]
@@ -0,0 +1,440 @@
+use crate::*;
+use std::collections::{HashMap, HashSet};
+
+/// Triangle mesh as the collision shape.
+///
+/// The vertices and the indices are the ones consumed by the physics engines, e.g.
+/// `parry3d::shape::TriMesh::new` or `rapier3d::geometry::ColliderBuilder::trimesh` after
+/// the conversion to `f32`. The convex hulls are also represented by this struct, and are
+/// consumed by `ColliderBuilder::convex_mesh` or `ColliderBuilder::convex_hull`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CollisionMesh {
+    /// the positions of the vertices
+    pub vertices: Vec<Point3>,
+    /// the triangles, oriented counterclockwise from the outside
+    pub indices: Vec<[u32; 3]>,
+}
+
+/// The options of the approximate convex decomposition.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct DecompositionOption {
+    /// A part is split if its concavity, the maximum distance from the surface of the part to
+    /// the boundary of its convex hull, is greater than `concavity` times the diagonal of
+    /// the bounding box of the whole mesh.
+    pub concavity: f64,
+    /// the maximum depth of the recursive bisections, i.e. there are at most
+    /// `2^max_depth` parts.
+    pub max_depth: usize,
+    /// the maximum number of the vertices of each hull, see [`simplified_hull`].
+    ///
+    /// [`simplified_hull`]: ./fn.simplified_hull.html
+    pub max_hull_vertices: usize,
+}
+
+impl Default for DecompositionOption {
+    #[inline(always)]
+    fn default() -> DecompositionOption {
+        DecompositionOption {
+            concavity: 0.01,
+            max_depth: 6,
+            max_hull_vertices: 64,
+        }
+    }
+}
+
+impl CollisionMesh {
+    /// Returns the enclosed volume. The mesh should be closed and oriented outward.
+    #[inline(always)]
+    pub fn volume(&self) -> f64 {
+        self.indices
+            .iter()
+            .map(|tri| {
+                let p = |i: usize| self.vertices[tri[i] as usize].to_vec();
+                p(0).dot(p(1).cross(p(2))) / 6.0
+            })
+            .sum()
+    }
+
+    /// Returns the vertices in `f32`, e.g. `parry3d::math::Point<f32>` by `Point::from`.
+    #[inline(always)]
+    pub fn vertices_f32(&self) -> Vec<[f32; 3]> {
+        self.vertices
+            .iter()
+            .map(|p| [p[0] as f32, p[1] as f32, p[2] as f32])
+            .collect()
+    }
+}
+
+impl From<&PolygonMesh> for CollisionMesh {
+    /// Triangulates the faces. The uv coordinates and the normals are dropped.
+    fn from(mesh: &PolygonMesh) -> CollisionMesh {
+        CollisionMesh {
+            vertices: mesh.positions().clone(),
+            indices: triangles(mesh).collect(),
+        }
+    }
+}
+
+fn triangles<'a>(mesh: &'a PolygonMesh) -> impl Iterator<Item = [u32; 3]> + 'a {
+    mesh.face_iter().flat_map(|face| {
+        (2..face.len()).map(move |i| {
+            [
+                face[0].pos as u32,
+                face[i - 1].pos as u32,
+                face[i].pos as u32,
+            ]
+        })
+    })
+}
+
+// the outward normal and the offset of the plane of the triangle
+#[inline(always)]
+fn plane(points: &[Point3], tri: [usize; 3]) -> Option<(Vector3, f64)> {
+    let (a, b, c) = (points[tri[0]], points[tri[1]], points[tri[2]]);
+    let normal = (b - a).cross(c - a);
+    match normal.magnitude() > TOLERANCE * TOLERANCE {
+        true => {
+            let normal = normal.normalize();
+            Some((normal, normal.dot(a.to_vec())))
+        }
+        false => None,
+    }
+}
+
+/// Returns the convex hull of `points` by the incremental algorithm.
+///
+/// Returns the empty mesh if there are less than four points or all points are coplanar.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::collision;
+/// let mut points: Vec<Point3> = (0..8)
+///     .map(|i| Point3::new((i & 1) as f64, ((i >> 1) & 1) as f64, (i >> 2) as f64))
+///     .collect();
+/// // inner points
+/// points.push(Point3::new(0.5, 0.5, 0.5));
+/// points.push(Point3::new(0.2, 0.7, 0.4));
+/// let hull = collision::convex_hull(&points);
+/// assert_eq!(hull.vertices.len(), 8);
+/// assert_eq!(hull.indices.len(), 12);
+/// assert!(f64::abs(hull.volume() - 1.0) < TOLERANCE);
+/// ```
+pub fn convex_hull(points: &[Point3]) -> CollisionMesh {
+    let scale = points
+        .iter()
+        .collect::<BoundingBox<Point3>>()
+        .diagonal()
+        .magnitude();
+    let eps = TOLERANCE * f64::max(scale, 1.0);
+    let tetra = match initial_tetrahedron(points, eps) {
+        Some(tetra) => tetra,
+        None => return CollisionMesh::default(),
+    };
+    let [a, b, c, d] = tetra;
+    // Each face is oriented so that the opposite vertex is behind it.
+    let mut faces: Vec<[usize; 3]> = [
+        ([a, b, c], d),
+        ([a, d, b], c),
+        ([b, d, c], a),
+        ([c, d, a], b),
+    ]
+    .iter()
+    .map(|([i, j, k], o)| match plane(points, [*i, *j, *k]) {
+        Some((normal, offset)) if normal.dot(points[*o].to_vec()) > offset => [*i, *k, *j],
+        _ => [*i, *j, *k],
+    })
+    .collect();
+    for (idx, p) in points.iter().enumerate() {
+        if tetra.contains(&idx) {
+            continue;
+        }
+        let visible: Vec<bool> = faces
+            .iter()
+            .map(|face| match plane(points, *face) {
+                Some((normal, offset)) => normal.dot(p.to_vec()) - offset > eps,
+                None => false,
+            })
+            .collect();
+        if !visible.iter().any(|v| *v) {
+            continue;
+        }
+        let visible_edges: HashSet<(usize, usize)> = faces
+            .iter()
+            .zip(&visible)
+            .filter(|(_, v)| **v)
+            .flat_map(|(face, _)| (0..3).map(move |i| (face[i], face[(i + 1) % 3])))
+            .collect();
+        let horizon: Vec<(usize, usize)> = visible_edges
+            .iter()
+            .filter(|(a, b)| !visible_edges.contains(&(*b, *a)))
+            .cloned()
+            .collect();
+        let mut i = 0;
+        faces.retain(|_| {
+            i += 1;
+            !visible[i - 1]
+        });
+        faces.extend(horizon.into_iter().map(|(a, b)| [a, b, idx]));
+    }
+    compact(points, &faces)
+}
+
+// the four points spanning the largest tetrahedron approximately
+fn initial_tetrahedron(points: &[Point3], eps: f64) -> Option<[usize; 4]> {
+    let farthest = |f: &dyn Fn(&Point3) -> f64| {
+        points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| (i, f(p)))
+            .fold((0, f64::NEG_INFINITY), |x, y| if y.1 > x.1 { y } else { x })
+    };
+    let a = farthest(&|p| -p[0]).0;
+    let (b, dist) = farthest(&|p| p.distance2(points[a]));
+    if points.len() < 4 || dist.sqrt() < eps {
+        return None;
+    }
+    let dir = (points[b] - points[a]).normalize();
+    let (c, dist) = farthest(&|p| {
+        let v = p - points[a];
+        (v - dir * v.dot(dir)).magnitude()
+    });
+    if dist < eps {
+        return None;
+    }
+    let (normal, offset) = plane(points, [a, b, c])?;
+    let (d, dist) = farthest(&|p| f64::abs(normal.dot(p.to_vec()) - offset));
+    match dist < eps {
+        true => None,
+        false => Some([a, b, c, d]),
+    }
+}
+
+// removes the unused points and renumbers the indices
+fn compact(points: &[Point3], faces: &[[usize; 3]]) -> CollisionMesh {
+    let mut map = HashMap::<usize, u32>::new();
+    let mut vertices = Vec::new();
+    let indices = faces
+        .iter()
+        .map(|face| {
+            let mut tri = [0; 3];
+            tri.iter_mut().zip(face).for_each(|(x, i)| {
+                *x = *map.entry(*i).or_insert_with(|| {
+                    vertices.push(points[*i]);
+                    vertices.len() as u32 - 1
+                });
+            });
+            tri
+        })
+        .collect();
+    CollisionMesh { vertices, indices }
+}
+
+/// Returns the convex hull of `points` with at most `max_vertices` vertices, which is
+/// cheaper in the collision detection.
+///
+/// The vertices are selected from the ones of the convex hull by the farthest point sampling,
+/// so the simplified hull is contained in the exact hull.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::collision;
+/// // the points on the unit sphere
+/// let points: Vec<Point3> = (0..500)
+///     .map(|i| {
+///         let z = 1.0 - (2 * i + 1) as f64 / 500.0;
+///         let theta = std::f64::consts::PI * (3.0 - f64::sqrt(5.0)) * i as f64;
+///         let r = f64::sqrt(1.0 - z * z);
+///         Point3::new(r * theta.cos(), r * theta.sin(), z)
+///     })
+///     .collect();
+/// let hull = collision::simplified_hull(&points, 32);
+/// assert!(hull.vertices.len() <= 32);
+/// let sphere = 4.0 / 3.0 * std::f64::consts::PI;
+/// assert!(hull.volume() < sphere && hull.volume() > 0.7 * sphere);
+/// ```
+pub fn simplified_hull(points: &[Point3], max_vertices: usize) -> CollisionMesh {
+    let hull = convex_hull(points);
+    if hull.vertices.len() <= max_vertices || max_vertices < 4 {
+        return hull;
+    }
+    let vertices = &hull.vertices;
+    let center = vertices.iter().fold(Point3::origin(), |c, p| {
+        c + p.to_vec() / vertices.len() as f64
+    });
+    let first = (0..vertices.len())
+        .max_by(|i, j| {
+            let (di, dj) = (
+                vertices[*i].distance2(center),
+                vertices[*j].distance2(center),
+            );
+            di.partial_cmp(&dj).unwrap()
+        })
+        .unwrap();
+    let mut distances: Vec<f64> = vertices
+        .iter()
+        .map(|p| p.distance2(vertices[first]))
+        .collect();
+    let mut selected = vec![vertices[first]];
+    while selected.len() < max_vertices {
+        let (next, _) = distances
+            .iter()
+            .enumerate()
+            .fold(
+                (0, f64::NEG_INFINITY),
+                |x, (i, d)| if *d > x.1 { (i, *d) } else { x },
+            );
+        selected.push(vertices[next]);
+        distances
+            .iter_mut()
+            .zip(vertices)
+            .for_each(|(d, p)| *d = f64::min(*d, p.distance2(vertices[next])));
+    }
+    convex_hull(&selected)
+}
+
+/// Decomposes `mesh` into the convex hulls approximately, e.g. for the compound shape
+/// `rapier3d::geometry::ColliderBuilder::compound` of the concave parts.
+///
+/// The triangles are split recursively by the plane through the center of the bounding box
+/// perpendicular to its longest edge, until the concavity of each part is small enough.
+/// In order to decompose a solid, mesh it by `MeshableShape::triangulation` in advance.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::collision::{self, DecompositionOption};
+/// // the L-shaped prism: the union of [0, 2] x [0, 1] x [0, 1] and [0, 1] x [1, 2] x [0, 1]
+/// let section = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (1.0, 1.0), (1.0, 2.0), (0.0, 2.0)];
+/// let positions: Vec<Point3> = (0..2)
+///     .flat_map(|z| section.iter().map(move |(x, y)| Point3::new(*x, *y, z as f64)))
+///     .collect();
+/// let mut faces = Faces::from_iter(&[&[5, 4, 3, 2, 1, 0], &[6, 7, 8, 9, 10, 11]]);
+/// (0..6usize).for_each(|i| faces.push(&[i, (i + 1) % 6, (i + 1) % 6 + 6, i + 6]));
+/// let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+///
+/// let hull = collision::convex_hull(mesh.positions());
+/// assert!(f64::abs(hull.volume() - 3.5) < TOLERANCE);
+/// let parts = collision::convex_decomposition(&mesh, &DecompositionOption::default());
+/// assert!(parts.len() > 1);
+/// let volume: f64 = parts.iter().map(|part| part.volume()).sum();
+/// assert!(volume < 3.5 - 0.1);
+/// ```
+pub fn convex_decomposition(
+    mesh: &PolygonMesh,
+    option: &DecompositionOption,
+) -> Vec<CollisionMesh> {
+    let points = mesh.positions();
+    let scale = mesh.bounding_box().diagonal().magnitude();
+    let triangles: Vec<[Point3; 3]> = triangles(mesh)
+        .map(|tri| {
+            [
+                points[tri[0] as usize],
+                points[tri[1] as usize],
+                points[tri[2] as usize],
+            ]
+        })
+        .collect();
+    let mut res = Vec::new();
+    decompose(triangles, option, option.concavity * scale, 0, &mut res);
+    res
+}
+
+fn decompose(
+    triangles: Vec<[Point3; 3]>,
+    option: &DecompositionOption,
+    tolerance: f64,
+    depth: usize,
+    res: &mut Vec<CollisionMesh>,
+) {
+    let part: Vec<Point3> = triangles.iter().flatten().cloned().collect();
+    let hull = simplified_hull(&part, option.max_hull_vertices);
+    if hull.indices.is_empty() {
+        return;
+    }
+    if depth >= option.max_depth || concavity(&triangles, &hull) <= tolerance {
+        res.push(hull);
+        return;
+    }
+    let bdb: BoundingBox<Point3> = part.iter().collect();
+    let diag = bdb.diagonal();
+    let axis = (0..3)
+        .max_by(|i, j| diag[*i].partial_cmp(&diag[*j]).unwrap())
+        .unwrap();
+    let center = bdb.center()[axis];
+    let (mut left, mut right) = (Vec::new(), Vec::new());
+    triangles
+        .iter()
+        .for_each(|tri| split_triangle(tri, axis, center, &mut left, &mut right));
+    if left.is_empty() || right.is_empty() {
+        res.push(hull);
+        return;
+    }
+    decompose(left, option, tolerance, depth + 1, res);
+    decompose(right, option, tolerance, depth + 1, res);
+}
+
+// Splits the triangle by the plane `p[axis] == center`. The triangle on the plane belongs to
+// the side opposite to its normal, i.e. the side of the inside of the mesh.
+fn split_triangle(
+    tri: &[Point3; 3],
+    axis: usize,
+    center: f64,
+    left: &mut Vec<[Point3; 3]>,
+    right: &mut Vec<[Point3; 3]>,
+) {
+    let s: Vec<f64> = tri.iter().map(|p| p[axis] - center).collect();
+    if s.iter().all(|s| s.abs() < TOLERANCE) {
+        let normal = (tri[1] - tri[0]).cross(tri[2] - tri[0]);
+        match normal[axis] > 0.0 {
+            true => left.push(*tri),
+            false => right.push(*tri),
+        }
+        return;
+    }
+    if s.iter().all(|s| *s < TOLERANCE) {
+        return left.push(*tri);
+    }
+    if s.iter().all(|s| *s > -TOLERANCE) {
+        return right.push(*tri);
+    }
+    let (mut lpoly, mut rpoly) = (Vec::new(), Vec::new());
+    for i in 0..3 {
+        let j = (i + 1) % 3;
+        match s[i] < 0.0 {
+            true => lpoly.push(tri[i]),
+            false => rpoly.push(tri[i]),
+        }
+        if (s[i] < 0.0) != (s[j] < 0.0) {
+            let t = s[i] / (s[i] - s[j]);
+            let p = tri[i] + (tri[j] - tri[i]) * t;
+            lpoly.push(p);
+            rpoly.push(p);
+        }
+    }
+    let fan = |poly: Vec<Point3>, res: &mut Vec<[Point3; 3]>| {
+        (2..poly.len()).for_each(|i| res.push([poly[0], poly[i - 1], poly[i]]))
+    };
+    fan(lpoly, left);
+    fan(rpoly, right);
+}
+
+// the maximum distance from the centroids of the triangles to the boundary of the hull
+fn concavity(triangles: &[[Point3; 3]], hull: &CollisionMesh) -> f64 {
+    let planes: Vec<(Vector3, f64)> = hull
+        .indices
+        .iter()
+        .filter_map(|tri| {
+            let tri = [tri[0] as usize, tri[1] as usize, tri[2] as usize];
+            plane(&hull.vertices, tri)
+        })
+        .collect();
+    triangles
+        .iter()
+        .map(|tri| {
+            let centroid = (tri[0].to_vec() + tri[1].to_vec() + tri[2].to_vec()) / 3.0;
+            planes
+                .iter()
+                .map(|(normal, offset)| offset - normal.dot(centroid))
+                .fold(f64::INFINITY, f64::min)
+        })
+        .fold(0.0, f64::max)
+}
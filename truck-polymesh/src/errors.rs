@@ -13,6 +13,12 @@ pub enum Error {
     IrregularArray,
     /// The division of uv coords of `StructuredMesh` is not sorted.
     UnsortedDivision,
+    /// The length of a field is not equal to the number of the points or the cells.
+    InvalidFieldLength(String, usize, usize),
+    /// The estimated size of the mesh exceeds the memory budget of the streaming loader.
+    MemoryBudgetExceeded(usize),
+    /// The file format is not supported.
+    UnsupportedFormat(String),
     /// Errors caused by obj files I/O.
     FromIO(std::io::Error),
 }
@@ -31,6 +37,17 @@ impl std::fmt::Display for Error {
             }
             Error::IrregularArray => f.pad("This 2-dim array is irregular."),
             Error::UnsortedDivision => f.pad("This division vector is unsorted."),
+            Error::InvalidFieldLength(name, expected, length) => f.write_fmt(format_args!(
+                "The length of the field {} is {}, however, it should be {}.",
+                name, length, expected
+            )),
+            Error::MemoryBudgetExceeded(budget) => f.write_fmt(format_args!(
+                "The mesh exceeds the memory budget {} bytes.",
+                budget
+            )),
+            Error::UnsupportedFormat(format) => {
+                f.write_fmt(format_args!("The format \"{}\" is not supported.", format))
+            }
             Error::FromIO(error) => f.write_fmt(format_args!("{:?}", error)),
         }
     }
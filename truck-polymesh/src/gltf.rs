@@ -0,0 +1,616 @@
+use crate::*;
+use errors::Error;
+use std::collections::HashMap;
+use std::io::{BufWriter, Write};
+
+const FLOAT: u32 = 5126;
+const UNSIGNED_INT: u32 = 5125;
+const ARRAY_BUFFER: u32 = 34962;
+const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+const GLB_MAGIC: u32 = 0x4654_6C67;
+const GLB_JSON: u32 = 0x4E4F_534A;
+const GLB_BIN: u32 = 0x004E_4942;
+
+/// glTF file type
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GLTFType {
+    /// JSON format whose binary buffer is embedded as the data URI, `.gltf`
+    GLTF,
+    /// binary format, `.glb`
+    GLB,
+}
+
+impl Default for GLTFType {
+    #[inline(always)]
+    fn default() -> GLTFType { GLTFType::GLTF }
+}
+
+/// Material by the metallic-roughness model of glTF.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Material {
+    /// the base color, [0, 1]-normalized linear rgba. If the alpha is less than `1.0`,
+    /// the material is alpha-blended. The default is `Vector4::new(1.0, 1.0, 1.0, 1.0)`.
+    pub base_color: Vector4,
+    /// the metalness: [0, 1]. The default is `0.0`.
+    pub metallic: f64,
+    /// the roughness: [0, 1]. The default is `0.5`.
+    pub roughness: f64,
+    /// If `true`, the back faces are also rendered. The default is `false`.
+    pub double_sided: bool,
+}
+
+impl Default for Material {
+    #[inline(always)]
+    fn default() -> Material {
+        Material {
+            base_color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            metallic: 0.0,
+            roughness: 0.5,
+            double_sided: false,
+        }
+    }
+}
+
+/// Node of the instance hierarchy.
+///
+/// The matrix of a node is relative to its parent, so the instance of a mesh is placed by
+/// the product of the matrices of the node and its ancestors.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Node {
+    /// the name of the node. The default is `None`.
+    pub name: Option<String>,
+    /// the local transform. The default is the identity.
+    pub matrix: Matrix4,
+    /// the index of the instantiated mesh. The default is `None`, i.e. the node is a group.
+    pub mesh: Option<usize>,
+    /// the index of the material of the mesh. The default is `0`.
+    pub material: usize,
+    /// the child nodes. The default is empty.
+    pub children: Vec<Node>,
+}
+
+impl Default for Node {
+    #[inline(always)]
+    fn default() -> Node {
+        Node {
+            name: None,
+            matrix: Matrix4::identity(),
+            mesh: None,
+            material: 0,
+            children: Vec::new(),
+        }
+    }
+}
+
+/// The options of [`gltf::write`](./fn.write.html).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct WriteOptions {
+    /// the file type. The default is `GLTFType::GLTF`.
+    pub gltf_type: GLTFType,
+    /// the materials referred by the nodes. If empty, the default material is used.
+    pub materials: Vec<Material>,
+    /// the root nodes of the scene. If empty, the scene consists of one node for each mesh
+    /// with the first material.
+    pub nodes: Vec<Node>,
+}
+
+/// Writes the meshes and the instance hierarchy to glTF 2.0.
+///
+/// Each mesh is written to the buffer once, however many nodes instantiate it. The attributes
+/// `NORMAL` and `TEXCOORD_0` are written as in [`write_lods`](./fn.write_lods.html).
+/// # Errors
+/// Returns [`Error::OutOfRange`] if a node refers to a mesh or a material which does not exist.
+///
+/// [`Error::OutOfRange`]: ../errors/enum.Error.html#variant.OutOfRange
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::gltf::*;
+/// let positions = vec![
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+/// ];
+/// let faces = Faces::from_iter(&[&[0, 1, 2]]);
+/// let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+///
+/// // the group of two instances of the triangle with the different materials
+/// let red = Material {
+///     base_color: Vector4::new(1.0, 0.0, 0.0, 1.0),
+///     ..Default::default()
+/// };
+/// let metal = Material {
+///     metallic: 1.0,
+///     roughness: 0.2,
+///     ..Default::default()
+/// };
+/// let group = Node {
+///     name: Some("group".to_string()),
+///     children: vec![
+///         Node {
+///             mesh: Some(0),
+///             ..Default::default()
+///         },
+///         Node {
+///             matrix: Matrix4::from_translation(Vector3::new(2.0, 0.0, 0.0)),
+///             mesh: Some(0),
+///             material: 1,
+///             ..Default::default()
+///         },
+///     ],
+///     ..Default::default()
+/// };
+/// let mut options = WriteOptions {
+///     materials: vec![red, metal],
+///     nodes: vec![group],
+///     ..Default::default()
+/// };
+///
+/// let mut output = Vec::new();
+/// gltf::write(&[mesh.clone()], &options, &mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("\"nodes\":[{\"name\":\"group\",\"children\":[1,2]},{\"mesh\":0}"));
+/// assert!(output.contains("\"baseColorFactor\":[1.0, 0.0, 0.0, 1.0]"));
+/// // the triangle is written to the buffer once
+/// assert_eq!(output.matches("\"type\":\"VEC3\"").count(), 1);
+///
+/// // binary glTF
+/// options.gltf_type = GLTFType::GLB;
+/// let mut output = Vec::new();
+/// gltf::write(&[mesh.clone()], &options, &mut output).unwrap();
+/// assert_eq!(&output[0..4], b"glTF");
+/// assert_eq!(output.len() % 4, 0);
+///
+/// // there is no third material
+/// options.nodes[0].children[1].material = 2;
+/// assert!(gltf::write(&[mesh], &options, &mut Vec::new()).is_err());
+/// ```
+pub fn write<W: Write>(meshes: &[PolygonMesh], options: &WriteOptions, writer: W) -> Result<()> {
+    let default_materials = [Material::default()];
+    let materials = match options.materials.is_empty() {
+        true => &default_materials[..],
+        false => &options.materials[..],
+    };
+    let default_nodes: Vec<Node>;
+    let roots = match options.nodes.is_empty() {
+        true => {
+            default_nodes = (0..meshes.len())
+                .map(|i| Node {
+                    mesh: Some(i),
+                    ..Default::default()
+                })
+                .collect();
+            &default_nodes
+        }
+        false => &options.nodes,
+    };
+    let mut scene = Scene {
+        meshes,
+        num_materials: materials.len(),
+        document: Document::default(),
+        nodes: Vec::new(),
+        primitives: HashMap::new(),
+        gltf_meshes: Vec::new(),
+        gltf_mesh_ids: HashMap::new(),
+    };
+    let roots = roots
+        .iter()
+        .map(|node| scene.push_node(node).map(|i| i.to_string()))
+        .collect::<Result<Vec<_>>>()?;
+    let materials: Vec<String> = materials.iter().map(material_json).collect();
+    let body = format!(
+        "\"scene\":0,\"scenes\":[{{\"nodes\":[{}]}}],\"nodes\":[{}],\"meshes\":[{}],\
+         \"materials\":[{}],",
+        roots.join(","),
+        scene.nodes.join(","),
+        scene.gltf_meshes.join(","),
+        materials.join(","),
+    );
+    scene.document.write(&body, options.gltf_type, writer)
+}
+
+/// Writes the chain of the levels of detail to the self-contained glTF 2.0 file, whose
+/// binary buffer is embedded as the data URI.
+///
+/// The first mesh is the most detailed level. The node of the first mesh refers to the nodes
+/// of the other levels by the extension `MSFT_lod`, and the viewers without the extension
+/// show only the first level. All levels share one material. The attributes `NORMAL` and
+/// `TEXCOORD_0` are written only if all corners of the level have the normals or the uv
+/// coordinates, and the v-coordinates are flipped to the convention of glTF.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// let positions = vec![
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(1.0, 1.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+/// ];
+/// let uv_coords = vec![
+///     Vector2::new(0.0, 0.0),
+///     Vector2::new(1.0, 0.0),
+///     Vector2::new(1.0, 1.0),
+///     Vector2::new(0.0, 1.0),
+/// ];
+/// let normals = vec![Vector3::unit_z()];
+/// let faces = Faces::from_iter(&[&[[0, 0, 0], [1, 1, 0], [2, 2, 0], [3, 3, 0]]]);
+/// let mesh = PolygonMesh::new(positions, uv_coords, normals, faces);
+///
+/// let lods = vec![mesh.clone(), mesh];
+/// let mut gltf = Vec::new();
+/// gltf::write_lods(&lods, &mut gltf).unwrap();
+/// let gltf = String::from_utf8(gltf).unwrap();
+/// assert!(gltf.contains("\"MSFT_lod\":{\"ids\":[1]}"));
+/// assert!(gltf.contains("\"TEXCOORD_0\""));
+/// ```
+pub fn write_lods<W: Write>(lods: &[PolygonMesh], writer: W) -> Result<()> {
+    let mut document = Document::default();
+    let meshes: Vec<String> = lods
+        .iter()
+        .map(|mesh| {
+            format!(
+                "{{\"primitives\":[{{{},\"material\":0}}]}}",
+                document.push_mesh(mesh)
+            )
+        })
+        .collect();
+    let nodes: Vec<String> = (0..lods.len())
+        .map(|i| match (i, lods.len()) {
+            (0, 1) => "{\"mesh\":0}".to_string(),
+            (0, len) => {
+                let ids: Vec<String> = (1..len).map(|i| i.to_string()).collect();
+                format!(
+                    "{{\"mesh\":0,\"extensions\":{{\"MSFT_lod\":{{\"ids\":[{}]}}}}}}",
+                    ids.join(",")
+                )
+            }
+            (i, _) => format!("{{\"mesh\":{}}}", i),
+        })
+        .collect();
+    let extensions = match lods.len() > 1 {
+        true => "\"extensionsUsed\":[\"MSFT_lod\"],",
+        false => "",
+    };
+    let scene_nodes = match lods.is_empty() {
+        true => "",
+        false => "0",
+    };
+    let body = format!(
+        "{}\"scene\":0,\"scenes\":[{{\"nodes\":[{}]}}],\"nodes\":[{}],\"meshes\":[{}],\
+         \"materials\":[{{\"pbrMetallicRoughness\":{{\"metallicFactor\":0.0}},\"doubleSided\":false}}],",
+        extensions,
+        scene_nodes,
+        nodes.join(","),
+        meshes.join(","),
+    );
+    document.write(&body, GLTFType::GLTF, writer)
+}
+
+// the nodes and the meshes of glTF collected from the instance hierarchy
+struct Scene<'a> {
+    meshes: &'a [PolygonMesh],
+    num_materials: usize,
+    document: Document,
+    nodes: Vec<String>,
+    // the attributes and the indices of the primitive of each written mesh
+    primitives: HashMap<usize, String>,
+    gltf_meshes: Vec<String>,
+    // the glTF mesh of each pair of the mesh and the material
+    gltf_mesh_ids: HashMap<(usize, usize), usize>,
+}
+
+impl<'a> Scene<'a> {
+    // Pushes the node and its descendants in the depth-first order, and returns the index.
+    fn push_node(&mut self, node: &Node) -> Result<usize> {
+        let idx = self.nodes.len();
+        self.nodes.push(String::new());
+        let mut fields = Vec::new();
+        if let Some(name) = &node.name {
+            fields.push(format!("\"name\":{}", json_string(name)));
+        }
+        if node.matrix != Matrix4::identity() {
+            let matrix: &[f64; 16] = node.matrix.as_ref();
+            fields.push(format!("\"matrix\":{:?}", matrix));
+        }
+        if let Some(mesh) = node.mesh {
+            fields.push(format!("\"mesh\":{}", self.gltf_mesh(mesh, node.material)?));
+        }
+        if !node.children.is_empty() {
+            let children = node
+                .children
+                .iter()
+                .map(|child| self.push_node(child).map(|i| i.to_string()))
+                .collect::<Result<Vec<_>>>()?;
+            fields.push(format!("\"children\":[{}]", children.join(",")));
+        }
+        self.nodes[idx] = format!("{{{}}}", fields.join(","));
+        Ok(idx)
+    }
+
+    fn gltf_mesh(&mut self, mesh: usize, material: usize) -> Result<usize> {
+        if mesh >= self.meshes.len() {
+            return Err(Error::OutOfRange("meshes", self.meshes.len(), mesh));
+        } else if material >= self.num_materials {
+            return Err(Error::OutOfRange("materials", self.num_materials, material));
+        }
+        if let Some(idx) = self.gltf_mesh_ids.get(&(mesh, material)) {
+            return Ok(*idx);
+        }
+        let (meshes, document) = (self.meshes, &mut self.document);
+        let primitive = self
+            .primitives
+            .entry(mesh)
+            .or_insert_with(|| document.push_mesh(&meshes[mesh]));
+        self.gltf_meshes.push(format!(
+            "{{\"primitives\":[{{{},\"material\":{}}}]}}",
+            primitive, material
+        ));
+        self.gltf_mesh_ids.insert((mesh, material), self.gltf_meshes.len() - 1);
+        Ok(self.gltf_meshes.len() - 1)
+    }
+}
+
+// the binary buffer, and the buffer views and the accessors on it
+#[derive(Default)]
+struct Document {
+    buffer: Vec<u8>,
+    buffer_views: Vec<String>,
+    accessors: Vec<String>,
+}
+
+impl Document {
+    fn push_view(&mut self, data: &[u8], target: u32) -> usize {
+        self.buffer_views.push(format!(
+            "{{\"buffer\":0,\"byteOffset\":{},\"byteLength\":{},\"target\":{}}}",
+            self.buffer.len(),
+            data.len(),
+            target
+        ));
+        self.buffer.extend_from_slice(data);
+        self.buffer_views.len() - 1
+    }
+
+    // Pushes the attributes and the indices of the mesh, and returns the fields of the primitive
+    // except the material.
+    fn push_mesh(&mut self, mesh: &PolygonMesh) -> String {
+        let expanded = ExpandedMesh::new(mesh);
+        let mut attributes = Vec::new();
+        let (min, max) = expanded.bounds();
+        let view = self.push_view(&f32_bytes(&expanded.positions), ARRAY_BUFFER);
+        self.accessors.push(format!(
+            "{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"VEC3\",\"min\":{:?},\"max\":{:?}}}",
+            view,
+            FLOAT,
+            expanded.positions.len() / 3,
+            min,
+            max
+        ));
+        attributes.push(format!("\"POSITION\":{}", self.accessors.len() - 1));
+        if let Some(normals) = &expanded.normals {
+            let view = self.push_view(&f32_bytes(normals), ARRAY_BUFFER);
+            self.accessors.push(format!(
+                "{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"VEC3\"}}",
+                view,
+                FLOAT,
+                normals.len() / 3
+            ));
+            attributes.push(format!("\"NORMAL\":{}", self.accessors.len() - 1));
+        }
+        if let Some(uv_coords) = &expanded.uv_coords {
+            let view = self.push_view(&f32_bytes(uv_coords), ARRAY_BUFFER);
+            self.accessors.push(format!(
+                "{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"VEC2\"}}",
+                view,
+                FLOAT,
+                uv_coords.len() / 2
+            ));
+            attributes.push(format!("\"TEXCOORD_0\":{}", self.accessors.len() - 1));
+        }
+        let indices: Vec<u8> = expanded
+            .indices
+            .iter()
+            .flat_map(|i| i.to_le_bytes().to_vec())
+            .collect();
+        let view = self.push_view(&indices, ELEMENT_ARRAY_BUFFER);
+        self.accessors.push(format!(
+            "{{\"bufferView\":{},\"componentType\":{},\"count\":{},\"type\":\"SCALAR\"}}",
+            view,
+            UNSIGNED_INT,
+            expanded.indices.len()
+        ));
+        format!(
+            "\"attributes\":{{{}}},\"indices\":{}",
+            attributes.join(","),
+            self.accessors.len() - 1
+        )
+    }
+
+    // Writes the document, where `body` is the fields of the scene, the nodes, the meshes and
+    // the materials followed by a comma.
+    fn write<W: Write>(self, body: &str, gltf_type: GLTFType, writer: W) -> Result<()> {
+        let buffer = match gltf_type {
+            GLTFType::GLTF => format!(
+                "{{\"byteLength\":{},\"uri\":\"data:application/octet-stream;base64,{}\"}}",
+                self.buffer.len(),
+                base64(&self.buffer)
+            ),
+            GLTFType::GLB => format!("{{\"byteLength\":{}}}", self.buffer.len()),
+        };
+        let json = format!(
+            "{{\"asset\":{{\"version\":\"2.0\",\"generator\":\"truck-polymesh\"}},{}\
+             \"accessors\":[{}],\"bufferViews\":[{}],\"buffers\":[{}]}}",
+            body,
+            self.accessors.join(","),
+            self.buffer_views.join(","),
+            buffer,
+        );
+        let mut writer = BufWriter::new(writer);
+        match gltf_type {
+            GLTFType::GLTF => writeln!(writer, "{}", json)?,
+            GLTFType::GLB => {
+                let mut json = json.into_bytes();
+                json.resize(json.len() + (4 - json.len() % 4) % 4, b' ');
+                let mut bin = self.buffer;
+                bin.resize(bin.len() + (4 - bin.len() % 4) % 4, 0);
+                let length = 12 + 8 + json.len() + 8 + bin.len();
+                for word in &[GLB_MAGIC, 2, length as u32, json.len() as u32, GLB_JSON] {
+                    writer.write_all(&word.to_le_bytes())?;
+                }
+                writer.write_all(&json)?;
+                writer.write_all(&(bin.len() as u32).to_le_bytes())?;
+                writer.write_all(&GLB_BIN.to_le_bytes())?;
+                writer.write_all(&bin)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn material_json(material: &Material) -> String {
+    let color = material.base_color;
+    let alpha_mode = match color[3] < 1.0 {
+        true => ",\"alphaMode\":\"BLEND\"",
+        false => "",
+    };
+    format!(
+        "{{\"pbrMetallicRoughness\":{{\"baseColorFactor\":{:?},\"metallicFactor\":{:?},\
+         \"roughnessFactor\":{:?}}},\"doubleSided\":{}{}}}",
+        [color[0], color[1], color[2], color[3]],
+        material.metallic,
+        material.roughness,
+        material.double_sided,
+        alpha_mode,
+    )
+}
+
+fn json_string(string: &str) -> String {
+    let mut res = String::with_capacity(string.len() + 2);
+    res.push('"');
+    for c in string.chars() {
+        match c {
+            '"' => res.push_str("\\\""),
+            '\\' => res.push_str("\\\\"),
+            c if (c as u32) < 0x20 => res.push_str(&format!("\\u{:04x}", c as u32)),
+            c => res.push(c),
+        }
+    }
+    res.push('"');
+    res
+}
+
+// the triangles of the vertices with the attributes, the form of glTF
+struct ExpandedMesh {
+    positions: Vec<f32>,
+    normals: Option<Vec<f32>>,
+    uv_coords: Option<Vec<f32>>,
+    indices: Vec<u32>,
+}
+
+impl ExpandedMesh {
+    fn new(mesh: &PolygonMesh) -> ExpandedMesh {
+        let corners: Vec<Vertex> = mesh
+            .face_iter()
+            .flat_map(|face| (2..face.len()).flat_map(move |i| vec![face[0], face[i - 1], face[i]]))
+            .collect();
+        let has_normals = corners.iter().all(|v| v.nor.is_some());
+        let has_uv_coords = corners.iter().all(|v| v.uv.is_some());
+        let mut map = HashMap::<Vertex, u32>::new();
+        let mut vertices = Vec::new();
+        let indices = corners
+            .into_iter()
+            .map(|mut v| {
+                v.nor = v.nor.filter(|_| has_normals);
+                v.uv = v.uv.filter(|_| has_uv_coords);
+                *map.entry(v).or_insert_with(|| {
+                    vertices.push(v);
+                    vertices.len() as u32 - 1
+                })
+            })
+            .collect();
+        let positions = vertices
+            .iter()
+            .flat_map(|v| {
+                let p = mesh.positions()[v.pos];
+                vec![p[0] as f32, p[1] as f32, p[2] as f32]
+            })
+            .collect();
+        let normals = match has_normals {
+            true => Some(
+                vertices
+                    .iter()
+                    .flat_map(|v| {
+                        let n = mesh.normals()[v.nor.unwrap()];
+                        vec![n[0] as f32, n[1] as f32, n[2] as f32]
+                    })
+                    .collect(),
+            ),
+            false => None,
+        };
+        let uv_coords = match has_uv_coords {
+            true => Some(
+                vertices
+                    .iter()
+                    .flat_map(|v| {
+                        let uv = mesh.uv_coords()[v.uv.unwrap()];
+                        vec![uv[0] as f32, 1.0 - uv[1] as f32]
+                    })
+                    .collect(),
+            ),
+            false => None,
+        };
+        ExpandedMesh {
+            positions,
+            normals,
+            uv_coords,
+            indices,
+        }
+    }
+
+    // the minimum and the maximum of the positions, required by glTF
+    fn bounds(&self) -> ([f32; 3], [f32; 3]) {
+        if self.positions.is_empty() {
+            return ([0.0; 3], [0.0; 3]);
+        }
+        let mut min = [f32::INFINITY; 3];
+        let mut max = [f32::NEG_INFINITY; 3];
+        self.positions.chunks(3).for_each(|p| {
+            (0..3).for_each(|k| {
+                min[k] = f32::min(min[k], p[k]);
+                max[k] = f32::max(max[k], p[k]);
+            })
+        });
+        (min, max)
+    }
+}
+
+#[inline(always)]
+fn f32_bytes(data: &[f32]) -> Vec<u8> {
+    data.iter().flat_map(|x| x.to_le_bytes().to_vec()).collect()
+}
+
+fn base64(data: &[u8]) -> String {
+    const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut res = String::with_capacity(data.chunks(3).len() * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        (0..4).for_each(|i| match i <= chunk.len() {
+            true => res.push(TABLE[((n >> (18 - 6 * i)) & 63) as usize] as char),
+            false => res.push('='),
+        });
+    }
+    res
+}
+
+#[test]
+fn base64_test() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foo"), "Zm9v");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+}
@@ -52,6 +52,25 @@ pub struct PolygonMesh {
     uv_coords: Vec<Vector2>,
     normals: Vec<Vector3>,
     faces: Faces,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    skin: Option<Skin>,
+}
+
+/// Skinning data of a polygon mesh for the skeletal animation, e.g. the one exported from
+/// DCC tools by glTF.
+///
+/// Each position of the mesh is influenced by up to four joints. The position `p` is deformed
+/// to `sum_i weights[i] * joint_matrices[joints[i]] * inverse_bind_matrices[joints[i]] * p`,
+/// where `joint_matrices` are the matrices of the joints at the current pose.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Skin {
+    /// the indices of the joints influencing each position
+    pub joints: Vec<[usize; 4]>,
+    /// the weights of the joints for each position, whose sum should be `1.0`
+    pub weights: Vec<[f64; 4]>,
+    /// the inverse bind matrices of the joints, which transform the mesh into the coordinates
+    /// of the joints at the bind pose
+    pub inverse_bind_matrices: Vec<Matrix4>,
 }
 
 /// structured quadrangle mesh
@@ -62,23 +81,64 @@ pub struct StructuredMesh {
     normals: Option<Vec<Vec<Vector3>>>,
 }
 
+/// Oriented point cloud, e.g. the output of a 3D scanner.
+///
+/// Each point has the normal vector directed to the outside of the scanned object.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct PointCloud {
+    positions: Vec<Point3>,
+    normals: Vec<Vector3>,
+}
+
+/// The options of the simplification of polygon meshes by
+/// [`PolygonMesh::simplify_with_options`](./struct.PolygonMesh.html#method.simplify_with_options).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SimplificationOptions {
+    /// If `true`, the positions on the boundaries are fixed, e.g. the seams between the
+    /// faces of a tessellated shell. Otherwise, the boundaries are simplified keeping their
+    /// shapes approximately. The default is `true`.
+    pub preserve_boundary: bool,
+    /// The maximum angle by which one collapse of an edge may rotate the normal of a triangle.
+    /// The default is `PI / 4`.
+    pub max_normal_deviation: Rad<f64>,
+}
+
 /// Error handler for [`Error`](./errors/enum.Error.html)
 pub type Result<T> = std::result::Result<T, errors::Error>;
 
+/// Collision shapes for the physics engines: triangle meshes, convex hulls and
+/// the approximate convex decomposition
+pub mod collision;
 /// Defines errors
 pub mod errors;
+/// Export of glTF 2.0, e.g. the chains of the levels of detail
+pub mod gltf;
+mod lod;
 mod meshing_shape;
 mod normal_filters;
 /// I/O of wavefront obj
 pub mod obj;
 mod optimizing;
+/// I/O of PLY, e.g. the scanned points with the colors
+pub mod ply;
+mod point_cloud;
+/// Registration of point clouds and meshes by the iterative closest point algorithm
+pub mod registration;
+mod simplification;
+mod skin;
 /// I/O of STL
 pub mod stl;
+/// Streaming loaders of large obj and STL files with the memory budgets
+pub mod streaming;
 /// Defines [`PolygonMeshEditor`](./polygon_mesh/struct.PolygonMeshEditor.html).
 pub mod polygon_mesh;
 mod splitting;
 mod structured_mesh;
 mod structuring;
+/// Tetrahedral mesh generation and the export to Gmsh and VTK
+pub mod tetrahedral;
+/// Export of meshes and fields in VTK legacy and VTU formats
+pub mod vtk;
 
 /// Re-exports root structs and all mesh filter traits.
 pub mod prelude {
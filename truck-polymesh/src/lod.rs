@@ -0,0 +1,137 @@
+use crate::*;
+use std::collections::HashMap;
+
+// the number of the bisections searching the size of the clusters
+const LOD_SEARCH_TRIALS: usize = 24;
+
+impl PolygonMesh {
+    /// Returns the number of the triangles of the triangulated faces.
+    #[inline(always)]
+    pub fn triangle_count(&self) -> usize { self.face_iter().map(|face| face.len() - 2).sum() }
+
+    /// Returns the chain of the levels of detail, progressively decimated meshes.
+    ///
+    /// Each element of `ratios` is the ratio of the number of the triangles of the level to
+    /// the one of `self`, which should be decreasing in `(0, 1]`. The positions are decimated
+    /// by the clustering on the regular grid, whose size is searched so that the number of the
+    /// triangles does not exceed the ratio. The faces keep the indices of the uv coordinates
+    /// and the normals of the original corners, so all levels share the materials and the
+    /// textures of `self`. The skinning data is inherited from a representative position of
+    /// each cluster.
+    /// # Examples
+    /// ```
+    /// use truck_polymesh::*;
+    /// // the 20 x 20 grid with the uv coordinates
+    /// let positions: Vec<Point3> = (0..441)
+    ///     .map(|i| Point3::new((i % 21) as f64 / 20.0, (i / 21) as f64 / 20.0, 0.0))
+    ///     .collect();
+    /// let uv_coords: Vec<Vector2> = positions.iter().map(|p| Vector2::new(p.x, p.y)).collect();
+    /// let mut faces = Faces::default();
+    /// for i in 0..20 {
+    ///     for j in 0..20 {
+    ///         let idx = |i: usize, j: usize| {
+    ///             let k = i * 21 + j;
+    ///             [k, k, 0]
+    ///         };
+    ///         faces.push(&[idx(i, j), idx(i, j + 1), idx(i + 1, j + 1), idx(i + 1, j)]);
+    ///     }
+    /// }
+    /// let normals = vec![Vector3::unit_z()];
+    /// let mesh = PolygonMesh::new(positions, uv_coords, normals, faces);
+    /// assert_eq!(mesh.triangle_count(), 800);
+    ///
+    /// let lods = mesh.generate_lods(&[0.5, 0.1]);
+    /// assert_eq!(lods.len(), 2);
+    /// assert!(lods[0].triangle_count() <= 400 && lods[0].triangle_count() > 0);
+    /// assert!(lods[1].triangle_count() <= 80 && lods[1].triangle_count() > 0);
+    /// // the uv coordinates are shared
+    /// assert_eq!(lods[1].uv_coords(), mesh.uv_coords());
+    /// ```
+    pub fn generate_lods(&self, ratios: &[f64]) -> Vec<PolygonMesh> {
+        let count = self.triangle_count();
+        let diagonal = self.bounding_box().diagonal().magnitude();
+        ratios
+            .iter()
+            .map(|ratio| {
+                if *ratio >= 1.0 || count == 0 {
+                    return self.clone();
+                }
+                let target = (count as f64 * f64::max(*ratio, 0.0)).floor() as usize;
+                let (mut lower, mut upper) = (0.0, diagonal);
+                let mut res = self.clustered(upper);
+                for _ in 0..LOD_SEARCH_TRIALS {
+                    let size = (lower + upper) / 2.0;
+                    let mesh = self.clustered(size);
+                    match mesh.triangle_count() > target {
+                        true => lower = size,
+                        false => {
+                            upper = size;
+                            res = mesh;
+                        }
+                    }
+                }
+                res
+            })
+            .collect()
+    }
+
+    // Merges the positions in each cell of the grid whose size is `size`, and removes the
+    // degenerate triangles.
+    fn clustered(&self, size: f64) -> PolygonMesh {
+        let bdb = self.bounding_box();
+        let min = *bdb.min();
+        let mut clusters = HashMap::<[i64; 3], usize>::new();
+        let mut sums: Vec<(Vector3, usize, usize)> = Vec::new();
+        let map: Vec<usize> = self
+            .positions
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let v = p - min;
+                let key = [
+                    (v[0] / size).floor() as i64,
+                    (v[1] / size).floor() as i64,
+                    (v[2] / size).floor() as i64,
+                ];
+                let idx = *clusters.entry(key).or_insert_with(|| {
+                    sums.push((Vector3::zero(), 0, i));
+                    sums.len() - 1
+                });
+                sums[idx].0 += p.to_vec();
+                sums[idx].1 += 1;
+                idx
+            })
+            .collect();
+        let positions: Vec<Point3> = sums
+            .iter()
+            .map(|(sum, n, _)| Point3::from_vec(sum / *n as f64))
+            .collect();
+        let tri_faces: Vec<[Vertex; 3]> = self
+            .face_iter()
+            .flat_map(|face| (2..face.len()).map(move |i| [face[0], face[i - 1], face[i]]))
+            .filter_map(|tri| {
+                let mut tri = tri;
+                tri.iter_mut().for_each(|v| v.pos = map[v.pos]);
+                let degenerate = tri[0].pos == tri[1].pos
+                    || tri[1].pos == tri[2].pos
+                    || tri[2].pos == tri[0].pos;
+                match degenerate {
+                    true => None,
+                    false => Some(tri),
+                }
+            })
+            .collect();
+        let skin = self.skin.as_ref().map(|skin| Skin {
+            joints: sums.iter().map(|(_, _, i)| skin.joints[*i]).collect(),
+            weights: sums.iter().map(|(_, _, i)| skin.weights[*i]).collect(),
+            inverse_bind_matrices: skin.inverse_bind_matrices.clone(),
+        });
+        PolygonMesh {
+            positions,
+            uv_coords: self.uv_coords.clone(),
+            normals: self.normals.clone(),
+            faces: Faces::from_iter(&tri_faces),
+            skin,
+        }
+    }
+}
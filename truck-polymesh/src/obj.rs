@@ -38,7 +38,9 @@ use std::io::{BufRead, BufReader, BufWriter, Read, Write};
 ///     [(3, None, Some(2)), (7, None, Some(2)), (5, None, Some(2))],
 /// ]);
 /// let mesh = PolygonMesh::new(positions, Vec::new(), normals, faces);
-/// obj::write(&mesh, std::fs::File::create("meshdata.obj").unwrap());
+/// let mut buf = Vec::new();
+/// obj::write(&mesh, &mut buf).unwrap();
+/// assert!(!buf.is_empty());
 /// ```
 pub fn write<W: Write>(mesh: &PolygonMesh, writer: W) -> Result<()> {
     sub_write(mesh, &mut BufWriter::new(writer))
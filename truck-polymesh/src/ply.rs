@@ -0,0 +1,552 @@
+use crate::*;
+use errors::Error;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+
+fn invalid_data(message: &str) -> Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, message).into()
+}
+
+fn syntax_error() -> Error { invalid_data("syntax error") }
+
+/// Converts the value of a list length or a vertex index to `usize`.
+fn to_index(x: f64) -> Result<usize> {
+    match x >= 0.0 && x.fract() == 0.0 {
+        true => Ok(x as usize),
+        false => Err(invalid_data("negative index or length")),
+    }
+}
+
+/// PLY format
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PLYType {
+    /// ascii format
+    ASCII,
+    /// little-endian binary format
+    BinaryLittleEndian,
+}
+
+impl Default for PLYType {
+    #[inline(always)]
+    fn default() -> PLYType { PLYType::BinaryLittleEndian }
+}
+
+/// The polygon mesh with the colors of the vertices.
+///
+/// The vertices of PLY have all attributes in common, so the `i`-th position, uv coordinate,
+/// normal and color belong to the `i`-th vertex in the read mesh.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PLYMesh {
+    /// the polygon mesh. The mesh of a point cloud has no faces.
+    pub mesh: PolygonMesh,
+    /// the RGBA colors of the positions in `[0, 1]`, or empty if the vertices have no colors.
+    pub colors: Vec<Vector4>,
+}
+
+impl PLYMesh {
+    /// Returns the point cloud of the positions and the normals.
+    /// # Errors
+    /// Returns [`Error::DifferentLengthArrays`] if the number of the normals is not equal to
+    /// the one of the positions.
+    ///
+    /// [`Error::DifferentLengthArrays`]: ../errors/enum.Error.html#variant.DifferentLengthArrays
+    #[inline(always)]
+    pub fn point_cloud(&self) -> Result<PointCloud> {
+        PointCloud::try_new(self.mesh.positions().clone(), self.mesh.normals().clone())
+    }
+}
+
+impl From<PolygonMesh> for PLYMesh {
+    #[inline(always)]
+    fn from(mesh: PolygonMesh) -> PLYMesh {
+        PLYMesh {
+            mesh,
+            colors: Vec::new(),
+        }
+    }
+}
+
+impl From<&PointCloud> for PLYMesh {
+    #[inline(always)]
+    fn from(cloud: &PointCloud) -> PLYMesh {
+        let (positions, normals) = (cloud.positions().clone(), cloud.normals().clone());
+        PolygonMesh::new(positions, Vec::new(), normals, Faces::default()).into()
+    }
+}
+
+/// scalar types of the properties
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Scalar {
+    Int8,
+    UInt8,
+    Int16,
+    UInt16,
+    Int32,
+    UInt32,
+    Float32,
+    Float64,
+}
+
+impl Scalar {
+    fn parse(name: &str) -> Result<Scalar> {
+        match name {
+            "char" | "int8" => Ok(Scalar::Int8),
+            "uchar" | "uint8" => Ok(Scalar::UInt8),
+            "short" | "int16" => Ok(Scalar::Int16),
+            "ushort" | "uint16" => Ok(Scalar::UInt16),
+            "int" | "int32" => Ok(Scalar::Int32),
+            "uint" | "uint32" => Ok(Scalar::UInt32),
+            "float" | "float32" => Ok(Scalar::Float32),
+            "double" | "float64" => Ok(Scalar::Float64),
+            _ => Err(syntax_error()),
+        }
+    }
+
+    /// the scale of the colors, which map the maximum of the unsigned integers to `1`.
+    fn color_scale(self) -> f64 {
+        match self {
+            Scalar::UInt8 => 1.0 / u8::MAX as f64,
+            Scalar::UInt16 => 1.0 / u16::MAX as f64,
+            Scalar::UInt32 => 1.0 / u32::MAX as f64,
+            _ => 1.0,
+        }
+    }
+
+    /// Parses the ascii value. The integers must be written as integers in the range of the type.
+    fn parse_ascii(self, token: &str) -> Result<f64> {
+        let (min, max) = match self {
+            Scalar::Int8 => (i8::MIN as i64, i8::MAX as i64),
+            Scalar::UInt8 => (0, u8::MAX as i64),
+            Scalar::Int16 => (i16::MIN as i64, i16::MAX as i64),
+            Scalar::UInt16 => (0, u16::MAX as i64),
+            Scalar::Int32 => (i32::MIN as i64, i32::MAX as i64),
+            Scalar::UInt32 => (0, u32::MAX as i64),
+            Scalar::Float32 | Scalar::Float64 => return Ok(token.parse::<f64>()?),
+        };
+        let x = token.parse::<i64>()?;
+        match (min..=max).contains(&x) {
+            true => Ok(x as f64),
+            false => Err(invalid_data("integer out of range")),
+        }
+    }
+
+    fn read_binary<R: Read>(self, reader: &mut R) -> Result<f64> {
+        fn bytes<R: Read, const N: usize>(reader: &mut R) -> Result<[u8; N]> {
+            let mut buf = [0; N];
+            reader.read_exact(&mut buf)?;
+            Ok(buf)
+        }
+        Ok(match self {
+            Scalar::Int8 => i8::from_le_bytes(bytes(reader)?) as f64,
+            Scalar::UInt8 => u8::from_le_bytes(bytes(reader)?) as f64,
+            Scalar::Int16 => i16::from_le_bytes(bytes(reader)?) as f64,
+            Scalar::UInt16 => u16::from_le_bytes(bytes(reader)?) as f64,
+            Scalar::Int32 => i32::from_le_bytes(bytes(reader)?) as f64,
+            Scalar::UInt32 => u32::from_le_bytes(bytes(reader)?) as f64,
+            Scalar::Float32 => f32::from_le_bytes(bytes(reader)?) as f64,
+            Scalar::Float64 => f64::from_le_bytes(bytes(reader)?),
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Property {
+    Scalar(Scalar, String),
+    List(Scalar, Scalar, String),
+}
+
+impl Property {
+    fn name(&self) -> &str {
+        match self {
+            Property::Scalar(_, name) => name,
+            Property::List(_, _, name) => name,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+impl Element {
+    fn position(&self, names: &[&str]) -> Option<usize> {
+        self.properties
+            .iter()
+            .position(|property| names.contains(&property.name()))
+    }
+
+    fn scalar_type(&self, idx: usize) -> Scalar {
+        match &self.properties[idx] {
+            Property::Scalar(scalar, _) => *scalar,
+            Property::List(_, scalar, _) => *scalar,
+        }
+    }
+}
+
+/// Parses the header, and returns the format and the elements.
+fn read_header<R: BufRead>(reader: &mut R) -> Result<(PLYType, Vec<Element>)> {
+    let mut line = String::new();
+    let mut next_line = |line: &mut String| -> Result<()> {
+        line.clear();
+        match reader.read_line(line)? {
+            0 => Err(syntax_error()),
+            _ => Ok(()),
+        }
+    };
+    next_line(&mut line)?;
+    if line.trim() != "ply" {
+        return Err(syntax_error());
+    }
+    let mut ply_type = None;
+    let mut elements = Vec::<Element>::new();
+    loop {
+        next_line(&mut line)?;
+        let args: Vec<&str> = line.split_whitespace().collect();
+        match args.as_slice() {
+            ["format", "ascii", _] => ply_type = Some(PLYType::ASCII),
+            ["format", "binary_little_endian", _] => {
+                ply_type = Some(PLYType::BinaryLittleEndian)
+            }
+            ["format", format, _] => return Err(Error::UnsupportedFormat(format.to_string())),
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse()?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count, item, name] => {
+                let (count, item) = (Scalar::parse(count)?, Scalar::parse(item)?);
+                let property = Property::List(count, item, name.to_string());
+                elements.last_mut().ok_or_else(syntax_error)?.properties.push(property);
+            }
+            ["property", scalar, name] => {
+                let property = Property::Scalar(Scalar::parse(scalar)?, name.to_string());
+                elements.last_mut().ok_or_else(syntax_error)?.properties.push(property);
+            }
+            ["end_header"] => break,
+            ["comment", ..] | ["obj_info", ..] | [] => {}
+            _ => return Err(syntax_error()),
+        }
+    }
+    Ok((ply_type.ok_or_else(syntax_error)?, elements))
+}
+
+/// A row of an element. The value of a list property is its length, and the items are
+/// stored in `lists` with the index of the property.
+#[derive(Clone, Debug, Default)]
+struct Row {
+    values: Vec<f64>,
+    lists: Vec<(usize, Vec<f64>)>,
+}
+
+impl Row {
+    fn list(&self, idx: usize) -> &[f64] {
+        match self.lists.iter().find(|(i, _)| *i == idx) {
+            Some((_, list)) => list,
+            None => &[],
+        }
+    }
+}
+
+fn read_rows(
+    element: &Element,
+    read_scalar: &mut impl FnMut(Scalar) -> Result<f64>,
+) -> Result<Vec<Row>> {
+    let mut rows = Vec::new();
+    for _ in 0..element.count {
+        let mut row = Row::default();
+        for (idx, property) in element.properties.iter().enumerate() {
+            match property {
+                Property::Scalar(scalar, _) => row.values.push(read_scalar(*scalar)?),
+                Property::List(count, item, _) => {
+                    let len = to_index(read_scalar(*count)?)?;
+                    let list = (0..len)
+                        .map(|_| read_scalar(*item))
+                        .collect::<Result<Vec<_>>>()?;
+                    row.values.push(len as f64);
+                    row.lists.push((idx, list));
+                }
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+/// Reads the PLY data in ascii or little-endian binary format.
+///
+/// The positions, the normals `nx`, `ny`, `nz`, the uv coordinates `u`, `v` or `s`, `t`, and
+/// the colors `red`, `green`, `blue`, `alpha` of the vertices, and the indices of the faces
+/// are read, and the other elements and properties are skipped.
+/// The colors of the unsigned integer types are normalized to `[0, 1]`.
+/// # Errors
+/// Returns `Error::UnsupportedFormat` if the format is big-endian binary, besides the errors
+/// of I/O and parsing. The integer values out of the range of their types, the negative
+/// lengths of lists and vertex indices, and the faces with less than three vertices are
+/// rejected as `std::io::ErrorKind::InvalidData`.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::ply::*;
+/// let data = b"ply
+/// format ascii 1.0
+/// comment a colored triangle
+/// element vertex 3
+/// property float x
+/// property float y
+/// property float z
+/// property uchar red
+/// property uchar green
+/// property uchar blue
+/// element face 1
+/// property list uchar int vertex_indices
+/// end_header
+/// 0 0 0 255 0 0
+/// 1 0 0 0 255 0
+/// 0 1 0 0 0 255
+/// 3 0 1 2
+/// ";
+/// let ply = ply::read(&data[..]).unwrap();
+/// assert_eq!(ply.mesh.positions().len(), 3);
+/// assert_eq!(ply.mesh.tri_faces().len(), 1);
+/// assert_eq!(ply.colors[1], Vector4::new(0.0, 1.0, 0.0, 1.0));
+///
+/// // the round trip in the binary format
+/// let mut binary = Vec::new();
+/// ply::write(&ply, &mut binary, PLYType::BinaryLittleEndian).unwrap();
+/// let ply2 = ply::read(&binary[..]).unwrap();
+/// assert_eq!(ply2.mesh.positions(), ply.mesh.positions());
+/// assert_eq!(ply2.colors, ply.colors);
+/// ```
+pub fn read<R: Read>(reader: R) -> Result<PLYMesh> {
+    let mut reader = BufReader::new(reader);
+    let (ply_type, elements) = read_header(&mut reader)?;
+    let mut all_rows = Vec::with_capacity(elements.len());
+    match ply_type {
+        PLYType::ASCII => {
+            let mut body = String::new();
+            reader.read_to_string(&mut body)?;
+            let mut tokens = body.split_whitespace();
+            let mut read_scalar = |scalar: Scalar| -> Result<f64> {
+                scalar.parse_ascii(tokens.next().ok_or_else(syntax_error)?)
+            };
+            for element in &elements {
+                all_rows.push(read_rows(element, &mut read_scalar)?);
+            }
+        }
+        PLYType::BinaryLittleEndian => {
+            let mut read_scalar = |scalar: Scalar| scalar.read_binary(&mut reader);
+            for element in &elements {
+                all_rows.push(read_rows(element, &mut read_scalar)?);
+            }
+        }
+    }
+    let mut ply = PLYMesh::default();
+    let (mut positions, mut uv_coords, mut normals) = (Vec::new(), Vec::new(), Vec::new());
+    let mut faces = Faces::default();
+    for (element, rows) in elements.iter().zip(all_rows) {
+        match element.name.as_str() {
+            "vertex" => {
+                let find = |names: &[&[&str]]| -> Option<Vec<usize>> {
+                    names.iter().map(|names| element.position(names)).collect()
+                };
+                let xyz = find(&[&["x"], &["y"], &["z"]]).ok_or_else(syntax_error)?;
+                let nor = find(&[&["nx"], &["ny"], &["nz"]]);
+                let uv = find(&[&["u", "s", "texture_u"], &["v", "t", "texture_v"]]);
+                let rgb = find(&[&["red", "r"], &["green", "g"], &["blue", "b"]]);
+                let alpha = element.position(&["alpha", "a"]);
+                for row in rows.iter().map(|row| &row.values) {
+                    positions.push(Point3::new(row[xyz[0]], row[xyz[1]], row[xyz[2]]));
+                    if let Some(nor) = &nor {
+                        normals.push(Vector3::new(row[nor[0]], row[nor[1]], row[nor[2]]));
+                    }
+                    if let Some(uv) = &uv {
+                        uv_coords.push(Vector2::new(row[uv[0]], row[uv[1]]));
+                    }
+                    if let Some(rgb) = &rgb {
+                        let value = |idx: usize| row[idx] * element.scalar_type(idx).color_scale();
+                        let alpha = alpha.map(value).unwrap_or(1.0);
+                        ply.colors
+                            .push(Vector4::new(value(rgb[0]), value(rgb[1]), value(rgb[2]), alpha));
+                    }
+                }
+            }
+            "face" => {
+                let idx = element
+                    .position(&["vertex_indices", "vertex_index"])
+                    .ok_or_else(syntax_error)?;
+                for row in &rows {
+                    let face = row
+                        .list(idx)
+                        .iter()
+                        .map(|i| {
+                            let i = to_index(*i)?;
+                            Ok(Vertex {
+                                pos: i,
+                                uv: (!uv_coords.is_empty()).then_some(i),
+                                nor: (!normals.is_empty()).then_some(i),
+                            })
+                        })
+                        .collect::<Result<Vec<Vertex>>>()?;
+                    if face.len() < 3 {
+                        return Err(invalid_data("face with less than three vertices"));
+                    }
+                    faces.push(face);
+                }
+            }
+            _ => {}
+        }
+    }
+    ply.mesh = PolygonMesh::try_new(positions, uv_coords, normals, faces)?;
+    Ok(ply)
+}
+
+/// Writes the PLY data in `ply_type` format.
+///
+/// The vertices of the faces with the same position, uv coordinate and normal are merged,
+/// and the colors are the ones of the positions. The normals and the uv coordinates are
+/// written if all vertices have them, and the colors are written as `uchar` if `ply.colors`
+/// is not empty. If the mesh has no faces, all positions are written as a point cloud.
+/// # Errors
+/// Returns [`Error::InvalidFieldLength`] if `ply.colors` is neither empty nor has the same
+/// length as the positions.
+///
+/// [`Error::InvalidFieldLength`]: ../errors/enum.Error.html#variant.InvalidFieldLength
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::ply::*;
+/// let positions = vec![Point3::new(0.0, 0.0, 0.0), Point3::new(1.0, 0.0, 0.0)];
+/// let normals = vec![Vector3::unit_z(), Vector3::unit_x()];
+/// let mut ply = PLYMesh::from(&PointCloud::new(positions, normals));
+/// ply.colors = vec![Vector4::new(1.0, 0.0, 0.0, 1.0), Vector4::new(0.0, 0.0, 1.0, 1.0)];
+///
+/// let mut output = Vec::new();
+/// ply::write(&ply, &mut output, PLYType::ASCII).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("element vertex 2\n"));
+/// assert!(output.contains("0 0 0 0 0 1 255 0 0 255\n"));
+///
+/// let cloud = ply::read(output.as_bytes()).unwrap().point_cloud().unwrap();
+/// assert_eq!(cloud.normals()[1], Vector3::unit_x());
+/// ```
+pub fn write<W: Write>(ply: &PLYMesh, writer: W, ply_type: PLYType) -> Result<()> {
+    let mesh = &ply.mesh;
+    let len = mesh.positions().len();
+    if !ply.colors.is_empty() && ply.colors.len() != len {
+        return Err(Error::InvalidFieldLength("colors".into(), len, ply.colors.len()));
+    }
+    let mut vertices = Vec::<Vertex>::new();
+    let mut faces = Vec::<Vec<usize>>::new();
+    if mesh.faces().len() == 0 {
+        let has_normals = mesh.normals().len() == len;
+        vertices = (0..len)
+            .map(|i| Vertex {
+                pos: i,
+                uv: None,
+                nor: has_normals.then_some(i),
+            })
+            .collect();
+    } else {
+        let mut map = HashMap::<Vertex, usize>::new();
+        for face in mesh.face_iter() {
+            let face = face
+                .iter()
+                .map(|v| {
+                    *map.entry(*v).or_insert_with(|| {
+                        vertices.push(*v);
+                        vertices.len() - 1
+                    })
+                })
+                .collect();
+            faces.push(face);
+        }
+    }
+    let has_uv = !vertices.is_empty() && vertices.iter().all(|v| v.uv.is_some());
+    let has_normals = !vertices.is_empty() && vertices.iter().all(|v| v.nor.is_some());
+    let has_colors = !ply.colors.is_empty();
+
+    let mut writer = BufWriter::new(writer);
+    let format = match ply_type {
+        PLYType::ASCII => "ascii",
+        PLYType::BinaryLittleEndian => "binary_little_endian",
+    };
+    writer.write_fmt(format_args!("ply\nformat {} 1.0\ncomment truck\n", format))?;
+    writer.write_fmt(format_args!("element vertex {}\n", vertices.len()))?;
+    writer.write_all(b"property double x\nproperty double y\nproperty double z\n")?;
+    if has_normals {
+        writer.write_all(b"property double nx\nproperty double ny\nproperty double nz\n")?;
+    }
+    if has_uv {
+        writer.write_all(b"property double u\nproperty double v\n")?;
+    }
+    if has_colors {
+        writer.write_all(b"property uchar red\nproperty uchar green\nproperty uchar blue\n")?;
+        writer.write_all(b"property uchar alpha\n")?;
+    }
+    // The number of the vertices of a face is written as `uchar` if possible, which is the
+    // most common one.
+    let short_faces = faces.iter().all(|face| face.len() <= u8::MAX as usize);
+    if !faces.is_empty() {
+        let count = if short_faces { "uchar" } else { "uint" };
+        writer.write_fmt(format_args!("element face {}\n", faces.len()))?;
+        writer.write_fmt(format_args!("property list {} uint vertex_indices\n", count))?;
+    }
+    writer.write_all(b"end_header\n")?;
+
+    for v in &vertices {
+        let p = mesh.positions()[v.pos];
+        let mut values = vec![p.x, p.y, p.z];
+        if let Some(nor) = v.nor.filter(|_| has_normals) {
+            let n = mesh.normals()[nor];
+            values.extend([n.x, n.y, n.z]);
+        }
+        if let Some(uv) = v.uv.filter(|_| has_uv) {
+            let uv = mesh.uv_coords()[uv];
+            values.extend([uv.x, uv.y]);
+        }
+        let color = match has_colors {
+            true => {
+                let c = ply.colors[v.pos];
+                let byte = |x: f64| (x.clamp(0.0, 1.0) * u8::MAX as f64).round() as u8;
+                vec![byte(c.x), byte(c.y), byte(c.z), byte(c.w)]
+            }
+            false => Vec::new(),
+        };
+        match ply_type {
+            PLYType::ASCII => {
+                let mut line: Vec<String> = values.iter().map(|x| x.to_string()).collect();
+                line.extend(color.iter().map(|x| x.to_string()));
+                writer.write_fmt(format_args!("{}\n", line.join(" ")))?;
+            }
+            PLYType::BinaryLittleEndian => {
+                values
+                    .iter()
+                    .try_for_each(|x| writer.write_all(&x.to_le_bytes()))?;
+                writer.write_all(&color)?;
+            }
+        }
+    }
+    for face in &faces {
+        match ply_type {
+            PLYType::ASCII => {
+                writer.write_fmt(format_args!("{}", face.len()))?;
+                face.iter()
+                    .try_for_each(|i| writer.write_fmt(format_args!(" {}", i)))?;
+                writer.write_all(b"\n")?;
+            }
+            PLYType::BinaryLittleEndian => {
+                match short_faces {
+                    true => writer.write_all(&[face.len() as u8])?,
+                    false => writer.write_all(&(face.len() as u32).to_le_bytes())?,
+                }
+                face.iter()
+                    .try_for_each(|i| writer.write_all(&(*i as u32).to_le_bytes()))?;
+            }
+        }
+    }
+    writer.flush()?;
+    Ok(())
+}
@@ -0,0 +1,307 @@
+use crate::errors::Error;
+use crate::*;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+impl PointCloud {
+    /// Creates a point cloud from positions and normals.
+    /// # Panics
+    /// Panic occurs if the length of `positions` and the one of `normals` are different.
+    #[inline(always)]
+    pub fn new(positions: Vec<Point3>, normals: Vec<Vector3>) -> PointCloud {
+        PointCloud::try_new(positions, normals).unwrap_or_else(|e| panic!("{:?}", e))
+    }
+    /// Creates a point cloud from positions and normals.
+    /// # Errors
+    /// Returns [`Error::DifferentLengthArrays`] if the length of `positions`
+    /// and the one of `normals` are different.
+    ///
+    /// [`Error::DifferentLengthArrays`]: ./errors/enum.Error.html#variant.DifferentLengthArrays
+    #[inline(always)]
+    pub fn try_new(positions: Vec<Point3>, normals: Vec<Vector3>) -> Result<PointCloud> {
+        match positions.len() == normals.len() {
+            true => Ok(PointCloud { positions, normals }),
+            false => Err(Error::DifferentLengthArrays),
+        }
+    }
+    /// Returns the vector of all positions.
+    #[inline(always)]
+    pub fn positions(&self) -> &Vec<Point3> { &self.positions }
+    /// Returns the vector of all normals.
+    #[inline(always)]
+    pub fn normals(&self) -> &Vec<Vector3> { &self.normals }
+    /// Adds a point.
+    #[inline(always)]
+    pub fn push(&mut self, position: Point3, normal: Vector3) {
+        self.positions.push(position);
+        self.normals.push(normal);
+    }
+    /// Returns the number of points.
+    #[inline(always)]
+    pub fn len(&self) -> usize { self.positions.len() }
+    /// Returns whether the point cloud has no points or not.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.positions.is_empty() }
+    /// Creates the bounding box of the point cloud.
+    #[inline(always)]
+    pub fn bounding_box(&self) -> BoundingBox<Point3> { self.positions.iter().collect() }
+
+    /// Reconstructs the surface from the oriented points.
+    ///
+    /// The signed distance function is estimated by the tangent plane of the nearest point,
+    /// and its zero level set is extracted by marching tetrahedra on the grid whose
+    /// longest side is divided by `division`.
+    /// The returned mesh consists only of triangles and is always closed and consistently
+    /// oriented, i.e. watertight, since the grid is bounded by the nodes outside of the surface.
+    /// # Examples
+    /// ```
+    /// use truck_polymesh::*;
+    /// use std::f64::consts::PI;
+    /// // points on the unit sphere
+    /// let (positions, normals): (Vec<Point3>, Vec<Vector3>) = (0..1000)
+    ///     .map(|i| {
+    ///         let z = 1.0 - (2 * i + 1) as f64 / 1000.0;
+    ///         let theta = PI * (3.0 - f64::sqrt(5.0)) * i as f64;
+    ///         let r = f64::sqrt(1.0 - z * z);
+    ///         let normal = Vector3::new(r * theta.cos(), r * theta.sin(), z);
+    ///         (Point3::from_vec(normal), normal)
+    ///     })
+    ///     .unzip();
+    /// let cloud = PointCloud::new(positions, normals);
+    /// let mesh = cloud.reconstruct(16);
+    /// assert!(!mesh.tri_faces().is_empty());
+    /// for pt in mesh.positions() {
+    ///     assert!((pt.to_vec().magnitude() - 1.0).abs() < 0.1);
+    /// }
+    /// ```
+    pub fn reconstruct(&self, division: usize) -> PolygonMesh {
+        if self.is_empty() || division == 0 {
+            return PolygonMesh::default();
+        }
+        let bdd = self.bounding_box();
+        let diag = bdd.diagonal();
+        let size = f64::max(diag[0], f64::max(diag[1], diag[2]));
+        let h = match size.so_small() {
+            true => 1.0,
+            false => size / division as f64,
+        };
+        const MARGIN: usize = 2;
+        let origin = bdd.min() - Vector3::new(h, h, h) * MARGIN as f64;
+        let n = [
+            (diag[0] / h).ceil() as usize + 2 * MARGIN + 1,
+            (diag[1] / h).ceil() as usize + 2 * MARGIN + 1,
+            (diag[2] / h).ceil() as usize + 2 * MARGIN + 1,
+        ];
+        let node = |idx: [usize; 3]| idx[0] + n[0] * (idx[1] + n[1] * idx[2]);
+        let node_position = |i: usize| {
+            let idx = [i % n[0], (i / n[0]) % n[1], i / (n[0] * n[1])];
+            origin + Vector3::new(idx[0] as f64, idx[1] as f64, idx[2] as f64) * h
+        };
+
+        let tree = KdTree::new(&self.positions);
+        let values: Vec<f64> = (0..n[0] * n[1] * n[2])
+            .map(|i| {
+                let idx = [i % n[0], (i / n[0]) % n[1], i / (n[0] * n[1])];
+                let on_boundary = (0..3).any(|j| idx[j] == 0 || idx[j] == n[j] - 1);
+                let pt = node_position(i);
+                match (on_boundary, tree.nearest(pt)) {
+                    (false, Some(j)) => {
+                        let normal = self.normals[j];
+                        match normal.so_small() {
+                            true => (pt - self.positions[j]).magnitude(),
+                            false => (pt - self.positions[j]).dot(normal.normalize()),
+                        }
+                    }
+                    _ => h,
+                }
+            })
+            .collect();
+
+        let crossing_point = |(a, b): (usize, usize)| {
+            let t = values[a] / (values[a] - values[b]);
+            let (p0, p1) = (node_position(a), node_position(b));
+            p0 + (p1 - p0) * t
+        };
+        let mut positions = Vec::new();
+        let mut vertex_map: HashMap<(usize, usize), usize> = HashMap::new();
+        let mut faces = Vec::new();
+        for k in 0..n[2] - 1 {
+            for j in 0..n[1] - 1 {
+                for i in 0..n[0] - 1 {
+                    let corners: Vec<usize> = (0..8)
+                        .map(|b| node([i + (b & 1), j + ((b >> 1) & 1), k + ((b >> 2) & 1)]))
+                        .collect();
+                    for tet in &KUHN_TETRAHEDRA {
+                        let tet = [
+                            corners[tet[0]],
+                            corners[tet[1]],
+                            corners[tet[2]],
+                            corners[tet[3]],
+                        ];
+                        let (inside, outside): (Vec<usize>, Vec<usize>) =
+                            tet.iter().copied().partition(|i| values[*i] < 0.0);
+                        // triangles whose vertices are given by the crossing edges
+                        let tris = match (inside.len(), outside.len()) {
+                            (1, 3) => vec![[
+                                (inside[0], outside[0]),
+                                (inside[0], outside[1]),
+                                (inside[0], outside[2]),
+                            ]],
+                            (3, 1) => vec![[
+                                (inside[0], outside[0]),
+                                (inside[1], outside[0]),
+                                (inside[2], outside[0]),
+                            ]],
+                            (2, 2) => {
+                                let quad = [
+                                    (inside[0], outside[0]),
+                                    (inside[0], outside[1]),
+                                    (inside[1], outside[1]),
+                                    (inside[1], outside[0]),
+                                ];
+                                vec![[quad[0], quad[1], quad[2]], [quad[0], quad[2], quad[3]]]
+                            }
+                            _ => continue,
+                        };
+                        let center = |vec: &Vec<usize>| {
+                            vec.iter().fold(Vector3::zero(), |sum, i| {
+                                sum + node_position(*i).to_vec()
+                            }) / vec.len() as f64
+                        };
+                        let dir = center(&outside) - center(&inside);
+                        for [a, b, c] in tris {
+                            let pts = [crossing_point(a), crossing_point(b), crossing_point(c)];
+                            let normal = (pts[1] - pts[0]).cross(pts[2] - pts[0]);
+                            let tri = match normal.dot(dir) < 0.0 {
+                                true => [a, c, b],
+                                false => [a, b, c],
+                            };
+                            let mut face = [0; 3];
+                            for (idx, (a, b)) in face.iter_mut().zip(tri.iter()) {
+                                let key = (usize::min(*a, *b), usize::max(*a, *b));
+                                *idx = *vertex_map.entry(key).or_insert_with(|| {
+                                    positions.push(crossing_point(key));
+                                    positions.len() - 1
+                                });
+                            }
+                            faces.push(face);
+                        }
+                    }
+                }
+            }
+        }
+        PolygonMesh::new(positions, Vec::new(), Vec::new(), Faces::from_iter(faces))
+    }
+}
+
+impl From<&PolygonMesh> for PointCloud {
+    /// Extracts the vertices of the mesh.
+    ///
+    /// The normal of each point is the average of the normals of the vertices at the position,
+    /// or, if the mesh has no normals there, the area-weighted average of the adjacent face normals.
+    fn from(mesh: &PolygonMesh) -> PointCloud {
+        let positions = mesh.positions().clone();
+        let mut attr_normals = vec![Vector3::zero(); positions.len()];
+        let mut face_normals = vec![Vector3::zero(); positions.len()];
+        mesh.face_iter().for_each(|face| {
+            let len = face.len();
+            let normal = (0..len).fold(Vector3::zero(), |sum, i| {
+                let p0 = positions[face[i].pos].to_vec();
+                let p1 = positions[face[(i + 1) % len].pos].to_vec();
+                sum + p0.cross(p1)
+            });
+            face.iter().for_each(|v| {
+                face_normals[v.pos] += normal;
+                if let Some(nor) = v.nor {
+                    attr_normals[v.pos] += mesh.normals()[nor];
+                }
+            })
+        });
+        let normals = attr_normals
+            .into_iter()
+            .zip(face_normals)
+            .map(|(attr, face)| match (attr.so_small(), face.so_small()) {
+                (false, _) => attr.normalize(),
+                (true, false) => face.normalize(),
+                (true, true) => Vector3::zero(),
+            })
+            .collect();
+        PointCloud { positions, normals }
+    }
+}
+
+/// Freudenthal-Kuhn decomposition of a cube into six tetrahedra.
+/// The corners of the cube are indexed by the bits of (x, y, z).
+/// Since all the tetrahedra share the main diagonal, the decomposition is compatible
+/// with the adjacent cubes.
+const KUHN_TETRAHEDRA: [[usize; 4]; 6] = [
+    [0, 1, 3, 7],
+    [0, 1, 5, 7],
+    [0, 2, 3, 7],
+    [0, 2, 6, 7],
+    [0, 4, 5, 7],
+    [0, 4, 6, 7],
+];
+
+/// Static k-d tree for the nearest point search.
+#[derive(Clone, Debug)]
+pub(crate) struct KdTree<'a> {
+    points: &'a [Point3],
+    indices: Vec<usize>,
+}
+
+impl<'a> KdTree<'a> {
+    pub(crate) fn new(points: &'a [Point3]) -> KdTree<'a> {
+        let mut indices: Vec<usize> = (0..points.len()).collect();
+        build_kdtree(points, &mut indices, 0);
+        KdTree { points, indices }
+    }
+
+    /// Returns the index of the nearest point. Returns `None` if there are no points.
+    pub(crate) fn nearest(&self, pt: Point3) -> Option<usize> {
+        let mut best = None;
+        self.search(&self.indices, 0, pt, &mut best);
+        best.map(|(idx, _)| idx)
+    }
+
+    fn search(&self, indices: &[usize], depth: usize, pt: Point3, best: &mut Option<(usize, f64)>) {
+        if indices.is_empty() {
+            return;
+        }
+        let mid = indices.len() / 2;
+        let idx = indices[mid];
+        let dist2 = self.points[idx].distance2(pt);
+        match best {
+            Some((_, best_dist2)) if *best_dist2 <= dist2 => {}
+            _ => *best = Some((idx, dist2)),
+        }
+        let axis = depth % 3;
+        let diff = pt[axis] - self.points[idx][axis];
+        let (near, far) = match diff < 0.0 {
+            true => (&indices[..mid], &indices[mid + 1..]),
+            false => (&indices[mid + 1..], &indices[..mid]),
+        };
+        self.search(near, depth + 1, pt, best);
+        if let Some((_, best_dist2)) = best {
+            if diff * diff < *best_dist2 {
+                self.search(far, depth + 1, pt, best);
+            }
+        }
+    }
+}
+
+fn build_kdtree(points: &[Point3], indices: &mut [usize], depth: usize) {
+    if indices.len() <= 1 {
+        return;
+    }
+    let axis = depth % 3;
+    let mid = indices.len() / 2;
+    indices.select_nth_unstable_by(mid, |a, b| {
+        points[*a][axis]
+            .partial_cmp(&points[*b][axis])
+            .unwrap_or(Ordering::Equal)
+    });
+    let (former, latter) = indices.split_at_mut(mid);
+    build_kdtree(points, former, depth + 1);
+    build_kdtree(points, &mut latter[1..], depth + 1);
+}
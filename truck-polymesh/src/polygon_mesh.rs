@@ -344,6 +344,7 @@ impl PolygonMesh {
             uv_coords,
             normals,
             faces,
+            skin: None,
         }
     }
 
@@ -492,6 +493,9 @@ impl PolygonMesh {
     }
 
     /// Returns polygonmesh merged `self` and `mesh`.
+    ///
+    /// The skins are merged only if both meshes have skins, and the joints of `mesh` follow
+    /// the ones of `self`. Otherwise, the skin of the merged mesh is removed.
     pub fn merge(&mut self, mut mesh: PolygonMesh) {
         let n_pos = self.positions.len();
         let n_uv = self.uv_coords.len();
@@ -503,6 +507,21 @@ impl PolygonMesh {
                 v.nor.as_mut().map(|nor| *nor += n_nor);
             })
         });
+        self.skin = match (self.skin.take(), mesh.skin) {
+            (Some(mut skin), Some(other)) => {
+                let n_joints = skin.inverse_bind_matrices.len();
+                let joints = other.joints.into_iter().map(|mut joints| {
+                    joints.iter_mut().for_each(|joint| *joint += n_joints);
+                    joints
+                });
+                skin.joints.extend(joints);
+                skin.weights.extend(other.weights);
+                skin.inverse_bind_matrices
+                    .extend(other.inverse_bind_matrices);
+                Some(skin)
+            }
+            _ => None,
+        };
         self.positions.extend(mesh.positions);
         self.uv_coords.extend(mesh.uv_coords);
         self.normals.extend(mesh.normals);
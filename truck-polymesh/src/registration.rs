@@ -0,0 +1,179 @@
+use crate::point_cloud::KdTree;
+use crate::*;
+
+/// The options of the iterative closest point algorithm.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RegistrationOption {
+    /// the initial guess of the transformation
+    pub initial: Matrix4,
+    /// If `true`, minimizes the distances between the source points and the tangent planes
+    /// of the target points. Otherwise, minimizes the distances between the points.
+    pub point_to_plane: bool,
+    /// The pairs whose distance is greater than `rejection_factor` times the median of the
+    /// distances of all pairs are rejected as outliers in each iteration.
+    pub rejection_factor: f64,
+    /// the maximum number of the iterations
+    pub max_iterations: usize,
+    /// The iteration is stopped if the rotation angle and the translation in one step
+    /// are both smaller than `tolerance`.
+    pub tolerance: f64,
+}
+
+impl Default for RegistrationOption {
+    #[inline(always)]
+    fn default() -> RegistrationOption {
+        RegistrationOption {
+            initial: Matrix4::identity(),
+            point_to_plane: true,
+            rejection_factor: 3.0,
+            max_iterations: 100,
+            tolerance: TOLERANCE,
+        }
+    }
+}
+
+/// Returns the rigid transformation which moves `source` onto `target`
+/// by the iterative closest point algorithm with the default option.
+///
+/// In order to register a mesh, convert it by `PointCloud::from(&mesh)`,
+/// or pass `mesh.positions()` as the source.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::registration;
+/// // points on the ellipsoid
+/// let (positions, normals): (Vec<Point3>, Vec<Vector3>) = (0..2000)
+///     .map(|i| {
+///         let z = 1.0 - (2 * i + 1) as f64 / 2000.0;
+///         let theta = std::f64::consts::PI * (3.0 - f64::sqrt(5.0)) * i as f64;
+///         let r = f64::sqrt(1.0 - z * z);
+///         let pt = Point3::new(3.0 * r * theta.cos(), 2.0 * r * theta.sin(), z);
+///         let normal = Vector3::new(pt[0] / 9.0, pt[1] / 4.0, pt[2]).normalize();
+///         (pt, normal)
+///     })
+///     .unzip();
+/// let target = PointCloud::new(positions, normals);
+///
+/// let answer = Matrix4::from_translation(Vector3::new(0.1, -0.2, 0.15))
+///     * Matrix4::from_axis_angle(Vector3::new(1.0, 1.0, 0.0).normalize(), Rad(0.1));
+/// let inverse = answer.invert().unwrap();
+/// let source: Vec<Point3> = target
+///     .positions()
+///     .iter()
+///     .map(|pt| inverse.transform_point(*pt))
+///     .collect();
+///
+/// let mat = registration::register(&source, &target);
+/// source.iter().zip(target.positions()).for_each(|(p, q)| {
+///     assert!(mat.transform_point(*p).distance(*q) < 1.0e-4);
+/// });
+/// ```
+#[inline(always)]
+pub fn register(source: &[Point3], target: &PointCloud) -> Matrix4 {
+    register_with_option(source, target, RegistrationOption::default())
+}
+
+/// Returns the rigid transformation which moves `source` onto `target`
+/// by the iterative closest point algorithm.
+/// # Remarks
+/// If `option.point_to_plane` is `true`, the pairs whose target normal is zero are ignored.
+pub fn register_with_option(
+    source: &[Point3],
+    target: &PointCloud,
+    option: RegistrationOption,
+) -> Matrix4 {
+    let tree = KdTree::new(target.positions());
+    let mut mat = option.initial;
+    for _ in 0..option.max_iterations {
+        let pairs: Vec<(Point3, usize, f64)> = source
+            .iter()
+            .filter_map(|pt| {
+                let pt = mat.transform_point(*pt);
+                let idx = tree.nearest(pt)?;
+                Some((pt, idx, pt.distance(target.positions()[idx])))
+            })
+            .collect();
+        if pairs.is_empty() {
+            break;
+        }
+        let mut distances: Vec<f64> = pairs.iter().map(|(_, _, dist)| *dist).collect();
+        let mid = distances.len() / 2;
+        distances.select_nth_unstable_by(mid, |a, b| {
+            a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal)
+        });
+        let threshold = f64::max(distances[mid] * option.rejection_factor, option.tolerance);
+
+        // linearized least squares of (rotation vector, translation)
+        let mut ata = [[0.0; 6]; 6];
+        let mut atb = [0.0; 6];
+        let mut add_row = |pt: Point3, target: Point3, dir: Vector3| {
+            let cross = pt.to_vec().cross(dir);
+            let row = [cross[0], cross[1], cross[2], dir[0], dir[1], dir[2]];
+            let b = dir.dot(target - pt);
+            ata.iter_mut().zip(&row).for_each(|(ata_row, ri)| {
+                ata_row.iter_mut().zip(&row).for_each(|(a, rj)| *a += ri * rj)
+            });
+            atb.iter_mut().zip(&row).for_each(|(a, r)| *a += r * b);
+        };
+        for (pt, idx, dist) in pairs {
+            if dist > threshold {
+                continue;
+            }
+            let q = target.positions()[idx];
+            match option.point_to_plane {
+                true => add_row(pt, q, target.normals()[idx]),
+                false => {
+                    add_row(pt, q, Vector3::unit_x());
+                    add_row(pt, q, Vector3::unit_y());
+                    add_row(pt, q, Vector3::unit_z());
+                }
+            }
+        }
+        let x = match solve6(ata, atb) {
+            Some(x) => x,
+            None => break,
+        };
+        let omega = Vector3::new(x[0], x[1], x[2]);
+        let translation = Vector3::new(x[3], x[4], x[5]);
+        let angle = omega.magnitude();
+        let rotation = match angle.so_small() {
+            true => Matrix4::identity(),
+            false => Matrix4::from_axis_angle(omega / angle, Rad(angle)),
+        };
+        mat = Matrix4::from_translation(translation) * rotation * mat;
+        if angle < option.tolerance && translation.magnitude() < option.tolerance {
+            break;
+        }
+    }
+    mat
+}
+
+/// Solves the 6x6 linear system by Gaussian elimination with partial pivoting.
+fn solve6(mut mat: [[f64; 6]; 6], mut vec: [f64; 6]) -> Option<[f64; 6]> {
+    for i in 0..6 {
+        let pivot = (i..6).max_by(|a, b| {
+            mat[*a][i]
+                .abs()
+                .partial_cmp(&mat[*b][i].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+        if mat[pivot][i].so_small() {
+            return None;
+        }
+        mat.swap(i, pivot);
+        vec.swap(i, pivot);
+        for j in i + 1..6 {
+            let coef = mat[j][i] / mat[i][i];
+            for k in i..6 {
+                mat[j][k] -= coef * mat[i][k];
+            }
+            vec[j] -= coef * vec[i];
+        }
+    }
+    let mut res = [0.0; 6];
+    for i in (0..6).rev() {
+        let sum = (i + 1..6).fold(vec[i], |sum, k| sum - mat[i][k] * res[k]);
+        res[i] = sum / mat[i][i];
+    }
+    Some(res)
+}
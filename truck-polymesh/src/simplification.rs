@@ -0,0 +1,383 @@
+use crate::*;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, HashSet};
+
+// the weight of the quadrics of the planes perpendicular to the faces along the boundaries
+const BOUNDARY_WEIGHT: f64 = 1000.0;
+
+impl Default for SimplificationOptions {
+    #[inline(always)]
+    fn default() -> SimplificationOptions {
+        SimplificationOptions {
+            preserve_boundary: true,
+            max_normal_deviation: Rad(std::f64::consts::PI / 4.0),
+        }
+    }
+}
+
+impl PolygonMesh {
+    /// Returns the mesh simplified by the edge collapses with the quadric error metric, with
+    /// the default options.
+    /// cf. [`PolygonMesh::simplify_with_options`](#method.simplify_with_options)
+    #[inline(always)]
+    pub fn simplify(&self, target_ratio: f64) -> PolygonMesh {
+        self.simplify_with_options(target_ratio, SimplificationOptions::default())
+    }
+
+    /// Returns the mesh simplified by the edge collapses with the quadric error metric.
+    ///
+    /// The faces are triangulated, and the edges are collapsed in the increasing order of the
+    /// squared distances from the new positions to the planes of the original triangles, until
+    /// the number of the triangles is at most `target_ratio` times the one of `self`.
+    /// The collapses which break the manifoldness, flip the triangles or rotate the normals of
+    /// the triangles more than `options.max_normal_deviation` are rejected, so the target may
+    /// not be reached.
+    ///
+    /// The faces keep the indices of the uv coordinates and the normals of the original corners,
+    /// as the levels of detail by [`PolygonMesh::generate_lods`]. The unused positions are
+    /// removed, and the skinning data of the remaining positions are kept.
+    /// # Examples
+    /// ```
+    /// use truck_polymesh::*;
+    /// // the 20 x 20 grid on the plane z = 0
+    /// let positions: Vec<Point3> = (0..441)
+    ///     .map(|i| Point3::new((i % 21) as f64 / 20.0, (i / 21) as f64 / 20.0, 0.0))
+    ///     .collect();
+    /// let mut faces = Faces::default();
+    /// for i in 0..20 {
+    ///     for j in 0..20 {
+    ///         let idx = |i: usize, j: usize| [i * 21 + j, 0, 0];
+    ///         faces.push(&[idx(i, j), idx(i, j + 1), idx(i + 1, j + 1), idx(i + 1, j)]);
+    ///     }
+    /// }
+    /// let uv_coords = vec![Vector2::new(0.0, 0.0)];
+    /// let normals = vec![Vector3::unit_z()];
+    /// let mesh = PolygonMesh::new(positions, uv_coords, normals, faces);
+    /// assert_eq!(mesh.triangle_count(), 800);
+    ///
+    /// let simplified = mesh.simplify(0.25);
+    /// assert!(simplified.triangle_count() <= 200);
+    /// // the plane is kept, and the boundary is fixed.
+    /// assert!(simplified.positions().iter().all(|p| p.z.so_small()));
+    /// let area: f64 = simplified
+    ///     .tri_faces()
+    ///     .iter()
+    ///     .map(|tri| {
+    ///         let p = |i: usize| simplified.positions()[tri[i].pos];
+    ///         (p(1) - p(0)).cross(p(2) - p(0)).z / 2.0
+    ///     })
+    ///     .sum();
+    /// assert!(area.near(&1.0));
+    /// ```
+    ///
+    /// [`PolygonMesh::generate_lods`]: ./struct.PolygonMesh.html#method.generate_lods
+    pub fn simplify_with_options(
+        &self,
+        target_ratio: f64,
+        options: SimplificationOptions,
+    ) -> PolygonMesh {
+        let mut simplifier = Simplifier::new(self, options);
+        let target = (simplifier.count as f64 * f64::max(target_ratio, 0.0)).floor() as usize;
+        simplifier.collapse_until(target);
+        simplifier.into_mesh(self)
+    }
+}
+
+// the candidate of the collapse of the edge between `keep` and `remove`
+#[derive(Clone, Copy, Debug)]
+struct Collapse {
+    cost: f64,
+    keep: usize,
+    remove: usize,
+    position: Point3,
+    // the versions of the positions when the candidate is created
+    stamps: (usize, usize),
+}
+
+impl PartialEq for Collapse {
+    #[inline(always)]
+    fn eq(&self, other: &Collapse) -> bool { self.cmp(other) == Ordering::Equal }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    #[inline(always)]
+    fn partial_cmp(&self, other: &Collapse) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Collapse {
+    // the reversed order, in order to pop the minimum cost from `BinaryHeap`
+    #[inline(always)]
+    fn cmp(&self, other: &Collapse) -> Ordering {
+        other
+            .cost
+            .partial_cmp(&self.cost)
+            .unwrap_or(Ordering::Equal)
+    }
+}
+
+#[derive(Debug)]
+struct Simplifier {
+    positions: Vec<Point3>,
+    quadrics: Vec<Matrix4>,
+    boundary: Vec<bool>,
+    versions: Vec<usize>,
+    triangles: Vec<[Vertex; 3]>,
+    alive: Vec<bool>,
+    // the indices of the triangles around each position, including the removed ones
+    incident: Vec<Vec<usize>>,
+    count: usize,
+    heap: BinaryHeap<Collapse>,
+    options: SimplificationOptions,
+}
+
+impl Simplifier {
+    fn new(mesh: &PolygonMesh, options: SimplificationOptions) -> Simplifier {
+        let positions = mesh.positions.clone();
+        let triangles: Vec<[Vertex; 3]> = mesh
+            .face_iter()
+            .flat_map(|face| (2..face.len()).map(move |i| [face[0], face[i - 1], face[i]]))
+            .filter(|tri| {
+                tri[0].pos != tri[1].pos && tri[1].pos != tri[2].pos && tri[2].pos != tri[0].pos
+            })
+            .collect();
+        let mut incident = vec![Vec::new(); positions.len()];
+        let mut edges = HashMap::<(usize, usize), Vec<usize>>::new();
+        for (i, tri) in triangles.iter().enumerate() {
+            for k in 0..3 {
+                let (a, b) = (tri[k].pos, tri[(k + 1) % 3].pos);
+                incident[a].push(i);
+                edges.entry((usize::min(a, b), usize::max(a, b))).or_default().push(i);
+            }
+        }
+        let mut quadrics = vec![Matrix4::zero(); positions.len()];
+        let mut boundary = vec![false; positions.len()];
+        for tri in &triangles {
+            let p = [0, 1, 2].map(|k| positions[tri[k].pos]);
+            let normal = (p[1] - p[0]).cross(p[2] - p[0]);
+            let area = normal.magnitude() / 2.0;
+            if area.so_small2() {
+                continue;
+            }
+            let quadric = plane_quadric(p[0], normal.normalize()) * area;
+            tri.iter().for_each(|v| quadrics[v.pos] += quadric);
+        }
+        for ((a, b), tris) in &edges {
+            if tris.len() != 1 {
+                continue;
+            }
+            boundary[*a] = true;
+            boundary[*b] = true;
+            let tri = &triangles[tris[0]];
+            let p = [0, 1, 2].map(|k| positions[tri[k].pos]);
+            let normal = (p[1] - p[0]).cross(p[2] - p[0]);
+            let edge = positions[*b] - positions[*a];
+            let perpendicular = edge.cross(normal);
+            if perpendicular.magnitude2().so_small2() {
+                continue;
+            }
+            let quadric = plane_quadric(positions[*a], perpendicular.normalize());
+            let quadric = quadric * (BOUNDARY_WEIGHT * edge.magnitude2());
+            quadrics[*a] += quadric;
+            quadrics[*b] += quadric;
+        }
+        let mut simplifier = Simplifier {
+            versions: vec![0; positions.len()],
+            alive: vec![true; triangles.len()],
+            count: triangles.len(),
+            heap: BinaryHeap::new(),
+            positions,
+            quadrics,
+            boundary,
+            triangles,
+            incident,
+            options,
+        };
+        let mut pairs: Vec<(usize, usize)> = edges.into_keys().collect();
+        pairs.sort();
+        pairs.into_iter().for_each(|(a, b)| simplifier.push_candidate(a, b));
+        simplifier
+    }
+
+    // pushes the collapse of the edge between `a` and `b` to the heap
+    fn push_candidate(&mut self, a: usize, b: usize) {
+        let (keep, remove, position) = match (self.boundary[a], self.boundary[b]) {
+            (true, true) if self.options.preserve_boundary => return,
+            (true, false) if self.options.preserve_boundary => (a, b, self.positions[a]),
+            (false, true) if self.options.preserve_boundary => (b, a, self.positions[b]),
+            _ => (a, b, self.optimal_position(a, b)),
+        };
+        let quadric = self.quadrics[a] + self.quadrics[b];
+        let v = position.to_homogeneous();
+        self.heap.push(Collapse {
+            cost: v.dot(quadric * v),
+            keep,
+            remove,
+            position,
+            stamps: (self.versions[keep], self.versions[remove]),
+        });
+    }
+
+    // the position minimizing the quadric error, or the best one of the ends and the middle
+    fn optimal_position(&self, a: usize, b: usize) -> Point3 {
+        let quadric = self.quadrics[a] + self.quadrics[b];
+        let matrix = Matrix3::from_cols(
+            quadric[0].truncate(),
+            quadric[1].truncate(),
+            quadric[2].truncate(),
+        );
+        let scale = matrix.x.magnitude() + matrix.y.magnitude() + matrix.z.magnitude();
+        if matrix.determinant().abs() > TOLERANCE * scale * scale * scale {
+            if let Some(inv) = matrix.invert() {
+                return Point3::from_vec(-(inv * quadric[3].truncate()));
+            }
+        }
+        let (p, q) = (self.positions[a], self.positions[b]);
+        let cost = |pt: &Point3| {
+            let v = pt.to_homogeneous();
+            v.dot(quadric * v)
+        };
+        vec![p, q, p.midpoint(q)]
+            .into_iter()
+            .min_by(|x, y| cost(x).partial_cmp(&cost(y)).unwrap_or(Ordering::Equal))
+            .unwrap()
+    }
+
+    fn collapse_until(&mut self, target: usize) {
+        while self.count > target {
+            let collapse = match self.heap.pop() {
+                Some(collapse) => collapse,
+                None => return,
+            };
+            let (keep, remove) = (collapse.keep, collapse.remove);
+            let stamps = (self.versions[keep], self.versions[remove]);
+            if stamps == collapse.stamps && self.is_valid(&collapse) {
+                self.collapse(&collapse);
+            }
+        }
+    }
+
+    #[inline(always)]
+    fn alive_triangles(&self, idx: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut set = HashSet::new();
+        self.incident[idx]
+            .iter()
+            .copied()
+            .filter(move |i| self.alive[*i] && set.insert(*i))
+    }
+
+    #[inline(always)]
+    fn contains(&self, tri: usize, idx: usize) -> bool {
+        self.triangles[tri].iter().any(|v| v.pos == idx)
+    }
+
+    fn neighbors(&self, idx: usize) -> HashSet<usize> {
+        self.alive_triangles(idx)
+            .flat_map(|i| self.triangles[i].iter().map(|v| v.pos))
+            .filter(|pos| *pos != idx)
+            .collect()
+    }
+
+    fn is_valid(&self, collapse: &Collapse) -> bool {
+        let (keep, remove) = (collapse.keep, collapse.remove);
+        // the link condition keeping the manifoldness
+        let shared = self
+            .alive_triangles(remove)
+            .filter(|i| self.contains(*i, keep))
+            .count();
+        let common = self.neighbors(keep).intersection(&self.neighbors(remove)).count();
+        if shared == 0 || common != shared {
+            return false;
+        }
+        let cos = self.options.max_normal_deviation.cos();
+        [keep, remove].iter().all(|idx| {
+            self.alive_triangles(*idx)
+                .filter(|i| !(self.contains(*i, keep) && self.contains(*i, remove)))
+                .all(|i| {
+                    let tri = &self.triangles[i];
+                    let p = [0, 1, 2].map(|k| self.positions[tri[k].pos]);
+                    let q = [0, 1, 2].map(|k| match tri[k].pos == *idx {
+                        true => collapse.position,
+                        false => p[k],
+                    });
+                    let old = (p[1] - p[0]).cross(p[2] - p[0]);
+                    let new = (q[1] - q[0]).cross(q[2] - q[0]);
+                    match old.magnitude2().so_small2() || new.magnitude2().so_small2() {
+                        true => false,
+                        false => old.normalize().dot(new.normalize()) >= cos,
+                    }
+                })
+        })
+    }
+
+    fn collapse(&mut self, collapse: &Collapse) {
+        let (keep, remove) = (collapse.keep, collapse.remove);
+        let tris: Vec<usize> = self.alive_triangles(remove).collect();
+        for i in tris {
+            match self.contains(i, keep) {
+                true => {
+                    self.alive[i] = false;
+                    self.count -= 1;
+                }
+                false => {
+                    self.triangles[i]
+                        .iter_mut()
+                        .filter(|v| v.pos == remove)
+                        .for_each(|v| v.pos = keep);
+                    self.incident[keep].push(i);
+                }
+            }
+        }
+        self.incident[remove].clear();
+        self.positions[keep] = collapse.position;
+        let quadric = self.quadrics[remove];
+        self.quadrics[keep] += quadric;
+        self.boundary[keep] |= self.boundary[remove];
+        self.versions[keep] += 1;
+        self.versions[remove] += 1;
+        let mut neighbors: Vec<usize> = self.neighbors(keep).into_iter().collect();
+        neighbors.sort();
+        neighbors.into_iter().for_each(|idx| self.push_candidate(keep, idx));
+    }
+
+    fn into_mesh(self, mesh: &PolygonMesh) -> PolygonMesh {
+        let mut map = vec![None; self.positions.len()];
+        let mut used = Vec::new();
+        let tri_faces: Vec<[Vertex; 3]> = self
+            .triangles
+            .iter()
+            .zip(&self.alive)
+            .filter(|(_, alive)| **alive)
+            .map(|(tri, _)| {
+                let mut tri = *tri;
+                tri.iter_mut().for_each(|v| {
+                    v.pos = *map[v.pos].get_or_insert_with(|| {
+                        used.push(v.pos);
+                        used.len() - 1
+                    })
+                });
+                tri
+            })
+            .collect();
+        let skin = mesh.skin.as_ref().map(|skin| Skin {
+            joints: used.iter().map(|i| skin.joints[*i]).collect(),
+            weights: used.iter().map(|i| skin.weights[*i]).collect(),
+            inverse_bind_matrices: skin.inverse_bind_matrices.clone(),
+        });
+        PolygonMesh {
+            positions: used.iter().map(|i| self.positions[*i]).collect(),
+            uv_coords: mesh.uv_coords.clone(),
+            normals: mesh.normals.clone(),
+            faces: Faces::from_iter(&tri_faces),
+            skin,
+        }
+    }
+}
+
+// the quadric of the squared distance from the plane through `origin` with the unit `normal`
+#[inline(always)]
+fn plane_quadric(origin: Point3, normal: Vector3) -> Matrix4 {
+    let plane = normal.extend(-normal.dot(origin.to_vec()));
+    Matrix4::from_cols(plane * plane[0], plane * plane[1], plane * plane[2], plane * plane[3])
+}
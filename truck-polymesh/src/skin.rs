@@ -0,0 +1,110 @@
+use crate::errors::Error;
+use crate::*;
+
+impl Skin {
+    /// Returns the matrix deforming the `idx`th position at the pose of `joint_matrices`.
+    /// # Panics
+    /// Panics if `idx` is out of range or a joint of the position is not in `joint_matrices`.
+    #[inline(always)]
+    pub fn skinning_matrix(&self, idx: usize, joint_matrices: &[Matrix4]) -> Matrix4 {
+        self.joints[idx]
+            .iter()
+            .zip(&self.weights[idx])
+            .filter(|(_, weight)| **weight != 0.0)
+            .fold(Matrix4::zero(), |sum, (joint, weight)| {
+                sum + joint_matrices[*joint] * self.inverse_bind_matrices[*joint] * *weight
+            })
+    }
+}
+
+impl PolygonMesh {
+    /// Returns the skinning data.
+    #[inline(always)]
+    pub fn skin(&self) -> Option<&Skin> { self.skin.as_ref() }
+
+    /// Sets the skinning data.
+    /// # Errors
+    /// Returns [`Error::InvalidFieldLength`] if the number of the joints or the weights is not
+    /// the one of the positions, and [`Error::OutOfRange`] if there is an index of a joint out
+    /// of range of the inverse bind matrices.
+    ///
+    /// [`Error::InvalidFieldLength`]: ./errors/enum.Error.html#variant.InvalidFieldLength
+    /// [`Error::OutOfRange`]: ./errors/enum.Error.html#variant.OutOfRange
+    pub fn set_skin(&mut self, skin: Skin) -> Result<()> {
+        let len = self.positions.len();
+        if skin.joints.len() != len {
+            return Err(Error::InvalidFieldLength(
+                "joints".to_string(),
+                len,
+                skin.joints.len(),
+            ));
+        }
+        if skin.weights.len() != len {
+            return Err(Error::InvalidFieldLength(
+                "weights".to_string(),
+                len,
+                skin.weights.len(),
+            ));
+        }
+        let n_joints = skin.inverse_bind_matrices.len();
+        if let Some(joint) = skin
+            .joints
+            .iter()
+            .flatten()
+            .find(|joint| **joint >= n_joints)
+        {
+            return Err(Error::OutOfRange("joints", n_joints, *joint));
+        }
+        self.skin = Some(skin);
+        Ok(())
+    }
+
+    /// Removes the skinning data and returns it.
+    #[inline(always)]
+    pub fn take_skin(&mut self) -> Option<Skin> { self.skin.take() }
+
+    /// Returns the positions deformed at the pose of `joint_matrices`, e.g. for picking
+    /// the animated meshes on CPU. If the mesh has no skin, returns the positions as they are.
+    /// # Panics
+    /// Panics if a joint of the skin is not in `joint_matrices`.
+    /// # Examples
+    /// ```
+    /// use truck_polymesh::*;
+    /// let mut mesh = PolygonMesh::new(
+    ///     vec![Point3::new(0.0, 0.0, 0.0), Point3::new(0.0, 1.0, 0.0), Point3::new(0.0, 2.0, 0.0)],
+    ///     Vec::new(),
+    ///     Vec::new(),
+    ///     Faces::from_iter(&[&[0, 1, 2]]),
+    /// );
+    /// // the upper arm at the origin and the forearm at the elbow (0, 1, 0)
+    /// mesh.set_skin(Skin {
+    ///     joints: vec![[0, 0, 0, 0], [0, 1, 0, 0], [1, 0, 0, 0]],
+    ///     weights: vec![[1.0, 0.0, 0.0, 0.0], [0.5, 0.5, 0.0, 0.0], [1.0, 0.0, 0.0, 0.0]],
+    ///     inverse_bind_matrices: vec![
+    ///         Matrix4::identity(),
+    ///         Matrix4::from_translation(Vector3::new(0.0, -1.0, 0.0)),
+    ///     ],
+    /// })
+    /// .unwrap();
+    /// // the forearm moves by (1, 0, 0)
+    /// let joint_matrices = [
+    ///     Matrix4::identity(),
+    ///     Matrix4::from_translation(Vector3::new(1.0, 1.0, 0.0)),
+    /// ];
+    /// let positions = mesh.skinned_positions(&joint_matrices);
+    /// assert!(positions[0].near(&Point3::new(0.0, 0.0, 0.0)));
+    /// assert!(positions[1].near(&Point3::new(0.5, 1.0, 0.0)));
+    /// assert!(positions[2].near(&Point3::new(1.0, 2.0, 0.0)));
+    /// ```
+    pub fn skinned_positions(&self, joint_matrices: &[Matrix4]) -> Vec<Point3> {
+        match &self.skin {
+            Some(skin) => self
+                .positions
+                .iter()
+                .enumerate()
+                .map(|(i, p)| skin.skinning_matrix(i, joint_matrices).transform_point(*p))
+                .collect(),
+            None => self.positions.clone(),
+        }
+    }
+}
@@ -21,6 +21,21 @@ pub struct STLFace {
 }
 
 impl STLFace {
+    /// Creates the facet whose normal is derived from the vertices by the right-hand rule.
+    /// The normal of the degenerate facet is the zero vector.
+    /// # Examples
+    /// ```
+    /// use truck_polymesh::stl::STLFace;
+    /// let face = STLFace::from_vertices([[0.0, 0.0, 0.0], [0.0, 2.0, 0.0], [0.0, 0.0, 2.0]]);
+    /// assert_eq!(face.normal, [1.0, 0.0, 0.0]);
+    /// let face = STLFace::from_vertices([[0.0, 0.0, 0.0], [1.0, 1.0, 1.0], [2.0, 2.0, 2.0]]);
+    /// assert_eq!(face.normal, [0.0, 0.0, 0.0]);
+    /// ```
+    #[inline(always)]
+    pub fn from_vertices(vertices: [[f32; 3]; 3]) -> STLFace {
+        let [a, b, c] = vertices.map(|v| Point3::new(v[0] as f64, v[1] as f64, v[2] as f64));
+        pos_to_face(a, b, c)
+    }
     #[inline(always)]
     fn is_empty(&self) -> bool { self == &STLFace::default() }
 }
@@ -56,19 +71,42 @@ impl Default for STLType {
     fn default() -> STLType { STLType::Automatic }
 }
 
+/// The options of [`read_with_options`].
+///
+/// [`read_with_options`]: ./fn.read_with_options.html
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+pub struct ReadOptions {
+    /// the format of the STL data. The default is `STLType::Automatic`.
+    pub stl_type: STLType,
+    /// If `true`, the vertices of the facets at the same positions are merged into the one
+    /// vertex, and the facets are connected. Otherwise, each facet has its own three vertices
+    /// and the positions are kept as they are. The default is `true`.
+    pub merge_vertices: bool,
+}
+
+impl Default for ReadOptions {
+    #[inline(always)]
+    fn default() -> ReadOptions {
+        ReadOptions {
+            stl_type: STLType::Automatic,
+            merge_vertices: true,
+        }
+    }
+}
+
 impl<R: Read> STLReader<R> {
     #[inline(always)]
     fn text_reader(reader: R) -> STLReader<R> { STLReader::ASCII(BufReader::new(reader).lines()) }
     fn binary_reader(mut reader: R, header_judge: bool) -> Result<STLReader<R>> {
         let mut header = [0; 5];
-        reader.read(&mut header)?;
+        reader.read_exact(&mut header)?;
         if header_judge && &header == b"solid" {
             return Ok(Self::text_reader(reader));
         }
         let mut header = [0; 75];
-        reader.read(&mut header)?;
+        reader.read_exact(&mut header)?;
         let mut length_bytes = [0; 4];
-        reader.read(&mut length_bytes)?;
+        reader.read_exact(&mut length_bytes)?;
         let length = u32::from_le_bytes(length_bytes) as usize;
         Ok(STLReader::Binary(reader, length))
     }
@@ -156,19 +194,48 @@ fn ascii_one_read<R: BufRead>(lines: &mut Lines<R>) -> Result<Option<STLFace>> {
 
 fn binary_one_read<R: Read>(reader: &mut R) -> Result<Option<STLFace>> {
     let mut chunk = [0; CHUNKSIZE];
-    let size = reader.read(&mut chunk)?;
-    if size == CHUNKSIZE {
-        let mut buf = [0; FACESIZE];
-        buf.copy_from_slice(&chunk[..FACESIZE]);
-        Ok(Some(bytemuck::cast(buf)))
-    } else {
-        Err(syntax_error().into())
+    match reader.read_exact(&mut chunk) {
+        Ok(()) => {
+            let mut buf = [0; FACESIZE];
+            buf.copy_from_slice(&chunk[..FACESIZE]);
+            Ok(Some(bytemuck::cast(buf)))
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::UnexpectedEof => {
+            Err(syntax_error().into())
+        }
+        Err(error) => Err(error.into()),
     }
 }
 
 /// write STL file in `stl_type` format.
 ///
-/// If `stl_type == STLType::Automatic`, write the binary format.
+/// If `stl_type == STLType::Automatic`, write the binary format. The facets of `PolygonMesh`
+/// are triangulated, and their normals are derived from the positions by the right-hand rule,
+/// so the normals are consistent with the orientations of the faces even if the mesh has no
+/// normals.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::stl::STLType;
+/// let positions = vec![
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(1.0, 1.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+/// ];
+/// let faces = Faces::from_iter(&[[0, 1, 2, 3]]);
+/// let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+///
+/// let mut data = Vec::new();
+/// stl::write(&mesh, &mut data, STLType::Binary).unwrap();
+/// // the header, the number of the facets and the two triangles
+/// assert_eq!(data.len(), 80 + 4 + 2 * 50);
+///
+/// let faces = stl::STLReader::new(&data[..], STLType::Binary).unwrap();
+/// for face in faces {
+///     assert_eq!(face.unwrap().normal, [0.0, 0.0, 1.0]);
+/// }
+/// ```
 #[inline(always)]
 pub fn write<I: IntoSTLIterator, W: Write>(
     iter: I,
@@ -184,23 +251,23 @@ pub fn write<I: IntoSTLIterator, W: Write>(
 /// Writes ASCII STL data
 fn write_ascii<I: IntoSTLIterator, W: Write>(iter: I, writer: &mut W) -> Result<()> {
     let mut iter = iter.into_iter();
-    writer.write(b"solid\n")?;
+    writer.write_all(b"solid\n")?;
     iter.try_for_each::<_, Result<()>>(|face| {
         writer.write_fmt(format_args!(
             "  facet normal {:e} {:e} {:e}\n",
             face.normal[0], face.normal[1], face.normal[2]
         ))?;
-        writer.write(b"    outer loop\n")?;
+        writer.write_all(b"    outer loop\n")?;
         face.vertices.iter().try_for_each(|pt| {
             writer.write_fmt(format_args!(
                 "      vertex {:e} {:e} {:e}\n",
                 pt[0], pt[1], pt[2]
             ))
         })?;
-        writer.write(b"    endloop\n  endfacet\n")?;
+        writer.write_all(b"    endloop\n  endfacet\n")?;
         Ok(())
     })?;
-    writer.write(b"endsolid\n")?;
+    writer.write_all(b"endsolid\n")?;
     Ok(())
 }
 
@@ -209,11 +276,11 @@ fn write_ascii<I: IntoSTLIterator, W: Write>(iter: I, writer: &mut W) -> Result<
 fn write_binary<I: IntoSTLIterator, W: Write>(iter: I, writer: &mut W) -> Result<()> {
     let mut iter = iter.into_iter();
     let len = iter.len() as u32;
-    writer.write(&[0u8; 80])?;
-    writer.write(&len.to_le_bytes())?;
+    writer.write_all(&[0u8; 80])?;
+    writer.write_all(&len.to_le_bytes())?;
     iter.try_for_each(|face| {
-        writer.write(bytemuck::cast_slice(&[face]))?;
-        writer.write(&[0u8, 0u8])?;
+        writer.write_all(bytemuck::cast_slice(&[face]))?;
+        writer.write_all(&[0u8, 0u8])?;
         Ok(())
     })
 }
@@ -241,7 +308,11 @@ pub struct PolygonMeshSTLFaceIterator<'a> {
 
 #[inline(always)]
 fn pos_to_face(a: Point3, b: Point3, c: Point3) -> STLFace {
-    let normal = (b - a).cross(c - a).normalize().cast().unwrap().into();
+    let normal = (b - a).cross(c - a);
+    let normal = match normal.so_small() {
+        true => [0.0; 3],
+        false => normal.normalize().cast().unwrap().into(),
+    };
     let vertices = [
         a.cast().unwrap().into(),
         b.cast().unwrap().into(),
@@ -288,7 +359,7 @@ impl<'a> ExactSizeIterator for PolygonMeshSTLFaceIterator<'a> {}
 impl<'a> IntoSTLIterator for &'a PolygonMesh {
     type IntoIter = PolygonMeshSTLFaceIterator<'a>;
     fn into_iter(self) -> Self::IntoIter {
-        let len = self.face_iter().fold(0, |len, face| len + face.len());
+        let len = self.face_iter().fold(0, |len, face| len + face.len() - 2);
         Self::IntoIter {
             positions: self.positions(),
             tri_faces: self.tri_faces().iter(),
@@ -376,7 +447,68 @@ impl std::iter::FromIterator<STLFace> for PolygonMesh {
 }
 
 /// Read STL file and parse to `PolygonMesh`.
+///
+/// The vertices at the same positions are merged. Use [`read_with_options`] to keep the
+/// facets separated.
+///
+/// [`read_with_options`]: ./fn.read_with_options.html
 #[inline(always)]
 pub fn read<R: Read>(reader: R, stl_type: STLType) -> Result<PolygonMesh> {
     STLReader::new(reader, stl_type)?.collect()
 }
+
+/// Read STL file and parse to `PolygonMesh` with `options`.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::stl::*;
+/// let faces = vec![
+///     STLFace::from_vertices([[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]]),
+///     STLFace::from_vertices([[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]]),
+/// ];
+/// let mut data = Vec::new();
+/// stl::write(faces, &mut data, STLType::ASCII).unwrap();
+///
+/// let merged = stl::read_with_options(&data[..], &ReadOptions::default()).unwrap();
+/// assert_eq!(merged.positions().len(), 4);
+///
+/// let options = ReadOptions {
+///     merge_vertices: false,
+///     ..Default::default()
+/// };
+/// let separated = stl::read_with_options(&data[..], &options).unwrap();
+/// assert_eq!(separated.positions().len(), 6);
+/// assert_eq!(separated.normals().len(), 2);
+/// assert_eq!(separated.tri_faces().len(), 2);
+/// ```
+pub fn read_with_options<R: Read>(reader: R, options: &ReadOptions) -> Result<PolygonMesh> {
+    let reader = STLReader::new(reader, options.stl_type)?;
+    if options.merge_vertices {
+        return reader.collect();
+    }
+    let mut positions = Vec::new();
+    let mut normals = Vec::new();
+    let mut faces = Vec::new();
+    for face in reader {
+        let face = face?;
+        let n = normals.len();
+        normals.push(Vector3::new(
+            face.normal[0] as f64,
+            face.normal[1] as f64,
+            face.normal[2] as f64,
+        ));
+        let p = positions.len();
+        positions.extend(
+            face.vertices
+                .iter()
+                .map(|v| Point3::new(v[0] as f64, v[1] as f64, v[2] as f64)),
+        );
+        faces.push([
+            (p, None, Some(n)).into(),
+            (p + 1, None, Some(n)).into(),
+            (p + 2, None, Some(n)).into(),
+        ]);
+    }
+    let faces = Faces::from_tri_and_quad_faces(faces, Vec::new());
+    Ok(PolygonMesh::debug_new(positions, Vec::new(), normals, faces))
+}
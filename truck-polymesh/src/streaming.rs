@@ -0,0 +1,371 @@
+use crate::errors::Error;
+use crate::stl::{STLReader, STLType};
+use crate::*;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use std::rc::Rc;
+use std::str::SplitWhitespace;
+
+/// The options of the streaming loaders.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct StreamingOption {
+    /// If `Some`, the loading fails by `Error::MemoryBudgetExceeded` as soon as the estimated
+    /// size of the mesh under construction exceeds the bytes. Default is `None`.
+    pub memory_budget: Option<usize>,
+    /// the interval of the reports of the progress in bytes. Default is `1 << 20`, i.e. 1 MiB.
+    pub progress_interval: u64,
+    /// If `true`, the uv coordinates and the normals are not stored. Default is `false`.
+    pub positions_only: bool,
+    /// the tolerance welding the positions of the STL facets. Default is `TOLERANCE`.
+    pub weld_tolerance: f64,
+}
+
+impl Default for StreamingOption {
+    #[inline(always)]
+    fn default() -> StreamingOption {
+        StreamingOption {
+            memory_budget: None,
+            progress_interval: 1 << 20,
+            positions_only: false,
+            weld_tolerance: TOLERANCE,
+        }
+    }
+}
+
+/// The progress of the streaming loaders, reported to the callback.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Progress {
+    /// the number of the bytes read so far
+    pub bytes_read: u64,
+    /// the size of the input if it is known, e.g. the size of the file
+    pub total_bytes: Option<u64>,
+    /// the number of the faces loaded so far
+    pub faces: usize,
+    /// the estimated size of the mesh under construction in bytes
+    pub memory: usize,
+}
+
+impl Progress {
+    /// Returns the ratio of the bytes read to the total bytes, if the total is known.
+    #[inline(always)]
+    pub fn ratio(&self) -> Option<f64> {
+        self.total_bytes
+            .filter(|total| *total > 0)
+            .map(|total| self.bytes_read as f64 / total as f64)
+    }
+}
+
+// the mesh under construction with the accounting of the memory and the progress
+struct MeshBuilder<F> {
+    positions: Vec<Point3>,
+    uv_coords: Vec<Vector2>,
+    normals: Vec<Vector3>,
+    faces: Faces,
+    corners: usize,
+    // the entries of the hash maps for welding
+    entries: usize,
+    option: StreamingOption,
+    progress: Progress,
+    reported: u64,
+    callback: F,
+}
+
+impl<F: FnMut(Progress)> MeshBuilder<F> {
+    fn new(option: &StreamingOption, total_bytes: Option<u64>, callback: F) -> Self {
+        MeshBuilder {
+            positions: Vec::new(),
+            uv_coords: Vec::new(),
+            normals: Vec::new(),
+            faces: Faces::default(),
+            corners: 0,
+            entries: 0,
+            option: *option,
+            progress: Progress {
+                bytes_read: 0,
+                total_bytes,
+                faces: 0,
+                memory: 0,
+            },
+            reported: 0,
+            callback,
+        }
+    }
+
+    #[inline(always)]
+    fn memory(&self) -> usize {
+        use std::mem::size_of;
+        self.positions.len() * size_of::<Point3>()
+            + self.uv_coords.len() * size_of::<Vector2>()
+            + self.normals.len() * size_of::<Vector3>()
+            + self.corners * size_of::<Vertex>()
+            + self.entries * (size_of::<[i64; 3]>() + size_of::<usize>())
+    }
+
+    // Updates the progress, and checks the memory budget.
+    fn update(&mut self, bytes_read: u64) -> Result<()> {
+        self.progress.bytes_read = bytes_read;
+        self.progress.memory = self.memory();
+        if let Some(budget) = self.option.memory_budget {
+            if self.progress.memory > budget {
+                return Err(Error::MemoryBudgetExceeded(budget));
+            }
+        }
+        if bytes_read >= self.reported + self.option.progress_interval {
+            self.reported = bytes_read;
+            (self.callback)(self.progress);
+        }
+        Ok(())
+    }
+
+    fn push_face(&mut self, face: &[Vertex]) {
+        self.faces.push(face);
+        self.corners += face.len();
+        self.progress.faces += 1;
+    }
+
+    fn finish(mut self, bytes_read: u64) -> Result<PolygonMesh> {
+        self.update(bytes_read)?;
+        (self.callback)(self.progress);
+        PolygonMesh::try_new(self.positions, self.uv_coords, self.normals, self.faces)
+    }
+}
+
+/// Reads the wavefront obj data line by line.
+///
+/// The faces are stored directly in the mesh without the intermediate strings, and the
+/// progress is reported to `callback` at every `option.progress_interval` bytes and at the end.
+/// # Errors
+/// Returns `Error::MemoryBudgetExceeded` if the estimated size of the mesh exceeds
+/// `option.memory_budget`, besides the errors of I/O and parsing.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::streaming::*;
+/// let obj = b"v 0 0 0\nv 1 0 0\nv 0 1 0\nv 1 1 0\nvn 0 0 1\nf 1//1 2//1 3//1\nf 2//1 4//1 3//1\n";
+/// let mut reports = Vec::new();
+/// let option = StreamingOption {
+///     progress_interval: 16,
+///     ..Default::default()
+/// };
+/// let mesh = streaming::read_obj(&obj[..], None, &option, |p| reports.push(p)).unwrap();
+/// assert_eq!(mesh.positions().len(), 4);
+/// assert_eq!(mesh.tri_faces().len(), 2);
+/// assert!(reports.len() > 1);
+/// assert_eq!(reports.last().unwrap().bytes_read, obj.len() as u64);
+/// assert_eq!(reports.last().unwrap().faces, 2);
+///
+/// // the memory budget
+/// let option = StreamingOption {
+///     memory_budget: Some(64),
+///     ..Default::default()
+/// };
+/// assert!(streaming::read_obj(&obj[..], None, &option, |_| {}).is_err());
+/// ```
+pub fn read_obj<R: Read, F: FnMut(Progress)>(
+    reader: R,
+    total_bytes: Option<u64>,
+    option: &StreamingOption,
+    callback: F,
+) -> Result<PolygonMesh> {
+    let mut builder = MeshBuilder::new(option, total_bytes, callback);
+    let mut reader = BufReader::new(reader);
+    let mut line = String::new();
+    let mut bytes_read = 0;
+    let mut face = Vec::new();
+    loop {
+        line.clear();
+        let size = reader.read_line(&mut line)?;
+        if size == 0 {
+            break;
+        }
+        bytes_read += size as u64;
+        let mut args = line.split_whitespace();
+        match args.next() {
+            Some("v") => {
+                let x = next_f64(&mut args)?;
+                let y = next_f64(&mut args)?;
+                let z = next_f64(&mut args)?;
+                builder.positions.push(Point3::new(x, y, z));
+            }
+            Some("vt") if !option.positions_only => {
+                let u = next_f64(&mut args)?;
+                let v = next_f64(&mut args)?;
+                builder.uv_coords.push(Vector2::new(u, v));
+            }
+            Some("vn") if !option.positions_only => {
+                let x = next_f64(&mut args)?;
+                let y = next_f64(&mut args)?;
+                let z = next_f64(&mut args)?;
+                builder.normals.push(Vector3::new(x, y, z));
+            }
+            Some("f") => {
+                face.clear();
+                for vert_str in args {
+                    if vert_str.starts_with('#') {
+                        break;
+                    }
+                    let mut iter = vert_str
+                        .split('/')
+                        .map(|val| val.parse::<usize>().ok().map(|i| i - 1));
+                    let pos = iter.next().flatten();
+                    let uv = iter.next().flatten().filter(|_| !option.positions_only);
+                    let nor = iter.next().flatten().filter(|_| !option.positions_only);
+                    if let Some(pos) = pos {
+                        face.push(Vertex { pos, uv, nor });
+                    }
+                }
+                builder.push_face(&face);
+            }
+            _ => {}
+        }
+        builder.update(bytes_read)?;
+    }
+    builder.finish(bytes_read)
+}
+
+#[inline(always)]
+fn next_f64(args: &mut SplitWhitespace) -> Result<f64> {
+    match args.next() {
+        Some(arg) => Ok(arg.parse::<f64>()?),
+        None => Err(Error::NotEnoughAttrs),
+    }
+}
+
+// the reader counting the bytes read
+struct CountingReader<R> {
+    reader: R,
+    count: Rc<Cell<u64>>,
+}
+
+impl<R: Read> Read for CountingReader<R> {
+    #[inline(always)]
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let size = self.reader.read(buf)?;
+        self.count.set(self.count.get() + size as u64);
+        Ok(size)
+    }
+}
+
+/// Reads the STL data facet by facet, and welds the positions within `option.weld_tolerance`.
+///
+/// Only the welded positions and the facet normals are stored, so the size of the mesh is
+/// much smaller than the one of the facets.
+/// # Errors
+/// Returns `Error::MemoryBudgetExceeded` if the estimated size of the mesh exceeds
+/// `option.memory_budget`, besides the errors of I/O and parsing.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::streaming::*;
+/// use truck_polymesh::stl::{STLFace, STLType};
+/// let normal = [0.0, 0.0, 1.0];
+/// let faces = vec![
+///     STLFace {
+///         normal,
+///         vertices: [[0.0, 0.0, 0.0], [1.0, 0.0, 0.0], [0.0, 1.0, 0.0]],
+///     },
+///     STLFace {
+///         normal,
+///         vertices: [[1.0, 0.0, 0.0], [1.0, 1.0, 0.0], [0.0, 1.0, 0.0]],
+///     },
+/// ];
+/// let mut data = Vec::new();
+/// stl::write(faces, &mut data, STLType::Binary).unwrap();
+///
+/// let total = Some(data.len() as u64);
+/// let mut ratio = 0.0;
+/// let option = StreamingOption::default();
+/// let read = streaming::read_stl(&data[..], total, &option, |p| ratio = p.ratio().unwrap())
+///     .unwrap();
+/// // The positions of the facets are welded.
+/// assert_eq!(read.positions().len(), 4);
+/// assert_eq!(read.tri_faces().len(), 2);
+/// assert_eq!(ratio, 1.0);
+/// ```
+pub fn read_stl<R: Read, F: FnMut(Progress)>(
+    reader: R,
+    total_bytes: Option<u64>,
+    option: &StreamingOption,
+    callback: F,
+) -> Result<PolygonMesh> {
+    let count = Rc::new(Cell::new(0));
+    let reader = CountingReader {
+        reader,
+        count: Rc::clone(&count),
+    };
+    let mut builder = MeshBuilder::new(option, total_bytes, callback);
+    let mut positions = HashMap::<[i64; 3], usize>::new();
+    let mut normals = HashMap::<[i64; 3], usize>::new();
+    let tol = f64::max(option.weld_tolerance, f64::EPSILON);
+    let key = |v: [f32; 3]| {
+        [
+            (v[0] as f64 / tol).round() as i64,
+            (v[1] as f64 / tol).round() as i64,
+            (v[2] as f64 / tol).round() as i64,
+        ]
+    };
+    for face in STLReader::new(reader, STLType::Automatic)? {
+        let face = face?;
+        let mut tri = [Vertex {
+            pos: 0,
+            uv: None,
+            nor: None,
+        }; 3];
+        for (vertex, p) in tri.iter_mut().zip(&face.vertices) {
+            vertex.pos = *positions.entry(key(*p)).or_insert_with(|| {
+                let p = Point3::new(p[0] as f64, p[1] as f64, p[2] as f64);
+                builder.positions.push(p);
+                builder.positions.len() - 1
+            });
+        }
+        if !option.positions_only {
+            let n = face.normal;
+            let nor = *normals.entry(key(n)).or_insert_with(|| {
+                let n = Vector3::new(n[0] as f64, n[1] as f64, n[2] as f64);
+                builder.normals.push(n);
+                builder.normals.len() - 1
+            });
+            tri.iter_mut().for_each(|vertex| vertex.nor = Some(nor));
+        }
+        let degenerate =
+            tri[0].pos == tri[1].pos || tri[1].pos == tri[2].pos || tri[2].pos == tri[0].pos;
+        if !degenerate {
+            builder.push_face(&tri);
+        }
+        builder.entries = positions.len() + normals.len();
+        builder.update(count.get())?;
+    }
+    let bytes_read = count.get();
+    builder.finish(bytes_read)
+}
+
+/// Reads the obj or STL file by [`read_obj`] or [`read_stl`], selected by the extension.
+///
+/// The total bytes of the progress are the size of the file.
+/// # Errors
+/// Returns `Error::UnsupportedFormat` if the extension is neither `obj` nor `stl`.
+///
+/// [`read_obj`]: ./fn.read_obj.html
+/// [`read_stl`]: ./fn.read_stl.html
+pub fn read_file<P: AsRef<Path>, F: FnMut(Progress)>(
+    path: P,
+    option: &StreamingOption,
+    callback: F,
+) -> Result<PolygonMesh> {
+    let path = path.as_ref();
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase())
+        .unwrap_or_default();
+    let file = File::open(path)?;
+    let total_bytes = Some(file.metadata()?.len());
+    match extension.as_str() {
+        "obj" => read_obj(file, total_bytes, option, callback),
+        "stl" => read_stl(file, total_bytes, option, callback),
+        _ => Err(Error::UnsupportedFormat(extension)),
+    }
+}
@@ -224,6 +224,7 @@ impl StructuredMesh {
             uv_coords,
             normals,
             faces,
+            skin: None,
         }
     }
 }
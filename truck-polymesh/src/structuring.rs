@@ -1,4 +1,5 @@
 use crate::*;
+use std::collections::HashMap;
 
 /// triangulation, quadrangulation, give a structure
 pub trait StructuringFilter {
@@ -72,6 +73,45 @@ pub trait StructuringFilter {
     /// assert_eq!(mesh.faces().len(), 6);
     /// ```
     fn quadrangulate(&mut self, plane_tol: f64, score_tol: f64) -> &mut Self;
+    /// merge the adjacent coplanar faces into convex n-gons.
+    /// # Arguments
+    /// * `tol` - the tolerance of the distances of the vertices from the plane
+    /// # Details
+    /// 1. The regions are grown from the faces in order. An adjacent face is added to the region
+    /// if its vertices are in the plane of the first face of the region within `tol`,
+    /// and its normal is not opposite.
+    /// 1. If the boundary of a region is a simple convex polygon, the region becomes one face.
+    /// 1. Otherwise, the adjacent pieces of the region are merged while the unions are
+    /// simple convex polygons, so the region is decomposed into the convex faces.
+    ///
+    /// The vertices on the boundaries of the regions are kept, so the shared vertices of
+    /// the adjacent regions do not make cracks. The attributes of each corner are taken from
+    /// the original face including the corner.
+    /// # Examples
+    /// ```
+    /// use truck_polymesh::prelude::*;
+    ///
+    /// // the 4 x 4 grid of the square, tessellated into triangles
+    /// let positions: Vec<Point3> = (0..25)
+    ///     .map(|i| Point3::new((i % 5) as f64, (i / 5) as f64, 0.0))
+    ///     .collect();
+    /// let mut faces = Faces::default();
+    /// for i in 0..4 {
+    ///     for j in 0..4 {
+    ///         let k = i * 5 + j;
+    ///         faces.push(&[k, k + 1, k + 6]);
+    ///         faces.push(&[k, k + 6, k + 5]);
+    ///     }
+    /// }
+    /// let mut mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+    /// assert_eq!(mesh.faces().len(), 32);
+    ///
+    /// mesh.merge_coplanar(1.0e-6);
+    /// assert_eq!(mesh.faces().len(), 1);
+    /// // The vertices on the boundary are kept.
+    /// assert_eq!(mesh.other_faces()[0].len(), 16);
+    /// ```
+    fn merge_coplanar(&mut self, tol: f64) -> &mut Self;
 }
 
 impl StructuringFilter for PolygonMesh {
@@ -94,6 +134,104 @@ impl StructuringFilter for PolygonMesh {
         self.reflect_face_edge_list(list);
         self
     }
+    fn merge_coplanar(&mut self, tol: f64) -> &mut Self {
+        let faces: Vec<Vec<Vertex>> = self.face_iter().map(|face| face.to_vec()).collect();
+        let positions = self.positions();
+        let normals: Vec<Vector3> = faces
+            .iter()
+            .map(|face| polygon_normal(positions, face))
+            .collect();
+        let mut edge_faces = HashMap::<[usize; 2], Vec<usize>>::new();
+        faces.iter().enumerate().for_each(|(i, face)| {
+            face_edges(face).for_each(|key| edge_faces.entry(key).or_insert_with(Vec::new).push(i))
+        });
+        // the faces adjacent by the manifold edges
+        let neighbors = |i: usize| {
+            face_edges(&faces[i])
+                .filter_map(|key| match edge_faces[&key].as_slice() {
+                    [j, k] if *j == i => Some(*k),
+                    [j, k] if *k == i => Some(*j),
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+        };
+        let mut used = vec![false; faces.len()];
+        let mut merged = Faces::default();
+        for seed in 0..faces.len() {
+            if used[seed] {
+                continue;
+            }
+            used[seed] = true;
+            if normals[seed].so_small() {
+                merged.push(&faces[seed]);
+                continue;
+            }
+            let normal = normals[seed].normalize();
+            let origin = positions[faces[seed][0].pos];
+            let mut region = vec![seed];
+            let mut stack = vec![seed];
+            while let Some(i) = stack.pop() {
+                for j in neighbors(i) {
+                    let coplanar = normals[j].dot(normal) > 0.0
+                        && faces[j]
+                            .iter()
+                            .all(|v| (positions[v.pos] - origin).dot(normal).abs() <= tol);
+                    if !used[j] && coplanar {
+                        used[j] = true;
+                        region.push(j);
+                        stack.push(j);
+                    }
+                }
+            }
+            if let Some(polygon) = convex_boundary(positions, &faces, &region, normal, tol) {
+                merged.push(&polygon);
+                continue;
+            }
+            // Merges the adjacent pieces of the region while the unions are convex.
+            let mut piece_of: HashMap<usize, usize> =
+                region.iter().enumerate().map(|(k, i)| (*i, k)).collect();
+            let mut pieces: Vec<(Vec<usize>, Vec<Vertex>)> = region
+                .iter()
+                .map(|i| (vec![*i], faces[*i].clone()))
+                .collect();
+            let mut updated = true;
+            while updated {
+                updated = false;
+                for k in 0..pieces.len() {
+                    let adjacent: Vec<usize> = pieces[k]
+                        .0
+                        .iter()
+                        .flat_map(|i| neighbors(*i))
+                        .filter_map(|j| piece_of.get(&j).copied())
+                        .filter(|l| *l != k && !pieces[*l].0.is_empty())
+                        .collect();
+                    for l in adjacent {
+                        if pieces[k].0.is_empty() || pieces[l].0.is_empty() {
+                            continue;
+                        }
+                        let union: Vec<usize> =
+                            pieces[k].0.iter().chain(&pieces[l].0).copied().collect();
+                        if let Some(polygon) =
+                            convex_boundary(positions, &faces, &union, normal, tol)
+                        {
+                            union.iter().for_each(|i| {
+                                piece_of.insert(*i, k);
+                            });
+                            pieces[l] = (Vec::new(), Vec::new());
+                            pieces[k] = (union, polygon);
+                            updated = true;
+                        }
+                    }
+                }
+            }
+            pieces
+                .iter()
+                .filter(|(piece, _)| !piece.is_empty())
+                .for_each(|(_, polygon)| merged.push(polygon));
+        }
+        *self.debug_editor().faces = merged;
+        self
+    }
 }
 
 impl PolygonMesh {
@@ -223,6 +361,85 @@ impl PolygonMesh {
     }
 }
 
+// the normal of the polygon by Newell's method
+fn polygon_normal(positions: &[Point3], face: &[Vertex]) -> Vector3 {
+    let center = face
+        .iter()
+        .fold(Vector3::zero(), |sum, v| sum + positions[v.pos].to_vec())
+        / face.len() as f64;
+    (0..face.len()).fold(Vector3::zero(), |sum, i| {
+        let p = positions[face[i].pos].to_vec() - center;
+        let q = positions[face[(i + 1) % face.len()].pos].to_vec() - center;
+        sum + p.cross(q)
+    })
+}
+
+// the keys of the edges of the polygon, independent of the directions
+fn face_edges(face: &[Vertex]) -> impl Iterator<Item = [usize; 2]> + '_ {
+    (0..face.len()).map(move |j| {
+        let (a, b) = (face[j].pos, face[(j + 1) % face.len()].pos);
+        [usize::min(a, b), usize::max(a, b)]
+    })
+}
+
+// the boundary of the faces in `region` if it is one simple convex polygon
+fn convex_boundary(
+    positions: &[Point3],
+    faces: &[Vec<Vertex>],
+    region: &[usize],
+    normal: Vector3,
+    tol: f64,
+) -> Option<Vec<Vertex>> {
+    let mut edges = HashMap::<(usize, usize), Vertex>::new();
+    region.iter().for_each(|i| {
+        let face = &faces[*i];
+        (0..face.len()).for_each(|j| {
+            edges.insert((face[j].pos, face[(j + 1) % face.len()].pos), face[j]);
+        })
+    });
+    let mut next = HashMap::<usize, (usize, Vertex)>::new();
+    for (&(a, b), v) in &edges {
+        if !edges.contains_key(&(b, a)) && next.insert(a, (b, *v)).is_some() {
+            return None;
+        }
+    }
+    let start = *next.keys().min()?;
+    let mut polygon = Vec::new();
+    let mut cursor = start;
+    loop {
+        let (b, v) = next.get(&cursor)?;
+        polygon.push(*v);
+        cursor = *b;
+        if cursor == start || polygon.len() > next.len() {
+            break;
+        }
+    }
+    if polygon.len() != next.len() {
+        return None;
+    }
+    // the signed distances of the vertices from the lines through the adjacent vertices
+    let len = polygon.len();
+    let convexities: Vec<f64> = (0..len)
+        .map(|i| {
+            let p = positions[polygon[(i + len - 1) % len].pos];
+            let q = positions[polygon[i].pos];
+            let r = positions[polygon[(i + 1) % len].pos];
+            let base = r - p;
+            match base.so_small() {
+                true => f64::NEG_INFINITY,
+                false => (q - p).cross(r - q).dot(normal) / base.magnitude(),
+            }
+        })
+        .collect();
+    if convexities.iter().any(|c| *c < -tol) {
+        return None;
+    }
+    // The fans of the triangulation are not degenerate from the strictly convex corner.
+    let first = convexities.iter().position(|c| *c > tol)?;
+    polygon.rotate_left(first);
+    Some(polygon)
+}
+
 #[inline(always)]
 fn calc_score(edge0: &Vector3, edge1: &Vector3, edge2: &Vector3, edge3: &Vector3) -> f64 {
     edge0.cos_angle(edge1).abs()
@@ -0,0 +1,498 @@
+use crate::*;
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use std::io::Write;
+
+/// Tetrahedral mesh, the positions and the tetrahedra of the indices of the positions.
+///
+/// All tetrahedra are positively oriented, i.e. `(p1 - p0).cross(p2 - p0).dot(p3 - p0) > 0`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct TetrahedralMesh {
+    positions: Vec<Point3>,
+    tetrahedra: Vec<[usize; 4]>,
+}
+
+/// The options of the tetrahedralization.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TetrahedralizationOption {
+    /// The interior of the mesh is filled by the lattice points with this spacing, and the
+    /// tetrahedra whose edges are longer than this value are refined.
+    /// If the value is infinite, only the vertices of the boundary mesh are used initially.
+    pub max_edge_length: f64,
+    /// The tetrahedra whose ratios of the circumradius to the shortest edge are greater than
+    /// this value are refined.
+    pub max_radius_edge_ratio: f64,
+    /// the maximum number of the iterations of the Delaunay refinement
+    pub max_refinement: usize,
+}
+
+impl Default for TetrahedralizationOption {
+    #[inline(always)]
+    fn default() -> TetrahedralizationOption {
+        TetrahedralizationOption {
+            max_edge_length: f64::INFINITY,
+            max_radius_edge_ratio: 2.0,
+            max_refinement: 3,
+        }
+    }
+}
+
+impl TetrahedralMesh {
+    /// Creates the tetrahedral mesh. The tetrahedra are oriented positively.
+    /// # Panics
+    /// Panic occurs if there is an index is out of range.
+    pub fn new(positions: Vec<Point3>, tetrahedra: Vec<[usize; 4]>) -> TetrahedralMesh {
+        TetrahedralMesh::try_new(positions, tetrahedra).unwrap_or_else(|e| panic!("{:?}", e))
+    }
+
+    /// Creates the tetrahedral mesh. The tetrahedra are oriented positively.
+    /// # Errors
+    /// Returns [`Error::OutOfRange`] if there is an index is out of range.
+    ///
+    /// [`Error::OutOfRange`]: ../errors/enum.Error.html#variant.OutOfRange
+    pub fn try_new(positions: Vec<Point3>, tetrahedra: Vec<[usize; 4]>) -> Result<TetrahedralMesh> {
+        let len = positions.len();
+        if let Some(idx) = tetrahedra.iter().flatten().find(|idx| **idx >= len) {
+            return Err(errors::Error::OutOfRange("positions", len, *idx));
+        }
+        let tetrahedra = tetrahedra
+            .into_iter()
+            .map(|tet| oriented(&positions, tet))
+            .collect();
+        Ok(TetrahedralMesh {
+            positions,
+            tetrahedra,
+        })
+    }
+
+    /// Returns the positions.
+    #[inline(always)]
+    pub fn positions(&self) -> &Vec<Point3> { &self.positions }
+
+    /// Returns the tetrahedra.
+    #[inline(always)]
+    pub fn tetrahedra(&self) -> &Vec<[usize; 4]> { &self.tetrahedra }
+
+    /// Returns the total volume.
+    pub fn volume(&self) -> f64 {
+        self.tetrahedra
+            .iter()
+            .map(|tet| signed_volume(&self.positions, *tet))
+            .sum()
+    }
+
+    /// Returns the radius ratio of each tetrahedron, `3 * inradius / circumradius`,
+    /// which is `1` for the regular tetrahedron and `0` for the degenerate one.
+    pub fn qualities(&self) -> Vec<f64> {
+        self.tetrahedra
+            .iter()
+            .map(|tet| {
+                let p: Vec<Point3> = tet.iter().map(|i| self.positions[*i]).collect();
+                let volume = signed_volume(&self.positions, *tet);
+                let area: f64 = [[0, 1, 2], [0, 1, 3], [0, 2, 3], [1, 2, 3]]
+                    .iter()
+                    .map(|f| (p[f[1]] - p[f[0]]).cross(p[f[2]] - p[f[0]]).magnitude() / 2.0)
+                    .sum();
+                match circumsphere([p[0], p[1], p[2], p[3]]) {
+                    Some((_, radius2)) if area > 0.0 => 9.0 * volume / area / radius2.sqrt(),
+                    _ => 0.0,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Tetrahedralizes the interior of the closed polygon mesh by the Delaunay refinement.
+///
+/// The vertices of the mesh and the interior lattice points are triangulated by the Delaunay
+/// tetrahedralization, and the tetrahedra outside of the mesh are removed by the winding number.
+/// The bad tetrahedra are refined by inserting their circumcenters. The boundary of the result
+/// approximates the mesh, however, the faces of the mesh are not always recovered exactly.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::tetrahedral::*;
+/// let positions = vec![
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(1.0, 1.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+///     Point3::new(0.0, 0.0, 1.0),
+///     Point3::new(1.0, 0.0, 1.0),
+///     Point3::new(1.0, 1.0, 1.0),
+///     Point3::new(0.0, 1.0, 1.0),
+/// ];
+/// let faces = Faces::from_iter(&[
+///     [0, 3, 2, 1],
+///     [4, 5, 6, 7],
+///     [0, 1, 5, 4],
+///     [1, 2, 6, 5],
+///     [2, 3, 7, 6],
+///     [3, 0, 4, 7],
+/// ]);
+/// let cube = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+///
+/// let option = TetrahedralizationOption {
+///     max_edge_length: 0.5,
+///     ..Default::default()
+/// };
+/// let tet_mesh = tetrahedralize(&cube, &option);
+/// assert!((tet_mesh.volume() - 1.0).abs() < 1.0e-6);
+/// assert!(tet_mesh.qualities().iter().all(|q| *q > 0.0));
+/// ```
+pub fn tetrahedralize(mesh: &PolygonMesh, option: &TetrahedralizationOption) -> TetrahedralMesh {
+    let triangles: Vec<[Point3; 3]> = mesh
+        .face_iter()
+        .flat_map(|face| {
+            let positions = mesh.positions();
+            (2..face.len()).map(move |i| {
+                [
+                    positions[face[0].pos],
+                    positions[face[i - 1].pos],
+                    positions[face[i].pos],
+                ]
+            })
+        })
+        .collect();
+    let bdd = mesh.bounding_box();
+    if bdd.is_empty() || triangles.is_empty() {
+        return TetrahedralMesh::default();
+    }
+    let mut delaunay = Delaunay::new(&bdd);
+    mesh.positions().iter().for_each(|pt| delaunay.insert(*pt));
+    let h = option.max_edge_length;
+    if h.is_finite() && h > 0.0 {
+        let (min, diag) = (*bdd.min(), bdd.diagonal());
+        let n = [
+            (diag[0] / h).ceil() as usize,
+            (diag[1] / h).ceil() as usize,
+            (diag[2] / h).ceil() as usize,
+        ];
+        for i in 0..n[0] {
+            for j in 0..n[1] {
+                for k in 0..n[2] {
+                    let pt = min + Vector3::new(i as f64 + 0.5, j as f64 + 0.5, k as f64 + 0.5) * h;
+                    if is_inside(&triangles, pt) && distance(&triangles, pt) > h / 2.0 {
+                        delaunay.insert(pt);
+                    }
+                }
+            }
+        }
+    }
+    for _ in 0..option.max_refinement {
+        let candidates: Vec<Point3> = delaunay
+            .inner_tetrahedra(&triangles)
+            .filter_map(|tet| {
+                let p = delaunay.points(tet);
+                let min_edge = min_edge_length(&p);
+                let (center, radius2) = (tet.center, tet.radius2);
+                let bad = radius2.sqrt() > option.max_radius_edge_ratio * min_edge
+                    || max_edge_length(&p) > h;
+                match bad && is_inside(&triangles, center) {
+                    true => Some((center, min_edge)),
+                    false => None,
+                }
+            })
+            .filter(|(center, min_edge)| distance(&triangles, *center) > min_edge / 4.0)
+            .map(|(center, _)| center)
+            .collect();
+        if candidates.is_empty() {
+            break;
+        }
+        candidates.into_iter().for_each(|pt| delaunay.insert(pt));
+    }
+    let tetrahedra: Vec<[usize; 4]> = delaunay
+        .inner_tetrahedra(&triangles)
+        .map(|tet| tet.vertices)
+        .collect();
+    // compress the indices
+    let mut indices = HashMap::new();
+    let mut positions = Vec::new();
+    let tetrahedra = tetrahedra
+        .into_iter()
+        .map(|tet| {
+            let mut res = [0; 4];
+            for (r, i) in res.iter_mut().zip(&tet) {
+                *r = *indices.entry(*i).or_insert_with(|| {
+                    positions.push(delaunay.positions[*i]);
+                    positions.len() - 1
+                });
+            }
+            res
+        })
+        .collect();
+    TetrahedralMesh::new(positions, tetrahedra)
+}
+
+/// Writes the tetrahedral mesh in the ASCII Gmsh format version 2.2.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::tetrahedral::*;
+/// let positions = vec![
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+///     Point3::new(0.0, 0.0, 1.0),
+/// ];
+/// let mesh = TetrahedralMesh::new(positions, vec![[0, 1, 2, 3]]);
+/// let mut output = Vec::new();
+/// write_msh(&mesh, &mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("$Elements\n1\n1 4 0 1 2 3 4\n$EndElements"));
+/// ```
+pub fn write_msh<W: Write>(mesh: &TetrahedralMesh, mut writer: W) -> Result<()> {
+    writer.write_fmt(format_args!("$MeshFormat\n2.2 0 8\n$EndMeshFormat\n"))?;
+    writer.write_fmt(format_args!("$Nodes\n{}\n", mesh.positions.len()))?;
+    for (i, p) in mesh.positions.iter().enumerate() {
+        writer.write_fmt(format_args!("{} {:?} {:?} {:?}\n", i + 1, p[0], p[1], p[2]))?;
+    }
+    writer.write_fmt(format_args!("$EndNodes\n"))?;
+    writer.write_fmt(format_args!("$Elements\n{}\n", mesh.tetrahedra.len()))?;
+    for (i, tet) in mesh.tetrahedra.iter().enumerate() {
+        writer.write_fmt(format_args!(
+            "{} 4 0 {} {} {} {}\n",
+            i + 1,
+            tet[0] + 1,
+            tet[1] + 1,
+            tet[2] + 1,
+            tet[3] + 1
+        ))?;
+    }
+    writer.write_fmt(format_args!("$EndElements\n"))?;
+    Ok(())
+}
+
+/// Writes the tetrahedral mesh in the legacy ASCII VTK format as an unstructured grid.
+/// Use [`vtk::write`](../vtk/fn.write.html) for attaching fields or the VTU format.
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::tetrahedral::*;
+/// let positions = vec![
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+///     Point3::new(0.0, 0.0, 1.0),
+/// ];
+/// let mesh = TetrahedralMesh::new(positions, vec![[0, 1, 2, 3]]);
+/// let mut output = Vec::new();
+/// write_vtk(&mesh, &mut output).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("CELLS 1 5\n4 0 1 2 3\nCELL_TYPES 1\n10\n"));
+/// ```
+#[inline(always)]
+pub fn write_vtk<W: Write>(mesh: &TetrahedralMesh, mut writer: W) -> Result<()> {
+    vtk::write(mesh, &Default::default(), &mut writer, vtk::VTKType::Legacy)
+}
+
+fn signed_volume(positions: &[Point3], tet: [usize; 4]) -> f64 {
+    let p = |i: usize| positions[tet[i]];
+    (p(1) - p(0)).cross(p(2) - p(0)).dot(p(3) - p(0)) / 6.0
+}
+
+fn oriented(positions: &[Point3], tet: [usize; 4]) -> [usize; 4] {
+    match signed_volume(positions, tet) < 0.0 {
+        true => [tet[0], tet[2], tet[1], tet[3]],
+        false => tet,
+    }
+}
+
+fn min_edge_length(p: &[Point3; 4]) -> f64 {
+    EDGES
+        .iter()
+        .map(|(i, j)| p[*i].distance(p[*j]))
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn max_edge_length(p: &[Point3; 4]) -> f64 {
+    EDGES
+        .iter()
+        .map(|(i, j)| p[*i].distance(p[*j]))
+        .fold(0.0, f64::max)
+}
+
+const EDGES: [(usize, usize); 6] = [(0, 1), (0, 2), (0, 3), (1, 2), (1, 3), (2, 3)];
+
+/// Returns the center and the square of the radius of the circumsphere.
+fn circumsphere(p: [Point3; 4]) -> Option<(Point3, f64)> {
+    let (a, b, c) = (p[1] - p[0], p[2] - p[0], p[3] - p[0]);
+    let det = a.dot(b.cross(c));
+    if det.abs() < f64::EPSILON * a.magnitude() * b.magnitude() * c.magnitude() {
+        return None;
+    }
+    let vec =
+        (b.cross(c) * a.magnitude2() + c.cross(a) * b.magnitude2() + a.cross(b) * c.magnitude2())
+            / (2.0 * det);
+    Some((p[0] + vec, vec.magnitude2()))
+}
+
+/// Returns whether the point is inside of the closed triangles by the winding number.
+fn is_inside(triangles: &[[Point3; 3]], pt: Point3) -> bool {
+    let winding: f64 = triangles
+        .iter()
+        .map(|tri| {
+            let (a, b, c) = (tri[0] - pt, tri[1] - pt, tri[2] - pt);
+            let (la, lb, lc) = (a.magnitude(), b.magnitude(), c.magnitude());
+            let numer = a.dot(b.cross(c));
+            let denom = la * lb * lc + a.dot(b) * lc + a.dot(c) * lb + b.dot(c) * la;
+            2.0 * f64::atan2(numer, denom)
+        })
+        .sum();
+    (winding / (4.0 * PI)).abs() > 0.5
+}
+
+/// Returns the distance from the point to the triangles.
+fn distance(triangles: &[[Point3; 3]], pt: Point3) -> f64 {
+    triangles
+        .iter()
+        .map(|tri| closest_point_on_triangle(tri, pt).distance(pt))
+        .fold(f64::INFINITY, f64::min)
+}
+
+fn closest_point_on_triangle(tri: &[Point3; 3], p: Point3) -> Point3 {
+    let (a, b, c) = (tri[0], tri[1], tri[2]);
+    let (ab, ac, ap) = (b - a, c - a, p - a);
+    let (d1, d2) = (ab.dot(ap), ac.dot(ap));
+    if d1 <= 0.0 && d2 <= 0.0 {
+        return a;
+    }
+    let bp = p - b;
+    let (d3, d4) = (ab.dot(bp), ac.dot(bp));
+    if d3 >= 0.0 && d4 <= d3 {
+        return b;
+    }
+    let vc = d1 * d4 - d3 * d2;
+    if vc <= 0.0 && d1 >= 0.0 && d3 <= 0.0 {
+        return a + ab * (d1 / (d1 - d3));
+    }
+    let cp = p - c;
+    let (d5, d6) = (ab.dot(cp), ac.dot(cp));
+    if d6 >= 0.0 && d5 <= d6 {
+        return c;
+    }
+    let vb = d5 * d2 - d1 * d6;
+    if vb <= 0.0 && d2 >= 0.0 && d6 <= 0.0 {
+        return a + ac * (d2 / (d2 - d6));
+    }
+    let va = d3 * d6 - d5 * d4;
+    if va <= 0.0 && d4 - d3 >= 0.0 && d5 - d6 >= 0.0 {
+        return b + (c - b) * ((d4 - d3) / ((d4 - d3) + (d5 - d6)));
+    }
+    let denom = 1.0 / (va + vb + vc);
+    a + ab * (vb * denom) + ac * (vc * denom)
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Tetrahedron {
+    vertices: [usize; 4],
+    center: Point3,
+    radius2: f64,
+}
+
+/// Delaunay tetrahedralization by the Bowyer-Watson algorithm.
+/// The first four positions are the vertices of the super tetrahedron.
+#[derive(Clone, Debug)]
+struct Delaunay {
+    positions: Vec<Point3>,
+    tetrahedra: Vec<Tetrahedron>,
+    /// the keys of the inserted positions, in order to skip the duplicated points
+    keys: HashMap<[i64; 3], usize>,
+}
+
+impl Delaunay {
+    fn new(bdd: &BoundingBox<Point3>) -> Delaunay {
+        let center = bdd.center();
+        let size = 10.0 * bdd.diameter().max(1.0);
+        let positions = vec![
+            center + Vector3::new(1.0, 1.0, 1.0) * size,
+            center + Vector3::new(1.0, -1.0, -1.0) * size,
+            center + Vector3::new(-1.0, 1.0, -1.0) * size,
+            center + Vector3::new(-1.0, -1.0, 1.0) * size,
+        ];
+        let mut res = Delaunay {
+            positions,
+            tetrahedra: Vec::new(),
+            keys: HashMap::new(),
+        };
+        res.push_tetrahedron([0, 1, 2, 3]);
+        res
+    }
+
+    fn points(&self, tet: &Tetrahedron) -> [Point3; 4] {
+        let v = tet.vertices;
+        [
+            self.positions[v[0]],
+            self.positions[v[1]],
+            self.positions[v[2]],
+            self.positions[v[3]],
+        ]
+    }
+
+    fn push_tetrahedron(&mut self, vertices: [usize; 4]) {
+        let p = [
+            self.positions[vertices[0]],
+            self.positions[vertices[1]],
+            self.positions[vertices[2]],
+            self.positions[vertices[3]],
+        ];
+        let (center, radius2) = circumsphere(p).unwrap_or((p[0], f64::INFINITY));
+        self.tetrahedra.push(Tetrahedron {
+            vertices,
+            center,
+            radius2,
+        });
+    }
+
+    fn insert(&mut self, pt: Point3) {
+        let key = [
+            (pt[0] / TOLERANCE).round() as i64,
+            (pt[1] / TOLERANCE).round() as i64,
+            (pt[2] / TOLERANCE).round() as i64,
+        ];
+        if self.keys.contains_key(&key) {
+            return;
+        }
+        let idx = self.positions.len();
+        self.keys.insert(key, idx);
+        self.positions.push(pt);
+        let (bad, good): (Vec<Tetrahedron>, Vec<Tetrahedron>) = self
+            .tetrahedra
+            .iter()
+            .partition(|tet| tet.center.distance2(pt) < tet.radius2 * (1.0 - 1.0e-12));
+        let mut faces: HashMap<[usize; 3], ([usize; 3], usize)> = HashMap::new();
+        for tet in &bad {
+            let v = tet.vertices;
+            for face in &[
+                [v[0], v[1], v[2]],
+                [v[0], v[1], v[3]],
+                [v[0], v[2], v[3]],
+                [v[1], v[2], v[3]],
+            ] {
+                let mut key = *face;
+                key.sort();
+                faces.entry(key).or_insert((*face, 0)).1 += 1;
+            }
+        }
+        self.tetrahedra = good;
+        for (face, count) in faces.values() {
+            if *count == 1 {
+                self.push_tetrahedron([face[0], face[1], face[2], idx]);
+            }
+        }
+    }
+
+    /// the tetrahedra not incident to the super tetrahedron and whose centroids are inside
+    fn inner_tetrahedra<'a>(
+        &'a self,
+        triangles: &'a [[Point3; 3]],
+    ) -> impl Iterator<Item = &'a Tetrahedron> + 'a {
+        self.tetrahedra.iter().filter(move |tet| {
+            let p = self.points(tet);
+            let centroid = Point3::from_vec(
+                (p[0].to_vec() + p[1].to_vec() + p[2].to_vec() + p[3].to_vec()) / 4.0,
+            );
+            tet.vertices.iter().all(|i| *i >= 4) && is_inside(triangles, centroid)
+        })
+    }
+}
@@ -0,0 +1,332 @@
+use crate::tetrahedral::TetrahedralMesh;
+use crate::*;
+use errors::Error;
+use std::io::Write;
+
+const VTK_VERTEX: u8 = 1;
+const VTK_TRIANGLE: u8 = 5;
+const VTK_POLYGON: u8 = 7;
+const VTK_QUAD: u8 = 9;
+const VTK_TETRA: u8 = 10;
+
+/// VTK file type
+#[derive(Copy, Clone, Debug, Serialize, Deserialize)]
+pub enum VTKType {
+    /// legacy ascii format, `.vtk`
+    Legacy,
+    /// XML ascii format of unstructured grid, `.vtu`
+    VTU,
+}
+
+impl Default for VTKType {
+    #[inline(always)]
+    fn default() -> VTKType { VTKType::Legacy }
+}
+
+/// The values of a field on the points or the cells.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Field {
+    /// scalar values, e.g. curvatures, thicknesses or deviations
+    Scalars(Vec<f64>),
+    /// vector values, e.g. normals or displacements
+    Vectors(Vec<Vector3>),
+}
+
+impl Field {
+    /// Returns the number of the values.
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        match self {
+            Field::Scalars(vec) => vec.len(),
+            Field::Vectors(vec) => vec.len(),
+        }
+    }
+
+    /// Returns `true` if the field has no values.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+}
+
+/// The named fields attached to the points and the cells.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Fields {
+    /// the fields on the points, whose lengths are the number of the points
+    pub point_data: Vec<(String, Field)>,
+    /// the fields on the cells, whose lengths are the number of the cells
+    pub cell_data: Vec<(String, Field)>,
+}
+
+impl Fields {
+    /// Adds a field on the points.
+    #[inline(always)]
+    pub fn with_point_data(mut self, name: &str, field: Field) -> Fields {
+        self.point_data.push((name.to_string(), field));
+        self
+    }
+
+    /// Adds a field on the cells.
+    #[inline(always)]
+    pub fn with_cell_data(mut self, name: &str, field: Field) -> Fields {
+        self.cell_data.push((name.to_string(), field));
+        self
+    }
+}
+
+/// Meshes which can be exported as VTK unstructured grids.
+pub trait UnstructuredGrid {
+    /// Returns the points.
+    fn points(&self) -> &[Point3];
+    /// Returns the cells, the pairs of the VTK cell type and the indices of the points.
+    fn cells(&self) -> Vec<(u8, Vec<usize>)>;
+}
+
+impl UnstructuredGrid for PolygonMesh {
+    #[inline(always)]
+    fn points(&self) -> &[Point3] { self.positions() }
+    fn cells(&self) -> Vec<(u8, Vec<usize>)> {
+        self.face_iter()
+            .map(|face| {
+                let cell_type = match face.len() {
+                    3 => VTK_TRIANGLE,
+                    4 => VTK_QUAD,
+                    _ => VTK_POLYGON,
+                };
+                (cell_type, face.iter().map(|v| v.pos).collect())
+            })
+            .collect()
+    }
+}
+
+impl UnstructuredGrid for TetrahedralMesh {
+    #[inline(always)]
+    fn points(&self) -> &[Point3] { self.positions() }
+    fn cells(&self) -> Vec<(u8, Vec<usize>)> {
+        self.tetrahedra()
+            .iter()
+            .map(|tet| (VTK_TETRA, tet.to_vec()))
+            .collect()
+    }
+}
+
+impl UnstructuredGrid for PointCloud {
+    #[inline(always)]
+    fn points(&self) -> &[Point3] { self.positions() }
+    fn cells(&self) -> Vec<(u8, Vec<usize>)> {
+        (0..self.positions().len())
+            .map(|i| (VTK_VERTEX, vec![i]))
+            .collect()
+    }
+}
+
+/// Writes the mesh and the fields as a VTK unstructured grid, which can be viewed in ParaView.
+/// # Errors
+/// Returns [`Error::InvalidFieldLength`] if the length of a field is not equal to
+/// the number of the points or the cells.
+///
+/// [`Error::InvalidFieldLength`]: ../errors/enum.Error.html#variant.InvalidFieldLength
+/// # Examples
+/// ```
+/// use truck_polymesh::*;
+/// use truck_polymesh::vtk::*;
+/// let positions = vec![
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(1.0, 1.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+/// ];
+/// let faces = Faces::from_iter(&[&[0, 1, 2], &[0, 2, 3]]);
+/// let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+/// let fields = Fields::default()
+///     .with_point_data("deviation", Field::Scalars(vec![0.0, 0.1, 0.2, 0.3]))
+///     .with_cell_data("normal", Field::Vectors(vec![Vector3::unit_z(); 2]));
+///
+/// let mut output = Vec::new();
+/// vtk::write(&mesh, &fields, &mut output, VTKType::Legacy).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("CELLS 2 8\n3 0 1 2\n3 0 2 3\nCELL_TYPES 2\n5\n5\n"));
+/// assert!(output.contains("POINT_DATA 4\nSCALARS deviation double 1\nLOOKUP_TABLE default\n"));
+/// assert!(output.contains("CELL_DATA 2\nVECTORS normal double\n"));
+///
+/// let mut output = Vec::new();
+/// vtk::write(&mesh, &fields, &mut output, VTKType::VTU).unwrap();
+/// let output = String::from_utf8(output).unwrap();
+/// assert!(output.contains("<Piece NumberOfPoints=\"4\" NumberOfCells=\"2\">"));
+/// assert!(output.contains("Name=\"deviation\" NumberOfComponents=\"1\""));
+///
+/// // the length of the field is not equal to the number of the points.
+/// let fields = Fields::default().with_point_data("deviation", Field::Scalars(vec![0.0]));
+/// assert!(vtk::write(&mesh, &fields, &mut Vec::new(), VTKType::Legacy).is_err());
+/// ```
+pub fn write<M: UnstructuredGrid + ?Sized, W: Write>(
+    mesh: &M,
+    fields: &Fields,
+    writer: &mut W,
+    vtk_type: VTKType,
+) -> Result<()> {
+    let (points, cells) = (mesh.points(), mesh.cells());
+    check_lengths(&fields.point_data, points.len())?;
+    check_lengths(&fields.cell_data, cells.len())?;
+    match vtk_type {
+        VTKType::Legacy => write_legacy(points, &cells, fields, writer),
+        VTKType::VTU => write_vtu(points, &cells, fields, writer),
+    }
+}
+
+fn check_lengths(data: &[(String, Field)], len: usize) -> Result<()> {
+    match data.iter().find(|(_, field)| field.len() != len) {
+        Some((name, field)) => Err(Error::InvalidFieldLength(name.clone(), len, field.len())),
+        None => Ok(()),
+    }
+}
+
+/// Writes legacy ascii VTK data
+fn write_legacy<W: Write>(
+    points: &[Point3],
+    cells: &[(u8, Vec<usize>)],
+    fields: &Fields,
+    writer: &mut W,
+) -> Result<()> {
+    writer.write_fmt(format_args!(
+        "# vtk DataFile Version 3.0\ntruck\nASCII\nDATASET UNSTRUCTURED_GRID\n"
+    ))?;
+    writer.write_fmt(format_args!("POINTS {} double\n", points.len()))?;
+    for p in points {
+        writer.write_fmt(format_args!("{:?} {:?} {:?}\n", p[0], p[1], p[2]))?;
+    }
+    let size: usize = cells.iter().map(|(_, cell)| cell.len() + 1).sum();
+    writer.write_fmt(format_args!("CELLS {} {}\n", cells.len(), size))?;
+    for (_, cell) in cells {
+        writer.write_fmt(format_args!("{}", cell.len()))?;
+        for idx in cell {
+            writer.write_fmt(format_args!(" {}", idx))?;
+        }
+        writer.write_fmt(format_args!("\n"))?;
+    }
+    writer.write_fmt(format_args!("CELL_TYPES {}\n", cells.len()))?;
+    for (cell_type, _) in cells {
+        writer.write_fmt(format_args!("{}\n", cell_type))?;
+    }
+    if !fields.point_data.is_empty() {
+        writer.write_fmt(format_args!("POINT_DATA {}\n", points.len()))?;
+        write_legacy_fields(&fields.point_data, writer)?;
+    }
+    if !fields.cell_data.is_empty() {
+        writer.write_fmt(format_args!("CELL_DATA {}\n", cells.len()))?;
+        write_legacy_fields(&fields.cell_data, writer)?;
+    }
+    Ok(())
+}
+
+fn write_legacy_fields<W: Write>(data: &[(String, Field)], writer: &mut W) -> Result<()> {
+    for (name, field) in data {
+        // The names in the legacy format cannot contain whitespaces.
+        let name = name.replace(char::is_whitespace, "_");
+        match field {
+            Field::Scalars(vec) => {
+                writer.write_fmt(format_args!(
+                    "SCALARS {} double 1\nLOOKUP_TABLE default\n",
+                    name
+                ))?;
+                for x in vec {
+                    writer.write_fmt(format_args!("{:?}\n", x))?;
+                }
+            }
+            Field::Vectors(vec) => {
+                writer.write_fmt(format_args!("VECTORS {} double\n", name))?;
+                for v in vec {
+                    writer.write_fmt(format_args!("{:?} {:?} {:?}\n", v[0], v[1], v[2]))?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Writes XML ascii VTK unstructured grid data
+fn write_vtu<W: Write>(
+    points: &[Point3],
+    cells: &[(u8, Vec<usize>)],
+    fields: &Fields,
+    writer: &mut W,
+) -> Result<()> {
+    writer.write_fmt(format_args!(
+        "<?xml version=\"1.0\"?>\n<VTKFile type=\"UnstructuredGrid\" version=\"0.1\" byte_order=\"LittleEndian\">\n<UnstructuredGrid>\n"
+    ))?;
+    writer.write_fmt(format_args!(
+        "<Piece NumberOfPoints=\"{}\" NumberOfCells=\"{}\">\n",
+        points.len(),
+        cells.len()
+    ))?;
+    writer.write_fmt(format_args!("<PointData>\n"))?;
+    write_vtu_fields(&fields.point_data, writer)?;
+    writer.write_fmt(format_args!("</PointData>\n<CellData>\n"))?;
+    write_vtu_fields(&fields.cell_data, writer)?;
+    writer.write_fmt(format_args!("</CellData>\n<Points>\n"))?;
+    writer.write_fmt(format_args!(
+        "<DataArray type=\"Float64\" NumberOfComponents=\"3\" format=\"ascii\">\n"
+    ))?;
+    for p in points {
+        writer.write_fmt(format_args!("{:?} {:?} {:?}\n", p[0], p[1], p[2]))?;
+    }
+    writer.write_fmt(format_args!("</DataArray>\n</Points>\n<Cells>\n"))?;
+    writer.write_fmt(format_args!(
+        "<DataArray type=\"Int64\" Name=\"connectivity\" format=\"ascii\">\n"
+    ))?;
+    for (_, cell) in cells {
+        let line: Vec<String> = cell.iter().map(|idx| idx.to_string()).collect();
+        writer.write_fmt(format_args!("{}\n", line.join(" ")))?;
+    }
+    writer.write_fmt(format_args!(
+        "</DataArray>\n<DataArray type=\"Int64\" Name=\"offsets\" format=\"ascii\">\n"
+    ))?;
+    let mut offset = 0;
+    for (_, cell) in cells {
+        offset += cell.len();
+        writer.write_fmt(format_args!("{}\n", offset))?;
+    }
+    writer.write_fmt(format_args!(
+        "</DataArray>\n<DataArray type=\"UInt8\" Name=\"types\" format=\"ascii\">\n"
+    ))?;
+    for (cell_type, _) in cells {
+        writer.write_fmt(format_args!("{}\n", cell_type))?;
+    }
+    writer.write_fmt(format_args!(
+        "</DataArray>\n</Cells>\n</Piece>\n</UnstructuredGrid>\n</VTKFile>\n"
+    ))?;
+    Ok(())
+}
+
+fn write_vtu_fields<W: Write>(data: &[(String, Field)], writer: &mut W) -> Result<()> {
+    for (name, field) in data {
+        let components = match field {
+            Field::Scalars(_) => 1,
+            Field::Vectors(_) => 3,
+        };
+        writer.write_fmt(format_args!(
+            "<DataArray type=\"Float64\" Name=\"{}\" NumberOfComponents=\"{}\" format=\"ascii\">\n",
+            escape_xml(name),
+            components
+        ))?;
+        match field {
+            Field::Scalars(vec) => {
+                for x in vec {
+                    writer.write_fmt(format_args!("{:?}\n", x))?;
+                }
+            }
+            Field::Vectors(vec) => {
+                for v in vec {
+                    writer.write_fmt(format_args!("{:?} {:?} {:?}\n", v[0], v[1], v[2]))?;
+                }
+            }
+        }
+        writer.write_fmt(format_args!("</DataArray>\n"))?;
+    }
+    Ok(())
+}
+
+fn escape_xml(name: &str) -> String {
+    name.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -0,0 +1,50 @@
+use truck_polymesh::gltf::{GLTFType, Node, WriteOptions};
+use truck_polymesh::*;
+
+fn read_u32(bytes: &[u8], offset: usize) -> usize {
+    let mut word = [0; 4];
+    word.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(word) as usize
+}
+
+#[test]
+fn glb_chunks() {
+    let positions = vec![
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(1.0, 1.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+    ];
+    let normals = vec![Vector3::unit_z()];
+    let faces = Faces::from_iter([&[[0, 0, 0], [1, 0, 0], [2, 0, 0], [3, 0, 0]]]);
+    let mesh = PolygonMesh::new(positions, vec![Vector2::new(0.0, 0.0)], normals, faces);
+    let options = WriteOptions {
+        gltf_type: GLTFType::GLB,
+        nodes: vec![Node {
+            name: Some("quad \"0\"".to_string()),
+            mesh: Some(0),
+            ..Default::default()
+        }],
+        ..Default::default()
+    };
+    let mut glb = Vec::new();
+    gltf::write(&[mesh], &options, &mut glb).unwrap();
+
+    assert_eq!(&glb[0..4], b"glTF");
+    assert_eq!(read_u32(&glb, 4), 2);
+    assert_eq!(read_u32(&glb, 8), glb.len());
+    let json_length = read_u32(&glb, 12);
+    assert_eq!(&glb[16..20], b"JSON");
+    assert_eq!(json_length % 4, 0);
+    let json = std::str::from_utf8(&glb[20..20 + json_length]).unwrap();
+    assert!(json.contains("\"name\":\"quad \\\"0\\\"\""));
+    assert!(!json.contains("\"uri\""));
+
+    let bin = 20 + json_length;
+    assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+    // 4 positions, 4 normals, 4 uv coordinates and 6 indices
+    let bin_length = read_u32(&glb, bin);
+    assert_eq!(bin_length, 4 * 12 + 4 * 12 + 4 * 8 + 6 * 4);
+    assert!(json.contains(&format!("\"buffers\":[{{\"byteLength\":{}}}]", bin_length)));
+    assert_eq!(glb.len(), bin + 8 + bin_length);
+}
@@ -0,0 +1,89 @@
+use ply::{PLYMesh, PLYType};
+use truck_polymesh::*;
+
+#[test]
+fn ply_round_trip() {
+    let mesh = obj::read(include_bytes!("data/teapot-with-normals.obj").as_ref()).unwrap();
+    let colors: Vec<Vector4> = mesh
+        .positions()
+        .iter()
+        .map(|p| Vector4::new(p.x.abs().min(1.0), 0.5, 0.0, 1.0))
+        .collect();
+    let ply = PLYMesh {
+        mesh: mesh.clone(),
+        colors,
+    };
+    for ply_type in [PLYType::ASCII, PLYType::BinaryLittleEndian] {
+        let mut data = Vec::new();
+        ply::write(&ply, &mut data, ply_type).unwrap();
+        let read = ply::read(data.as_slice()).unwrap();
+        assert_eq!(read.mesh.faces().len(), mesh.faces().len());
+        assert_eq!(read.colors.len(), read.mesh.positions().len());
+        for (face0, face1) in mesh.face_iter().zip(read.mesh.face_iter()) {
+            assert_eq!(face0.len(), face1.len());
+            for (v0, v1) in face0.iter().zip(face1) {
+                let (p0, p1) = (mesh.positions()[v0.pos], read.mesh.positions()[v1.pos]);
+                assert_eq!(p0, p1);
+                let n0 = mesh.normals()[v0.nor.unwrap()];
+                assert_eq!(n0, read.mesh.normals()[v1.nor.unwrap()]);
+                let (c0, c1) = (ply.colors[v0.pos], read.colors[v1.pos]);
+                assert!((c0 - c1).magnitude() < 1.0 / 255.0);
+            }
+        }
+    }
+}
+
+#[test]
+fn ply_unsupported() {
+    let data = b"ply\nformat binary_big_endian 1.0\nelement vertex 0\nend_header\n";
+    assert!(matches!(
+        ply::read(data.as_ref()),
+        Err(errors::Error::UnsupportedFormat(_)),
+    ));
+    // the data is shorter than the header says
+    let data = b"ply\nformat ascii 1.0\nelement vertex 2\nproperty float x\nproperty float y\n\
+                 property float z\nend_header\n0 0 0\n";
+    assert!(ply::read(data.as_ref()).is_err());
+}
+
+#[test]
+fn ply_invalid_data() {
+    const HEADER: &str = "ply\nformat ascii 1.0\nelement vertex 3\nproperty float x\n\
+                          property float y\nproperty float z\nproperty uchar red\n\
+                          property uchar green\nproperty uchar blue\nelement face 1\n\
+                          property list char int vertex_indices\nend_header\n\
+                          0 0 0 255 0 0\n1 0 0 0 255 0\n";
+    let read = |body: &str| ply::read(format!("{}{}", HEADER, body).as_bytes());
+    let is_invalid_data = |body: &str| match read(body) {
+        Err(errors::Error::FromIO(error)) => error.kind() == std::io::ErrorKind::InvalidData,
+        _ => false,
+    };
+    assert!(read("0 1 0 0 0 255\n3 0 1 2\n").is_ok());
+    // a uchar color out of range
+    assert!(is_invalid_data("0 1 0 0 0 300\n3 0 1 2\n"));
+    // a negative color
+    assert!(is_invalid_data("0 1 0 0 0 -1\n3 0 1 2\n"));
+    // an integer written as a real number
+    assert!(is_invalid_data("0 1 0 0 0 1.5\n3 0 1 2\n"));
+    // a negative index
+    assert!(is_invalid_data("0 1 0 0 0 255\n3 0 1 -1\n"));
+    // a negative length of the list
+    assert!(is_invalid_data("0 1 0 0 0 255\n-1 0 1 2\n"));
+    // an index out of the range of int
+    assert!(is_invalid_data("0 1 0 0 0 255\n3 0 1 4294967296\n"));
+    // a face with two vertices
+    assert!(is_invalid_data("0 1 0 0 0 255\n2 0 1\n"));
+
+    // a negative index in the binary format
+    let mut data = b"ply\nformat binary_little_endian 1.0\nelement vertex 3\n\
+                     property float x\nproperty float y\nproperty float z\nelement face 1\n\
+                     property list uchar int vertex_indices\nend_header\n"
+        .to_vec();
+    data.extend((0..9).flat_map(|_| 0.0f32.to_le_bytes()));
+    data.push(3);
+    data.extend([0i32, 1, -1].iter().flat_map(|i| i.to_le_bytes()));
+    assert!(matches!(
+        ply::read(data.as_slice()),
+        Err(errors::Error::FromIO(error)) if error.kind() == std::io::ErrorKind::InvalidData,
+    ));
+}
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+use std::f64::consts::PI;
+use truck_polymesh::*;
+
+fn sphere_cloud(center: Point3, radius: f64, n: usize) -> PointCloud {
+    let (positions, normals) = (0..n)
+        .map(|i| {
+            let z = 1.0 - (2 * i + 1) as f64 / n as f64;
+            let theta = PI * (3.0 - f64::sqrt(5.0)) * i as f64;
+            let r = f64::sqrt(1.0 - z * z);
+            let normal = Vector3::new(r * theta.cos(), r * theta.sin(), z);
+            (center + normal * radius, normal)
+        })
+        .unzip();
+    PointCloud::new(positions, normals)
+}
+
+#[test]
+fn different_length_arrays() {
+    let positions = vec![Point3::origin(); 3];
+    let normals = vec![Vector3::unit_z(); 2];
+    assert!(PointCloud::try_new(positions, normals).is_err());
+}
+
+#[test]
+fn reconstruct_sphere() {
+    let center = Point3::new(1.0, -2.0, 3.0);
+    let cloud = sphere_cloud(center, 2.0, 3000);
+    let mesh = cloud.reconstruct(24);
+    assert!(mesh.quad_faces().is_empty() && mesh.other_faces().is_empty());
+    for pt in mesh.positions() {
+        assert!(((pt - center).magnitude() - 2.0).abs() < 0.05, "{:?}", pt);
+    }
+    // watertight: each directed edge appears exactly once, and so does its inverse.
+    let mut edges = HashMap::new();
+    for tri in mesh.tri_faces() {
+        for i in 0..3 {
+            *edges.entry((tri[i].pos, tri[(i + 1) % 3].pos)).or_insert(0) += 1;
+        }
+    }
+    for ((a, b), count) in &edges {
+        assert_eq!(*count, 1);
+        assert_eq!(edges.get(&(*b, *a)), Some(&1));
+    }
+    // outward orientation: the signed volume is positive
+    let volume = mesh.tri_faces().iter().fold(0.0, |sum, tri| {
+        let p0 = mesh.positions()[tri[0].pos] - center;
+        let p1 = mesh.positions()[tri[1].pos] - center;
+        let p2 = mesh.positions()[tri[2].pos] - center;
+        sum + p0.dot(p1.cross(p2)) / 6.0
+    });
+    let exact = 4.0 / 3.0 * PI * 8.0;
+    assert!((volume - exact).abs() < exact * 0.05, "{} {}", volume, exact);
+}
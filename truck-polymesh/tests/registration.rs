@@ -0,0 +1,78 @@
+use truck_polymesh::registration::*;
+use truck_polymesh::*;
+
+fn ellipsoid_cloud(n: usize) -> PointCloud {
+    let (positions, normals) = (0..n)
+        .map(|i| {
+            let z = 1.0 - (2 * i + 1) as f64 / n as f64;
+            let theta = std::f64::consts::PI * (3.0 - f64::sqrt(5.0)) * i as f64;
+            let r = f64::sqrt(1.0 - z * z);
+            let pt = Point3::new(3.0 * r * theta.cos(), 2.0 * r * theta.sin(), z);
+            let normal = Vector3::new(pt[0] / 9.0, pt[1] / 4.0, pt[2]).normalize();
+            (pt, normal)
+        })
+        .unzip();
+    PointCloud::new(positions, normals)
+}
+
+fn answer() -> Matrix4 {
+    Matrix4::from_translation(Vector3::new(-0.15, 0.1, 0.2))
+        * Matrix4::from_axis_angle(Vector3::new(0.0, 1.0, 1.0).normalize(), Rad(0.08))
+}
+
+#[test]
+fn point_to_point() {
+    let target = ellipsoid_cloud(2000);
+    // point-to-point converges slowly, so the displacement is smaller than `answer`
+    let answer = Matrix4::from_translation(Vector3::new(-0.03, 0.02, 0.04))
+        * Matrix4::from_axis_angle(Vector3::new(0.0, 1.0, 1.0).normalize(), Rad(0.02));
+    let inverse = answer.invert().unwrap();
+    let source: Vec<Point3> = target
+        .positions()
+        .iter()
+        .map(|pt| inverse.transform_point(*pt))
+        .collect();
+    let option = RegistrationOption {
+        point_to_plane: false,
+        ..Default::default()
+    };
+    let mat = register_with_option(&source, &target, option);
+    source.iter().zip(target.positions()).for_each(|(p, q)| {
+        assert!(mat.transform_point(*p).distance(*q) < 1.0e-4);
+    });
+}
+
+#[test]
+fn reject_outliers() {
+    let target = ellipsoid_cloud(2000);
+    let inverse = answer().invert().unwrap();
+    let mut source: Vec<Point3> = target
+        .positions()
+        .iter()
+        .map(|pt| inverse.transform_point(*pt))
+        .collect();
+    // noises far from the surface
+    source.extend((0..20).map(|i| Point3::new(10.0 + i as f64, -10.0, 5.0)));
+    let mat = register(&source, &target);
+    source.iter().zip(target.positions()).for_each(|(p, q)| {
+        assert!(mat.transform_point(*p).distance(*q) < 1.0e-4);
+    });
+}
+
+#[test]
+fn mesh_to_point_cloud() {
+    let positions = vec![
+        Point3::new(0.0, 0.0, 0.0),
+        Point3::new(1.0, 0.0, 0.0),
+        Point3::new(1.0, 1.0, 0.0),
+        Point3::new(0.0, 1.0, 0.0),
+    ];
+    let faces = Faces::from_iter(&[[0, 1, 2], [0, 2, 3]]);
+    let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+    let cloud = PointCloud::from(&mesh);
+    assert_eq!(cloud.len(), 4);
+    cloud
+        .normals()
+        .iter()
+        .for_each(|normal| assert!(normal.near(&Vector3::unit_z())));
+}
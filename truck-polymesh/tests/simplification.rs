@@ -0,0 +1,106 @@
+use std::collections::HashMap;
+use truck_polymesh::*;
+
+// the cube whose faces are divided into `n` x `n` grids, projected onto the unit sphere
+fn cube_sphere(n: usize) -> PolygonMesh {
+    let mut positions = Vec::new();
+    let mut indices = HashMap::<[i64; 3], usize>::new();
+    let mut faces = Faces::default();
+    let axes = [
+        (Vector3::unit_x(), Vector3::unit_y(), Vector3::unit_z()),
+        (Vector3::unit_y(), Vector3::unit_z(), Vector3::unit_x()),
+        (Vector3::unit_z(), Vector3::unit_x(), Vector3::unit_y()),
+    ];
+    for (normal, u, v) in axes.iter() {
+        for sign in &[1.0, -1.0] {
+            let (normal, u) = (normal * *sign, u * *sign);
+            let mut index = |i: usize, j: usize| {
+                let (s, t) = (2 * i as i64 - n as i64, 2 * j as i64 - n as i64);
+                let p = normal * n as f64 + u * s as f64 + v * t as f64;
+                let key = [p.x as i64, p.y as i64, p.z as i64];
+                *indices.entry(key).or_insert_with(|| {
+                    positions.push(Point3::from_vec(p.normalize()));
+                    positions.len() - 1
+                })
+            };
+            for i in 0..n {
+                for j in 0..n {
+                    let quad = [index(i, j), index(i + 1, j), index(i + 1, j + 1), index(i, j + 1)];
+                    faces.push(&quad);
+                }
+            }
+        }
+    }
+    PolygonMesh::new(positions, Vec::new(), Vec::new(), faces)
+}
+
+fn edge_counts(mesh: &PolygonMesh) -> HashMap<(usize, usize), usize> {
+    let mut counts = HashMap::new();
+    for tri in mesh.tri_faces() {
+        for k in 0..3 {
+            let (a, b) = (tri[k].pos, tri[(k + 1) % 3].pos);
+            *counts.entry((usize::min(a, b), usize::max(a, b))).or_insert(0) += 1;
+        }
+    }
+    counts
+}
+
+#[test]
+fn simplify_closed_mesh() {
+    let mesh = cube_sphere(16);
+    assert_eq!(mesh.triangle_count(), 3072);
+    let simplified = mesh.simplify(0.1);
+    assert!(simplified.triangle_count() <= 307);
+    assert!(simplified.triangle_count() > 100);
+    // the mesh is still closed and oriented outward.
+    assert!(edge_counts(&simplified).values().all(|count| *count == 2));
+    for tri in simplified.tri_faces() {
+        let p = |i: usize| simplified.positions()[tri[i].pos];
+        let normal = (p(1) - p(0)).cross(p(2) - p(0));
+        assert!(normal.dot(p(0).to_vec()) > 0.0);
+    }
+    for p in simplified.positions() {
+        assert!((p.to_vec().magnitude() - 1.0).abs() < 0.05, "{:?}", p);
+    }
+}
+
+#[test]
+fn simplify_boundary() {
+    // the 20 x 20 grid on the plane z = 0
+    let positions: Vec<Point3> = (0..441)
+        .map(|i| Point3::new((i % 21) as f64 / 20.0, (i / 21) as f64 / 20.0, 0.0))
+        .collect();
+    let mut faces = Faces::default();
+    for i in 0..20 {
+        for j in 0..20 {
+            let idx = |i: usize, j: usize| i * 21 + j;
+            faces.push(&[idx(i, j), idx(i, j + 1), idx(i + 1, j + 1), idx(i + 1, j)]);
+        }
+    }
+    let mesh = PolygonMesh::new(positions, Vec::new(), Vec::new(), faces);
+
+    // the 80 positions on the boundary are fixed.
+    let preserved = mesh.simplify(0.01);
+    let boundary = |mesh: &PolygonMesh| {
+        let counts = edge_counts(mesh);
+        counts.values().filter(|count| **count == 1).count()
+    };
+    assert_eq!(boundary(&preserved), 80);
+    assert_eq!(preserved.triangle_count(), 78);
+
+    let options = SimplificationOptions {
+        preserve_boundary: false,
+        ..Default::default()
+    };
+    let simplified = mesh.simplify_with_options(0.01, options);
+    assert!(simplified.triangle_count() <= 8);
+    let area: f64 = simplified
+        .tri_faces()
+        .iter()
+        .map(|tri| {
+            let p = |i: usize| simplified.positions()[tri[i].pos];
+            (p(1) - p(0)).cross(p(2) - p(0)).z / 2.0
+        })
+        .sum();
+    assert!(area.near(&1.0), "{}", area);
+}
@@ -74,3 +74,48 @@ fn through_polymesh() {
         assert!(f32::abs(face0.normal[2] - face1.normal[2]) < 1.0e-4);
     } 
 }
+
+#[test]
+fn separated_facets() {
+    let options = stl::ReadOptions {
+        merge_vertices: false,
+        ..Default::default()
+    };
+    let data: &[u8] = include_bytes!("data/bunny_binary.stl");
+    let separated = stl::read_with_options(data, &options).unwrap();
+    let faces = STLReader::<&[u8]>::new(data, STLType::Automatic)
+        .unwrap()
+        .collect::<Result<Vec<_>>>()
+        .unwrap();
+    assert_eq!(separated.positions().len(), faces.len() * 3);
+    assert_eq!(separated.tri_faces().len(), faces.len());
+    // the positions are not snapped to the grid of the merging
+    for (tri, face) in separated.tri_faces().iter().zip(&faces) {
+        for (v, p) in tri.iter().zip(&face.vertices) {
+            let q = separated.positions()[v.pos].cast::<f32>().unwrap();
+            assert_eq!([q.x, q.y, q.z], *p);
+        }
+    }
+    let merged = stl::read(data, STLType::Automatic).unwrap();
+    assert!(merged.positions().len() < separated.positions().len());
+}
+
+#[test]
+fn written_normals() {
+    let polymesh = stl::read(&include_bytes!("data/bunny_binary.stl")[..], STLType::Automatic)
+        .unwrap();
+    let mut data = Vec::new();
+    stl::write(&polymesh, &mut data, STLType::Binary).unwrap();
+    let faces = STLReader::<&[u8]>::new(&data[..], STLType::Binary).unwrap();
+    for face in faces {
+        let face = face.unwrap();
+        let expected = STLFace::from_vertices(face.vertices);
+        let diff = (0..3).map(|i| f32::abs(face.normal[i] - expected.normal[i]));
+        assert!(diff.fold(0.0, f32::max) < 1.0e-4);
+    }
+    // truncated binary data
+    let truncated = STLReader::<&[u8]>::new(&data[..data.len() - 10], STLType::Binary)
+        .unwrap()
+        .collect::<Result<Vec<_>>>();
+    assert!(truncated.is_err());
+}
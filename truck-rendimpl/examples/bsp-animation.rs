@@ -117,8 +117,12 @@ impl App for MyApp {
                 position: Point3::new(0.5, 2.0, 0.5),
                 color: Vector3::new(1.0, 1.0, 1.0),
                 light_type: LightType::Point,
+                ..Default::default()
             }],
-            sample_count,
+            render_settings: RenderSettings {
+                sample_count,
+                ..Default::default()
+            },
             ..Default::default()
         };
         let mut scene = Scene::new(handler.clone(), &desc);
@@ -46,30 +46,37 @@ impl App for MyApp {
                     position: Point3::new(-a, -a, b),
                     color: Vector3::new(0.5, 0.5, 0.5),
                     light_type: LightType::Point,
+                    ..Default::default()
                 },
                 Light {
                     position: Point3::new(-a, a, b),
                     color: Vector3::new(0.5, 0.5, 0.5),
                     light_type: LightType::Point,
+                    ..Default::default()
                 },
                 Light {
                     position: Point3::new(a, -a, b),
                     color: Vector3::new(0.5, 0.5, 0.5),
                     light_type: LightType::Point,
+                    ..Default::default()
                 },
                 Light {
                     position: Point3::new(a, a, b),
                     color: Vector3::new(0.5, 0.5, 0.5),
                     light_type: LightType::Point,
+                    ..Default::default()
                 },
             ],
-            background: Color {
-                r: BACKGROUND[0],
-                g: BACKGROUND[1],
-                b: BACKGROUND[2],
-                a: BACKGROUND[3],
+            render_settings: RenderSettings {
+                background: Color {
+                    r: BACKGROUND[0],
+                    g: BACKGROUND[1],
+                    b: BACKGROUND[2],
+                    a: BACKGROUND[3],
+                },
+                sample_count,
+                ..Default::default()
             },
-            sample_count,
             ..Default::default()
         };
         let mut scene = Scene::new(handler.clone(), &scene_desc);
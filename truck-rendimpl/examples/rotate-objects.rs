@@ -122,8 +122,12 @@ impl App for MyRender {
                 position: Point3::new(0.0, 20.0, 0.0),
                 color: Vector3::new(1.0, 1.0, 1.0) * 1.5,
                 light_type: LightType::Point,
+                ..Default::default()
             }],
-            sample_count,
+            render_settings: RenderSettings {
+                sample_count,
+                ..Default::default()
+            },
             ..Default::default()
         };
         let mut app = MyRender {
@@ -252,6 +256,7 @@ impl App for MyRender {
                             position: vec,
                             color: Vector3::new(1.0, 1.0, 1.0),
                             light_type: LightType::Uniform,
+                            ..Default::default()
                         }
                     }
                     LightType::Uniform => {
@@ -260,6 +265,7 @@ impl App for MyRender {
                             position,
                             color: Vector3::new(1.0, 1.0, 1.0),
                             light_type: LightType::Point,
+                            ..Default::default()
                         }
                     }
                 };
@@ -127,14 +127,18 @@ impl App for MyApp {
             _ => 1,
         };
         let scene_desc = SceneDescriptor {
-            background: Color::BLACK,
             camera: MyApp::create_camera(),
             lights: vec![Light {
                 position: Point3::new(1.0, 1.0, 1.0),
                 color: Vector3::new(1.0, 1.0, 1.0),
                 light_type: LightType::Point,
+                ..Default::default()
             }],
-            sample_count,
+            render_settings: RenderSettings {
+                sample_count,
+                ..Default::default()
+            },
+            ..Default::default()
         };
         let scene = Scene::new(handler.clone(), &scene_desc);
         let creator = scene.instance_creator();
@@ -256,6 +260,7 @@ impl App for MyApp {
                             position: vec,
                             color: Vector3::new(1.0, 1.0, 1.0),
                             light_type: LightType::Uniform,
+                            ..Default::default()
                         }
                     }
                     LightType::Uniform => {
@@ -264,6 +269,7 @@ impl App for MyApp {
                             position,
                             color: Vector3::new(1.0, 1.0, 1.0),
                             light_type: LightType::Point,
+                            ..Default::default()
                         }
                     }
                 };
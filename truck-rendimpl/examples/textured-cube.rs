@@ -56,8 +56,12 @@ impl App for MyApp {
                 position: Point3::new(1.0, 1.0, 1.0),
                 color: Vector3::new(1.0, 1.0, 1.0),
                 light_type: LightType::Point,
+                ..Default::default()
             }],
-            sample_count,
+            render_settings: RenderSettings {
+                sample_count,
+                ..Default::default()
+            },
             ..Default::default()
         };
         let mut scene = Scene::new(handler.clone(), &desc);
@@ -76,7 +80,7 @@ impl App for MyApp {
                     ambient_ratio: 0.05,
                 },
                 texture: Some(std::sync::Arc::new(texture)),
-                backface_culling: true,
+                ..Default::default()
             },
             ..Default::default()
         };
@@ -199,6 +203,7 @@ impl App for MyApp {
                             position,
                             color: Vector3::new(1.0, 1.0, 1.0),
                             light_type: LightType::Uniform,
+                            ..Default::default()
                         }
                     }
                     LightType::Uniform => {
@@ -207,6 +212,7 @@ impl App for MyApp {
                             position,
                             color: Vector3::new(1.0, 1.0, 1.0),
                             light_type: LightType::Point,
+                            ..Default::default()
                         }
                     }
                 }
@@ -0,0 +1,284 @@
+//! The debug instances displaying the differential geometry of curves and surfaces: the
+//! curvature combs along curves, and the glyphs of the normals and the tangents over surfaces.
+//!
+//! The debug instances are `WireFrameInstance`, so they are colored, moved and added to the
+//! scene in the same way as the other wire frames, e.g. over the shaded shapes.
+//! # Examples
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use truck_platform::*;
+//! use truck_rendimpl::*;
+//! use truck_rendimpl::debug::*;
+//! use truck_platform::wgpu::*;
+//! let instance = Instance::new(BackendBit::PRIMARY);
+//! let (device, queue, _) =
+//!     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+//! let sc_desc = SwapChainDescriptor {
+//!     usage: TextureUsage::OUTPUT_ATTACHMENT,
+//!     format: TextureFormat::Rgba8UnormSrgb,
+//!     width: 128,
+//!     height: 128,
+//!     present_mode: PresentMode::Mailbox,
+//! };
+//! let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+//! let mut scene = Scene::new(handler, &Default::default());
+//! let creator = scene.instance_creator();
+//!
+//! // a cylinder
+//! let v = builder::vertex(Point3::new(1.0, 0.0, 0.0));
+//! let edge = builder::tsweep(&v, Vector3::new(0.0, 1.0, 0.0));
+//! let shell: Shell = builder::rsweep(&edge, Point3::origin(), Vector3::unit_y(), Rad(7.0));
+//!
+//! let comb_desc = CurvatureCombDescriptor {
+//!     scale: 0.5,
+//!     ..Default::default()
+//! };
+//! let comb = creator.create_curvature_comb(&shell_curves(&shell), &comb_desc);
+//! let glyph_desc = SurfaceGlyphDescriptor {
+//!     wireframe_state: WireFrameState {
+//!         color: Vector4::new(1.0, 0.0, 0.0, 1.0),
+//!         ..Default::default()
+//!     },
+//!     kind: GlyphKind::Normal,
+//!     division: (16, 4),
+//!     length: 0.2,
+//! };
+//! let glyphs = creator.create_surface_glyphs(&shell_surfaces(&shell), &glyph_desc);
+//! scene.add_object(&comb);
+//! scene.add_object(&glyphs);
+//! assert_eq!(scene.number_of_objects(), 2);
+//! ```
+
+use crate::*;
+use modeling::geometry::Surface;
+use std::collections::HashSet;
+
+/// Configures of the curvature combs.
+#[derive(Clone, Debug)]
+pub struct CurvatureCombDescriptor {
+    /// configure of wire frame
+    pub wireframe_state: WireFrameState,
+    /// the number of the teeth of each curve. Default is `64`.
+    pub samples: usize,
+    /// the length of the tooth per unit curvature. Default is `1.0`.
+    pub scale: f64,
+}
+
+/// The vectors drawn by the surface glyphs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum GlyphKind {
+    /// the unit normal vectors
+    Normal,
+    /// the unit tangent vectors in the direction of the derivations by `u`
+    UTangent,
+    /// the unit tangent vectors in the direction of the derivations by `v`
+    VTangent,
+}
+
+/// Configures of the surface glyphs.
+#[derive(Clone, Debug)]
+pub struct SurfaceGlyphDescriptor {
+    /// configure of wire frame
+    pub wireframe_state: WireFrameState,
+    /// the vectors drawn by the glyphs. Default is `GlyphKind::Normal`.
+    pub kind: GlyphKind,
+    /// the numbers of the glyphs in the `u`- and `v`-directions of each surface.
+    /// Default is `(8, 8)`.
+    pub division: (usize, usize),
+    /// the length of the glyphs. Default is `0.1`.
+    pub length: f64,
+}
+
+impl Default for CurvatureCombDescriptor {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            wireframe_state: WireFrameState::default(),
+            samples: 64,
+            scale: 1.0,
+        }
+    }
+}
+
+impl Default for SurfaceGlyphDescriptor {
+    #[inline(always)]
+    fn default() -> Self {
+        Self {
+            wireframe_state: WireFrameState::default(),
+            kind: GlyphKind::Normal,
+            division: (8, 8),
+            length: 0.1,
+        }
+    }
+}
+
+/// Returns the teeth of the curvature comb of `curve` at the `samples` parameters dividing
+/// the parameter range uniformly.
+///
+/// Each tooth starts at the point on the curve, and is directed to the opposite side of
+/// the center of the curvature. Its length is the curvature multiplied by `scale`. The teeth at
+/// the singular points, where the derivation vanishes, are skipped.
+/// # Examples
+/// ```
+/// use truck_rendimpl::*;
+/// use truck_rendimpl::debug::curvature_comb;
+/// // the semicircle with the radius 2.0
+/// let v0 = builder::vertex(Point3::new(2.0, 0.0, 0.0));
+/// let v1 = builder::vertex(Point3::new(-2.0, 0.0, 0.0));
+/// let edge = builder::circle_arc(&v0, &v1, Point3::new(0.0, 2.0, 0.0));
+///
+/// let teeth = curvature_comb(&edge.oriented_curve(), 10, 1.0);
+/// assert_eq!(teeth.len(), 10);
+/// for [pt, tip] in teeth {
+///     assert_near!(pt.to_vec().magnitude(), 2.0);
+///     // The length of the tooth is the curvature 1 / 2.
+///     assert_near!(tip.to_vec().magnitude(), 2.5);
+/// }
+/// ```
+pub fn curvature_comb<C>(curve: &C, samples: usize, scale: f64) -> Vec<[Point3; 2]>
+where C: ParametricCurve<Point = Point3, Vector = Vector3> {
+    grid_parameters(curve.parameter_range(), samples)
+        .into_iter()
+        .filter_map(|t| {
+            let (der, der2) = (curve.der(t), curve.der2(t));
+            let mag2 = der.magnitude2();
+            if mag2.so_small2() {
+                return None;
+            }
+            let curvature = (der2 - der * (der2.dot(der) / mag2)) / mag2;
+            let pt = curve.subs(t);
+            Some([pt, pt - curvature * scale])
+        })
+        .collect()
+}
+
+/// Returns the glyphs of `surface` on the uniform grid of the parameter range, which has
+/// `division.0` points in the `u`-direction and `division.1` points in the `v`-direction.
+///
+/// Each glyph starts at the point on the surface, and its length is `length`. The glyphs at
+/// the singular points, where the vector is not determined, are skipped.
+/// # Examples
+/// ```
+/// use truck_rendimpl::*;
+/// use truck_rendimpl::debug::*;
+/// let plane = Plane::new(
+///     Point3::new(0.0, 0.0, 0.0),
+///     Point3::new(1.0, 0.0, 0.0),
+///     Point3::new(0.0, 1.0, 0.0),
+/// );
+/// let glyphs = surface_glyphs(&plane, GlyphKind::Normal, (3, 4), 0.5);
+/// assert_eq!(glyphs.len(), 12);
+/// assert_near!(glyphs[5][0], Point3::new(0.5, 1.0 / 3.0, 0.0));
+/// assert_near!(glyphs[5][1] - glyphs[5][0], Vector3::new(0.0, 0.0, 0.5));
+///
+/// let glyphs = surface_glyphs(&plane, GlyphKind::VTangent, (3, 4), 0.5);
+/// assert_near!(glyphs[5][1] - glyphs[5][0], Vector3::new(0.0, 0.5, 0.0));
+/// ```
+pub fn surface_glyphs<S>(
+    surface: &S,
+    kind: GlyphKind,
+    division: (usize, usize),
+    length: f64,
+) -> Vec<[Point3; 2]>
+where
+    S: ParametricSurface<Point = Point3, Vector = Vector3> + BoundedSurface,
+{
+    let (urange, vrange) = surface.parameter_range();
+    let vs = grid_parameters(vrange, division.1);
+    grid_parameters(urange, division.0)
+        .into_iter()
+        .flat_map(|u| vs.iter().map(move |v| (u, *v)))
+        .filter_map(|(u, v)| {
+            let vec = match kind {
+                GlyphKind::Normal => surface.normal(u, v),
+                GlyphKind::UTangent => surface.uder(u, v),
+                GlyphKind::VTangent => surface.vder(u, v),
+            };
+            let mag = vec.magnitude();
+            if !mag.is_finite() || mag.so_small() {
+                return None;
+            }
+            let pt = surface.subs(u, v);
+            Some([pt, pt + vec * (length / mag)])
+        })
+        .collect()
+}
+
+/// Returns the oriented curves of the edges of `shell`. Each edge shared by faces is
+/// returned once.
+pub fn shell_curves(shell: &Shell) -> Vec<Curve> {
+    let mut set = HashSet::new();
+    shell
+        .face_iter()
+        .flat_map(|face| face.boundary_iters())
+        .flatten()
+        .filter(|edge| set.insert(edge.id()))
+        .map(|edge| edge.oriented_curve())
+        .collect()
+}
+
+/// Returns the oriented surfaces of the faces of `shell`.
+///
+/// The glyphs of the surfaces are not trimmed by the boundaries of the faces.
+#[inline(always)]
+pub fn shell_surfaces(shell: &Shell) -> Vec<Surface> {
+    shell
+        .face_iter()
+        .map(|face| face.oriented_surface())
+        .collect()
+}
+
+impl InstanceCreator {
+    /// Creates the wire frame of the curvature combs of `curves`: the teeth and the polyline
+    /// connecting the tips of them for each curve.
+    ///
+    /// cf. [`curvature_comb`](./debug/fn.curvature_comb.html)
+    pub fn create_curvature_comb<'a, C, I>(
+        &self,
+        curves: I,
+        desc: &CurvatureCombDescriptor,
+    ) -> WireFrameInstance
+    where
+        C: 'a + ParametricCurve<Point = Point3, Vector = Vector3>,
+        I: IntoIterator<Item = &'a C>,
+    {
+        let mut points = Vec::<[f32; 3]>::new();
+        let mut strips = Vec::<u32>::new();
+        for curve in curves {
+            let counter = points.len() as u32;
+            let teeth = curvature_comb(curve, desc.samples, desc.scale);
+            for (i, [pt, tip]) in teeth.iter().enumerate() {
+                points.push(pt.cast().unwrap().into());
+                points.push(tip.cast().unwrap().into());
+                let i = counter + 2 * i as u32;
+                strips.extend(&[i, i + 1]);
+                if i > counter {
+                    strips.extend(&[i - 1, i + 1]);
+                }
+            }
+        }
+        self.wire_frame_instance(&points, &strips, &desc.wireframe_state)
+    }
+
+    /// Creates the wire frame of the glyphs of `surfaces`.
+    ///
+    /// cf. [`surface_glyphs`](./debug/fn.surface_glyphs.html)
+    pub fn create_surface_glyphs<'a, S, I>(
+        &self,
+        surfaces: I,
+        desc: &SurfaceGlyphDescriptor,
+    ) -> WireFrameInstance
+    where
+        S: 'a + ParametricSurface<Point = Point3, Vector = Vector3> + BoundedSurface,
+        I: IntoIterator<Item = &'a S>,
+    {
+        let points: Vec<[f32; 3]> = surfaces
+            .into_iter()
+            .flat_map(|surface| surface_glyphs(surface, desc.kind, desc.division, desc.length))
+            .flat_map(|glyph| glyph.to_vec())
+            .map(|pt| pt.cast().unwrap().into())
+            .collect();
+        let strips: Vec<u32> = (0..points.len() as u32).collect();
+        self.wire_frame_instance(&points, &strips, &desc.wireframe_state)
+    }
+}
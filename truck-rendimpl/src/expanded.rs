@@ -1,4 +1,5 @@
 use crate::*;
+use std::collections::HashMap;
 
 impl<V: Sized + Zeroable + Pod> ExpandedPolygon<V> {
     pub fn buffers(
@@ -11,6 +12,153 @@ impl<V: Sized + Zeroable + Pod> ExpandedPolygon<V> {
         let index_buffer = BufferHandler::from_slice(&self.indices, device, index_usage);
         (vertex_buffer, index_buffer)
     }
+
+    /// Creates the buffers of the chunks, each of which is drawn by a draw call.
+    /// The indices are 16-bit if all vertices can be indexed by them, and 32-bit otherwise.
+    /// Returns at least one chunk, even if the polygon is empty.
+    pub fn polygon_buffers(
+        &self,
+        vertex_usage: BufferUsage,
+        index_usage: BufferUsage,
+        device: &Device,
+    ) -> PolygonBuffers
+    where
+        V: VertexPosition,
+    {
+        self.polygon_buffers_with(index_usage, device, |vertices| {
+            BufferHandler::from_slice(&vertices, device, vertex_usage)
+        })
+    }
+
+    /// Creates the buffers of the chunks, whose vertex buffers are created by `vertex_buffer`.
+    pub fn polygon_buffers_with(
+        &self,
+        index_usage: BufferUsage,
+        device: &Device,
+        vertex_buffer: impl Fn(&[V]) -> BufferHandler,
+    ) -> PolygonBuffers
+    where
+        V: VertexPosition,
+    {
+        // `u16::MAX` is reserved for the primitive restart value.
+        let index_format = match self.vertices.len() < u16::MAX as usize {
+            true => IndexFormat::Uint16,
+            false => IndexFormat::Uint32,
+        };
+        let create = |polygon: &ExpandedPolygon<V>| {
+            let vb = vertex_buffer(&polygon.vertices);
+            let ib = match index_format {
+                // `PRIMITIVE_RESTART as u16` is the primitive restart value of `Uint16`.
+                IndexFormat::Uint16 => {
+                    let indices: Vec<u16> = polygon.indices.iter().map(|i| *i as u16).collect();
+                    BufferHandler::from_slice(&indices, device, index_usage)
+                }
+                IndexFormat::Uint32 => {
+                    BufferHandler::from_slice(&polygon.indices, device, index_usage)
+                }
+            };
+            (Arc::new(vb), Arc::new(ib))
+        };
+        let chunks = match self.vertices.len() <= MAX_CHUNK_VERTICES
+            && self.indices.len() <= MAX_CHUNK_INDICES
+        {
+            true => vec![create(self)],
+            false => self
+                .chunks(MAX_CHUNK_VERTICES, MAX_CHUNK_INDICES)
+                .iter()
+                .map(create)
+                .collect(),
+        };
+        PolygonBuffers {
+            chunks,
+            index_format,
+            primitive_topology: self.primitive_topology,
+            attributes: Vec::new(),
+            picking: Arc::new(self.picking_mesh()),
+        }
+    }
+
+    /// Creates the picking mesh of the triangles.
+    pub fn picking_mesh(&self) -> PickingMesh
+    where V: VertexPosition {
+        let positions = self
+            .vertices
+            .iter()
+            .map(|v| {
+                let [x, y, z] = v.position();
+                Point3::new(x as f64, y as f64, z as f64)
+            })
+            .collect();
+        let triangles = match self.primitive_topology {
+            PrimitiveTopology::TriangleStrip => self
+                .indices
+                .split(|i| *i == PRIMITIVE_RESTART)
+                .flat_map(|strip| strip.windows(3))
+                .map(|w| [w[0] as usize, w[1] as usize, w[2] as usize])
+                .collect(),
+            _ => self
+                .indices
+                .chunks_exact(3)
+                .map(|w| [w[0] as usize, w[1] as usize, w[2] as usize])
+                .collect(),
+        };
+        PickingMesh::new(positions, triangles)
+    }
+
+    /// Maps the vertices by `f`.
+    pub fn map_vertices<W>(self, f: impl FnMut(V) -> W) -> ExpandedPolygon<W> {
+        ExpandedPolygon {
+            vertices: self.vertices.into_iter().map(f).collect(),
+            indices: self.indices,
+            primitive_topology: self.primitive_topology,
+        }
+    }
+
+    /// Splits the triangles or the strips into the polygons with at most `max_vertices` vertices
+    /// and `max_indices` indices. The indices are renumbered in each chunk.
+    fn chunks(&self, max_vertices: usize, max_indices: usize) -> Vec<ExpandedPolygon<V>> {
+        let strip = self.primitive_topology == PrimitiveTopology::TriangleStrip;
+        let units: Box<dyn Iterator<Item = &[u32]>> = match strip {
+            true => Box::new(
+                self.indices
+                    .split(|i| *i == PRIMITIVE_RESTART)
+                    .filter(|strip| !strip.is_empty()),
+            ),
+            false => Box::new(self.indices.chunks(3)),
+        };
+        let new_chunk = || ExpandedPolygon {
+            primitive_topology: self.primitive_topology,
+            ..Default::default()
+        };
+        let mut res = Vec::new();
+        let mut current = new_chunk();
+        let mut map = HashMap::<u32, u32>::new();
+        for unit in units {
+            let new_vertices = unit.iter().filter(|idx| !map.contains_key(*idx)).count();
+            let separator = (strip && !current.indices.is_empty()) as usize;
+            if current.vertices.len() + new_vertices > max_vertices
+                || current.indices.len() + separator + unit.len() > max_indices
+            {
+                res.push(std::mem::replace(&mut current, new_chunk()));
+                map.clear();
+            }
+            if strip && !current.indices.is_empty() {
+                current.indices.push(PRIMITIVE_RESTART);
+            }
+            for idx in unit {
+                let vertices = &mut current.vertices;
+                let new_idx = *map.entry(*idx).or_insert_with(|| {
+                    vertices.push(self.vertices[*idx as usize]);
+                    vertices.len() as u32 - 1
+                });
+                current.indices.push(new_idx);
+            }
+        }
+        if res.is_empty() || !current.indices.is_empty() {
+            res.push(current);
+        }
+        res
+    }
 }
 
 impl<V> Default for ExpandedPolygon<V> {
@@ -18,6 +166,77 @@ impl<V> Default for ExpandedPolygon<V> {
         ExpandedPolygon {
             vertices: Vec::new(),
             indices: Vec::new(),
+            primitive_topology: PrimitiveTopology::TriangleList,
         }
     }
 }
+
+#[test]
+fn chunks_test() {
+    let polygon = ExpandedPolygon {
+        vertices: (0..6).map(|i| [i as f32; 2]).collect::<Vec<_>>(),
+        indices: vec![0, 1, 2, 2, 1, 3, 3, 4, 5, 5, 4, 0],
+        primitive_topology: PrimitiveTopology::TriangleList,
+    };
+    let chunks = polygon.chunks(4, 100);
+    assert_eq!(chunks.len(), 2);
+    for chunk in &chunks {
+        assert!(chunk.vertices.len() <= 4);
+        assert_eq!(chunk.indices.len() % 3, 0);
+    }
+    let restored: Vec<[f32; 2]> = chunks
+        .iter()
+        .flat_map(|chunk| {
+            chunk
+                .indices
+                .iter()
+                .map(move |i| chunk.vertices[*i as usize])
+        })
+        .collect();
+    let original: Vec<[f32; 2]> = polygon
+        .indices
+        .iter()
+        .map(|i| polygon.vertices[*i as usize])
+        .collect();
+    assert_eq!(restored, original);
+
+    let chunks = polygon.chunks(100, 6);
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].vertices.len(), 4);
+    assert_eq!(chunks[1].indices, vec![0, 1, 2, 2, 1, 3]);
+}
+
+#[test]
+fn strip_chunks_test() {
+    let polygon = ExpandedPolygon {
+        vertices: (0..8).map(|i| [i as f32; 2]).collect::<Vec<_>>(),
+        indices: vec![
+            0,
+            1,
+            2,
+            3,
+            PRIMITIVE_RESTART,
+            4,
+            5,
+            6,
+            7,
+            PRIMITIVE_RESTART,
+            2,
+            3,
+            4,
+            5,
+        ],
+        primitive_topology: PrimitiveTopology::TriangleStrip,
+    };
+    let chunks = polygon.chunks(6, 100);
+    assert_eq!(chunks.len(), 2);
+    assert_eq!(chunks[0].indices, vec![0, 1, 2, 3]);
+    assert_eq!(
+        chunks[1].indices,
+        vec![0, 1, 2, 3, PRIMITIVE_RESTART, 4, 5, 0, 1]
+    );
+    assert_eq!(chunks[1].vertices[4], [2.0; 2]);
+    assert!(chunks
+        .iter()
+        .all(|chunk| chunk.primitive_topology == PrimitiveTopology::TriangleStrip));
+}
@@ -0,0 +1,225 @@
+//! The normals and the depths in the camera space are rendered to the offscreen target in
+//! `TextureFormat::Rgba32Float`, and read back as the G-buffer.
+//! # Examples
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use truck_platform::*;
+//! use truck_rendimpl::*;
+//! use truck_rendimpl::gbuffer::*;
+//! use truck_platform::wgpu::*;
+//! let instance = Instance::new(BackendBit::PRIMARY);
+//! let (device, queue, _) =
+//!     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+//! let sc_desc = SwapChainDescriptor {
+//!     usage: TextureUsage::OUTPUT_ATTACHMENT,
+//!     format: TextureFormat::Rgba8UnormSrgb,
+//!     width: 256,
+//!     height: 256,
+//!     present_mode: PresentMode::Mailbox,
+//! };
+//! let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+//! let scene = Scene::new(handler, &Default::default());
+//! let creator = scene.instance_creator();
+//! let mesh = PolygonMesh::new(
+//!     vec![
+//!         Point3::new(-1.0, -1.0, -3.0),
+//!         Point3::new(1.0, -1.0, -3.0),
+//!         Point3::new(0.0, 1.0, -3.0),
+//!     ],
+//!     Vec::new(),
+//!     vec![Vector3::new(0.0, 0.0, 1.0)],
+//!     Faces::from_iter(&[&[(0, None, Some(0)), (1, None, Some(0)), (2, None, Some(0))]]),
+//! );
+//! let polygon: PolygonInstance = creator.create_instance(&mesh, &Default::default());
+//!
+//! let gbuffer = render_gbuffer(scene.device_handler(), scene.descriptor(), &[&polygon]);
+//! assert_eq!(gbuffer.size(), (256, 256));
+//! // the background
+//! assert_eq!(gbuffer.depth(0, 0), None);
+//! // the triangle faces the camera.
+//! let normal = gbuffer.normal(128, 128).unwrap();
+//! assert!(f32::abs(normal[2] - 1.0) < 1.0e-3);
+//! assert!(f32::abs(gbuffer.depth(128, 128).unwrap() - 3.0) < 1.0e-3);
+//!
+//! let normal_image = gbuffer.normal_image();
+//! let depth_image = gbuffer.depth_image();
+//! assert_eq!(normal_image.dimensions(), depth_image.dimensions());
+//! ```
+
+use crate::*;
+use image::{GrayImage, Luma, Rgba, RgbaImage};
+
+/// The normals and the depths in the camera space rendered by [`render_gbuffer`].
+///
+/// The normal is the one in the coordinate of the camera, i.e. the normal facing the camera
+/// is `[0.0, 0.0, 1.0]`, and the depth is the distance from the camera along the direction
+/// of the camera. The pixels of the background have no normals and no depths.
+///
+/// [`render_gbuffer`]: ./fn.render_gbuffer.html
+#[derive(Clone, Debug)]
+pub struct GBuffer {
+    width: u32,
+    height: u32,
+    pixels: Vec<[f32; 4]>,
+}
+
+// the rendering of the normal and the depth of a polygon
+#[derive(Debug)]
+struct NormalDepthInstance(PolygonInstance);
+
+/// Renders the normals and the depths of `polygons` in the camera space by a single call.
+///
+/// The temporary scene shares the device and the queue with `device_handler`, whose size is
+/// the one of the swap chain descriptor of `device_handler`. The camera is the one of
+/// `scene_desc`, and MSAA is disabled since the depths should not be blended.
+pub fn render_gbuffer(
+    device_handler: &DeviceHandler,
+    scene_desc: &SceneDescriptor,
+    polygons: &[&PolygonInstance],
+) -> GBuffer {
+    let handler = offscreen::offscreen_handler(device_handler, TextureFormat::Rgba32Float);
+    let scene_desc = SceneDescriptor {
+        render_settings: RenderSettings {
+            background: Color::TRANSPARENT,
+            ..Default::default()
+        },
+        ..scene_desc.clone()
+    };
+    let mut scene = Scene::new(handler, &scene_desc);
+    polygons.iter().for_each(|polygon| {
+        scene.add_object(&NormalDepthInstance(polygon.clone_instance()));
+    });
+    let sc_desc = scene.sc_desc();
+    let pixels = offscreen::render_to_bytes(&mut scene, 16)
+        .chunks(16)
+        .map(|bytes| {
+            let mut pixel = [0.0; 4];
+            pixel.iter_mut().zip(bytes.chunks(4)).for_each(|(x, b)| {
+                *x = f32::from_ne_bytes([b[0], b[1], b[2], b[3]]);
+            });
+            pixel
+        })
+        .collect();
+    GBuffer {
+        width: sc_desc.width,
+        height: sc_desc.height,
+        pixels,
+    }
+}
+
+/// Returns the default fragment shader module source rendering the normals and the depths.
+///
+/// The GLSL original code is `src/shaders/normal-depth.frag`.
+#[inline(always)]
+pub fn default_fragment_shader() -> ShaderModuleSource<'static> {
+    include_spirv!("shaders/normal-depth.frag.spv")
+}
+
+impl GBuffer {
+    /// Returns the width and the height.
+    #[inline(always)]
+    pub fn size(&self) -> (u32, u32) { (self.width, self.height) }
+    /// Returns the normal at the pixel `(x, y)`, or `None` if the pixel is the background.
+    #[inline(always)]
+    pub fn normal(&self, x: u32, y: u32) -> Option<[f32; 3]> {
+        let pixel = self.pixel(x, y);
+        match pixel[3] > 0.0 {
+            true => Some([pixel[0], pixel[1], pixel[2]]),
+            false => None,
+        }
+    }
+    /// Returns the depth at the pixel `(x, y)`, or `None` if the pixel is the background.
+    #[inline(always)]
+    pub fn depth(&self, x: u32, y: u32) -> Option<f32> {
+        let depth = self.pixel(x, y)[3];
+        match depth > 0.0 {
+            true => Some(depth),
+            false => None,
+        }
+    }
+    /// Returns the raw pixels in the row-major order. The first three components are
+    /// the normal and the last one is the depth, and all of them are zero in the background.
+    #[inline(always)]
+    pub fn pixels(&self) -> &[[f32; 4]] { &self.pixels }
+
+    /// Returns the image of normals whose components are mapped from `[-1, 1]` to `[0, 255]`.
+    /// The background is transparent.
+    pub fn normal_image(&self) -> RgbaImage {
+        RgbaImage::from_fn(self.width, self.height, |x, y| match self.normal(x, y) {
+            Some(normal) => {
+                let f = |a: f32| ((a * 0.5 + 0.5) * 255.0).round().clamp(0.0, 255.0) as u8;
+                Rgba([f(normal[0]), f(normal[1]), f(normal[2]), 255])
+            }
+            None => Rgba([0, 0, 0, 0]),
+        })
+    }
+
+    /// Returns the image of depths, where the nearest pixel is white and the farthest one is
+    /// black. The background is also black, and can be distinguished by [`GBuffer::depth`].
+    ///
+    /// [`GBuffer::depth`]: #method.depth
+    pub fn depth_image(&self) -> GrayImage {
+        let (min, max) = self
+            .pixels
+            .iter()
+            .map(|pixel| pixel[3])
+            .filter(|depth| *depth > 0.0)
+            .fold((f32::INFINITY, 0.0_f32), |(min, max), depth| {
+                (min.min(depth), max.max(depth))
+            });
+        GrayImage::from_fn(self.width, self.height, |x, y| match self.depth(x, y) {
+            Some(depth) if max > min => Luma([((max - depth) / (max - min) * 255.0).round() as u8]),
+            Some(_) => Luma([255]),
+            None => Luma([0]),
+        })
+    }
+
+    #[inline(always)]
+    fn pixel(&self, x: u32, y: u32) -> [f32; 4] { self.pixels[(y * self.width + x) as usize] }
+}
+
+impl Rendered for NormalDepthInstance {
+    derive_render_id!(0);
+    derive_vertex_buffer!(0);
+    #[inline(always)]
+    fn bind_group_layout(&self, handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        handler.cached_bind_group_layout(&[InstanceState::matrix_bgl_entry()])
+    }
+    #[inline(always)]
+    fn bind_group(&self, handler: &DeviceHandler, layout: &BindGroupLayout) -> Arc<BindGroup> {
+        let device = handler.device();
+        Arc::new(bind_group_util::create_bind_group(
+            device,
+            layout,
+            vec![self.0.state.matrix_buffer(device).binding_resource()],
+        ))
+    }
+    #[inline(always)]
+    fn pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let instance = &self.0;
+        let key = PipelineKey {
+            primitive_topology: instance.polygon.primitive_topology,
+            index_format: instance.polygon.index_format,
+            ..instance.state.pipeline_key(
+                "truck-rendimpl/normal-depth-polygon",
+                &instance.polygon.vertex_layout(),
+                handler,
+                sample_count,
+            )
+        };
+        handler.cached_pipeline(&key, || {
+            instance.pipeline_with_shader_module(
+                &instance.shaders.vertex,
+                &instance.shaders.normal_depth_fragment,
+                handler,
+                layout,
+                sample_count,
+            )
+        })
+    }
+}
@@ -0,0 +1,413 @@
+use crate::*;
+
+// the occluders are drawn first, and the hidden edges are drawn before the visible ones.
+const OCCLUDER_DRAW_ORDER: i32 = -2;
+const HIDDEN_LINE_DRAW_ORDER: i32 = -1;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod)]
+struct HiddenLineInfo {
+    color: [f32; 4],
+    dash: [f32; 4],
+}
+
+impl Default for HiddenLineState {
+    #[inline(always)]
+    fn default() -> HiddenLineState {
+        HiddenLineState {
+            color: Vector4::new(0.5, 0.5, 0.5, 1.0),
+            dash_length: 4.0,
+        }
+    }
+}
+
+impl HiddenLineState {
+    #[inline(always)]
+    fn hidden_line_info(&self) -> HiddenLineInfo {
+        HiddenLineInfo {
+            color: self.color.cast().unwrap().into(),
+            dash: [self.dash_length as f32, 0.0, 0.0, 0.0],
+        }
+    }
+
+    /// Creates a `UNIFORM` buffer of the hidden edges.
+    ///
+    /// # Shader Example
+    /// ```glsl
+    /// layout(set = 1, binding = 1) uniform HiddenLine {
+    ///     vec4 hidden_color;
+    ///     vec4 dash; // (the length of the dashes in pixels, 0.0, 0.0, 0.0)
+    /// };
+    /// ```
+    #[inline(always)]
+    pub fn buffer(&self, device: &Device) -> BufferHandler {
+        BufferHandler::from_slice(&[self.hidden_line_info()], device, BufferUsage::UNIFORM)
+    }
+}
+
+// the key of the pipeline drawing only the depths
+fn occluder_pipeline_key(
+    state: &InstanceState,
+    shader: &str,
+    vertex_layout: &str,
+    handler: &DeviceHandler,
+    sample_count: u32,
+) -> PipelineKey {
+    let key = state.pipeline_key(shader, vertex_layout, handler, sample_count);
+    let color_states = key
+        .color_states
+        .iter()
+        .map(|color_state| ColorStateDescriptor {
+            write_mask: ColorWrite::empty(),
+            ..color_state.clone()
+        })
+        .collect();
+    PipelineKey {
+        color_states,
+        ..key
+    }
+}
+
+impl PolygonInstance {
+    /// Creates the occluder of `self`, which shares the mesh data with `self`.
+    #[inline(always)]
+    pub fn occluder_instance(&self) -> OccluderInstance<PolygonInstance> {
+        OccluderInstance {
+            instance: self.clone_instance(),
+        }
+    }
+}
+
+impl ShapeInstance {
+    /// Creates the occluder of `self`, which shares the mesh data with `self`.
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_platform::*;
+    /// use truck_rendimpl::*;
+    /// use truck_platform::wgpu::*;
+    /// # let instance = Instance::new(BackendBit::PRIMARY);
+    /// # let (device, queue, _) =
+    /// #     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// # let sc_desc = SwapChainDescriptor {
+    /// #     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    /// #     format: TextureFormat::Rgba8UnormSrgb,
+    /// #     width: 256,
+    /// #     height: 256,
+    /// #     present_mode: PresentMode::Mailbox,
+    /// # };
+    /// # let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// let mut scene = Scene::new(handler, &Default::default());
+    /// let creator = scene.instance_creator();
+    /// let v = builder::vertex(Point3::new(-0.5, -0.5, -3.0));
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let cube = builder::tsweep(&f, Vector3::unit_z());
+    /// // the invisible faces, the visible edges and the dashed hidden edges
+    /// let shape: ShapeInstance = creator.create_instance(&cube, &Default::default());
+    /// let wireframe: WireFrameInstance = creator.create_instance(&cube, &Default::default());
+    /// let hidden_line = wireframe.hidden_line_instance(Default::default());
+    /// scene.add_object(&shape.occluder_instance());
+    /// scene.add_object(&wireframe);
+    /// scene.add_object(&hidden_line);
+    /// ```
+    #[inline(always)]
+    pub fn occluder_instance(&self) -> OccluderInstance<ShapeInstance> {
+        OccluderInstance {
+            instance: self.clone_instance(),
+        }
+    }
+}
+
+impl<T> OccluderInstance<T> {
+    /// Returns a reference to the original instance, whose instance state configures the
+    /// matrix, the depth bias and the backface culling of the occluder.
+    #[inline(always)]
+    pub fn instance(&self) -> &T { &self.instance }
+    /// Returns the mutable reference to the original instance.
+    #[inline(always)]
+    pub fn instance_mut(&mut self) -> &mut T { &mut self.instance }
+}
+
+impl OccluderInstance<PolygonInstance> {
+    // the duplication sharing the mesh data with `self`, used for re-creating the pipeline
+    #[inline(always)]
+    fn clone_instance(&self) -> Self {
+        OccluderInstance {
+            instance: self.instance.clone_instance(),
+        }
+    }
+}
+
+impl OccluderInstance<ShapeInstance> {
+    // the duplication sharing the mesh data with `self`, used for re-creating the pipeline
+    #[inline(always)]
+    fn clone_instance(&self) -> Self {
+        OccluderInstance {
+            instance: self.instance.clone_instance(),
+        }
+    }
+}
+
+impl Rendered for OccluderInstance<PolygonInstance> {
+    derive_render_id!(instance);
+    impl_pipeline_builder!(clone_instance);
+    derive_vertex_buffer!(instance);
+    derive_bind_group_layout!(instance);
+    derive_bind_group!(instance);
+    derive_picking_mesh!(instance);
+    #[inline(always)]
+    fn pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let instance = &self.instance;
+        let key = PipelineKey {
+            primitive_topology: instance.polygon.primitive_topology,
+            index_format: instance.polygon.index_format,
+            ..occluder_pipeline_key(
+                &instance.state,
+                "truck-rendimpl/occluder-polygon",
+                &instance.polygon.vertex_layout(),
+                handler,
+                sample_count,
+            )
+        };
+        let cull_mode = key.cull_mode;
+        handler.cached_pipeline(&key, || {
+            instance.pipeline_with_write_mask(
+                &instance.shaders.vertex,
+                &instance.shaders.fragment,
+                cull_mode,
+                ColorWrite::empty(),
+                handler,
+                layout,
+                sample_count,
+            )
+        })
+    }
+    #[inline(always)]
+    fn draw_order(&self) -> i32 { OCCLUDER_DRAW_ORDER }
+}
+
+impl Rendered for OccluderInstance<ShapeInstance> {
+    derive_render_id!(instance);
+    impl_pipeline_builder!(clone_instance);
+    derive_vertex_buffer!(instance);
+    derive_bind_group_layout!(instance);
+    derive_bind_group!(instance);
+    derive_picking_mesh!(instance);
+    #[inline(always)]
+    fn pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let instance = &self.instance;
+        let key = PipelineKey {
+            primitive_topology: instance.polygon.primitive_topology,
+            index_format: instance.polygon.index_format,
+            ..occluder_pipeline_key(
+                &instance.state,
+                "truck-rendimpl/occluder-shape",
+                "truck-rendimpl/shape-vertex",
+                handler,
+                sample_count,
+            )
+        };
+        handler.cached_pipeline(&key, || {
+            instance.pipeline_with_write_mask(
+                &instance.shaders.vertex,
+                &instance.shaders.fragment,
+                ColorWrite::empty(),
+                handler,
+                layout,
+                sample_count,
+            )
+        })
+    }
+    #[inline(always)]
+    fn draw_order(&self) -> i32 { OCCLUDER_DRAW_ORDER }
+}
+
+impl WireFrameInstance {
+    /// Creates the instance drawing the hidden edges of `self`, which shares the vertex data
+    /// with `self`.
+    #[inline(always)]
+    pub fn hidden_line_instance(&self, hidden_line: HiddenLineState) -> HiddenLineInstance {
+        HiddenLineInstance {
+            instance: self.clone_instance(),
+            hidden_line,
+        }
+    }
+}
+
+impl HiddenLineInstance {
+    /// Returns a reference to the configures of the hidden edges.
+    #[inline(always)]
+    pub fn hidden_line_state(&self) -> &HiddenLineState { &self.hidden_line }
+    /// Returns the mutable reference to the configures of the hidden edges.
+    #[inline(always)]
+    pub fn hidden_line_state_mut(&mut self) -> &mut HiddenLineState { &mut self.hidden_line }
+    /// Returns a reference to the wireframe state.
+    ///
+    /// The matrix should be the same as the one of the original wireframe.
+    /// The color is ignored.
+    #[inline(always)]
+    pub fn instance_state(&self) -> &WireFrameState { self.instance.instance_state() }
+    /// Returns the mutable reference to the wireframe state.
+    #[inline(always)]
+    pub fn instance_state_mut(&mut self) -> &mut WireFrameState {
+        self.instance.instance_state_mut()
+    }
+    // the duplication sharing the vertex data with `self`, used for re-creating the pipeline
+    #[inline(always)]
+    fn clone_instance(&self) -> HiddenLineInstance {
+        self.instance.hidden_line_instance(self.hidden_line)
+    }
+
+    /// Returns the default vertex shader module source.
+    ///
+    /// The GLSL original code is `src/shaders/hidden-line.vert`.
+    #[inline(always)]
+    pub fn default_vertex_shader() -> ShaderModuleSource<'static> {
+        include_spirv!("shaders/hidden-line.vert.spv")
+    }
+
+    /// Returns the default fragment shader module source.
+    ///
+    /// The GLSL original code is `src/shaders/hidden-line.frag`.
+    #[inline(always)]
+    pub fn default_fragment_shader() -> ShaderModuleSource<'static> {
+        include_spirv!("shaders/hidden-line.frag.spv")
+    }
+
+    fn create_pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let (device, sc_desc) = (handler.device(), handler.sc_desc());
+        let depth_bias = self.instance.state.depth_bias.oriented(handler);
+        let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
+            layout: Some(layout),
+            vertex_stage: ProgrammableStageDescriptor {
+                module: &self.instance.shaders.hidden_vertex,
+                entry_point: "main",
+            },
+            fragment_stage: Some(ProgrammableStageDescriptor {
+                module: &self.instance.shaders.hidden_fragment,
+                entry_point: "main",
+            }),
+            rasterization_state: Some(RasterizationStateDescriptor {
+                front_face: FrontFace::Ccw,
+                cull_mode: CullMode::None,
+                depth_bias: depth_bias.constant,
+                depth_bias_slope_scale: depth_bias.slope_scale as f32,
+                depth_bias_clamp: depth_bias.clamp as f32,
+                clamp_depth: false,
+            }),
+            primitive_topology: PrimitiveTopology::LineList,
+            color_states: &[ColorStateDescriptor {
+                format: sc_desc.format,
+                color_blend: BlendDescriptor::REPLACE,
+                alpha_blend: BlendDescriptor::REPLACE,
+                write_mask: ColorWrite::ALL,
+            }],
+            depth_stencil_state: Some(Self::depth_stencil_state(handler)),
+            vertex_state: VertexStateDescriptor {
+                index_format: IndexFormat::Uint32,
+                vertex_buffers: &[VertexBufferDescriptor {
+                    stride: std::mem::size_of::<[f32; 3]>() as BufferAddress,
+                    step_mode: InputStepMode::Vertex,
+                    attributes: &[VertexAttributeDescriptor {
+                        format: VertexFormat::Float3,
+                        offset: 0,
+                        shader_location: 0,
+                    }],
+                }],
+            },
+            sample_count,
+            sample_mask: !0,
+            alpha_to_coverage_enabled: false,
+            label: None,
+        });
+        Arc::new(pipeline)
+    }
+
+    // Only the fragments behind the depths drawn in advance are drawn, and the depths are kept.
+    #[inline(always)]
+    fn depth_stencil_state(handler: &DeviceHandler) -> DepthStencilStateDescriptor {
+        DepthStencilStateDescriptor {
+            format: TextureFormat::Depth32Float,
+            depth_write_enabled: false,
+            depth_compare: handler.depth_compare(wgpu::CompareFunction::Greater),
+            stencil: StencilStateDescriptor::default(),
+        }
+    }
+}
+
+impl Rendered for HiddenLineInstance {
+    derive_render_id!(instance);
+    impl_pipeline_builder!(clone_instance);
+    // the hidden edges are always drawn by the lines of one pixel width.
+    #[inline(always)]
+    fn vertex_buffer(&self, _: &DeviceHandler) -> (Arc<BufferHandler>, Option<Arc<BufferHandler>>) {
+        let instance = &self.instance;
+        (instance.vertices.clone(), Some(instance.strips.clone()))
+    }
+    derive_bind_group_layout!(instance);
+    fn bind_group(&self, handler: &DeviceHandler, layout: &BindGroupLayout) -> Arc<BindGroup> {
+        let device = handler.device();
+        let state = self.instance.instance_state();
+        let matrix_data: [[f32; 4]; 4] = state.world_matrix().cast::<f32>().unwrap().into();
+        let matrix_buffer = BufferHandler::from_slice(&matrix_data, device, BufferUsage::UNIFORM);
+        let hidden_line = HiddenLineState {
+            color: state.color_space.to_linear(self.hidden_line.color),
+            ..self.hidden_line
+        };
+        Arc::new(bind_group_util::create_bind_group(
+            device,
+            layout,
+            vec![
+                matrix_buffer.binding_resource(),
+                hidden_line.buffer(device).binding_resource(),
+            ],
+        ))
+    }
+    fn pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let depth_bias = self.instance.state.depth_bias.oriented(handler);
+        let key = PipelineKey {
+            shader: "truck-rendimpl/hidden-line".to_string(),
+            vertex_layout: "truck-rendimpl/wireframe-vertex".to_string(),
+            primitive_topology: PrimitiveTopology::LineList,
+            index_format: IndexFormat::Uint32,
+            cull_mode: CullMode::None,
+            depth_bias: (
+                depth_bias.constant,
+                (depth_bias.slope_scale as f32).to_bits(),
+                (depth_bias.clamp as f32).to_bits(),
+            ),
+            color_states: vec![ColorStateDescriptor {
+                format: handler.sc_desc().format,
+                color_blend: BlendDescriptor::REPLACE,
+                alpha_blend: BlendDescriptor::REPLACE,
+                write_mask: ColorWrite::ALL,
+            }],
+            depth_stencil_state: Some(Self::depth_stencil_state(handler)),
+            sample_count,
+        };
+        handler.cached_pipeline(&key, || self.create_pipeline(handler, layout, sample_count))
+    }
+    #[inline(always)]
+    fn draw_order(&self) -> i32 { HIDDEN_LINE_DRAW_ORDER }
+}
@@ -0,0 +1,191 @@
+//! The labels are rendered to the id buffer in `TextureFormat::R32Uint`, and read back as
+//! the labeled image. The label `0` is reserved for the background.
+//! # Examples
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use truck_platform::*;
+//! use truck_rendimpl::*;
+//! use truck_rendimpl::id_buffer::*;
+//! use truck_platform::wgpu::*;
+//! let instance = Instance::new(BackendBit::PRIMARY);
+//! let (device, queue, _) =
+//!     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+//! let sc_desc = SwapChainDescriptor {
+//!     usage: TextureUsage::OUTPUT_ATTACHMENT,
+//!     format: TextureFormat::Rgba8UnormSrgb,
+//!     width: 256,
+//!     height: 256,
+//!     present_mode: PresentMode::Mailbox,
+//! };
+//! let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+//! let scene = Scene::new(handler, &Default::default());
+//! let creator = scene.instance_creator();
+//! let mesh = PolygonMesh::new(
+//!     vec![
+//!         Point3::new(-1.0, -1.0, -3.0),
+//!         Point3::new(1.0, -1.0, -3.0),
+//!         Point3::new(0.0, 1.0, -3.0),
+//!     ],
+//!     Vec::new(),
+//!     Vec::new(),
+//!     Faces::from_iter(&[&[0, 1, 2]]),
+//! );
+//! let polygon: PolygonInstance = creator.create_instance(&mesh, &Default::default());
+//!
+//! // The camera is shared with the scene for the display.
+//! let mut id_scene = id_scene(scene.device_handler(), scene.descriptor());
+//! id_scene.add_object(&polygon.id_instance(1));
+//! let labels = render_id_buffer(&mut id_scene);
+//! assert_eq!(labels.len(), 256 * 256);
+//! assert_eq!(labels[0], 0);
+//! assert_eq!(labels[128 * 256 + 128], 1);
+//! ```
+
+use crate::*;
+
+/// Creates the scene rendering the id instances to the id buffer in `TextureFormat::R32Uint`.
+///
+/// The new scene shares the device and the queue with `device_handler`, and has its own
+/// swap chain descriptor of the same size. The camera and the lights are the ones of
+/// `scene_desc`, and MSAA is disabled since the labels cannot be resolved.
+pub fn id_scene(device_handler: &DeviceHandler, scene_desc: &SceneDescriptor) -> Scene {
+    let handler = offscreen::offscreen_handler(device_handler, TextureFormat::R32Uint);
+    let scene_desc = SceneDescriptor {
+        render_settings: RenderSettings::default(),
+        ..scene_desc.clone()
+    };
+    Scene::new(handler, &scene_desc)
+}
+
+/// Renders the id scene to the offscreen target, and reads back the labels.
+///
+/// Returns the labels of the pixels in the row-major order, whose size is the one of
+/// the swap chain descriptor of the scene.
+/// # Panics
+/// Panic occurs if the format of the swap chain descriptor is not `TextureFormat::R32Uint`.
+pub fn render_id_buffer(scene: &mut Scene) -> Vec<u32> {
+    assert_eq!(
+        scene.sc_desc().format,
+        TextureFormat::R32Uint,
+        "The scene is not the one for the id buffer."
+    );
+    offscreen::render_to_bytes(scene, 4)
+        .chunks(4)
+        .map(|bytes| u32::from_ne_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+        .collect()
+}
+
+impl PolygonInstance {
+    /// Creates the id instance rendering `label`, which shares the mesh data with `self`.
+    /// The label should not be `0`, which is reserved for the background.
+    #[inline(always)]
+    pub fn id_instance(&self, label: u32) -> IdInstance {
+        IdInstance {
+            instance: self.clone_instance(),
+            label,
+        }
+    }
+}
+
+impl IdInstance {
+    /// Returns the label.
+    #[inline(always)]
+    pub fn label(&self) -> u32 { self.label }
+    /// Sets the label.
+    #[inline(always)]
+    pub fn set_label(&mut self, label: u32) { self.label = label }
+    /// Returns a reference to the instance descriptor.
+    ///
+    /// The matrix, the depth bias and the backface culling are used for the rendering,
+    /// and the material and the texture are ignored.
+    #[inline(always)]
+    pub fn instance_state(&self) -> &InstanceState { self.instance.instance_state() }
+    /// Returns the mutable reference to the instance descriptor.
+    #[inline(always)]
+    pub fn instance_state_mut(&mut self) -> &mut InstanceState {
+        self.instance.instance_state_mut()
+    }
+    // the duplication sharing the mesh data with `self`, used for re-creating the pipeline
+    #[inline(always)]
+    fn clone_instance(&self) -> IdInstance {
+        IdInstance {
+            instance: self.instance.clone_instance(),
+            label: self.label,
+        }
+    }
+
+    /// Returns the default fragment shader module source.
+    ///
+    /// The GLSL original code is `src/shaders/id.frag`.
+    #[inline(always)]
+    pub fn default_fragment_shader() -> ShaderModuleSource<'static> {
+        include_spirv!("shaders/id.frag.spv")
+    }
+
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn label_bgl_entry() -> PreBindGroupLayoutEntry {
+        PreBindGroupLayoutEntry {
+            visibility: ShaderStage::FRAGMENT,
+            ty: BindingType::UniformBuffer {
+                dynamic: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }
+    }
+}
+
+impl Rendered for IdInstance {
+    derive_render_id!(instance);
+    impl_pipeline_builder!(clone_instance);
+    derive_vertex_buffer!(instance);
+    #[inline(always)]
+    fn bind_group_layout(&self, handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        handler.cached_bind_group_layout(&[
+            InstanceState::matrix_bgl_entry(),
+            IdInstance::label_bgl_entry(),
+        ])
+    }
+    #[inline(always)]
+    fn bind_group(&self, handler: &DeviceHandler, layout: &BindGroupLayout) -> Arc<BindGroup> {
+        let device = handler.device();
+        let label = [self.label, 0, 0, 0];
+        Arc::new(bind_group_util::create_bind_group(
+            device,
+            layout,
+            vec![
+                self.instance.state.matrix_buffer(device).binding_resource(),
+                BufferHandler::from_slice(&label, device, BufferUsage::UNIFORM).binding_resource(),
+            ],
+        ))
+    }
+    #[inline(always)]
+    fn pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let instance = &self.instance;
+        let key = PipelineKey {
+            primitive_topology: instance.polygon.primitive_topology,
+            index_format: instance.polygon.index_format,
+            ..instance.state.pipeline_key(
+                "truck-rendimpl/id-polygon",
+                &instance.polygon.vertex_layout(),
+                handler,
+                sample_count,
+            )
+        };
+        handler.cached_pipeline(&key, || {
+            instance.pipeline_with_shader_module(
+                &instance.shaders.vertex,
+                &instance.shaders.id_fragment,
+                handler,
+                layout,
+                sample_count,
+            )
+        })
+    }
+}
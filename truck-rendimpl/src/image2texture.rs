@@ -1,42 +1,102 @@
 use super::*;
 use image::*;
 
+/// Configures of creating textures from images.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TextureConfig {
+    /// If `true`, the format of the texture is `Rgba8UnormSrgb` and the sampled colors are
+    /// converted to the linear space. Otherwise, the format is `Rgba8Unorm`.
+    /// Default is `false`.  
+    /// The images of colors, such as photographs and the base color maps of glTF,
+    /// are usually in sRGB.
+    pub srgb: bool,
+    /// If `true`, the mipmaps are generated by downsampling the image by the triangle filter,
+    /// until the size of the level becomes 1x1. Otherwise, the texture has only the base level.
+    /// Default is `true`.
+    pub generate_mipmaps: bool,
+}
+
+impl Default for TextureConfig {
+    #[inline(always)]
+    fn default() -> TextureConfig {
+        TextureConfig {
+            srgb: false,
+            generate_mipmaps: true,
+        }
+    }
+}
+
 /// Utility for creating `Texture` from `DynamicImage`
+///
+/// The texture is created by the default `TextureConfig`: the format is `Rgba8Unorm`,
+/// and the full chain of mipmaps is generated.
 #[inline(always)]
 pub fn image2texture(device_handler: &DeviceHandler, image: &DynamicImage) -> Texture {
-    let buffer = image.to_rgba8();
-    imagebuffer2texture(device_handler, &buffer, TextureFormat::Rgba8Unorm)
+    image2texture_with_config(device_handler, image, &Default::default())
+}
+
+/// Utility for creating `Texture` from `DynamicImage` with the configures.
+pub fn image2texture_with_config(
+    device_handler: &DeviceHandler,
+    image: &DynamicImage,
+    config: &TextureConfig,
+) -> Texture {
+    let (width, height) = image.dimensions();
+    let mip_level_count = match config.generate_mipmaps {
+        true => mip_level_count(width, height),
+        false => 1,
+    };
+    let format = match config.srgb {
+        true => TextureFormat::Rgba8UnormSrgb,
+        false => TextureFormat::Rgba8Unorm,
+    };
+    let texture = device_handler.device().create_texture(&TextureDescriptor {
+        label: None,
+        size: Extent3d {
+            width,
+            height,
+            depth: 1,
+        },
+        mip_level_count,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format,
+        usage: TextureUsage::SAMPLED | TextureUsage::COPY_DST,
+    });
+    write_level(device_handler, &texture, 0, &image.to_rgba8());
+    for level in 1..mip_level_count {
+        let (width, height) = (u32::max(width >> level, 1), u32::max(height >> level, 1));
+        let buffer = image.resize_exact(width, height, imageops::FilterType::Triangle);
+        write_level(device_handler, &texture, level, &buffer.to_rgba8());
+    }
+    texture
+}
+
+/// Returns the number of the mipmap levels of the full chain.
+#[inline(always)]
+fn mip_level_count(width: u32, height: u32) -> u32 {
+    32 - u32::max(u32::max(width, height), 1).leading_zeros()
 }
 
-fn imagebuffer2texture<P, Container>(
+fn write_level<P, Container>(
     device_handler: &DeviceHandler,
+    texture: &Texture,
+    mip_level: u32,
     image_buffer: &ImageBuffer<P, Container>,
-    format: TextureFormat,
-) -> Texture
-where
+) where
     P: Pixel + 'static,
     P::Subpixel: Pod + Zeroable + 'static,
     Container: std::ops::Deref<Target = [P::Subpixel]>,
 {
-    let (device, queue) = (device_handler.device(), device_handler.queue());
     let size = Extent3d {
         width: image_buffer.width(),
         height: image_buffer.height(),
         depth: 1,
     };
-    let texture = device.create_texture(&TextureDescriptor {
-        label: None,
-        size,
-        mip_level_count: 1,
-        sample_count: 1,
-        dimension: TextureDimension::D2,
-        format,
-        usage: TextureUsage::SAMPLED | TextureUsage::COPY_DST,
-    });
-    queue.write_texture(
+    device_handler.queue().write_texture(
         TextureCopyView {
-            texture: &texture,
-            mip_level: 0,
+            texture,
+            mip_level,
             origin: Origin3d::ZERO,
         },
         bytemuck::cast_slice(&image_buffer),
@@ -47,5 +107,12 @@ where
         },
         size,
     );
-    texture
+}
+
+#[test]
+fn mip_level_count_test() {
+    assert_eq!(mip_level_count(1, 1), 1);
+    assert_eq!(mip_level_count(256, 256), 9);
+    assert_eq!(mip_level_count(300, 20), 9);
+    assert_eq!(mip_level_count(1, 1024), 11);
 }
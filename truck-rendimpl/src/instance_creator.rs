@@ -8,6 +8,15 @@ impl PolygonShaders {
             fragment: device.create_shader_module(PolygonInstance::default_fragment_shader()),
             tex_fragment: device
                 .create_shader_module(PolygonInstance::default_textured_fragment_shader()),
+            id_fragment: device.create_shader_module(IdInstance::default_fragment_shader()),
+            normal_depth_fragment: device.create_shader_module(gbuffer::default_fragment_shader()),
+            outline_vertex: device.create_shader_module(OutlineInstance::default_vertex_shader()),
+            outline_fragment: device
+                .create_shader_module(OutlineInstance::default_fragment_shader()),
+            toon_fragment: device.create_shader_module(ToonInstance::default_fragment_shader()),
+            skinned_vertex: device.create_shader_module(SkinnedInstance::default_vertex_shader()),
+            skinned_tex_vertex: device
+                .create_shader_module(SkinnedInstance::default_textured_vertex_shader()),
         }
     }
 }
@@ -29,7 +38,13 @@ impl WireShaders {
     fn new(device: &Device) -> Self {
         Self {
             vertex: device.create_shader_module(include_spirv!("shaders/line.vert.spv")),
+            wide_vertex: device.create_shader_module(include_spirv!("shaders/wide-line.vert.spv")),
             fragment: device.create_shader_module(include_spirv!("shaders/line.frag.spv")),
+            sdf_vertex: device.create_shader_module(include_spirv!("shaders/sdf-line.vert.spv")),
+            sdf_fragment: device.create_shader_module(include_spirv!("shaders/sdf-line.frag.spv")),
+            hidden_vertex: device.create_shader_module(HiddenLineInstance::default_vertex_shader()),
+            hidden_fragment: device
+                .create_shader_module(HiddenLineInstance::default_fragment_shader()),
         }
     }
 }
@@ -66,9 +81,68 @@ impl InstanceCreator {
     ) -> Instance {
         object.into_instance(self, desc)
     }
+    /// Creates the wire frame of the edges of `Shell` or `Solid`.
+    ///
+    /// Each edge curve is divided into a polyline with `desc.polyline_precision`,
+    /// and drawn with the color and the line width of `desc.wireframe_state`.
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_platform::*;
+    /// use truck_rendimpl::*;
+    /// use truck_platform::wgpu::*;
+    /// # let instance = Instance::new(BackendBit::PRIMARY);
+    /// # let (device, queue, _) =
+    /// #     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// # let sc_desc = SwapChainDescriptor {
+    /// #     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    /// #     format: TextureFormat::Rgba8UnormSrgb,
+    /// #     width: 256,
+    /// #     height: 256,
+    /// #     present_mode: PresentMode::Mailbox,
+    /// # };
+    /// # let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// let mut scene = Scene::new(handler, &Default::default());
+    /// let creator = scene.instance_creator();
+    /// let v = builder::vertex(Point3::new(-0.5, -0.5, -3.0));
+    /// let e = builder::tsweep(&v, Vector3::unit_x());
+    /// let f = builder::tsweep(&e, Vector3::unit_y());
+    /// let cube = builder::tsweep(&f, Vector3::unit_z());
+    /// // the black edges drawn over the shaded faces
+    /// let shape: ShapeInstance = creator.create_instance(&cube, &Default::default());
+    /// let desc = ShapeWireFrameInstanceDescriptor {
+    ///     wireframe_state: WireFrameState {
+    ///         color: Vector4::new(0.0, 0.0, 0.0, 1.0),
+    ///         line_width: 2.0,
+    ///         ..Default::default()
+    ///     },
+    ///     ..Default::default()
+    /// };
+    /// let wireframe = creator.create_wire_frame(&cube, &desc);
+    /// scene.add_object(&shape);
+    /// scene.add_object(&wireframe);
+    /// ```
+    #[inline(always)]
+    pub fn create_wire_frame<T>(&self, object: &T, desc: &T::Descriptor) -> WireFrameInstance
+    where T: IntoInstance<WireFrameInstance, Descriptor = ShapeWireFrameInstanceDescriptor> {
+        object.into_instance(self, desc)
+    }
     /// Creates `Texture` for attaching faces.
     #[inline(always)]
     pub fn create_texture(&self, image: &DynamicImage) -> Arc<Texture> {
         Arc::new(image2texture::image2texture(&self.handler, image))
     }
+    /// Creates `Texture` for attaching faces with the configures.
+    #[inline(always)]
+    pub fn create_texture_with_config(
+        &self,
+        image: &DynamicImage,
+        config: &image2texture::TextureConfig,
+    ) -> Arc<Texture> {
+        Arc::new(image2texture::image2texture_with_config(
+            &self.handler,
+            image,
+            config,
+        ))
+    }
 }
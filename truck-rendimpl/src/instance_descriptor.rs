@@ -53,6 +53,98 @@ impl Material {
     }
 }
 
+impl From<Material> for gltf::Material {
+    /// Maps the material to the metallic-roughness model of glTF. The ratio of the specular
+    /// is mapped to the metalness, since both of them move the albedo from the diffuse color
+    /// to the specular one. The ratio of the ambient is dropped.
+    /// # Examples
+    /// ```
+    /// use truck_rendimpl::*;
+    /// let material = Material {
+    ///     albedo: Vector4::new(0.8, 0.2, 0.2, 1.0),
+    ///     roughness: 0.3,
+    ///     reflectance: 0.6,
+    ///     ambient_ratio: 0.02,
+    /// };
+    /// let gltf_material = gltf::Material::from(material);
+    /// assert_eq!(gltf_material.base_color, material.albedo);
+    /// assert_eq!(gltf_material.metallic, 0.6);
+    /// assert_eq!(gltf_material.roughness, 0.3);
+    /// ```
+    #[inline(always)]
+    fn from(material: Material) -> gltf::Material {
+        gltf::Material {
+            base_color: material.albedo,
+            metallic: material.reflectance,
+            roughness: material.roughness,
+            double_sided: false,
+        }
+    }
+}
+
+impl Default for ColorSpace {
+    #[inline(always)]
+    fn default() -> ColorSpace { ColorSpace::Linear }
+}
+
+impl ColorSpace {
+    /// Converts the rgba color in `self` to the linear space.
+    /// # Examples
+    /// ```
+    /// use truck_rendimpl::*;
+    /// let color = Vector4::new(0.5, 1.0, 0.0, 0.5);
+    /// assert_eq!(ColorSpace::Linear.to_linear(color), color);
+    /// let linear = ColorSpace::Srgb.to_linear(color);
+    /// assert!(f64::abs(linear[0] - 0.21404) < 1.0e-5);
+    /// assert!(f64::abs(linear[1] - 1.0) < 1.0e-5);
+    /// assert_eq!(linear[2], 0.0);
+    /// assert_eq!(linear[3], 0.5);
+    /// ```
+    pub fn to_linear(self, color: Vector4) -> Vector4 {
+        let decode = |c: f64| match c <= 0.04045 {
+            true => c / 12.92,
+            false => f64::powf((c + 0.055) / 1.055, 2.4),
+        };
+        match self {
+            ColorSpace::Linear => color,
+            ColorSpace::Srgb => Vector4::new(
+                decode(color[0]),
+                decode(color[1]),
+                decode(color[2]),
+                color[3],
+            ),
+        }
+    }
+}
+
+impl Default for TextureSampler {
+    #[inline(always)]
+    fn default() -> TextureSampler {
+        TextureSampler {
+            address_mode_u: AddressMode::ClampToEdge,
+            address_mode_v: AddressMode::ClampToEdge,
+            mag_filter: FilterMode::Linear,
+            min_filter: FilterMode::Linear,
+            mipmap_filter: FilterMode::Linear,
+            anisotropy_clamp: None,
+        }
+    }
+}
+
+impl DepthBias {
+    // the bias in the depths of `handler`, turned over if the depths are reversed
+    pub(crate) fn oriented(&self, handler: &DeviceHandler) -> DepthBias {
+        match handler.reversed_z() {
+            true => DepthBias {
+                constant: -self.constant,
+                slope_scale: -self.slope_scale,
+                clamp: -self.clamp,
+            },
+            false => *self,
+        }
+    }
+}
+
 impl Default for InstanceState {
     #[inline(always)]
     fn default() -> InstanceState {
@@ -60,13 +152,43 @@ impl Default for InstanceState {
             matrix: Matrix4::identity(),
             material: Default::default(),
             texture: None,
+            texture_sampler: Default::default(),
+            color_space: ColorSpace::Linear,
+            depth_bias: Default::default(),
             backface_culling: true,
+            parent: None,
         }
     }
 }
 
 impl InstanceState {
-    /// Creates a `UNIFORM` buffer of instance matrix.
+    /// Returns the matrix in the world coordinate, i.e. the product of the world matrix of
+    /// the parent node and `matrix`.
+    /// # Examples
+    /// ```
+    /// use truck_rendimpl::*;
+    /// let group = TransformNode::new(Matrix4::from_translation(Vector3::unit_x()));
+    /// let state = InstanceState {
+    ///     matrix: Matrix4::from_scale(2.0),
+    ///     parent: Some(group.clone()),
+    ///     ..Default::default()
+    /// };
+    /// let p = state.world_matrix().transform_point(Point3::new(1.0, 0.0, 0.0));
+    /// assert_eq!(p, Point3::new(3.0, 0.0, 0.0));
+    /// // moving the group moves the instance.
+    /// group.set_matrix(Matrix4::from_translation(Vector3::unit_y()));
+    /// let p = state.world_matrix().transform_point(Point3::new(1.0, 0.0, 0.0));
+    /// assert_eq!(p, Point3::new(2.0, 1.0, 0.0));
+    /// ```
+    #[inline(always)]
+    pub fn world_matrix(&self) -> Matrix4 {
+        match &self.parent {
+            Some(parent) => parent.world_matrix() * self.matrix,
+            None => self.matrix,
+        }
+    }
+
+    /// Creates a `UNIFORM` buffer of instance matrix in the world coordinate.
     ///
     /// The bind group provided by the instances holds this uniform buffer.
     /// # Shader Examples
@@ -77,7 +199,7 @@ impl InstanceState {
     /// ```
     #[inline(always)]
     pub fn matrix_buffer(&self, device: &Device) -> BufferHandler {
-        let matrix_data: [[f32; 4]; 4] = self.matrix.cast::<f32>().unwrap().into();
+        let matrix_data: [[f32; 4]; 4] = self.world_matrix().cast::<f32>().unwrap().into();
         BufferHandler::from_slice(&matrix_data, device, BufferUsage::UNIFORM)
     }
 
@@ -106,14 +228,23 @@ impl InstanceState {
     ///     float ambient_ratio;
     /// };
     /// ```
+    ///
+    /// The albedo is converted to the linear space by `color_space`.
     #[inline(always)]
-    pub fn material_buffer(&self, device: &Device) -> BufferHandler { self.material.buffer(device) }
+    pub fn material_buffer(&self, device: &Device) -> BufferHandler {
+        let material = Material {
+            albedo: self.color_space.to_linear(self.material.albedo),
+            ..self.material
+        };
+        material.buffer(device)
+    }
 
     #[doc(hidden)]
     #[inline(always)]
     pub fn material_bgl_entry() -> PreBindGroupLayoutEntry { Material::bgl_entry() }
 
     /// Creates texture view and sampler of the instance's texture image.
+    /// The sampler is configured by `texture_sampler`.
     ///
     /// The bind group provided by the instances holds this uniform buffer.
     /// # Shader Examples
@@ -127,17 +258,18 @@ impl InstanceState {
     ) -> (TextureView, Sampler) {
         let texture = self.texture.as_ref().unwrap();
         let view = texture.create_view(&Default::default());
+        let config = &self.texture_sampler;
         let sampler = device.create_sampler(&SamplerDescriptor {
-            address_mode_u: AddressMode::ClampToEdge,
-            address_mode_v: AddressMode::ClampToEdge,
+            address_mode_u: config.address_mode_u,
+            address_mode_v: config.address_mode_v,
             address_mode_w: AddressMode::ClampToEdge,
-            mag_filter: FilterMode::Linear,
-            min_filter: FilterMode::Nearest,
-            mipmap_filter: FilterMode::Nearest,
+            mag_filter: config.mag_filter,
+            min_filter: config.min_filter,
+            mipmap_filter: config.mipmap_filter,
             lod_min_clamp: -100.0,
             lod_max_clamp: 100.0,
             compare: None,
-            anisotropy_clamp: None,
+            anisotropy_clamp: config.anisotropy_clamp,
             label: None,
         });
         (view, sampler)
@@ -166,4 +298,45 @@ impl InstanceState {
             count: None,
         }
     }
+
+    /// Returns the key of the pipeline of the standard shaders in the cache.
+    /// The primitive topology and the index format are the ones of the triangle lists
+    /// of 32-bit indices, which should be overwritten by the ones of the polygon.
+    pub(crate) fn pipeline_key(
+        &self,
+        shader: &str,
+        vertex_layout: &str,
+        device_handler: &DeviceHandler,
+        sample_count: u32,
+    ) -> PipelineKey {
+        let depth_bias = self.depth_bias.oriented(device_handler);
+        PipelineKey {
+            shader: shader.to_string(),
+            vertex_layout: vertex_layout.to_string(),
+            primitive_topology: PrimitiveTopology::TriangleList,
+            index_format: IndexFormat::Uint32,
+            cull_mode: match self.backface_culling {
+                true => CullMode::Back,
+                false => CullMode::None,
+            },
+            depth_bias: (
+                depth_bias.constant,
+                (depth_bias.slope_scale as f32).to_bits(),
+                (depth_bias.clamp as f32).to_bits(),
+            ),
+            color_states: vec![ColorStateDescriptor {
+                format: device_handler.sc_desc().format,
+                color_blend: BlendDescriptor::REPLACE,
+                alpha_blend: BlendDescriptor::REPLACE,
+                write_mask: ColorWrite::ALL,
+            }],
+            depth_stencil_state: Some(DepthStencilStateDescriptor {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: device_handler.depth_compare(wgpu::CompareFunction::Less),
+                stencil: StencilStateDescriptor::default(),
+            }),
+            sample_count,
+        }
+    }
 }
@@ -16,10 +16,15 @@ extern crate truck_platform;
 extern crate truck_polymesh;
 use bytemuck::{Pod, Zeroable};
 use image::DynamicImage;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use truck_platform::{wgpu::*, *};
 
 const SURFACE_MESHING_TRIALS: usize = 100;
+// the maximum numbers of the vertices and the indices drawn by a draw call
+const MAX_CHUNK_VERTICES: usize = 1 << 21;
+const MAX_CHUNK_INDICES: usize = 3 << 21;
+// the primitive restart value separating the triangle strips in `ExpandedPolygon`
+const PRIMITIVE_RESTART: u32 = u32::MAX;
 
 /// Re-exports `truck_modeling`.
 pub mod modeling {
@@ -58,8 +63,93 @@ pub struct InstanceState {
     pub material: Material,
     /// texture of instance
     pub texture: Option<Arc<Texture>>,
+    /// sampler configures of the texture
+    pub texture_sampler: TextureSampler,
+    /// color space of the albedo of the material. Default is `ColorSpace::Linear`.
+    pub color_space: ColorSpace,
+    /// depth bias of the pipeline. Default is no bias.
+    pub depth_bias: DepthBias,
     /// If this parameter is true, the backface culling will be activated.
     pub backface_culling: bool,
+    /// the parent node of the transform hierarchy. Default is `None`.
+    ///
+    /// If there is the parent, `matrix` is the local matrix in the coordinate of the parent.
+    pub parent: Option<TransformNode>,
+}
+
+/// Node of the transform hierarchy, e.g. an assembly group.
+///
+/// Moving a node moves all the instances and the nodes referencing it as the parent, without
+/// touching the matrices of them. The world matrices are cached in the nodes, and recomputed
+/// only for the nodes whose ancestors are modified when the bind groups are created, i.e. by
+/// `Scene::update_bind_group`. The clone of a node is the handle of the same node.
+#[derive(Clone, Debug)]
+pub struct TransformNode(Arc<Mutex<TransformNodeData>>);
+
+#[derive(Debug)]
+struct TransformNodeData {
+    matrix: Matrix4,
+    parent: Option<TransformNode>,
+    revision: u64,
+    // the latest revision of the ancestors and the world matrix at the revision
+    cache: Option<(u64, Matrix4)>,
+}
+
+/// Color space of the colors of instances.
+///
+/// The shaders compute the colors in the linear space, and the outputs are encoded to sRGB
+/// by the hardware only if the format of the swap chain or the target texture is `*Srgb`,
+/// e.g. `TextureFormat::Bgra8UnormSrgb`. The sRGB colors, such as the ones picked by
+/// color pickers, should be converted to the linear space by `ColorSpace::Srgb`.
+/// For the textures, the color space is selected by the format at the creation.
+/// cf. [`image2texture::TextureConfig`](./image2texture/struct.TextureConfig.html)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ColorSpace {
+    /// The colors are in the linear space and sent to the shaders as they are.
+    Linear,
+    /// The rgb components are in the sRGB space and converted to the linear space on upload.
+    /// The alpha component is linear.
+    Srgb,
+}
+
+/// Configures of the sampler of textures.
+///
+/// The default is the trilinear filtering with the clamped edges.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextureSampler {
+    /// address mode of the u-coordinate. Default is `AddressMode::ClampToEdge`.
+    pub address_mode_u: AddressMode,
+    /// address mode of the v-coordinate. Default is `AddressMode::ClampToEdge`.
+    pub address_mode_v: AddressMode,
+    /// filter for magnification. Default is `FilterMode::Linear`.
+    pub mag_filter: FilterMode,
+    /// filter for minification. Default is `FilterMode::Linear`.
+    pub min_filter: FilterMode,
+    /// filter between the mipmap levels. Default is `FilterMode::Linear`.
+    pub mipmap_filter: FilterMode,
+    /// maximal anisotropy of the anisotropic filtering: 1, 2, 4, 8 or 16.
+    /// Default is `None`, the anisotropic filtering is disabled.  
+    /// The anisotropic filtering is available only if the adapter supports it.
+    pub anisotropy_clamp: Option<std::num::NonZeroU8>,
+}
+
+/// Depth bias of pipelines, the polygon offset for rendering the coincident geometries
+/// without z-fighting.
+///
+/// The bias `constant * r + slope_scale * m` is added to the depth of each fragment,
+/// where `r` is the minimum resolvable difference of the depth buffer and `m` is the maximum
+/// slope of the depth of the polygon. The result is clamped by `clamp` if it is not zero.
+/// Since the depth test passes the nearer fragments, the positive bias pushes the polygons
+/// away from the camera, also in the scenes with the reversed depths. For example, the shaded faces with the bias `constant: 2` and
+/// `slope_scale: 2.0` are drawn behind the wireframes of their boundaries.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DepthBias {
+    /// constant bias in the unit of the minimum resolvable difference. Default is 0.
+    pub constant: i32,
+    /// bias scaled by the slope of the polygon. Default is 0.0.
+    pub slope_scale: f64,
+    /// the maximum absolute value of the bias. Default is 0.0, no clamp.
+    pub clamp: f64,
 }
 
 /// Configures of `WireFrameInstance`.
@@ -69,6 +159,22 @@ pub struct WireFrameState {
     pub matrix: Matrix4,
     /// color of instance
     pub color: Vector4,
+    /// color space of the color. Default is `ColorSpace::Linear`.
+    pub color_space: ColorSpace,
+    /// depth bias of the pipeline. Default is no bias.
+    pub depth_bias: DepthBias,
+    /// the parent node of the transform hierarchy. Default is `None`.
+    pub parent: Option<TransformNode>,
+    /// the width of the lines in pixels. Default is `1.0`.
+    ///
+    /// If the width is greater than `1.0`, each segment is drawn as a screen-space quad.
+    pub line_width: f64,
+    /// whether the lines are antialiased analytically or not. Default is `false`.
+    ///
+    /// If `true`, each segment of any width is drawn as a screen-space strip whose coverage
+    /// is given by the distance from each pixel to the segment, so the edges are smooth at
+    /// any zoom without the multisampling. The lines are alpha blended.
+    pub antialiased: bool,
 }
 
 /// Configures of polygon instance
@@ -78,6 +184,35 @@ pub struct PolygonInstanceDescriptor {
     pub instance_state: InstanceState,
 }
 
+/// Values of a custom vertex attribute, given for each position of the polygon mesh.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AttributeValues {
+    /// `float` in GLSL, e.g. the scalar fields
+    Float(Vec<f32>),
+    /// `vec2` in GLSL
+    Float2(Vec<[f32; 2]>),
+    /// `vec3` in GLSL
+    Float3(Vec<[f32; 3]>),
+    /// `vec4` in GLSL, e.g. the weights of the bones
+    Float4(Vec<[f32; 4]>),
+    /// `uvec4` in GLSL, e.g. the indices of the bones
+    Uint4(Vec<[u32; 4]>),
+}
+
+/// Custom per-vertex attribute appended to the vertices of `PolygonInstance`.
+///
+/// The standard attributes, the position, the uv coordinate and the normal, are in the
+/// locations `0`, `1` and `2` respectively, so the location of a custom attribute must be
+/// `3` or more. The default shaders ignore the custom attributes, and the custom shaders can
+/// refer them by `PolygonInstance::pipeline_with_shader`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct VertexAttribute {
+    /// the location of the attribute in the vertex shader
+    pub shader_location: u32,
+    /// the values for each position of the mesh
+    pub values: AttributeValues,
+}
+
 /// Configures of shape instance
 #[derive(Clone, Debug)]
 pub struct ShapeInstanceDescriptor {
@@ -108,6 +243,13 @@ struct PolygonShaders {
     vertex: ShaderModule,
     fragment: ShaderModule,
     tex_fragment: ShaderModule,
+    id_fragment: ShaderModule,
+    normal_depth_fragment: ShaderModule,
+    outline_vertex: ShaderModule,
+    outline_fragment: ShaderModule,
+    toon_fragment: ShaderModule,
+    skinned_vertex: ShaderModule,
+    skinned_tex_vertex: ShaderModule,
 }
 
 #[derive(Debug)]
@@ -120,7 +262,12 @@ struct ShapeShaders {
 #[derive(Debug)]
 struct WireShaders {
     vertex: ShaderModule,
+    wide_vertex: ShaderModule,
     fragment: ShaderModule,
+    sdf_vertex: ShaderModule,
+    sdf_fragment: ShaderModule,
+    hidden_vertex: ShaderModule,
+    hidden_fragment: ShaderModule,
 }
 
 /// Instance of polygon
@@ -133,22 +280,143 @@ struct WireShaders {
 /// with original, however, its render id is different from the one of original.
 #[derive(Debug)]
 pub struct PolygonInstance {
-    polygon: (Arc<BufferHandler>, Arc<BufferHandler>),
+    polygon: PolygonBuffers,
     state: InstanceState,
     shaders: Arc<PolygonShaders>,
     id: RenderID,
 }
 
+/// Instance rendering the integer label of a polygon, for picking and segmentation.
+///
+/// The id instance is created by `PolygonInstance::id_instance`, and shares the mesh data
+/// on the GPU with the original polygon. It is rendered to the target in
+/// `TextureFormat::R32Uint` of the scene created by [`id_buffer::id_scene`].
+///
+/// [`id_buffer::id_scene`]: ./id_buffer/fn.id_scene.html
+#[derive(Debug)]
+pub struct IdInstance {
+    instance: PolygonInstance,
+    label: u32,
+}
+
+/// Configures of the silhouette outlines.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OutlineState {
+    /// the color of the outline, [0, 1]-normalized rgba in the linear space.
+    /// Default is `Vector4::new(0.0, 0.0, 0.0, 1.0)`.
+    pub color: Vector4,
+    /// the width of the outline in the world space. Default is `0.01`.
+    pub width: f64,
+}
+
+/// Instance rendering the silhouette outline of a polygon, for the selection emphasis and
+/// the technical illustrations.
+///
+/// The outline is rendered by the inverted hull: the back faces of the polygon pushed out
+/// along the normals by the width of the outline are filled with the color of the outline.
+/// The outline instance is created by `PolygonInstance::outline_instance`, shares the mesh
+/// data on the GPU with the original polygon, and should be added to the scene together with
+/// the original polygon. The polygon should have the smooth normals, since the hull splits
+/// at the creases.
+#[derive(Debug)]
+pub struct OutlineInstance {
+    instance: PolygonInstance,
+    outline: OutlineState,
+}
+
+/// Instance of polygon deformed by the skeletal animation on GPU.
+///
+/// The skinned instance is created from `PolygonMesh` with `Skin` by
+/// `InstanceCreator::try_create_instance`, and is rendered in the dedicated pipeline, whose
+/// vertex shader blends the joint matrices by the weights of the vertices. The pose is set by
+/// `SkinnedInstance::set_joint_matrices`, and is reflected by `Scene::update_bind_group`.
+#[derive(Debug)]
+pub struct SkinnedInstance {
+    instance: PolygonInstance,
+    inverse_bind_matrices: Vec<Matrix4>,
+    joint_matrices: Vec<Matrix4>,
+}
+
+/// Configures of the toon shading.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ToonState {
+    /// the color of the brightest band, [0, 1]-normalized rgba in the linear space.
+    /// Default is `Vector4::new(1.0, 1.0, 1.0, 1.0)`.
+    pub color: Vector4,
+    /// the number of the bands of the lighting. Default is `3`.
+    pub bands: u32,
+    /// If `true`, the darker bands are drawn by the hatching instead of the darker colors.
+    /// Default is `false`.
+    pub hatching: bool,
+    /// the period of the hatching lines in pixels. Default is `8.0`.
+    pub hatch_spacing: f64,
+    /// the color of the hatching lines, [0, 1]-normalized rgba in the linear space.
+    /// Default is `Vector4::new(0.0, 0.0, 0.0, 1.0)`.
+    pub hatch_color: Vector4,
+}
+
+/// Instance rendering a polygon by the toon shading, for the technical illustrations such as
+/// the patent drawings and the manuals.
+///
+/// The lighting is quantized into the bands, and the light is the head light from the camera,
+/// i.e. the lights of the scene are ignored. With the hatching, the darker bands are drawn by
+/// the diagonal lines in the screen space, denser in the darker bands. The toon instance is
+/// created by `PolygonInstance::toon_instance`, and shares the mesh data on the GPU with
+/// the original polygon. Combine with `OutlineInstance` for the silhouettes.
+#[derive(Debug)]
+pub struct ToonInstance {
+    instance: PolygonInstance,
+    toon: ToonState,
+}
+
 /// Wire frame rendering
 #[derive(Debug)]
 pub struct WireFrameInstance {
     vertices: Arc<BufferHandler>,
     strips: Arc<BufferHandler>,
+    ribbon_vertices: Arc<BufferHandler>,
+    ribbon_indices: Arc<BufferHandler>,
     state: WireFrameState,
     shaders: Arc<WireShaders>,
     id: RenderID,
 }
 
+/// Configures of the hidden edges of the hidden-line rendering.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HiddenLineState {
+    /// the color of the hidden edges, [0, 1]-normalized rgba in the linear space.
+    /// Default is `Vector4::new(0.5, 0.5, 0.5, 1.0)`.
+    pub color: Vector4,
+    /// the length of the dashes and the gaps in pixels. If zero, the hidden edges are drawn by
+    /// the solid lines. Default is `4.0`.
+    pub dash_length: f64,
+}
+
+/// Instance filling the depth buffer by the faces of a polygon or a shape without drawing
+/// any colors, for the hidden-line rendering.
+///
+/// The occluder is created by `PolygonInstance::occluder_instance` or
+/// `ShapeInstance::occluder_instance`, shares the mesh data on the GPU with the original one,
+/// and is drawn before the other objects. The wireframes of the edges added to the scene
+/// together are hidden behind the invisible faces, which is the classic "hidden line"
+/// display of CAD without the exact hidden-line removal.
+#[derive(Debug)]
+pub struct OccluderInstance<T> {
+    instance: T,
+}
+
+/// Instance drawing the hidden edges of a wireframe by the dashed lines.
+///
+/// The hidden-line instance is created by `WireFrameInstance::hidden_line_instance`, and
+/// shares the vertex data on the GPU with the original wireframe. Only the fragments behind
+/// the depths drawn in advance, i.e. the ones of `OccluderInstance`, are drawn, so it is
+/// drawn after the occluders and before the other objects, e.g. the visible edges.
+#[derive(Debug)]
+pub struct HiddenLineInstance {
+    instance: WireFrameInstance,
+    hidden_line: HiddenLineState,
+}
+
 /// Instance of shape: `Shell` and `Solid` with geometric data.
 ///
 /// One can duplicate shapes with different postures and materials
@@ -159,7 +427,7 @@ pub struct WireFrameInstance {
 /// with original, however, its render id is different from the one of original.
 #[derive(Debug)]
 pub struct ShapeInstance {
-    polygon: (Arc<BufferHandler>, Arc<BufferHandler>),
+    polygon: PolygonBuffers,
     boundary: Arc<BufferHandler>,
     state: InstanceState,
     shaders: Arc<ShapeShaders>,
@@ -183,7 +451,10 @@ pub trait CreatorCreator {
 
 /// The trait for Buffer Objects.
 pub trait CreateBuffers {
-    /// Creates buffer handlers of attributes and indices.
+    /// Creates buffer handlers of attributes and 32-bit indices.
+    ///
+    /// The indices of `PolygonMesh` are of the triangle list. The ones of `StructuredMesh`
+    /// are of the triangle strips separated by the primitive restart value `u32::MAX`.
     fn buffers(
         &self,
         vertex_usage: BufferUsage,
@@ -220,13 +491,49 @@ pub trait IntoInstance<Instance> {
 struct ExpandedPolygon<V> {
     vertices: Vec<V>,
     indices: Vec<u32>,
+    // `TriangleList` or `TriangleStrip`, whose strips are separated by `PRIMITIVE_RESTART`
+    primitive_topology: PrimitiveTopology,
+}
+
+// the buffers of the chunks of a polygon, sharing the index format and the topology
+#[derive(Debug, Clone)]
+struct PolygonBuffers {
+    chunks: Vec<(Arc<BufferHandler>, Arc<BufferHandler>)>,
+    index_format: IndexFormat,
+    primitive_topology: PrimitiveTopology,
+    // the custom attributes following the standard ones: (location, format, size)
+    attributes: Vec<(u32, VertexFormat, u64)>,
+    // the triangles for `Scene::pick`
+    picking: Arc<PickingMesh>,
+}
+
+// the vertices with the positions, for the picking meshes
+trait VertexPosition {
+    fn position(&self) -> [f32; 3];
 }
 
+/// debug instances of the curvature combs and the glyphs of the normals and the tangents
+pub mod debug;
 mod expanded;
+/// rendering the normals and the depths in the camera space to the G-buffers
+pub mod gbuffer;
+mod hidden_line;
+/// rendering the labels of instances to the id buffers
+pub mod id_buffer;
 /// utility for creating `Texture`
 pub mod image2texture;
 mod instance_creator;
 mod instance_descriptor;
+/// loading polygon meshes, materials and textures from files
+pub mod loader;
+mod offscreen;
+mod outline;
 mod polyrend;
 mod shaperend;
+mod skinned;
+mod toon;
+mod transform;
+/// turntable cameras around the visual centers and the capture of the frames
+pub mod turntable;
+mod vertex_attribute;
 mod wireframe;
@@ -0,0 +1,376 @@
+use crate::*;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// Errors occured by loading files.
+#[derive(Debug)]
+pub enum Error {
+    /// The extension of the file is not supported.
+    UnsupportedFormat(String),
+    /// Errors caused by file I/O.
+    FromIO(std::io::Error),
+    /// Errors caused by parsing the polygon meshes.
+    FromPolymesh(polymesh::errors::Error),
+    /// Errors caused by loading the images of textures.
+    FromImage(image::ImageError),
+    /// Errors caused by importing glTF files.
+    FromGltf(::gltf::Error),
+}
+
+/// `Result` with the errors of loading files.
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::UnsupportedFormat(ext) => {
+                f.write_fmt(format_args!("The extension \"{}\" is not supported.", ext))
+            }
+            Error::FromIO(error) => f.write_fmt(format_args!("{:?}", error)),
+            Error::FromPolymesh(error) => f.write_fmt(format_args!("{}", error)),
+            Error::FromImage(error) => f.write_fmt(format_args!("{}", error)),
+            Error::FromGltf(error) => f.write_fmt(format_args!("{}", error)),
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error { Error::FromIO(error) }
+}
+
+impl From<polymesh::errors::Error> for Error {
+    fn from(error: polymesh::errors::Error) -> Error { Error::FromPolymesh(error) }
+}
+
+impl From<image::ImageError> for Error {
+    fn from(error: image::ImageError) -> Error { Error::FromImage(error) }
+}
+
+impl From<::gltf::Error> for Error {
+    fn from(error: ::gltf::Error) -> Error { Error::FromGltf(error) }
+}
+
+impl std::error::Error for Error {}
+
+/// The color maps of obj and glTF are in sRGB.
+const SRGB_TEXTURE: image2texture::TextureConfig = image2texture::TextureConfig {
+    srgb: true,
+    generate_mipmaps: true,
+};
+
+impl InstanceCreator {
+    /// Loads the polygon meshes, the materials and the textures in the file,
+    /// and creates the instances ready for rendering.
+    ///
+    /// The format is detected by the extension of the file.
+    /// - `obj`: each group of faces by `usemtl` is an instance. The materials are read from
+    /// the files by `mtllib`: the diffuse color `Kd`, the opacity `d` or `Tr`,
+    /// the specular exponent `Ns` and the diffuse texture map `map_Kd` are supported.
+    /// - `gltf`, `glb`: each triangle primitive of the meshes in the default scene is an
+    /// instance. The base color, the roughness and the base color texture of the PBR materials
+    /// are supported, and the transforms of the nodes are set to the instance matrices.
+    ///
+    /// The other attributes of instance states are the default ones.
+    /// # Examples
+    /// ```no_run
+    /// use truck_platform::*;
+    /// use truck_rendimpl::*;
+    /// # use std::sync::{Arc, Mutex};
+    /// # use truck_platform::wgpu::*;
+    /// # fn main() -> truck_rendimpl::loader::Result<()> {
+    /// # let instance = Instance::new(BackendBit::PRIMARY);
+    /// # let (device, queue, _) =
+    /// #     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// # let sc_desc = SwapChainDescriptor {
+    /// #     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    /// #     format: TextureFormat::Bgra8UnormSrgb,
+    /// #     width: 512,
+    /// #     height: 512,
+    /// #     present_mode: PresentMode::Mailbox,
+    /// # };
+    /// # let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// let mut scene = Scene::new(handler, &Default::default());
+    /// let creator = scene.instance_creator();
+    /// for instance in creator.load_file("model.gltf")? {
+    ///     scene.add_object(&instance);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn load_file<P: AsRef<Path>>(&self, path: P) -> Result<Vec<PolygonInstance>> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or("")
+            .to_lowercase();
+        match ext.as_str() {
+            "obj" => self.load_obj(path),
+            "gltf" | "glb" => self.load_gltf(path),
+            _ => Err(Error::UnsupportedFormat(ext)),
+        }
+    }
+
+    fn load_obj(&self, path: &Path) -> Result<Vec<PolygonInstance>> {
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        let text = std::fs::read_to_string(path)?;
+        let mut materials = HashMap::new();
+        // the lines of vertex attributes shared by all groups
+        let mut attrs = String::new();
+        // the pairs of the material name and the lines of faces
+        let mut groups: Vec<(Option<String>, String)> = vec![(None, String::new())];
+        for line in text.lines() {
+            let mut args = line.split_whitespace();
+            match args.next() {
+                Some("v") | Some("vt") | Some("vn") => {
+                    attrs += line;
+                    attrs.push('\n');
+                }
+                Some("f") => {
+                    let faces = &mut groups.last_mut().unwrap().1;
+                    *faces += line;
+                    faces.push('\n');
+                }
+                Some("usemtl") => {
+                    let name = args.collect::<Vec<_>>().join(" ");
+                    groups.push((Some(name), String::new()));
+                }
+                Some("mtllib") => {
+                    for file in args {
+                        materials.extend(read_mtl(&dir.join(file))?);
+                    }
+                }
+                _ => {}
+            }
+        }
+        groups
+            .into_iter()
+            .filter(|(_, faces)| !faces.is_empty())
+            .map(|(name, faces)| {
+                let mesh = obj::read((attrs.clone() + &faces).as_bytes())?;
+                // the origin of uv coordinates is the bottom-left in obj files.
+                let uv_coords = mesh
+                    .uv_coords()
+                    .iter()
+                    .map(|uv| Vector2::new(uv[0], 1.0 - uv[1]))
+                    .collect();
+                let mesh = PolygonMesh::try_new(
+                    mesh.positions().clone(),
+                    uv_coords,
+                    mesh.normals().clone(),
+                    mesh.faces().clone(),
+                )?;
+                let mut instance_state = InstanceState::default();
+                if let Some(mtl) = name.and_then(|name| materials.get(&name)) {
+                    instance_state.material = mtl.material;
+                    if let Some(texture) = &mtl.texture {
+                        let image = image::open(texture)?;
+                        let texture = self.create_texture_with_config(&image, &SRGB_TEXTURE);
+                        instance_state.texture = Some(texture);
+                    }
+                }
+                Ok(self.create_instance(&mesh, &PolygonInstanceDescriptor { instance_state }))
+            })
+            .collect()
+    }
+
+    fn load_gltf(&self, path: &Path) -> Result<Vec<PolygonInstance>> {
+        let (document, buffers, images) = ::gltf::import(path)?;
+        let mut textures = HashMap::new();
+        let mut res = Vec::new();
+        let mut nodes: Vec<(::gltf::Node, Matrix4)> = match document.default_scene() {
+            Some(scene) => scene
+                .nodes()
+                .map(|node| (node, Matrix4::identity()))
+                .collect(),
+            None => document
+                .scenes()
+                .flat_map(|scene| scene.nodes())
+                .map(|node| (node, Matrix4::identity()))
+                .collect(),
+        };
+        while let Some((node, parent)) = nodes.pop() {
+            let matrix = parent * gltf_matrix(node.transform().matrix());
+            nodes.extend(node.children().map(|child| (child, matrix)));
+            let mesh = match node.mesh() {
+                Some(mesh) => mesh,
+                None => continue,
+            };
+            for primitive in mesh.primitives() {
+                if primitive.mode() != ::gltf::mesh::Mode::Triangles {
+                    continue;
+                }
+                let reader = primitive.reader(|buffer| Some(&buffers[buffer.index()]));
+                let positions: Vec<Point3> = match reader.read_positions() {
+                    Some(iter) => iter
+                        .map(|p| Point3::new(p[0] as f64, p[1] as f64, p[2] as f64))
+                        .collect(),
+                    None => continue,
+                };
+                let normals: Vec<Vector3> = reader
+                    .read_normals()
+                    .map(|iter| {
+                        iter.map(|n| Vector3::new(n[0] as f64, n[1] as f64, n[2] as f64))
+                            .collect()
+                    })
+                    .unwrap_or_else(Vec::new);
+                let uv_coords: Vec<Vector2> = reader
+                    .read_tex_coords(0)
+                    .map(|iter| {
+                        iter.into_f32()
+                            .map(|uv| Vector2::new(uv[0] as f64, uv[1] as f64))
+                            .collect()
+                    })
+                    .unwrap_or_else(Vec::new);
+                let indices: Vec<usize> = match reader.read_indices() {
+                    Some(iter) => iter.into_u32().map(|i| i as usize).collect(),
+                    None => (0..positions.len()).collect(),
+                };
+                let vertex = |i: usize| {
+                    (
+                        i,
+                        Some(i).filter(|_| !uv_coords.is_empty()),
+                        Some(i).filter(|_| !normals.is_empty()),
+                    )
+                };
+                let faces = Faces::from_iter(
+                    indices
+                        .chunks_exact(3)
+                        .map(|tri| [vertex(tri[0]), vertex(tri[1]), vertex(tri[2])]),
+                );
+                let mesh = PolygonMesh::try_new(positions, uv_coords, normals, faces)?;
+
+                let pbr = primitive.material().pbr_metallic_roughness();
+                let color = pbr.base_color_factor();
+                let material = Material {
+                    albedo: Vector4::new(
+                        color[0] as f64,
+                        color[1] as f64,
+                        color[2] as f64,
+                        color[3] as f64,
+                    ),
+                    roughness: pbr.roughness_factor() as f64,
+                    ..Default::default()
+                };
+                let texture = match pbr.base_color_texture() {
+                    Some(info) => {
+                        let idx = info.texture().source().index();
+                        match textures.get(&idx) {
+                            Some(texture) => Some(Arc::clone(texture)),
+                            None => {
+                                let texture = gltf_image(&images[idx]).map(|image| {
+                                    self.create_texture_with_config(&image, &SRGB_TEXTURE)
+                                });
+                                if let Some(texture) = &texture {
+                                    textures.insert(idx, Arc::clone(texture));
+                                }
+                                texture
+                            }
+                        }
+                    }
+                    None => None,
+                };
+                let instance_state = InstanceState {
+                    matrix,
+                    material,
+                    texture,
+                    ..Default::default()
+                };
+                res.push(
+                    self.create_instance(&mesh, &PolygonInstanceDescriptor { instance_state }),
+                );
+            }
+        }
+        Ok(res)
+    }
+}
+
+/// The material and the path of the texture image in mtl files.
+#[derive(Clone, Debug)]
+struct MtlMaterial {
+    material: Material,
+    texture: Option<PathBuf>,
+}
+
+fn read_mtl(path: &Path) -> Result<HashMap<String, MtlMaterial>> {
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let text = std::fs::read_to_string(path)?;
+    let mut res = HashMap::new();
+    let mut current: Option<(String, MtlMaterial)> = None;
+    let parse = |s: Option<&str>| -> Result<f64> {
+        s.unwrap_or("")
+            .parse::<f64>()
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e).into())
+    };
+    for line in text.lines() {
+        let mut args = line.split_whitespace();
+        let first = args.next();
+        if first == Some("newmtl") {
+            let name = args.collect::<Vec<_>>().join(" ");
+            let mtl = MtlMaterial {
+                material: Material::default(),
+                texture: None,
+            };
+            res.extend(current.replace((name, mtl)));
+            continue;
+        }
+        let mtl = match &mut current {
+            Some((_, mtl)) => mtl,
+            None => continue,
+        };
+        match first {
+            Some("Kd") => {
+                let alpha = mtl.material.albedo[3];
+                let (r, g, b) = (
+                    parse(args.next())?,
+                    parse(args.next())?,
+                    parse(args.next())?,
+                );
+                mtl.material.albedo = Vector4::new(r, g, b, alpha);
+            }
+            Some("d") => mtl.material.albedo[3] = parse(args.next())?,
+            Some("Tr") => mtl.material.albedo[3] = 1.0 - parse(args.next())?,
+            // the conversion from the Phong exponent to the roughness of the microfacet model
+            Some("Ns") => mtl.material.roughness = f64::sqrt(2.0 / (parse(args.next())? + 2.0)),
+            // the file name is the last argument, after the options
+            Some("map_Kd") => mtl.texture = args.last().map(|file| dir.join(file)),
+            _ => {}
+        }
+    }
+    res.extend(current);
+    Ok(res)
+}
+
+/// Converts the column-major matrix of glTF.
+fn gltf_matrix(m: [[f32; 4]; 4]) -> Matrix4 {
+    let col = |i: usize| {
+        Vector4::new(
+            m[i][0] as f64,
+            m[i][1] as f64,
+            m[i][2] as f64,
+            m[i][3] as f64,
+        )
+    };
+    Matrix4::from_cols(col(0), col(1), col(2), col(3))
+}
+
+/// Converts the image of glTF. Returns `None` if the format is not of 8-bit channels.
+fn gltf_image(data: &::gltf::image::Data) -> Option<DynamicImage> {
+    use ::gltf::image::Format;
+    let (width, height, pixels) = (data.width, data.height, data.pixels.clone());
+    match data.format {
+        Format::R8 => {
+            image::ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageLuma8)
+        }
+        Format::R8G8 => {
+            image::ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageLumaA8)
+        }
+        Format::R8G8B8 => {
+            image::ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageRgb8)
+        }
+        Format::R8G8B8A8 => {
+            image::ImageBuffer::from_raw(width, height, pixels).map(DynamicImage::ImageRgba8)
+        }
+        _ => None,
+    }
+}
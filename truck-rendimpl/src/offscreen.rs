@@ -0,0 +1,79 @@
+use crate::*;
+
+/// Renders `scene` to the offscreen texture, and reads back the pixels.
+///
+/// Returns the bytes of the pixels in the row-major order without the row paddings.
+pub(crate) fn render_to_bytes(scene: &mut Scene, bytes_per_pixel: u32) -> Vec<u8> {
+    let sc_desc = scene.sc_desc();
+    let (device, queue) = (Arc::clone(scene.device()), Arc::clone(scene.queue()));
+    let size = Extent3d {
+        width: sc_desc.width,
+        height: sc_desc.height,
+        depth: 1,
+    };
+    let texture = device.create_texture(&TextureDescriptor {
+        label: None,
+        size,
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: TextureDimension::D2,
+        format: sc_desc.format,
+        usage: TextureUsage::OUTPUT_ATTACHMENT | TextureUsage::COPY_SRC,
+    });
+    scene.render_scene(&texture.create_view(&Default::default()));
+
+    let row_size = sc_desc.width * bytes_per_pixel;
+    let bytes_per_row = (row_size + COPY_BYTES_PER_ROW_ALIGNMENT - 1)
+        / COPY_BYTES_PER_ROW_ALIGNMENT
+        * COPY_BYTES_PER_ROW_ALIGNMENT;
+    let buffer = device.create_buffer(&BufferDescriptor {
+        label: None,
+        size: (bytes_per_row * sc_desc.height) as u64,
+        usage: BufferUsage::MAP_READ | BufferUsage::COPY_DST,
+        mapped_at_creation: false,
+    });
+    let mut encoder = device.create_command_encoder(&CommandEncoderDescriptor { label: None });
+    encoder.copy_texture_to_buffer(
+        TextureCopyView {
+            texture: &texture,
+            mip_level: 0,
+            origin: Origin3d::ZERO,
+        },
+        BufferCopyView {
+            buffer: &buffer,
+            layout: TextureDataLayout {
+                offset: 0,
+                bytes_per_row,
+                rows_per_image: sc_desc.height,
+            },
+        },
+        size,
+    );
+    queue.submit(vec![encoder.finish()]);
+
+    let buffer_slice = buffer.slice(..);
+    let buffer_future = buffer_slice.map_async(MapMode::Read);
+    device.poll(Maintain::Wait);
+    futures::executor::block_on(buffer_future).expect("failed to read the offscreen buffer.");
+    let data = buffer_slice.get_mapped_range();
+    data.chunks(bytes_per_row as usize)
+        .flat_map(|row| row[..row_size as usize].iter().copied())
+        .collect()
+}
+
+/// Creates the device handler sharing the device and the queue with `device_handler`,
+/// whose swap chain descriptor has the same size and `format`.
+pub(crate) fn offscreen_handler(
+    device_handler: &DeviceHandler,
+    format: TextureFormat,
+) -> DeviceHandler {
+    let sc_desc = SwapChainDescriptor {
+        format,
+        ..device_handler.sc_desc()
+    };
+    DeviceHandler::new(
+        Arc::clone(device_handler.device()),
+        Arc::clone(device_handler.queue()),
+        Arc::new(std::sync::Mutex::new(sc_desc)),
+    )
+}
@@ -0,0 +1,204 @@
+use crate::*;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod)]
+struct OutlineInfo {
+    color: [f32; 4],
+    width: [f32; 4],
+}
+
+impl Default for OutlineState {
+    #[inline(always)]
+    fn default() -> OutlineState {
+        OutlineState {
+            color: Vector4::new(0.0, 0.0, 0.0, 1.0),
+            width: 0.01,
+        }
+    }
+}
+
+impl OutlineState {
+    #[inline(always)]
+    fn outline_info(&self) -> OutlineInfo {
+        OutlineInfo {
+            color: self.color.cast().unwrap().into(),
+            width: [self.width as f32, 0.0, 0.0, 0.0],
+        }
+    }
+
+    /// Creates a `UNIFORM` buffer of the outline.
+    ///
+    /// # Shader Example
+    /// ```glsl
+    /// layout(set = 1, binding = 1) uniform Outline {
+    ///     vec4 outline_color;
+    ///     vec4 outline_width; // (width, 0.0, 0.0, 0.0)
+    /// };
+    /// ```
+    #[inline(always)]
+    pub fn buffer(&self, device: &Device) -> BufferHandler {
+        BufferHandler::from_slice(&[self.outline_info()], device, BufferUsage::UNIFORM)
+    }
+
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn bgl_entry() -> PreBindGroupLayoutEntry {
+        PreBindGroupLayoutEntry {
+            visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+            ty: BindingType::UniformBuffer {
+                dynamic: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }
+    }
+}
+
+impl PolygonInstance {
+    /// Creates the outline instance of `self`, which shares the mesh data with `self`.
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_platform::*;
+    /// use truck_rendimpl::*;
+    /// use truck_platform::wgpu::*;
+    /// # let instance = Instance::new(BackendBit::PRIMARY);
+    /// # let (device, queue, _) =
+    /// #     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// # let sc_desc = SwapChainDescriptor {
+    /// #     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    /// #     format: TextureFormat::Rgba8UnormSrgb,
+    /// #     width: 256,
+    /// #     height: 256,
+    /// #     present_mode: PresentMode::Mailbox,
+    /// # };
+    /// # let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// let mut scene = Scene::new(handler, &Default::default());
+    /// let creator = scene.instance_creator();
+    /// let mesh = PolygonMesh::new(
+    ///     vec![
+    ///         Point3::new(-1.0, -1.0, -3.0),
+    ///         Point3::new(1.0, -1.0, -3.0),
+    ///         Point3::new(0.0, 1.0, -3.0),
+    ///     ],
+    ///     Vec::new(),
+    ///     vec![Vector3::new(0.0, 0.0, 1.0)],
+    ///     Faces::from_iter(&[&[(0, None, Some(0)), (1, None, Some(0)), (2, None, Some(0))]]),
+    /// );
+    /// let polygon: PolygonInstance = creator.create_instance(&mesh, &Default::default());
+    /// // the selected polygon is emphasized by the red outline.
+    /// let outline = polygon.outline_instance(OutlineState {
+    ///     color: Vector4::new(1.0, 0.0, 0.0, 1.0),
+    ///     width: 0.05,
+    /// });
+    /// scene.add_object(&polygon);
+    /// scene.add_object(&outline);
+    /// ```
+    #[inline(always)]
+    pub fn outline_instance(&self, outline: OutlineState) -> OutlineInstance {
+        OutlineInstance {
+            instance: self.clone_instance(),
+            outline,
+        }
+    }
+}
+
+impl OutlineInstance {
+    /// Returns a reference to the configures of the outline.
+    #[inline(always)]
+    pub fn outline_state(&self) -> &OutlineState { &self.outline }
+    /// Returns the mutable reference to the configures of the outline.
+    #[inline(always)]
+    pub fn outline_state_mut(&mut self) -> &mut OutlineState { &mut self.outline }
+    /// Returns a reference to the instance descriptor.
+    ///
+    /// The matrix should be the same as the one of the original polygon. The material and
+    /// the texture are ignored.
+    #[inline(always)]
+    pub fn instance_state(&self) -> &InstanceState { self.instance.instance_state() }
+    /// Returns the mutable reference to the instance descriptor.
+    #[inline(always)]
+    pub fn instance_state_mut(&mut self) -> &mut InstanceState {
+        self.instance.instance_state_mut()
+    }
+    // the duplication sharing the mesh data with `self`, used for re-creating the pipeline
+    #[inline(always)]
+    fn clone_instance(&self) -> OutlineInstance {
+        OutlineInstance {
+            instance: self.instance.clone_instance(),
+            outline: self.outline,
+        }
+    }
+
+    /// Returns the default vertex shader module source.
+    ///
+    /// The GLSL original code is `src/shaders/outline.vert`.
+    #[inline(always)]
+    pub fn default_vertex_shader() -> ShaderModuleSource<'static> {
+        include_spirv!("shaders/outline.vert.spv")
+    }
+
+    /// Returns the default fragment shader module source.
+    ///
+    /// The GLSL original code is `src/shaders/outline.frag`.
+    #[inline(always)]
+    pub fn default_fragment_shader() -> ShaderModuleSource<'static> {
+        include_spirv!("shaders/outline.frag.spv")
+    }
+}
+
+impl Rendered for OutlineInstance {
+    derive_render_id!(instance);
+    impl_pipeline_builder!(clone_instance);
+    derive_vertex_buffer!(instance);
+    #[inline(always)]
+    fn bind_group_layout(&self, handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        handler.cached_bind_group_layout(&[
+            InstanceState::matrix_bgl_entry(),
+            OutlineState::bgl_entry(),
+        ])
+    }
+    #[inline(always)]
+    fn bind_group(&self, handler: &DeviceHandler, layout: &BindGroupLayout) -> Arc<BindGroup> {
+        let device = handler.device();
+        Arc::new(bind_group_util::create_bind_group(
+            device,
+            layout,
+            vec![
+                self.instance.state.matrix_buffer(device).binding_resource(),
+                self.outline.buffer(device).binding_resource(),
+            ],
+        ))
+    }
+    #[inline(always)]
+    fn pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let instance = &self.instance;
+        // Only the back faces of the hull are drawn.
+        let key = PipelineKey {
+            primitive_topology: instance.polygon.primitive_topology,
+            index_format: instance.polygon.index_format,
+            cull_mode: CullMode::Front,
+            ..instance.state.pipeline_key(
+                "truck-rendimpl/outline-polygon",
+                &instance.polygon.vertex_layout(),
+                handler,
+                sample_count,
+            )
+        };
+        handler.cached_pipeline(&key, || {
+            instance.pipeline_with_cull_mode(
+                &instance.shaders.outline_vertex,
+                &instance.shaders.outline_fragment,
+                CullMode::Front,
+                handler,
+                layout,
+                sample_count,
+            )
+        })
+    }
+}
@@ -4,12 +4,30 @@ use std::collections::HashMap;
 
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Zeroable, Pod)]
-struct AttrVertex {
+pub(crate) struct AttrVertex {
     pub position: [f32; 3],
     pub uv_coord: [f32; 2],
     pub normal: [f32; 3],
 }
 
+// the vertex with the index of the position in the original mesh
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Zeroable, Pod)]
+pub(crate) struct IndexedVertex {
+    pub vertex: AttrVertex,
+    pub position_index: u32,
+}
+
+impl VertexPosition for AttrVertex {
+    #[inline(always)]
+    fn position(&self) -> [f32; 3] { self.position }
+}
+
+impl VertexPosition for IndexedVertex {
+    #[inline(always)]
+    fn position(&self) -> [f32; 3] { self.vertex.position }
+}
+
 impl CreateBuffers for PolygonMesh {
     #[inline(always)]
     fn buffers(
@@ -18,7 +36,7 @@ impl CreateBuffers for PolygonMesh {
         index_usage: BufferUsage,
         device: &Device,
     ) -> (BufferHandler, BufferHandler) {
-        ExpandedPolygon::from(self).buffers(vertex_usage, index_usage, device)
+        ExpandedPolygon::<AttrVertex>::from(self).buffers(vertex_usage, index_usage, device)
     }
 }
 
@@ -30,13 +48,13 @@ impl IntoInstance<PolygonInstance> for PolygonMesh {
         creator: &InstanceCreator,
         desc: &PolygonInstanceDescriptor,
     ) -> PolygonInstance {
-        let (vb, ib) = self.buffers(
+        let polygon = ExpandedPolygon::<AttrVertex>::from(self).polygon_buffers(
             BufferUsage::VERTEX,
             BufferUsage::INDEX,
             creator.handler.device(),
         );
         PolygonInstance {
-            polygon: (Arc::new(vb), Arc::new(ib)),
+            polygon,
             state: desc.instance_state.clone(),
             shaders: Arc::clone(&creator.polygon_shaders),
             id: RenderID::gen(),
@@ -52,7 +70,6 @@ impl IntoInstance<WireFrameInstance> for PolygonMesh {
         creator: &InstanceCreator,
         desc: &PolygonWireFrameInstanceDescriptor,
     ) -> WireFrameInstance {
-        let device = creator.handler.device();
         let positions: Vec<[f32; 3]> = self
             .positions()
             .iter()
@@ -65,15 +82,7 @@ impl IntoInstance<WireFrameInstance> for PolygonMesh {
                 strips.push(face[(i + 1) % face.len()].pos as u32);
             }
         });
-        let vb = BufferHandler::from_slice(&positions, device, BufferUsage::VERTEX);
-        let ib = BufferHandler::from_slice(&strips, device, BufferUsage::INDEX);
-        WireFrameInstance {
-            vertices: Arc::new(vb),
-            strips: Arc::new(ib),
-            state: desc.wireframe_state.clone(),
-            shaders: Arc::clone(&creator.wire_shaders),
-            id: RenderID::gen(),
-        }
+        creator.wire_frame_instance(&positions, &strips, &desc.wireframe_state)
     }
 
 }
@@ -86,7 +95,7 @@ impl CreateBuffers for StructuredMesh {
         index_usage: BufferUsage,
         device: &Device,
     ) -> (BufferHandler, BufferHandler) {
-        ExpandedPolygon::from(self).buffers(vertex_usage, index_usage, device)
+        ExpandedPolygon::<AttrVertex>::from(self).buffers(vertex_usage, index_usage, device)
     }
 }
 
@@ -98,13 +107,13 @@ impl IntoInstance<PolygonInstance> for StructuredMesh {
         creator: &InstanceCreator,
         desc: &PolygonInstanceDescriptor,
     ) -> PolygonInstance {
-        let (vb, ib) = self.buffers(
+        let polygon = ExpandedPolygon::<AttrVertex>::from(self).polygon_buffers(
             BufferUsage::VERTEX,
             BufferUsage::INDEX,
             creator.handler.device(),
         );
         PolygonInstance {
-            polygon: (Arc::new(vb), Arc::new(ib)),
+            polygon,
             state: desc.instance_state.clone(),
             shaders: Arc::clone(&creator.polygon_shaders),
             id: RenderID::gen(),
@@ -120,7 +129,6 @@ impl IntoInstance<WireFrameInstance> for StructuredMesh {
         creator: &InstanceCreator,
         desc: &PolygonWireFrameInstanceDescriptor,
     ) -> WireFrameInstance {
-        let device = creator.handler.device();
         let positions: Vec<[f32; 3]> = self
             .positions()
             .iter()
@@ -145,15 +153,7 @@ impl IntoInstance<WireFrameInstance> for StructuredMesh {
                 strips.push(i * len + j);
             }
         }
-        let vb = BufferHandler::from_slice(&positions, device, BufferUsage::VERTEX);
-        let ib = BufferHandler::from_slice(&strips, device, BufferUsage::INDEX);
-        WireFrameInstance {
-            vertices: Arc::new(vb),
-            strips: Arc::new(ib),
-            state: desc.wireframe_state.clone(),
-            shaders: Arc::clone(&creator.wire_shaders),
-            id: RenderID::gen(),
-        }
+        creator.wire_frame_instance(&positions, &strips, &desc.wireframe_state)
     }
 }
 
@@ -184,26 +184,21 @@ impl PolygonInstance {
     }
 
     #[inline(always)]
-    fn non_textured_bdl(&self, device: &Device) -> BindGroupLayout {
-        bind_group_util::create_bind_group_layout(device, {
-            &[
-                InstanceState::matrix_bgl_entry(),
-                InstanceState::material_bgl_entry(),
-            ]
-        })
+    fn non_textured_bdl(&self, handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        handler.cached_bind_group_layout(&[
+            InstanceState::matrix_bgl_entry(),
+            InstanceState::material_bgl_entry(),
+        ])
     }
 
     #[inline(always)]
-    fn textured_bdl(&self, device: &Device) -> BindGroupLayout {
-        bind_group_util::create_bind_group_layout(
-            device,
-            &[
-                InstanceState::matrix_bgl_entry(),
-                InstanceState::material_bgl_entry(),
-                InstanceState::textureview_bgl_entry(),
-                InstanceState::sampler_bgl_entry(),
-            ],
-        )
+    fn textured_bdl(&self, handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        handler.cached_bind_group_layout(&[
+            InstanceState::matrix_bgl_entry(),
+            InstanceState::material_bgl_entry(),
+            InstanceState::textureview_bgl_entry(),
+            InstanceState::sampler_bgl_entry(),
+        ])
     }
 
     #[inline(always)]
@@ -213,7 +208,7 @@ impl PolygonInstance {
             layout,
             vec![
                 self.state.matrix_buffer(device).binding_resource(),
-                self.state.material.buffer(device).binding_resource(),
+                self.state.material_buffer(device).binding_resource(),
             ],
         )
     }
@@ -225,7 +220,7 @@ impl PolygonInstance {
             layout,
             vec![
                 self.state.matrix_buffer(device).binding_resource(),
-                self.state.material.buffer(device).binding_resource(),
+                self.state.material_buffer(device).binding_resource(),
                 BindingResource::TextureView(&view),
                 BindingResource::Sampler(&sampler),
             ],
@@ -286,12 +281,56 @@ impl PolygonInstance {
         layout: &PipelineLayout,
         sample_count: u32,
     ) -> Arc<RenderPipeline> {
-        let device = device_handler.device();
-        let sc_desc = device_handler.sc_desc();
         let cull_mode = match self.state.backface_culling {
             true => CullMode::Back,
             false => CullMode::None,
         };
+        self.pipeline_with_cull_mode(
+            vertex_module,
+            fragment_module,
+            cull_mode,
+            device_handler,
+            layout,
+            sample_count,
+        )
+    }
+
+    pub(crate) fn pipeline_with_cull_mode(
+        &self,
+        vertex_module: &ShaderModule,
+        fragment_module: &ShaderModule,
+        cull_mode: CullMode,
+        device_handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        self.pipeline_with_write_mask(
+            vertex_module,
+            fragment_module,
+            cull_mode,
+            ColorWrite::ALL,
+            device_handler,
+            layout,
+            sample_count,
+        )
+    }
+
+    // the pipeline writing only the channels in `write_mask`, e.g. no channels for occluders
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn pipeline_with_write_mask(
+        &self,
+        vertex_module: &ShaderModule,
+        fragment_module: &ShaderModule,
+        cull_mode: CullMode,
+        write_mask: ColorWrite,
+        device_handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let device = device_handler.device();
+        let sc_desc = device_handler.sc_desc();
+        let depth_bias = self.state.depth_bias.oriented(device_handler);
+        let (stride, attributes) = self.polygon.vertex_attributes();
         let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
             layout: Some(layout),
             vertex_stage: ProgrammableStageDescriptor {
@@ -305,46 +344,30 @@ impl PolygonInstance {
             rasterization_state: Some(RasterizationStateDescriptor {
                 front_face: FrontFace::Ccw,
                 cull_mode,
-                depth_bias: 0,
-                depth_bias_slope_scale: 0.0,
-                depth_bias_clamp: 0.0,
+                depth_bias: depth_bias.constant,
+                depth_bias_slope_scale: depth_bias.slope_scale as f32,
+                depth_bias_clamp: depth_bias.clamp as f32,
                 clamp_depth: false,
             }),
-            primitive_topology: PrimitiveTopology::TriangleList,
+            primitive_topology: self.polygon.primitive_topology,
             color_states: &[ColorStateDescriptor {
                 format: sc_desc.format,
                 color_blend: BlendDescriptor::REPLACE,
                 alpha_blend: BlendDescriptor::REPLACE,
-                write_mask: ColorWrite::ALL,
+                write_mask,
             }],
             depth_stencil_state: Some(DepthStencilStateDescriptor {
                 format: TextureFormat::Depth32Float,
                 depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
+                depth_compare: device_handler.depth_compare(wgpu::CompareFunction::Less),
                 stencil: StencilStateDescriptor::default(),
             }),
             vertex_state: VertexStateDescriptor {
-                index_format: IndexFormat::Uint32,
+                index_format: self.polygon.index_format,
                 vertex_buffers: &[VertexBufferDescriptor {
-                    stride: std::mem::size_of::<AttrVertex>() as BufferAddress,
+                    stride,
                     step_mode: InputStepMode::Vertex,
-                    attributes: &[
-                        VertexAttributeDescriptor {
-                            format: VertexFormat::Float3,
-                            offset: 0,
-                            shader_location: 0,
-                        },
-                        VertexAttributeDescriptor {
-                            format: VertexFormat::Float2,
-                            offset: 3 * 4,
-                            shader_location: 1,
-                        },
-                        VertexAttributeDescriptor {
-                            format: VertexFormat::Float3,
-                            offset: 2 * 4 + 3 * 4,
-                            shader_location: 2,
-                        },
-                    ],
+                    attributes: &attributes,
                 }],
             },
             sample_count,
@@ -358,18 +381,35 @@ impl PolygonInstance {
 
 impl Rendered for PolygonInstance {
     impl_render_id!(id);
+    impl_pipeline_builder!(clone_instance);
+
+    #[inline(always)]
+    fn picking_mesh(&self) -> Option<(Arc<PickingMesh>, Matrix4)> {
+        Some((Arc::clone(&self.polygon.picking), self.state.world_matrix()))
+    }
 
     #[inline(always)]
     fn vertex_buffer(&self, _: &DeviceHandler) -> (Arc<BufferHandler>, Option<Arc<BufferHandler>>) {
-        let polygon = self.polygon.clone();
-        (polygon.0, Some(polygon.1))
+        let (vb, ib) = self.polygon.chunks[0].clone();
+        (vb, Some(ib))
+    }
+    #[inline(always)]
+    fn vertex_buffers(
+        &self,
+        _: &DeviceHandler,
+    ) -> Vec<(Arc<BufferHandler>, Option<Arc<BufferHandler>>)> {
+        self.polygon
+            .chunks
+            .iter()
+            .map(|(vb, ib)| (Arc::clone(vb), Some(Arc::clone(ib))))
+            .collect()
     }
     #[inline(always)]
     fn bind_group_layout(&self, device_handler: &DeviceHandler) -> Arc<BindGroupLayout> {
-        Arc::new(match self.state.texture.is_some() {
-            true => self.textured_bdl(device_handler.device()),
-            false => self.non_textured_bdl(device_handler.device()),
-        })
+        match self.state.texture.is_some() {
+            true => self.textured_bdl(device_handler),
+            false => self.non_textured_bdl(device_handler),
+        }
     }
     #[inline(always)]
     fn bind_group(
@@ -389,24 +429,37 @@ impl Rendered for PolygonInstance {
         layout: &PipelineLayout,
         sample_count: u32,
     ) -> Arc<RenderPipeline> {
-        let fragment_shader = match self.state.texture.is_some() {
-            true => &self.shaders.tex_fragment,
-            false => &self.shaders.fragment,
+        let (fragment_shader, shader) = match self.state.texture.is_some() {
+            true => (
+                &self.shaders.tex_fragment,
+                "truck-rendimpl/textured-polygon",
+            ),
+            false => (&self.shaders.fragment, "truck-rendimpl/polygon"),
         };
-        self.pipeline_with_shader_module(
-            &self.shaders.vertex,
-            fragment_shader,
-            device_handler,
-            layout,
-            sample_count,
-        )
+        let vertex_layout = self.polygon.vertex_layout();
+        let key = PipelineKey {
+            primitive_topology: self.polygon.primitive_topology,
+            index_format: self.polygon.index_format,
+            ..self
+                .state
+                .pipeline_key(shader, &vertex_layout, device_handler, sample_count)
+        };
+        device_handler.cached_pipeline(&key, || {
+            self.pipeline_with_shader_module(
+                &self.shaders.vertex,
+                fragment_shader,
+                device_handler,
+                layout,
+                sample_count,
+            )
+        })
     }
 }
 
 fn signup_vertex(
     polymesh: &PolygonMesh,
     vertex: Vertex,
-    glpolymesh: &mut ExpandedPolygon<AttrVertex>,
+    glpolymesh: &mut ExpandedPolygon<IndexedVertex>,
     vertex_map: &mut HashMap<Vertex, u32>,
 ) {
     let idx = match vertex_map.get(&vertex) {
@@ -428,7 +481,10 @@ fn signup_vertex(
                 normal,
             };
             vertex_map.insert(vertex, idx);
-            glpolymesh.vertices.push(wgpuvertex);
+            glpolymesh.vertices.push(IndexedVertex {
+                vertex: wgpuvertex,
+                position_index: vertex.pos as u32,
+            });
             idx
         }
     };
@@ -436,7 +492,14 @@ fn signup_vertex(
 }
 
 impl From<&PolygonMesh> for ExpandedPolygon<AttrVertex> {
+    #[inline(always)]
     fn from(polymesh: &PolygonMesh) -> ExpandedPolygon<AttrVertex> {
+        ExpandedPolygon::<IndexedVertex>::from(polymesh).map_vertices(|v| v.vertex)
+    }
+}
+
+impl From<&PolygonMesh> for ExpandedPolygon<IndexedVertex> {
+    fn from(polymesh: &PolygonMesh) -> ExpandedPolygon<IndexedVertex> {
         let mut glpolymesh = ExpandedPolygon::default();
         let mut vertex_map = HashMap::<Vertex, u32>::new();
         for tri in polymesh.faces().tri_faces() {
@@ -482,13 +545,13 @@ impl From<&StructuredMesh> for ExpandedPolygon<AttrVertex> {
                 });
             }
         }
+        glpolymesh.primitive_topology = PrimitiveTopology::TriangleStrip;
         for i in 1..m {
-            for j in 1..n {
-                glpolymesh.indices.push(((i - 1) * n + j - 1) as u32);
-                glpolymesh.indices.push((i * n + j - 1) as u32);
-                glpolymesh.indices.push(((i - 1) * n + j) as u32);
+            if i > 1 {
+                glpolymesh.indices.push(PRIMITIVE_RESTART);
+            }
+            for j in 0..n {
                 glpolymesh.indices.push(((i - 1) * n + j) as u32);
-                glpolymesh.indices.push((i * n + j - 1) as u32);
                 glpolymesh.indices.push((i * n + j) as u32);
             }
         }
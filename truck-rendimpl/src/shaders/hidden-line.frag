@@ -0,0 +1,22 @@
+#version 450
+
+layout(set = 1, binding = 1) uniform HiddenLine {
+    vec4 hidden_color;
+    vec4 dash; // (the length of the dashes in pixels, 0.0, 0.0, 0.0)
+};
+
+layout(location = 0) noperspective in vec2 ndc;
+layout(location = 1) flat in vec2 start_ndc;
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    // The dashes are measured along the major axis of the segment on the screen,
+    // so that the pattern continues across the segments of the polylines.
+    vec2 dir = abs(ndc - start_ndc);
+    float s = dir.x > dir.y ? gl_FragCoord.x : gl_FragCoord.y;
+    if (dash.x > 0.0 && mod(s, 2.0 * dash.x) >= dash.x) {
+        discard;
+    }
+    color = hidden_color;
+}
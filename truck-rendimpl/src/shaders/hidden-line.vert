@@ -0,0 +1,25 @@
+#version 450
+
+layout(location = 0) in vec3 position;
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 camera_matrix;
+    mat4 camera_projection;
+};
+
+layout(set = 1, binding = 0) uniform ModelMatrix {
+    mat4 matrix;
+};
+
+// the normalized device coordinates of the fragment and of the first vertex of the segment
+layout(location = 0) noperspective out vec2 ndc;
+layout(location = 1) flat out vec2 start_ndc;
+
+void main() {
+    vec4 world_position = matrix * vec4(position, 1.0);
+    gl_Position = camera_projection * world_position;
+    // the offset toward the camera, whose sign is turned over if the depths are reversed
+    gl_Position.z += 1.0e-4 * sign((camera_projection * camera_matrix)[2][2]);
+    ndc = gl_Position.xy / gl_Position.w;
+    start_ndc = ndc;
+}
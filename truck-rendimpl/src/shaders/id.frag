@@ -0,0 +1,11 @@
+#version 450
+
+layout(set = 1, binding = 1) uniform RenderId {
+    uint id;
+};
+
+layout(location = 0) out uint color;
+
+void main() {
+    color = id;
+}
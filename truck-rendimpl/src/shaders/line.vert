@@ -14,5 +14,6 @@ layout(set = 1, binding = 0) uniform ModelMatrix {
 void main() {
     vec4 world_position = matrix * vec4(position, 1.0);
     gl_Position = camera_projection * world_position;
-    gl_Position.z -= 1.0e-4;
+    // the offset toward the camera, whose sign is turned over if the depths are reversed
+    gl_Position.z += 1.0e-4 * sign((camera_projection * camera_matrix)[2][2]);
 }
\ No newline at end of file
@@ -0,0 +1,23 @@
+#version 450
+
+layout(location = 0) in vec3 position;
+layout(location = 2) in vec3 vertex_normal;
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 camera_matrix;
+    mat4 camera_projection;
+};
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    vec3 normal = normalize(vertex_normal);
+    vec3 relative = position - camera_matrix[3].xyz;
+    // the normal in the camera space and the depth along the camera direction
+    color = vec4(
+        dot(normal, camera_matrix[0].xyz),
+        dot(normal, camera_matrix[1].xyz),
+        dot(normal, camera_matrix[2].xyz),
+        -dot(relative, camera_matrix[2].xyz)
+    );
+}
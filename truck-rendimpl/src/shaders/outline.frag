@@ -0,0 +1,12 @@
+#version 450
+
+layout(set = 1, binding = 1) uniform Outline {
+    vec4 outline_color;
+    vec4 outline_width; // (width, 0.0, 0.0, 0.0)
+};
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    color = outline_color;
+}
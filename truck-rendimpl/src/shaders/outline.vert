@@ -0,0 +1,26 @@
+#version 450
+
+layout(location = 0) in vec3 position;
+layout(location = 2) in vec3 normal;
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 camera_matrix;
+    mat4 camera_projection;
+};
+
+layout(set = 1, binding = 0) uniform ModelMatrix {
+    mat4 matrix;
+};
+
+layout(set = 1, binding = 1) uniform Outline {
+    vec4 outline_color;
+    vec4 outline_width; // (width, 0.0, 0.0, 0.0)
+};
+
+void main() {
+    vec4 world_position = matrix * vec4(position, 1.0);
+    vec3 world_normal = normalize((matrix * vec4(normal, 0.0)).xyz);
+    // the inverted hull: the vertices are pushed out along the normals
+    vec4 offset = vec4(world_normal * outline_width.x, 0.0);
+    gl_Position = camera_projection * (world_position + offset);
+}
@@ -15,10 +15,9 @@ layout(set = 0, binding = 1) buffer Lights {
     Light lights[];
 };
 
-layout(set = 0, binding = 2) uniform Scene {
-    float _time;
-    uint nlights;
-};
+#include "scene-module.frag"
+
+#include "shadow-module.frag"
 
 layout(set = 1, binding = 1) uniform ModelMaterial {
     Material material;
@@ -27,15 +26,17 @@ layout(set = 1, binding = 1) uniform ModelMaterial {
 layout(location = 0) out vec4 color;
 
 void main() {
+    if (is_clipped(position)) discard;
     vec3 camera_dir = normalize(camera_matrix[3].xyz - position);
     vec3 normal = normalize(vertex_normal);
     vec3 pre_color = vec3(0.0, 0.0, 0.0);
     Material mat = material;
     for (uint i = 0; i < nlights; i++) {
         Light light = lights[i];
-        pre_color += microfacet_color(position, normal, light, camera_dir, mat);
+        pre_color += microfacet_color(position, normal, light, camera_dir, mat)
+            * shadow_factor(light, position, normal);
     }
-    pre_color = clamp(pre_color, 0.0, 1.0);
+    pre_color = apply_tone_mapping(pre_color);
     pre_color = ambient_correction(pre_color, mat);
     color = vec4(pre_color, 1.0);
 }
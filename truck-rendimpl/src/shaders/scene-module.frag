@@ -0,0 +1,28 @@
+layout(set = 0, binding = 2) uniform Scene {
+    float _time;
+    uint nlights;
+    float exposure;
+    uint tone_mapping;  // Linear => 0, Reinhard => 1, Aces => 2
+    uint nclip_planes;
+    vec4 clip_planes[6];
+};
+
+// Returns whether the position in the world coordinate is cut away by the clip planes.
+bool is_clipped(vec3 position) {
+    for (uint i = 0; i < nclip_planes; i++) {
+        if (dot(clip_planes[i], vec4(position, 1.0)) < 0.0) return true;
+    }
+    return false;
+}
+
+// Maps the linear color to [0, 1] by the exposure and the tone mapping.
+vec3 apply_tone_mapping(vec3 pre_color) {
+    vec3 c = max(pre_color * exposure, 0.0);
+    if (tone_mapping == 1) {
+        return c / (1.0 + c);
+    } else if (tone_mapping == 2) {
+        // the fitted ACES filmic curve by Krzysztof Narkowicz
+        return clamp(c * (2.51 * c + 0.03) / (c * (2.43 * c + 0.59) + 0.14), 0.0, 1.0);
+    }
+    return clamp(c, 0.0, 1.0);
+}
@@ -0,0 +1,21 @@
+#version 450
+
+layout(set = 1, binding = 1) uniform Line {
+    vec4 color;
+    vec4 line; // (the width of the line in pixels, the width of the screen, the height of the screen, 0.0)
+};
+
+layout(location = 0) noperspective in vec2 local;
+layout(location = 1) flat in float half_length;
+
+layout(location = 0) out vec4 outColor;
+
+void main() {
+    // the distance in pixels from the center of the pixel to the segment
+    float dist = length(vec2(max(abs(local.x) - half_length, 0.0), local.y));
+    float coverage = clamp(line.x / 2.0 + 0.5 - dist, 0.0, 1.0);
+    if (coverage <= 0.0) {
+        discard;
+    }
+    outColor = vec4(color.rgb, color.a * coverage);
+}
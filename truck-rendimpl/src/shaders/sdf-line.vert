@@ -0,0 +1,48 @@
+#version 450
+
+layout(location = 0) in vec3 position;
+// the other end of the segment
+layout(location = 1) in vec3 other;
+// (-1.0 at the start of the segment or +1.0 at the end, the side of the ribbon +1.0 or -1.0)
+layout(location = 2) in vec2 corner;
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 camera_matrix;
+    mat4 camera_projection;
+};
+
+layout(set = 1, binding = 0) uniform ModelMatrix {
+    mat4 matrix;
+};
+
+layout(set = 1, binding = 1) uniform Line {
+    vec4 color;
+    vec4 line; // (the width of the line in pixels, the width of the screen, the height of the screen, 0.0)
+};
+
+// the position in pixels from the midpoint of the segment, along and across the segment
+layout(location = 0) noperspective out vec2 local;
+// the half of the length of the segment in pixels
+layout(location = 1) flat out float half_length;
+
+void main() {
+    vec4 clip = camera_projection * matrix * vec4(position, 1.0);
+    vec4 other_clip = camera_projection * matrix * vec4(other, 1.0);
+    vec2 size = line.yz;
+    vec2 screen = clip.xy / clip.w * size / 2.0;
+    vec2 other_screen = other_clip.xy / other_clip.w * size / 2.0;
+    // the direction from the start to the end
+    vec2 dir = (other_screen - screen) * -corner.x;
+    float len = length(dir);
+    dir = len > 1.0e-6 ? dir / len : vec2(1.0, 0.0);
+    vec2 normal = vec2(-dir.y, dir.x);
+    // the quad covers the round caps and the fringe of one pixel for the antialiasing
+    float extent = line.x / 2.0 + 1.0;
+    vec2 offset = (dir * corner.x + normal * corner.y) * extent;
+    gl_Position = clip;
+    gl_Position.xy += offset * 2.0 / size * clip.w;
+    // the offset toward the camera, whose sign is turned over if the depths are reversed
+    gl_Position.z += 1.0e-4 * sign((camera_projection * camera_matrix)[2][2]);
+    half_length = len / 2.0;
+    local = vec2(corner.x * (half_length + extent), corner.y * extent);
+}
@@ -0,0 +1,37 @@
+// The shadow maps of the point lights, sampled by the 3x3 PCF.
+// cf: the scene bind group of truck-platform
+
+layout(set = 0, binding = 3) uniform texture2DArray shadow_maps;
+layout(set = 0, binding = 4) uniform samplerShadow shadow_sampler;
+
+layout(set = 0, binding = 5) buffer ShadowMatrices {
+    mat4 shadow_matrices[];
+};
+
+// the ratio of the light reaching the position, 1.0 if the light casts no shadows
+float shadow_factor(Light light, vec3 position, vec3 normal) {
+    if (light.light_type[1] == 0) return 1.0;
+    vec3 dir = position - light.position.xyz;
+    vec3 a = abs(dir);
+    // the face of the cube around the light
+    uint face;
+    if (a.x >= a.y && a.x >= a.z) face = dir.x > 0.0 ? 0 : 1;
+    else if (a.y >= a.z) face = dir.y > 0.0 ? 2 : 3;
+    else face = dir.z > 0.0 ? 4 : 5;
+    uint layer = light.light_type[2] + face;
+    float texel = 1.0 / float(light.light_type[3]);
+    // the normal offset against the shadow acne
+    vec3 offset_position = position + normal * (4.0 * texel * length(dir));
+    vec4 clip = shadow_matrices[layer] * vec4(offset_position, 1.0);
+    vec3 ndc = clip.xyz / clip.w;
+    vec2 uv = vec2(ndc.x * 0.5 + 0.5, 0.5 - ndc.y * 0.5);
+    float depth = min(ndc.z, 1.0);
+    float sum = 0.0;
+    for (int i = -1; i <= 1; i++) {
+        for (int j = -1; j <= 1; j++) {
+            vec3 coord = vec3(uv + vec2(i, j) * texel, float(layer));
+            sum += texture(sampler2DArrayShadow(shadow_maps, shadow_sampler), vec4(coord, depth));
+        }
+    }
+    return sum / 9.0;
+}
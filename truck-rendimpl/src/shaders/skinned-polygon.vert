@@ -0,0 +1,44 @@
+#version 450
+
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec2 uv_coord;
+layout(location = 2) in vec3 normal;
+layout(location = 3) in uvec4 joints;
+layout(location = 4) in vec4 weights;
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 camera_matrix;
+    mat4 camera_projection;
+};
+
+layout(set = 1, binding = 0) uniform ModelMatrix {
+    mat4 matrix;
+};
+
+// the products of the joint matrices and the inverse bind matrices
+layout(set = 1, binding = 2) readonly buffer Joints {
+    mat4 joint_matrices[];
+};
+
+layout(location = 0) out vec3 vertex_position;
+layout(location = 1) out vec2 uv;
+layout(location = 2) out vec3 vertex_normal;
+
+void main() {
+    vec4 p = vec4(position, 1.0);
+    vec4 n = vec4(normal, 0.0);
+    vec4 skinned_position = weights.x * (joint_matrices[joints.x] * p)
+        + weights.y * (joint_matrices[joints.y] * p)
+        + weights.z * (joint_matrices[joints.z] * p)
+        + weights.w * (joint_matrices[joints.w] * p);
+    vec4 skinned_normal = weights.x * (joint_matrices[joints.x] * n)
+        + weights.y * (joint_matrices[joints.y] * n)
+        + weights.z * (joint_matrices[joints.z] * n)
+        + weights.w * (joint_matrices[joints.w] * n);
+    vec4 world_position = matrix * skinned_position;
+    vec4 world_normal = normalize(matrix * skinned_normal);
+    gl_Position = camera_projection * world_position;
+    vertex_position = world_position.xyz;
+    uv = uv_coord;
+    vertex_normal = world_normal.xyz;
+}
@@ -16,10 +16,9 @@ layout(set = 0, binding = 1) buffer Lights {
     Light lights[];
 };
 
-layout(set = 0, binding = 2) uniform Scene {
-    float _time;
-    uint nlights;
-};
+#include "scene-module.frag"
+
+#include "shadow-module.frag"
 
 layout(set = 1, binding = 1) uniform ModelMaterial {
     Material default_material;
@@ -57,7 +56,7 @@ bool in_domain() {
 }
 
 void main() {
-    if (!in_domain()) discard;
+    if (!in_domain() || is_clipped(position)) discard;
     Material material = default_material;
     material.albedo = textured_material();
     vec3 camera_dir = normalize(camera_matrix[3].xyz - position);
@@ -65,9 +64,10 @@ void main() {
     vec3 pre_color = vec3(0.0, 0.0, 0.0);
     for (uint i = 0; i < nlights; i++) {
         Light light = lights[i];
-        pre_color += microfacet_color(position, normal, light, camera_dir, material);
+        pre_color += microfacet_color(position, normal, light, camera_dir, material)
+            * shadow_factor(light, position, normal);
     }
-    pre_color = clamp(pre_color, 0.0, 1.0);
+    pre_color = apply_tone_mapping(pre_color);
     pre_color = ambient_correction(pre_color, material);
     color = vec4(pre_color, 1.0);
 }
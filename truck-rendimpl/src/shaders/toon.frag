@@ -0,0 +1,36 @@
+#version 450
+
+layout(location = 0) in vec3 position;
+layout(location = 2) in vec3 vertex_normal;
+
+layout(set = 0, binding = 0) uniform Camera {
+    mat4 camera_matrix;
+    mat4 camera_projection;
+};
+
+#include "scene-module.frag"
+
+layout(set = 1, binding = 1) uniform Toon {
+    vec4 base_color;
+    vec4 hatch_color;
+    vec4 toon_parameters; // (bands, hatching ? 1.0 : 0.0, hatch spacing, 0.0)
+};
+
+layout(location = 0) out vec4 color;
+
+void main() {
+    if (is_clipped(position)) discard;
+    vec3 normal = normalize(vertex_normal);
+    // the head light from the camera
+    vec3 light_dir = normalize(camera_matrix[3].xyz - position);
+    float intensity = abs(dot(normal, light_dir));
+    float bands = toon_parameters.x;
+    float shade = min((floor(intensity * bands) + 1.0) / bands, 1.0);
+
+    // the diagonal hatching in the screen space, denser in the darker bands
+    float stripe = fract((gl_FragCoord.x + gl_FragCoord.y) / toon_parameters.z);
+    float hatch = 1.0 - step(1.0 - shade, stripe);
+    vec4 hatched = mix(base_color, hatch_color, vec4(hatch));
+    vec4 shaded = vec4(base_color.rgb * shade, base_color.a);
+    color = mix(shaded, hatched, vec4(toon_parameters.y));
+}
@@ -10,6 +10,11 @@ struct AttrVertex {
     pub boundary_range: [u32; 2],
 }
 
+impl VertexPosition for AttrVertex {
+    #[inline(always)]
+    fn position(&self) -> [f32; 3] { self.position }
+}
+
 impl Default for ShapeInstanceDescriptor {
     #[inline(always)]
     fn default() -> Self {
@@ -70,6 +75,7 @@ fn add_face(
     let ExpandedPolygon {
         ref mut vertices,
         ref mut indices,
+        ..
     } = expolygon;
     let inf = boundaries.len() as u32;
     let index_offset = vertices.len() as u32;
@@ -120,16 +126,15 @@ fn add_face(
             }),
     );
     let len = mesh.positions()[0].len() as u32;
-    (1..mesh.positions().len() as u32)
-        .flat_map(move |i| (1..len).map(move |j| (i, j)))
-        .for_each(move |(i, j)| {
-            indices.push(index_offset + (i - 1) * len + (j - 1));
-            indices.push(index_offset + i * len + (j - 1));
-            indices.push(index_offset + (i - 1) * len + j);
+    for i in 1..mesh.positions().len() as u32 {
+        if !indices.is_empty() {
+            indices.push(PRIMITIVE_RESTART);
+        }
+        for j in 0..len {
             indices.push(index_offset + (i - 1) * len + j);
-            indices.push(index_offset + i * len + (j - 1));
             indices.push(index_offset + i * len + j);
-        });
+        }
+    }
     Some(())
 }
 
@@ -149,14 +154,17 @@ impl TryIntoInstance<ShapeInstance> for Shell {
         desc: &ShapeInstanceDescriptor,
     ) -> Option<ShapeInstance> {
         let device = creator.handler.device();
-        let mut expolygon = ExpandedPolygon::default();
+        let mut expolygon = ExpandedPolygon {
+            primitive_topology: PrimitiveTopology::TriangleStrip,
+            ..Default::default()
+        };
         let mut boundaries = Vec::new();
         self.face_iter().try_for_each(|face| {
             add_face(face, desc.mesh_precision, &mut expolygon, &mut boundaries)
         })?;
-        let (vb, ib) = expolygon.buffers(BufferUsage::VERTEX, BufferUsage::INDEX, device);
+        let polygon = expolygon.polygon_buffers(BufferUsage::VERTEX, BufferUsage::INDEX, device);
         Some(ShapeInstance {
-            polygon: (Arc::new(vb), Arc::new(ib)),
+            polygon,
             boundary: Arc::new(BufferHandler::from_slice(
                 &boundaries,
                 device,
@@ -197,7 +205,6 @@ impl IntoInstance<WireFrameInstance> for Shell {
         creator: &InstanceCreator,
         desc: &ShapeWireFrameInstanceDescriptor,
     ) -> WireFrameInstance {
-        let handler = &creator.handler;
         let mut lengths = Vec::new();
         let points: Vec<[f32; 3]> = self
             .face_iter()
@@ -221,15 +228,7 @@ impl IntoInstance<WireFrameInstance> for Shell {
             }
             counter += len;
         }
-        let vertices = BufferHandler::from_slice(&points, handler.device(), BufferUsage::VERTEX);
-        let strips = BufferHandler::from_slice(&strips, handler.device(), BufferUsage::INDEX);
-        WireFrameInstance {
-            vertices: Arc::new(vertices),
-            strips: Arc::new(strips),
-            state: desc.wireframe_state.clone(),
-            shaders: Arc::clone(&creator.wire_shaders),
-            id: RenderID::gen(),
-        }
+        creator.wire_frame_instance(&points, &strips, &desc.wireframe_state)
     }
 }
 
@@ -249,7 +248,10 @@ impl TryIntoInstance<ShapeInstance> for Solid {
         desc: &ShapeInstanceDescriptor,
     ) -> Option<ShapeInstance> {
         let device = creator.handler.device();
-        let mut expolygon = ExpandedPolygon::default();
+        let mut expolygon = ExpandedPolygon {
+            primitive_topology: PrimitiveTopology::TriangleStrip,
+            ..Default::default()
+        };
         let mut boundaries = Vec::new();
         self.boundaries()
             .iter()
@@ -257,9 +259,9 @@ impl TryIntoInstance<ShapeInstance> for Solid {
             .try_for_each(|face| {
                 add_face(face, desc.mesh_precision, &mut expolygon, &mut boundaries)
             })?;
-        let (vb, ib) = expolygon.buffers(BufferUsage::VERTEX, BufferUsage::INDEX, device);
+        let polygon = expolygon.polygon_buffers(BufferUsage::VERTEX, BufferUsage::INDEX, device);
         Some(ShapeInstance {
-            polygon: (Arc::new(vb), Arc::new(ib)),
+            polygon,
             boundary: Arc::new(BufferHandler::from_slice(
                 &boundaries,
                 device,
@@ -300,7 +302,6 @@ impl IntoInstance<WireFrameInstance> for Solid {
         creator: &InstanceCreator,
         desc: &ShapeWireFrameInstanceDescriptor,
     ) -> WireFrameInstance {
-        let handler = &creator.handler;
         let mut lengths = Vec::new();
         let points: Vec<[f32; 3]> = self
             .boundaries()
@@ -326,15 +327,35 @@ impl IntoInstance<WireFrameInstance> for Solid {
             }
             counter += len;
         }
-        let vertices = BufferHandler::from_slice(&points, handler.device(), BufferUsage::VERTEX);
-        let strips = BufferHandler::from_slice(&strips, handler.device(), BufferUsage::INDEX);
-        WireFrameInstance {
-            vertices: Arc::new(vertices),
-            strips: Arc::new(strips),
-            state: desc.wireframe_state.clone(),
-            shaders: Arc::clone(&creator.wire_shaders),
-            id: RenderID::gen(),
+        creator.wire_frame_instance(&points, &strips, &desc.wireframe_state)
+    }
+}
+
+impl IntoInstance<WireFrameInstance> for thread::Thread {
+    type Descriptor = ShapeWireFrameInstanceDescriptor;
+    /// Creates the overlay of the cosmetic thread.
+    fn into_instance(
+        &self,
+        creator: &InstanceCreator,
+        desc: &ShapeWireFrameInstanceDescriptor,
+    ) -> WireFrameInstance {
+        let polylines = self.overlay(desc.polyline_precision);
+        let points: Vec<[f32; 3]> = polylines
+            .iter()
+            .flatten()
+            .map(|pt| pt.cast().unwrap().into())
+            .collect();
+        let mut strips = Vec::<u32>::new();
+        let mut counter = 0_u32;
+        for polyline in &polylines {
+            let len = polyline.len() as u32;
+            for i in 1..len {
+                strips.push(counter + i - 1);
+                strips.push(counter + i);
+            }
+            counter += len;
         }
+        creator.wire_frame_instance(&points, &strips, &desc.wireframe_state)
     }
 }
 
@@ -353,29 +374,23 @@ impl ShapeInstance {
     }
 
     #[inline(always)]
-    fn non_textured_bgl(device: &Device) -> BindGroupLayout {
-        bind_group_util::create_bind_group_layout(
-            device,
-            &[
-                InstanceState::matrix_bgl_entry(),
-                InstanceState::material_bgl_entry(),
-                Self::boundary_bgl_entry(),
-            ],
-        )
+    fn non_textured_bgl(handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        handler.cached_bind_group_layout(&[
+            InstanceState::matrix_bgl_entry(),
+            InstanceState::material_bgl_entry(),
+            Self::boundary_bgl_entry(),
+        ])
     }
 
     #[inline(always)]
-    fn textured_bgl(device: &Device) -> BindGroupLayout {
-        bind_group_util::create_bind_group_layout(
-            device,
-            &[
-                InstanceState::matrix_bgl_entry(),
-                InstanceState::material_bgl_entry(),
-                InstanceState::textureview_bgl_entry(),
-                InstanceState::sampler_bgl_entry(),
-                Self::boundary_bgl_entry(),
-            ],
-        )
+    fn textured_bgl(handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        handler.cached_bind_group_layout(&[
+            InstanceState::matrix_bgl_entry(),
+            InstanceState::material_bgl_entry(),
+            InstanceState::textureview_bgl_entry(),
+            InstanceState::sampler_bgl_entry(),
+            Self::boundary_bgl_entry(),
+        ])
     }
 
     #[inline(always)]
@@ -473,9 +488,30 @@ impl ShapeInstance {
         device_handler: &DeviceHandler,
         layout: &PipelineLayout,
         sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        self.pipeline_with_write_mask(
+            vertex_module,
+            fragment_module,
+            ColorWrite::ALL,
+            device_handler,
+            layout,
+            sample_count,
+        )
+    }
+
+    // the pipeline writing only the channels in `write_mask`, e.g. no channels for occluders
+    pub(crate) fn pipeline_with_write_mask(
+        &self,
+        vertex_module: &ShaderModule,
+        fragment_module: &ShaderModule,
+        write_mask: ColorWrite,
+        device_handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
     ) -> Arc<RenderPipeline> {
         let device = device_handler.device();
         let sc_desc = device_handler.sc_desc();
+        let depth_bias = self.state.depth_bias.oriented(device_handler);
         let cull_mode = match self.state.backface_culling {
             true => CullMode::Back,
             false => CullMode::None,
@@ -493,26 +529,26 @@ impl ShapeInstance {
             rasterization_state: Some(RasterizationStateDescriptor {
                 front_face: FrontFace::Ccw,
                 cull_mode,
-                depth_bias: 0,
-                depth_bias_slope_scale: 0.0,
-                depth_bias_clamp: 0.0,
+                depth_bias: depth_bias.constant,
+                depth_bias_slope_scale: depth_bias.slope_scale as f32,
+                depth_bias_clamp: depth_bias.clamp as f32,
                 clamp_depth: false,
             }),
-            primitive_topology: PrimitiveTopology::TriangleList,
+            primitive_topology: self.polygon.primitive_topology,
             color_states: &[ColorStateDescriptor {
                 format: sc_desc.format,
                 color_blend: BlendDescriptor::REPLACE,
                 alpha_blend: BlendDescriptor::REPLACE,
-                write_mask: ColorWrite::ALL,
+                write_mask,
             }],
             depth_stencil_state: Some(DepthStencilStateDescriptor {
                 format: TextureFormat::Depth32Float,
                 depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
+                depth_compare: device_handler.depth_compare(wgpu::CompareFunction::Less),
                 stencil: StencilStateDescriptor::default(),
             }),
             vertex_state: VertexStateDescriptor {
-                index_format: IndexFormat::Uint32,
+                index_format: self.polygon.index_format,
                 vertex_buffers: &[VertexBufferDescriptor {
                     stride: std::mem::size_of::<AttrVertex>() as BufferAddress,
                     step_mode: InputStepMode::Vertex,
@@ -551,18 +587,35 @@ impl ShapeInstance {
 
 impl Rendered for ShapeInstance {
     impl_render_id!(id);
+    impl_pipeline_builder!(clone_instance);
+
+    #[inline(always)]
+    fn picking_mesh(&self) -> Option<(Arc<PickingMesh>, Matrix4)> {
+        Some((Arc::clone(&self.polygon.picking), self.state.world_matrix()))
+    }
 
     #[inline(always)]
     fn vertex_buffer(&self, _: &DeviceHandler) -> (Arc<BufferHandler>, Option<Arc<BufferHandler>>) {
-        let (vb, ib) = self.polygon.clone();
+        let (vb, ib) = self.polygon.chunks[0].clone();
         (vb, Some(ib))
     }
     #[inline(always)]
+    fn vertex_buffers(
+        &self,
+        _: &DeviceHandler,
+    ) -> Vec<(Arc<BufferHandler>, Option<Arc<BufferHandler>>)> {
+        self.polygon
+            .chunks
+            .iter()
+            .map(|(vb, ib)| (Arc::clone(vb), Some(Arc::clone(ib))))
+            .collect()
+    }
+    #[inline(always)]
     fn bind_group_layout(&self, handler: &DeviceHandler) -> Arc<BindGroupLayout> {
-        Arc::new(match self.state.texture.is_some() {
-            true => Self::textured_bgl(handler.device()),
-            false => Self::non_textured_bgl(handler.device()),
-        })
+        match self.state.texture.is_some() {
+            true => Self::textured_bgl(handler),
+            false => Self::non_textured_bgl(handler),
+        }
     }
     #[inline(always)]
     fn bind_group(&self, handler: &DeviceHandler, layout: &BindGroupLayout) -> Arc<BindGroup> {
@@ -579,17 +632,27 @@ impl Rendered for ShapeInstance {
         layout: &PipelineLayout,
         sample_count: u32,
     ) -> Arc<RenderPipeline> {
-        let fragment_shader = match self.state.texture.is_some() {
-            true => &self.shaders.tex_fragment,
-            false => &self.shaders.fragment,
+        let (fragment_shader, shader) = match self.state.texture.is_some() {
+            true => (&self.shaders.tex_fragment, "truck-rendimpl/textured-shape"),
+            false => (&self.shaders.fragment, "truck-rendimpl/shape"),
         };
-        self.pipeline_with_shader_module(
-            &self.shaders.vertex,
-            fragment_shader,
-            handler,
-            layout,
-            sample_count,
-        )
+        let vertex_layout = "truck-rendimpl/shape-vertex";
+        let key = PipelineKey {
+            primitive_topology: self.polygon.primitive_topology,
+            index_format: self.polygon.index_format,
+            ..self
+                .state
+                .pipeline_key(shader, vertex_layout, handler, sample_count)
+        };
+        handler.cached_pipeline(&key, || {
+            self.pipeline_with_shader_module(
+                &self.shaders.vertex,
+                fragment_shader,
+                handler,
+                layout,
+                sample_count,
+            )
+        })
     }
 }
 
@@ -0,0 +1,285 @@
+use crate::*;
+
+impl TryIntoInstance<SkinnedInstance> for PolygonMesh {
+    type Descriptor = PolygonInstanceDescriptor;
+    /// Tries to create `SkinnedInstance` from `PolygonMesh`.
+    /// # Failures
+    /// Failure occurs when the polygon mesh has no skin.
+    fn try_into_instance(
+        &self,
+        creator: &InstanceCreator,
+        desc: &PolygonInstanceDescriptor,
+    ) -> Option<SkinnedInstance> {
+        let skin = self.skin()?;
+        let joints = VertexAttribute {
+            shader_location: 3,
+            values: AttributeValues::Uint4(
+                skin.joints
+                    .iter()
+                    .map(|joints| {
+                        let mut res = [0; 4];
+                        res.iter_mut().zip(joints).for_each(|(x, j)| *x = *j as u32);
+                        res
+                    })
+                    .collect(),
+            ),
+        };
+        let weights = VertexAttribute {
+            shader_location: 4,
+            values: AttributeValues::Float4(
+                skin.weights
+                    .iter()
+                    .map(|weights| {
+                        let mut res = [0.0; 4];
+                        res.iter_mut()
+                            .zip(weights)
+                            .for_each(|(x, w)| *x = *w as f32);
+                        res
+                    })
+                    .collect(),
+            ),
+        };
+        let instance = creator.create_instance_with_attributes(self, desc, &[joints, weights]);
+        // the bind pose
+        let joint_matrices = skin
+            .inverse_bind_matrices
+            .iter()
+            .map(|matrix| matrix.invert().unwrap_or_else(Matrix4::identity))
+            .collect();
+        Some(SkinnedInstance {
+            instance,
+            inverse_bind_matrices: skin.inverse_bind_matrices.clone(),
+            joint_matrices,
+        })
+    }
+}
+
+impl SkinnedInstance {
+    /// Returns the matrices of the joints at the current pose.
+    /// The default pose is the bind pose.
+    #[inline(always)]
+    pub fn joint_matrices(&self) -> &[Matrix4] { &self.joint_matrices }
+
+    /// Sets the matrices of the joints at the current pose, e.g. the global transforms of
+    /// the nodes of the joints interpolated from the animation channels of glTF.
+    ///
+    /// The pose is reflected by `Scene::update_bind_group`.
+    /// # Panics
+    /// Panics if the number of the matrices is not the one of the joints of the skin.
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_platform::*;
+    /// use truck_rendimpl::*;
+    /// use truck_platform::wgpu::*;
+    /// # let instance = Instance::new(BackendBit::PRIMARY);
+    /// # let (device, queue, _) =
+    /// #     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// # let sc_desc = SwapChainDescriptor {
+    /// #     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    /// #     format: TextureFormat::Rgba8UnormSrgb,
+    /// #     width: 256,
+    /// #     height: 256,
+    /// #     present_mode: PresentMode::Mailbox,
+    /// # };
+    /// # let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// let mut scene = Scene::new(handler, &Default::default());
+    /// let creator = scene.instance_creator();
+    /// let mut mesh = PolygonMesh::new(
+    ///     vec![
+    ///         Point3::new(-1.0, -1.0, -3.0),
+    ///         Point3::new(1.0, -1.0, -3.0),
+    ///         Point3::new(0.0, 1.0, -3.0),
+    ///     ],
+    ///     Vec::new(),
+    ///     vec![Vector3::new(0.0, 0.0, 1.0)],
+    ///     Faces::from_iter(&[&[(0, None, Some(0)), (1, None, Some(0)), (2, None, Some(0))]]),
+    /// );
+    /// // the top vertex is moved by the second joint.
+    /// mesh.set_skin(Skin {
+    ///     joints: vec![[0, 0, 0, 0], [0, 0, 0, 0], [1, 0, 0, 0]],
+    ///     weights: vec![[1.0, 0.0, 0.0, 0.0]; 3],
+    ///     inverse_bind_matrices: vec![Matrix4::identity(); 2],
+    /// })
+    /// .unwrap();
+    /// let mut skinned: SkinnedInstance =
+    ///     creator.try_create_instance(&mesh, &Default::default()).unwrap();
+    /// scene.add_object(&skinned);
+    /// skinned.set_joint_matrices(&[
+    ///     Matrix4::identity(),
+    ///     Matrix4::from_translation(Vector3::new(0.5, 0.0, 0.0)),
+    /// ]);
+    /// scene.update_bind_group(&skinned);
+    /// ```
+    #[inline(always)]
+    pub fn set_joint_matrices(&mut self, joint_matrices: &[Matrix4]) {
+        self.joint_matrices.copy_from_slice(joint_matrices);
+    }
+
+    /// Returns a reference to the instance descriptor.
+    #[inline(always)]
+    pub fn instance_state(&self) -> &InstanceState { self.instance.instance_state() }
+    /// Returns the mutable reference to the instance descriptor.
+    #[inline(always)]
+    pub fn instance_state_mut(&mut self) -> &mut InstanceState {
+        self.instance.instance_state_mut()
+    }
+    // the duplication sharing the mesh data with `self`, used for re-creating the pipeline
+    #[inline(always)]
+    fn clone_instance(&self) -> SkinnedInstance {
+        SkinnedInstance {
+            instance: self.instance.clone_instance(),
+            inverse_bind_matrices: self.inverse_bind_matrices.clone(),
+            joint_matrices: self.joint_matrices.clone(),
+        }
+    }
+
+    /// Creates a `STORAGE` buffer of the products of the joint matrices and the inverse bind
+    /// matrices.
+    ///
+    /// # Shader Example
+    /// ```glsl
+    /// layout(set = 1, binding = 2) readonly buffer Joints {
+    ///     mat4 joint_matrices[];
+    /// };
+    /// ```
+    pub fn joints_buffer(&self, device: &Device) -> BufferHandler {
+        let mut matrices: Vec<[[f32; 4]; 4]> = self
+            .joint_matrices
+            .iter()
+            .zip(&self.inverse_bind_matrices)
+            .map(|(joint, inverse)| (joint * inverse).cast::<f32>().unwrap().into())
+            .collect();
+        // The empty buffer cannot be bound.
+        if matrices.is_empty() {
+            matrices.push(Matrix4::identity().cast::<f32>().unwrap().into());
+        }
+        BufferHandler::from_slice(&matrices, device, BufferUsage::STORAGE)
+    }
+
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn joints_bgl_entry() -> PreBindGroupLayoutEntry {
+        PreBindGroupLayoutEntry {
+            visibility: ShaderStage::VERTEX,
+            ty: BindingType::StorageBuffer {
+                dynamic: false,
+                min_binding_size: None,
+                readonly: true,
+            },
+            count: None,
+        }
+    }
+
+    /// Returns the default vertex shader module source for non-textured polygons.
+    ///
+    /// The GLSL original code is `src/shaders/skinned-polygon.vert`.
+    #[inline(always)]
+    pub fn default_vertex_shader() -> ShaderModuleSource<'static> {
+        include_spirv!("shaders/skinned-polygon.vert.spv")
+    }
+
+    /// Returns the default vertex shader module source for textured polygons.
+    ///
+    /// The GLSL original code is `src/shaders/skinned-textured-polygon.vert`.
+    #[inline(always)]
+    pub fn default_textured_vertex_shader() -> ShaderModuleSource<'static> {
+        include_spirv!("shaders/skinned-textured-polygon.vert.spv")
+    }
+}
+
+impl Rendered for SkinnedInstance {
+    derive_render_id!(instance);
+    impl_pipeline_builder!(clone_instance);
+    derive_vertex_buffer!(instance);
+    #[inline(always)]
+    fn bind_group_layout(&self, handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        match self.instance.state.texture.is_some() {
+            true => handler.cached_bind_group_layout(&[
+                InstanceState::matrix_bgl_entry(),
+                InstanceState::material_bgl_entry(),
+                InstanceState::textureview_bgl_entry(),
+                InstanceState::sampler_bgl_entry(),
+                SkinnedInstance::joints_bgl_entry(),
+            ]),
+            false => handler.cached_bind_group_layout(&[
+                InstanceState::matrix_bgl_entry(),
+                InstanceState::material_bgl_entry(),
+                SkinnedInstance::joints_bgl_entry(),
+            ]),
+        }
+    }
+    #[inline(always)]
+    fn bind_group(&self, handler: &DeviceHandler, layout: &BindGroupLayout) -> Arc<BindGroup> {
+        let device = handler.device();
+        let state = &self.instance.state;
+        let (matrix, material) = (state.matrix_buffer(device), state.material_buffer(device));
+        let joints = self.joints_buffer(device);
+        Arc::new(match state.texture.is_some() {
+            true => {
+                let (view, sampler) = state.textureview_and_sampler(device);
+                bind_group_util::create_bind_group(
+                    device,
+                    layout,
+                    vec![
+                        matrix.binding_resource(),
+                        material.binding_resource(),
+                        BindingResource::TextureView(&view),
+                        BindingResource::Sampler(&sampler),
+                        joints.binding_resource(),
+                    ],
+                )
+            }
+            false => bind_group_util::create_bind_group(
+                device,
+                layout,
+                vec![
+                    matrix.binding_resource(),
+                    material.binding_resource(),
+                    joints.binding_resource(),
+                ],
+            ),
+        })
+    }
+    #[inline(always)]
+    fn pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let instance = &self.instance;
+        let shaders = &instance.shaders;
+        let (vertex_shader, fragment_shader, shader) = match instance.state.texture.is_some() {
+            true => (
+                &shaders.skinned_tex_vertex,
+                &shaders.tex_fragment,
+                "truck-rendimpl/skinned-textured-polygon",
+            ),
+            false => (
+                &shaders.skinned_vertex,
+                &shaders.fragment,
+                "truck-rendimpl/skinned-polygon",
+            ),
+        };
+        let key = PipelineKey {
+            primitive_topology: instance.polygon.primitive_topology,
+            index_format: instance.polygon.index_format,
+            ..instance.state.pipeline_key(
+                shader,
+                &instance.polygon.vertex_layout(),
+                handler,
+                sample_count,
+            )
+        };
+        handler.cached_pipeline(&key, || {
+            instance.pipeline_with_shader_module(
+                vertex_shader,
+                fragment_shader,
+                handler,
+                layout,
+                sample_count,
+            )
+        })
+    }
+}
@@ -0,0 +1,203 @@
+use crate::*;
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod)]
+struct ToonInfo {
+    color: [f32; 4],
+    hatch_color: [f32; 4],
+    parameters: [f32; 4],
+}
+
+impl Default for ToonState {
+    #[inline(always)]
+    fn default() -> ToonState {
+        ToonState {
+            color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            bands: 3,
+            hatching: false,
+            hatch_spacing: 8.0,
+            hatch_color: Vector4::new(0.0, 0.0, 0.0, 1.0),
+        }
+    }
+}
+
+impl ToonState {
+    #[inline(always)]
+    fn toon_info(&self) -> ToonInfo {
+        ToonInfo {
+            color: self.color.cast().unwrap().into(),
+            hatch_color: self.hatch_color.cast().unwrap().into(),
+            parameters: [
+                u32::max(self.bands, 1) as f32,
+                if self.hatching { 1.0 } else { 0.0 },
+                self.hatch_spacing as f32,
+                0.0,
+            ],
+        }
+    }
+
+    /// Creates a `UNIFORM` buffer of the toon shading.
+    ///
+    /// # Shader Example
+    /// ```glsl
+    /// layout(set = 1, binding = 1) uniform Toon {
+    ///     vec4 base_color;
+    ///     vec4 hatch_color;
+    ///     vec4 toon_parameters; // (bands, hatching ? 1.0 : 0.0, hatch spacing, 0.0)
+    /// };
+    /// ```
+    #[inline(always)]
+    pub fn buffer(&self, device: &Device) -> BufferHandler {
+        BufferHandler::from_slice(&[self.toon_info()], device, BufferUsage::UNIFORM)
+    }
+
+    #[doc(hidden)]
+    #[inline(always)]
+    pub fn bgl_entry() -> PreBindGroupLayoutEntry {
+        PreBindGroupLayoutEntry {
+            visibility: ShaderStage::FRAGMENT,
+            ty: BindingType::UniformBuffer {
+                dynamic: false,
+                min_binding_size: None,
+            },
+            count: None,
+        }
+    }
+}
+
+impl PolygonInstance {
+    /// Creates the toon shading instance of `self`, which shares the mesh data with `self`.
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_platform::*;
+    /// use truck_rendimpl::*;
+    /// use truck_platform::wgpu::*;
+    /// # let instance = Instance::new(BackendBit::PRIMARY);
+    /// # let (device, queue, _) =
+    /// #     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// # let sc_desc = SwapChainDescriptor {
+    /// #     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    /// #     format: TextureFormat::Rgba8UnormSrgb,
+    /// #     width: 256,
+    /// #     height: 256,
+    /// #     present_mode: PresentMode::Mailbox,
+    /// # };
+    /// # let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// let mut scene = Scene::new(handler, &Default::default());
+    /// let creator = scene.instance_creator();
+    /// let mesh = PolygonMesh::new(
+    ///     vec![
+    ///         Point3::new(-1.0, -1.0, -3.0),
+    ///         Point3::new(1.0, -1.0, -3.0),
+    ///         Point3::new(0.0, 1.0, -3.0),
+    ///     ],
+    ///     Vec::new(),
+    ///     vec![Vector3::new(0.0, 0.0, 1.0)],
+    ///     Faces::from_iter(&[&[(0, None, Some(0)), (1, None, Some(0)), (2, None, Some(0))]]),
+    /// );
+    /// let polygon: PolygonInstance = creator.create_instance(&mesh, &Default::default());
+    /// // the patent drawing style: white faces with black hatching and outlines
+    /// let toon = polygon.toon_instance(ToonState {
+    ///     hatching: true,
+    ///     ..Default::default()
+    /// });
+    /// let outline = polygon.outline_instance(Default::default());
+    /// scene.add_object(&toon);
+    /// scene.add_object(&outline);
+    /// ```
+    #[inline(always)]
+    pub fn toon_instance(&self, toon: ToonState) -> ToonInstance {
+        ToonInstance {
+            instance: self.clone_instance(),
+            toon,
+        }
+    }
+}
+
+impl ToonInstance {
+    /// Returns a reference to the configures of the toon shading.
+    #[inline(always)]
+    pub fn toon_state(&self) -> &ToonState { &self.toon }
+    /// Returns the mutable reference to the configures of the toon shading.
+    #[inline(always)]
+    pub fn toon_state_mut(&mut self) -> &mut ToonState { &mut self.toon }
+    /// Returns a reference to the instance descriptor.
+    ///
+    /// The material and the texture are ignored.
+    #[inline(always)]
+    pub fn instance_state(&self) -> &InstanceState { self.instance.instance_state() }
+    /// Returns the mutable reference to the instance descriptor.
+    #[inline(always)]
+    pub fn instance_state_mut(&mut self) -> &mut InstanceState {
+        self.instance.instance_state_mut()
+    }
+    // the duplication sharing the mesh data with `self`, used for re-creating the pipeline
+    #[inline(always)]
+    fn clone_instance(&self) -> ToonInstance {
+        ToonInstance {
+            instance: self.instance.clone_instance(),
+            toon: self.toon,
+        }
+    }
+
+    /// Returns the default fragment shader module source.
+    ///
+    /// The GLSL original code is `src/shaders/toon.frag`.
+    #[inline(always)]
+    pub fn default_fragment_shader() -> ShaderModuleSource<'static> {
+        include_spirv!("shaders/toon.frag.spv")
+    }
+}
+
+impl Rendered for ToonInstance {
+    derive_render_id!(instance);
+    impl_pipeline_builder!(clone_instance);
+    derive_vertex_buffer!(instance);
+    derive_picking_mesh!(instance);
+    #[inline(always)]
+    fn bind_group_layout(&self, handler: &DeviceHandler) -> Arc<BindGroupLayout> {
+        handler
+            .cached_bind_group_layout(&[InstanceState::matrix_bgl_entry(), ToonState::bgl_entry()])
+    }
+    #[inline(always)]
+    fn bind_group(&self, handler: &DeviceHandler, layout: &BindGroupLayout) -> Arc<BindGroup> {
+        let device = handler.device();
+        Arc::new(bind_group_util::create_bind_group(
+            device,
+            layout,
+            vec![
+                self.instance.state.matrix_buffer(device).binding_resource(),
+                self.toon.buffer(device).binding_resource(),
+            ],
+        ))
+    }
+    #[inline(always)]
+    fn pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let instance = &self.instance;
+        let key = PipelineKey {
+            primitive_topology: instance.polygon.primitive_topology,
+            index_format: instance.polygon.index_format,
+            ..instance.state.pipeline_key(
+                "truck-rendimpl/toon-polygon",
+                &instance.polygon.vertex_layout(),
+                handler,
+                sample_count,
+            )
+        };
+        handler.cached_pipeline(&key, || {
+            instance.pipeline_with_shader_module(
+                &instance.shaders.vertex,
+                &instance.shaders.toon_fragment,
+                handler,
+                layout,
+                sample_count,
+            )
+        })
+    }
+}
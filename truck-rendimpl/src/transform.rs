@@ -0,0 +1,123 @@
+use crate::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// the global counter of the modifications of the nodes
+static REVISION: AtomicU64 = AtomicU64::new(1);
+
+#[inline(always)]
+fn next_revision() -> u64 { REVISION.fetch_add(1, Ordering::SeqCst) }
+
+impl TransformNode {
+    /// Creates the root node with the local matrix `matrix`.
+    #[inline(always)]
+    pub fn new(matrix: Matrix4) -> TransformNode {
+        TransformNode(Arc::new(Mutex::new(TransformNodeData {
+            matrix,
+            parent: None,
+            revision: next_revision(),
+            cache: None,
+        })))
+    }
+
+    /// Creates the child node of `parent` with the local matrix `matrix`.
+    #[inline(always)]
+    pub fn with_parent(matrix: Matrix4, parent: &TransformNode) -> TransformNode {
+        let node = TransformNode::new(matrix);
+        node.0.lock().unwrap().parent = Some(parent.clone());
+        node
+    }
+
+    /// Returns the local matrix in the coordinate of the parent.
+    #[inline(always)]
+    pub fn matrix(&self) -> Matrix4 { self.0.lock().unwrap().matrix }
+
+    /// Sets the local matrix in the coordinate of the parent.
+    #[inline(always)]
+    pub fn set_matrix(&self, matrix: Matrix4) {
+        let mut data = self.0.lock().unwrap();
+        data.matrix = matrix;
+        data.revision = next_revision();
+    }
+
+    /// Returns the parent node.
+    #[inline(always)]
+    pub fn parent(&self) -> Option<TransformNode> { self.0.lock().unwrap().parent.clone() }
+
+    /// Sets the parent node.
+    ///
+    /// Returns `false` and does nothing if `parent` is `self` or a descendant of `self`,
+    /// since the hierarchy must not have cycles.
+    /// # Examples
+    /// ```
+    /// use truck_rendimpl::*;
+    /// let assembly = TransformNode::new(Matrix4::identity());
+    /// let part = TransformNode::with_parent(Matrix4::identity(), &assembly);
+    /// assert!(!assembly.set_parent(Some(&part)));
+    /// assert!(part.set_parent(None));
+    /// assert!(assembly.set_parent(Some(&part)));
+    /// ```
+    pub fn set_parent(&self, parent: Option<&TransformNode>) -> bool {
+        let mut ancestor = parent.cloned();
+        while let Some(node) = ancestor {
+            if Arc::ptr_eq(&node.0, &self.0) {
+                return false;
+            }
+            ancestor = node.parent();
+        }
+        let mut data = self.0.lock().unwrap();
+        data.parent = parent.cloned();
+        data.revision = next_revision();
+        true
+    }
+
+    /// Returns the matrix in the world coordinate, i.e. the product of the local matrices of
+    /// the ancestors and `self`.
+    ///
+    /// The world matrix is cached, and recomputed only if `self` or an ancestor is modified.
+    /// # Examples
+    /// ```
+    /// use truck_rendimpl::*;
+    /// let assembly = TransformNode::new(Matrix4::from_translation(Vector3::unit_x()));
+    /// let part = TransformNode::with_parent(Matrix4::from_scale(2.0), &assembly);
+    /// let revision = part.revision();
+    /// assert_eq!(
+    ///     part.world_matrix(),
+    ///     Matrix4::from_translation(Vector3::unit_x()) * Matrix4::from_scale(2.0),
+    /// );
+    /// assert_eq!(part.revision(), revision);
+    ///
+    /// assembly.set_matrix(Matrix4::identity());
+    /// assert_eq!(part.world_matrix(), Matrix4::from_scale(2.0));
+    /// assert_ne!(part.revision(), revision);
+    /// ```
+    #[inline(always)]
+    pub fn world_matrix(&self) -> Matrix4 { self.world().1 }
+
+    /// Returns the revision of the world matrix, which changes whenever `self` or an ancestor
+    /// is modified. The instances need not be updated if the revision is not changed.
+    #[inline(always)]
+    pub fn revision(&self) -> u64 { self.world().0 }
+
+    fn world(&self) -> (u64, Matrix4) {
+        let mut guard = self.0.lock().unwrap();
+        let data = &mut *guard;
+        let (revision, parent_matrix) = match &data.parent {
+            Some(parent) => {
+                let (revision, matrix) = parent.world();
+                (u64::max(revision, data.revision), Some(matrix))
+            }
+            None => (data.revision, None),
+        };
+        match data.cache {
+            Some(cache) if cache.0 == revision => cache,
+            _ => {
+                let matrix = match parent_matrix {
+                    Some(parent_matrix) => parent_matrix * data.matrix,
+                    None => data.matrix,
+                };
+                data.cache = Some((revision, matrix));
+                (revision, matrix)
+            }
+        }
+    }
+}
@@ -0,0 +1,236 @@
+//! The turntable cameras orbiting around the visual center of a model, and the offscreen
+//! capture of the frames, e.g. for the automated generation of the product images.
+//!
+//! The visual center and the radius are estimated robustly: the center is the median of the
+//! sample points, and the radius is the quantile of the distances, so that a few outliers,
+//! e.g. a stray part placed far away, do not shrink the model in the images.
+//! # Examples
+//! ```
+//! use std::sync::{Arc, Mutex};
+//! use truck_platform::*;
+//! use truck_rendimpl::*;
+//! use truck_rendimpl::turntable::*;
+//! use truck_platform::wgpu::*;
+//! let instance = Instance::new(BackendBit::PRIMARY);
+//! let (device, queue, _) =
+//!     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+//! let sc_desc = SwapChainDescriptor {
+//!     usage: TextureUsage::OUTPUT_ATTACHMENT,
+//!     format: TextureFormat::Rgba8UnormSrgb,
+//!     width: 128,
+//!     height: 128,
+//!     present_mode: PresentMode::Mailbox,
+//! };
+//! let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+//! let mut scene = Scene::new(handler, &Default::default());
+//! let creator = scene.instance_creator();
+//! let mesh = PolygonMesh::new(
+//!     vec![
+//!         Point3::new(-1.0, -1.0, 0.0),
+//!         Point3::new(1.0, -1.0, 0.0),
+//!         Point3::new(0.0, 1.0, 0.0),
+//!     ],
+//!     Vec::new(),
+//!     Vec::new(),
+//!     Faces::from_iter(&[&[0, 1, 2], &[0, 2, 1]]),
+//! );
+//! let polygon: PolygonInstance = creator.create_instance(&mesh, &Default::default());
+//! scene.add_object(&polygon);
+//!
+//! let bounds = VisualBounds::from_points(mesh.positions(), 1.0);
+//! let option = TurntableOption {
+//!     frames: 4,
+//!     ..Default::default()
+//! };
+//! let cameras = turntable_cameras(&bounds, &scene.descriptor().camera, &option);
+//! let mut frames = Vec::new();
+//! capture_turntable(&mut scene, &cameras, |i, image| frames.push((i, image)));
+//! assert_eq!(frames.len(), 4);
+//! assert_eq!(frames[3].0, 3);
+//! assert_eq!(frames[3].1.dimensions(), (128, 128));
+//! ```
+
+use crate::*;
+use image::RgbaImage;
+use std::f64::consts::PI;
+use truck_modeling::assembly::{Assembly, Part};
+use truck_modeling::tessellation::tessellate_solid;
+
+/// The visual center and the radius of a model, estimated ignoring the outliers.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct VisualBounds {
+    /// the visual center, the component-wise median of the sample points
+    pub center: Point3,
+    /// the radius of the sphere containing the given quantile of the sample points
+    pub radius: f64,
+}
+
+/// The configures of the turntable cameras.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TurntableOption {
+    /// the number of the frames of a revolution. Default is `120`.
+    ///
+    /// The last frame is followed by the first one smoothly, so the frames can be looped.
+    pub frames: usize,
+    /// the angle of the camera above the horizontal plane.
+    /// Default is `Rad(PI / 9.0)`, i.e. 20 degrees.
+    pub elevation: Rad<f64>,
+    /// the azimuth of the first frame around the y-axis. Default is `Rad(0.0)`, i.e. the front.
+    pub start_angle: Rad<f64>,
+    /// the ratio of the margin to the radius of the visual bounds. Default is `1.1`.
+    pub margin: f64,
+}
+
+impl Default for TurntableOption {
+    #[inline(always)]
+    fn default() -> TurntableOption {
+        TurntableOption {
+            frames: 120,
+            elevation: Rad(PI / 9.0),
+            start_angle: Rad(0.0),
+            margin: 1.1,
+        }
+    }
+}
+
+impl VisualBounds {
+    /// Estimates the visual bounds of the sample points.
+    ///
+    /// `quantile` in `(0, 1]` is the ratio of the points contained in the sphere of the radius.
+    /// Returns the unit sphere at the origin if `points` is empty.
+    /// # Examples
+    /// ```
+    /// use truck_rendimpl::*;
+    /// use truck_rendimpl::turntable::VisualBounds;
+    /// let mut points: Vec<Point3> = (0..100)
+    ///     .map(|i| Point3::new((i % 10) as f64, (i / 10) as f64, 0.0) / 9.0)
+    ///     .collect();
+    /// // an outlier
+    /// points.push(Point3::new(100.0, 0.0, 0.0));
+    /// let bounds = VisualBounds::from_points(&points, 0.95);
+    /// assert!(bounds.center.distance(Point3::new(0.5, 0.5, 0.0)) < 0.1);
+    /// assert!(bounds.radius < 1.0);
+    /// ```
+    pub fn from_points(points: &[Point3], quantile: f64) -> VisualBounds {
+        if points.is_empty() {
+            return VisualBounds {
+                center: Point3::origin(),
+                radius: 1.0,
+            };
+        }
+        let median = |k: usize| {
+            let mut coords: Vec<f64> = points.iter().map(|p| p[k]).collect();
+            coords.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            coords[coords.len() / 2]
+        };
+        let center = Point3::new(median(0), median(1), median(2));
+        let mut distances: Vec<f64> = points.iter().map(|p| p.distance(center)).collect();
+        distances.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let len = distances.len();
+        let idx = (f64::min(f64::max(quantile, 0.0), 1.0) * len as f64).ceil() as usize;
+        let radius = distances[usize::max(usize::min(idx, len), 1) - 1];
+        VisualBounds {
+            center,
+            radius: f64::max(radius, TOLERANCE),
+        }
+    }
+
+    /// Estimates the visual bounds of the placed parts of `assembly`.
+    ///
+    /// The sample points are the corners of the bounding boxes of the placed parts, and each
+    /// part is tessellated only once with the tolerance `tol`, which may be coarse.
+    pub fn from_assembly(assembly: &Assembly, tol: f64, quantile: f64) -> VisualBounds {
+        let mut boxes: Vec<(Arc<Part>, BoundingBox<Point3>)> = Vec::new();
+        let mut points = Vec::new();
+        for (part, mat) in assembly.placed_parts() {
+            let bdd_box = match boxes.iter().find(|(p, _)| Arc::ptr_eq(p, &part)) {
+                Some((_, bdd_box)) => bdd_box.clone(),
+                None => {
+                    let bdd_box = tessellate_solid(&part.solid, tol).bounding_box();
+                    boxes.push((part, bdd_box.clone()));
+                    bdd_box
+                }
+            };
+            if bdd_box.is_empty() {
+                continue;
+            }
+            let (min, max) = (bdd_box.min(), bdd_box.max());
+            points.extend((0..8).map(|i| {
+                let corner = Point3::new(
+                    if i & 1 == 0 { min[0] } else { max[0] },
+                    if i & 2 == 0 { min[1] } else { max[1] },
+                    if i & 4 == 0 { min[2] } else { max[2] },
+                );
+                mat.transform_point(corner)
+            }));
+        }
+        VisualBounds::from_points(&points, quantile)
+    }
+}
+
+/// Returns the cameras orbiting around the y-axis through the center of `bounds`.
+///
+/// The cameras have the projection type and the field of view of `camera`, and are placed
+/// so that the sphere of the bounds with the margin fits the vertical direction of the screen.
+/// The azimuth increases at the constant rate, so the motion is smooth.
+pub fn turntable_cameras(
+    bounds: &VisualBounds,
+    camera: &Camera,
+    option: &TurntableOption,
+) -> Vec<Camera> {
+    let radius = bounds.radius * option.margin;
+    let (distance, fov) = match camera.projection_type() {
+        ProjectionType::Perspective => {
+            let half = f64::atan(camera.screen_size() / 2.0);
+            (radius / half.sin(), Some(Rad(2.0 * half)))
+        }
+        ProjectionType::Parallel => (2.0 * radius, None),
+    };
+    let near_clip = f64::max(distance - 2.0 * radius, distance * 0.01);
+    let far_clip = distance + 4.0 * radius;
+    let elevation = option.elevation.0;
+    (0..option.frames)
+        .map(|i| {
+            let azimuth = option.start_angle.0 + 2.0 * PI * i as f64 / option.frames as f64;
+            let direction = Vector3::new(
+                elevation.cos() * azimuth.sin(),
+                elevation.sin(),
+                elevation.cos() * azimuth.cos(),
+            );
+            let eye = bounds.center + direction * distance;
+            let matrix = Matrix4::look_at_rh(eye, bounds.center, Vector3::unit_y())
+                .invert()
+                .unwrap();
+            match fov {
+                Some(fov) => Camera::perspective_camera(matrix, fov, near_clip, far_clip),
+                None => Camera::parallel_camera(matrix, 2.0 * radius, near_clip, far_clip),
+            }
+        })
+        .collect()
+}
+
+/// Renders `scene` from each camera to the offscreen target, and passes the index and the
+/// image of the frame to `f`. The camera of the scene is restored after the capture.
+///
+/// The frames are passed one by one, so that they can be encoded or saved without keeping
+/// all of them in the memory.
+/// # Panics
+/// Panic occurs if the format of the swap chain descriptor of the scene is not the one of
+/// the 8-bit RGBA or BGRA.
+pub fn capture_turntable<F: FnMut(usize, RgbaImage)>(
+    scene: &mut Scene,
+    cameras: &[Camera],
+    mut f: F,
+) {
+    let sc_desc = scene.sc_desc();
+    let camera = scene.descriptor().camera.clone();
+    for (i, frame_camera) in cameras.iter().enumerate() {
+        scene.descriptor_mut().camera = frame_camera.clone();
+        let bytes = scene.read_pixels();
+        f(
+            i,
+            RgbaImage::from_raw(sc_desc.width, sc_desc.height, bytes).unwrap(),
+        );
+    }
+    scene.descriptor_mut().camera = camera;
+}
@@ -0,0 +1,191 @@
+use crate::*;
+use polyrend::{AttrVertex, IndexedVertex};
+
+impl AttributeValues {
+    #[inline(always)]
+    fn len(&self) -> usize {
+        match self {
+            AttributeValues::Float(values) => values.len(),
+            AttributeValues::Float2(values) => values.len(),
+            AttributeValues::Float3(values) => values.len(),
+            AttributeValues::Float4(values) => values.len(),
+            AttributeValues::Uint4(values) => values.len(),
+        }
+    }
+
+    #[inline(always)]
+    fn format(&self) -> (VertexFormat, u64) {
+        match self {
+            AttributeValues::Float(_) => (VertexFormat::Float, 4),
+            AttributeValues::Float2(_) => (VertexFormat::Float2, 8),
+            AttributeValues::Float3(_) => (VertexFormat::Float3, 12),
+            AttributeValues::Float4(_) => (VertexFormat::Float4, 16),
+            AttributeValues::Uint4(_) => (VertexFormat::Uint4, 16),
+        }
+    }
+
+    // pushes the 4-byte words of the `idx`th value
+    #[inline(always)]
+    fn extend_words(&self, idx: usize, words: &mut Vec<u32>) {
+        match self {
+            AttributeValues::Float(values) => words.push(values[idx].to_bits()),
+            AttributeValues::Float2(values) => {
+                words.extend(values[idx].iter().map(|x| x.to_bits()))
+            }
+            AttributeValues::Float3(values) => {
+                words.extend(values[idx].iter().map(|x| x.to_bits()))
+            }
+            AttributeValues::Float4(values) => {
+                words.extend(values[idx].iter().map(|x| x.to_bits()))
+            }
+            AttributeValues::Uint4(values) => words.extend(values[idx].iter()),
+        }
+    }
+}
+
+impl PolygonBuffers {
+    /// Returns the key of the vertex layout for `PipelineKey`.
+    pub(crate) fn vertex_layout(&self) -> String {
+        self.attributes.iter().fold(
+            String::from("truck-rendimpl/polygon-vertex"),
+            |string, (location, format, _)| format!("{};{}:{:?}", string, location, format),
+        )
+    }
+
+    /// Returns the stride and the attributes of the vertex buffer.
+    pub(crate) fn vertex_attributes(&self) -> (BufferAddress, Vec<VertexAttributeDescriptor>) {
+        let mut attributes = vec![
+            VertexAttributeDescriptor {
+                format: VertexFormat::Float3,
+                offset: 0,
+                shader_location: 0,
+            },
+            VertexAttributeDescriptor {
+                format: VertexFormat::Float2,
+                offset: 3 * 4,
+                shader_location: 1,
+            },
+            VertexAttributeDescriptor {
+                format: VertexFormat::Float3,
+                offset: 2 * 4 + 3 * 4,
+                shader_location: 2,
+            },
+        ];
+        let mut stride = std::mem::size_of::<AttrVertex>() as BufferAddress;
+        self.attributes.iter().for_each(|(location, format, size)| {
+            attributes.push(VertexAttributeDescriptor {
+                format: *format,
+                offset: stride,
+                shader_location: *location,
+            });
+            stride += size;
+        });
+        (stride, attributes)
+    }
+}
+
+impl InstanceCreator {
+    /// Creates the polygon instance whose vertices have the custom attributes following
+    /// the standard ones.
+    ///
+    /// The custom attributes are given for each position of `mesh`, and are passed to
+    /// the vertex shader set by `PolygonInstance::pipeline_with_shader`.
+    /// # Panics
+    /// Panics if the number of the values of an attribute is not the one of the positions of
+    /// `mesh`, or a location of the attributes is less than `3` or duplicated.
+    /// # Examples
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use truck_platform::*;
+    /// use truck_rendimpl::*;
+    /// use truck_platform::wgpu::*;
+    /// # let instance = Instance::new(BackendBit::PRIMARY);
+    /// # let (device, queue, _) =
+    /// #     futures::executor::block_on(DeviceHandler::request_device(&instance, None)).unwrap();
+    /// # let sc_desc = SwapChainDescriptor {
+    /// #     usage: TextureUsage::OUTPUT_ATTACHMENT,
+    /// #     format: TextureFormat::Rgba8UnormSrgb,
+    /// #     width: 256,
+    /// #     height: 256,
+    /// #     present_mode: PresentMode::Mailbox,
+    /// # };
+    /// # let handler = DeviceHandler::new(device, queue, Arc::new(Mutex::new(sc_desc)));
+    /// let mut scene = Scene::new(handler, &Default::default());
+    /// let creator = scene.instance_creator();
+    /// let mesh = PolygonMesh::new(
+    ///     vec![
+    ///         Point3::new(-1.0, -1.0, -3.0),
+    ///         Point3::new(1.0, -1.0, -3.0),
+    ///         Point3::new(0.0, 1.0, -3.0),
+    ///     ],
+    ///     Vec::new(),
+    ///     vec![Vector3::new(0.0, 0.0, 1.0)],
+    ///     Faces::from_iter(&[&[(0, None, Some(0)), (1, None, Some(0)), (2, None, Some(0))]]),
+    /// );
+    /// // the temperatures at the vertices, `layout(location = 3) in float temperature;`
+    /// let temperature = VertexAttribute {
+    ///     shader_location: 3,
+    ///     values: AttributeValues::Float(vec![20.0, 25.0, 80.0]),
+    /// };
+    /// let polygon = creator.create_instance_with_attributes(
+    ///     &mesh,
+    ///     &Default::default(),
+    ///     &[temperature],
+    /// );
+    /// // The default shaders ignore the custom attributes.
+    /// scene.add_object(&polygon);
+    /// ```
+    pub fn create_instance_with_attributes(
+        &self,
+        mesh: &PolygonMesh,
+        desc: &PolygonInstanceDescriptor,
+        attributes: &[VertexAttribute],
+    ) -> PolygonInstance {
+        let len = mesh.positions().len();
+        attributes.iter().enumerate().for_each(|(i, attr)| {
+            assert_eq!(
+                attr.values.len(),
+                len,
+                "the number of values is not the one of positions"
+            );
+            assert!(
+                attr.shader_location >= 3,
+                "the locations 0, 1, 2 are reserved"
+            );
+            assert!(
+                attributes[..i]
+                    .iter()
+                    .all(|a| a.shader_location != attr.shader_location),
+                "the location {} is duplicated",
+                attr.shader_location,
+            );
+        });
+        let device = self.handler.device();
+        let expanded = ExpandedPolygon::<IndexedVertex>::from(mesh);
+        let mut polygon = expanded.polygon_buffers_with(BufferUsage::INDEX, device, |vertices| {
+            let mut words = Vec::<u32>::new();
+            vertices.iter().for_each(|v| {
+                words.extend(bytemuck::cast_slice::<AttrVertex, u32>(&[v.vertex]));
+                attributes.iter().for_each(|attr| {
+                    attr.values
+                        .extend_words(v.position_index as usize, &mut words)
+                });
+            });
+            // The stride of the buffer is not used since the polygon is drawn by the indices.
+            BufferHandler::from_slice(&words, device, BufferUsage::VERTEX)
+        });
+        polygon.attributes = attributes
+            .iter()
+            .map(|attr| {
+                let (format, size) = attr.values.format();
+                (attr.shader_location, format, size)
+            })
+            .collect();
+        PolygonInstance {
+            polygon,
+            state: desc.instance_state.clone(),
+            shaders: Arc::clone(&self.polygon_shaders),
+            id: RenderID::gen(),
+        }
+    }
+}
@@ -1,11 +1,55 @@
 use crate::*;
 
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod)]
+struct RibbonVertex {
+    position: [f32; 3],
+    other: [f32; 3],
+    // (-1.0 at the start of the segment or 1.0 at the end, the side of the strip)
+    corner: [f32; 2],
+}
+
+// the ways to draw the lines
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LineMode {
+    // GPU line primitives
+    Thin,
+    // the quads expanded in the screen space
+    Wide,
+    // the strips antialiased by the distance to the segments
+    Sdf,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Zeroable, Pod)]
+struct LineInfo {
+    color: [f32; 4],
+    line: [f32; 4],
+}
+
 impl Default for WireFrameState {
     #[inline(always)]
     fn default() -> WireFrameState {
         WireFrameState {
             matrix: Matrix4::identity(),
             color: Vector4::new(1.0, 1.0, 1.0, 1.0),
+            color_space: ColorSpace::Linear,
+            depth_bias: Default::default(),
+            parent: None,
+            line_width: 1.0,
+            antialiased: false,
+        }
+    }
+}
+
+impl WireFrameState {
+    /// Returns the matrix in the world coordinate, i.e. the product of the world matrix of
+    /// the parent node and `matrix`.
+    #[inline(always)]
+    pub fn world_matrix(&self) -> Matrix4 {
+        match &self.parent {
+            Some(parent) => parent.world_matrix() * self.matrix,
+            None => self.matrix,
         }
     }
 }
@@ -27,6 +71,8 @@ impl WireFrameInstance {
         Self {
             vertices: Arc::clone(&self.vertices),
             strips: Arc::clone(&self.strips),
+            ribbon_vertices: Arc::clone(&self.ribbon_vertices),
+            ribbon_indices: Arc::clone(&self.ribbon_indices),
             state: self.state.clone(),
             shaders: Arc::clone(&self.shaders),
             id: RenderID::gen(),
@@ -36,52 +82,131 @@ impl WireFrameInstance {
     #[inline(always)]
     pub fn instance_state(&self) -> &WireFrameState { &self.state }
     /// Returns the mutable reference to wireframe state
+    ///
+    /// If `line_width` is changed across `1.0` or `antialiased` is switched, the vertex buffer
+    /// and the pipeline of the scene have to be updated by `Scene::update_vertex_buffer` and
+    /// `Scene::update_pipeline`.
     #[inline(always)]
     pub fn instance_state_mut(&mut self) -> &mut WireFrameState { &mut self.state }
+
+    // The antialiased lines are drawn by the strips with the coverages, and the other lines
+    // thicker than one pixel are drawn by the quads expanded in the screen space.
+    #[inline(always)]
+    fn line_mode(&self) -> LineMode {
+        match (self.state.antialiased, self.state.line_width > 1.0) {
+            (true, _) => LineMode::Sdf,
+            (false, true) => LineMode::Wide,
+            (false, false) => LineMode::Thin,
+        }
+    }
+}
+
+impl InstanceCreator {
+    /// Creates the wire frame of the segments `(points[strips[2 * i]], points[strips[2 * i + 1]])`.
+    pub(crate) fn wire_frame_instance(
+        &self,
+        points: &[[f32; 3]],
+        strips: &[u32],
+        state: &WireFrameState,
+    ) -> WireFrameInstance {
+        let device = self.handler.device();
+        let ribbon_vertices: Vec<RibbonVertex> = strips
+            .chunks(2)
+            .filter(|segment| segment.len() == 2)
+            .flat_map(|segment| {
+                let (a, b) = (points[segment[0] as usize], points[segment[1] as usize]);
+                let vertex = move |position, other, corner| RibbonVertex {
+                    position,
+                    other,
+                    corner,
+                };
+                vec![
+                    vertex(a, b, [-1.0, 1.0]),
+                    vertex(a, b, [-1.0, -1.0]),
+                    vertex(b, a, [1.0, 1.0]),
+                    vertex(b, a, [1.0, -1.0]),
+                ]
+            })
+            .collect();
+        let ribbon_indices: Vec<u32> = (0..ribbon_vertices.len() as u32 / 4)
+            .flat_map(|i| vec![0, 1, 2, 2, 1, 3].into_iter().map(move |j| 4 * i + j))
+            .collect();
+        let vertices = BufferHandler::from_slice(&points, device, BufferUsage::VERTEX);
+        let strips = BufferHandler::from_slice(&strips, device, BufferUsage::INDEX);
+        let ribbon_vertices =
+            BufferHandler::from_slice(&ribbon_vertices, device, BufferUsage::VERTEX);
+        let ribbon_indices = BufferHandler::from_slice(&ribbon_indices, device, BufferUsage::INDEX);
+        WireFrameInstance {
+            vertices: Arc::new(vertices),
+            strips: Arc::new(strips),
+            ribbon_vertices: Arc::new(ribbon_vertices),
+            ribbon_indices: Arc::new(ribbon_indices),
+            state: state.clone(),
+            shaders: Arc::clone(&self.wire_shaders),
+            id: RenderID::gen(),
+        }
+    }
 }
 
 impl Rendered for WireFrameInstance {
     impl_render_id!(id);
+    impl_pipeline_builder!(clone_instance);
     fn vertex_buffer(&self, _: &DeviceHandler) -> (Arc<BufferHandler>, Option<Arc<BufferHandler>>) {
-        (self.vertices.clone(), Some(self.strips.clone()))
+        match self.line_mode() {
+            LineMode::Thin => (self.vertices.clone(), Some(self.strips.clone())),
+            _ => (
+                self.ribbon_vertices.clone(),
+                Some(self.ribbon_indices.clone()),
+            ),
+        }
     }
     fn bind_group_layout(&self, handler: &DeviceHandler) -> Arc<BindGroupLayout> {
-        Arc::new(bind_group_util::create_bind_group_layout(
-            handler.device(),
-            &[
-                // matrix
-                PreBindGroupLayoutEntry {
-                    visibility: ShaderStage::VERTEX,
-                    ty: BindingType::UniformBuffer {
-                        dynamic: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
+        handler.cached_bind_group_layout(&[
+            // matrix
+            PreBindGroupLayoutEntry {
+                visibility: ShaderStage::VERTEX,
+                ty: BindingType::UniformBuffer {
+                    dynamic: false,
+                    min_binding_size: None,
                 },
-                // color
-                PreBindGroupLayoutEntry {
-                    visibility: ShaderStage::FRAGMENT,
-                    ty: BindingType::UniformBuffer {
-                        dynamic: false,
-                        min_binding_size: None,
-                    },
-                    count: None,
+                count: None,
+            },
+            // color and line width
+            PreBindGroupLayoutEntry {
+                visibility: ShaderStage::VERTEX | ShaderStage::FRAGMENT,
+                ty: BindingType::UniformBuffer {
+                    dynamic: false,
+                    min_binding_size: None,
                 },
-            ],
-        ))
+                count: None,
+            },
+        ])
     }
+    /// Creates the bind group.
+    ///
+    /// The size of the screen is stored for the lines thicker than one pixel, so the bind group
+    /// has to be updated by `Scene::update_bind_group` after resizing the swap chain.
     fn bind_group(&self, handler: &DeviceHandler, layout: &BindGroupLayout) -> Arc<BindGroup> {
-        let device = handler.device();
-        let matrix_data: [[f32; 4]; 4] = self.state.matrix.cast::<f32>().unwrap().into();
+        let (device, sc_desc) = (handler.device(), handler.sc_desc());
+        let matrix_data: [[f32; 4]; 4] = self.state.world_matrix().cast::<f32>().unwrap().into();
         let matrix_buffer = BufferHandler::from_slice(&matrix_data, device, BufferUsage::UNIFORM);
-        let color_data: [f32; 4] = self.state.color.cast::<f32>().unwrap().into();
-        let color_buffer = BufferHandler::from_slice(&color_data, device, BufferUsage::UNIFORM);
+        let color = self.state.color_space.to_linear(self.state.color);
+        let line_info = LineInfo {
+            color: color.cast::<f32>().unwrap().into(),
+            line: [
+                self.state.line_width as f32,
+                sc_desc.width as f32,
+                sc_desc.height as f32,
+                0.0,
+            ],
+        };
+        let line_buffer = BufferHandler::from_slice(&[line_info], device, BufferUsage::UNIFORM);
         Arc::new(bind_group_util::create_bind_group(
             device,
             layout,
             vec![
                 matrix_buffer.binding_resource(),
-                color_buffer.binding_resource(),
+                line_buffer.binding_resource(),
             ],
         ))
     }
@@ -91,38 +216,159 @@ impl Rendered for WireFrameInstance {
         layout: &PipelineLayout,
         sample_count: u32,
     ) -> Arc<RenderPipeline> {
-        let (device, sc_desc) = (handler.device(), handler.sc_desc());
-        let vertex_module = device.create_shader_module(include_spirv!("shaders/line.vert.spv"));
-        let fragment_module = device.create_shader_module(include_spirv!("shaders/line.frag.spv"));
+        let depth_bias = self.state.depth_bias.oriented(handler);
+        let (shader, vertex_layout) = match self.line_mode() {
+            LineMode::Thin => (
+                "truck-rendimpl/wireframe",
+                "truck-rendimpl/wireframe-vertex",
+            ),
+            LineMode::Wide => (
+                "truck-rendimpl/wide-wireframe",
+                "truck-rendimpl/wide-wireframe-vertex",
+            ),
+            LineMode::Sdf => (
+                "truck-rendimpl/sdf-wireframe",
+                "truck-rendimpl/wide-wireframe-vertex",
+            ),
+        };
+        let key = PipelineKey {
+            shader: shader.to_string(),
+            vertex_layout: vertex_layout.to_string(),
+            primitive_topology: self.primitive_topology(),
+            index_format: IndexFormat::Uint32,
+            cull_mode: CullMode::None,
+            depth_bias: (
+                depth_bias.constant,
+                (depth_bias.slope_scale as f32).to_bits(),
+                (depth_bias.clamp as f32).to_bits(),
+            ),
+            color_states: vec![self.color_state(handler)],
+            depth_stencil_state: Some(DepthStencilStateDescriptor {
+                format: TextureFormat::Depth32Float,
+                depth_write_enabled: true,
+                depth_compare: handler.depth_compare(wgpu::CompareFunction::Less),
+                stencil: StencilStateDescriptor {
+                    front: StencilStateFaceDescriptor::IGNORE,
+                    back: StencilStateFaceDescriptor::IGNORE,
+                    read_mask: 0,
+                    write_mask: 0,
+                },
+            }),
+            sample_count,
+        };
+        handler.cached_pipeline(&key, || self.create_pipeline(handler, layout, sample_count))
+    }
+}
+
+impl WireFrameInstance {
+    #[inline(always)]
+    fn primitive_topology(&self) -> PrimitiveTopology {
+        match self.line_mode() {
+            LineMode::Thin => PrimitiveTopology::LineList,
+            _ => PrimitiveTopology::TriangleList,
+        }
+    }
+
+    // the antialiased lines are blended by the coverages stored in the alpha
+    fn color_state(&self, handler: &DeviceHandler) -> ColorStateDescriptor {
+        let (color_blend, alpha_blend) = match self.line_mode() {
+            LineMode::Sdf => (
+                BlendDescriptor {
+                    src_factor: BlendFactor::SrcAlpha,
+                    dst_factor: BlendFactor::OneMinusSrcAlpha,
+                    operation: BlendOperation::Add,
+                },
+                BlendDescriptor {
+                    src_factor: BlendFactor::One,
+                    dst_factor: BlendFactor::OneMinusSrcAlpha,
+                    operation: BlendOperation::Add,
+                },
+            ),
+            _ => (BlendDescriptor::REPLACE, BlendDescriptor::REPLACE),
+        };
+        ColorStateDescriptor {
+            format: handler.sc_desc().format,
+            color_blend,
+            alpha_blend,
+            write_mask: ColorWrite::ALL,
+        }
+    }
+
+    fn create_pipeline(
+        &self,
+        handler: &DeviceHandler,
+        layout: &PipelineLayout,
+        sample_count: u32,
+    ) -> Arc<RenderPipeline> {
+        let device = handler.device();
+        let depth_bias = self.state.depth_bias.oriented(handler);
+        let line_buffer = [VertexBufferDescriptor {
+            stride: std::mem::size_of::<[f32; 3]>() as BufferAddress,
+            step_mode: InputStepMode::Vertex,
+            attributes: &[VertexAttributeDescriptor {
+                format: VertexFormat::Float3,
+                offset: 0,
+                shader_location: 0,
+            }],
+        }];
+        let ribbon_buffer = [VertexBufferDescriptor {
+            stride: std::mem::size_of::<RibbonVertex>() as BufferAddress,
+            step_mode: InputStepMode::Vertex,
+            attributes: &[
+                VertexAttributeDescriptor {
+                    format: VertexFormat::Float3,
+                    offset: 0,
+                    shader_location: 0,
+                },
+                VertexAttributeDescriptor {
+                    format: VertexFormat::Float3,
+                    offset: 3 * 4,
+                    shader_location: 1,
+                },
+                VertexAttributeDescriptor {
+                    format: VertexFormat::Float2,
+                    offset: 6 * 4,
+                    shader_location: 2,
+                },
+            ],
+        }];
+        let (vertex_module, fragment_module, vertex_buffers) = match self.line_mode() {
+            LineMode::Thin => (&self.shaders.vertex, &self.shaders.fragment, &line_buffer),
+            LineMode::Wide => (
+                &self.shaders.wide_vertex,
+                &self.shaders.fragment,
+                &ribbon_buffer,
+            ),
+            LineMode::Sdf => (
+                &self.shaders.sdf_vertex,
+                &self.shaders.sdf_fragment,
+                &ribbon_buffer,
+            ),
+        };
         let pipeline = device.create_render_pipeline(&RenderPipelineDescriptor {
             layout: Some(layout),
             vertex_stage: ProgrammableStageDescriptor {
-                module: &vertex_module,
+                module: vertex_module,
                 entry_point: "main",
             },
             fragment_stage: Some(ProgrammableStageDescriptor {
-                module: &fragment_module,
+                module: fragment_module,
                 entry_point: "main",
             }),
             rasterization_state: Some(RasterizationStateDescriptor {
                 front_face: FrontFace::Ccw,
                 cull_mode: CullMode::None,
-                depth_bias: 0,
-                depth_bias_slope_scale: 0.0,
-                depth_bias_clamp: 0.0,
+                depth_bias: depth_bias.constant,
+                depth_bias_slope_scale: depth_bias.slope_scale as f32,
+                depth_bias_clamp: depth_bias.clamp as f32,
                 clamp_depth: false,
             }),
-            primitive_topology: PrimitiveTopology::LineList,
-            color_states: &[ColorStateDescriptor {
-                format: sc_desc.format,
-                color_blend: BlendDescriptor::REPLACE,
-                alpha_blend: BlendDescriptor::REPLACE,
-                write_mask: ColorWrite::ALL,
-            }],
+            primitive_topology: self.primitive_topology(),
+            color_states: &[self.color_state(handler)],
             depth_stencil_state: Some(DepthStencilStateDescriptor {
                 format: TextureFormat::Depth32Float,
                 depth_write_enabled: true,
-                depth_compare: wgpu::CompareFunction::Less,
+                depth_compare: handler.depth_compare(wgpu::CompareFunction::Less),
                 stencil: StencilStateDescriptor {
                     front: StencilStateFaceDescriptor::IGNORE,
                     back: StencilStateFaceDescriptor::IGNORE,
@@ -132,15 +378,7 @@ impl Rendered for WireFrameInstance {
             }),
             vertex_state: VertexStateDescriptor {
                 index_format: IndexFormat::Uint32,
-                vertex_buffers: &[VertexBufferDescriptor {
-                    stride: std::mem::size_of::<[f32; 3]>() as BufferAddress,
-                    step_mode: InputStepMode::Vertex,
-                    attributes: &[VertexAttributeDescriptor {
-                        format: VertexFormat::Float3,
-                        offset: 0,
-                        shader_location: 0,
-                    }],
-                }],
+                vertex_buffers,
             },
             sample_count,
             sample_mask: !0,
@@ -173,28 +173,10 @@ pub fn gradation_texture(scene: &mut Scene) -> Texture {
 }
 
 pub fn init_device(instance: &Instance) -> (Arc<Device>, Arc<Queue>) {
-    futures::executor::block_on(async {
-        let adapter = instance
-            .request_adapter(&RequestAdapterOptions {
-                power_preference: PowerPreference::Default,
-                compatible_surface: None,
-            })
-            .await
-            .unwrap();
-        writeln!(&mut std::io::stderr(), "{:?}", adapter.get_info()).unwrap();
-        let (device, queue) = adapter
-            .request_device(
-                &DeviceDescriptor {
-                    features: Default::default(),
-                    limits: Limits::default(),
-                    shader_validation: true,
-                },
-                None,
-            )
-            .await
-            .unwrap();
-        (Arc::new(device), Arc::new(queue))
-    })
+    let (device, queue, info) =
+        futures::executor::block_on(DeviceHandler::request_device(instance, None)).unwrap();
+    writeln!(&mut std::io::stderr(), "{:?}", info).unwrap();
+    (device, queue)
 }
 
 pub fn swap_chain_descriptor(size: (u32, u32)) -> SwapChainDescriptor {
@@ -282,9 +264,7 @@ pub fn save_buffer<P: AsRef<std::path::Path>>(path: P, vec: &Vec<u8>, size: (u32
 }
 
 pub fn same_buffer(vec0: &Vec<u8>, vec1: &Vec<u8>) -> bool {
-    vec0.par_iter()
-        .zip(vec1)
-        .all(move |(i, j)| std::cmp::max(i, j) - std::cmp::min(i, j) < 3)
+    ImageComparison::new(vec0, vec1, [2; 4]).is_within_tolerance()
 }
 
 pub fn count_difference(vec0: &Vec<u8>, vec1: &Vec<u8>) -> usize {
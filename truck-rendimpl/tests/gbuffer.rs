@@ -0,0 +1,74 @@
+mod common;
+use std::sync::{Arc, Mutex};
+use truck_platform::*;
+use truck_rendimpl::gbuffer::*;
+use truck_rendimpl::*;
+use wgpu::*;
+
+const SIZE: u32 = 256;
+
+fn triangle(positions: Vec<Point3>, normal: Vector3) -> PolygonMesh {
+    PolygonMesh::new(
+        positions,
+        Vec::new(),
+        vec![normal],
+        Faces::from_iter([&[(0, None, Some(0)), (1, None, Some(0)), (2, None, Some(0))]]),
+    )
+}
+
+fn exec_gbuffer_test(backend: BackendBit, _: &str) {
+    let instance = Instance::new(backend);
+    let (device, queue) = common::init_device(&instance);
+    let sc_desc = Arc::new(Mutex::new(common::swap_chain_descriptor((SIZE, SIZE))));
+    let handler = DeviceHandler::new(device, queue, sc_desc);
+    let scene = Scene::new(handler, &Default::default());
+    let creator = scene.instance_creator();
+    // the triangle facing the camera at the depth 3
+    let front = triangle(
+        vec![
+            Point3::new(-1.0, -1.0, -3.0),
+            Point3::new(0.0, -1.0, -3.0),
+            Point3::new(-0.5, 1.0, -3.0),
+        ],
+        Vector3::unit_z(),
+    );
+    // the triangle tilted around the y-axis, whose depth increases along the x-axis
+    let tilted = triangle(
+        vec![
+            Point3::new(0.0, -1.0, -2.5),
+            Point3::new(1.0, -1.0, -3.5),
+            Point3::new(0.5, 1.0, -3.0),
+        ],
+        Vector3::new(1.0, 0.0, 1.0).normalize(),
+    );
+    let front: PolygonInstance = creator.create_instance(&front, &Default::default());
+    let tilted: PolygonInstance = creator.create_instance(&tilted, &Default::default());
+
+    let gbuffer = render_gbuffer(scene.device_handler(), scene.descriptor(), &[&front, &tilted]);
+    assert_eq!(gbuffer.size(), (SIZE, SIZE));
+    assert_eq!(gbuffer.pixels().len(), (SIZE * SIZE) as usize);
+    assert_eq!(gbuffer.normal(0, 0), None);
+    assert_eq!(gbuffer.depth(0, 0), None);
+    assert_eq!(gbuffer.pixels()[0], [0.0; 4]);
+
+    let normal = gbuffer.normal(77, 128).unwrap();
+    assert!(f32::abs(normal[0]) < 1.0e-3 && f32::abs(normal[2] - 1.0) < 1.0e-3);
+    assert!(f32::abs(gbuffer.depth(77, 128).unwrap() - 3.0) < 1.0e-3);
+    let normal = gbuffer.normal(170, 128).unwrap();
+    let half = std::f32::consts::FRAC_1_SQRT_2;
+    assert!(f32::abs(normal[0] - half) < 1.0e-3 && f32::abs(normal[2] - half) < 1.0e-3);
+    let (near, far) = (gbuffer.depth(160, 128).unwrap(), gbuffer.depth(180, 128).unwrap());
+    assert!(2.5 < near && near < far && far < 3.5, "{} {}", near, far);
+
+    // the images of the normals and the depths
+    let normal_image = gbuffer.normal_image();
+    assert_eq!(normal_image.get_pixel(0, 0).0, [0, 0, 0, 0]);
+    assert_eq!(normal_image.get_pixel(77, 128).0, [128, 128, 255, 255]);
+    let depth_image = gbuffer.depth_image();
+    assert_eq!(depth_image.dimensions(), (SIZE, SIZE));
+    assert_eq!(depth_image.get_pixel(0, 0).0, [0]);
+    assert!(depth_image.get_pixel(160, 128).0[0] > depth_image.get_pixel(180, 128).0[0]);
+}
+
+#[test]
+fn gbuffer_test() { common::os_alt_exec_test(exec_gbuffer_test) }
@@ -0,0 +1,65 @@
+mod common;
+use std::sync::{Arc, Mutex};
+use truck_platform::*;
+use truck_rendimpl::id_buffer::*;
+use truck_rendimpl::*;
+use wgpu::*;
+
+const SIZE: u32 = 256;
+
+fn triangle(x: f64) -> PolygonMesh {
+    PolygonMesh::new(
+        vec![
+            Point3::new(x - 0.5, -1.0, -3.0),
+            Point3::new(x + 0.5, -1.0, -3.0),
+            Point3::new(x, 1.0, -3.0),
+        ],
+        Vec::new(),
+        Vec::new(),
+        Faces::from_iter([&[0, 1, 2]]),
+    )
+}
+
+fn exec_id_buffer_test(backend: BackendBit, _: &str) {
+    let instance = Instance::new(backend);
+    let (device, queue) = common::init_device(&instance);
+    let sc_desc = Arc::new(Mutex::new(common::swap_chain_descriptor((SIZE, SIZE))));
+    let handler = DeviceHandler::new(device, queue, sc_desc);
+    let scene = Scene::new(handler, &Default::default());
+    let creator = scene.instance_creator();
+    let left: PolygonInstance = creator.create_instance(&triangle(-0.5), &Default::default());
+    let right: PolygonInstance = creator.create_instance(&triangle(0.5), &Default::default());
+    // the pixels of the centers of the triangles
+    let left_pixel = (77 + 128 * SIZE) as usize;
+    let right_pixel = (179 + 128 * SIZE) as usize;
+
+    let mut id_scene = id_scene(scene.device_handler(), scene.descriptor());
+    assert_eq!(id_scene.sc_desc().format, TextureFormat::R32Uint);
+    assert_eq!((id_scene.sc_desc().width, id_scene.sc_desc().height), (SIZE, SIZE));
+    let mut behind = right.id_instance(3);
+    behind.instance_state_mut().matrix = Matrix4::from_translation(-Vector3::unit_z());
+    id_scene.add_object(&left.id_instance(1));
+    id_scene.add_object(&right.id_instance(2));
+    id_scene.add_object(&behind);
+    let labels = render_id_buffer(&mut id_scene);
+    assert_eq!(labels.len(), (SIZE * SIZE) as usize);
+    assert_eq!(labels[0], 0);
+    assert_eq!(labels[left_pixel], 1);
+    assert_eq!(labels[right_pixel], 2);
+
+    // the triangle moved to the front hides the right one.
+    behind.instance_state_mut().matrix = Matrix4::from_translation(Vector3::unit_z());
+    id_scene.update_bind_group(&behind);
+    let labels = render_id_buffer(&mut id_scene);
+    assert_eq!(labels[left_pixel], 1);
+    assert_eq!(labels[right_pixel], 3);
+
+    // the labels are updated with the bind group.
+    behind.set_label(4);
+    id_scene.update_bind_group(&behind);
+    let labels = render_id_buffer(&mut id_scene);
+    assert_eq!(labels[right_pixel], 4);
+}
+
+#[test]
+fn id_buffer_test() { common::os_alt_exec_test(exec_id_buffer_test) }
@@ -0,0 +1,47 @@
+mod common;
+use std::sync::{Arc, Mutex};
+use truck_platform::*;
+use truck_rendimpl::*;
+use wgpu::*;
+
+fn exec_picking_test(backend: BackendBit, _: &str) {
+    let instance = Instance::new(backend);
+    let (device, queue) = common::init_device(&instance);
+    let sc_desc = Arc::new(Mutex::new(common::swap_chain_descriptor((256, 256))));
+    let handler = DeviceHandler::new(device, queue, sc_desc);
+    let mut scene = Scene::new(handler, &Default::default());
+    let creator = scene.instance_creator();
+    let mesh = PolygonMesh::new(
+        vec![
+            Point3::new(-1.0, -1.0, -3.0),
+            Point3::new(1.0, -1.0, -3.0),
+            Point3::new(0.0, 1.0, -3.0),
+        ],
+        Vec::new(),
+        Vec::new(),
+        Faces::from_iter(&[&[0, 1, 2]]),
+    );
+    let mut polygon: PolygonInstance = creator.create_instance(&mesh, &Default::default());
+    let wireframe: WireFrameInstance = creator.create_instance(&mesh, &Default::default());
+    scene.add_object(&polygon);
+    scene.add_object(&wireframe);
+
+    let (id, point) = scene.pick((128.0, 128.0)).unwrap();
+    assert_eq!(id, polygon.render_id());
+    assert_near!(point, Point3::new(0.0, 0.0, -3.0));
+    assert!(scene.pick((0.0, 0.0)).is_none());
+
+    // The picking mesh is moved by the matrix of the instance.
+    polygon.instance_state_mut().matrix = Matrix4::from_translation(Vector3::new(0.0, 0.0, 1.0));
+    scene.update_bind_group(&polygon);
+    let (id, point) = scene.pick((128.0, 128.0)).unwrap();
+    assert_eq!(id, polygon.render_id());
+    assert_near!(point, Point3::new(0.0, 0.0, -2.0));
+
+    polygon.instance_state_mut().matrix = Matrix4::from_translation(Vector3::new(5.0, 0.0, 0.0));
+    scene.update_bind_group(&polygon);
+    assert!(scene.pick((128.0, 128.0)).is_none());
+}
+
+#[test]
+fn picking_test() { common::os_alt_exec_test(exec_picking_test) }
@@ -70,8 +70,7 @@ fn nontex_inst_desc() -> PolygonInstanceDescriptor {
                 reflectance: 0.29613,
                 ambient_ratio: 0.92,
             },
-            texture: None,
-            backface_culling: true,
+            ..Default::default()
         },
     }
 }
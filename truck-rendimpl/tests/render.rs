@@ -33,6 +33,7 @@ fn test_scene(backend: BackendBit) -> Scene {
                 position: Point3::new(-3.0, 4.0, -2.0),
                 color: Vector3::new(1.0, 1.0, 1.0),
                 light_type: LightType::Point,
+                ..Default::default()
             }],
             ..Default::default()
         },
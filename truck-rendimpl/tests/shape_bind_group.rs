@@ -84,8 +84,7 @@ fn nontex_inst_desc() -> ShapeInstanceDescriptor {
                 reflectance: 0.29613,
                 ambient_ratio: 0.92,
             },
-            texture: None,
-            backface_culling: true,
+            ..Default::default()
         },
         ..Default::default()
     }
@@ -0,0 +1,78 @@
+/// Errors occured by STEP files I/O
+#[derive(Debug)]
+pub enum Error {
+    /// The syntax of the exchange structure is invalid at the line.
+    SyntaxError(usize),
+    /// The referred entity instance is not found.
+    MissingEntity(usize),
+    /// The attributes of the entity instance are invalid.
+    InvalidEntity(usize),
+    /// The entity instance has the type which is not supported.
+    UnsupportedEntity(usize, String),
+    /// The topological elements cannot be constructed.
+    FromTopology(truck_topology::errors::Error),
+    /// Errors caused by STEP files I/O.
+    FromIO(std::io::Error),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::SyntaxError(line) => f.write_fmt(format_args!(
+                "There is a syntax error at the line {}.",
+                line
+            )),
+            Error::MissingEntity(id) => {
+                f.write_fmt(format_args!("The entity instance #{} is not found.", id))
+            }
+            Error::InvalidEntity(id) => f.write_fmt(format_args!(
+                "The attributes of the entity instance #{} are invalid.",
+                id
+            )),
+            Error::UnsupportedEntity(id, name) => f.write_fmt(format_args!(
+                "The entity instance #{} of the type {} is not supported.",
+                id, name
+            )),
+            Error::FromTopology(error) => f.write_fmt(format_args!("{}", error)),
+            Error::FromIO(error) => f.write_fmt(format_args!("{:?}", error)),
+        }
+    }
+}
+
+impl From<truck_topology::errors::Error> for Error {
+    fn from(error: truck_topology::errors::Error) -> Error { Error::FromTopology(error) }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Error { Error::FromIO(error) }
+}
+
+impl std::error::Error for Error {}
+
+#[test]
+fn print_messages() {
+    use std::io::Write;
+    let io_error = std::io::Error::new(std::io::ErrorKind::InvalidData, "invalid UTF-8");
+    writeln!(&mut std::io::stderr(), "****** test of the expressions of error messages ******\n").unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::SyntaxError(12)).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::MissingEntity(3)).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::InvalidEntity(4)).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::UnsupportedEntity(5, "CONICAL_SURFACE".into())).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::from(truck_topology::errors::Error::SameVertex)).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::from(io_error)).unwrap();
+    writeln!(&mut std::io::stderr(), "*******************************************************").unwrap();
+}
+
+#[test]
+fn error_conversions() {
+    let error = Error::from(truck_topology::errors::Error::SameVertex);
+    assert!(matches!(error, Error::FromTopology(truck_topology::errors::Error::SameVertex)));
+    let error = Error::from(std::io::Error::new(std::io::ErrorKind::UnexpectedEof, "eof"));
+    match error {
+        Error::FromIO(error) => assert_eq!(error.kind(), std::io::ErrorKind::UnexpectedEof),
+        error => panic!("{:?}", error),
+    }
+    let message = Error::UnsupportedEntity(5, "CONICAL_SURFACE".into()).to_string();
+    assert_eq!(message, "The entity instance #5 of the type CONICAL_SURFACE is not supported.");
+    assert_eq!(Error::SyntaxError(12).to_string(), "There is a syntax error at the line 12.");
+}
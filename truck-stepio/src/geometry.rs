@@ -0,0 +1,132 @@
+use crate::*;
+use std::f64::consts::FRAC_1_SQRT_2;
+
+/// The surfaces written into STEP files.
+#[derive(Clone, Debug)]
+pub enum SurfaceGeometry {
+    /// written as `PLANE`
+    Plane(Plane),
+    /// written as `B_SPLINE_SURFACE_WITH_KNOTS`
+    NURBS(NURBSSurface<Vector4>),
+}
+
+/// The curves which can be written into STEP files.
+pub trait StepCurve {
+    /// Returns the NURBS curve with the same shape and the same direction.
+    fn to_nurbs(&self) -> NURBSCurve<Vector4>;
+}
+
+/// The surfaces which can be written into STEP files.
+pub trait StepSurface {
+    /// Returns the plane or the NURBS surface with the same shape and the same orientation.
+    fn to_step_surface(&self) -> SurfaceGeometry;
+}
+
+impl StepCurve for NURBSCurve<Vector4> {
+    #[inline(always)]
+    fn to_nurbs(&self) -> NURBSCurve<Vector4> { self.clone() }
+}
+
+impl StepCurve for BSplineCurve<Vector3> {
+    #[inline(always)]
+    fn to_nurbs(&self) -> NURBSCurve<Vector4> {
+        NURBSCurve::new(BSplineCurve::new(
+            self.knot_vec().clone(),
+            self.control_points()
+                .iter()
+                .map(|pt| pt.extend(1.0))
+                .collect(),
+        ))
+    }
+}
+
+impl StepCurve for Curve {
+    #[inline(always)]
+    fn to_nurbs(&self) -> NURBSCurve<Vector4> {
+        match self {
+            Curve::BSplineCurve(curve) => curve.to_nurbs(),
+            Curve::NURBSCurve(curve) => curve.to_nurbs(),
+        }
+    }
+}
+
+impl StepSurface for Plane {
+    #[inline(always)]
+    fn to_step_surface(&self) -> SurfaceGeometry { SurfaceGeometry::Plane(*self) }
+}
+
+impl StepSurface for NURBSSurface<Vector4> {
+    #[inline(always)]
+    fn to_step_surface(&self) -> SurfaceGeometry { SurfaceGeometry::NURBS(self.clone()) }
+}
+
+impl StepSurface for BSplineSurface<Vector3> {
+    #[inline(always)]
+    fn to_step_surface(&self) -> SurfaceGeometry {
+        let control_points = self
+            .control_points()
+            .iter()
+            .map(|vec| vec.iter().map(|pt| pt.extend(1.0)).collect())
+            .collect();
+        SurfaceGeometry::NURBS(NURBSSurface::new(BSplineSurface::new(
+            self.knot_vecs().clone(),
+            control_points,
+        )))
+    }
+}
+
+impl StepSurface for Surface {
+    fn to_step_surface(&self) -> SurfaceGeometry {
+        match self {
+            Surface::Plane(plane) => plane.to_step_surface(),
+            Surface::BSplineSurface(surface) => surface.to_step_surface(),
+            Surface::NURBSSurface(surface) => surface.to_step_surface(),
+            Surface::RevolutedCurve(surface) => {
+                let mut nurbs = revolution_to_nurbs(surface.entity());
+                nurbs.transform_by(*surface.transform());
+                if !surface.orientation() {
+                    nurbs.swap_axes();
+                }
+                SurfaceGeometry::NURBS(nurbs)
+            }
+        }
+    }
+}
+
+// The exact NURBS representation of the surface of revolution: each control point of
+// the curve is revolved along the rational quadratic full circle with nine control points.
+// Only the parameterization of the angle differs from `RevolutedCurve`.
+fn revolution_to_nurbs(surface: &RevolutedCurve<Curve>) -> NURBSSurface<Vector4> {
+    let curve = surface.entity_curve().to_nurbs();
+    let (origin, axis) = (surface.origin(), surface.axis());
+    let circle_knots = KnotVec::from(vec![
+        0.0, 0.0, 0.0, 0.25, 0.25, 0.5, 0.5, 0.75, 0.75, 1.0, 1.0, 1.0,
+    ]);
+    let control_points = curve
+        .control_points()
+        .iter()
+        .map(|pt| {
+            let w = pt[3];
+            let pt = pt.to_point();
+            let center = origin + (pt - origin).dot(axis) * axis;
+            let r = pt - center;
+            let b = axis.cross(r);
+            let offsets = [r, r + b, b, b - r, -r, -r - b, -b, r - b, r];
+            offsets
+                .iter()
+                .enumerate()
+                .map(|(i, offset)| {
+                    let weight = match i % 2 {
+                        0 => w,
+                        _ => w * FRAC_1_SQRT_2,
+                    };
+                    (center + *offset).to_vec().extend(1.0) * weight
+                })
+                .collect()
+        })
+        .collect();
+    NURBSSurface::new(BSplineSurface::new(
+        (curve.knot_vec().clone(), circle_knots),
+        control_points,
+    ))
+}
@@ -0,0 +1,33 @@
+//! Reads and writes STEP files (ISO 10303-21) of the solids of truck
+//!
+//! The boundary representations are written as the entities of AP203 or AP214:
+//! `MANIFOLD_SOLID_BREP`, `ADVANCED_FACE`, `EDGE_CURVE`, `VERTEX_POINT`, `PLANE`,
+//! `B_SPLINE_CURVE_WITH_KNOTS`, `B_SPLINE_SURFACE_WITH_KNOTS` and so on,
+//! and the solids and shells are reconstructed from these entities with the shared topology.
+
+#![warn(
+    missing_docs,
+    missing_debug_implementations,
+    trivial_casts,
+    trivial_numeric_casts,
+    unsafe_code,
+    unstable_features,
+    unused_import_braces,
+    unused_qualifications
+)]
+
+use truck_modeling::*;
+
+/// Errors occured by STEP files I/O
+pub mod errors;
+mod geometry;
+mod parser;
+mod reader;
+mod writer;
+
+pub use geometry::{StepCurve, StepSurface, SurfaceGeometry};
+pub use reader::{read, read_shells};
+pub use writer::{write, write_shell, Schema, StepOption};
+
+/// `Result` with crate's errors.
+pub type Result<T> = std::result::Result<T, errors::Error>;
@@ -0,0 +1,386 @@
+use crate::errors::Error;
+use crate::Result;
+use std::collections::HashMap;
+
+/// The parameter of the entity instances.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) enum Parameter {
+    Reference(usize),
+    Integer(i64),
+    Real(f64),
+    String(String),
+    Enumeration(String),
+    List(Vec<Parameter>),
+    Typed(String, Vec<Parameter>),
+    Omitted,
+    Derived,
+}
+
+impl Parameter {
+    #[inline(always)]
+    pub(crate) fn reference(&self) -> Option<usize> {
+        match self {
+            Parameter::Reference(id) => Some(*id),
+            _ => None,
+        }
+    }
+    #[inline(always)]
+    pub(crate) fn integer(&self) -> Option<i64> {
+        match self {
+            Parameter::Integer(x) => Some(*x),
+            _ => None,
+        }
+    }
+    pub(crate) fn real(&self) -> Option<f64> {
+        match self {
+            Parameter::Real(x) => Some(*x),
+            Parameter::Integer(x) => Some(*x as f64),
+            Parameter::Typed(_, params) if params.len() == 1 => params[0].real(),
+            _ => None,
+        }
+    }
+    #[inline(always)]
+    pub(crate) fn logical(&self) -> Option<bool> {
+        match self {
+            Parameter::Enumeration(x) if x == "T" => Some(true),
+            Parameter::Enumeration(x) if x == "F" => Some(false),
+            _ => None,
+        }
+    }
+    #[inline(always)]
+    pub(crate) fn list(&self) -> Option<&[Parameter]> {
+        match self {
+            Parameter::List(list) => Some(list),
+            _ => None,
+        }
+    }
+}
+
+/// The partial entity instance: the type name and the attributes.
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct Record {
+    pub name: String,
+    pub params: Vec<Parameter>,
+}
+
+/// The entity instances of the data sections. The simple entity instances consist of one record,
+/// and the complex entity instances consist of the records of the partial entities.
+pub(crate) type Entities = HashMap<usize, Vec<Record>>;
+
+/// Parses the exchange structure of ISO 10303-21 and returns the entity instances.
+pub(crate) fn parse(text: &str) -> Result<Entities> {
+    let mut parser = Parser {
+        text: text.as_bytes(),
+        pos: 0,
+    };
+    let mut entities = Entities::new();
+    parser.expect_keyword("ISO-10303-21")?;
+    parser.consume(b';')?;
+    parser.expect_keyword("HEADER")?;
+    parser.consume(b';')?;
+    loop {
+        let name = parser.keyword()?;
+        if name == "ENDSEC" {
+            parser.consume(b';')?;
+            break;
+        }
+        parser.params()?;
+        parser.consume(b';')?;
+    }
+    loop {
+        match parser.keyword()?.as_str() {
+            "DATA" => {
+                // the parameters of the data sections since the third edition
+                if parser.peek() == Some(b'(') {
+                    parser.params()?;
+                }
+                parser.consume(b';')?;
+                parser.data(&mut entities)?;
+            }
+            "END-ISO-10303-21" => return Ok(entities),
+            _ => return Err(parser.error()),
+        }
+    }
+}
+
+struct Parser<'a> {
+    text: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self) -> Error {
+        let end = usize::min(self.pos, self.text.len());
+        let line = self.text[..end].iter().filter(|c| **c == b'\n').count() + 1;
+        Error::SyntaxError(line)
+    }
+
+    // skips the white spaces and the comments
+    fn skip(&mut self) {
+        loop {
+            while self.pos < self.text.len() && self.text[self.pos].is_ascii_whitespace() {
+                self.pos += 1;
+            }
+            if !self.text[self.pos..].starts_with(b"/*") {
+                return;
+            }
+            self.pos = match self.text[self.pos + 2..]
+                .windows(2)
+                .position(|w| w == b"*/")
+            {
+                Some(idx) => self.pos + idx + 4,
+                None => self.text.len(),
+            };
+        }
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip();
+        self.text.get(self.pos).copied()
+    }
+
+    fn consume(&mut self, c: u8) -> Result<()> {
+        match self.peek() == Some(c) {
+            true => {
+                self.pos += 1;
+                Ok(())
+            }
+            false => Err(self.error()),
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(u8) -> bool) -> &'a [u8] {
+        let text = self.text;
+        let start = self.pos;
+        while self.pos < text.len() && predicate(text[self.pos]) {
+            self.pos += 1;
+        }
+        &text[start..self.pos]
+    }
+
+    fn keyword(&mut self) -> Result<String> {
+        self.skip();
+        let keyword = self.take_while(|c| c.is_ascii_alphanumeric() || c == b'_' || c == b'-');
+        match keyword.first() {
+            Some(c) if c.is_ascii_alphabetic() => {
+                Ok(String::from_utf8_lossy(keyword).to_ascii_uppercase())
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    fn expect_keyword(&mut self, expected: &str) -> Result<()> {
+        match self.keyword()? == expected {
+            true => Ok(()),
+            false => Err(self.error()),
+        }
+    }
+
+    fn unsigned(&mut self) -> Result<usize> {
+        let digits = self.take_while(|c| c.is_ascii_digit());
+        std::str::from_utf8(digits)
+            .ok()
+            .and_then(|digits| digits.parse().ok())
+            .ok_or_else(|| self.error())
+    }
+
+    fn data(&mut self, entities: &mut Entities) -> Result<()> {
+        while self.peek() == Some(b'#') {
+            self.pos += 1;
+            let id = self.unsigned()?;
+            self.consume(b'=')?;
+            let records = match self.peek() {
+                Some(b'(') => {
+                    self.pos += 1;
+                    let mut records = Vec::new();
+                    while self.peek() != Some(b')') {
+                        records.push(self.record()?);
+                    }
+                    self.pos += 1;
+                    records
+                }
+                _ => vec![self.record()?],
+            };
+            self.consume(b';')?;
+            entities.insert(id, records);
+        }
+        self.expect_keyword("ENDSEC")?;
+        self.consume(b';')
+    }
+
+    fn record(&mut self) -> Result<Record> {
+        let name = self.keyword()?;
+        let params = self.params()?;
+        Ok(Record { name, params })
+    }
+
+    fn params(&mut self) -> Result<Vec<Parameter>> {
+        self.consume(b'(')?;
+        let mut params = Vec::new();
+        if self.peek() == Some(b')') {
+            self.pos += 1;
+            return Ok(params);
+        }
+        loop {
+            params.push(self.parameter()?);
+            match self.peek() {
+                Some(b',') => self.pos += 1,
+                Some(b')') => {
+                    self.pos += 1;
+                    return Ok(params);
+                }
+                _ => return Err(self.error()),
+            }
+        }
+    }
+
+    fn parameter(&mut self) -> Result<Parameter> {
+        match self.peek() {
+            Some(b'#') => {
+                self.pos += 1;
+                Ok(Parameter::Reference(self.unsigned()?))
+            }
+            Some(b'\'') => self.string(),
+            Some(b'"') => {
+                self.pos += 1;
+                let binary = self.take_while(|c| c != b'"');
+                let binary = String::from_utf8_lossy(binary).into_owned();
+                self.consume(b'"')?;
+                Ok(Parameter::String(binary))
+            }
+            Some(b'.') => {
+                self.pos += 1;
+                let enumeration = self.take_while(|c| c != b'.');
+                let enumeration = String::from_utf8_lossy(enumeration).to_ascii_uppercase();
+                self.consume(b'.')?;
+                Ok(Parameter::Enumeration(enumeration))
+            }
+            Some(b'$') => {
+                self.pos += 1;
+                Ok(Parameter::Omitted)
+            }
+            Some(b'*') => {
+                self.pos += 1;
+                Ok(Parameter::Derived)
+            }
+            Some(b'(') => Ok(Parameter::List(self.params()?)),
+            Some(c) if c.is_ascii_digit() || c == b'+' || c == b'-' => self.number(),
+            Some(c) if c.is_ascii_alphabetic() => {
+                let name = self.keyword()?;
+                Ok(Parameter::Typed(name, self.params()?))
+            }
+            _ => Err(self.error()),
+        }
+    }
+
+    // The apostrophes are escaped by doubling, as well as the backslashes.
+    fn string(&mut self) -> Result<Parameter> {
+        self.pos += 1;
+        let mut bytes = Vec::new();
+        loop {
+            match self.text.get(self.pos) {
+                Some(b'\'') if self.text.get(self.pos + 1) == Some(&b'\'') => {
+                    bytes.push(b'\'');
+                    self.pos += 2;
+                }
+                Some(b'\'') => {
+                    self.pos += 1;
+                    return Ok(Parameter::String(
+                        String::from_utf8_lossy(&bytes).into_owned(),
+                    ));
+                }
+                Some(b'\\') if self.text.get(self.pos + 1) == Some(&b'\\') => {
+                    bytes.push(b'\\');
+                    self.pos += 2;
+                }
+                Some(c) => {
+                    bytes.push(*c);
+                    self.pos += 1;
+                }
+                None => return Err(self.error()),
+            }
+        }
+    }
+
+    fn number(&mut self) -> Result<Parameter> {
+        let number = self.take_while(|c| {
+            c.is_ascii_digit() || c == b'+' || c == b'-' || c == b'.' || c == b'E' || c == b'e'
+        });
+        let number = std::str::from_utf8(number).map_err(|_| self.error())?;
+        let parameter = match number.contains(['.', 'E', 'e']) {
+            true => number.parse().ok().map(Parameter::Real),
+            false => number.parse().ok().map(Parameter::Integer),
+        };
+        parameter.ok_or_else(|| self.error())
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn data_section(data: &str) -> String {
+    "ISO-10303-21;\nHEADER;\nFILE_NAME('a.stp', '', (''), (''), '', '', '');\nENDSEC;\nDATA;\n"
+        .to_string()
+        + data
+        + "\nENDSEC;\nEND-ISO-10303-21;\n"
+}
+
+#[test]
+fn parse_entities() {
+    let text = data_section(
+        "#1 = CARTESIAN_POINT('it''s', (0., -1.5E1, +2));\n\
+         /* a comment; */ #2 = ORIENTED_EDGE('', *, *, #1, .T.);\n\
+         #3 = (GEOMETRIC_REPRESENTATION_CONTEXT(3) LENGTH_UNIT());\n\
+         #4 = B_SPLINE_CURVE('', $, POSITIVE_LENGTH_MEASURE(0.5));",
+    );
+    let entities = parse(&text).unwrap();
+    assert_eq!(entities.len(), 4);
+    let params = &entities[&1][0].params;
+    assert_eq!(params[0], Parameter::String("it's".to_string()));
+    let reals: Vec<f64> = params[1].list().unwrap().iter().map(|x| x.real().unwrap()).collect();
+    assert_eq!(reals, vec![0.0, -15.0, 2.0]);
+    assert_eq!(params[1].list().unwrap()[2].integer(), Some(2));
+    let params = &entities[&2][0].params;
+    assert_eq!(params[1], Parameter::Derived);
+    assert_eq!(params[3].reference(), Some(1));
+    assert_eq!(params[4].logical(), Some(true));
+    let names: Vec<&str> = entities[&3].iter().map(|record| record.name.as_str()).collect();
+    assert_eq!(names, vec!["GEOMETRIC_REPRESENTATION_CONTEXT", "LENGTH_UNIT"]);
+    let params = &entities[&4][0].params;
+    assert_eq!(params[1], Parameter::Omitted);
+    assert_eq!(params[2].real(), Some(0.5));
+}
+
+#[test]
+fn parse_malformed_entities() {
+    // the data section starts at the line 6.
+    let malformed = [
+        // the missing semicolon
+        ("#1 = CARTESIAN_POINT('', (0., 0., 0.))\n#2 = VERTEX_POINT('', #1);", 7),
+        // the missing equal sign
+        ("#1 CARTESIAN_POINT('', (0., 0., 0.));", 6),
+        // the unclosed list
+        ("#1 = CARTESIAN_POINT('', (0., 0., 0.);", 6),
+        // the missing comma
+        ("#1 = CARTESIAN_POINT('' (0., 0., 0.));", 6),
+        // the invalid number
+        ("\n#1 = CARTESIAN_POINT('', (0.-1, 0., 0.));", 7),
+        // the entity name starting with a digit
+        ("#1 = 2D_POINT('');", 6),
+        // the reference without an id
+        ("#1 = VERTEX_POINT('', #);", 6),
+    ];
+    for (data, line) in &malformed {
+        match parse(&data_section(data)) {
+            Err(Error::SyntaxError(l)) => assert_eq!(l, *line, "{}", data),
+            result => panic!("{}: {:?}", data, result),
+        }
+    }
+    // the unterminated string runs to the end of the file.
+    let text = data_section("#1 = CARTESIAN_POINT('a, (0., 0., 0.));");
+    assert!(matches!(parse(&text), Err(Error::SyntaxError(9))));
+    // the missing end of the exchange structure
+    let text = data_section("").replace("END-ISO-10303-21;\n", "");
+    assert!(matches!(parse(&text), Err(Error::SyntaxError(_))));
+    // the missing header
+    let text = "ISO-10303-21;\nDATA;\nENDSEC;\nEND-ISO-10303-21;\n";
+    assert!(matches!(parse(text), Err(Error::SyntaxError(2))));
+}
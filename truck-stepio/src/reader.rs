@@ -0,0 +1,591 @@
+use crate::errors::Error;
+use crate::parser::{parse, Entities, Parameter, Record};
+use crate::*;
+use std::collections::HashMap;
+use std::io::Read;
+
+/// Reads the solids of `ADVANCED_BREP_SHAPE_REPRESENTATION` from the STEP file.
+///
+/// `MANIFOLD_SOLID_BREP` and `BREP_WITH_VOIDS` are reconstructed with the shared vertices and
+/// edges. The supported geometries are `PLANE`, `LINE`, `B_SPLINE_CURVE_WITH_KNOTS`,
+/// `B_SPLINE_SURFACE_WITH_KNOTS` and their rational versions. The units are not converted.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// use truck_modeling::statistics::SolidStatistics;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let mut step = Vec::new();
+/// truck_stepio::write(&cube, &mut step, &Default::default()).unwrap();
+/// let solids = truck_stepio::read(step.as_slice()).unwrap();
+/// assert_eq!(solids.len(), 1);
+///
+/// // The round trip preserves the topology.
+/// let (stats0, stats1) = (cube.stats(), solids[0].stats());
+/// assert_eq!(stats0.faces, stats1.faces);
+/// assert_eq!(stats0.edges, stats1.edges);
+/// assert_eq!(stats0.vertices, stats1.vertices);
+/// assert!(solids[0].is_geometric_consistent());
+/// ```
+pub fn read<R: Read>(reader: R) -> Result<Vec<Solid>> {
+    let entities = parse_reader(reader)?;
+    let mut builder = Builder::new(&entities);
+    let mut solids = Vec::new();
+    for id in builder.instances_of("ADVANCED_BREP_SHAPE_REPRESENTATION") {
+        for item in builder.items(id)? {
+            match builder.name(item)? {
+                "MANIFOLD_SOLID_BREP" | "BREP_WITH_VOIDS" => solids.push(builder.solid(item)?),
+                _ => {}
+            }
+        }
+    }
+    Ok(solids)
+}
+
+/// Reads the shells of `MANIFOLD_SURFACE_SHAPE_REPRESENTATION` from the STEP file.
+///
+/// The shells of all `SHELL_BASED_SURFACE_MODEL` are reconstructed with the shared vertices
+/// and edges. The supported geometries are the same as [`read`](./fn.read.html).
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let shell: Shell = vec![f].into();
+///
+/// let mut step = Vec::new();
+/// truck_stepio::write_shell(&shell, &mut step, &Default::default()).unwrap();
+/// let shells = truck_stepio::read_shells(step.as_slice()).unwrap();
+/// assert_eq!(shells.len(), 1);
+/// assert_eq!(shells[0].len(), 1);
+/// assert_eq!(shells[0][0].boundaries()[0].len(), 4);
+/// ```
+pub fn read_shells<R: Read>(reader: R) -> Result<Vec<Shell>> {
+    let entities = parse_reader(reader)?;
+    let mut builder = Builder::new(&entities);
+    let mut shells = Vec::new();
+    for id in builder.instances_of("MANIFOLD_SURFACE_SHAPE_REPRESENTATION") {
+        for item in builder.items(id)? {
+            if builder.name(item)? != "SHELL_BASED_SURFACE_MODEL" {
+                continue;
+            }
+            let params = builder.params(item, 2)?;
+            let list = params[1].list().ok_or(Error::InvalidEntity(item))?;
+            for shell in list {
+                let shell = shell.reference().ok_or(Error::InvalidEntity(item))?;
+                shells.push(builder.shell(shell)?);
+            }
+        }
+    }
+    Ok(shells)
+}
+
+fn parse_reader<R: Read>(mut reader: R) -> Result<Entities> {
+    let mut text = String::new();
+    reader.read_to_string(&mut text)?;
+    parse(&text)
+}
+
+// Reconstructs the topology, sharing the vertices and the edges referred by several elements.
+struct Builder<'a> {
+    entities: &'a Entities,
+    vertices: HashMap<usize, Vertex>,
+    edges: HashMap<usize, Edge>,
+}
+
+impl<'a> Builder<'a> {
+    fn new(entities: &'a Entities) -> Self {
+        Builder {
+            entities,
+            vertices: HashMap::new(),
+            edges: HashMap::new(),
+        }
+    }
+
+    // the ids of the simple entity instances of the type, in the ascending order
+    fn instances_of(&self, name: &str) -> Vec<usize> {
+        let mut ids: Vec<usize> = self
+            .entities
+            .iter()
+            .filter(|(_, records)| records.len() == 1 && records[0].name == name)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    fn records(&self, id: usize) -> Result<&'a [Record]> {
+        match self.entities.get(&id) {
+            Some(records) => Ok(records),
+            None => Err(Error::MissingEntity(id)),
+        }
+    }
+
+    // the type name of the simple entity instance
+    fn name(&self, id: usize) -> Result<&'a str> {
+        match self.records(id)? {
+            [record] => Ok(&record.name),
+            _ => Err(Error::UnsupportedEntity(id, "complex entity".into())),
+        }
+    }
+
+    // the attributes of the simple entity instance, which are checked to be at least `len`
+    fn params(&self, id: usize, len: usize) -> Result<&'a [Parameter]> {
+        match self.records(id)? {
+            [record] if record.params.len() >= len => Ok(&record.params),
+            [_] => Err(Error::InvalidEntity(id)),
+            _ => Err(Error::UnsupportedEntity(id, "complex entity".into())),
+        }
+    }
+
+    // the attributes of the partial entity of the complex entity instance
+    fn partial(&self, id: usize, name: &str) -> Result<Option<&'a [Parameter]>> {
+        Ok(self
+            .records(id)?
+            .iter()
+            .find(|record| record.name == name)
+            .map(|record| record.params.as_slice()))
+    }
+
+    // the representation items
+    fn items(&self, id: usize) -> Result<Vec<usize>> {
+        let params = self.params(id, 3)?;
+        let list = params[1].list().ok_or(Error::InvalidEntity(id))?;
+        references(list, id)
+    }
+
+    fn point(&self, id: usize) -> Result<Point3> {
+        let params = self.params(id, 2)?;
+        match reals(&params[1], id)?.as_slice() {
+            [x, y, z] => Ok(Point3::new(*x, *y, *z)),
+            _ => Err(Error::InvalidEntity(id)),
+        }
+    }
+
+    fn direction(&self, id: usize) -> Result<Vector3> {
+        let params = self.params(id, 2)?;
+        match reals(&params[1], id)?.as_slice() {
+            [x, y, z] => Ok(Vector3::new(*x, *y, *z)),
+            _ => Err(Error::InvalidEntity(id)),
+        }
+    }
+
+    fn vertex(&mut self, id: usize) -> Result<Vertex> {
+        if let Some(vertex) = self.vertices.get(&id) {
+            return Ok(vertex.clone());
+        }
+        let params = self.params(id, 2)?;
+        let point = self.point(reference(&params[1], id)?)?;
+        let vertex = Vertex::new(point);
+        self.vertices.insert(id, vertex.clone());
+        Ok(vertex)
+    }
+
+    // `EDGE_CURVE`, whose curve is along the direction of the edge.
+    fn edge(&mut self, id: usize) -> Result<Edge> {
+        if let Some(edge) = self.edges.get(&id) {
+            return Ok(edge.clone());
+        }
+        let params = self.params(id, 5)?;
+        let front = self.vertex(reference(&params[1], id)?)?;
+        let back = self.vertex(reference(&params[2], id)?)?;
+        let same_sense = logical(&params[4], id)?;
+        let (p, q) = (*front.lock_point().unwrap(), *back.lock_point().unwrap());
+        let curve = match same_sense {
+            true => self.curve(reference(&params[3], id)?, p, q)?,
+            false => self.curve(reference(&params[3], id)?, q, p)?.inverse(),
+        };
+        let edge = Edge::try_new(&front, &back, curve)?;
+        self.edges.insert(id, edge.clone());
+        Ok(edge)
+    }
+
+    // `start` and `end` are the end points of the bounded curve, used for the lines.
+    fn curve(&self, id: usize, start: Point3, end: Point3) -> Result<Curve> {
+        let records = self.records(id)?;
+        if records.len() > 1 {
+            return self.bspline_curve(id);
+        }
+        let params = &records[0].params;
+        match records[0].name.as_str() {
+            "LINE" => Ok(Curve::BSplineCurve(BSplineCurve::new(
+                KnotVec::bezier_knot(1),
+                vec![start.to_vec(), end.to_vec()],
+            ))),
+            "B_SPLINE_CURVE_WITH_KNOTS" => self.bspline_curve(id),
+            "SURFACE_CURVE" | "SEAM_CURVE" if params.len() >= 2 => {
+                self.curve(reference(&params[1], id)?, start, end)
+            }
+            name => Err(Error::UnsupportedEntity(id, name.into())),
+        }
+    }
+
+    // `Option::is_none_or` is too new for the supported compilers.
+    #[allow(clippy::unnecessary_map_or)]
+    fn bspline_curve(&self, id: usize) -> Result<Curve> {
+        let (points, mults, knots, weights) = match self.records(id)? {
+            [record] if record.params.len() >= 8 => {
+                let params = &record.params;
+                (&params[2], &params[6], &params[7], None)
+            }
+            [_] => return Err(Error::InvalidEntity(id)),
+            _ => {
+                let curve = self.partial(id, "B_SPLINE_CURVE")?;
+                let with_knots = self.partial(id, "B_SPLINE_CURVE_WITH_KNOTS")?;
+                let rational = self.partial(id, "RATIONAL_B_SPLINE_CURVE")?;
+                match (curve, with_knots) {
+                    (Some(curve), Some(with_knots))
+                        if curve.len() >= 2
+                            && with_knots.len() >= 2
+                            && rational.map_or(true, |r| !r.is_empty()) =>
+                    {
+                        (
+                            &curve[1],
+                            &with_knots[0],
+                            &with_knots[1],
+                            rational.map(|r| &r[0]),
+                        )
+                    }
+                    _ => return Err(Error::UnsupportedEntity(id, "complex entity".into())),
+                }
+            }
+        };
+        let knot_vec = knot_vec(mults, knots, id)?;
+        let points = points.list().ok_or(Error::InvalidEntity(id))?;
+        let points = references(points, id)?
+            .into_iter()
+            .map(|pt| self.point(pt))
+            .collect::<Result<Vec<_>>>()?;
+        match weights {
+            None => {
+                let points = points.into_iter().map(|pt| pt.to_vec()).collect();
+                BSplineCurve::try_new(knot_vec, points)
+                    .map(Curve::BSplineCurve)
+                    .map_err(|_| Error::InvalidEntity(id))
+            }
+            Some(weights) => {
+                let weights = reals(weights, id)?;
+                if weights.len() != points.len() {
+                    return Err(Error::InvalidEntity(id));
+                }
+                let points = points
+                    .into_iter()
+                    .zip(weights)
+                    .map(|(pt, w)| (pt.to_vec() * w).extend(w))
+                    .collect();
+                BSplineCurve::try_new(knot_vec, points)
+                    .map(|curve| Curve::NURBSCurve(NURBSCurve::new(curve)))
+                    .map_err(|_| Error::InvalidEntity(id))
+            }
+        }
+    }
+
+    fn surface(&self, id: usize) -> Result<Surface> {
+        let records = self.records(id)?;
+        if records.len() > 1 {
+            return self.bspline_surface(id);
+        }
+        match records[0].name.as_str() {
+            "PLANE" => {
+                let params = self.params(id, 2)?;
+                self.plane(reference(&params[1], id)?)
+            }
+            "B_SPLINE_SURFACE_WITH_KNOTS" => self.bspline_surface(id),
+            name => Err(Error::UnsupportedEntity(id, name.into())),
+        }
+    }
+
+    // the plane of `AXIS2_PLACEMENT_3D`, whose normal is the axis
+    fn plane(&self, id: usize) -> Result<Surface> {
+        let params = self.params(id, 4)?;
+        let origin = self.point(reference(&params[1], id)?)?;
+        let axis = match &params[2] {
+            Parameter::Omitted => Vector3::unit_z(),
+            param => self.direction(reference(param, id)?)?.normalize(),
+        };
+        let ref_direction = match &params[3] {
+            Parameter::Omitted if axis[0].abs() < 0.9 => Vector3::unit_x(),
+            Parameter::Omitted => Vector3::unit_y(),
+            param => self.direction(reference(param, id)?)?,
+        };
+        let x = (ref_direction - ref_direction.dot(axis) * axis).normalize();
+        let y = axis.cross(x);
+        Ok(Surface::Plane(Plane::new(origin, origin + x, origin + y)))
+    }
+
+    #[allow(clippy::unnecessary_map_or)]
+    fn bspline_surface(&self, id: usize) -> Result<Surface> {
+        let (points, umults, vmults, uknots, vknots, weights) = match self.records(id)? {
+            [record] if record.params.len() >= 12 => {
+                let params = &record.params;
+                (
+                    &params[3],
+                    &params[8],
+                    &params[9],
+                    &params[10],
+                    &params[11],
+                    None,
+                )
+            }
+            [_] => return Err(Error::InvalidEntity(id)),
+            _ => {
+                let surface = self.partial(id, "B_SPLINE_SURFACE")?;
+                let with_knots = self.partial(id, "B_SPLINE_SURFACE_WITH_KNOTS")?;
+                let rational = self.partial(id, "RATIONAL_B_SPLINE_SURFACE")?;
+                match (surface, with_knots) {
+                    (Some(surface), Some(with_knots))
+                        if surface.len() >= 3
+                            && with_knots.len() >= 4
+                            && rational.map_or(true, |r| !r.is_empty()) =>
+                    {
+                        (
+                            &surface[2],
+                            &with_knots[0],
+                            &with_knots[1],
+                            &with_knots[2],
+                            &with_knots[3],
+                            rational.map(|r| &r[0]),
+                        )
+                    }
+                    _ => return Err(Error::UnsupportedEntity(id, "complex entity".into())),
+                }
+            }
+        };
+        let knot_vecs = (knot_vec(umults, uknots, id)?, knot_vec(vmults, vknots, id)?);
+        let points = points
+            .list()
+            .ok_or(Error::InvalidEntity(id))?
+            .iter()
+            .map(|row| {
+                let row = row.list().ok_or(Error::InvalidEntity(id))?;
+                references(row, id)?
+                    .into_iter()
+                    .map(|pt| self.point(pt))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .collect::<Result<Vec<_>>>()?;
+        match weights {
+            None => {
+                let points = points
+                    .into_iter()
+                    .map(|row| row.into_iter().map(|pt| pt.to_vec()).collect())
+                    .collect();
+                BSplineSurface::try_new(knot_vecs, points)
+                    .map(Surface::BSplineSurface)
+                    .map_err(|_| Error::InvalidEntity(id))
+            }
+            Some(weights) => {
+                let weights = weights
+                    .list()
+                    .ok_or(Error::InvalidEntity(id))?
+                    .iter()
+                    .map(|row| reals(row, id))
+                    .collect::<Result<Vec<_>>>()?;
+                let same_shape = weights.len() == points.len()
+                    && weights
+                        .iter()
+                        .zip(&points)
+                        .all(|(w, pt)| w.len() == pt.len());
+                if !same_shape {
+                    return Err(Error::InvalidEntity(id));
+                }
+                let points = points
+                    .into_iter()
+                    .zip(weights)
+                    .map(|(row, weights)| {
+                        row.into_iter()
+                            .zip(weights)
+                            .map(|(pt, w)| (pt.to_vec() * w).extend(w))
+                            .collect()
+                    })
+                    .collect();
+                BSplineSurface::try_new(knot_vecs, points)
+                    .map(|surface| Surface::NURBSSurface(NURBSSurface::new(surface)))
+                    .map_err(|_| Error::InvalidEntity(id))
+            }
+        }
+    }
+
+    // `ORIENTED_EDGE`
+    fn oriented_edge(&mut self, id: usize) -> Result<Edge> {
+        let params = self.params(id, 5)?;
+        let edge = self.edge(reference(&params[3], id)?)?;
+        match logical(&params[4], id)? {
+            true => Ok(edge),
+            false => Ok(edge.inverse()),
+        }
+    }
+
+    // `FACE_BOUND` or `FACE_OUTER_BOUND`, the wire along the orientation of the face
+    fn bound(&mut self, id: usize) -> Result<Wire> {
+        let params = self.params(id, 3)?;
+        let edge_loop = reference(&params[1], id)?;
+        let loop_params = self.params(edge_loop, 2)?;
+        let edges = loop_params[1]
+            .list()
+            .ok_or(Error::InvalidEntity(edge_loop))?;
+        let wire = references(edges, edge_loop)?
+            .into_iter()
+            .map(|edge| self.oriented_edge(edge))
+            .collect::<Result<Wire>>()?;
+        match logical(&params[2], id)? {
+            true => Ok(wire),
+            false => Ok(wire.inverse()),
+        }
+    }
+
+    // `ADVANCED_FACE` or `FACE_SURFACE`
+    fn face(&mut self, id: usize) -> Result<Face> {
+        let params = self.params(id, 4)?;
+        let bounds = params[1].list().ok_or(Error::InvalidEntity(id))?;
+        let mut bounds = references(bounds, id)?;
+        // The outer boundary comes first.
+        bounds.sort_by_key(|bound| self.name(*bound).ok() != Some("FACE_OUTER_BOUND"));
+        let mut wires = bounds
+            .into_iter()
+            .map(|bound| self.bound(bound))
+            .collect::<Result<Vec<_>>>()?;
+        let surface = self.surface(reference(&params[2], id)?)?;
+        match logical(&params[3], id)? {
+            true => Ok(Face::try_new(wires, surface)?),
+            false => {
+                wires.iter_mut().for_each(|wire| {
+                    wire.invert();
+                });
+                let mut face = Face::try_new(wires, surface)?;
+                face.invert();
+                Ok(face)
+            }
+        }
+    }
+
+    // `CLOSED_SHELL`, `OPEN_SHELL` or `ORIENTED_CLOSED_SHELL`
+    fn shell(&mut self, id: usize) -> Result<Shell> {
+        match self.name(id)? {
+            "CLOSED_SHELL" | "OPEN_SHELL" => {
+                let params = self.params(id, 2)?;
+                let faces = params[1].list().ok_or(Error::InvalidEntity(id))?;
+                references(faces, id)?
+                    .into_iter()
+                    .map(|face| self.face(face))
+                    .collect()
+            }
+            "ORIENTED_CLOSED_SHELL" => {
+                let params = self.params(id, 4)?;
+                let shell = self.shell(reference(&params[2], id)?)?;
+                match logical(&params[3], id)? {
+                    true => Ok(shell),
+                    false => Ok(shell.iter().map(Face::inverse).collect()),
+                }
+            }
+            name => Err(Error::UnsupportedEntity(id, name.into())),
+        }
+    }
+
+    // `MANIFOLD_SOLID_BREP` or `BREP_WITH_VOIDS`
+    fn solid(&mut self, id: usize) -> Result<Solid> {
+        let params = self.params(id, 2)?;
+        let mut shells = vec![self.shell(reference(&params[1], id)?)?];
+        if self.name(id)? == "BREP_WITH_VOIDS" {
+            let voids = params
+                .get(2)
+                .and_then(Parameter::list)
+                .ok_or(Error::InvalidEntity(id))?;
+            for void in references(voids, id)? {
+                shells.push(self.shell(void)?);
+            }
+        }
+        Ok(Solid::try_new(shells)?)
+    }
+}
+
+#[inline(always)]
+fn reference(param: &Parameter, id: usize) -> Result<usize> {
+    param.reference().ok_or(Error::InvalidEntity(id))
+}
+
+fn references(list: &[Parameter], id: usize) -> Result<Vec<usize>> {
+    list.iter().map(|param| reference(param, id)).collect()
+}
+
+#[inline(always)]
+fn logical(param: &Parameter, id: usize) -> Result<bool> {
+    param.logical().ok_or(Error::InvalidEntity(id))
+}
+
+fn reals(param: &Parameter, id: usize) -> Result<Vec<f64>> {
+    param
+        .list()
+        .ok_or(Error::InvalidEntity(id))?
+        .iter()
+        .map(|x| x.real().ok_or(Error::InvalidEntity(id)))
+        .collect()
+}
+
+fn knot_vec(mults: &Parameter, knots: &Parameter, id: usize) -> Result<KnotVec> {
+    let mults = mults
+        .list()
+        .ok_or(Error::InvalidEntity(id))?
+        .iter()
+        .map(|x| match x.integer() {
+            Some(x) if x > 0 => Ok(x as usize),
+            _ => Err(Error::InvalidEntity(id)),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    KnotVec::from_single_multi(reals(knots, id)?, mults).map_err(|_| Error::InvalidEntity(id))
+}
+
+#[test]
+fn read_invalid_entities() {
+    use crate::parser::data_section;
+    let read_data = |data: &str| read(data_section(data).as_bytes());
+    const REPRESENTATION: &str = "#1 = ADVANCED_BREP_SHAPE_REPRESENTATION('', (#2), #9);\n";
+    let result = read_data("#1 = ADVANCED_BREP_SHAPE_REPRESENTATION('', (#10), #9);");
+    assert!(matches!(result, Err(Error::MissingEntity(10))));
+    let result = read_data("#1 = ADVANCED_BREP_SHAPE_REPRESENTATION('', #10, #9);");
+    assert!(matches!(result, Err(Error::InvalidEntity(1))));
+    let result = read_data(&(REPRESENTATION.to_string() + "#2 = MANIFOLD_SOLID_BREP('', '');"));
+    assert!(matches!(result, Err(Error::InvalidEntity(2))));
+    let result = read_data(
+        &(REPRESENTATION.to_string()
+            + "#2 = MANIFOLD_SOLID_BREP('', #3);\n#3 = CONNECTED_FACE_SET('', ());"),
+    );
+    match result {
+        Err(Error::UnsupportedEntity(3, name)) => assert_eq!(name, "CONNECTED_FACE_SET"),
+        result => panic!("{:?}", result),
+    }
+    let result = read_data(
+        &(REPRESENTATION.to_string()
+            + "#2 = MANIFOLD_SOLID_BREP('', #3);\n\
+               #3 = (CLOSED_SHELL('', ()) REPRESENTATION_ITEM(''));"),
+    );
+    match result {
+        Err(Error::UnsupportedEntity(3, name)) => assert_eq!(name, "complex entity"),
+        result => panic!("{:?}", result),
+    }
+    // the solid without faces
+    let result = read_data(
+        &(REPRESENTATION.to_string()
+            + "#2 = MANIFOLD_SOLID_BREP('', #3);\n#3 = CLOSED_SHELL('', ());"),
+    );
+    assert!(matches!(result, Err(Error::FromTopology(_))));
+    // the syntax error in the file
+    let result = read_data("#1 = ADVANCED_BREP_SHAPE_REPRESENTATION('', (#2), #9)");
+    assert!(matches!(result, Err(Error::SyntaxError(7))));
+    // the file not in UTF-8
+    let result = read(&[0xff_u8, 0xfe, 0xfd][..]);
+    assert!(matches!(result, Err(Error::FromIO(_))));
+
+    let result = read_shells(
+        data_section(
+            "#1 = MANIFOLD_SURFACE_SHAPE_REPRESENTATION('', (#2), #9);\n\
+             #2 = SHELL_BASED_SURFACE_MODEL('', #3);",
+        )
+        .as_bytes(),
+    );
+    assert!(matches!(result, Err(Error::InvalidEntity(2))));
+}
@@ -0,0 +1,513 @@
+use crate::*;
+use std::collections::HashMap;
+use std::io::Write;
+use truck_topology::shell::ShellCondition;
+use truck_topology::{EdgeID, VertexID};
+
+/// The application protocols of the output STEP files.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Schema {
+    /// AP203, `CONFIG_CONTROL_DESIGN`
+    AP203,
+    /// AP214, `AUTOMOTIVE_DESIGN`
+    AP214,
+}
+
+/// The options of the output STEP files.
+///
+/// The lengths are written in millimetres.
+#[derive(Clone, Debug)]
+pub struct StepOption {
+    /// the application protocol, default is `Schema::AP214`
+    pub schema: Schema,
+    /// the name of the file and the product
+    pub name: String,
+    /// the time stamp of the header in ISO 8601, e.g. `2021-04-01T12:00:00`
+    pub time_stamp: String,
+    /// the author of the file
+    pub author: String,
+    /// the organization of the author
+    pub organization: String,
+}
+
+impl Default for StepOption {
+    #[inline(always)]
+    fn default() -> StepOption {
+        StepOption {
+            schema: Schema::AP214,
+            name: String::from("truck"),
+            time_stamp: String::new(),
+            author: String::new(),
+            organization: String::new(),
+        }
+    }
+}
+
+/// Writes the solid to the STEP file as `MANIFOLD_SOLID_BREP`.
+///
+/// The first boundary shell is written as the outer shell, and the others are written as
+/// the voids of `BREP_WITH_VOIDS`.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let cube = builder::tsweep(&f, Vector3::unit_z());
+///
+/// let mut step = Vec::new();
+/// truck_stepio::write(&cube, &mut step, &Default::default()).unwrap();
+/// let step = String::from_utf8(step).unwrap();
+/// assert!(step.starts_with("ISO-10303-21;"));
+/// assert_eq!(step.matches("MANIFOLD_SOLID_BREP").count(), 1);
+/// assert_eq!(step.matches("ADVANCED_FACE").count(), 6);
+/// assert_eq!(step.matches("EDGE_CURVE").count(), 12);
+/// assert_eq!(step.matches("VERTEX_POINT").count(), 8);
+/// assert!(step.contains("B_SPLINE_CURVE_WITH_KNOTS"));
+/// ```
+pub fn write<C: StepCurve, S: StepSurface, W: Write>(
+    solid: &truck_topology::Solid<Point3, C, S>,
+    writer: W,
+    option: &StepOption,
+) -> Result<()> {
+    let mut data = DataSection::default();
+    let (shape, context) = data.product(option);
+    let mut items = Vec::new();
+    if let Some((outer, voids)) = solid.boundaries().split_first() {
+        let outer = data.shell(outer, "CLOSED_SHELL", false);
+        let brep = match voids.is_empty() {
+            true => data.push(format!("MANIFOLD_SOLID_BREP('',{})", outer)),
+            false => {
+                let voids: Vec<String> = voids
+                    .iter()
+                    .map(|shell| {
+                        let shell = data.shell(shell, "CLOSED_SHELL", true);
+                        data.push(format!("ORIENTED_CLOSED_SHELL('',*,{},.F.)", shell))
+                    })
+                    .collect();
+                data.push(format!(
+                    "BREP_WITH_VOIDS('',{},({}))",
+                    outer,
+                    voids.join(",")
+                ))
+            }
+        };
+        items.push(brep);
+    }
+    items.push(data.origin());
+    let representation = data.push(format!(
+        "ADVANCED_BREP_SHAPE_REPRESENTATION({},({}),{})",
+        string(&option.name),
+        items.join(","),
+        context
+    ));
+    data.push(format!(
+        "SHAPE_DEFINITION_REPRESENTATION({},{})",
+        shape, representation
+    ));
+    data.write(writer, option)
+}
+
+/// Writes the shell to the STEP file as `SHELL_BASED_SURFACE_MODEL`.
+///
+/// The shell is written as `CLOSED_SHELL` if it is closed, and as `OPEN_SHELL` otherwise.
+/// # Examples
+/// ```
+/// use truck_modeling::*;
+/// let v = builder::vertex(Point3::origin());
+/// let e = builder::tsweep(&v, Vector3::unit_x());
+/// let f = builder::tsweep(&e, Vector3::unit_y());
+/// let shell: Shell = vec![f].into();
+///
+/// let mut step = Vec::new();
+/// truck_stepio::write_shell(&shell, &mut step, &Default::default()).unwrap();
+/// let step = String::from_utf8(step).unwrap();
+/// assert_eq!(step.matches("OPEN_SHELL").count(), 1);
+/// assert_eq!(step.matches("ADVANCED_FACE").count(), 1);
+/// ```
+pub fn write_shell<C: StepCurve, S: StepSurface, W: Write>(
+    shell: &truck_topology::Shell<Point3, C, S>,
+    writer: W,
+    option: &StepOption,
+) -> Result<()> {
+    let mut data = DataSection::default();
+    let (shape, context) = data.product(option);
+    let kind = match shell.shell_condition() {
+        ShellCondition::Closed => "CLOSED_SHELL",
+        _ => "OPEN_SHELL",
+    };
+    let shell = data.shell(shell, kind, false);
+    let model = data.push(format!("SHELL_BASED_SURFACE_MODEL('',({}))", shell));
+    let origin = data.origin();
+    let representation = data.push(format!(
+        "MANIFOLD_SURFACE_SHAPE_REPRESENTATION({},({},{}),{})",
+        string(&option.name),
+        model,
+        origin,
+        context
+    ));
+    data.push(format!(
+        "SHAPE_DEFINITION_REPRESENTATION({},{})",
+        shape, representation
+    ));
+    data.write(writer, option)
+}
+
+// The entities of the data section. The entity `#n` is `entities[n - 1]`.
+struct DataSection<C, S> {
+    entities: Vec<String>,
+    vertices: HashMap<VertexID<Point3>, String>,
+    edges: HashMap<EdgeID<C>, String>,
+    _surface: std::marker::PhantomData<S>,
+}
+
+impl<C, S> Default for DataSection<C, S> {
+    fn default() -> Self {
+        DataSection {
+            entities: Vec::new(),
+            vertices: HashMap::new(),
+            edges: HashMap::new(),
+            _surface: std::marker::PhantomData,
+        }
+    }
+}
+
+impl<C: StepCurve, S: StepSurface> DataSection<C, S> {
+    // Adds the entity and returns its reference `#n`.
+    fn push(&mut self, entity: String) -> String {
+        self.entities.push(entity);
+        format!("#{}", self.entities.len())
+    }
+
+    // Returns the references of the product definition shape and the representation context.
+    fn product(&mut self, option: &StepOption) -> (String, String) {
+        let name = string(&option.name);
+        let (application, protocol, year) = match option.schema {
+            Schema::AP203 => (
+                "configuration controlled 3d designs of mechanical parts and assemblies",
+                "config_control_design",
+                1994,
+            ),
+            Schema::AP214 => ("automotive design", "automotive_design", 2000),
+        };
+        let application = self.push(format!("APPLICATION_CONTEXT('{}')", application));
+        self.push(format!(
+            "APPLICATION_PROTOCOL_DEFINITION('international standard','{}',{},{})",
+            protocol, year, application
+        ));
+        let product_context = match option.schema {
+            Schema::AP203 => format!("MECHANICAL_CONTEXT('',{},'mechanical')", application),
+            Schema::AP214 => format!("PRODUCT_CONTEXT('',{},'mechanical')", application),
+        };
+        let product_context = self.push(product_context);
+        let product = self.push(format!(
+            "PRODUCT({},{},'',({}))",
+            name, name, product_context
+        ));
+        let formation = match option.schema {
+            Schema::AP203 => format!(
+                "PRODUCT_DEFINITION_FORMATION_WITH_SPECIFIED_SOURCE('','',{},.NOT_KNOWN.)",
+                product
+            ),
+            Schema::AP214 => format!("PRODUCT_DEFINITION_FORMATION('','',{})", product),
+        };
+        let formation = self.push(formation);
+        let definition_context = match option.schema {
+            Schema::AP203 => format!("DESIGN_CONTEXT('',{},'design')", application),
+            Schema::AP214 => format!(
+                "PRODUCT_DEFINITION_CONTEXT('part definition',{},'design')",
+                application
+            ),
+        };
+        let definition_context = self.push(definition_context);
+        let definition = self.push(format!(
+            "PRODUCT_DEFINITION('design','',{},{})",
+            formation, definition_context
+        ));
+        let shape = self.push(format!("PRODUCT_DEFINITION_SHAPE('','',{})", definition));
+
+        let length = self.push("( LENGTH_UNIT() NAMED_UNIT(*) SI_UNIT(.MILLI.,.METRE.) )".into());
+        let angle = self.push("( NAMED_UNIT(*) PLANE_ANGLE_UNIT() SI_UNIT($,.RADIAN.) )".into());
+        let solid_angle =
+            self.push("( NAMED_UNIT(*) SI_UNIT($,.STERADIAN.) SOLID_ANGLE_UNIT() )".into());
+        let uncertainty = self.push(format!(
+            "UNCERTAINTY_MEASURE_WITH_UNIT(LENGTH_MEASURE({}),{},'distance_accuracy_value','')",
+            real(TOLERANCE),
+            length
+        ));
+        let context = self.push(format!(
+            "( GEOMETRIC_REPRESENTATION_CONTEXT(3) GLOBAL_UNCERTAINTY_ASSIGNED_CONTEXT(({})) \
+             GLOBAL_UNIT_ASSIGNED_CONTEXT(({},{},{})) REPRESENTATION_CONTEXT('','3D') )",
+            uncertainty, length, angle, solid_angle
+        ));
+        (shape, context)
+    }
+
+    fn point(&mut self, point: Point3) -> String {
+        self.push(format!(
+            "CARTESIAN_POINT('',({},{},{}))",
+            real(point[0]),
+            real(point[1]),
+            real(point[2])
+        ))
+    }
+
+    fn direction(&mut self, vector: Vector3) -> String {
+        self.push(format!(
+            "DIRECTION('',({},{},{}))",
+            real(vector[0]),
+            real(vector[1]),
+            real(vector[2])
+        ))
+    }
+
+    fn placement(&mut self, origin: Point3, axis: Vector3, ref_direction: Vector3) -> String {
+        let origin = self.point(origin);
+        let axis = self.direction(axis);
+        let ref_direction = self.direction(ref_direction);
+        self.push(format!(
+            "AXIS2_PLACEMENT_3D('',{},{},{})",
+            origin, axis, ref_direction
+        ))
+    }
+
+    #[inline(always)]
+    fn origin(&mut self) -> String {
+        self.placement(Point3::origin(), Vector3::unit_z(), Vector3::unit_x())
+    }
+
+    fn curve(&mut self, curve: &NURBSCurve<Vector4>) -> String {
+        let points: Vec<String> = curve
+            .control_points()
+            .iter()
+            .map(|pt| self.point(pt.to_point()))
+            .collect();
+        let (knots, mults) = curve.knot_vec().to_single_multi();
+        let (knots, mults) = (reals(&knots), integers(&mults));
+        let degree = curve.degree();
+        match is_rational(curve.control_points().iter()) {
+            false => self.push(format!(
+                "B_SPLINE_CURVE_WITH_KNOTS('',{},({}),.UNSPECIFIED.,.F.,.F.,({}),({}),\
+                 .UNSPECIFIED.)",
+                degree,
+                points.join(","),
+                mults,
+                knots
+            )),
+            true => {
+                let weights: Vec<f64> = curve.control_points().iter().map(|pt| pt[3]).collect();
+                self.push(format!(
+                    "( BOUNDED_CURVE() B_SPLINE_CURVE({},({}),.UNSPECIFIED.,.F.,.F.) \
+                     B_SPLINE_CURVE_WITH_KNOTS(({}),({}),.UNSPECIFIED.) CURVE() \
+                     GEOMETRIC_REPRESENTATION_ITEM() RATIONAL_B_SPLINE_CURVE(({})) \
+                     REPRESENTATION_ITEM('') )",
+                    degree,
+                    points.join(","),
+                    mults,
+                    knots,
+                    reals(&weights)
+                ))
+            }
+        }
+    }
+
+    fn surface(&mut self, surface: &SurfaceGeometry) -> String {
+        let surface = match surface {
+            SurfaceGeometry::Plane(plane) => {
+                let placement =
+                    self.placement(plane.origin(), plane.normal(), plane.u_axis().normalize());
+                return self.push(format!("PLANE('',{})", placement));
+            }
+            SurfaceGeometry::NURBS(surface) => surface,
+        };
+        let points: Vec<String> = surface
+            .control_points()
+            .iter()
+            .map(|vec| {
+                let vec: Vec<String> = vec.iter().map(|pt| self.point(pt.to_point())).collect();
+                format!("({})", vec.join(","))
+            })
+            .collect();
+        let (uknots, umults) = surface.knot_vecs().0.to_single_multi();
+        let (vknots, vmults) = surface.knot_vecs().1.to_single_multi();
+        let (udegree, vdegree) = surface.degrees();
+        let knots = format!(
+            "({}),({}),({}),({})",
+            integers(&umults),
+            integers(&vmults),
+            reals(&uknots),
+            reals(&vknots)
+        );
+        match is_rational(surface.control_points().iter().flatten()) {
+            false => self.push(format!(
+                "B_SPLINE_SURFACE_WITH_KNOTS('',{},{},({}),.UNSPECIFIED.,.F.,.F.,.F.,{},\
+                 .UNSPECIFIED.)",
+                udegree,
+                vdegree,
+                points.join(","),
+                knots
+            )),
+            true => {
+                let weights: Vec<String> = surface
+                    .control_points()
+                    .iter()
+                    .map(|vec| {
+                        let weights: Vec<f64> = vec.iter().map(|pt| pt[3]).collect();
+                        format!("({})", reals(&weights))
+                    })
+                    .collect();
+                self.push(format!(
+                    "( BOUNDED_SURFACE() B_SPLINE_SURFACE({},{},({}),.UNSPECIFIED.,.F.,.F.,.F.) \
+                     B_SPLINE_SURFACE_WITH_KNOTS({},.UNSPECIFIED.) \
+                     GEOMETRIC_REPRESENTATION_ITEM() RATIONAL_B_SPLINE_SURFACE(({})) \
+                     REPRESENTATION_ITEM('') SURFACE() )",
+                    udegree,
+                    vdegree,
+                    points.join(","),
+                    knots,
+                    weights.join(",")
+                ))
+            }
+        }
+    }
+
+    fn vertex(&mut self, vertex: &truck_topology::Vertex<Point3>) -> String {
+        if let Some(entity) = self.vertices.get(&vertex.id()) {
+            return entity.clone();
+        }
+        let point = *vertex.lock_point().unwrap();
+        let point = self.point(point);
+        let entity = self.push(format!("VERTEX_POINT('',{})", point));
+        self.vertices.insert(vertex.id(), entity.clone());
+        entity
+    }
+
+    // Returns `EDGE_CURVE` along the absolute direction of the edge.
+    fn edge(&mut self, edge: &truck_topology::Edge<Point3, C>) -> String {
+        if let Some(entity) = self.edges.get(&edge.id()) {
+            return entity.clone();
+        }
+        let front = self.vertex(edge.absolute_front());
+        let back = self.vertex(edge.absolute_back());
+        let curve = edge.lock_curve().unwrap().to_nurbs();
+        let curve = self.curve(&curve);
+        let entity = self.push(format!("EDGE_CURVE('',{},{},{},.T.)", front, back, curve));
+        self.edges.insert(edge.id(), entity.clone());
+        entity
+    }
+
+    fn face(&mut self, face: &truck_topology::Face<Point3, C, S>) -> String {
+        let bounds: Vec<String> = face
+            .boundaries()
+            .iter()
+            .map(|wire| {
+                let edges: Vec<String> = wire
+                    .iter()
+                    .map(|edge| {
+                        let curve = self.edge(edge);
+                        self.push(format!(
+                            "ORIENTED_EDGE('',*,*,{},{})",
+                            curve,
+                            logical(edge.orientation())
+                        ))
+                    })
+                    .collect();
+                let edge_loop = self.push(format!("EDGE_LOOP('',({}))", edges.join(",")));
+                self.push(format!("FACE_BOUND('',{},.T.)", edge_loop))
+            })
+            .collect();
+        let surface = face.lock_surface().unwrap().to_step_surface();
+        let surface = self.surface(&surface);
+        self.push(format!(
+            "ADVANCED_FACE('',({}),{},{})",
+            bounds.join(","),
+            surface,
+            logical(face.orientation())
+        ))
+    }
+
+    // If `inverted`, all faces are written inverted, as the voids of `BREP_WITH_VOIDS`.
+    fn shell(
+        &mut self,
+        shell: &truck_topology::Shell<Point3, C, S>,
+        kind: &str,
+        inverted: bool,
+    ) -> String {
+        let faces: Vec<String> = shell
+            .iter()
+            .map(|face| match inverted {
+                true => self.face(&face.inverse()),
+                false => self.face(face),
+            })
+            .collect();
+        self.push(format!("{}('',({}))", kind, faces.join(",")))
+    }
+
+    fn write<W: Write>(&self, mut writer: W, option: &StepOption) -> Result<()> {
+        let schema = match option.schema {
+            Schema::AP203 => "CONFIG_CONTROL_DESIGN",
+            Schema::AP214 => "AUTOMOTIVE_DESIGN { 1 0 10303 214 1 1 1 1 }",
+        };
+        writeln!(writer, "ISO-10303-21;")?;
+        writeln!(writer, "HEADER;")?;
+        writeln!(writer, "FILE_DESCRIPTION(('truck'),'2;1');")?;
+        writeln!(
+            writer,
+            "FILE_NAME({},{},({}),({}),'truck-stepio','truck','');",
+            string(&option.name),
+            string(&option.time_stamp),
+            string(&option.author),
+            string(&option.organization)
+        )?;
+        writeln!(writer, "FILE_SCHEMA(('{}'));", schema)?;
+        writeln!(writer, "ENDSEC;")?;
+        writeln!(writer, "DATA;")?;
+        for (i, entity) in self.entities.iter().enumerate() {
+            writeln!(writer, "#{}={};", i + 1, entity)?;
+        }
+        writeln!(writer, "ENDSEC;")?;
+        writeln!(writer, "END-ISO-10303-21;")?;
+        Ok(())
+    }
+}
+
+#[inline(always)]
+fn is_rational<'a>(mut points: impl Iterator<Item = &'a Vector4>) -> bool {
+    points.any(|pt| !pt[3].near(&1.0))
+}
+
+#[inline(always)]
+fn logical(x: bool) -> &'static str {
+    match x {
+        true => ".T.",
+        false => ".F.",
+    }
+}
+
+// The string literal of STEP, in which the apostrophes and the backslashes are doubled.
+fn string(x: &str) -> String { format!("'{}'", x.replace('\\', "\\\\").replace('\'', "''")) }
+
+// The real literal of STEP, which has a decimal point and an upper case exponent.
+fn real(x: f64) -> String {
+    let x = format!("{:?}", x);
+    match x.find('e') {
+        Some(idx) => {
+            let (mantissa, exponent) = x.split_at(idx);
+            match mantissa.contains('.') {
+                true => format!("{}E{}", mantissa, &exponent[1..]),
+                false => format!("{}.E{}", mantissa, &exponent[1..]),
+            }
+        }
+        None => x,
+    }
+}
+
+fn reals(x: &[f64]) -> String {
+    let x: Vec<String> = x.iter().map(|x| real(*x)).collect();
+    x.join(",")
+}
+
+fn integers(x: &[usize]) -> String {
+    let x: Vec<String> = x.iter().map(ToString::to_string).collect();
+    x.join(",")
+}
@@ -2,10 +2,10 @@ use crate::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-#[derive(Debug, Serialize, Deserialize)]
-struct CompressedEdge<C> {
-    vertices: (usize, usize),
-    curve: C,
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct CompressedEdge<C> {
+    pub(crate) vertices: (usize, usize),
+    pub(crate) curve: C,
 }
 
 impl<C> CompressedEdge<C> {
@@ -16,11 +16,11 @@ impl<C> CompressedEdge<C> {
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-struct CompressedFace<S> {
-    boundaries: Vec<Vec<(usize, bool)>>,
-    orientation: bool,
-    surface: S,
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub(crate) struct CompressedFace<S> {
+    pub(crate) boundaries: Vec<Vec<(usize, bool)>>,
+    pub(crate) orientation: bool,
+    pub(crate) surface: S,
 }
 
 impl<S> CompressedFace<S> {
@@ -46,17 +46,17 @@ impl<S> CompressedFace<S> {
 }
 
 /// Serialized compressed shell
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CompressedShell<P, C, S> {
-    vertices: Vec<P>,
-    edges: Vec<CompressedEdge<C>>,
-    faces: Vec<CompressedFace<S>>,
+    pub(crate) vertices: Vec<P>,
+    pub(crate) edges: Vec<CompressedEdge<C>>,
+    pub(crate) faces: Vec<CompressedFace<S>>,
 }
 
 /// Serialized compressed solid
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct CompressedSolid<P, C, S> {
-    boundaries: Vec<CompressedShell<P, C, S>>,
+    pub(crate) boundaries: Vec<CompressedShell<P, C, S>>,
 }
 
 struct CompressDirector<P, C> {
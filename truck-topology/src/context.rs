@@ -0,0 +1,342 @@
+use crate::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// The stable id assigned by [`TopologyContext`].
+///
+/// Unlike [`VertexID`], [`EdgeID`] and [`FaceID`], which are the addresses of the entities,
+/// the stable id is the pair of the index of the slot and the generation counter of the slot.
+/// It is serializable, does not change across the processes, and is never reused: after the
+/// element is removed from the context, the generation of the slot is incremented and the old
+/// id refers to nothing. An id is unique among the elements of the same kind in one context.
+///
+/// [`TopologyContext`]: ./struct.TopologyContext.html
+/// [`VertexID`]: ./type.VertexID.html
+/// [`EdgeID`]: ./type.EdgeID.html
+/// [`FaceID`]: ./type.FaceID.html
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct StableID {
+    index: u32,
+    generation: u32,
+}
+
+impl StableID {
+    /// Returns the index of the slot.
+    #[inline(always)]
+    pub fn index(&self) -> u32 { self.index }
+    /// Returns the generation of the slot.
+    #[inline(always)]
+    pub fn generation(&self) -> u32 { self.generation }
+}
+
+impl std::fmt::Display for StableID {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_fmt(format_args!("{}v{}", self.index, self.generation))
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Slot<T> {
+    generation: u32,
+    entry: Option<T>,
+}
+
+// the slot map with the generation counters
+#[derive(Clone, Debug)]
+struct Arena<T> {
+    slots: Vec<Slot<T>>,
+    free: Vec<u32>,
+    len: usize,
+}
+
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Arena {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+        }
+    }
+}
+
+impl<T> Arena<T> {
+    fn insert(&mut self, entry: T) -> StableID {
+        self.len += 1;
+        match self.free.pop() {
+            Some(index) => {
+                let slot = &mut self.slots[index as usize];
+                slot.entry = Some(entry);
+                StableID {
+                    index,
+                    generation: slot.generation,
+                }
+            }
+            None => {
+                self.slots.push(Slot {
+                    generation: 0,
+                    entry: Some(entry),
+                });
+                StableID {
+                    index: self.slots.len() as u32 - 1,
+                    generation: 0,
+                }
+            }
+        }
+    }
+
+    fn get(&self, id: StableID) -> Option<&T> {
+        let slot = self.slots.get(id.index as usize)?;
+        match slot.generation == id.generation {
+            true => slot.entry.as_ref(),
+            false => None,
+        }
+    }
+
+    fn remove(&mut self, id: StableID) -> Option<T> {
+        let slot = self.slots.get_mut(id.index as usize)?;
+        if slot.generation != id.generation {
+            return None;
+        }
+        let entry = slot.entry.take()?;
+        self.len -= 1;
+        // The slot whose generation is exhausted is retired, so that the ids are never reused.
+        if slot.generation < u32::MAX {
+            slot.generation += 1;
+            self.free.push(id.index);
+        }
+        Some(entry)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (StableID, &T)> {
+        self.slots.iter().enumerate().filter_map(|(index, slot)| {
+            let id = StableID {
+                index: index as u32,
+                generation: slot.generation,
+            };
+            slot.entry.as_ref().map(|entry| (id, entry))
+        })
+    }
+}
+
+/// The context assigning the stable ids to the vertices, the edges and the faces.
+///
+/// The context keeps the registered elements alive, so the addresses of them are not reused
+/// while they are registered. The stable ids are safe to be used for the persistent naming,
+/// the undo histories, and the references across the processes.
+/// # Examples
+/// ```
+/// use truck_topology::*;
+/// let v = Vertex::news(&[(), ()]);
+/// let edge = Edge::new(&v[0], &v[1], ());
+///
+/// let mut context = TopologyContext::<(), (), ()>::new();
+/// let id = context.register_edge(&edge);
+/// // The ids do not depend on the directions, and registering twice returns the same id.
+/// assert_eq!(context.register_edge(&edge.inverse()), id);
+/// assert_eq!(context.edge(id), Some(&edge));
+/// // The end vertices are also registered.
+/// assert!(context.vertex_id(&v[1]).is_some());
+///
+/// // The removed id refers to nothing, even if the slot is reused.
+/// context.remove_edge(id);
+/// let id1 = context.register_edge(&Edge::new(&v[1], &v[0], ()));
+/// assert_eq!(id.index(), id1.index());
+/// assert_ne!(id, id1);
+/// assert_eq!(context.edge(id), None);
+/// ```
+#[derive(Debug)]
+pub struct TopologyContext<P, C, S> {
+    vertices: Arena<Vertex<P>>,
+    edges: Arena<Edge<P, C>>,
+    faces: Arena<Face<P, C, S>>,
+    vertex_ids: HashMap<VertexID<P>, StableID>,
+    edge_ids: HashMap<EdgeID<C>, StableID>,
+    face_ids: HashMap<FaceID<S>, StableID>,
+}
+
+impl<P, C, S> Default for TopologyContext<P, C, S> {
+    fn default() -> Self {
+        TopologyContext {
+            vertices: Default::default(),
+            edges: Default::default(),
+            faces: Default::default(),
+            vertex_ids: HashMap::new(),
+            edge_ids: HashMap::new(),
+            face_ids: HashMap::new(),
+        }
+    }
+}
+
+impl<P, C, S> TopologyContext<P, C, S> {
+    /// Creates an empty context.
+    #[inline(always)]
+    pub fn new() -> Self { Self::default() }
+
+    /// Registers the vertex and returns its stable id.
+    /// If the vertex is already registered, returns the registered id.
+    pub fn register_vertex(&mut self, vertex: &Vertex<P>) -> StableID {
+        if let Some(id) = self.vertex_ids.get(&vertex.id()) {
+            return *id;
+        }
+        let id = self.vertices.insert(vertex.clone());
+        self.vertex_ids.insert(vertex.id(), id);
+        id
+    }
+
+    /// Registers the edge and its end vertices, and returns the stable id of the edge.
+    /// If the edge is already registered, returns the registered id.
+    pub fn register_edge(&mut self, edge: &Edge<P, C>) -> StableID {
+        if let Some(id) = self.edge_ids.get(&edge.id()) {
+            return *id;
+        }
+        self.register_vertex(edge.front());
+        self.register_vertex(edge.back());
+        let id = self.edges.insert(edge.clone());
+        self.edge_ids.insert(edge.id(), id);
+        id
+    }
+
+    /// Registers the face and its boundary edges and vertices, and returns the stable id
+    /// of the face. If the face is already registered, returns the registered id.
+    pub fn register_face(&mut self, face: &Face<P, C, S>) -> StableID {
+        if let Some(id) = self.face_ids.get(&face.id()) {
+            return *id;
+        }
+        face.absolute_boundaries()
+            .iter()
+            .flatten()
+            .for_each(|edge| {
+                self.register_edge(edge);
+            });
+        let id = self.faces.insert(face.clone());
+        self.face_ids.insert(face.id(), id);
+        id
+    }
+
+    /// Registers all faces of the shell, and returns the stable ids of the faces.
+    pub fn register_shell(&mut self, shell: &Shell<P, C, S>) -> Vec<StableID> {
+        shell.iter().map(|face| self.register_face(face)).collect()
+    }
+
+    /// Registers all faces of the solid, and returns the stable ids of the faces.
+    pub fn register_solid(&mut self, solid: &Solid<P, C, S>) -> Vec<Vec<StableID>> {
+        solid
+            .boundaries()
+            .iter()
+            .map(|shell| self.register_shell(shell))
+            .collect()
+    }
+
+    /// Returns the vertex of the id. Returns `None` if the id is removed.
+    #[inline(always)]
+    pub fn vertex(&self, id: StableID) -> Option<&Vertex<P>> { self.vertices.get(id) }
+    /// Returns the edge of the id in the registered direction. Returns `None` if the id is removed.
+    #[inline(always)]
+    pub fn edge(&self, id: StableID) -> Option<&Edge<P, C>> { self.edges.get(id) }
+    /// Returns the face of the id in the registered direction. Returns `None` if the id is removed.
+    #[inline(always)]
+    pub fn face(&self, id: StableID) -> Option<&Face<P, C, S>> { self.faces.get(id) }
+
+    /// Returns the stable id of the vertex if it is registered.
+    #[inline(always)]
+    pub fn vertex_id(&self, vertex: &Vertex<P>) -> Option<StableID> {
+        self.vertex_ids.get(&vertex.id()).copied()
+    }
+    /// Returns the stable id of the edge if it is registered.
+    #[inline(always)]
+    pub fn edge_id(&self, edge: &Edge<P, C>) -> Option<StableID> {
+        self.edge_ids.get(&edge.id()).copied()
+    }
+    /// Returns the stable id of the face if it is registered.
+    #[inline(always)]
+    pub fn face_id(&self, face: &Face<P, C, S>) -> Option<StableID> {
+        self.face_ids.get(&face.id()).copied()
+    }
+
+    /// Removes the vertex from the context. Returns the removed vertex.
+    pub fn remove_vertex(&mut self, id: StableID) -> Option<Vertex<P>> {
+        let vertex = self.vertices.remove(id)?;
+        self.vertex_ids.remove(&vertex.id());
+        Some(vertex)
+    }
+    /// Removes the edge from the context. The end vertices are kept. Returns the removed edge.
+    pub fn remove_edge(&mut self, id: StableID) -> Option<Edge<P, C>> {
+        let edge = self.edges.remove(id)?;
+        self.edge_ids.remove(&edge.id());
+        Some(edge)
+    }
+    /// Removes the face from the context. The boundaries are kept. Returns the removed face.
+    pub fn remove_face(&mut self, id: StableID) -> Option<Face<P, C, S>> {
+        let face = self.faces.remove(id)?;
+        self.face_ids.remove(&face.id());
+        Some(face)
+    }
+
+    /// Removes the elements referred only by the context, and returns the number of the
+    /// removed elements. The faces are removed first, so the edges and vertices referred
+    /// only by the removed faces are also removed.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// let mut context = TopologyContext::<(), (), ()>::new();
+    /// let v = Vertex::new(());
+    /// let id0 = context.register_vertex(&v);
+    /// let id1 = context.register_vertex(&Vertex::new(()));
+    /// assert_eq!(context.collect_garbage(), 1);
+    /// assert!(context.vertex(id0).is_some());
+    /// assert!(context.vertex(id1).is_none());
+    /// ```
+    pub fn collect_garbage(&mut self) -> usize {
+        let faces: Vec<StableID> = self
+            .faces
+            .iter()
+            .filter(|(_, face)| Arc::strong_count(&face.surface) == 1)
+            .map(|(id, _)| id)
+            .collect();
+        faces.iter().for_each(|id| {
+            self.remove_face(*id);
+        });
+        let edges: Vec<StableID> = self
+            .edges
+            .iter()
+            .filter(|(_, edge)| Arc::strong_count(&edge.curve) == 1)
+            .map(|(id, _)| id)
+            .collect();
+        edges.iter().for_each(|id| {
+            self.remove_edge(*id);
+        });
+        let vertices: Vec<StableID> = self
+            .vertices
+            .iter()
+            .filter(|(_, vertex)| Arc::strong_count(&vertex.point) == 1)
+            .map(|(id, _)| id)
+            .collect();
+        vertices.iter().for_each(|id| {
+            self.remove_vertex(*id);
+        });
+        faces.len() + edges.len() + vertices.len()
+    }
+
+    /// Returns the iterator over the registered vertices and their ids.
+    #[inline(always)]
+    pub fn vertex_iter(&self) -> impl Iterator<Item = (StableID, &Vertex<P>)> {
+        self.vertices.iter()
+    }
+    /// Returns the iterator over the registered edges and their ids.
+    #[inline(always)]
+    pub fn edge_iter(&self) -> impl Iterator<Item = (StableID, &Edge<P, C>)> { self.edges.iter() }
+    /// Returns the iterator over the registered faces and their ids.
+    #[inline(always)]
+    pub fn face_iter(&self) -> impl Iterator<Item = (StableID, &Face<P, C, S>)> {
+        self.faces.iter()
+    }
+
+    /// Returns the numbers of the registered vertices, edges and faces.
+    #[inline(always)]
+    pub fn len(&self) -> (usize, usize, usize) {
+        (self.vertices.len, self.edges.len, self.faces.len)
+    }
+    /// Returns whether no element is registered.
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool { self.len() == (0, 0, 0) }
+}
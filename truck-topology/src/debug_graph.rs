@@ -0,0 +1,189 @@
+use crate::*;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// The connectivity of a shell whose elements are numbered in the order of appearance.
+struct ConnectivityGraph {
+    num_vertices: usize,
+    // the indices of the absolute front and back vertices
+    edges: Vec<(usize, usize)>,
+    // the orientation and the boundaries: the pairs of the index and the orientation of edges
+    faces: Vec<(bool, Vec<Vec<(usize, bool)>>)>,
+}
+
+impl ConnectivityGraph {
+    fn new<P, C, S>(shell: &Shell<P, C, S>) -> ConnectivityGraph {
+        let mut vmap: HashMap<VertexID<P>, usize> = HashMap::new();
+        let mut emap: HashMap<EdgeID<C>, usize> = HashMap::new();
+        let mut edges = Vec::new();
+        let faces = shell
+            .face_iter()
+            .map(|face| {
+                let boundaries = face
+                    .boundaries()
+                    .iter()
+                    .map(|wire| {
+                        wire.edge_iter()
+                            .map(|edge| {
+                                let len = emap.len();
+                                let idx = *emap.entry(edge.id()).or_insert_with(|| {
+                                    let mut vertex_index = |v: &Vertex<P>| {
+                                        let n = vmap.len();
+                                        *vmap.entry(v.id()).or_insert(n)
+                                    };
+                                    let front = vertex_index(edge.absolute_front());
+                                    let back = vertex_index(edge.absolute_back());
+                                    edges.push((front, back));
+                                    len
+                                });
+                                (idx, edge.orientation())
+                            })
+                            .collect()
+                    })
+                    .collect();
+                (face.orientation(), boundaries)
+            })
+            .collect();
+        ConnectivityGraph {
+            num_vertices: vmap.len(),
+            edges,
+            faces,
+        }
+    }
+}
+
+#[inline(always)]
+fn sign(orientation: bool) -> char {
+    match orientation {
+        true => '+',
+        false => '-',
+    }
+}
+
+impl<P, C, S> Shell<P, C, S> {
+    /// Returns the connectivity graph of the shell in the DOT language of Graphviz.
+    ///
+    /// The vertices, edges and faces are numbered in the order of appearance in the boundaries
+    /// of the faces, and are named `v0`, `e0`, `f0`, and so on. The orientations of the faces
+    /// and the edges in the boundaries are signed by `+` or `-`.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// use std::iter::FromIterator;
+    /// let v = Vertex::news(&[(); 3]);
+    /// let wire = Wire::from_iter(vec![
+    ///     Edge::new(&v[0], &v[1], ()),
+    ///     Edge::new(&v[1], &v[2], ()),
+    ///     Edge::new(&v[0], &v[2], ()).inverse(),
+    /// ]);
+    /// let shell: Shell<_, _, _> = vec![Face::new(vec![wire], ())].into();
+    /// let dot = shell.to_dot();
+    /// assert!(dot.starts_with("digraph shell {"));
+    /// assert!(dot.contains("f0 -> e2 [label=\"0: -\"];"));
+    /// assert!(dot.contains("e2 -> v0 [label=\"front\"];"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let graph = ConnectivityGraph::new(self);
+        let mut res = String::from("digraph shell {\n");
+        for i in 0..graph.num_vertices {
+            writeln!(res, "    v{} [shape=point, xlabel=\"v{}\"];", i, i).unwrap();
+        }
+        for (i, (front, back)) in graph.edges.iter().enumerate() {
+            writeln!(res, "    e{} [shape=ellipse];", i).unwrap();
+            writeln!(res, "    e{} -> v{} [label=\"front\"];", i, front).unwrap();
+            writeln!(res, "    e{} -> v{} [label=\"back\"];", i, back).unwrap();
+        }
+        for (i, (orientation, boundaries)) in graph.faces.iter().enumerate() {
+            let label = format!("f{} ({})", i, sign(*orientation));
+            writeln!(res, "    f{} [shape=box, label=\"{}\"];", i, label).unwrap();
+            for (j, wire) in boundaries.iter().enumerate() {
+                for (idx, ori) in wire {
+                    let label = format!("{}: {}", j, sign(*ori));
+                    writeln!(res, "    f{} -> e{} [label=\"{}\"];", i, idx, label).unwrap();
+                }
+            }
+        }
+        res.push('}');
+        res
+    }
+
+    /// Returns the connectivity graph of the shell in JSON.
+    ///
+    /// The elements are numbered in the same way as [`Shell::to_dot`].
+    /// The edges have the indices of the absolute front and back vertices, and the faces
+    /// have the orientations and the boundaries. Each boundary is the array of the pairs of
+    /// the index and the orientation of an edge.
+    ///
+    /// [`Shell::to_dot`]: ./struct.Shell.html#method.to_dot
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// use std::iter::FromIterator;
+    /// let v = Vertex::news(&[(); 3]);
+    /// let wire = Wire::from_iter(vec![
+    ///     Edge::new(&v[0], &v[1], ()),
+    ///     Edge::new(&v[1], &v[2], ()),
+    ///     Edge::new(&v[0], &v[2], ()).inverse(),
+    /// ]);
+    /// let mut face = Face::new(vec![wire], ());
+    /// face.invert();
+    /// let shell: Shell<_, _, _> = vec![face].into();
+    /// assert_eq!(
+    ///     shell.to_debug_json(),
+    ///     "{
+    ///   \"vertices\": 3,
+    ///   \"edges\": [
+    ///     {\"id\": 0, \"front\": 0, \"back\": 1},
+    ///     {\"id\": 1, \"front\": 2, \"back\": 1},
+    ///     {\"id\": 2, \"front\": 0, \"back\": 2}
+    ///   ],
+    ///   \"faces\": [
+    ///     {\"id\": 0, \"orientation\": false, \"boundaries\": [[[0, true], [1, false], [2, false]]]}
+    ///   ]
+    /// }"
+    /// );
+    /// ```
+    pub fn to_debug_json(&self) -> String {
+        let graph = ConnectivityGraph::new(self);
+        let edges: Vec<String> = graph
+            .edges
+            .iter()
+            .enumerate()
+            .map(|(i, (front, back))| {
+                format!(
+                    "    {{\"id\": {}, \"front\": {}, \"back\": {}}}",
+                    i, front, back
+                )
+            })
+            .collect();
+        let faces: Vec<String> = graph
+            .faces
+            .iter()
+            .enumerate()
+            .map(|(i, (orientation, boundaries))| {
+                let boundaries: Vec<String> = boundaries
+                    .iter()
+                    .map(|wire| {
+                        let wire: Vec<String> = wire
+                            .iter()
+                            .map(|(idx, ori)| format!("[{}, {}]", idx, ori))
+                            .collect();
+                        format!("[{}]", wire.join(", "))
+                    })
+                    .collect();
+                format!(
+                    "    {{\"id\": {}, \"orientation\": {}, \"boundaries\": [{}]}}",
+                    i,
+                    orientation,
+                    boundaries.join(", "),
+                )
+            })
+            .collect();
+        format!(
+            "{{\n  \"vertices\": {},\n  \"edges\": [\n{}\n  ],\n  \"faces\": [\n{}\n  ]\n}}",
+            graph.num_vertices,
+            edges.join(",\n"),
+            faces.join(",\n"),
+        )
+    }
+}
@@ -103,6 +103,31 @@ pub enum Error {
     /// assert_eq!(Solid::try_new(vec![shell]), Err(Error::NotManifold));
     /// ```
     NotManifold,
+    /// The line of the text format "truck brep v1" is invalid.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// use truck_topology::errors::Error;
+    /// let text = "truck brep v1\nshell 1 0 0\nv 0.0 1.0\n";
+    /// assert_eq!(
+    ///     CompressedShell::<f64, (), ()>::from_text(text).unwrap_err(),
+    ///     Error::InvalidText(3),
+    /// );
+    /// ```
+    InvalidText(usize),
+    /// The edges cannot be chained into one wire, since they branch or are not connected.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// use truck_topology::errors::Error;
+    /// let v = Vertex::news(&[0.0, 1.0, 2.0, 3.0]);
+    /// let edges = vec![Edge::new(&v[0], &v[1], ()), Edge::new(&v[2], &v[3], ())];
+    /// assert_eq!(
+    ///     Wire::from_unordered_edges(&edges, 1.0e-6).unwrap_err(),
+    ///     Error::UnchainableEdges,
+    /// );
+    /// ```
+    UnchainableEdges,
 }
 
 impl std::fmt::Display for Error {
@@ -117,6 +142,10 @@ impl std::fmt::Display for Error {
             Error::NotConnected => f.pad("This shell is not connected."),
             Error::NotClosedShell => f.pad("This shell is not oriented and closed."),
             Error::NotManifold => f.pad("This shell is not a manifold."),
+            Error::InvalidText(line) => {
+                f.write_fmt(format_args!("The line {} of the text is invalid.", line))
+            }
+            Error::UnchainableEdges => f.pad("These edges cannot be chained into one wire."),
         }
     }
 }
@@ -139,6 +168,8 @@ fn print_messages() {
     writeln!(&mut std::io::stderr(), "{}\n", Error::NotConnected).unwrap();
     writeln!(&mut std::io::stderr(), "{}\n", Error::NotClosedShell).unwrap();
     writeln!(&mut std::io::stderr(), "{}\n", Error::NotManifold).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::InvalidText(1)).unwrap();
+    writeln!(&mut std::io::stderr(), "{}\n", Error::UnchainableEdges).unwrap();
     writeln!(
         &mut std::io::stderr(),
         "*******************************************************"
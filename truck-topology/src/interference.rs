@@ -0,0 +1,265 @@
+use crate::*;
+use truck_base::cgmath64::*;
+
+/// The intersection of two edges found by the detection of self-intersections.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EdgeIntersection<P> {
+    /// the index of the first edge: the pair of the index of the boundary wire and
+    /// the index of the edge in the wire. The former is always `0` for a wire.
+    pub edge0: (usize, usize),
+    /// the index of the second edge
+    pub edge1: (usize, usize),
+    /// the midpoint of the nearest points of the two edges
+    pub point: P,
+}
+
+impl<P, C> Wire<P, C>
+where
+    P: EuclideanSpace<Scalar = f64>,
+    P::Diff: InnerSpace<Scalar = f64>,
+    C: ParametricCurve<Point = P> + ParameterDivision1D,
+{
+    /// Returns the pairs of edges which intersect each other.
+    ///
+    /// Each edge is approximated by the polyline with the tolerance `tol`, and two edges
+    /// are regarded as intersecting if the distance between the polylines is less than `tol`.
+    /// The contacts at the common vertices of the edges are not intersections.
+    /// At most one intersection is reported for each pair of edges.
+    pub fn self_intersections(&self, tol: f64) -> Vec<EdgeIntersection<P>> {
+        detect_intersections(&edge_polylines(std::iter::once(self), tol), tol)
+    }
+
+    /// Returns whether the wire intersects itself.
+    /// cf. [`Wire::self_intersections`](./struct.Wire.html#method.self_intersections)
+    #[inline(always)]
+    pub fn has_self_intersection(&self, tol: f64) -> bool {
+        !self.self_intersections(tol).is_empty()
+    }
+}
+
+impl<P, C, S> Face<P, C, S>
+where
+    P: EuclideanSpace<Scalar = f64>,
+    P::Diff: InnerSpace<Scalar = f64>,
+    C: ParametricCurve<Point = P> + ParameterDivision1D,
+{
+    /// Returns the pairs of boundary edges which intersect each other,
+    /// including the intersections between the different boundary wires.
+    /// The indices of the boundary wires are the ones of `Face::absolute_boundaries()`.
+    /// cf. [`Wire::self_intersections`](./struct.Wire.html#method.self_intersections)
+    pub fn self_intersections(&self, tol: f64) -> Vec<EdgeIntersection<P>> {
+        detect_intersections(&edge_polylines(self.absolute_boundaries(), tol), tol)
+    }
+
+    /// Returns whether the boundaries of the face intersect themselves or each other.
+    /// cf. [`Face::self_intersections`](./struct.Face.html#method.self_intersections)
+    #[inline(always)]
+    pub fn has_self_intersection(&self, tol: f64) -> bool {
+        !self.self_intersections(tol).is_empty()
+    }
+}
+
+struct EdgePolyline<P> {
+    index: (usize, usize),
+    ends: (VertexID<P>, VertexID<P>),
+    points: Vec<P>,
+}
+
+impl<P> EdgePolyline<P> {
+    /// Returns the vertices touched by the `i`th segment.
+    fn segment_vertices(&self, i: usize) -> [Option<VertexID<P>>; 2] {
+        [
+            Some(self.ends.0).filter(|_| i == 0),
+            Some(self.ends.1).filter(|_| i + 2 == self.points.len()),
+        ]
+    }
+}
+
+fn edge_polylines<'a, P: 'a, C: 'a, I>(wires: I, tol: f64) -> Vec<EdgePolyline<P>>
+where
+    I: IntoIterator<Item = &'a Wire<P, C>>,
+    C: ParametricCurve<Point = P> + ParameterDivision1D, {
+    wires
+        .into_iter()
+        .enumerate()
+        .flat_map(|(i, wire)| {
+            wire.edge_iter().enumerate().map(move |(j, edge)| {
+                let curve = edge.lock_curve().unwrap();
+                EdgePolyline {
+                    index: (i, j),
+                    ends: (edge.absolute_front().id(), edge.absolute_back().id()),
+                    points: curve
+                        .parameter_division(tol)
+                        .into_iter()
+                        .map(|t| curve.subs(t))
+                        .collect(),
+                }
+            })
+        })
+        .collect()
+}
+
+fn detect_intersections<P>(polylines: &[EdgePolyline<P>], tol: f64) -> Vec<EdgeIntersection<P>>
+where
+    P: EuclideanSpace<Scalar = f64>,
+    P::Diff: InnerSpace<Scalar = f64>, {
+    let mut res = Vec::new();
+    for (i, poly0) in polylines.iter().enumerate() {
+        for poly1 in &polylines[i..] {
+            let same = poly0.index == poly1.index;
+            // the points of the common vertices
+            let common: Vec<P> = [poly0.ends.0, poly0.ends.1]
+                .iter()
+                .zip(&[poly0.points[0], *poly0.points.last().unwrap()])
+                .filter(|(v, _)| *v == &poly1.ends.0 || *v == &poly1.ends.1)
+                .map(|(_, pt)| *pt)
+                .collect();
+            let found = segment_pairs(poly0, poly1, same).find_map(|(k, l)| {
+                let vs0 = poly0.segment_vertices(k);
+                let vs1 = poly1.segment_vertices(l);
+                if vs0
+                    .iter()
+                    .flatten()
+                    .any(|v| vs1.iter().flatten().any(|w| v == w))
+                {
+                    return None;
+                }
+                let (dist, pt) = segment_distance(
+                    (poly0.points[k], poly0.points[k + 1]),
+                    (poly1.points[l], poly1.points[l + 1]),
+                );
+                match dist < tol && common.iter().all(|q| (pt - *q).magnitude() >= tol) {
+                    true => Some(pt),
+                    false => None,
+                }
+            });
+            if let Some(point) = found {
+                res.push(EdgeIntersection {
+                    edge0: poly0.index,
+                    edge1: poly1.index,
+                    point,
+                });
+            }
+        }
+    }
+    res
+}
+
+/// Returns the indices of the pairs of the segments to be checked.
+/// The adjacent segments in the same edge are skipped.
+fn segment_pairs<'a, P>(
+    poly0: &'a EdgePolyline<P>,
+    poly1: &'a EdgePolyline<P>,
+    same: bool,
+) -> impl Iterator<Item = (usize, usize)> + 'a {
+    let (n0, n1) = (poly0.points.len() - 1, poly1.points.len() - 1);
+    (0..n0).flat_map(move |k| {
+        let start = if same { k + 2 } else { 0 };
+        (start..n1).map(move |l| (k, l))
+    })
+}
+
+/// Returns the distance between two segments and the midpoint of the nearest points.
+fn segment_distance<P>((p0, p1): (P, P), (q0, q1): (P, P)) -> (f64, P)
+where
+    P: EuclideanSpace<Scalar = f64>,
+    P::Diff: InnerSpace<Scalar = f64>, {
+    let clamp = |x: f64| f64::min(f64::max(x, 0.0), 1.0);
+    let (d0, d1, r) = (p1 - p0, q1 - q0, p0 - q0);
+    let (a, e, f) = (d0.magnitude2(), d1.magnitude2(), d1.dot(r));
+    let (s, t) = if a < TOLERANCE2 && e < TOLERANCE2 {
+        (0.0, 0.0)
+    } else if a < TOLERANCE2 {
+        (0.0, clamp(f / e))
+    } else {
+        let c = d0.dot(r);
+        if e < TOLERANCE2 {
+            (clamp(-c / a), 0.0)
+        } else {
+            let b = d0.dot(d1);
+            let denom = a * e - b * b;
+            let s = match denom > TOLERANCE2 {
+                true => clamp((b * f - c * e) / denom),
+                false => 0.0,
+            };
+            let t = (b * s + f) / e;
+            if t < 0.0 {
+                (clamp(-c / a), 0.0)
+            } else if t > 1.0 {
+                (clamp((b - c) / a), 1.0)
+            } else {
+                (s, t)
+            }
+        }
+    };
+    let (x, y) = (p0 + d0 * s, q0 + d1 * t);
+    ((y - x).magnitude(), x.midpoint(y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use truck_base::assert_near;
+
+    #[derive(Clone, Copy, Debug)]
+    struct Line(Point2, Point2);
+
+    impl ParametricCurve for Line {
+        type Point = Point2;
+        type Vector = Vector2;
+        fn subs(&self, t: f64) -> Point2 { self.0 + (self.1 - self.0) * t }
+        fn der(&self, _: f64) -> Vector2 { self.1 - self.0 }
+        fn der2(&self, _: f64) -> Vector2 { Vector2::zero() }
+        fn parameter_range(&self) -> (f64, f64) { (0.0, 1.0) }
+    }
+
+    impl ParameterDivision1D for Line {
+        fn parameter_division(&self, _: f64) -> Vec<f64> { vec![0.0, 0.5, 1.0] }
+    }
+
+    fn polygon(points: &[Point2]) -> Wire<Point2, Line> {
+        let v = Vertex::news(points);
+        (0..v.len())
+            .map(|i| {
+                let j = (i + 1) % v.len();
+                Edge::new(&v[i], &v[j], Line(points[i], points[j]))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn wire_self_intersection() {
+        let square = polygon(&[
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(0.0, 1.0),
+        ]);
+        assert!(!square.has_self_intersection(1.0e-3));
+
+        let bowtie = polygon(&[
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 1.0),
+            Point2::new(1.0, 0.0),
+            Point2::new(0.0, 1.0),
+        ]);
+        let intersections = bowtie.self_intersections(1.0e-3);
+        assert_eq!(intersections.len(), 1);
+        assert_eq!(intersections[0].edge0, (0, 0));
+        assert_eq!(intersections[0].edge1, (0, 2));
+        assert_near!(intersections[0].point, Point2::new(0.5, 0.5));
+
+        let inner = polygon(&[
+            Point2::new(0.5, 0.5),
+            Point2::new(1.5, 0.5),
+            Point2::new(1.5, 1.5),
+            Point2::new(0.5, 1.5),
+        ]);
+        let face = Face::new(vec![square, inner.inverse()], ());
+        let intersections = face.self_intersections(1.0e-3);
+        assert_eq!(intersections.len(), 2);
+        assert!(intersections
+            .iter()
+            .all(|x| x.edge0.0 == 0 && x.edge1.0 == 1));
+    }
+}
@@ -190,6 +190,11 @@ impl<T> RemoveTry<T> for Result<T> {
 /// entity_map.insert(v.clone(), 0); // v must be cloned for sign up the hashmap.
 /// id_map.insert(v_id, 0); // v_id is implemented Copy trait!
 /// ```
+/// The id is the address of the point, so it is not stable across the serializations,
+/// and may be reused after the vertex is dropped. Use [`TopologyContext`] for the stable ids.
+///
+/// [`TopologyContext`]: ./struct.TopologyContext.html
+///
 /// The id does not changed even if the value of point changes.
 /// ```
 /// use truck_topology::*;
@@ -239,15 +244,24 @@ pub type EdgeID<C> = ID<Mutex<C>>;
 pub type FaceID<S> = ID<Mutex<S>>;
 
 mod compress;
+mod context;
+mod debug_graph;
 mod edge;
 /// classifies the errors that can occur in this crate.
 pub mod errors;
 /// Defines the boundary iterator.
 pub mod face;
+/// detects the self-intersections of wires and faces.
+pub mod interference;
 /// classifies shell conditions and defines the face iterators.
 pub mod shell;
 mod solid;
+mod text;
 mod vertex;
+mod weak;
 /// define the edge iterators and the vertex iterator.
 pub mod wire;
 pub use compress::{CompressedShell, CompressedSolid};
+pub use context::{StableID, TopologyContext};
+pub use text::TextGeometry;
+pub use weak::{WeakEdge, WeakFace, WeakVertex};
@@ -444,6 +444,301 @@ impl<P, C, S> Shell<P, C, S> {
             })
             .collect()
     }
+
+    /// Returns the Euler characteristic of the shell.
+    ///
+    /// A face with `k` boundary wires is regarded as a disk with `k - 1` holes,
+    /// i.e. its Euler characteristic is `2 - k`.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// use std::iter::FromIterator;
+    /// // the boundary of a tetrahedron
+    /// let v = Vertex::news(&[(); 4]);
+    /// let edge = [
+    ///     Edge::new(&v[0], &v[1], ()),
+    ///     Edge::new(&v[0], &v[2], ()),
+    ///     Edge::new(&v[0], &v[3], ()),
+    ///     Edge::new(&v[1], &v[2], ()),
+    ///     Edge::new(&v[1], &v[3], ()),
+    ///     Edge::new(&v[2], &v[3], ()),
+    /// ];
+    /// let wire = vec![
+    ///     Wire::from_iter(vec![&edge[0], &edge[3], &edge[1].inverse()]),
+    ///     Wire::from_iter(vec![&edge[1], &edge[5], &edge[2].inverse()]),
+    ///     Wire::from_iter(vec![&edge[2], &edge[4].inverse(), &edge[0].inverse()]),
+    ///     Wire::from_iter(vec![&edge[3], &edge[5], &edge[4].inverse()]),
+    /// ];
+    /// let mut shell: Shell<_, _, _> = wire.into_iter().map(|w| Face::new(vec![w], ())).collect();
+    /// assert_eq!(shell.euler_characteristic(), 2);
+    ///
+    /// // a tetrahedron without a face is a disk.
+    /// shell.pop();
+    /// assert_eq!(shell.euler_characteristic(), 1);
+    /// ```
+    pub fn euler_characteristic(&self) -> isize {
+        let (vertices, edges) = self.vertex_edge_ids();
+        let faces: isize = self
+            .face_iter()
+            .map(|face| 2 - face.boundaries.len() as isize)
+            .sum();
+        vertices.len() as isize - edges.len() as isize + faces
+    }
+
+    /// Returns the genus of the shell, i.e. the number of the handles.
+    ///
+    /// The genus is computed from the Euler characteristic, the number of the connected
+    /// components and the number of the boundaries. Returns `None` if the shell is not
+    /// orientable.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// use std::iter::FromIterator;
+    /// // a torus divided into 3 x 3 faces
+    /// const N: usize = 3;
+    /// let v = Vertex::news(&[(); N * N]);
+    /// let row_edge: Vec<Vec<_>> = (0..N)
+    ///     .map(|i| {
+    ///         (0..N)
+    ///             .map(|j| Edge::new(&v[i * N + j], &v[i * N + (j + 1) % N], ()))
+    ///             .collect()
+    ///     })
+    ///     .collect();
+    /// let col_edge: Vec<Vec<_>> = (0..N)
+    ///     .map(|i| {
+    ///         (0..N)
+    ///             .map(|j| Edge::new(&v[i * N + j], &v[((i + 1) % N) * N + j], ()))
+    ///             .collect()
+    ///     })
+    ///     .collect();
+    /// let shell: Shell<_, _, _> = (0..N)
+    ///     .flat_map(|i| (0..N).map(move |j| (i, j)))
+    ///     .map(|(i, j)| {
+    ///         let wire = Wire::from_iter(vec![
+    ///             &row_edge[i][j],
+    ///             &col_edge[i][(j + 1) % N],
+    ///             &row_edge[(i + 1) % N][j].inverse(),
+    ///             &col_edge[i][j].inverse(),
+    ///         ]);
+    ///         Face::new(vec![wire], ())
+    ///     })
+    ///     .collect();
+    /// assert_eq!(shell.euler_characteristic(), 0);
+    /// assert_eq!(shell.genus(), Some(1));
+    /// ```
+    pub fn genus(&self) -> Option<usize> {
+        if !self.is_orientable() {
+            return None;
+        }
+        let components = self.connected_components().len() as isize;
+        let boundaries = self.extract_boundaries().len() as isize;
+        let twice = 2 * components - boundaries - self.euler_characteristic();
+        Some((twice / 2).max(0) as usize)
+    }
+
+    /// Determines whether the orientations of the faces can be made compatible by inverting
+    /// some faces. Returns `false` if there is an edge shared by more than two faces.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// use truck_topology::shell::ShellCondition;
+    /// use std::iter::FromIterator;
+    /// // The orientations of the faces are not compatible, but the shell is orientable.
+    /// let v = Vertex::news(&[(); 4]);
+    /// let edge = [
+    ///     Edge::new(&v[0], &v[1], ()),
+    ///     Edge::new(&v[1], &v[2], ()),
+    ///     Edge::new(&v[2], &v[0], ()),
+    ///     Edge::new(&v[1], &v[3], ()),
+    ///     Edge::new(&v[3], &v[2], ()),
+    /// ];
+    /// let wire = vec![
+    ///     Wire::from_iter(vec![&edge[0], &edge[1], &edge[2]]),
+    ///     Wire::from_iter(vec![&edge[1], &edge[4].inverse(), &edge[3].inverse()]),
+    /// ];
+    /// let shell: Shell<_, _, _> = wire.into_iter().map(|w| Face::new(vec![w], ())).collect();
+    /// assert_eq!(shell.shell_condition(), ShellCondition::Regular);
+    /// assert!(shell.is_orientable());
+    ///
+    /// // Mobius bundle
+    /// let v = Vertex::news(&[(); 4]);
+    /// let edge = [
+    ///     Edge::new(&v[0], &v[1], ()),
+    ///     Edge::new(&v[1], &v[2], ()),
+    ///     Edge::new(&v[2], &v[0], ()),
+    ///     Edge::new(&v[1], &v[3], ()),
+    ///     Edge::new(&v[3], &v[2], ()),
+    ///     Edge::new(&v[0], &v[3], ()),
+    /// ];
+    /// let wire = vec![
+    ///     Wire::from_iter(vec![&edge[0], &edge[3], &edge[4], &edge[2]]),
+    ///     Wire::from_iter(vec![&edge[1], &edge[2], &edge[5], &edge[3].inverse()]),
+    /// ];
+    /// let shell: Shell<_, _, _> = wire.into_iter().map(|w| Face::new(vec![w], ())).collect();
+    /// assert!(!shell.is_orientable());
+    /// assert_eq!(shell.genus(), None);
+    /// ```
+    pub fn is_orientable(&self) -> bool {
+        let mut edge_faces: HashMap<EdgeID<C>, Vec<(usize, bool)>> = HashMap::new();
+        for (i, face) in self.face_iter().enumerate() {
+            for edge in face.boundary_iters().into_iter().flatten() {
+                let ori = edge.absolute_front() == edge.front();
+                edge_faces
+                    .entry(edge.id())
+                    .or_insert_with(Vec::new)
+                    .push((i, ori));
+            }
+        }
+        // adjacency[i] consists of the pairs (j, p): the i-th face should be inverted
+        // relative to the j-th face if and only if p is true.
+        let mut adjacency = vec![Vec::new(); self.face_list.len()];
+        for faces in edge_faces.values() {
+            match faces.as_slice() {
+                [_] => {}
+                [(i, ori0), (j, ori1)] => {
+                    adjacency[*i].push((*j, ori0 == ori1));
+                    adjacency[*j].push((*i, ori0 == ori1));
+                }
+                _ => return false,
+            }
+        }
+        let mut inverted: Vec<Option<bool>> = vec![None; self.face_list.len()];
+        for first in 0..self.face_list.len() {
+            if inverted[first].is_some() {
+                continue;
+            }
+            inverted[first] = Some(false);
+            let mut stack = vec![first];
+            while let Some(i) = stack.pop() {
+                let inv = inverted[i].unwrap();
+                for (j, p) in &adjacency[i] {
+                    match inverted[*j] {
+                        None => {
+                            inverted[*j] = Some(inv != *p);
+                            stack.push(*j);
+                        }
+                        Some(inv1) => {
+                            if inv1 != (inv != *p) {
+                                return false;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Returns the summary of the topology of the shell.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// use truck_topology::shell::ShellCondition;
+    /// use std::iter::FromIterator;
+    /// let v = Vertex::news(&[(); 4]);
+    /// let edge = [
+    ///     Edge::new(&v[0], &v[1], ()),
+    ///     Edge::new(&v[1], &v[2], ()),
+    ///     Edge::new(&v[2], &v[0], ()),
+    ///     Edge::new(&v[1], &v[3], ()),
+    ///     Edge::new(&v[3], &v[2], ()),
+    /// ];
+    /// let wire = vec![
+    ///     Wire::from_iter(vec![&edge[0], &edge[1], &edge[2]]),
+    ///     Wire::from_iter(vec![&edge[1].inverse(), &edge[3], &edge[4]]),
+    /// ];
+    /// let shell: Shell<_, _, _> = wire.into_iter().map(|w| Face::new(vec![w], ())).collect();
+    /// let report = shell.report();
+    /// assert_eq!(report.num_faces, 2);
+    /// assert_eq!(report.num_edges, 5);
+    /// assert_eq!(report.num_vertices, 4);
+    /// assert_eq!(report.num_boundaries, 1);
+    /// assert_eq!(report.euler_characteristic, 1);
+    /// assert_eq!(report.condition, ShellCondition::Oriented);
+    /// assert_eq!(report.genus, Some(0));
+    /// println!("{}", report);
+    /// ```
+    pub fn report(&self) -> ShellReport {
+        let (vertices, edges) = self.vertex_edge_ids();
+        ShellReport {
+            num_faces: self.face_list.len(),
+            num_edges: edges.len(),
+            num_vertices: vertices.len(),
+            num_boundaries: self.extract_boundaries().len(),
+            num_components: self.connected_components().len(),
+            euler_characteristic: self.euler_characteristic(),
+            condition: self.shell_condition(),
+            genus: self.genus(),
+        }
+    }
+
+    /// Replaces the points, the curves and the surfaces of the shell in place.
+    ///
+    /// Each point, curve and surface shared by the elements is replaced exactly once.
+    /// The curves and the surfaces are passed in the absolute directions, i.e. independent of
+    /// the orientations of the edges and the faces. Unlike rebuilding the shell, all ids are
+    /// preserved, so the references to the elements, e.g. the attribute tables, survive.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// use std::iter::FromIterator;
+    /// let v = Vertex::news(&[0, 1, 2]);
+    /// let edge = [
+    ///     Edge::new(&v[0], &v[1], 10),
+    ///     Edge::new(&v[1], &v[2], 20),
+    ///     Edge::new(&v[2], &v[0], 30),
+    /// ];
+    /// let wire = Wire::from_iter(&edge);
+    /// let shell: Shell<_, _, _> = vec![Face::new(vec![wire], 100)].into();
+    /// let face_id = shell[0].id();
+    ///
+    /// shell.replace_geometry(&|p: &usize| p + 1, &|c: &usize| c * 2, &|s: &usize| s + 5);
+    /// assert_eq!(*v[2].lock_point().unwrap(), 3);
+    /// assert_eq!(*edge[0].lock_curve().unwrap(), 20);
+    /// assert_eq!(*shell[0].lock_surface().unwrap(), 105);
+    /// assert_eq!(shell[0].id(), face_id);
+    /// ```
+    pub fn replace_geometry(
+        &self,
+        point_fn: &impl Fn(&P) -> P,
+        curve_fn: &impl Fn(&C) -> C,
+        surface_fn: &impl Fn(&S) -> S,
+    ) {
+        let mut vertices = HashSet::new();
+        let mut edges = HashSet::new();
+        let mut faces = HashSet::new();
+        for face in self.face_iter() {
+            if !faces.insert(face.id()) {
+                continue;
+            }
+            let mut surface = face.lock_surface().unwrap();
+            *surface = surface_fn(&*surface);
+            for edge in face.absolute_boundaries().iter().flatten() {
+                if !edges.insert(edge.id()) {
+                    continue;
+                }
+                let mut curve = edge.lock_curve().unwrap();
+                *curve = curve_fn(&*curve);
+                for vertex in &[edge.front(), edge.back()] {
+                    if vertices.insert(vertex.id()) {
+                        let mut point = vertex.lock_point().unwrap();
+                        *point = point_fn(&*point);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns the sets of the ids of all vertices and all edges in the shell.
+    fn vertex_edge_ids(&self) -> (HashSet<VertexID<P>>, HashSet<EdgeID<C>>) {
+        let mut vertices = HashSet::new();
+        let mut edges = HashSet::new();
+        for edge in self.face_iter().flat_map(Face::boundary_iters).flatten() {
+            vertices.insert(edge.front().id());
+            edges.insert(edge.id());
+        }
+        (vertices, edges)
+    }
 }
 
 impl<P, C, S> Shell<P, C, S>
@@ -517,7 +812,7 @@ pub type FaceIterMut<'a, P, C, S> = std::slice::IterMut<'a, Face<P, C, S>>;
 pub type FaceIntoIter<P, C, S> = std::vec::IntoIter<Face<P, C, S>>;
 
 /// The shell conditions being determined by the half-edge model.
-#[derive(PartialEq, Eq, Debug)]
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Hash)]
 pub enum ShellCondition {
     /// This shell is not regular.
     /// # Examples
@@ -641,6 +936,47 @@ pub enum ShellCondition {
     Closed,
 }
 
+/// The summary of the topology of a shell, created by
+/// [`Shell::report`](../struct.Shell.html#method.report).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct ShellReport {
+    /// the number of the faces
+    pub num_faces: usize,
+    /// the number of the edges
+    pub num_edges: usize,
+    /// the number of the vertices
+    pub num_vertices: usize,
+    /// the number of the open boundaries
+    pub num_boundaries: usize,
+    /// the number of the connected components by the face adjacency
+    pub num_components: usize,
+    /// the Euler characteristic
+    pub euler_characteristic: isize,
+    /// the shell condition
+    pub condition: ShellCondition,
+    /// the genus, `None` if the shell is not orientable
+    pub genus: Option<usize>,
+}
+
+impl std::fmt::Display for ShellReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_fmt(format_args!(
+            "faces: {}, edges: {}, vertices: {}, boundaries: {}, components: {}, euler characteristic: {}, condition: {:?}, genus: ",
+            self.num_faces,
+            self.num_edges,
+            self.num_vertices,
+            self.num_boundaries,
+            self.num_components,
+            self.euler_characteristic,
+            self.condition,
+        ))?;
+        match self.genus {
+            Some(genus) => f.write_fmt(format_args!("{}", genus)),
+            None => f.pad("non-orientable"),
+        }
+    }
+}
+
 fn check_connectivity<T>(adjacency: &mut HashMap<T, Vec<T>>) -> bool
 where T: Eq + Clone + Hash {
     create_one_component(adjacency);
@@ -0,0 +1,342 @@
+use crate::compress::{CompressedEdge, CompressedFace};
+use crate::errors::Error;
+use crate::*;
+use std::str::SplitWhitespace;
+use truck_base::cgmath64::*;
+
+const HEADER: &str = "truck brep v1";
+
+/// The geometries written in the text format "truck brep v1".
+///
+/// Each geometry is written as the tokens separated by the spaces in one line, so that the
+/// golden files of the shapes are diffed line by line. The real numbers are written by the
+/// shortest representations which are read back to the same values.
+pub trait TextGeometry: Sized {
+    /// Appends the tokens of the geometry to `tokens`.
+    fn write_tokens(&self, tokens: &mut Vec<String>);
+    /// Reads the geometry from the tokens written by `write_tokens`.
+    /// Returns `None` if the tokens are invalid.
+    fn read_tokens(tokens: &mut SplitWhitespace) -> Option<Self>;
+}
+
+impl TextGeometry for () {
+    #[inline(always)]
+    fn write_tokens(&self, _: &mut Vec<String>) {}
+    #[inline(always)]
+    fn read_tokens(_: &mut SplitWhitespace) -> Option<()> { Some(()) }
+}
+
+impl TextGeometry for bool {
+    #[inline(always)]
+    fn write_tokens(&self, tokens: &mut Vec<String>) { tokens.push(self.to_string()) }
+    #[inline(always)]
+    fn read_tokens(tokens: &mut SplitWhitespace) -> Option<bool> { tokens.next()?.parse().ok() }
+}
+
+impl TextGeometry for usize {
+    #[inline(always)]
+    fn write_tokens(&self, tokens: &mut Vec<String>) { tokens.push(self.to_string()) }
+    #[inline(always)]
+    fn read_tokens(tokens: &mut SplitWhitespace) -> Option<usize> { tokens.next()?.parse().ok() }
+}
+
+impl TextGeometry for f64 {
+    #[inline(always)]
+    fn write_tokens(&self, tokens: &mut Vec<String>) {
+        // The negative zero is written as the zero, not to make the meaningless diffs.
+        let x = if *self == 0.0 { 0.0 } else { *self };
+        tokens.push(format!("{:?}", x))
+    }
+    #[inline(always)]
+    fn read_tokens(tokens: &mut SplitWhitespace) -> Option<f64> { tokens.next()?.parse().ok() }
+}
+
+/// The length and the elements.
+impl<T: TextGeometry> TextGeometry for Vec<T> {
+    fn write_tokens(&self, tokens: &mut Vec<String>) {
+        self.len().write_tokens(tokens);
+        self.iter().for_each(|x| x.write_tokens(tokens));
+    }
+    fn read_tokens(tokens: &mut SplitWhitespace) -> Option<Vec<T>> {
+        let len = usize::read_tokens(tokens)?;
+        (0..len).map(|_| T::read_tokens(tokens)).collect()
+    }
+}
+
+macro_rules! impl_text_geometry {
+    ($($typename: ident, $dim: expr),*) => {
+        $(
+            impl TextGeometry for $typename {
+                #[inline(always)]
+                fn write_tokens(&self, tokens: &mut Vec<String>) {
+                    (0..$dim).for_each(|i| self[i].write_tokens(tokens))
+                }
+                #[inline(always)]
+                fn read_tokens(tokens: &mut SplitWhitespace) -> Option<$typename> {
+                    let mut array = [0.0; $dim];
+                    for x in array.iter_mut() {
+                        *x = f64::read_tokens(tokens)?;
+                    }
+                    Some(array.into())
+                }
+            }
+        )*
+    };
+}
+
+impl_text_geometry!(Point2, 2, Point3, 3, Vector2, 2, Vector3, 3, Vector4, 4);
+
+/// The columns.
+impl TextGeometry for Matrix4 {
+    fn write_tokens(&self, tokens: &mut Vec<String>) {
+        (0..4).for_each(|i| self[i].write_tokens(tokens))
+    }
+    fn read_tokens(tokens: &mut SplitWhitespace) -> Option<Matrix4> {
+        let x = Vector4::read_tokens(tokens)?;
+        let y = Vector4::read_tokens(tokens)?;
+        let z = Vector4::read_tokens(tokens)?;
+        let w = Vector4::read_tokens(tokens)?;
+        Some(Matrix4::from_cols(x, y, z, w))
+    }
+}
+
+impl<P: TextGeometry, C: TextGeometry, S: TextGeometry> CompressedShell<P, C, S> {
+    /// Writes the shell in the text format "truck brep v1".
+    ///
+    /// The vertices, the edges and the faces are written one per line in the order of
+    /// the compression, which is determined by the order of the faces.
+    /// # Examples
+    /// ```
+    /// use truck_topology::*;
+    /// let v = Vertex::news(&[0.0, 1.0, 2.0]);
+    /// let wire = Wire::from(vec![
+    ///     Edge::new(&v[0], &v[1], ()),
+    ///     Edge::new(&v[1], &v[2], ()),
+    ///     Edge::new(&v[2], &v[0], ()),
+    /// ]);
+    /// let shell: Shell<f64, (), ()> = vec![Face::new(vec![wire], ())].into();
+    ///
+    /// let text = shell.compress().to_text();
+    /// assert_eq!(
+    ///     text,
+    ///     "truck brep v1
+    /// shell 3 3 1
+    /// v 0.0
+    /// v 1.0
+    /// v 2.0
+    /// e 0 1
+    /// e 1 2
+    /// e 2 0
+    /// f true 1 3 0+ 1+ 2+
+    /// ",
+    /// );
+    ///
+    /// // The text is read back to the same shell.
+    /// let cshell = CompressedShell::<f64, (), ()>::from_text(&text).unwrap();
+    /// let shell = Shell::extract(cshell).unwrap();
+    /// assert_eq!(shell.compress().to_text(), text);
+    /// ```
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![HEADER.to_string()];
+        self.write_lines(&mut lines);
+        lines.join("\n") + "\n"
+    }
+
+    /// Reads the shell written by [`to_text`](#method.to_text).
+    /// The empty lines and the comment lines starting with `#` are skipped.
+    pub fn from_text(text: &str) -> Result<Self> {
+        let mut lines = TextLines::new(text);
+        lines.header()?;
+        let shell = Self::read_lines(&mut lines)?;
+        lines.end()?;
+        Ok(shell)
+    }
+
+    fn write_lines(&self, lines: &mut Vec<String>) {
+        lines.push(format!(
+            "shell {} {} {}",
+            self.vertices.len(),
+            self.edges.len(),
+            self.faces.len()
+        ));
+        for point in &self.vertices {
+            let mut tokens = vec!["v".to_string()];
+            point.write_tokens(&mut tokens);
+            lines.push(tokens.join(" "));
+        }
+        for edge in &self.edges {
+            let mut tokens = vec!["e".to_string()];
+            edge.vertices.0.write_tokens(&mut tokens);
+            edge.vertices.1.write_tokens(&mut tokens);
+            edge.curve.write_tokens(&mut tokens);
+            lines.push(tokens.join(" "));
+        }
+        for face in &self.faces {
+            let mut tokens = vec!["f".to_string()];
+            face.orientation.write_tokens(&mut tokens);
+            face.boundaries.len().write_tokens(&mut tokens);
+            for wire in &face.boundaries {
+                wire.len().write_tokens(&mut tokens);
+                tokens.extend(wire.iter().map(|(idx, ori)| match ori {
+                    true => format!("{}+", idx),
+                    false => format!("{}-", idx),
+                }));
+            }
+            face.surface.write_tokens(&mut tokens);
+            lines.push(tokens.join(" "));
+        }
+    }
+
+    fn read_lines(lines: &mut TextLines) -> Result<Self> {
+        let (vlen, elen, flen) = lines.parse("shell", |tokens| {
+            Some((
+                usize::read_tokens(tokens)?,
+                usize::read_tokens(tokens)?,
+                usize::read_tokens(tokens)?,
+            ))
+        })?;
+        let vertices = (0..vlen)
+            .map(|_| lines.parse("v", |tokens| P::read_tokens(tokens)))
+            .collect::<Result<Vec<_>>>()?;
+        let edges = (0..elen)
+            .map(|_| {
+                lines.parse("e", |tokens| {
+                    let vertices = (usize::read_tokens(tokens)?, usize::read_tokens(tokens)?);
+                    if vertices.0 >= vlen || vertices.1 >= vlen {
+                        return None;
+                    }
+                    let curve = C::read_tokens(tokens)?;
+                    Some(CompressedEdge { vertices, curve })
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let faces = (0..flen)
+            .map(|_| {
+                lines.parse("f", |tokens| {
+                    let orientation = bool::read_tokens(tokens)?;
+                    let boundaries = (0..usize::read_tokens(tokens)?)
+                        .map(|_| {
+                            (0..usize::read_tokens(tokens)?)
+                                .map(|_| edge_reference(tokens.next()?, elen))
+                                .collect()
+                        })
+                        .collect::<Option<Vec<_>>>()?;
+                    let surface = S::read_tokens(tokens)?;
+                    Some(CompressedFace {
+                        boundaries,
+                        orientation,
+                        surface,
+                    })
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(CompressedShell {
+            vertices,
+            edges,
+            faces,
+        })
+    }
+}
+
+impl<P: TextGeometry, C: TextGeometry, S: TextGeometry> CompressedSolid<P, C, S> {
+    /// Writes the solid in the text format "truck brep v1".
+    ///
+    /// The boundary shells are written in order, in the same way as
+    /// [`CompressedShell::to_text`](./struct.CompressedShell.html#method.to_text).
+    pub fn to_text(&self) -> String {
+        let mut lines = vec![
+            HEADER.to_string(),
+            format!("solid {}", self.boundaries.len()),
+        ];
+        self.boundaries
+            .iter()
+            .for_each(|shell| shell.write_lines(&mut lines));
+        lines.join("\n") + "\n"
+    }
+
+    /// Reads the solid written by [`to_text`](#method.to_text).
+    /// The empty lines and the comment lines starting with `#` are skipped.
+    pub fn from_text(text: &str) -> Result<Self> {
+        let mut lines = TextLines::new(text);
+        lines.header()?;
+        let len = lines.parse("solid", |tokens| usize::read_tokens(tokens))?;
+        let boundaries = (0..len)
+            .map(|_| CompressedShell::read_lines(&mut lines))
+            .collect::<Result<Vec<_>>>()?;
+        lines.end()?;
+        Ok(CompressedSolid { boundaries })
+    }
+}
+
+// `3+` is the third edge, and `3-` is its inverse.
+fn edge_reference(token: &str, len: usize) -> Option<(usize, bool)> {
+    let (idx, ori) = match token.strip_suffix('+') {
+        Some(idx) => (idx, true),
+        None => (token.strip_suffix('-')?, false),
+    };
+    let idx: usize = idx.parse().ok()?;
+    match idx < len {
+        true => Some((idx, ori)),
+        false => None,
+    }
+}
+
+// The iterator over the lines with the line numbers, skipping the empty lines and the comments.
+struct TextLines<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+    last: usize,
+}
+
+impl<'a> TextLines<'a> {
+    fn new(text: &'a str) -> Self {
+        TextLines {
+            lines: text.lines().enumerate(),
+            last: 0,
+        }
+    }
+
+    fn next_line(&mut self) -> Option<(usize, &'a str)> {
+        for (idx, line) in &mut self.lines {
+            self.last = idx + 1;
+            let line = line.trim();
+            if !line.is_empty() && !line.starts_with('#') {
+                return Some((idx + 1, line));
+            }
+        }
+        None
+    }
+
+    fn header(&mut self) -> Result<()> {
+        match self.next_line() {
+            Some((_, HEADER)) => Ok(()),
+            Some((line, _)) => Err(Error::InvalidText(line)),
+            None => Err(Error::InvalidText(self.last + 1)),
+        }
+    }
+
+    // Parses the line starting with `key` by `parse`, which must consume all tokens.
+    fn parse<T>(
+        &mut self,
+        key: &str,
+        parse: impl FnOnce(&mut SplitWhitespace<'a>) -> Option<T>,
+    ) -> Result<T> {
+        let (line, mut tokens) = match self.next_line() {
+            Some((line, text)) => (line, text.split_whitespace()),
+            None => return Err(Error::InvalidText(self.last + 1)),
+        };
+        if tokens.next() != Some(key) {
+            return Err(Error::InvalidText(line));
+        }
+        match parse(&mut tokens) {
+            Some(x) if tokens.next().is_none() => Ok(x),
+            _ => Err(Error::InvalidText(line)),
+        }
+    }
+
+    fn end(&mut self) -> Result<()> {
+        match self.next_line() {
+            None => Ok(()),
+            Some((line, _)) => Err(Error::InvalidText(line)),
+        }
+    }
+}
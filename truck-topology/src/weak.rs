@@ -0,0 +1,267 @@
+use crate::*;
+use std::sync::Weak;
+
+/// Non-owning handle of a vertex.
+///
+/// The handle does not keep the point alive, so it is suitable for the caches, e.g. the picking
+/// maps and the attribute tables, referring the vertices of the models which may be dropped.
+/// # Examples
+/// ```
+/// use truck_topology::*;
+/// let v = Vertex::new(());
+/// let weak = v.downgrade();
+/// assert_eq!(weak.upgrade(), Some(v.clone()));
+/// drop(v);
+/// assert_eq!(weak.upgrade(), None);
+/// ```
+#[derive(Debug)]
+pub struct WeakVertex<P> {
+    point: Weak<Mutex<P>>,
+}
+
+/// Non-owning handle of an edge, which does not keep the curve and the vertices alive.
+/// # Examples
+/// ```
+/// use truck_topology::*;
+/// let v = Vertex::news(&[(), ()]);
+/// let edge = Edge::new(&v[0], &v[1], ());
+/// let weak = edge.inverse().downgrade();
+/// assert_eq!(weak.id(), edge.id());
+/// assert_eq!(weak.upgrade(), Some(edge.inverse()));
+///
+/// // The edge is dropped even if the end vertices are alive.
+/// drop(edge);
+/// assert!(!weak.is_alive());
+/// ```
+#[derive(Debug)]
+pub struct WeakEdge<P, C> {
+    vertices: (WeakVertex<P>, WeakVertex<P>),
+    orientation: bool,
+    curve: Weak<Mutex<C>>,
+}
+
+/// Non-owning handle of a face, which does not keep the surface and the boundaries alive.
+/// # Examples
+/// ```
+/// use truck_topology::*;
+/// let v = Vertex::news(&[(); 3]);
+/// let wire = Wire::from(vec![
+///     Edge::new(&v[0], &v[1], ()),
+///     Edge::new(&v[1], &v[2], ()),
+///     Edge::new(&v[2], &v[0], ()),
+/// ]);
+/// let shell: Shell<(), (), ()> = vec![Face::new(vec![wire], ())].into();
+///
+/// // a cache which does not keep the shell alive
+/// let cache: Vec<WeakFace<(), (), ()>> = shell.weak_face_iter().collect();
+/// assert_eq!(cache[0].upgrade().as_ref(), Some(&shell[0]));
+/// drop(shell);
+/// assert!(cache.iter().all(|face| face.upgrade().is_none()));
+/// ```
+#[derive(Debug)]
+pub struct WeakFace<P, C, S> {
+    boundaries: Vec<Vec<WeakEdge<P, C>>>,
+    orientation: bool,
+    surface: Weak<Mutex<S>>,
+}
+
+impl<P> Vertex<P> {
+    /// Returns the non-owning handle of the vertex.
+    #[inline(always)]
+    pub fn downgrade(&self) -> WeakVertex<P> {
+        WeakVertex {
+            point: Arc::downgrade(&self.point),
+        }
+    }
+}
+
+impl<P, C> Edge<P, C> {
+    /// Returns the non-owning handle of the edge.
+    #[inline(always)]
+    pub fn downgrade(&self) -> WeakEdge<P, C> {
+        WeakEdge {
+            vertices: (self.vertices.0.downgrade(), self.vertices.1.downgrade()),
+            orientation: self.orientation,
+            curve: Arc::downgrade(&self.curve),
+        }
+    }
+}
+
+impl<P, C, S> Face<P, C, S> {
+    /// Returns the non-owning handle of the face.
+    #[inline(always)]
+    pub fn downgrade(&self) -> WeakFace<P, C, S> {
+        WeakFace {
+            boundaries: self
+                .boundaries
+                .iter()
+                .map(|wire| wire.iter().map(Edge::downgrade).collect())
+                .collect(),
+            orientation: self.orientation,
+            surface: Arc::downgrade(&self.surface),
+        }
+    }
+}
+
+impl<P, C> Wire<P, C> {
+    /// Returns the iterator over the non-owning handles of the edges.
+    #[inline(always)]
+    pub fn weak_edge_iter(&self) -> impl Iterator<Item = WeakEdge<P, C>> + '_ {
+        self.iter().map(Edge::downgrade)
+    }
+}
+
+impl<P, C, S> Shell<P, C, S> {
+    /// Returns the iterator over the non-owning handles of the faces.
+    #[inline(always)]
+    pub fn weak_face_iter(&self) -> impl Iterator<Item = WeakFace<P, C, S>> + '_ {
+        self.iter().map(Face::downgrade)
+    }
+}
+
+impl<P> WeakVertex<P> {
+    /// Returns the vertex if it is still alive.
+    #[inline(always)]
+    pub fn upgrade(&self) -> Option<Vertex<P>> {
+        self.point.upgrade().map(|point| Vertex { point })
+    }
+    /// Returns whether the vertex is still alive.
+    #[inline(always)]
+    pub fn is_alive(&self) -> bool { self.point.strong_count() > 0 }
+    /// Returns the id of the vertex.
+    /// # Remarks
+    /// After the vertex is dropped, the same id may be assigned to another vertex.
+    #[inline(always)]
+    pub fn id(&self) -> VertexID<P> { ID::new(self.point.as_ptr()) }
+}
+
+impl<P, C> WeakEdge<P, C> {
+    /// Returns the edge if the curve and the end vertices are still alive.
+    #[inline(always)]
+    pub fn upgrade(&self) -> Option<Edge<P, C>> {
+        Some(Edge {
+            vertices: (self.vertices.0.upgrade()?, self.vertices.1.upgrade()?),
+            orientation: self.orientation,
+            curve: self.curve.upgrade()?,
+        })
+    }
+    /// Returns whether the edge is still alive.
+    #[inline(always)]
+    pub fn is_alive(&self) -> bool { self.curve.strong_count() > 0 }
+    /// Returns the orientation of the edge.
+    #[inline(always)]
+    pub fn orientation(&self) -> bool { self.orientation }
+    /// Returns the id of the edge, which does not depend on the direction.
+    /// # Remarks
+    /// After the edge is dropped, the same id may be assigned to another edge.
+    #[inline(always)]
+    pub fn id(&self) -> EdgeID<C> { ID::new(self.curve.as_ptr()) }
+}
+
+impl<P, C, S> WeakFace<P, C, S> {
+    /// Returns the face if the surface and the boundaries are still alive.
+    pub fn upgrade(&self) -> Option<Face<P, C, S>> {
+        let boundaries = self
+            .boundaries
+            .iter()
+            .map(|wire| {
+                wire.iter()
+                    .map(WeakEdge::upgrade)
+                    .collect::<Option<Wire<P, C>>>()
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Face {
+            boundaries,
+            orientation: self.orientation,
+            surface: self.surface.upgrade()?,
+        })
+    }
+    /// Returns whether the face is still alive.
+    #[inline(always)]
+    pub fn is_alive(&self) -> bool { self.surface.strong_count() > 0 }
+    /// Returns the orientation of the face.
+    #[inline(always)]
+    pub fn orientation(&self) -> bool { self.orientation }
+    /// Returns the id of the face, which does not depend on the direction.
+    /// # Remarks
+    /// After the face is dropped, the same id may be assigned to another face.
+    #[inline(always)]
+    pub fn id(&self) -> FaceID<S> { ID::new(self.surface.as_ptr()) }
+}
+
+impl<P> Clone for WeakVertex<P> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        WeakVertex {
+            point: Weak::clone(&self.point),
+        }
+    }
+}
+
+impl<P, C> Clone for WeakEdge<P, C> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        WeakEdge {
+            vertices: self.vertices.clone(),
+            orientation: self.orientation,
+            curve: Weak::clone(&self.curve),
+        }
+    }
+}
+
+impl<P, C, S> Clone for WeakFace<P, C, S> {
+    #[inline(always)]
+    fn clone(&self) -> Self {
+        WeakFace {
+            boundaries: self.boundaries.clone(),
+            orientation: self.orientation,
+            surface: Weak::clone(&self.surface),
+        }
+    }
+}
+
+impl<P> PartialEq for WeakVertex<P> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool { self.point.ptr_eq(&other.point) }
+}
+
+impl<P> Eq for WeakVertex<P> {}
+
+impl<P, C> PartialEq for WeakEdge<P, C> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.curve.ptr_eq(&other.curve) && self.orientation == other.orientation
+    }
+}
+
+impl<P, C> Eq for WeakEdge<P, C> {}
+
+impl<P, C, S> PartialEq for WeakFace<P, C, S> {
+    #[inline(always)]
+    fn eq(&self, other: &Self) -> bool {
+        self.surface.ptr_eq(&other.surface) && self.orientation == other.orientation
+    }
+}
+
+impl<P, C, S> Eq for WeakFace<P, C, S> {}
+
+impl<P> Hash for WeakVertex<P> {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) { std::ptr::hash(self.point.as_ptr(), state); }
+}
+
+impl<P, C> Hash for WeakEdge<P, C> {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self.curve.as_ptr(), state);
+        self.orientation.hash(state);
+    }
+}
+
+impl<P, C, S> Hash for WeakFace<P, C, S> {
+    #[inline(always)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self.surface.as_ptr(), state);
+        self.orientation.hash(state);
+    }
+}
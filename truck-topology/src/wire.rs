@@ -1,3 +1,4 @@
+use crate::errors::Error;
 use crate::*;
 use std::collections::vec_deque;
 use std::collections::{HashSet, VecDeque};
@@ -284,6 +285,121 @@ impl<P: Tolerance, C: ParametricCurve<Point=P>> Wire<P, C> {
     }
 }
 
+impl<P: Tolerance + Clone, C: Clone> Wire<P, C> {
+    /// Chains the unordered edges into a wire.
+    ///
+    /// The ends of the edges are identified if they are the same vertex or if their points
+    /// are near within `tol`. The edges whose ends are identified with other vertices are
+    /// re-created by the representative vertices, and the edges are inverted if necessary.
+    /// If the edges form a path, the wire is oriented so that the first edge in `edges`
+    /// keeps its orientation. If they form a cycle, the wire starts with the first edge.
+    /// # Examples
+    /// ```
+    /// use truck_base::cgmath64::*;
+    /// use truck_topology::*;
+    /// // the sides of the unit square with the distinct end vertices
+    /// let p = [
+    ///     Point2::new(0.0, 0.0),
+    ///     Point2::new(1.0, 0.0),
+    ///     Point2::new(1.0, 1.0),
+    ///     Point2::new(0.0, 1.0),
+    /// ];
+    /// let edge = |i: usize, j: usize| Edge::new(&Vertex::new(p[i]), &Vertex::new(p[j]), ());
+    /// let edges = vec![edge(0, 1), edge(2, 3), edge(2, 1), edge(3, 0)];
+    ///
+    /// let wire = Wire::from_unordered_edges(&edges, 1.0e-6).unwrap();
+    /// assert_eq!(wire.len(), 4);
+    /// assert!(wire.is_closed());
+    /// assert!(wire.is_simple());
+    /// let points: Vec<Point2> = wire.vertex_iter().map(|v| *v.lock_point().unwrap()).collect();
+    /// assert_eq!(points, p.to_vec());
+    /// ```
+    /// # Failures
+    /// - If the ends of an edge are identified,
+    ///   returns [`Error::SameVertex`](./errors/enum.Error.html#variant.SameVertex).
+    /// - If the edges branch or are not connected, returns
+    ///   [`Error::UnchainableEdges`](./errors/enum.Error.html#variant.UnchainableEdges).
+    pub fn from_unordered_edges(edges: &[Edge<P, C>], tol: f64) -> Result<Wire<P, C>> {
+        let mut reps: Vec<(Vertex<P>, P)> = Vec::new();
+        let mut rep_index = |vertex: &Vertex<P>| {
+            let point = vertex.lock_point().unwrap().clone();
+            let position = reps
+                .iter()
+                .position(|(v, pt)| v == vertex || pt.abs_diff_eq(&point, tol));
+            position.unwrap_or_else(|| {
+                reps.push((vertex.clone(), point));
+                reps.len() - 1
+            })
+        };
+        // the indices of the representatives of the oriented ends
+        let ends: Vec<(usize, usize)> = edges
+            .iter()
+            .map(|edge| (rep_index(edge.front()), rep_index(edge.back())))
+            .collect();
+        let mut adjacency = vec![Vec::new(); reps.len()];
+        for (i, (front, back)) in ends.iter().enumerate() {
+            if front == back {
+                return Err(Error::SameVertex);
+            }
+            adjacency[*front].push(i);
+            adjacency[*back].push(i);
+        }
+        if adjacency.iter().any(|edges| edges.len() > 2) {
+            return Err(Error::UnchainableEdges);
+        }
+        let rebuilt = |i: usize| {
+            let edge = &edges[i];
+            let (front, back) = match edge.orientation() {
+                true => ends[i],
+                false => (ends[i].1, ends[i].0),
+            };
+            if edge.absolute_front() == &reps[front].0 && edge.absolute_back() == &reps[back].0 {
+                return edge.clone();
+            }
+            let curve = edge.lock_curve().unwrap().clone();
+            let mut new_edge = Edge::new(&reps[front].0, &reps[back].0, curve);
+            if !edge.orientation() {
+                new_edge.invert();
+            }
+            new_edge
+        };
+        let mut used = vec![false; edges.len()];
+        let mut wire = Wire::with_capacity(edges.len());
+        let mut first_inverted = false;
+        let open_end = (0..reps.len()).find(|idx| adjacency[*idx].len() == 1);
+        let mut current = match (open_end, edges.is_empty()) {
+            (_, true) => return Ok(wire),
+            (Some(idx), false) => idx,
+            (None, false) => {
+                used[0] = true;
+                wire.push_back(rebuilt(0));
+                ends[0].1
+            }
+        };
+        while let Some(&i) = adjacency[current].iter().find(|i| !used[**i]) {
+            used[i] = true;
+            match ends[i].0 == current {
+                true => {
+                    wire.push_back(rebuilt(i));
+                    current = ends[i].1;
+                }
+                false => {
+                    wire.push_back(rebuilt(i).inverse());
+                    current = ends[i].0;
+                    first_inverted |= i == 0;
+                }
+            }
+        }
+        if used.iter().any(|used| !used) {
+            return Err(Error::UnchainableEdges);
+        }
+        if first_inverted {
+            wire.invert();
+        }
+        Ok(wire)
+    }
+}
+
 impl<T, P, C> From<T> for Wire<P, C>
 where T: Into<VecDeque<Edge<P, C>>>
 {